@@ -0,0 +1,72 @@
+use clap::{Arg, ArgAction, Command};
+use std::path::PathBuf;
+use std::sync::Arc;
+use tracing::{error, info};
+use url::Url;
+use zeroxbridge_sequencer::config::load_config;
+use zeroxbridge_sequencer::db::database::get_db_pool;
+use zeroxbridge_sequencer::events::EventBus;
+use zeroxbridge_sequencer::http::webhook::WebhookNotifier;
+use zeroxbridge_sequencer::relayer::ethereum_relayer::EthereumRelayer;
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let matches = Command::new("Ethereum Relayer")
+        .version("1.0")
+        .about("Relay withdrawals that are ready for relay to the L1 bridge contract")
+        .arg(
+            Arg::new("config")
+                .long("config")
+                .value_name("CONFIG_FILE")
+                .help("Path to configuration file")
+                .default_value("config.toml")
+                .value_parser(clap::value_parser!(String)),
+        )
+        .arg(
+            Arg::new("dry_run")
+                .long("dry-run")
+                .help("Simulate every unlock transaction with eth_call instead of sending it")
+                .action(ArgAction::SetTrue),
+        )
+        .get_matches();
+
+    tracing_subscriber::fmt()
+        .with_env_filter(tracing_subscriber::EnvFilter::from_default_env())
+        .init();
+
+    let config_path = PathBuf::from(matches.get_one::<String>("config").unwrap());
+    let mut config = load_config(Some(&config_path))?;
+    // The flag can only turn dry runs on, never off
+    config.relayer.dry_run |= matches.get_flag("dry_run");
+
+    let db_pool = get_db_pool(config.database.get_db_url().expose()).await?;
+    let mut relayer = EthereumRelayer::new(
+        db_pool,
+        Url::parse(&config.ethereum.get_rpc_url())?,
+        &config.contracts.l1_contract_address,
+        config.relayer.clone(),
+    )
+    .await?;
+
+    if let Some(webhook) = config.webhook.clone() {
+        let completions = EventBus::default();
+        Arc::new(WebhookNotifier::new(webhook)).subscribe_completions(&completions);
+        relayer = relayer.with_completion_bus(completions);
+    }
+
+    info!(
+        "Starting Ethereum relayer (dry run: {})",
+        config.relayer.dry_run
+    );
+    tokio::select! {
+        _ = relayer.run() => {}
+        result = tokio::signal::ctrl_c() => {
+            if let Err(e) = result {
+                error!("Failed to listen for Ctrl-C: {:?}", e);
+            }
+            info!("Shutting down Ethereum relayer");
+        }
+    }
+
+    Ok(())
+}
@@ -1,25 +1,48 @@
-use clap::{Arg, Command};
-use std::path::PathBuf;
+use clap::{Arg, ArgAction, ArgMatches, Command};
+use std::path::{Path, PathBuf};
 use std::str::FromStr;
+use std::time::Duration;
 use tracing::{error, info};
 use zeroxbridge_sequencer::config::load_config;
-use zeroxbridge_sequencer::db::database::get_db_pool;
+use zeroxbridge_sequencer::db::database::{cleanup_old_proof_jobs, get_db_pool};
+use zeroxbridge_sequencer::relayer::calldata::{read_calldata_dir, CalldataFormat};
 use zeroxbridge_sequencer::relayer::client::ProofSubmissionClient;
+use zeroxbridge_sequencer::relayer::proof_submission::ProofSubmissionConfig;
+
+const DEFAULT_STALE_JOB_AGE_MINUTES: i64 = 30;
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
-    // Initialize logging
-    tracing_subscriber::fmt::init();
-
     let matches = Command::new("Proof Submitter")
         .version("1.0")
         .about("Submit proofs from calldata directory to Starknet")
+        .subcommand_negates_reqs(true)
+        .subcommand(
+            Command::new("dump-calldata")
+                .about("Write the combined calldata of a calldata directory to stdout")
+                .arg(
+                    Arg::new("calldata_dir")
+                        .long("calldata_dir")
+                        .value_name("PATH")
+                        .help("Path to the calldata directory")
+                        .required(true)
+                        .value_parser(clap::value_parser!(String)),
+                )
+                .arg(
+                    Arg::new("format")
+                        .long("format")
+                        .value_name("FORMAT")
+                        .help("Output format: json, hex-text or binary")
+                        .default_value("hex-text")
+                        .value_parser(CalldataFormat::from_str),
+                ),
+        )
         .arg(
             Arg::new("calldata_dir")
                 .long("calldata_dir")
                 .value_name("PATH")
                 .help("Path to the calldata directory")
-                .required(true)
+                .required_unless_present("watch")
                 .value_parser(clap::value_parser!(String)),
         )
         .arg(
@@ -27,7 +50,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
                 .long("job_id")
                 .value_name("ID")
                 .help("Proof job ID")
-                .required(true)
+                .required_unless_present("watch")
                 .value_parser(clap::value_parser!(String)),
         )
         .arg(
@@ -62,6 +85,21 @@ async fn main() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
                 .default_value("true")
                 .value_parser(clap::value_parser!(String)),
         )
+        .arg(
+            Arg::new("deposit_ids")
+                .long("deposit_ids")
+                .value_name("IDS")
+                .help("Comma-separated IDs of the deposits covered by this proof")
+                .value_delimiter(',')
+                .value_parser(clap::value_parser!(i32)),
+        )
+        .arg(
+            Arg::new("fact_hash")
+                .long("fact_hash")
+                .value_name("FACT")
+                .help("Fact the final proof registers; the job only completes once the fact registry accepts it")
+                .value_parser(clap::value_parser!(String)),
+        )
         .arg(
             Arg::new("config")
                 .long("config")
@@ -70,8 +108,70 @@ async fn main() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
                 .default_value("config.toml")
                 .value_parser(clap::value_parser!(String)),
         )
+        .arg(
+            Arg::new("dry_run")
+                .long("dry-run")
+                .help("Simulate every proof call without sending transactions or updating the database")
+                .action(ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("resume")
+                .long("resume")
+                .help("Continue an existing proof job from its last submitted stage")
+                .action(ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("watch")
+                .long("watch")
+                .help("Keep running and submit queued proof jobs as they appear")
+                .action(ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("poll_interval")
+                .long("poll-interval")
+                .value_name("SECONDS")
+                .help("Seconds between queue polls in --watch mode")
+                .default_value("30")
+                .value_parser(clap::value_parser!(u64)),
+        )
         .get_matches();
 
+    // Initialize logging as JSON so every line carries the fields of its enclosing spans (job_id)
+    let logger = tracing_subscriber::fmt()
+        .json()
+        .with_current_span(true)
+        .with_span_list(true)
+        .with_env_filter(tracing_subscriber::EnvFilter::from_default_env());
+
+    if let Some(("dump-calldata", dump_matches)) = matches.subcommand() {
+        // stdout carries the calldata itself
+        logger.with_writer(std::io::stderr).init();
+        return dump_calldata(dump_matches);
+    }
+    logger.init();
+
+    let config_path = PathBuf::from(matches.get_one::<String>("config").unwrap());
+    let dry_run = matches.get_flag("dry_run");
+    let watch = matches.get_flag("watch");
+    let poll_interval = *matches.get_one::<u64>("poll_interval").unwrap();
+
+    if watch {
+        info!(
+            "Starting proof submitter in watch mode (poll interval: {}s, dry run: {})",
+            poll_interval, dry_run
+        );
+        let client = init_client(&config_path, dry_run).await?;
+
+        client
+            .watch_queued_jobs(Duration::from_secs(poll_interval), async {
+                if let Err(e) = tokio::signal::ctrl_c().await {
+                    error!("Failed to listen for Ctrl-C: {:?}", e);
+                }
+            })
+            .await;
+        return Ok(());
+    }
+
     // Parse arguments
     let calldata_dir = PathBuf::from(matches.get_one::<String>("calldata_dir").unwrap());
     let job_id = u64::from_str(matches.get_one::<String>("job_id").unwrap())
@@ -83,7 +183,12 @@ async fn main() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
         .get_one::<String>("memory_verification")
         .unwrap()
         .clone();
-    let config_path = PathBuf::from(matches.get_one::<String>("config").unwrap());
+    let resume = matches.get_flag("resume");
+    let deposit_ids: Vec<i32> = matches
+        .get_many::<i32>("deposit_ids")
+        .map(|ids| ids.copied().collect())
+        .unwrap_or_default();
+    let fact_hash = matches.get_one::<String>("fact_hash").cloned();
 
     info!("Starting proof submission with parameters:");
     info!("  Calldata directory: {:?}", calldata_dir);
@@ -92,20 +197,12 @@ async fn main() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
     info!("  Hasher: {}", hasher);
     info!("  Stone version: {}", stone_version);
     info!("  Memory verification: {}", memory_verification);
+    info!("  Dry run: {}", dry_run);
+    info!("  Resume: {}", resume);
+    info!("  Deposit IDs: {:?}", deposit_ids);
+    info!("  Fact hash: {:?}", fact_hash);
 
-    // Load configuration
-    let config = load_config(Some(&config_path))?;
-    info!("Configuration loaded successfully");
-
-    // Initialize database connection
-    let db_pool = get_db_pool(&config.database.get_db_url()).await?;
-    info!("Database connection established");
-
-    // Create proof submission client
-    let client = ProofSubmissionClient::new(db_pool, config)
-        .await
-        .map_err(|e| Box::new(e) as Box<dyn std::error::Error + Send + Sync>)?;
-    info!("Proof submission client initialized");
+    let client = init_client(&config_path, dry_run).await?;
 
     // Submit the proof
     match client
@@ -116,6 +213,9 @@ async fn main() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
             hasher,
             stone_version,
             memory_verification,
+            resume,
+            deposit_ids,
+            fact_hash,
         )
         .await
     {
@@ -129,3 +229,67 @@ async fn main() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
         }
     }
 }
+
+/// Write the calldata of every proof file in the directory to stdout in the chosen format
+fn dump_calldata(matches: &ArgMatches) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let calldata_dir = PathBuf::from(matches.get_one::<String>("calldata_dir").unwrap());
+    let format = *matches.get_one::<CalldataFormat>("format").unwrap();
+
+    let calldata = read_calldata_dir(&calldata_dir)?;
+    format.to_writer(&calldata, std::io::stdout().lock())?;
+    Ok(())
+}
+
+/// Load the config, build the proof submission client and run startup queue maintenance
+async fn init_client(
+    config_path: &Path,
+    dry_run: bool,
+) -> Result<ProofSubmissionClient, Box<dyn std::error::Error + Send + Sync>> {
+    // Load configuration
+    let config = load_config(Some(config_path))?;
+    info!("Configuration loaded successfully");
+
+    let stale_job_age_minutes = config
+        .proof
+        .stale_job_age_minutes
+        .unwrap_or(DEFAULT_STALE_JOB_AGE_MINUTES);
+    let retention_days = config.proof.retention_days.unwrap_or(0);
+
+    // Initialize database connection
+    let db_pool = get_db_pool(config.database.get_db_url().expose()).await?;
+    info!("Database connection established");
+
+    // Archive old finished jobs so they do not slow down queue queries
+    if retention_days > 0 && !dry_run {
+        let archived = cleanup_old_proof_jobs(&db_pool, retention_days).await?;
+        if archived > 0 {
+            info!(
+                "Archived {} proof jobs older than {} days",
+                archived, retention_days
+            );
+        }
+    }
+
+    // Create proof submission client
+    let mut proof_config = ProofSubmissionConfig::from(config);
+    proof_config.dry_run = dry_run;
+    let client = ProofSubmissionClient::from_config(db_pool, proof_config)
+        .await
+        .map_err(|e| Box::new(e) as Box<dyn std::error::Error + Send + Sync>)?;
+    info!("Proof submission client initialized");
+
+    // Queue maintenance writes to the database, so it is skipped for dry runs
+    if !dry_run {
+        // Reset jobs left stuck in `processing` by a previous crash before submitting
+        let recovered = client
+            .relayer()
+            .recover_stale_jobs(stale_job_age_minutes)
+            .await
+            .map_err(|e| Box::new(e) as Box<dyn std::error::Error + Send + Sync>)?;
+        if recovered > 0 {
+            info!("Recovered {} stale proof jobs", recovered);
+        }
+    }
+
+    Ok(client)
+}
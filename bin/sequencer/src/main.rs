@@ -1,3 +1,340 @@
-fn main() {
-    println!("Hello, world!");
+use sqlx::{postgres::PgPoolOptions, Pool, Postgres};
+use starknet::core::types::Felt;
+use starknet::providers::jsonrpc::{HttpTransport, JsonRpcClient};
+use std::env;
+use std::error::Error;
+use std::path::Path;
+use std::process;
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::{error, info, warn};
+use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
+use url::Url;
+use zeroxbridge_sequencer::api::routes::{create_router, AppState};
+use zeroxbridge_sequencer::config::{load_config, redact_database_url, ConfigValidator};
+use zeroxbridge_sequencer::db::migrations::SqlxMigrationRunner;
+use zeroxbridge_sequencer::events::l1_event_watcher::{L1EventWatcher, RpcEthereumProvider};
+use zeroxbridge_sequencer::events::l2_event_watcher::{L2EventWatcher, RpcStarknetProvider};
+use zeroxbridge_sequencer::http::webhook::WebhookNotifier;
+use zeroxbridge_sequencer::oracle_service::oracle_service;
+use zeroxbridge_sequencer::queue::commitment_verifier::CommitmentHashVerifier;
+use zeroxbridge_sequencer::queue::l1_queue::L1Queue;
+use zeroxbridge_sequencer::queue::l2_queue::L2Queue;
+use zeroxbridge_sequencer::relayer::ethereum_relayer::EthereumRelayer;
+use zeroxbridge_sequencer::relayer::proof_submission::{
+    ProofSubmissionConfig, ProofSubmissionRelayer,
+};
+use zeroxbridge_sequencer::relayer::provider_pool::ProviderPool;
+use zeroxbridge_sequencer::relayer::starknet_relayer::{StarknetRelayer, StarknetRelayerConfig};
+use zeroxbridge_sequencer::workers::finalization::FinalizationWatcher;
+use zeroxbridge_sequencer::workers::heartbeat::HeartbeatWriter;
+use zeroxbridge_sequencer::workers::proof_generation::ProofGenerationWorker;
+use zeroxbridge_sequencer::workers::registry::ServiceRegistry;
+
+const CONFIG_PATH: &str = "config.toml";
+
+/// How long running services get to finish after Ctrl-C before they are aborted
+const SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(30);
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn Error>> {
+    // Initialize tracing
+    tracing_subscriber::registry()
+        .with(tracing_subscriber::EnvFilter::new(
+            env::var("RUST_LOG").unwrap_or_else(|_| "info".into()),
+        ))
+        .with(tracing_subscriber::fmt::layer())
+        .init();
+
+    info!("Starting ZeroXBridge Sequencer");
+
+    // Load configuration from environment or config file
+    let app_config = load_config(Some(Path::new(CONFIG_PATH)))?;
+    if let Err(errors) = ConfigValidator::validate(&app_config) {
+        for e in &errors {
+            error!("Invalid configuration: {}", e);
+        }
+        error!("Found {} configuration errors, exiting", errors.len());
+        process::exit(1);
+    }
+
+    if let Err(e) = app_config.database.test_connection().await {
+        error!("{}", e);
+        process::exit(1);
+    }
+
+    let database_url = env::var("DATABASE_URL").expect("DATABASE_URL must be set");
+    info!(
+        "Connecting to database: {}",
+        redact_database_url(&database_url)
+    );
+
+    // Create database connection pool
+    let db_pool = PgPoolOptions::new()
+        .max_connections(10)
+        .connect(&database_url)
+        .await?;
+
+    // Run database migrations
+    info!("Running database migrations");
+    let migration_runner = SqlxMigrationRunner::new(
+        db_pool.clone(),
+        app_config.database.migration_timeout_seconds,
+    );
+    if let Err(e) = migration_runner.run_with_timeout().await {
+        error!("{}", e);
+        process::exit(1);
+    }
+
+    // Create and start services
+    let db_pool_arc = Arc::new(db_pool);
+    let mut app_state = AppState::new(db_pool_arc.as_ref().clone(), app_config.clone())
+        .with_config_path(CONFIG_PATH);
+    if let Some(redis_url) = &app_config.database.redis_url {
+        info!("Keeping block trackers in Redis");
+        app_state = app_state.with_redis(redis_url)?;
+    }
+    // Backs the proof job endpoints that estimate or queue submissions; the queued jobs
+    // themselves are submitted by `proof-submitter --watch`
+    match ProofSubmissionRelayer::new(
+        db_pool_arc.as_ref().clone(),
+        ProofSubmissionConfig::from(app_config.clone()),
+    )
+    .await
+    {
+        Ok(relayer) => app_state = app_state.with_proof_relayer(Arc::new(relayer)),
+        Err(e) => warn!(
+            "Proof submission relayer unavailable, its endpoints will answer 503: {}",
+            e
+        ),
+    }
+    // Start the Starknet Relayer service, which also backs POST /relayer/simulate
+    let starknet_relayer = spawn_starknet_relayer(
+        db_pool_arc.clone(),
+        app_config.starknet.fallback_rpc_urls.clone(),
+        &app_state.services,
+    )
+    .await?;
+    app_state = app_state.with_starknet_relayer(starknet_relayer);
+    let services = &app_state.services;
+
+    // Publish config.toml again on SIGHUP; PUT /admin/config/reload does the same
+    #[cfg(unix)]
+    {
+        let config_updates = app_state.config_updates.clone();
+        services.spawn("config_reloader", async move {
+            config_updates.run_sighup_listener().await;
+        });
+    }
+
+    // Release deposits to READY_TO_CLAIM once their challenge window has passed
+    let ethereum_rpc_url = Url::parse(&app_config.ethereum.get_rpc_url())?;
+    let finalization_watcher =
+        FinalizationWatcher::new(db_pool_arc.as_ref().clone(), ethereum_rpc_url);
+    services.spawn("finalization_watcher", async move {
+        info!("Starting deposit finalization watcher");
+        finalization_watcher.run().await;
+    });
+
+    // Deposit events seen on both chains, for the L1 queue to check against each other
+    let commitment_verifier = CommitmentHashVerifier::new();
+    services.spawn(
+        "commitment_verifier",
+        commitment_verifier.record_l2_logs(&app_state.l2_event_bus),
+    );
+
+    // Record L1 deposits as they are emitted
+    let l1_event_watcher = L1EventWatcher::new(
+        app_config.clone(),
+        db_pool_arc.as_ref().clone(),
+        Arc::new(RpcEthereumProvider::new(app_config.ethereum.get_rpc_url())),
+    )
+    .with_commitment_registry(app_state.commitment_registry.clone())
+    .with_commitment_verifier(commitment_verifier.clone());
+    services.spawn("l1_event_watcher", async move {
+        info!("Starting L1 event watcher");
+        l1_event_watcher.run().await;
+    });
+
+    // Validate pending deposits against their L1 commitments, appending processed ones to the
+    // deposit tree GET /deposits/{id}/proof serves. Reloaded queue settings apply from the next
+    // cycle.
+    let l1_queue = L1Queue::new(db_pool_arc.as_ref().clone(), app_config.queue.clone())
+        .with_config_updates(app_state.subscribe_config())
+        .with_deposit_tree(app_state.deposit_tree.clone())
+        .with_commitment_registry(app_state.commitment_registry.clone())
+        .with_commitment_verifier(commitment_verifier);
+    services.spawn("l1_queue", async move {
+        info!("Starting L1 deposit queue");
+        l1_queue.run().await;
+    });
+
+    // Record L2 burns and withdrawal commitments, publishing burns on the L2 event bus
+    let l2_event_watcher = L2EventWatcher::new(
+        app_config.clone(),
+        db_pool_arc.as_ref().clone(),
+        Arc::new(RpcStarknetProvider::new(
+            &app_config.starknet.get_rpc_url(),
+        )?),
+    )
+    .with_event_bus(app_state.l2_event_bus.clone())
+    .with_withdrawal_tree(app_state.withdrawal_tree.clone())
+    .with_block_tracker(app_state.block_tracker.clone());
+    services.spawn("l2_event_watcher", async move {
+        info!("Starting L2 event watcher");
+        l2_event_watcher.run().await;
+    });
+
+    // Prepare burned withdrawals for relay once the bridge contract has recorded their
+    // commitment, woken early by each burn on the bus
+    let l2_queue = L2Queue::new(db_pool_arc.as_ref().clone(), (&app_config.queue).into())
+        .with_starknet_provider(
+            Arc::new(JsonRpcClient::new(HttpTransport::new(Url::parse(
+                &app_config.starknet.get_rpc_url(),
+            )?))),
+            Felt::from_hex(&app_config.contracts.l2_contract_address)?,
+        )
+        .with_event_bus(&app_state.l2_event_bus);
+    services.spawn("l2_queue", async move {
+        info!("Starting L2 withdrawal queue");
+        l2_queue.run().await;
+    });
+
+    // Relay ready withdrawals to L1, publishing each completion for GET /events/completions
+    if app_config.relayer.run_in_sequencer {
+        if let Some(webhook) = app_config.webhook.clone() {
+            Arc::new(WebhookNotifier::new(webhook))
+                .subscribe_completions(&app_state.withdrawal_completions);
+        }
+        let ethereum_relayer = EthereumRelayer::new(
+            db_pool_arc.as_ref().clone(),
+            Url::parse(&app_config.ethereum.get_rpc_url())?,
+            &app_config.contracts.l1_contract_address,
+            app_config.relayer.clone(),
+        )
+        .await?
+        .with_completion_bus(app_state.withdrawal_completions.clone());
+        services.spawn("ethereum_relayer", async move {
+            info!("Starting Ethereum relayer");
+            ethereum_relayer.run().await;
+        });
+    }
+
+    // Generate withdrawal proofs through the Herodotus Atlantic API
+    let proof_generation_worker =
+        ProofGenerationWorker::new(db_pool_arc.as_ref().clone(), app_config.herodotus.clone());
+    services.spawn("proof_generation_worker", async move {
+        info!("Starting withdrawal proof generation worker");
+        proof_generation_worker.run().await;
+    });
+
+    // Keep the L2 oracle's TVL in line with L1, recording the snapshots GET /oracle/tvl reports
+    if app_config.oracle.enabled {
+        let (oracle_config, oracle_pool) = (app_config.clone(), db_pool_arc.as_ref().clone());
+        services.spawn("tvl_oracle", async move {
+            info!("Starting TVL oracle");
+            if let Err(e) = oracle_service::initializer(oracle_config, oracle_pool).await {
+                error!("TVL oracle stopped with error: {}", e);
+            }
+        });
+    }
+
+    // Keep this instance's heartbeat fresh so GET /health reports it as alive
+    let heartbeat_writer = HeartbeatWriter::new(
+        db_pool_arc.as_ref().clone(),
+        app_config.server.get_instance_id(),
+    );
+    services.spawn("heartbeat_writer", async move {
+        heartbeat_writer.run().await;
+    });
+
+    // Serve the HTTP API on the host and port of server_url
+    let port = Url::parse(&app_config.server.server_url)?
+        .port_or_known_default()
+        .ok_or("server.server_url has no port")?;
+    let listener = tokio::net::TcpListener::bind((app_config.server.host.as_str(), port)).await?;
+    let router = create_router(app_state.clone());
+    services.spawn("api_server", async move {
+        info!("Serving the API on port {}", port);
+        if let Err(e) = axum::serve(listener, router).await {
+            error!("API server stopped with error: {:?}", e);
+        }
+    });
+
+    info!("All services started successfully");
+
+    // Keep the main thread alive
+    tokio::signal::ctrl_c().await?;
+    info!("Shutting down ZeroXBridge Sequencer");
+    app_state.shutdown_services(SHUTDOWN_TIMEOUT).await;
+
+    Ok(())
+}
+
+async fn spawn_starknet_relayer(
+    db_pool: Arc<Pool<Postgres>>,
+    fallback_rpc_urls: Vec<String>,
+    services: &ServiceRegistry,
+) -> Result<Arc<StarknetRelayer>, Box<dyn Error>> {
+    // Load Starknet relayer configuration
+    let config = StarknetRelayerConfig {
+        bridge_contract_address: env::var("STARKNET_BRIDGE_CONTRACT")
+            .expect("STARKNET_BRIDGE_CONTRACT must be set"),
+        rpc_url: env::var("STARKNET_RPC_URL").expect("STARKNET_RPC_URL must be set"),
+        fallback_rpc_urls,
+        private_key: env::var("STARKNET_PRIVATE_KEY").expect("STARKNET_PRIVATE_KEY must be set"),
+        max_retries: env::var("STARKNET_MAX_RETRIES")
+            .unwrap_or_else(|_| "3".to_string())
+            .parse()
+            .expect("STARKNET_MAX_RETRIES must be a valid number"),
+        retry_delay_ms: env::var("STARKNET_RETRY_DELAY_MS")
+            .unwrap_or_else(|_| "5000".to_string())
+            .parse()
+            .expect("STARKNET_RETRY_DELAY_MS must be a valid number"),
+        transaction_timeout_ms: env::var("STARKNET_TX_TIMEOUT_MS")
+            .unwrap_or_else(|_| "60000".to_string())
+            .parse()
+            .expect("STARKNET_TX_TIMEOUT_MS must be a valid number"),
+        max_batch_size: env::var("STARKNET_MAX_BATCH_SIZE")
+            .unwrap_or_else(|_| "1".to_string())
+            .parse()
+            .expect("STARKNET_MAX_BATCH_SIZE must be a valid number"),
+        // Left empty when unset so the address is derived from the private key
+        account_address: env::var("STARKNET_ACCOUNT_ADDRESS").unwrap_or_default(),
+        account_type: env::var("STARKNET_ACCOUNT_TYPE")
+            .map(|value| {
+                value
+                    .parse()
+                    .expect("STARKNET_ACCOUNT_TYPE must be openzeppelin or argentx")
+            })
+            .unwrap_or_default(),
+    };
+
+    let max_connections = env::var("STARKNET_MAX_CONNECTIONS")
+        .unwrap_or_else(|_| "8".to_string())
+        .parse()
+        .expect("STARKNET_MAX_CONNECTIONS must be a valid number");
+    let provider_pool = Arc::new(ProviderPool::new(&config.rpc_url, max_connections)?);
+
+    // Initialize the Starknet relayer
+    let relayer = StarknetRelayer::new(db_pool.as_ref().clone(), config, provider_pool)
+        .await
+        .map(Arc::new)
+        .map_err(|e| {
+            error!("Failed to initialize Starknet relayer: {:?}", e);
+            Box::new(e) as Box<dyn Error>
+        })?;
+
+    // Spawn the relayer service in a separate task
+    let service_relayer = relayer.clone();
+    services.spawn("starknet_relayer", async move {
+        info!("Starting Starknet relayer service");
+        if let Err(e) = service_relayer.start().await {
+            error!("Starknet relayer service stopped with error: {:?}", e);
+        }
+    });
+
+    info!("Starknet relayer service spawned");
+
+    Ok(relayer)
 }
@@ -0,0 +1,73 @@
+use clap::{Arg, ArgAction, Command};
+use std::path::PathBuf;
+use std::process;
+use zeroxbridge_sequencer::config::{load_config, AppConfig, ConfigValidator};
+
+fn main() {
+    let matches = Command::new("ZeroXBridge Config")
+        .version("1.0")
+        .about("Parse a sequencer configuration file and print it with secrets redacted")
+        .arg(
+            Arg::new("config")
+                .long("config")
+                .value_name("CONFIG_FILE")
+                .help("Path to configuration file")
+                .default_value("config.toml")
+                .value_parser(clap::value_parser!(String)),
+        )
+        .arg(
+            Arg::new("format")
+                .long("format")
+                .value_name("FORMAT")
+                .help("Output format for the parsed configuration")
+                .default_value("json")
+                .value_parser(["json", "toml"]),
+        )
+        .arg(
+            Arg::new("check")
+                .long("check")
+                .help("Only validate the configuration; exit 0 if it is valid and 1 otherwise")
+                .action(ArgAction::SetTrue),
+        )
+        .get_matches();
+
+    let config_path = PathBuf::from(matches.get_one::<String>("config").unwrap());
+    let format = matches.get_one::<String>("format").unwrap();
+
+    let config = match load_config(Some(&config_path)) {
+        Ok(config) => config,
+        Err(e) => {
+            eprintln!("Failed to load {}: {}", config_path.display(), e);
+            process::exit(1);
+        }
+    };
+
+    if matches.get_flag("check") {
+        match ConfigValidator::validate(&config) {
+            Ok(()) => println!("{} is valid", config_path.display()),
+            Err(errors) => {
+                for e in &errors {
+                    eprintln!("Invalid configuration: {}", e);
+                }
+                process::exit(1);
+            }
+        }
+        return;
+    }
+
+    match render(&config, format) {
+        Ok(rendered) => println!("{}", rendered),
+        Err(e) => {
+            eprintln!("Failed to serialize configuration: {}", e);
+            process::exit(1);
+        }
+    }
+}
+
+/// Secrets are `SensitiveField`s, which serialize as `"***"` in either format
+fn render(config: &AppConfig, format: &str) -> Result<String, String> {
+    match format {
+        "toml" => toml::to_string_pretty(config).map_err(|e| e.to_string()),
+        _ => serde_json::to_string_pretty(config).map_err(|e| e.to_string()),
+    }
+}
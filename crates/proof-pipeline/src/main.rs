@@ -1,11 +1,20 @@
 pub mod pipeline;
-use crate::pipeline::{run_full_stone_pipeline, CalldataArtifacts, ProofError, ProofInputArgs};
+pub mod progress;
+use crate::pipeline::{
+    run_full_stone_pipeline, HasherType, ProgressUpdate, ProofError, ProofInputArgs, StoneVersion,
+};
+use crate::progress::record_stage;
+use sqlx::postgres::PgPoolOptions;
 use std::path::PathBuf;
 use structopt::StructOpt;
+use tokio::sync::watch;
 
 #[derive(Debug, StructOpt)]
 #[structopt(name = "proof-generator", about = "STARK proof generation pipeline")]
 struct Cli {
+    #[structopt(long)]
+    job_id: u64,
+
     #[structopt(long)]
     sierra_path: PathBuf,
     
@@ -22,19 +31,32 @@ struct Cli {
     layout: String,
     
     #[structopt(long, default_value = "keccak_160_lsb")]
-    hasher: String,
+    hasher: HasherType,
     
     #[structopt(long, default_value = "stone6")]
-    stone_version: String,
+    stone_version: StoneVersion,
     
     #[structopt(long)]
     verify: bool,
     
     #[structopt(long)]
     keep_temp_files: bool,
+
+    /// Keep the working directory of a failed run instead of deleting it
+    #[structopt(long)]
+    keep_failed_artifacts: bool,
+
+    /// Kill any prover command still running after this many seconds
+    #[structopt(long)]
+    per_command_timeout_seconds: Option<u64>,
+
+    /// Sequencer database to record each stage in, so the proof-jobs API can report it
+    #[structopt(long)]
+    database_url: Option<String>,
 }
 
-fn main() -> Result<(), ProofError> {
+#[tokio::main]
+async fn main() -> Result<(), ProofError> {
     env_logger::init();
     log::info!("Starting STARK proof generation pipeline");
 
@@ -43,7 +65,16 @@ fn main() -> Result<(), ProofError> {
     let inputs = std::fs::read_to_string(&args.inputs_path)?;
     let program_inputs = serde_json::from_str(&inputs)?;
 
+    let progress_db = args
+        .database_url
+        .as_deref()
+        .map(|url| PgPoolOptions::new().max_connections(1).connect_lazy(url))
+        .transpose()
+        .map_err(|e| ProofError::ValidationErrors(vec![format!("Invalid --database-url: {e}")]))?;
+    let job_id = args.job_id;
+
     let proof_args = ProofInputArgs {
+        job_id: args.job_id,
         sierra_path: args.sierra_path,
         program_inputs,
         prover_parameters: args.prover_params,
@@ -53,9 +84,30 @@ fn main() -> Result<(), ProofError> {
         stone_version: args.stone_version,
         run_verifier: args.verify,
         keep_temp_files: args.keep_temp_files,
+        cleanup_on_failure: !args.keep_failed_artifacts,
+        per_command_timeout_seconds: args.per_command_timeout_seconds,
     };
 
-    let artifacts = run_full_stone_pipeline(proof_args)?;
+    let (progress_tx, mut progress_rx) = watch::channel::<Option<ProgressUpdate>>(None);
+    let progress_logger = tokio::spawn(async move {
+        while progress_rx.changed().await.is_ok() {
+            let Some(update) = *progress_rx.borrow_and_update() else {
+                continue;
+            };
+            log::info!("Pipeline stage: {}", update.stage);
+            if let Some(pool) = &progress_db {
+                // Progress is informational, so a failed write never stops the pipeline
+                if let Err(e) = record_stage(pool, job_id, update).await {
+                    log::warn!("Failed to record pipeline stage {}: {}", update.stage, e);
+                }
+            }
+        }
+    });
+
+    let artifacts = run_full_stone_pipeline(proof_args, &progress_tx).await?;
+    // Dropping the sender ends the logger
+    drop(progress_tx);
+    let _ = progress_logger.await;
 
     println!("\nProof generation successful!");
     println!("Calldata directory: {:?}", artifacts.calldata_dir);
@@ -1,9 +1,14 @@
 use std::{
-    io::{self, Write},
+    collections::HashMap,
+    fmt, io,
     path::{Path, PathBuf},
-    process::Command,
+    process::{Command, Stdio},
+    str::FromStr,
+    time::{Duration, Instant},
 };
-use tempfile::{tempdir, TempDir};
+use tempfile::TempDir;
+use tokio::io::AsyncReadExt;
+use tokio::sync::watch;
 
 #[derive(Debug)]
 pub enum ProofError {
@@ -14,45 +19,357 @@ pub enum ProofError {
         exit_code: Option<i32>,
         stderr: String,
     },
+    /// An external command ran past `ProofInputArgs::per_command_timeout_seconds` and was killed
+    CommandTimeout {
+        command: String,
+        timeout_seconds: u64,
+    },
     VerificationFailed,
+    /// A prover parameter or config file is not the JSON `cpu_air_prover` expects
+    InvalidProverConfig(String),
+    /// Every problem `ProofInputArgs::validate` found with the pipeline's inputs
+    ValidationErrors(Vec<String>),
+}
+
+/// Lines of a failed command's stderr kept in `ProofError`'s message
+const STDERR_TAIL_LINES: usize = 10;
+
+impl ProofError {
+    /// Short, stable label of what went wrong, for structured logging
+    pub fn root_cause(&self) -> &str {
+        match self {
+            ProofError::Io(_) => "io",
+            ProofError::Serialization(_) => "serialization",
+            ProofError::CommandExecution { stderr, .. } if stderr.contains("out of memory") => {
+                "out_of_memory"
+            }
+            ProofError::CommandExecution { stderr, .. } if stderr.contains("assertion failed") => {
+                "assertion_failed"
+            }
+            ProofError::CommandExecution { .. } => "command_failed",
+            ProofError::CommandTimeout { .. } => "command_timeout",
+            ProofError::VerificationFailed => "verification_failed",
+            ProofError::InvalidProverConfig(_) => "invalid_prover_config",
+            ProofError::ValidationErrors(_) => "invalid_inputs",
+        }
+    }
 }
 
+impl fmt::Display for ProofError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ProofError::Io(e) => write!(f, "I/O error: {e}"),
+            ProofError::Serialization(e) => write!(f, "Serialization error: {e}"),
+            ProofError::CommandExecution {
+                command,
+                exit_code,
+                stderr,
+            } => {
+                match exit_code {
+                    Some(code) => write!(f, "`{command}` failed with exit code {code}")?,
+                    None => write!(f, "`{command}` was terminated by a signal")?,
+                }
+
+                // `cpu_air_prover` can print hundreds of lines; the cause is at the end
+                let lines: Vec<&str> = stderr.lines().collect();
+                let tail = &lines[lines.len().saturating_sub(STDERR_TAIL_LINES)..];
+                for line in tail {
+                    write!(f, "\n{line}")?;
+                }
+
+                if stderr.contains("out of memory") {
+                    write!(f, "\nHint: try reducing proof chunk size")?;
+                }
+                if stderr.contains("assertion failed") {
+                    write!(f, "\nHint: verify your cairo program inputs are correct")?;
+                }
+                Ok(())
+            }
+            ProofError::CommandTimeout {
+                command,
+                timeout_seconds,
+            } => write!(
+                f,
+                "`{command}` timed out after {timeout_seconds}s and was killed"
+            ),
+            ProofError::VerificationFailed => write!(f, "Proof verification failed"),
+            ProofError::InvalidProverConfig(message) => {
+                write!(f, "Invalid prover config: {message}")
+            }
+            ProofError::ValidationErrors(errors) => {
+                write!(f, "Invalid pipeline inputs: {}", errors.join("; "))
+            }
+        }
+    }
+}
+
+impl std::error::Error for ProofError {}
+
 #[derive(Debug)]
 pub struct CalldataArtifacts {
     pub calldata_dir: PathBuf,
     pub fact_hash: Option<String>,
     pub proof_path: PathBuf,
+    /// `--version` output of each Stone tool the proof was made with, also written to
+    /// `tool_versions.json` in `calldata_dir`
+    pub tool_versions: HashMap<String, String>,
     _temp_dir: Option<TempDir>,
 }
 
+/// Stone prover versions supported by `swiftness`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StoneVersion {
+    Stone5,
+    Stone6,
+}
+
+impl FromStr for StoneVersion {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "stone5" => Ok(StoneVersion::Stone5),
+            "stone6" => Ok(StoneVersion::Stone6),
+            other => Err(format!(
+                "Invalid stone version '{other}'. Valid options are 'stone5' or 'stone6'"
+            )),
+        }
+    }
+}
+
+impl fmt::Display for StoneVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StoneVersion::Stone5 => write!(f, "stone5"),
+            StoneVersion::Stone6 => write!(f, "stone6"),
+        }
+    }
+}
+
+/// Commitment hashers supported by `swiftness`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HasherType {
+    Keccak160Lsb,
+    Blake2s248Lsb,
+}
+
+impl FromStr for HasherType {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "keccak_160_lsb" => Ok(HasherType::Keccak160Lsb),
+            "blake2s_248_lsb" => Ok(HasherType::Blake2s248Lsb),
+            other => Err(format!(
+                "Invalid hasher '{other}'. Valid options are 'keccak_160_lsb' or 'blake2s_248_lsb'"
+            )),
+        }
+    }
+}
+
+impl fmt::Display for HasherType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            HasherType::Keccak160Lsb => write!(f, "keccak_160_lsb"),
+            HasherType::Blake2s248Lsb => write!(f, "blake2s_248_lsb"),
+        }
+    }
+}
+
+/// Stage of the pipeline that is currently running
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PipelineStage {
+    Execution,
+    ProofGeneration,
+    Verification,
+    CalldataPreparation,
+    Completed,
+}
+
+impl fmt::Display for PipelineStage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PipelineStage::Execution => write!(f, "execution"),
+            PipelineStage::ProofGeneration => write!(f, "proof_generation"),
+            PipelineStage::Verification => write!(f, "verification"),
+            PipelineStage::CalldataPreparation => write!(f, "calldata_preparation"),
+            PipelineStage::Completed => write!(f, "completed"),
+        }
+    }
+}
+
+/// Sent on the progress channel whenever the pipeline moves to a new stage
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ProgressUpdate {
+    pub stage: PipelineStage,
+    pub started_at: Instant,
+}
+
+fn report_progress(progress: &watch::Sender<Option<ProgressUpdate>>, stage: PipelineStage) {
+    // `send_replace` keeps the latest stage even while nobody is subscribed
+    progress.send_replace(Some(ProgressUpdate {
+        stage,
+        started_at: Instant::now(),
+    }));
+}
+
 pub struct ProofInputArgs {
+    /// Names the run's working directory, so concurrent runs never share one
+    pub job_id: u64,
     pub sierra_path: PathBuf,
     pub program_inputs: serde_json::Value,
     pub prover_parameters: PathBuf,
     pub prover_config: PathBuf,
     pub layout: String,
-    pub hasher: String,
-    pub stone_version: String,
+    pub hasher: HasherType,
+    pub stone_version: StoneVersion,
     pub run_verifier: bool,
     pub keep_temp_files: bool,
+    /// Delete the working directory of a failed run, even with `keep_temp_files` set. Turn it
+    /// off to inspect the partial artifacts of a failure.
+    pub cleanup_on_failure: bool,
+    /// Kill any external command still running after this many seconds. `None` lets commands
+    /// run for as long as they take.
+    pub per_command_timeout_seconds: Option<u64>,
+}
+
+impl ProofInputArgs {
+    /// Checks the input files exist and the program inputs are a JSON array, so a bad run fails
+    /// before any external command is started. Every problem found is reported, not just the
+    /// first.
+    pub fn validate(&self) -> Result<(), ProofError> {
+        let mut errors = Vec::new();
+
+        if !self.sierra_path.exists() {
+            errors.push(format!(
+                "Sierra file {} does not exist",
+                self.sierra_path.display()
+            ));
+        }
+        // `.sierra.json` files have a `json` extension too
+        if self.sierra_path.extension().and_then(|ext| ext.to_str()) != Some("json") {
+            errors.push(format!(
+                "Sierra file {} must have a .json or .sierra.json extension",
+                self.sierra_path.display()
+            ));
+        }
+        if !self.prover_parameters.exists() {
+            errors.push(format!(
+                "Prover parameter file {} does not exist",
+                self.prover_parameters.display()
+            ));
+        }
+        if !self.prover_config.exists() {
+            errors.push(format!(
+                "Prover config file {} does not exist",
+                self.prover_config.display()
+            ));
+        }
+        if !self.program_inputs.is_array() {
+            errors.push("Program inputs must be a JSON array".to_string());
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(ProofError::ValidationErrors(errors))
+        }
+    }
+}
+
+/// Top-level keys `cpu_air_prover` requires in its `--parameter_file`
+const REQUIRED_PARAMETER_KEYS: [&str; 2] = ["field", "stark"];
+
+/// Top-level keys `cpu_air_prover` requires in its `--prover_config_file`
+const REQUIRED_CONFIG_KEYS: [&str; 3] = [
+    "constraint_polynomial_task_size",
+    "n_out_of_memory_merkle_layers",
+    "table_prover_n_tasks_per_segment",
+];
+
+/// Checks that the prover parameter and config files are JSON objects holding the keys
+/// `cpu_air_prover` needs, so a bad file fails before the Cairo program is run
+pub fn validate_prover_files(params: &Path, config: &Path) -> Result<(), ProofError> {
+    check_required_keys(params, "Prover parameter", &REQUIRED_PARAMETER_KEYS)?;
+    check_required_keys(config, "Prover config", &REQUIRED_CONFIG_KEYS)
 }
 
-fn execute_command(
+fn check_required_keys(path: &Path, description: &str, keys: &[&str]) -> Result<(), ProofError> {
+    let file = std::fs::File::open(path)?;
+    let value: serde_json::Value =
+        serde_json::from_reader(io::BufReader::new(file)).map_err(|e| {
+            ProofError::InvalidProverConfig(format!(
+                "{description} file {} is not valid JSON: {e}",
+                path.display()
+            ))
+        })?;
+
+    let object = value.as_object().ok_or_else(|| {
+        ProofError::InvalidProverConfig(format!(
+            "{description} file {} is not a JSON object",
+            path.display()
+        ))
+    })?;
+    let missing: Vec<&str> = keys
+        .iter()
+        .copied()
+        .filter(|key| !object.contains_key(*key))
+        .collect();
+    if !missing.is_empty() {
+        return Err(ProofError::InvalidProverConfig(format!(
+            "{description} file {} is missing {}",
+            path.display(),
+            missing.join(", ")
+        )));
+    }
+
+    Ok(())
+}
+
+async fn execute_command(
     command: &str,
     args: &[&str],
+    cwd: &Path,
     description: &str,
+    timeout_seconds: Option<u64>,
 ) -> Result<(), ProofError> {
-    let output = Command::new(command)
+    let mut child = tokio::process::Command::new(command)
         .args(args)
-        .output()
-        .map_err(|e| ProofError::Io(e))?;
+        .current_dir(cwd)
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .spawn()?;
 
-    if !output.status.success() {
-        let stderr = String::from_utf8_lossy(&output.stderr).into_owned();
+    // Drained while the command runs, so a chatty command never blocks on a full pipe
+    let mut stderr_pipe = child.stderr.take().expect("stderr is piped");
+    let stderr_reader = tokio::spawn(async move {
+        let mut stderr = Vec::new();
+        stderr_pipe.read_to_end(&mut stderr).await.map(|_| stderr)
+    });
+
+    let status = match timeout_seconds {
+        Some(timeout_seconds) => {
+            match tokio::time::timeout(Duration::from_secs(timeout_seconds), child.wait()).await {
+                Ok(status) => status?,
+                Err(_) => {
+                    child.kill().await?;
+                    stderr_reader.abort();
+                    return Err(ProofError::CommandTimeout {
+                        command: format!("{command} {}", args.join(" ")),
+                        timeout_seconds,
+                    });
+                }
+            }
+        }
+        None => child.wait().await?,
+    };
+
+    if !status.success() {
+        let stderr = stderr_reader.await.map_err(io::Error::other)??;
         return Err(ProofError::CommandExecution {
             command: format!("{command} {}", args.join(" ")),
-            exit_code: output.status.code(),
-            stderr,
+            exit_code: status.code(),
+            stderr: String::from_utf8_lossy(&stderr).into_owned(),
         });
     }
 
@@ -60,11 +377,89 @@ fn execute_command(
     Ok(())
 }
 
-pub fn run_full_stone_pipeline(
+/// Tools whose versions are recorded with every proof
+const VERSIONED_TOOLS: [&str; 3] = ["cairo1-run", "cpu_air_prover", "swiftness"];
+
+/// Recorded for a tool whose `--version` could not be run
+const UNKNOWN_VERSION: &str = "unknown";
+
+/// `--version` output of `cairo1-run`, `cpu_air_prover` and `swiftness`, keyed by tool
+pub fn detect_tool_versions() -> HashMap<String, String> {
+    VERSIONED_TOOLS
+        .iter()
+        .map(|tool| {
+            let version = match Command::new(tool).arg("--version").output() {
+                Ok(output) if output.status.success() => {
+                    String::from_utf8_lossy(&output.stdout).trim().to_owned()
+                }
+                Ok(output) => {
+                    log::warn!("`{tool} --version` exited with {}", output.status);
+                    UNKNOWN_VERSION.to_owned()
+                }
+                Err(e) => {
+                    log::warn!("Could not run `{tool} --version`: {e}");
+                    UNKNOWN_VERSION.to_owned()
+                }
+            };
+            (tool.to_string(), version)
+        })
+        .collect()
+}
+
+/// Runs the Stone proving pipeline, reporting each stage on `progress` as it starts
+pub async fn run_full_stone_pipeline(
     args: ProofInputArgs,
+    progress: &watch::Sender<Option<ProgressUpdate>>,
 ) -> Result<CalldataArtifacts, ProofError> {
-    let temp_dir = tempdir().map_err(ProofError::Io)?;
-    let temp_path = temp_dir.path();
+    args.validate()?;
+    validate_prover_files(&args.prover_parameters, &args.prover_config)?;
+    let tool_versions = detect_tool_versions();
+
+    let temp_dir = TempDir::with_prefix(format!("stone-{}-", args.job_id))?;
+    if let Err(e) = run_stages(&args, temp_dir.path(), &tool_versions, progress).await {
+        discard_failed_run(temp_dir, args.cleanup_on_failure);
+        return Err(e);
+    }
+    let calldata_dir = temp_dir.path().join("calldata");
+    let proof_path = temp_dir.path().join("target/proof.json");
+
+    // Handle temp directory persistence
+    let (calldata_dir, proof_path, _temp_dir) = if args.keep_temp_files {
+        let persistent_path = temp_dir.keep();
+        (
+            persistent_path.join("calldata"),
+            persistent_path.join("target/proof.json"),
+            None,
+        )
+    } else {
+        (
+            calldata_dir,
+            proof_path.clone(),
+            Some(temp_dir),
+        )
+    };
+
+    Ok(CalldataArtifacts {
+        calldata_dir: calldata_dir.clone(),
+        fact_hash: extract_fact_hash(&calldata_dir)?,
+        proof_path,
+        tool_versions,
+        _temp_dir,
+    })
+}
+
+/// Runs every pipeline command inside `temp_path`, leaving the proof in `target/proof.json`
+/// and the calldata in `calldata`
+async fn run_stages(
+    args: &ProofInputArgs,
+    temp_path: &Path,
+    tool_versions: &HashMap<String, String>,
+    progress: &watch::Sender<Option<ProgressUpdate>>,
+) -> Result<(), ProofError> {
+    // Commands run inside the temp dir, so relative input paths must be resolved first
+    let sierra_path = std::path::absolute(&args.sierra_path)?;
+    let prover_parameters = std::path::absolute(&args.prover_parameters)?;
+    let prover_config = std::path::absolute(&args.prover_config)?;
     let target_dir = temp_path.join("target");
     std::fs::create_dir(&target_dir)?;
 
@@ -78,10 +473,11 @@ pub fn run_full_stone_pipeline(
     let trace_file = target_dir.join("trace");
     let memory_file = target_dir.join("memory");
 
+    report_progress(progress, PipelineStage::Execution);
     execute_command(
         "cairo1-run",
         &[
-            args.sierra_path.to_str().unwrap(),
+            sierra_path.to_str().unwrap(),
             "--layout",
             &args.layout,
             "--arguments-file",
@@ -96,8 +492,12 @@ pub fn run_full_stone_pipeline(
             "--memory_file",
             memory_file.to_str().unwrap(),
         ],
+        temp_path,
         "Cairo execution (cairo1-run)",
-    )?;
+        args.per_command_timeout_seconds,
+    )
+    .await?;
+    report_progress(progress, PipelineStage::ProofGeneration);
 
     // 3. Generate proof with cpu_air_prover
     let proof_path = target_dir.join("proof.json");
@@ -105,9 +505,9 @@ pub fn run_full_stone_pipeline(
         "cpu_air_prover",
         &[
             "--parameter_file",
-            args.prover_parameters.to_str().unwrap(),
+            prover_parameters.to_str().unwrap(),
             "--prover_config_file",
-            args.prover_config.to_str().unwrap(),
+            prover_config.to_str().unwrap(),
             "--private_input_file",
             private_input.to_str().unwrap(),
             "--public_input_file",
@@ -117,20 +517,30 @@ pub fn run_full_stone_pipeline(
             "--generate_annotations",
             "true",
         ],
+        temp_path,
         "Proof generation (cpu_air_prover)",
-    )?;
+        args.per_command_timeout_seconds,
+    )
+    .await?;
 
     // 4. Optionally verify proof
     if args.run_verifier {
+        report_progress(progress, PipelineStage::Verification);
         execute_command(
             "cpu_air_verifier",
             &["--in_file", proof_path.to_str().unwrap()],
+            temp_path,
             "Proof verification (cpu_air_verifier)",
-        )?;
+            args.per_command_timeout_seconds,
+        )
+        .await?;
     }
+    report_progress(progress, PipelineStage::CalldataPreparation);
 
     // 5. Prepare calldata with swiftness
     let calldata_dir = temp_path.join("calldata");
+    let hasher = args.hasher.to_string();
+    let stone_version = args.stone_version.to_string();
     execute_command(
         "swiftness",
         &[
@@ -139,37 +549,45 @@ pub fn run_full_stone_pipeline(
             "--layout",
             &args.layout,
             "--hasher",
-            &args.hasher,
+            &hasher,
             "--stone-version",
-            &args.stone_version,
+            &stone_version,
             "--out",
             calldata_dir.to_str().unwrap(),
         ],
+        temp_path,
         "Calldata preparation (swiftness)",
+        args.per_command_timeout_seconds,
+    )
+    .await?;
+    std::fs::create_dir_all(&calldata_dir)?;
+    std::fs::write(
+        calldata_dir.join("tool_versions.json"),
+        serde_json::to_vec_pretty(&tool_versions)?,
     )?;
+    report_progress(progress, PipelineStage::Completed);
 
-    // Handle temp directory persistence
-    let (calldata_dir, proof_path, _temp_dir) = if args.keep_temp_files {
-        let persistent_path = temp_dir.into_path();
-        (
-            persistent_path.join("calldata"),
-            persistent_path.join("target/proof.json"),
-            None,
-        )
-    } else {
-        (
-            calldata_dir,
-            proof_path.clone(),
-            Some(temp_dir),
-        )
-    };
+    Ok(())
+}
 
-    Ok(CalldataArtifacts {
-        calldata_dir: calldata_dir.clone(),
-        fact_hash: extract_fact_hash(&calldata_dir)?,
-        proof_path,
-        _temp_dir,
-    })
+/// Deletes the working directory of a failed run, or keeps it for inspection when
+/// `cleanup_on_failure` is off
+fn discard_failed_run(temp_dir: TempDir, cleanup_on_failure: bool) {
+    if cleanup_on_failure {
+        let path = temp_dir.path().to_path_buf();
+        if let Err(e) = temp_dir.close() {
+            log::warn!(
+                "Could not remove failed pipeline run {}: {e}",
+                path.display()
+            );
+        }
+    } else {
+        let path = temp_dir.keep();
+        log::warn!(
+            "Kept artifacts of failed pipeline run in {}",
+            path.display()
+        );
+    }
 }
 
 /// Extract fact hash from swiftness output
@@ -198,4 +616,400 @@ impl From<serde_json::Error> for ProofError {
     fn from(e: serde_json::Error) -> Self {
         ProofError::Serialization(e)
     }
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::os::unix::fs::PermissionsExt;
+
+    const STONE_COMMANDS: [&str; 4] = [
+        "cairo1-run",
+        "cpu_air_prover",
+        "cpu_air_verifier",
+        "swiftness",
+    ];
+
+    /// Layout that makes the `swiftness` stand-in fail, after the proof has been generated
+    const FAILING_LAYOUT: &str = "failing_layout";
+
+    /// Puts stand-ins for the Stone tools on `PATH` that succeed immediately, each leaving a
+    /// marker in the directory it ran in. `--version` prints `<command> 1.2.3` instead.
+    ///
+    /// Installed once for every test, since `PATH` is shared by the whole process.
+    fn install_stub_commands() -> &'static Path {
+        static BIN_DIR: std::sync::OnceLock<TempDir> = std::sync::OnceLock::new();
+
+        BIN_DIR
+            .get_or_init(|| {
+                let bin_dir = tempfile::tempdir().unwrap();
+                for command in STONE_COMMANDS {
+                    let path = bin_dir.path().join(command);
+                    let mut script = format!(
+                        "#!/bin/sh\n\
+                         if [ \"$1\" = \"--version\" ]; then echo \"{command} 1.2.3\"; exit 0; fi\n\
+                         touch {command}.ran\n"
+                    );
+                    if command == "swiftness" {
+                        script.push_str(&format!(
+                            "case \"$*\" in *{FAILING_LAYOUT}*) echo boom >&2; exit 1;; esac\n"
+                        ));
+                    }
+                    std::fs::write(&path, script).unwrap();
+                    std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755))
+                        .unwrap();
+                }
+
+                let path = std::env::var_os("PATH").unwrap_or_default();
+                let paths = std::iter::once(bin_dir.path().to_path_buf())
+                    .chain(std::env::split_paths(&path));
+                // SAFETY: this is the only place in the crate's tests touching the environment
+                unsafe { std::env::set_var("PATH", std::env::join_paths(paths).unwrap()) };
+                bin_dir
+            })
+            .path()
+    }
+
+    const VALID_PARAMS: &str = r#"{"field": "PrimeField0", "stark": {"log_n_cosets": 2}}"#;
+    const VALID_CONFIG: &str = r#"{
+        "constraint_polynomial_task_size": 256,
+        "n_out_of_memory_merkle_layers": 1,
+        "table_prover_n_tasks_per_segment": 32
+    }"#;
+
+    /// Writes `prover_params.json` and `prover_config.json` into `dir`
+    fn write_prover_files(dir: &Path, params: &str, config: &str) -> (PathBuf, PathBuf) {
+        let params_path = dir.join("prover_params.json");
+        let config_path = dir.join("prover_config.json");
+        std::fs::write(&params_path, params).unwrap();
+        std::fs::write(&config_path, config).unwrap();
+        (params_path, config_path)
+    }
+
+    /// Empty stand-in for a compiled Sierra program
+    fn sierra_file() -> tempfile::NamedTempFile {
+        tempfile::Builder::new()
+            .suffix(".sierra.json")
+            .tempfile()
+            .unwrap()
+    }
+
+    fn stub_args(job_id: u64, sierra_path: &Path, prover_dir: &Path) -> ProofInputArgs {
+        ProofInputArgs {
+            job_id,
+            sierra_path: sierra_path.to_path_buf(),
+            program_inputs: serde_json::json!([1, 2]),
+            prover_parameters: prover_dir.join("prover_params.json"),
+            prover_config: prover_dir.join("prover_config.json"),
+            layout: "recursive_with_poseidon".to_string(),
+            hasher: HasherType::Keccak160Lsb,
+            stone_version: StoneVersion::Stone6,
+            run_verifier: true,
+            keep_temp_files: false,
+            cleanup_on_failure: true,
+            per_command_timeout_seconds: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_pipelines_use_separate_working_dirs() {
+        install_stub_commands();
+        let sierra = sierra_file();
+        let prover_dir = tempfile::tempdir().unwrap();
+        write_prover_files(prover_dir.path(), VALID_PARAMS, VALID_CONFIG);
+
+        let run = |job_id| {
+            let args = stub_args(job_id, sierra.path(), prover_dir.path());
+            async move {
+                let (progress, _) = watch::channel(None);
+                run_full_stone_pipeline(args, &progress).await.unwrap()
+            }
+        };
+        let (first, second) = tokio::join!(run(1), run(2));
+
+        let first_dir = first.calldata_dir.parent().unwrap();
+        let second_dir = second.calldata_dir.parent().unwrap();
+        assert_ne!(first_dir, second_dir);
+        for (dir, job_id) in [(first_dir, 1), (second_dir, 2)] {
+            let name = dir.file_name().unwrap().to_string_lossy();
+            assert!(name.starts_with(&format!("stone-{job_id}-")), "{name}");
+            for command in STONE_COMMANDS {
+                assert!(dir.join(format!("{command}.ran")).exists());
+            }
+            assert!(dir.join("input.json").exists());
+        }
+
+        for artifacts in [&first, &second] {
+            let written: HashMap<String, String> = serde_json::from_slice(
+                &std::fs::read(artifacts.calldata_dir.join("tool_versions.json")).unwrap(),
+            )
+            .unwrap();
+            assert_eq!(written, artifacts.tool_versions);
+            assert_eq!(written.len(), 3);
+            for tool in VERSIONED_TOOLS {
+                assert_eq!(written[tool], format!("{tool} 1.2.3"));
+            }
+        }
+    }
+
+    fn command_error(stderr: &str) -> ProofError {
+        ProofError::CommandExecution {
+            command: "cpu_air_prover --out_file proof.json".to_string(),
+            exit_code: Some(1),
+            stderr: stderr.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_command_error_keeps_last_stderr_lines() {
+        let stderr: String = (1..=50).map(|n| format!("line {n}\n")).collect();
+
+        let message = command_error(&stderr).to_string();
+
+        assert!(
+            message.starts_with("`cpu_air_prover --out_file proof.json` failed with exit code 1")
+        );
+        assert!(message.contains("line 41\n"), "{message}");
+        assert!(message.ends_with("line 50"), "{message}");
+        assert!(!message.contains("line 40\n"), "{message}");
+        assert!(!message.contains("Hint"), "{message}");
+    }
+
+    #[test]
+    fn test_command_error_hints() {
+        let oom = command_error("allocating trace\nerror: out of memory\n");
+        assert!(
+            oom.to_string()
+                .ends_with("Hint: try reducing proof chunk size")
+        );
+        assert_eq!(oom.root_cause(), "out_of_memory");
+
+        let assertion = command_error("thread 'main' panicked: assertion failed: n_steps > 0\n");
+        assert!(
+            assertion
+                .to_string()
+                .ends_with("Hint: verify your cairo program inputs are correct")
+        );
+        assert_eq!(assertion.root_cause(), "assertion_failed");
+
+        assert_eq!(command_error("segfault").root_cause(), "command_failed");
+        assert_eq!(
+            ProofError::VerificationFailed.root_cause(),
+            "verification_failed"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_hung_command_is_killed_after_timeout() {
+        let dir = tempfile::tempdir().unwrap();
+        let started = Instant::now();
+
+        let result = execute_command("sleep", &["30"], dir.path(), "Sleeping", Some(1)).await;
+
+        assert!(
+            started.elapsed() < Duration::from_secs(5),
+            "{:?}",
+            started.elapsed()
+        );
+        let error = result.unwrap_err();
+        assert_eq!(error.root_cause(), "command_timeout");
+        match error {
+            ProofError::CommandTimeout {
+                command,
+                timeout_seconds,
+            } => {
+                assert_eq!(command, "sleep 30");
+                assert_eq!(timeout_seconds, 1);
+            }
+            other => panic!("Expected CommandTimeout, got {other:?}"),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_command_within_timeout_succeeds() {
+        let dir = tempfile::tempdir().unwrap();
+
+        execute_command("true", &[], dir.path(), "Nothing", Some(5))
+            .await
+            .unwrap();
+        let result = execute_command("false", &[], dir.path(), "Failing", Some(5)).await;
+        assert!(
+            matches!(
+                result,
+                Err(ProofError::CommandExecution {
+                    exit_code: Some(1),
+                    ..
+                })
+            ),
+            "{result:?}"
+        );
+    }
+
+    #[test]
+    fn test_valid_prover_files_pass() {
+        let dir = tempfile::tempdir().unwrap();
+        let (params, config) = write_prover_files(dir.path(), VALID_PARAMS, VALID_CONFIG);
+
+        validate_prover_files(&params, &config).unwrap();
+    }
+
+    #[test]
+    fn test_missing_prover_file_is_io_error() {
+        let dir = tempfile::tempdir().unwrap();
+        let (params, _) = write_prover_files(dir.path(), VALID_PARAMS, VALID_CONFIG);
+
+        let result = validate_prover_files(&params, &dir.path().join("missing.json"));
+
+        assert!(matches!(result, Err(ProofError::Io(_))), "{result:?}");
+    }
+
+    #[test]
+    fn test_malformed_prover_files_are_rejected() {
+        let cases = [
+            ("{not json", VALID_CONFIG, "not valid JSON"),
+            ("[1, 2]", VALID_CONFIG, "not a JSON object"),
+            (r#"{"field": "PrimeField0"}"#, VALID_CONFIG, "missing stark"),
+            (
+                VALID_PARAMS,
+                r#"{"constraint_polynomial_task_size": 256}"#,
+                "missing n_out_of_memory_merkle_layers, table_prover_n_tasks_per_segment",
+            ),
+        ];
+
+        for (params, config, expected) in cases {
+            let dir = tempfile::tempdir().unwrap();
+            let (params, config) = write_prover_files(dir.path(), params, config);
+
+            match validate_prover_files(&params, &config) {
+                Err(ProofError::InvalidProverConfig(message)) => {
+                    assert!(message.contains(expected), "{message}")
+                }
+                other => panic!("Expected InvalidProverConfig, got {other:?}"),
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_pipeline_rejects_invalid_prover_config_before_running() {
+        let sierra = sierra_file();
+        let prover_dir = tempfile::tempdir().unwrap();
+        write_prover_files(prover_dir.path(), VALID_PARAMS, "{}");
+        let (progress, _) = watch::channel(None);
+
+        let result =
+            run_full_stone_pipeline(stub_args(3, sierra.path(), prover_dir.path()), &progress)
+                .await;
+
+        assert!(matches!(result, Err(ProofError::InvalidProverConfig(_))));
+        assert!(progress.borrow().is_none());
+    }
+
+    #[test]
+    fn test_valid_inputs_pass_validation() {
+        let sierra = sierra_file();
+        let prover_dir = tempfile::tempdir().unwrap();
+        write_prover_files(prover_dir.path(), VALID_PARAMS, VALID_CONFIG);
+
+        stub_args(4, sierra.path(), prover_dir.path())
+            .validate()
+            .unwrap();
+    }
+
+    #[test]
+    fn test_validation_collects_every_error() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut args = stub_args(5, &dir.path().join("program.cairo"), dir.path());
+        args.program_inputs = serde_json::json!({"inputs": [1, 2]});
+
+        let errors = match args.validate() {
+            Err(ProofError::ValidationErrors(errors)) => errors,
+            other => panic!("Expected ValidationErrors, got {other:?}"),
+        };
+
+        assert_eq!(errors.len(), 5, "{errors:?}");
+        assert!(errors[0].ends_with("program.cairo does not exist"));
+        assert!(errors[1].ends_with("must have a .json or .sierra.json extension"));
+        assert!(errors[2].starts_with("Prover parameter file"));
+        assert!(errors[3].starts_with("Prover config file"));
+        assert_eq!(errors[4], "Program inputs must be a JSON array");
+    }
+
+    #[tokio::test]
+    async fn test_pipeline_validates_inputs_before_running() {
+        let dir = tempfile::tempdir().unwrap();
+        let (progress, _) = watch::channel(None);
+
+        let result = run_full_stone_pipeline(
+            stub_args(6, &dir.path().join("missing.sierra.json"), dir.path()),
+            &progress,
+        )
+        .await;
+
+        match result {
+            Err(error @ ProofError::ValidationErrors(_)) => {
+                assert_eq!(error.root_cause(), "invalid_inputs");
+                assert!(error.to_string().starts_with("Invalid pipeline inputs: "));
+            }
+            other => panic!("Expected ValidationErrors, got {other:?}"),
+        }
+        assert!(progress.borrow().is_none());
+    }
+
+    /// Working directories of `job_id`'s runs still on disk
+    fn working_dirs(job_id: u64) -> Vec<PathBuf> {
+        let prefix = format!("stone-{job_id}-");
+        std::fs::read_dir(std::env::temp_dir())
+            .unwrap()
+            .filter_map(|entry| entry.ok())
+            .filter(|entry| entry.file_name().to_string_lossy().starts_with(&prefix))
+            .map(|entry| entry.path())
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_working_dir_cleanup_for_each_flag_combination() {
+        install_stub_commands();
+        let sierra = sierra_file();
+        let prover_dir = tempfile::tempdir().unwrap();
+        write_prover_files(prover_dir.path(), VALID_PARAMS, VALID_CONFIG);
+
+        // (keep_temp_files, cleanup_on_failure, kept after success, kept after failure)
+        let cases = [
+            (false, true, false, false),
+            (false, false, false, true),
+            (true, true, true, false),
+            (true, false, true, true),
+        ];
+
+        for (index, (keep_temp_files, cleanup_on_failure, kept_on_success, kept_on_failure)) in
+            cases.into_iter().enumerate()
+        {
+            for (failing, expected) in [(false, kept_on_success), (true, kept_on_failure)] {
+                let job_id = 7_000 + index as u64 * 2 + failing as u64;
+                let mut args = stub_args(job_id, sierra.path(), prover_dir.path());
+                args.keep_temp_files = keep_temp_files;
+                args.cleanup_on_failure = cleanup_on_failure;
+                if failing {
+                    args.layout = FAILING_LAYOUT.to_string();
+                }
+                let (progress, _) = watch::channel(None);
+
+                let result = run_full_stone_pipeline(args, &progress).await;
+                assert_eq!(result.is_err(), failing);
+                drop(result);
+
+                let dirs = working_dirs(job_id);
+                let case = format!(
+                    "keep_temp_files={keep_temp_files}, cleanup_on_failure={cleanup_on_failure}, failing={failing}"
+                );
+                assert_eq!(dirs.len(), expected as usize, "{case}");
+                if failing && expected {
+                    // Partial artifacts of the stages that did run are left for inspection
+                    assert!(dirs[0].join("cpu_air_prover.ran").exists(), "{case}");
+                }
+                for dir in dirs {
+                    std::fs::remove_dir_all(dir).unwrap();
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,30 @@
+use crate::pipeline::ProgressUpdate;
+use chrono::Utc;
+use sqlx::PgPool;
+
+/// Records `update` as the current stage of `job_id` in the sequencer's `proof_pipeline_progress`
+/// table, which `GET /proof-jobs/{job_id}/pipeline` reads
+pub async fn record_stage(
+    pool: &PgPool,
+    job_id: u64,
+    update: ProgressUpdate,
+) -> Result<(), sqlx::Error> {
+    let elapsed = chrono::Duration::from_std(update.started_at.elapsed())
+        .unwrap_or_else(|_| chrono::Duration::zero());
+
+    sqlx::query(
+        r#"
+        INSERT INTO proof_pipeline_progress (job_id, stage, stage_started_at, updated_at)
+        VALUES ($1, $2, $3, NOW())
+        ON CONFLICT (job_id) DO UPDATE
+        SET stage = EXCLUDED.stage, stage_started_at = EXCLUDED.stage_started_at, updated_at = NOW()
+        "#,
+    )
+    .bind(job_id as i64)
+    .bind(update.stage.to_string())
+    .bind(Utc::now() - elapsed)
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
@@ -0,0 +1,74 @@
+use std::sync::Mutex;
+
+use async_trait::async_trait;
+
+use crate::types::Result;
+
+/// Computes a tree's root by bagging its peaks, along with the element count it covers
+#[async_trait]
+pub trait RootComputer {
+    async fn compute_root(&self) -> Result<([u8; 32], usize)>;
+}
+
+#[derive(Debug)]
+struct CacheState {
+    dirty: bool,
+    root: Option<([u8; 32], usize)>,
+    /// Bumped by every `invalidate`, so a root computed before one isn't cached after it
+    generation: u64,
+}
+
+/// Remembers the last computed root so it is only recomputed after new leaves are added
+#[derive(Debug)]
+pub struct PeaksCache {
+    state: Mutex<CacheState>,
+}
+
+impl PeaksCache {
+    pub fn new() -> Self {
+        Self {
+            state: Mutex::new(CacheState {
+                dirty: true,
+                root: None,
+                generation: 0,
+            }),
+        }
+    }
+
+    /// Marks the cached root stale, e.g. after appending leaves
+    pub fn invalidate(&self) {
+        let mut state = self.state.lock().unwrap();
+        state.dirty = true;
+        state.generation += 1;
+    }
+
+    /// Returns the cached root, or computes it with `computer` when it is stale
+    pub async fn get_or_compute<C>(&self, computer: &C) -> Result<([u8; 32], usize)>
+    where
+        C: RootComputer + Sync + ?Sized,
+    {
+        let generation = {
+            let state = self.state.lock().unwrap();
+            if let (false, Some(root)) = (state.dirty, state.root) {
+                return Ok(root);
+            }
+            state.generation
+        };
+
+        let root = computer.compute_root().await?;
+
+        // Leaves appended while the root was computed leave the cache stale
+        let mut state = self.state.lock().unwrap();
+        if state.generation == generation {
+            state.root = Some(root);
+            state.dirty = false;
+        }
+        Ok(root)
+    }
+}
+
+impl Default for PeaksCache {
+    fn default() -> Self {
+        Self::new()
+    }
+}
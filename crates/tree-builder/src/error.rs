@@ -18,6 +18,12 @@ pub enum TreeBuilderError {
     ConversionError(String),
     #[error("Invalid leaf hash: {0}")]
     InvalidLeafHash(String),
+    #[error("Index {index} is out of bounds for a tree with {len} leaves")]
+    IndexOutOfBounds { index: usize, len: usize },
+    #[error("Invalid tree checkpoint: {0}")]
+    InvalidCheckpoint(String),
+    #[error("Tree already holds the maximum of {limit} elements")]
+    MaxElementsExceeded { limit: u64 },
     #[error(transparent)]
     FromHexError(#[from] hex::FromHexError),
 }
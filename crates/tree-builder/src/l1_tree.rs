@@ -1,16 +1,30 @@
-use std::{array::TryFromSliceError, sync::Arc};
+use std::{array::TryFromSliceError, collections::HashMap, sync::Arc, time::Instant};
 
 use accumulators::{
     hasher::keccak::KeccakHasher,
     mmr::{Proof, MMR},
-    store::memory::InMemoryStore,
+    store::{memory::InMemoryStore, SubKey},
 };
+use sqlx::PgPool;
 
-use crate::{error::TreeBuilderError, types::Result};
+use crate::{
+    error::TreeBuilderError,
+    pg_store::PgStore,
+    types::{Result, RootSender},
+};
+
+/// Stored leaves fetched per query when a builder picks up an existing tree
+const LEAF_FETCH_BATCH_SIZE: usize = 10_000;
 
 /// A builder for constructing Merkle trees and generating proofs
 pub struct L1MerkleTreeBuilder {
     mmr: MMR,
+    /// Maps leaf hex strings to their MMR element index for constant-time lookups
+    leaf_indices: HashMap<String, usize>,
+    /// Notified with the new root after every build
+    root_notifier: Option<RootSender>,
+    /// `(elements_count, root, taken_at)` after every build, oldest first
+    root_snapshots: Vec<(u64, [u8; 32], Instant)>,
 }
 
 impl L1MerkleTreeBuilder {
@@ -32,47 +46,127 @@ impl L1MerkleTreeBuilder {
 
         Self {
             mmr: MMR::new(store_rc, hasher, None),
+            leaf_indices: HashMap::new(),
+            root_notifier: None,
+            root_snapshots: Vec::new(),
         }
     }
 
+    /// Creates a builder whose MMR nodes are stored in `{table_prefix}_mmr_nodes`, e.g. `l1`
+    /// for the deposit tree, picking up the tree a previous builder on the same table left.
+    ///
+    /// The leaves already stored are indexed up front, so `find_leaf_by_commitment_hash` and
+    /// `get_proof` find them too. No root snapshots are kept from before.
+    pub async fn new_with_pg_store(pool: PgPool, table_prefix: &str) -> Result<Self> {
+        let store = Arc::new(PgStore::new(pool, table_prefix));
+        let hasher = Arc::new(KeccakHasher::new());
+
+        let mut builder = Self {
+            // A fixed id keys the nodes the same way after every restart
+            mmr: MMR::new(store, hasher, Some(table_prefix.to_string())),
+            leaf_indices: HashMap::new(),
+            root_notifier: None,
+            root_snapshots: Vec::new(),
+        };
+        builder.load_leaf_indices().await?;
+        Ok(builder)
+    }
+
+    /// Fills `leaf_indices` with the leaves already in the store, `LEAF_FETCH_BATCH_SIZE` per query
+    async fn load_leaf_indices(&mut self) -> Result<()> {
+        let elements_count = self.mmr.elements_count.get().await?;
+
+        // The n-th leaf (0-based) sits at element index 2n - popcount(n) + 1
+        let leaf_positions: Vec<usize> = (0..)
+            .map(|n: usize| 2 * n - n.count_ones() as usize + 1)
+            .take_while(|index| *index <= elements_count)
+            .collect();
+        for batch in leaf_positions.chunks(LEAF_FETCH_BATCH_SIZE) {
+            let sub_keys = batch.iter().map(|index| SubKey::Usize(*index)).collect();
+            let hashes = self.mmr.hashes.get_many(sub_keys).await?;
+            for index in batch {
+                if let Some(leaf_str) = hashes.get(&index.to_string()) {
+                    // Keep the first position if the same commitment was appended twice
+                    self.leaf_indices.entry(leaf_str.clone()).or_insert(*index);
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Publishes the new root to `notifier` after every `build_merkle`
+    pub fn with_root_notifier(mut self, notifier: RootSender) -> Self {
+        self.root_notifier = Some(notifier);
+        self
+    }
+
     /// Builds a Merkle tree from a list of commitment hashes
     pub async fn build_merkle(&mut self, leaves: Vec<[u8; 32]>) -> Result<()> {
         for leaf in leaves {
-            self.mmr.append(format!("0x{}", hex::encode(leaf))).await?;
+            let leaf_str = format!("0x{}", hex::encode(leaf));
+            let result = self.mmr.append(leaf_str.clone()).await?;
+            // Keep the first position if the same commitment is appended twice
+            self.leaf_indices
+                .entry(leaf_str)
+                .or_insert(result.element_index);
+        }
+
+        let (elements_count, root) = self.get_root_with_elements_count().await?;
+        self.record_snapshot(elements_count, root);
+        if let Some(notifier) = &self.root_notifier {
+            notifier.send_replace(Some(root));
         }
         Ok(())
     }
 
+    fn record_snapshot(&mut self, elements_count: u64, root: [u8; 32]) {
+        // A build without leaves leaves the tree unchanged
+        if let Some((last_count, _, _)) = self.root_snapshots.last() {
+            if *last_count == elements_count {
+                return;
+            }
+        }
+        self.root_snapshots
+            .push((elements_count, root, Instant::now()));
+    }
+
+    /// Looks up the MMR element index of a leaf by its commitment hash
+    pub fn find_leaf_by_commitment_hash(&self, leaf: [u8; 32]) -> Option<usize> {
+        let leaf_str = format!("0x{}", hex::encode(leaf));
+        self.leaf_indices.get(&leaf_str).copied()
+    }
+
     /// Gets the current Merkle root
     pub async fn get_root(&self) -> Result<[u8; 32]> {
+        let (_, root) = self.get_root_with_elements_count().await?;
+        Ok(root)
+    }
+
+    /// Gets the current MMR elements count together with the root it produces
+    pub async fn get_root_with_elements_count(&self) -> Result<(u64, [u8; 32])> {
         let bag = self.mmr.bag_the_peaks(None).await?;
         let elements_count = self.mmr.elements_count.get().await?;
         let root = self.mmr.calculate_root_hash(&bag, elements_count)?;
-        Self::decode_hex(&root)
+        Ok((elements_count as u64, Self::decode_hex(&root)?))
     }
 
-    /// Generates a Merkle proof for a given leaf
-    pub async fn get_proof(&self, leaf: [u8; 32]) -> Result<Option<Proof>> {
-        let elements_count = self.mmr.elements_count.get().await?;
-        let leaf_str = format!("0x{}", hex::encode(leaf));
+    /// Gets the current MMR elements count, leaves and parent nodes alike
+    pub async fn elements_count(&self) -> Result<u64> {
+        Ok(self.mmr.elements_count.get().await? as u64)
+    }
 
-        // Find the leaf index by scanning elements
-        let mut leaf_index = None;
-        for i in 1..=elements_count {
-            if let Some(hash) = self
-                .mmr
-                .hashes
-                .get(accumulators::store::SubKey::Usize(i))
-                .await?
-            {
-                if hash == leaf_str {
-                    leaf_index = Some(i);
-                    break;
-                }
-            }
-        }
+    /// Root the tree had when it held `elements_count` elements, if a build ended there
+    pub fn get_root_at_elements_count(&self, elements_count: u64) -> Option<[u8; 32]> {
+        self.root_snapshots
+            .binary_search_by_key(&elements_count, |(count, _, _)| *count)
+            .ok()
+            .map(|index| self.root_snapshots[index].1)
+    }
 
-        if let Some(idx) = leaf_index {
+    /// Generates a Merkle proof for a given leaf
+    pub async fn get_proof(&self, leaf: [u8; 32]) -> Result<Option<Proof>> {
+        if let Some(idx) = self.find_leaf_by_commitment_hash(leaf) {
             let proof = self.mmr.get_proof(idx, None).await?;
             Ok(Some(proof))
         } else {
@@ -136,4 +230,113 @@ mod tests {
 
         Ok(())
     }
+
+    #[tokio::test]
+    async fn test_find_leaf_by_commitment_hash() -> Result<()> {
+        let mut builder = L1MerkleTreeBuilder::new();
+
+        let leaves: Vec<[u8; 32]> = (0u32..10_000)
+            .map(|i| {
+                let mut leaf = [0u8; 32];
+                leaf[28..].copy_from_slice(&i.to_be_bytes());
+                leaf
+            })
+            .collect();
+        builder.build_merkle(leaves.clone()).await?;
+
+        let target = leaves[7_777];
+        let start = std::time::Instant::now();
+        let index = builder.find_leaf_by_commitment_hash(target);
+        let elapsed = start.elapsed();
+
+        assert!(index.is_some(), "Should find index for existing leaf");
+        assert!(
+            elapsed < std::time::Duration::from_millis(1),
+            "Lookup took {:?}",
+            elapsed
+        );
+
+        let proof = builder.get_proof(target).await?.unwrap();
+        assert!(builder.verify_proof(proof, target).await?);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_root_notifier_receives_new_root() -> Result<()> {
+        let (sender, mut receiver) = tokio::sync::watch::channel(None);
+        let mut builder = L1MerkleTreeBuilder::new().with_root_notifier(Arc::new(sender));
+
+        builder.build_merkle(vec![[3u8; 32], [4u8; 32]]).await?;
+
+        tokio::time::timeout(std::time::Duration::from_millis(100), receiver.changed())
+            .await
+            .expect("root update should arrive within 100ms")
+            .expect("sender should still be alive");
+        assert_eq!(*receiver.borrow(), Some(builder.get_root().await?));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_historic_roots_by_elements_count() -> Result<()> {
+        let mut builder = L1MerkleTreeBuilder::new();
+
+        let mut snapshots = Vec::new();
+        for i in 0u8..10 {
+            builder.build_merkle(vec![[i + 1; 32]]).await?;
+            snapshots.push(builder.get_root_with_elements_count().await?);
+        }
+
+        // Every snapshot's root is still retrievable after later insertions
+        for (elements_count, root) in &snapshots {
+            assert_eq!(
+                builder.get_root_at_elements_count(*elements_count),
+                Some(*root)
+            );
+        }
+        assert_eq!(snapshots.last().unwrap().1, builder.get_root().await?);
+
+        // Counts the tree passed through mid-append never ended a build
+        assert_eq!(builder.get_root_at_elements_count(2), None);
+        assert_eq!(builder.get_root_at_elements_count(0), None);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_elements_count_includes_parent_nodes() -> Result<()> {
+        let mut builder = L1MerkleTreeBuilder::new();
+        assert_eq!(builder.elements_count().await?, 0);
+
+        builder.build_merkle(vec![[1u8; 32]]).await?;
+        assert_eq!(builder.elements_count().await?, 1);
+
+        // Two leaves and their parent, then a third leaf on its own peak
+        builder.build_merkle(vec![[2u8; 32], [3u8; 32]]).await?;
+        assert_eq!(builder.elements_count().await?, 4);
+        assert_eq!(
+            builder.elements_count().await?,
+            builder.get_root_with_elements_count().await?.0
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_empty_build_does_not_duplicate_snapshot() -> Result<()> {
+        let mut builder = L1MerkleTreeBuilder::new();
+
+        builder.build_merkle(vec![[1u8; 32], [2u8; 32]]).await?;
+        builder.build_merkle(vec![]).await?;
+
+        assert_eq!(builder.root_snapshots.len(), 1);
+        let (elements_count, root) = builder.get_root_with_elements_count().await?;
+        assert_eq!(
+            builder.get_root_at_elements_count(elements_count),
+            Some(root)
+        );
+
+        Ok(())
+    }
 }
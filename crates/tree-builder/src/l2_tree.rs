@@ -1,26 +1,56 @@
-use std::{array::TryFromSliceError, sync::Arc};
+use std::{
+    array::TryFromSliceError,
+    collections::{BTreeMap, HashMap},
+    sync::Arc,
+};
 
 use accumulators::{
     hasher::stark_poseidon,
     mmr::{Proof, MMR},
-    store::memory::InMemoryStore,
+    store::{memory::InMemoryStore, SubKey},
 };
+use async_trait::async_trait;
 
-use crate::{error::TreeBuilderError, types::Result};
+use crate::{
+    cache::{PeaksCache, RootComputer},
+    error::TreeBuilderError,
+    types::{Result, RootSender},
+};
 
 /// A builder for constructing Merkle trees and generating proofs
 pub struct L2MerkleTreeBuilder {
     mmr: MMR,
+    /// Leaves in append order, so the tree can be split and rebuilt
+    leaves: Vec<[u8; 32]>,
+    /// Notified with the new root after every build
+    root_notifier: Option<RootSender>,
+    /// Last root, so `get_root` only bags the peaks again after new leaves
+    peaks_cache: PeaksCache,
+    /// Most leaves the tree may hold; `None` leaves it unbounded
+    max_elements: Option<u64>,
+    /// Commitment hash of each Starknet public key added through `build_merkle_for_keys`
+    key_index: HashMap<String, [u8; 32]>,
+}
+
+#[async_trait]
+impl RootComputer for MMR {
+    async fn compute_root(&self) -> Result<([u8; 32], usize)> {
+        let bag = self.bag_the_peaks(None).await?;
+        let elements_count = self.elements_count.get().await?;
+        let root = self.calculate_root_hash(&bag, elements_count)?;
+        Ok((L2MerkleTreeBuilder::decode_hex(&root)?, elements_count))
+    }
 }
 
 impl L2MerkleTreeBuilder {
     fn decode_hex(hex_str: &str) -> Result<[u8; 32]> {
-        let hex_to_decode = if hex_str.starts_with("0x") {
-            &hex_str[2..]
+        let hex_to_decode = hex_str.strip_prefix("0x").unwrap_or(hex_str);
+        // The Poseidon hasher pads its output to 63 digits only, leaving an odd length
+        let mut bytes = if hex_to_decode.len() % 2 == 1 {
+            hex::decode(format!("0{}", hex_to_decode))?
         } else {
-            hex_str
+            hex::decode(hex_to_decode)?
         };
-        let mut bytes = hex::decode(hex_to_decode)?;
 
         // Pad with zeros if needed
         while bytes.len() < 32 {
@@ -45,23 +75,132 @@ impl L2MerkleTreeBuilder {
 
         Self {
             mmr: MMR::new(store_rc, hasher, None),
+            leaves: Vec::new(),
+            root_notifier: None,
+            peaks_cache: PeaksCache::new(),
+            max_elements: None,
+            key_index: HashMap::new(),
         }
     }
 
-    /// Builds a Merkle tree from a list of commitment hashes
+    /// Publishes the new root to `notifier` after every `build_merkle`
+    pub fn with_root_notifier(mut self, notifier: RootSender) -> Self {
+        self.root_notifier = Some(notifier);
+        self
+    }
+
+    /// Caps the tree at `max_elements` leaves, after which `build_merkle` fails
+    pub fn with_max_elements(mut self, max_elements: u64) -> Self {
+        self.max_elements = Some(max_elements);
+        self
+    }
+
+    /// Number of leaves appended so far
+    pub fn current_elements_count(&self) -> u64 {
+        self.leaves.len() as u64
+    }
+
+    /// Builds a Merkle tree from a list of commitment hashes.
+    ///
+    /// Fails with `MaxElementsExceeded` without appending anything when the leaves would take
+    /// the tree past `max_elements`.
     pub async fn build_merkle(&mut self, leaves: Vec<[u8; 32]>) -> Result<()> {
+        if let Some(limit) = self.max_elements {
+            if self.current_elements_count() + leaves.len() as u64 > limit {
+                return Err(TreeBuilderError::MaxElementsExceeded { limit });
+            }
+        }
+
+        // Invalidated first, so the root cached before these leaves is never served while they
+        // are being appended, nor after an append fails partway
+        self.peaks_cache.invalidate();
         for leaf in leaves {
             self.mmr.append(format!("0x{}", hex::encode(leaf))).await?;
+            self.leaves.push(leaf);
+        }
+        self.notify_root().await
+    }
+
+    /// Builds the tree from `(stark_pub_key, commitment_hash)` pairs, indexing each commitment
+    /// under its key for `membership_proof_for_key`. A key added again points at its latest
+    /// commitment.
+    pub async fn build_merkle_for_keys(&mut self, entries: Vec<(String, [u8; 32])>) -> Result<()> {
+        let leaves = entries.iter().map(|(_, leaf)| *leaf).collect();
+        self.build_merkle(leaves).await?;
+
+        for (stark_pub_key, leaf) in entries {
+            self.key_index.insert(Self::index_key(&stark_pub_key), leaf);
+        }
+        Ok(())
+    }
+
+    /// Proof of the commitment indexed under `stark_pub_key`, along with the commitment itself.
+    ///
+    /// `None` when no commitment was added for the key. The index is not part of checkpoints,
+    /// so a restored tree has to be rebuilt through `build_merkle_for_keys` to be searchable.
+    pub async fn membership_proof_for_key(
+        &self,
+        stark_pub_key: &str,
+    ) -> Result<Option<(Proof, [u8; 32])>> {
+        let Some(leaf) = self.key_index.get(&Self::index_key(stark_pub_key)).copied() else {
+            return Ok(None);
+        };
+
+        Ok(self.get_proof(leaf).await?.map(|proof| (proof, leaf)))
+    }
+
+    /// Keys differ only in hex letter case between sources, so they are indexed in lowercase
+    fn index_key(stark_pub_key: &str) -> String {
+        stark_pub_key.to_ascii_lowercase()
+    }
+
+    /// Appends every leaf of `right` after those of `left`, e.g. to combine trees built by
+    /// separate sequencer instances from consecutive shards of deposits
+    pub async fn merge_trees(mut left: Self, right: Self) -> Result<Self> {
+        left.build_merkle(right.leaves).await?;
+        left.key_index.extend(right.key_index);
+        Ok(left)
+    }
+
+    /// Rebuilds the leaves before `index` and those from `index` on as two separate trees
+    pub async fn split_at(&self, index: usize) -> Result<(Self, Self)> {
+        if index > self.leaves.len() {
+            return Err(TreeBuilderError::IndexOutOfBounds {
+                index,
+                len: self.leaves.len(),
+            });
+        }
+        let (head, tail) = self.leaves.split_at(index);
+
+        let mut left = Self::new();
+        left.build_merkle(head.to_vec()).await?;
+        let mut right = Self::new();
+        right.build_merkle(tail.to_vec()).await?;
+
+        for (stark_pub_key, leaf) in &self.key_index {
+            let half = if head.contains(leaf) {
+                &mut left
+            } else {
+                &mut right
+            };
+            half.key_index.insert(stark_pub_key.clone(), *leaf);
+        }
+
+        Ok((left, right))
+    }
+
+    async fn notify_root(&self) -> Result<()> {
+        if let Some(notifier) = &self.root_notifier {
+            let root = self.get_root().await?;
+            notifier.send_replace(Some(root));
         }
         Ok(())
     }
 
     /// Gets the current Merkle root
     pub async fn get_root(&self) -> Result<[u8; 32]> {
-        let bag = self.mmr.bag_the_peaks(None).await?;
-        let elements_count = self.mmr.elements_count.get().await?;
-        let root = self.mmr.calculate_root_hash(&bag, elements_count)?;
-        Self::decode_hex(&root)
+        let (root, _) = self.peaks_cache.get_or_compute(&self.mmr).await?;
+        Ok(root)
     }
 
     /// Generates a Merkle proof for a given leaf
@@ -98,6 +237,72 @@ impl L2MerkleTreeBuilder {
         let leaf_str = format!("0x{}", hex::encode(leaf));
         Ok(self.mmr.verify_proof(proof, leaf_str, None).await?)
     }
+
+    /// Checkpoints every MMR node as a hex-encoded JSON object of element index to hash
+    pub async fn serialize_to_hex(&self) -> Result<String> {
+        let elements_count = self.mmr.elements_count.get().await?;
+        let mut nodes = BTreeMap::new();
+        for i in 1..=elements_count {
+            let hash = self
+                .mmr
+                .hashes
+                .get(SubKey::Usize(i))
+                .await?
+                .ok_or_else(|| {
+                    TreeBuilderError::InvalidCheckpoint(format!("node {} is missing", i))
+                })?;
+            nodes.insert(i, hash);
+        }
+
+        let json = serde_json::to_vec(&nodes)
+            .map_err(|e| TreeBuilderError::InvalidCheckpoint(e.to_string()))?;
+        Ok(hex::encode(json))
+    }
+
+    /// Restores a tree from a `serialize_to_hex` checkpoint.
+    ///
+    /// The leaves are appended again and every node the rebuild produces must match the
+    /// checkpoint, so a truncated or tampered checkpoint is rejected rather than restored.
+    pub async fn deserialize_from_hex(hex_str: &str) -> Result<Self> {
+        let json = hex::decode(hex_str.strip_prefix("0x").unwrap_or(hex_str))?;
+        let nodes: BTreeMap<usize, String> = serde_json::from_slice(&json)
+            .map_err(|e| TreeBuilderError::InvalidCheckpoint(e.to_string()))?;
+
+        let elements_count = nodes.len();
+        if nodes.keys().copied().ne(1..=elements_count) {
+            return Err(TreeBuilderError::InvalidCheckpoint(
+                "node indices are not contiguous from 1".to_string(),
+            ));
+        }
+
+        // The n-th leaf (0-based) sits at element index 2n - popcount(n) + 1
+        let leaves = (0..)
+            .map(|n: usize| 2 * n - n.count_ones() as usize + 1)
+            .take_while(|index| *index <= elements_count)
+            .map(|index| Self::decode_hex(&nodes[&index]))
+            .collect::<Result<Vec<_>>>()?;
+
+        let mut builder = Self::new();
+        builder.build_merkle(leaves).await?;
+
+        if builder.mmr.elements_count.get().await? != elements_count {
+            return Err(TreeBuilderError::InvalidCheckpoint(format!(
+                "{} nodes is not a valid tree size",
+                elements_count
+            )));
+        }
+        for (index, hash) in &nodes {
+            let rebuilt = builder.mmr.hashes.get(SubKey::Usize(*index)).await?;
+            if rebuilt.as_ref() != Some(hash) {
+                return Err(TreeBuilderError::InvalidCheckpoint(format!(
+                    "node {} does not match the rebuilt tree",
+                    index
+                )));
+            }
+        }
+
+        Ok(builder)
+    }
 }
 
 impl Default for L2MerkleTreeBuilder {
@@ -110,6 +315,7 @@ impl Default for L2MerkleTreeBuilder {
 mod tests {
     use super::*;
     use crate::utils::felt252_to_hex;
+    use std::sync::atomic::{AtomicUsize, Ordering};
 
     #[tokio::test]
     async fn test_basic_tree_operations() -> Result<()> {
@@ -198,4 +404,285 @@ mod tests {
 
         Ok(())
     }
+
+    #[tokio::test]
+    async fn test_merge_of_split_tree_matches_original_root() -> Result<()> {
+        let leaves: Vec<[u8; 32]> = (1u8..=11).map(|i| [i; 32]).collect();
+        let mut original = L2MerkleTreeBuilder::new();
+        original.build_merkle(leaves.clone()).await?;
+        let original_root = original.get_root().await?;
+
+        for index in [0, 1, 4, 7, leaves.len()] {
+            let (left, right) = original.split_at(index).await?;
+            let merged = L2MerkleTreeBuilder::merge_trees(left, right).await?;
+
+            assert_eq!(
+                merged.get_root().await?,
+                original_root,
+                "root differs after splitting at {}",
+                index
+            );
+            let proof = merged.get_proof(leaves[5]).await?.unwrap();
+            assert!(merged.verify_proof(proof, leaves[5]).await?);
+        }
+
+        assert!(matches!(
+            original.split_at(leaves.len() + 1).await,
+            Err(TreeBuilderError::IndexOutOfBounds { index: 12, len: 11 })
+        ));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_checkpoint_round_trip_keeps_root_and_proofs() -> Result<()> {
+        let leaves: Vec<[u8; 32]> = (1u8..=7).map(|i| [i; 32]).collect();
+        let mut original = L2MerkleTreeBuilder::new();
+        original.build_merkle(leaves.clone()).await?;
+
+        let checkpoint = original.serialize_to_hex().await?;
+        let restored = L2MerkleTreeBuilder::deserialize_from_hex(&checkpoint).await?;
+
+        assert_eq!(restored.get_root().await?, original.get_root().await?);
+        assert_eq!(restored.serialize_to_hex().await?, checkpoint);
+        for leaf in &leaves {
+            let proof = restored.get_proof(*leaf).await?.unwrap();
+            assert!(restored.verify_proof(proof, *leaf).await?);
+            let proof = restored.get_proof(*leaf).await?.unwrap();
+            assert!(original.verify_proof(proof, *leaf).await?);
+        }
+
+        let empty = L2MerkleTreeBuilder::new().serialize_to_hex().await?;
+        let restored_empty = L2MerkleTreeBuilder::deserialize_from_hex(&empty).await?;
+        assert_eq!(
+            restored_empty.get_root().await?,
+            L2MerkleTreeBuilder::new().get_root().await?
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_tampered_checkpoint_is_rejected() -> Result<()> {
+        let mut builder = L2MerkleTreeBuilder::new();
+        builder.build_merkle(vec![[1u8; 32], [2u8; 32]]).await?;
+        let checkpoint = builder.serialize_to_hex().await?;
+
+        let json = String::from_utf8(hex::decode(&checkpoint)?).unwrap();
+        let mut nodes: BTreeMap<usize, String> = serde_json::from_str(&json).unwrap();
+        nodes.insert(3, format!("0x{}", hex::encode([9u8; 32])));
+        let tampered = hex::encode(serde_json::to_vec(&nodes).unwrap());
+        assert!(matches!(
+            L2MerkleTreeBuilder::deserialize_from_hex(&tampered).await,
+            Err(TreeBuilderError::InvalidCheckpoint(_))
+        ));
+
+        nodes.remove(&3);
+        let truncated = hex::encode(serde_json::to_vec(&nodes).unwrap());
+        assert!(matches!(
+            L2MerkleTreeBuilder::deserialize_from_hex(&truncated).await,
+            Err(TreeBuilderError::InvalidCheckpoint(_))
+        ));
+
+        assert!(L2MerkleTreeBuilder::deserialize_from_hex("not hex")
+            .await
+            .is_err());
+
+        Ok(())
+    }
+
+    /// Counts how often the wrapped MMR's peaks are bagged
+    struct SpyMmr<'a> {
+        mmr: &'a MMR,
+        bag_calls: AtomicUsize,
+    }
+
+    #[async_trait]
+    impl RootComputer for SpyMmr<'_> {
+        async fn compute_root(&self) -> Result<([u8; 32], usize)> {
+            self.bag_calls.fetch_add(1, Ordering::SeqCst);
+            self.mmr.compute_root().await
+        }
+    }
+
+    #[tokio::test]
+    async fn test_get_root_reuses_cached_peaks_until_leaves_change() -> Result<()> {
+        let mut builder = L2MerkleTreeBuilder::new();
+        builder.build_merkle(vec![[5u8; 32], [6u8; 32]]).await?;
+        let spy = SpyMmr {
+            mmr: &builder.mmr,
+            bag_calls: AtomicUsize::new(0),
+        };
+
+        let first = builder.peaks_cache.get_or_compute(&spy).await?;
+        let second = builder.peaks_cache.get_or_compute(&spy).await?;
+
+        assert_eq!(first, second);
+        assert_eq!(first.1, builder.mmr.elements_count.get().await?);
+        assert_eq!(spy.bag_calls.load(Ordering::SeqCst), 1);
+
+        builder.peaks_cache.invalidate();
+        builder.peaks_cache.get_or_compute(&spy).await?;
+        assert_eq!(spy.bag_calls.load(Ordering::SeqCst), 2);
+
+        Ok(())
+    }
+
+    /// Invalidates `cache` while computing, as leaves appended mid-computation would
+    struct InvalidatingMmr<'a> {
+        mmr: &'a MMR,
+        cache: &'a PeaksCache,
+    }
+
+    #[async_trait]
+    impl RootComputer for InvalidatingMmr<'_> {
+        async fn compute_root(&self) -> Result<([u8; 32], usize)> {
+            let root = self.mmr.compute_root().await;
+            self.cache.invalidate();
+            root
+        }
+    }
+
+    #[tokio::test]
+    async fn test_root_computed_across_an_invalidation_is_not_cached() -> Result<()> {
+        let mut builder = L2MerkleTreeBuilder::new();
+        builder.build_merkle(vec![[9u8; 32]]).await?;
+        let invalidating = InvalidatingMmr {
+            mmr: &builder.mmr,
+            cache: &builder.peaks_cache,
+        };
+        builder.peaks_cache.get_or_compute(&invalidating).await?;
+
+        let spy = SpyMmr {
+            mmr: &builder.mmr,
+            bag_calls: AtomicUsize::new(0),
+        };
+        builder.peaks_cache.get_or_compute(&spy).await?;
+        assert_eq!(spy.bag_calls.load(Ordering::SeqCst), 1);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_cached_root_tracks_new_leaves() -> Result<()> {
+        let mut builder = L2MerkleTreeBuilder::new();
+        builder.build_merkle(vec![[7u8; 32]]).await?;
+        let before = builder.get_root().await?;
+        assert_eq!(builder.get_root().await?, before);
+
+        builder.build_merkle(vec![[8u8; 32]]).await?;
+        let mut rebuilt = L2MerkleTreeBuilder::new();
+        rebuilt.build_merkle(vec![[7u8; 32], [8u8; 32]]).await?;
+
+        assert_ne!(builder.get_root().await?, before);
+        assert_eq!(builder.get_root().await?, rebuilt.get_root().await?);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_root_notifier_receives_new_root() -> Result<()> {
+        let (sender, mut receiver) = tokio::sync::watch::channel(None);
+        let mut builder = L2MerkleTreeBuilder::new().with_root_notifier(Arc::new(sender));
+
+        builder.build_merkle(vec![[3u8; 32], [4u8; 32]]).await?;
+
+        tokio::time::timeout(std::time::Duration::from_millis(100), receiver.changed())
+            .await
+            .expect("root update should arrive within 100ms")
+            .expect("sender should still be alive");
+        assert_eq!(*receiver.borrow(), Some(builder.get_root().await?));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_membership_proof_for_key_in_multi_user_tree() -> Result<()> {
+        let entries: Vec<(String, [u8; 32])> = (1u8..=5)
+            .map(|i| (format!("0x{:x}", 0xabc0 + i as u32), [i; 32]))
+            .collect();
+        let mut builder = L2MerkleTreeBuilder::new();
+        builder.build_merkle_for_keys(entries[..3].to_vec()).await?;
+        builder.build_merkle_for_keys(entries[3..].to_vec()).await?;
+
+        for (stark_pub_key, leaf) in &entries {
+            let (proof, found) = builder
+                .membership_proof_for_key(stark_pub_key)
+                .await?
+                .expect("key should be indexed");
+            assert_eq!(found, *leaf);
+            assert!(builder.verify_proof(proof, *leaf).await?);
+        }
+
+        let (_, found) = builder
+            .membership_proof_for_key("0xABC2")
+            .await?
+            .expect("lookup should ignore hex case");
+        assert_eq!(found, [2u8; 32]);
+        assert!(builder.membership_proof_for_key("0xdead").await?.is_none());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_key_index_survives_split_and_merge() -> Result<()> {
+        let entries: Vec<(String, [u8; 32])> =
+            (1u8..=4).map(|i| (format!("0x{}", i), [i; 32])).collect();
+        let mut builder = L2MerkleTreeBuilder::new();
+        builder.build_merkle_for_keys(entries.clone()).await?;
+
+        let (left, right) = builder.split_at(2).await?;
+        assert!(left.membership_proof_for_key("0x1").await?.is_some());
+        assert!(left.membership_proof_for_key("0x3").await?.is_none());
+        assert!(right.membership_proof_for_key("0x3").await?.is_some());
+
+        let merged = L2MerkleTreeBuilder::merge_trees(left, right).await?;
+        for (stark_pub_key, leaf) in &entries {
+            let (proof, found) = merged
+                .membership_proof_for_key(stark_pub_key)
+                .await?
+                .unwrap();
+            assert_eq!(found, *leaf);
+            assert!(merged.verify_proof(proof, *leaf).await?);
+        }
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_max_elements_guard_fires_at_limit() -> Result<()> {
+        let mut builder = L2MerkleTreeBuilder::new().with_max_elements(3);
+
+        builder.build_merkle(vec![[1u8; 32], [2u8; 32]]).await?;
+        builder.build_merkle(vec![[3u8; 32]]).await?;
+        assert_eq!(builder.current_elements_count(), 3);
+        let root = builder.get_root().await?;
+
+        let result = builder.build_merkle(vec![[4u8; 32]]).await;
+        assert!(matches!(
+            result,
+            Err(TreeBuilderError::MaxElementsExceeded { limit: 3 })
+        ));
+        assert_eq!(builder.current_elements_count(), 3);
+        assert_eq!(builder.get_root().await?, root);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_max_elements_guard_rejects_whole_batch() -> Result<()> {
+        let mut builder = L2MerkleTreeBuilder::new().with_max_elements(2);
+        builder.build_merkle(vec![[1u8; 32]]).await?;
+
+        let result = builder.build_merkle(vec![[2u8; 32], [3u8; 32]]).await;
+
+        assert!(matches!(
+            result,
+            Err(TreeBuilderError::MaxElementsExceeded { limit: 2 })
+        ));
+        assert_eq!(builder.current_elements_count(), 1);
+        assert!(builder.get_proof([2u8; 32]).await?.is_none());
+
+        Ok(())
+    }
 }
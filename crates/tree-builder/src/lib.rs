@@ -1,5 +1,7 @@
-mod error;
+pub mod cache;
+pub mod error;
 pub mod l1_tree;
 pub mod l2_tree;
+pub mod pg_store;
 pub mod types;
 pub mod utils;
@@ -0,0 +1,129 @@
+use std::collections::HashMap;
+
+use accumulators::store::{Store, StoreError};
+use async_trait::async_trait;
+use sqlx::{PgPool, Row};
+use tracing::error;
+
+/// Accumulators store keeping every entry in the `{table_prefix}_mmr_nodes` table, so an MMR
+/// built on it survives restarts
+#[derive(Debug, Clone)]
+pub struct PgStore {
+    pool: PgPool,
+    table: String,
+}
+
+impl PgStore {
+    /// Stores entries in `{table_prefix}_mmr_nodes`, which must already exist.
+    ///
+    /// # Panics
+    ///
+    /// When `table_prefix` is not made of ASCII letters, digits and underscores, since it is
+    /// spliced into the queries as an identifier.
+    pub fn new(pool: PgPool, table_prefix: &str) -> Self {
+        assert!(
+            !table_prefix.is_empty()
+                && table_prefix
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || c == '_'),
+            "Invalid MMR table prefix {:?}",
+            table_prefix
+        );
+
+        Self {
+            pool,
+            table: format!("{}_mmr_nodes", table_prefix),
+        }
+    }
+
+    /// Logs `error`, which `StoreError` has no room for, and returns `store_error` in its place
+    fn failed(&self, operation: &str, error: sqlx::Error, store_error: StoreError) -> StoreError {
+        error!(
+            "MMR store {} on {} failed: {}",
+            operation, self.table, error
+        );
+        store_error
+    }
+}
+
+#[async_trait]
+impl Store for PgStore {
+    fn id(&self) -> String {
+        self.table.clone()
+    }
+
+    async fn get(&self, key: &str) -> Result<Option<String>, StoreError> {
+        sqlx::query_scalar(&format!("SELECT value FROM {} WHERE key = $1", self.table))
+            .bind(key)
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(|e| self.failed("get", e, StoreError::GetError))
+    }
+
+    async fn get_many(&self, keys: Vec<&str>) -> Result<HashMap<String, String>, StoreError> {
+        let rows = sqlx::query(&format!(
+            "SELECT key, value FROM {} WHERE key = ANY($1)",
+            self.table
+        ))
+        .bind(keys)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| self.failed("get_many", e, StoreError::GetManyError))?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| (row.get("key"), row.get("value")))
+            .collect())
+    }
+
+    async fn set(&self, key: &str, value: &str) -> Result<(), StoreError> {
+        sqlx::query(&format!(
+            "INSERT INTO {} (key, value) VALUES ($1, $2) \
+             ON CONFLICT (key) DO UPDATE SET value = EXCLUDED.value",
+            self.table
+        ))
+        .bind(key)
+        .bind(value)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| self.failed("set", e, StoreError::SetError))?;
+
+        Ok(())
+    }
+
+    async fn set_many(&self, entries: HashMap<String, String>) -> Result<(), StoreError> {
+        let (keys, values): (Vec<String>, Vec<String>) = entries.into_iter().unzip();
+        sqlx::query(&format!(
+            "INSERT INTO {} (key, value) SELECT * FROM UNNEST($1::TEXT[], $2::TEXT[]) \
+             ON CONFLICT (key) DO UPDATE SET value = EXCLUDED.value",
+            self.table
+        ))
+        .bind(keys)
+        .bind(values)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| self.failed("set_many", e, StoreError::SetManyError))?;
+
+        Ok(())
+    }
+
+    async fn delete(&self, key: &str) -> Result<(), StoreError> {
+        sqlx::query(&format!("DELETE FROM {} WHERE key = $1", self.table))
+            .bind(key)
+            .execute(&self.pool)
+            .await
+            .map_err(|e| self.failed("delete", e, StoreError::DeleteError))?;
+
+        Ok(())
+    }
+
+    async fn delete_many(&self, keys: Vec<&str>) -> Result<(), StoreError> {
+        sqlx::query(&format!("DELETE FROM {} WHERE key = ANY($1)", self.table))
+            .bind(keys)
+            .execute(&self.pool)
+            .await
+            .map_err(|e| self.failed("delete_many", e, StoreError::DeleteManyError))?;
+
+        Ok(())
+    }
+}
@@ -1,3 +1,8 @@
+pub use accumulators::mmr::Proof;
+
 use crate::error::TreeBuilderError;
 
 pub type Result<T> = std::result::Result<T, TreeBuilderError>;
+
+/// Channel the builders publish each newly built root to
+pub type RootSender = std::sync::Arc<tokio::sync::watch::Sender<Option<[u8; 32]>>>;
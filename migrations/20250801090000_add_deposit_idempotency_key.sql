@@ -0,0 +1,7 @@
+-- Allow clients to safely retry deposit creation
+ALTER TABLE deposits ADD COLUMN IF NOT EXISTS idempotency_key TEXT;
+
+-- NULL keys never conflict, so deposits without a key are unaffected
+CREATE UNIQUE INDEX IF NOT EXISTS idx_deposits_idempotency_key ON deposits (idempotency_key);
+
+COMMENT ON COLUMN deposits.idempotency_key IS 'Client supplied key enforcing at-most-once deposit creation';
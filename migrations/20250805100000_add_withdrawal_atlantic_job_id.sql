@@ -0,0 +1,6 @@
+-- Track the Herodotus Atlantic query generating each withdrawal proof
+ALTER TABLE withdrawals ADD COLUMN IF NOT EXISTS atlantic_job_id TEXT;
+
+CREATE INDEX IF NOT EXISTS idx_withdrawals_status ON withdrawals (status);
+
+COMMENT ON COLUMN withdrawals.atlantic_job_id IS 'Atlantic query id returned when the withdrawal proof job was submitted';
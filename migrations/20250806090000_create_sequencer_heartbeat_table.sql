@@ -0,0 +1,6 @@
+-- One row per sequencer instance, refreshed periodically while the instance is processing
+CREATE TABLE IF NOT EXISTS sequencer_heartbeat (
+    instance_id TEXT PRIMARY KEY,
+    started_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    last_seen_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
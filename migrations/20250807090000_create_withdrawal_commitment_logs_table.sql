@@ -0,0 +1,14 @@
+-- WithdrawalHashAppended events observed on L2
+CREATE TABLE IF NOT EXISTS withdrawal_commitment_logs (
+    id SERIAL PRIMARY KEY,
+    index TEXT NOT NULL,
+    commitment_hash TEXT NOT NULL,
+    root_hash TEXT NOT NULL,
+    elements_count TEXT NOT NULL,
+    block_number BIGINT NOT NULL,
+    transaction_hash TEXT NOT NULL UNIQUE,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_withdrawal_commitment_logs_block_number
+    ON withdrawal_commitment_logs (block_number);
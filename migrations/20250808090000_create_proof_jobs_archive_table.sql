@@ -0,0 +1,19 @@
+-- Completed and failed proof jobs are moved here once they pass the retention window
+CREATE TABLE IF NOT EXISTS proof_jobs_archive (
+    id BIGINT PRIMARY KEY,
+    job_id BIGINT NOT NULL,
+    calldata_dir TEXT NOT NULL,
+    layout TEXT NOT NULL,
+    hasher TEXT NOT NULL,
+    stone_version TEXT NOT NULL,
+    memory_verification TEXT NOT NULL,
+    status TEXT NOT NULL,
+    current_stage TEXT,
+    retry_count INT NOT NULL DEFAULT 0,
+    error_message TEXT,
+    tx_hashes JSONB DEFAULT '{}',
+    created_at TIMESTAMPTZ NOT NULL,
+    updated_at TIMESTAMPTZ NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS proof_jobs_archive_job_id_idx ON proof_jobs_archive (job_id);
@@ -0,0 +1,2 @@
+-- MMR element index of the deposit's commitment once it has been appended to the deposit tree
+ALTER TABLE deposits ADD COLUMN IF NOT EXISTS leaf_index BIGINT;
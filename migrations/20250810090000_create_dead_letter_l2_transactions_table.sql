@@ -0,0 +1,21 @@
+-- L2 transactions that were marked failed, kept so operators can inspect and requeue them
+CREATE TABLE IF NOT EXISTS dead_letter_l2_transactions (
+    id BIGSERIAL PRIMARY KEY,
+    l2_transaction_id BIGINT NOT NULL,
+    stark_pub_key VARCHAR(42) NOT NULL,
+    amount BIGINT NOT NULL,
+    token_address VARCHAR(42) NOT NULL,
+    status VARCHAR NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL,
+    updated_at TIMESTAMPTZ NOT NULL,
+    retry_count INT NOT NULL DEFAULT 0,
+    tx_hash VARCHAR(66),
+    error TEXT,
+    proof_data TEXT,
+    failed_reason TEXT,
+    failed_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    -- Set once the entry has been copied back into l2_transactions
+    requeued_at TIMESTAMPTZ
+);
+
+CREATE INDEX IF NOT EXISTS dead_letter_l2_transactions_failed_at_idx ON dead_letter_l2_transactions (failed_at);
@@ -0,0 +1,19 @@
+-- Composite indexes for the queue polling queries, which all look like
+--   WHERE status = 'pending' [AND retry_count < $1] ORDER BY created_at ASC LIMIT n
+
+-- `status` leads because every lookup pins it with an equality match, which also lets this index
+-- serve plain status filters. `retry_count` follows so the `retry_count < $1` range is applied in
+-- the index rather than on heap rows, and `created_at` trails so entries that share a retry count
+-- come out of the index already in FIFO order.
+CREATE INDEX IF NOT EXISTS deposits_status_retry ON deposits (status, retry_count, created_at);
+
+-- Same column order as `deposits_status_retry`, for `fetch_pending_withdrawals`
+CREATE INDEX IF NOT EXISTS withdrawals_status_retry ON withdrawals (status, retry_count, created_at);
+
+-- The L2 queue has no retry filter, so `created_at` directly follows the `status` equality and the
+-- index returns pending rows already in FIFO order, letting LIMIT stop after the first matches.
+CREATE INDEX IF NOT EXISTS l2_transactions_status_created ON l2_transactions (status, created_at);
+
+-- Status-only lookups are covered by the leading column of the composite indexes above
+DROP INDEX IF EXISTS idx_withdrawals_status;
+DROP INDEX IF EXISTS l2_transactions_status_idx;
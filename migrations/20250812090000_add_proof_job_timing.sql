@@ -0,0 +1,15 @@
+-- When the job entered its current stage, i.e. when the next submission started
+ALTER TABLE proof_jobs ADD COLUMN IF NOT EXISTS stage_started_at TIMESTAMPTZ;
+
+-- One row per completed proof job, used to estimate how long running jobs have left
+CREATE TABLE IF NOT EXISTS proof_job_stats (
+    id BIGSERIAL PRIMARY KEY,
+    job_id BIGINT NOT NULL,
+    -- Contract calls made for the job: initial, every step and final
+    num_steps INT NOT NULL,
+    total_duration_seconds DOUBLE PRECISION NOT NULL,
+    average_step_duration_seconds DOUBLE PRECISION NOT NULL,
+    completed_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS proof_job_stats_completed_at_idx ON proof_job_stats (completed_at);
@@ -0,0 +1,12 @@
+-- Higher values are dequeued first; see `PriorityLevel` in the L2 queue for the levels in use
+ALTER TABLE l2_transactions ADD COLUMN priority SMALLINT NOT NULL DEFAULT 1;
+
+-- Kept on dead-letter entries so a requeued transaction returns at its original priority
+ALTER TABLE dead_letter_l2_transactions ADD COLUMN priority SMALLINT NOT NULL DEFAULT 1;
+
+-- The pending lookup now orders by `priority DESC, created_at ASC`, so `priority` sits between the
+-- `status` equality and `created_at` for the index to keep returning rows in dequeue order.
+CREATE INDEX IF NOT EXISTS l2_transactions_status_priority_created
+    ON l2_transactions (status, priority DESC, created_at);
+
+DROP INDEX IF EXISTS l2_transactions_status_created;
@@ -0,0 +1,7 @@
+-- Ethereum block after which a submitted deposit proof is past its challenge window
+ALTER TABLE deposits ADD COLUMN finalization_block BIGINT;
+
+-- The finalization watcher only ever scans deposits still waiting out their window
+CREATE INDEX IF NOT EXISTS deposits_awaiting_finalization
+    ON deposits (finalization_block)
+    WHERE status = 'PROOF_SUBMITTED';
@@ -0,0 +1,7 @@
+-- depositId from the L1 DepositEvent, so a deposit can be matched to its on-chain event
+ALTER TABLE deposits ADD COLUMN l1_deposit_id TEXT;
+
+-- Deposits created through the API have no L1 event yet
+CREATE UNIQUE INDEX IF NOT EXISTS idx_deposits_l1_deposit_id
+    ON deposits (l1_deposit_id)
+    WHERE l1_deposit_id IS NOT NULL;
@@ -0,0 +1,6 @@
+-- Proof job whose proof covers the deposit; only linked deposits are scheduled for finalization
+-- when that job completes
+ALTER TABLE deposits
+    ADD COLUMN proof_job_id BIGINT REFERENCES proof_jobs(id) ON DELETE SET NULL;
+
+CREATE INDEX IF NOT EXISTS idx_deposits_proof_job_id ON deposits (proof_job_id);
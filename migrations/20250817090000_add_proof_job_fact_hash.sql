@@ -0,0 +1,4 @@
+-- Fact the job's final proof registers; checked against the fact registry before the job
+-- is marked completed
+ALTER TABLE proof_jobs
+    ADD COLUMN fact_hash TEXT;
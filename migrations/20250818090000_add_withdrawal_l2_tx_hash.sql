@@ -0,0 +1,4 @@
+-- Starknet transaction that initiated an L2-originated withdrawal
+ALTER TABLE withdrawals ADD COLUMN IF NOT EXISTS l2_tx_hash TEXT;
+
+COMMENT ON COLUMN withdrawals.l2_tx_hash IS 'Hash of the L2 transaction the user initiated the withdrawal with, as a 0x-prefixed felt';
@@ -0,0 +1,8 @@
+-- Hash of the last processed block, checked against the chain to detect reorgs
+ALTER TABLE block_trackers ADD COLUMN IF NOT EXISTS last_processed_block_hash TEXT;
+
+-- L1 block a deposit event was emitted in, so deposits from reorged blocks can be invalidated
+ALTER TABLE deposits ADD COLUMN IF NOT EXISTS block_number BIGINT;
+
+COMMENT ON COLUMN block_trackers.last_processed_block_hash IS 'Hash of last_block when the watcher recorded it, NULL when unknown';
+COMMENT ON COLUMN deposits.block_number IS 'L1 block the DepositEvent was emitted in';
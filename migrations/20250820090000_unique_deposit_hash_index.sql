@@ -0,0 +1,6 @@
+-- Each Merkle tree index is appended once; drop duplicates left by reprocessed events, keeping the latest
+DELETE FROM deposit_hashes a
+USING deposit_hashes b
+WHERE a.index = b.index AND a.id < b.id;
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_deposit_hashes_index ON deposit_hashes (index);
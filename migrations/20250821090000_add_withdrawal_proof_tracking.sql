@@ -0,0 +1,67 @@
+-- Track the Atlantic job behind each withdrawal proof, so users can see when it is ready to claim
+ALTER TABLE withdrawal_proofs ADD COLUMN IF NOT EXISTS atlantic_job_id TEXT;
+ALTER TABLE withdrawal_proofs ADD COLUMN IF NOT EXISTS submitted_at TIMESTAMPTZ;
+
+-- One proof per withdrawal. A withdrawal that ended up with several keeps its newest; the older
+-- ones are moved to withdrawal_proofs_archive, and logged, rather than lost.
+CREATE TABLE IF NOT EXISTS withdrawal_proofs_archive (
+    id INTEGER PRIMARY KEY,
+    withdrawal_id INTEGER,
+    proof_params BYTEA,
+    proof_data BYTEA,
+    status TEXT NOT NULL,
+    atlantic_job_id TEXT,
+    submitted_at TIMESTAMPTZ,
+    created_at TIMESTAMP NOT NULL,
+    updated_at TIMESTAMP NOT NULL,
+    archived_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+DO $$
+DECLARE
+    duplicates TEXT;
+BEGIN
+    SELECT string_agg(format('withdrawal %s (ids %s)', withdrawal_id, ids), '; ' ORDER BY withdrawal_id)
+    INTO duplicates
+    FROM (
+        SELECT withdrawal_id, string_agg(id::TEXT, ', ' ORDER BY id) AS ids
+        FROM withdrawal_proofs
+        WHERE withdrawal_id IS NOT NULL
+        GROUP BY withdrawal_id
+        HAVING count(*) > 1
+    ) d;
+
+    IF duplicates IS NOT NULL THEN
+        RAISE WARNING 'Archiving all but the newest proof of: %', duplicates;
+    END IF;
+END
+$$;
+
+WITH superseded AS (
+    DELETE FROM withdrawal_proofs a
+    USING withdrawal_proofs b
+    WHERE a.withdrawal_id = b.withdrawal_id AND a.id < b.id
+    RETURNING a.*
+)
+INSERT INTO withdrawal_proofs_archive (
+    id, withdrawal_id, proof_params, proof_data, status, atlantic_job_id, submitted_at,
+    created_at, updated_at
+)
+SELECT DISTINCT ON (id)
+    id, withdrawal_id, proof_params, proof_data, status, atlantic_job_id, submitted_at,
+    created_at, updated_at
+FROM superseded;
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_withdrawal_proofs_withdrawal_id ON withdrawal_proofs (withdrawal_id);
+
+COMMENT ON COLUMN withdrawal_proofs.atlantic_job_id IS 'Atlantic query generating the proof';
+COMMENT ON COLUMN withdrawal_proofs.submitted_at IS 'When the Atlantic query was submitted';
+COMMENT ON COLUMN withdrawal_proofs.status IS 'proof_submitted while Atlantic is proving, ready once verified, proof_failed if Atlantic failed';
+
+-- Running averages of sequencer metrics, e.g. how long Atlantic takes to prove a withdrawal
+CREATE TABLE IF NOT EXISTS system_stats (
+    key TEXT PRIMARY KEY,
+    value DOUBLE PRECISION NOT NULL,
+    sample_count BIGINT NOT NULL DEFAULT 1,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
@@ -0,0 +1,4 @@
+-- Decimal places of the withdrawn token; amounts stay in USD cents and are scaled when relayed
+ALTER TABLE withdrawals ADD COLUMN IF NOT EXISTS amount_precision SMALLINT NOT NULL DEFAULT 2;
+
+COMMENT ON COLUMN withdrawals.amount_precision IS 'Decimals of the withdrawn token, used to convert amount to its base units; 2 keeps amount as-is';
@@ -0,0 +1,14 @@
+-- Burn events seen on L2, kept so withdrawals created through the API can be linked to them
+CREATE TABLE IF NOT EXISTS l2_burn_events (
+    id SERIAL PRIMARY KEY,
+    stark_pub_key TEXT NOT NULL,
+    amount BIGINT NOT NULL,
+    commitment_hash TEXT NOT NULL UNIQUE,
+    block_number BIGINT NOT NULL,
+    transaction_hash TEXT NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+-- WithdrawalMatcher looks up unlinked pending withdrawals by their burn's commitment hash
+CREATE INDEX IF NOT EXISTS withdrawals_pending_commitment_hash
+    ON withdrawals (commitment_hash) WHERE status = 'pending' AND l2_tx_id IS NULL;
@@ -0,0 +1,5 @@
+-- Commitment of the L2 burn behind the transaction; the queue checks it against the bridge contract
+ALTER TABLE l2_transactions ADD COLUMN commitment_hash VARCHAR(66);
+
+-- Kept on dead-letter entries so a requeued transaction can still be checked on L2
+ALTER TABLE dead_letter_l2_transactions ADD COLUMN commitment_hash VARCHAR(66);
@@ -0,0 +1,6 @@
+-- Ethereum address each Stark key proved ownership of, through a signature over that address
+CREATE TABLE IF NOT EXISTS user_mappings (
+    starknet_address TEXT PRIMARY KEY,
+    ethereum_address TEXT NOT NULL UNIQUE,
+    verified_at TIMESTAMPTZ
+);
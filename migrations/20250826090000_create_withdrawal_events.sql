@@ -0,0 +1,12 @@
+-- Every status transition of a withdrawal, backing GET /withdrawals/{id}/timeline
+CREATE TABLE IF NOT EXISTS withdrawal_events (
+    id SERIAL PRIMARY KEY,
+    withdrawal_id INTEGER NOT NULL REFERENCES withdrawals(id) ON DELETE CASCADE,
+    old_status TEXT NOT NULL,
+    new_status TEXT NOT NULL,
+    actor TEXT NOT NULL,
+    occurred_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_withdrawal_events_withdrawal_id
+    ON withdrawal_events (withdrawal_id, occurred_at);
@@ -0,0 +1,5 @@
+-- SHA-256 of proof_data, so a second transaction for an already relayed proof is caught
+ALTER TABLE l2_transactions ADD COLUMN IF NOT EXISTS proof_data_hash VARCHAR(64);
+
+CREATE INDEX IF NOT EXISTS l2_transactions_proof_data_hash
+    ON l2_transactions (proof_data_hash) WHERE proof_data_hash IS NOT NULL;
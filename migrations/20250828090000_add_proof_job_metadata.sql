@@ -0,0 +1,4 @@
+-- Free-form per-job context such as covered deposit ids, program version or operator notes
+ALTER TABLE proof_jobs ADD COLUMN IF NOT EXISTS metadata JSONB NOT NULL DEFAULT '{}';
+
+ALTER TABLE proof_jobs_archive ADD COLUMN IF NOT EXISTS metadata JSONB NOT NULL DEFAULT '{}';
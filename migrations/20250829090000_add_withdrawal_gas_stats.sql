@@ -0,0 +1,3 @@
+-- Gas spent by the Ethereum transaction that relayed the withdrawal
+ALTER TABLE withdrawals ADD COLUMN IF NOT EXISTS gas_used BIGINT;
+ALTER TABLE withdrawals ADD COLUMN IF NOT EXISTS gas_price_gwei BIGINT;
@@ -0,0 +1,11 @@
+-- MMR nodes of the Merkle trees that must survive restarts, keyed as the accumulators store
+-- keys them; see `L1MerkleTreeBuilder::new_with_pg_store`
+CREATE TABLE IF NOT EXISTS l1_mmr_nodes (
+    key TEXT PRIMARY KEY,
+    value TEXT NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS l2_mmr_nodes (
+    key TEXT PRIMARY KEY,
+    value TEXT NOT NULL
+);
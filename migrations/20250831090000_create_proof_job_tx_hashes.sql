@@ -0,0 +1,17 @@
+-- Every Starknet transaction a proof job submitted, one row per call, replacing the
+-- proof_jobs.tx_hashes JSON object
+CREATE TABLE IF NOT EXISTS proof_job_tx_hashes (
+    id BIGSERIAL PRIMARY KEY,
+    proof_job_id BIGINT NOT NULL REFERENCES proof_jobs(id) ON DELETE CASCADE,
+    stage TEXT NOT NULL,
+    tx_hash TEXT NOT NULL,
+    submitted_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_proof_job_tx_hashes_proof_job_id ON proof_job_tx_hashes (proof_job_id);
+
+INSERT INTO proof_job_tx_hashes (proof_job_id, stage, tx_hash, submitted_at)
+SELECT proof_jobs.id, hashes.key, hashes.value, proof_jobs.updated_at
+FROM proof_jobs, jsonb_each_text(COALESCE(proof_jobs.tx_hashes, '{}')) AS hashes;
+
+ALTER TABLE proof_jobs DROP COLUMN IF EXISTS tx_hashes;
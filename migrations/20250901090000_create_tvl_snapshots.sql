@@ -0,0 +1,10 @@
+-- TVL read from each chain by the oracle service, one row per fetch
+CREATE TABLE IF NOT EXISTS tvl_snapshots (
+    id BIGSERIAL PRIMARY KEY,
+    chain TEXT NOT NULL CHECK (chain IN ('l1', 'l2')),
+    tvl_wei NUMERIC NOT NULL,
+    block_number BIGINT NOT NULL,
+    captured_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_tvl_snapshots_chain_captured_at ON tvl_snapshots (chain, captured_at DESC);
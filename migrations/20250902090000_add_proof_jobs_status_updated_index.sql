@@ -0,0 +1,12 @@
+-- `recover_stale_jobs` looks for jobs stuck in one status:
+--   WHERE status = 'processing' AND updated_at < NOW() - make_interval(mins => $1)
+-- `status` leads for the equality match and `updated_at` follows so the staleness cutoff is an
+-- index range scan over just the processing jobs, instead of a filter on every row.
+CREATE INDEX IF NOT EXISTS proof_jobs_status_updated ON proof_jobs (status, updated_at);
+
+-- Status-only lookups are covered by the leading column of the composite index above
+DROP INDEX IF EXISTS proof_jobs_status_idx;
+
+-- deposits_status_retry already serves the deposit queue. The L2 queue is served by
+-- l2_transactions_status_priority_created, since every pending and ready-for-relay lookup orders
+-- by priority before created_at.
@@ -0,0 +1,5 @@
+-- L1 TVL is now recorded per vault (`l1-<address>`) and summed (`l1-total`). Plain `l1` rows
+-- predate multi-vault support and are kept.
+ALTER TABLE tvl_snapshots DROP CONSTRAINT IF EXISTS tvl_snapshots_chain_check;
+ALTER TABLE tvl_snapshots ADD CONSTRAINT tvl_snapshots_chain_check
+    CHECK (chain IN ('l1', 'l2', 'l1-total') OR chain LIKE 'l1-0x%');
@@ -0,0 +1,8 @@
+-- Decimals and USD price of the L1 tokens withdrawals are paid out in, checked against the
+-- USD cents `amount` of every withdrawal request. Addresses are stored lowercase.
+CREATE TABLE IF NOT EXISTS token_metadata (
+    address TEXT PRIMARY KEY CHECK (address = LOWER(address)),
+    symbol TEXT NOT NULL,
+    decimals SMALLINT NOT NULL CHECK (decimals BETWEEN 0 AND 255),
+    usd_price_cents BIGINT NOT NULL CHECK (usd_price_cents > 0)
+);
@@ -0,0 +1,2 @@
+-- SHA-256 of a job's calldata files when it was created, checked again before resuming it
+ALTER TABLE proof_jobs ADD COLUMN IF NOT EXISTS calldata_hash TEXT;
@@ -0,0 +1,3 @@
+-- Serves each user's withdrawal history, newest first
+CREATE INDEX IF NOT EXISTS withdrawals_stark_pub_key_created_at_idx
+    ON withdrawals (stark_pub_key, created_at);
@@ -0,0 +1,50 @@
+-- Rewrites stored commitment hashes in the form normalize_commitment_hash produces: no 0x prefix,
+-- lowercase, zero padded to 64 hex digits.
+--
+-- Rows whose hashes only differ in format would collide on the unique hash of deposits and
+-- l2_burn_events once normalized. Picking one of them could drop a deposit or burn, so the
+-- migration fails instead, naming every collision for an operator to resolve by hand.
+DO $$
+DECLARE
+    collisions TEXT;
+BEGIN
+    SELECT string_agg(format('%s %s (ids %s)', tbl, normalized, ids), '; ' ORDER BY tbl, normalized)
+    INTO collisions
+    FROM (
+        SELECT 'deposits' AS tbl, normalized, string_agg(id::TEXT, ', ' ORDER BY id) AS ids
+        FROM (
+            SELECT id, lpad(lower(regexp_replace(commitment_hash, '^0[xX]', '')), 64, '0') AS normalized
+            FROM deposits
+            WHERE commitment_hash ~ '^(0[xX])?[0-9a-fA-F]{1,64}$'
+        ) d
+        GROUP BY normalized
+        HAVING count(*) > 1
+        UNION ALL
+        SELECT 'l2_burn_events' AS tbl, normalized, string_agg(id::TEXT, ', ' ORDER BY id) AS ids
+        FROM (
+            SELECT id, lpad(lower(regexp_replace(commitment_hash, '^0[xX]', '')), 64, '0') AS normalized
+            FROM l2_burn_events
+            WHERE commitment_hash ~ '^(0[xX])?[0-9a-fA-F]{1,64}$'
+        ) e
+        GROUP BY normalized
+        HAVING count(*) > 1
+    ) duplicates;
+
+    IF collisions IS NOT NULL THEN
+        RAISE EXCEPTION 'Commitment hashes collide once normalized: %', collisions
+            USING HINT = 'Merge or remove the duplicate rows, then run the migrations again';
+    END IF;
+END
+$$;
+
+UPDATE deposits
+SET commitment_hash = lpad(lower(regexp_replace(commitment_hash, '^0[xX]', '')), 64, '0')
+WHERE commitment_hash ~ '^(0[xX])?[0-9a-fA-F]{1,64}$';
+
+UPDATE l2_burn_events
+SET commitment_hash = lpad(lower(regexp_replace(commitment_hash, '^0[xX]', '')), 64, '0')
+WHERE commitment_hash ~ '^(0[xX])?[0-9a-fA-F]{1,64}$';
+
+UPDATE withdrawals
+SET commitment_hash = lpad(lower(regexp_replace(commitment_hash, '^0[xX]', '')), 64, '0')
+WHERE commitment_hash ~ '^(0[xX])?[0-9a-fA-F]{1,64}$';
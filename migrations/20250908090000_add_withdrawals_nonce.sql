@@ -0,0 +1,6 @@
+-- Nonce of the withdrawal in its user's sequence, assigned by next_nonce_for_user. Withdrawals
+-- created before nonces were assigned have none.
+ALTER TABLE withdrawals ADD COLUMN IF NOT EXISTS nonce BIGINT;
+
+CREATE UNIQUE INDEX IF NOT EXISTS withdrawals_stark_pub_key_nonce_idx
+    ON withdrawals (stark_pub_key, nonce);
@@ -0,0 +1,8 @@
+-- Stage the proof generation pipeline last reported for each job. The pipeline runs before the
+-- job's proof_jobs row exists, so its progress is kept apart, keyed by the same job_id.
+CREATE TABLE IF NOT EXISTS proof_pipeline_progress (
+    job_id BIGINT PRIMARY KEY,
+    stage TEXT NOT NULL,
+    stage_started_at TIMESTAMPTZ NOT NULL,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
@@ -0,0 +1,5 @@
+-- Withdrawn amount in base units of l1_token, as checked against token_metadata. Withdrawals
+-- created before it was recorded have none.
+ALTER TABLE withdrawals ADD COLUMN IF NOT EXISTS token_amount TEXT;
+
+COMMENT ON COLUMN withdrawals.token_amount IS 'Withdrawn amount in base units of l1_token, as a decimal string';
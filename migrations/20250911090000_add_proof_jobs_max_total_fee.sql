@@ -0,0 +1,3 @@
+-- Fee budget a suspended proof job was resumed with. It replaces starknet.max_total_fee for the
+-- job's next run; jobs that were never resumed have none.
+ALTER TABLE proof_jobs ADD COLUMN IF NOT EXISTS max_total_fee NUMERIC(39, 0);
@@ -0,0 +1,4 @@
+-- elementCount the L1 contract's deposit tree reported after the deposit's DepositEvent. The L1
+-- queue compares its local tree against it once the deposit is appended; deposits recorded
+-- through the API have none.
+ALTER TABLE deposits ADD COLUMN IF NOT EXISTS l1_element_count BIGINT;
@@ -0,0 +1,41 @@
+-- A commitment is relayed at most once, whatever proof data it was checked with. Where several
+-- active transactions already share a commitment, the one furthest along (oldest among equals)
+-- is kept and the others are marked duplicate, and logged, before the index is built.
+DO $$
+DECLARE
+    duplicates TEXT;
+BEGIN
+    SELECT string_agg(format('commitment %s (ids %s)', commitment_hash, ids), '; ' ORDER BY commitment_hash)
+    INTO duplicates
+    FROM (
+        SELECT commitment_hash, string_agg(id::TEXT, ', ' ORDER BY id) AS ids
+        FROM l2_transactions
+        WHERE commitment_hash IS NOT NULL
+        AND status IN ('ready_for_relay', 'processing', 'completed')
+        GROUP BY commitment_hash
+        HAVING count(*) > 1
+    ) d;
+
+    IF duplicates IS NOT NULL THEN
+        RAISE WARNING 'Marking all but one active transaction duplicate for: %', duplicates;
+    END IF;
+END
+$$;
+
+WITH ranked AS (
+    SELECT id, row_number() OVER (
+        PARTITION BY commitment_hash
+        ORDER BY CASE status WHEN 'completed' THEN 0 WHEN 'processing' THEN 1 ELSE 2 END, id
+    ) AS rank
+    FROM l2_transactions
+    WHERE commitment_hash IS NOT NULL
+    AND status IN ('ready_for_relay', 'processing', 'completed')
+)
+UPDATE l2_transactions t
+SET status = 'duplicate', updated_at = NOW()
+FROM ranked
+WHERE t.id = ranked.id AND ranked.rank > 1;
+
+CREATE UNIQUE INDEX IF NOT EXISTS l2_transactions_relayed_commitment_hash
+    ON l2_transactions (commitment_hash)
+    WHERE status IN ('ready_for_relay', 'processing', 'completed');
@@ -0,0 +1,5 @@
+-- Columns added to proof_jobs after the archive was created, so archived jobs keep them
+ALTER TABLE proof_jobs_archive ADD COLUMN IF NOT EXISTS stage_started_at TIMESTAMPTZ;
+ALTER TABLE proof_jobs_archive ADD COLUMN IF NOT EXISTS fact_hash TEXT;
+ALTER TABLE proof_jobs_archive ADD COLUMN IF NOT EXISTS calldata_hash TEXT;
+ALTER TABLE proof_jobs_archive ADD COLUMN IF NOT EXISTS max_total_fee NUMERIC(39, 0);
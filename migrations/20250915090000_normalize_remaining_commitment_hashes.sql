@@ -0,0 +1,64 @@
+-- Rewrites the commitment hashes 20250907090000_normalize_commitment_hashes left behind in the
+-- form normalize_commitment_hash produces: no 0x prefix, lowercase, zero padded to 64 hex digits.
+
+-- deposit_hashes keeps raw bytes; rows written as the hex text of the hash get its bytes instead
+UPDATE deposit_hashes
+SET commitment_hash = decode(
+    lpad(lower(regexp_replace(encode(commitment_hash, 'escape'), '^0[xX]', '')), 64, '0'),
+    'hex'
+)
+WHERE octet_length(commitment_hash) <> 32
+AND encode(commitment_hash, 'escape') ~ '^(0[xX])?[0-9a-fA-F]{1,64}$';
+
+UPDATE withdrawal_commitment_logs
+SET commitment_hash = lpad(lower(regexp_replace(commitment_hash, '^0[xX]', '')), 64, '0')
+WHERE commitment_hash ~ '^(0[xX])?[0-9a-fA-F]{1,64}$';
+
+UPDATE dead_letter_l2_transactions
+SET commitment_hash = lpad(lower(regexp_replace(commitment_hash, '^0[xX]', '')), 64, '0')
+WHERE commitment_hash ~ '^(0[xX])?[0-9a-fA-F]{1,64}$';
+
+-- Active transactions whose hashes only differ in format would collide on
+-- l2_transactions_relayed_commitment_hash once normalized. As when the index was built, the one
+-- furthest along is kept and the others are marked duplicate, and logged.
+DO $$
+DECLARE
+    duplicates TEXT;
+BEGIN
+    SELECT string_agg(format('commitment %s (ids %s)', normalized, ids), '; ' ORDER BY normalized)
+    INTO duplicates
+    FROM (
+        SELECT normalized, string_agg(id::TEXT, ', ' ORDER BY id) AS ids
+        FROM (
+            SELECT id, lpad(lower(regexp_replace(commitment_hash, '^0[xX]', '')), 64, '0') AS normalized
+            FROM l2_transactions
+            WHERE commitment_hash ~ '^(0[xX])?[0-9a-fA-F]{1,64}$'
+            AND status IN ('ready_for_relay', 'processing', 'completed')
+        ) t
+        GROUP BY normalized
+        HAVING count(*) > 1
+    ) d;
+
+    IF duplicates IS NOT NULL THEN
+        RAISE WARNING 'Marking all but one active transaction duplicate for: %', duplicates;
+    END IF;
+END
+$$;
+
+WITH ranked AS (
+    SELECT id, row_number() OVER (
+        PARTITION BY lpad(lower(regexp_replace(commitment_hash, '^0[xX]', '')), 64, '0')
+        ORDER BY CASE status WHEN 'completed' THEN 0 WHEN 'processing' THEN 1 ELSE 2 END, id
+    ) AS rank
+    FROM l2_transactions
+    WHERE commitment_hash ~ '^(0[xX])?[0-9a-fA-F]{1,64}$'
+    AND status IN ('ready_for_relay', 'processing', 'completed')
+)
+UPDATE l2_transactions t
+SET status = 'duplicate', updated_at = NOW()
+FROM ranked
+WHERE t.id = ranked.id AND ranked.rank > 1;
+
+UPDATE l2_transactions
+SET commitment_hash = lpad(lower(regexp_replace(commitment_hash, '^0[xX]', '')), 64, '0')
+WHERE commitment_hash ~ '^(0[xX])?[0-9a-fA-F]{1,64}$';
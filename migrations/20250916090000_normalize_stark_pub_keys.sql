@@ -0,0 +1,15 @@
+-- Rewrites the stark_pub_key of deposits and withdrawals stored before keys were normalized into
+-- the form normalize_felt_hex produces: 0x followed by 64 lowercase hex digits. Lookups by key
+-- only match that form, so older rows written as 0xABC or 0x0abc were left out of them.
+-- Those rows predate per-user withdrawal nonces, so their nonce is NULL and the normalized keys
+-- can't collide on withdrawals_stark_pub_key_nonce_idx.
+
+UPDATE deposits
+SET stark_pub_key = '0x' || lpad(lower(substring(stark_pub_key FROM 3)), 64, '0')
+WHERE stark_pub_key ~ '^0[xX][0-9a-fA-F]{1,64}$'
+AND stark_pub_key <> '0x' || lpad(lower(substring(stark_pub_key FROM 3)), 64, '0');
+
+UPDATE withdrawals
+SET stark_pub_key = '0x' || lpad(lower(substring(stark_pub_key FROM 3)), 64, '0')
+WHERE stark_pub_key ~ '^0[xX][0-9a-fA-F]{1,64}$'
+AND stark_pub_key <> '0x' || lpad(lower(substring(stark_pub_key FROM 3)), 64, '0');
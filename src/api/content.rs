@@ -0,0 +1,187 @@
+//! Content-type negotiation for handlers that speak both JSON and MessagePack
+
+use axum::{
+    body::Bytes,
+    extract::{FromRequest, FromRequestParts, Request},
+    http::{
+        header::{ACCEPT, CONTENT_TYPE},
+        request::Parts,
+        HeaderMap, HeaderValue, StatusCode,
+    },
+    response::{IntoResponse, Response},
+};
+use serde::{de::DeserializeOwned, Serialize};
+use std::convert::Infallible;
+
+pub const JSON_CONTENT_TYPE: &str = "application/json";
+pub const MSGPACK_CONTENT_TYPE: &str = "application/msgpack";
+
+/// Wire format of a request or response body
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ContentFormat {
+    #[default]
+    Json,
+    MessagePack,
+}
+
+impl ContentFormat {
+    /// Recognises a media type, ignoring any parameters such as `charset`
+    pub fn from_media_type(media_type: &str) -> Option<Self> {
+        let essence = media_type.split(';').next()?.trim().to_ascii_lowercase();
+        match essence.as_str() {
+            "application/json" => Some(ContentFormat::Json),
+            "application/msgpack" | "application/x-msgpack" | "application/vnd.msgpack" => {
+                Some(ContentFormat::MessagePack)
+            }
+            _ if essence.starts_with("application/") && essence.ends_with("+json") => {
+                Some(ContentFormat::Json)
+            }
+            _ => None,
+        }
+    }
+
+    /// Format to answer in for an `Accept` header; the first supported type listed wins,
+    /// and JSON is used when nothing listed is supported
+    pub fn from_accept(headers: &HeaderMap) -> Self {
+        headers
+            .get_all(ACCEPT)
+            .iter()
+            .filter_map(|value| value.to_str().ok())
+            .flat_map(|value| value.split(','))
+            .find_map(ContentFormat::from_media_type)
+            .unwrap_or_default()
+    }
+
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            ContentFormat::Json => JSON_CONTENT_TYPE,
+            ContentFormat::MessagePack => MSGPACK_CONTENT_TYPE,
+        }
+    }
+}
+
+/// Extracts the format the client accepts, so handlers can pass it to [`FlexibleResponse`]
+impl<S> FromRequestParts<S> for ContentFormat
+where
+    S: Send + Sync,
+{
+    type Rejection = Infallible;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        Ok(ContentFormat::from_accept(&parts.headers))
+    }
+}
+
+/// Request body decoded as JSON or MessagePack depending on its `Content-Type`
+///
+/// Any other content type is rejected with 415 Unsupported Media Type.
+#[derive(Debug, Clone)]
+pub struct FlexibleBody<T>(pub T);
+
+impl<S, T> FromRequest<S> for FlexibleBody<T>
+where
+    T: DeserializeOwned,
+    S: Send + Sync,
+{
+    type Rejection = (StatusCode, String);
+
+    async fn from_request(req: Request, state: &S) -> Result<Self, Self::Rejection> {
+        let format = req
+            .headers()
+            .get(CONTENT_TYPE)
+            .and_then(|value| value.to_str().ok())
+            .and_then(ContentFormat::from_media_type)
+            .ok_or((
+                StatusCode::UNSUPPORTED_MEDIA_TYPE,
+                format!(
+                    "Expected request with `Content-Type: {}` or `Content-Type: {}`",
+                    JSON_CONTENT_TYPE, MSGPACK_CONTENT_TYPE
+                ),
+            ))?;
+
+        let bytes = Bytes::from_request(req, state)
+            .await
+            .map_err(|rejection| (rejection.status(), rejection.body_text()))?;
+
+        let value = match format {
+            ContentFormat::Json => serde_json::from_slice(&bytes).map_err(|e| {
+                let status = if e.is_data() {
+                    StatusCode::UNPROCESSABLE_ENTITY
+                } else {
+                    StatusCode::BAD_REQUEST
+                };
+                (
+                    status,
+                    format!("Failed to parse the request body as JSON: {}", e),
+                )
+            })?,
+            ContentFormat::MessagePack => rmp_serde::from_slice(&bytes).map_err(|e| {
+                let status = match e {
+                    rmp_serde::decode::Error::InvalidMarkerRead(_)
+                    | rmp_serde::decode::Error::InvalidDataRead(_) => StatusCode::BAD_REQUEST,
+                    _ => StatusCode::UNPROCESSABLE_ENTITY,
+                };
+                (
+                    status,
+                    format!("Failed to parse the request body as MessagePack: {}", e),
+                )
+            })?,
+        };
+
+        Ok(FlexibleBody(value))
+    }
+}
+
+/// Response body serialized in the format the client asked for
+#[derive(Debug, Clone)]
+pub struct FlexibleResponse<T> {
+    pub format: ContentFormat,
+    pub status: StatusCode,
+    pub headers: HeaderMap,
+    pub value: T,
+}
+
+impl<T> FlexibleResponse<T> {
+    pub fn new(format: ContentFormat, value: T) -> Self {
+        Self {
+            format,
+            status: StatusCode::OK,
+            headers: HeaderMap::new(),
+            value,
+        }
+    }
+
+    pub fn with_status(mut self, status: StatusCode) -> Self {
+        self.status = status;
+        self
+    }
+
+    pub fn with_headers(mut self, headers: HeaderMap) -> Self {
+        self.headers.extend(headers);
+        self
+    }
+}
+
+impl<T: Serialize> IntoResponse for FlexibleResponse<T> {
+    fn into_response(self) -> Response {
+        let body = match self.format {
+            ContentFormat::Json => serde_json::to_vec(&self.value).map_err(|e| e.to_string()),
+            // Named fields keep MessagePack bodies shaped like their JSON counterparts
+            ContentFormat::MessagePack => {
+                rmp_serde::to_vec_named(&self.value).map_err(|e| e.to_string())
+            }
+        };
+
+        match body {
+            Ok(body) => {
+                let mut headers = self.headers;
+                headers.insert(
+                    CONTENT_TYPE,
+                    HeaderValue::from_static(self.format.content_type()),
+                );
+                (self.status, headers, body).into_response()
+            }
+            Err(e) => (StatusCode::INTERNAL_SERVER_ERROR, e).into_response(),
+        }
+    }
+}
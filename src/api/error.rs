@@ -0,0 +1,20 @@
+use serde::{Deserialize, Serialize};
+
+/// Error body carrying a machine-readable `code` alongside the message
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ApiError {
+    pub code: String,
+    pub message: String,
+}
+
+/// `ApiError` code for a request value that cannot be parsed
+pub const INVALID_INPUT: &str = "INVALID_INPUT";
+
+impl ApiError {
+    pub fn invalid_input(message: impl Into<String>) -> Self {
+        Self {
+            code: INVALID_INPUT.to_string(),
+            message: message.into(),
+        }
+    }
+}
@@ -1,14 +1,78 @@
-use axum::{http::StatusCode, response::IntoResponse, Extension, Json};
-use serde::{Deserialize, Serialize};
+use axum::{
+    body::{Body, Bytes},
+    extract::{Path, Query},
+    http::{header::CONTENT_TYPE, HeaderMap, HeaderValue, StatusCode},
+    response::{
+        sse::{Event, KeepAlive, Sse},
+        IntoResponse, Response,
+    },
+    Extension, Json,
+};
+use chrono::{DateTime, Utc};
+use futures_util::stream::{self, Stream};
+use serde::{de, Deserialize, Deserializer, Serialize};
 use serde_json::json;
+use serde_json::value::RawValue;
 use sqlx::PgPool;
+use std::collections::BTreeMap;
+use std::convert::Infallible;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::io::AsyncReadExt;
+use tokio::sync::broadcast::error::RecvError;
+use tokio::time::timeout;
+use tracing::warn;
+use url::Url;
 
+use crate::api::content::{ContentFormat, FlexibleBody, FlexibleResponse};
+use crate::api::error::ApiError;
+use crate::config::{AppConfig, DEFAULT_TOLERANCE_BPS};
 use crate::db::database::{
-    fetch_pending_deposits, fetch_pending_withdrawals, insert_deposit, insert_withdrawal, Deposit,
-    Withdrawal,
+    average_proof_step_duration, check_commitment_hash_unique, cleanup_old_proof_jobs,
+    count_l2_transactions_by_status, count_pending_deposits, count_pending_withdrawals,
+    fetch_dead_letter_l2_transactions, fetch_deposit_by_id, fetch_gas_metrics,
+    fetch_heartbeat_status, fetch_latest_tvl_snapshot, fetch_pending_deposits,
+    fetch_proof_job_by_job_id, fetch_proof_job_tx_hashes, fetch_proof_pipeline_progress,
+    fetch_system_stat, fetch_token_metadata, fetch_user_mapping, fetch_withdrawal_by_id,
+    fetch_withdrawal_commitment_logs, fetch_withdrawal_events, fetch_withdrawal_proof,
+    fetch_withdrawals_by_stark_pub_key, import_proof_job, insert_deposit,
+    insert_deposit_idempotent, insert_deposits_bulk, insert_user_mapping,
+    insert_withdrawal_with_next_nonce, list_block_trackers, list_proof_jobs, list_withdrawals,
+    merge_proof_job_metadata, next_nonce_for_user, ping_database,
+    requeue_dead_letter_l2_transaction, requeue_failed_l2_transactions, reset_deposits_after_block,
+    BlockTrackerRow, BulkInsertDepositsResult, DeadLetterL2Transaction, Deposit, GasMetrics,
+    NewDeposit, PaginatedResult, PaginationParams, ProofJobFilter, ProofJobTxHash,
+    ProofPipelineProgress, SortField, TokenMetadata, TvlSnapshot, UserMapping, Withdrawal,
+    WithdrawalEvent, WithdrawalFilter, WithdrawalNonceError, WithdrawalProofRow, WithdrawalStatus,
+    L1_TOTAL_TVL_CHAIN, L2_TVL_CHAIN,
+};
+use crate::events::{
+    CommitmentLog, ConfigReloadError, ConfigWatcher, EventBus, WithdrawalCommitmentLog,
+};
+use crate::merkle::{DepositTree, MerkleProofJson, WithdrawalTree};
+use crate::relayer::ethereum_relayer::CompletedWithdrawalEvent;
+use crate::relayer::proof_submission::{
+    count_proof_steps, is_calldata_stage, ProofJob, ProofSubmissionError, ProofSubmissionRelayer,
+    StepFeeEstimate, PROOF_JOB_STATUSES,
+};
+use crate::relayer::starknet_relayer::{SimulationResult, StarknetRelayer};
+use crate::utils::{
+    calculate_fact_hash, canonical_felt_hex, compute_poseidon_commitment_hash,
+    normalize_commitment_hash, normalize_felt_hex, parse_felt_in_range, parse_stark_pub_key,
+    token_amount_to_usd_cents, tvl_diff_bps, validate_amount_cents,
+    verify_ethereum_address_signature, within_tolerance_bps, BurnData, FeltRangeError, HashMethod,
+    MAX_AMOUNT_PRECISION,
 };
-use crate::utils::{BurnData, HashMethod, compute_poseidon_commitment_hash};
+use crate::workers::finalization::ethereum_block_number;
+use crate::workers::proof_generation::{
+    ATLANTIC_JOB_DURATION_STAT, PROOF_STATUS_READY, STATUS_PROOF_FAILED, STATUS_PROOF_SUBMITTED,
+    STATUS_READY_FOR_RELAY,
+};
+use alloy_primitives::Address;
+use alloy_rpc_client::ClientBuilder;
 use starknet::core::types::Felt;
+use starknet::providers::jsonrpc::{HttpTransport, JsonRpcClient};
+use starknet::providers::Provider;
 
 // UPDATED: Added l1_token field
 #[derive(Debug, Serialize, Deserialize)]
@@ -17,6 +81,19 @@ pub struct CreateWithdrawalRequest {
     pub amount: i64,
     pub commitment_hash: String,
     pub l1_token: String, // ADDED: New required field
+    /// Hash of the L2 transaction the withdrawal was initiated with, when known
+    pub l2_tx_hash: Option<String>,
+    /// Decimals of `l1_token`, taken from its `token_metadata` when omitted and refused when
+    /// they differ; `amount` stays in USD cents and is scaled when relayed
+    #[serde(default)]
+    pub amount_precision: Option<u8>,
+    /// Amount of `l1_token` withdrawn, in base units as a decimal string. Required; its USD
+    /// value at the `token_metadata` price is checked against `amount`.
+    #[serde(default)]
+    pub token_amount: Option<String>,
+    /// Nonce hashed into `commitment_hash`, from `GET /users/{stark_pub_key}/next-nonce`. The
+    /// withdrawal is refused unless it is the user's next nonce.
+    pub nonce: u64,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -24,6 +101,9 @@ pub struct DepositRequest {
     pub stark_pub_key: String,
     pub amount: i64,
     pub commitment_hash: String,
+    /// Optional client key; retries with the same key return the original deposit
+    #[serde(default)]
+    pub idempotency_key: Option<String>,
 }
 
 #[derive(Serialize, Deserialize)]
@@ -31,31 +111,88 @@ pub struct DepositResponse {
     pub deposit_id: i32,
 }
 
+/// Most deposits accepted by one `POST /deposits/bulk` request
+pub const MAX_BULK_DEPOSITS: usize = 100;
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BulkDepositRequest {
+    pub deposits: Vec<NewDeposit>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct NextNonceResponse {
+    /// Nonce the user's next withdrawal will be assigned
+    pub nonce: u64,
+}
+
 #[derive(Serialize, Deserialize)]
 pub struct WithrawalResponse {
     pub withdrawal_id: i32,
+    /// Nonce assigned to the withdrawal, the next in its user's sequence
+    pub nonce: u64,
 }
 
 #[derive(Serialize, Deserialize)]
-pub struct PoseidonHashRequest {
+pub struct CommitmentHashRequest {
     /// Starknet address of the recipient
     pub recipient: String,
-    /// USD amount to mint
-    pub amount: u128,
+    /// USD amount to mint, as a decimal string or a JSON number, up to `u128::MAX`
+    #[serde(deserialize_with = "deserialize_decimal_string")]
+    pub amount: String,
     /// Transaction nonce
     pub nonce: u64,
     /// Block timestamp
     pub timestamp: u64,
-    /// Optional hash method to use: "batch" or "sequential" (default: "sequential")
+    /// Optional algorithm: "poseidon_batch", "poseidon_sequential" or "blake2s"
+    /// (default: "poseidon_sequential")
+    #[serde(default, alias = "hash_method")]
+    pub algorithm: Option<String>,
+    /// Optional domain prepended to the hash input (1 = deposit, 2 = withdrawal); omitted hashes
+    /// the inputs alone, as the bridge contracts do
     #[serde(default)]
-    pub hash_method: Option<String>,
+    pub domain: Option<u64>,
+}
+
+/// Reads an unsigned integer given either as a JSON number or as a string, keeping its digits.
+/// Numbers are taken from their raw JSON text, so they aren't capped at `u64::MAX` the way serde's
+/// integers are; the handler checks the range.
+fn deserialize_decimal_string<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<String, D::Error> {
+    let raw = Box::<RawValue>::deserialize(deserializer)?;
+    let text = raw.get();
+    if text.starts_with('"') {
+        return serde_json::from_str(text).map_err(de::Error::custom);
+    }
+
+    if text.bytes().all(|b| b.is_ascii_digit()) {
+        Ok(text.to_string())
+    } else {
+        Err(de::Error::invalid_value(
+            de::Unexpected::Other(text),
+            &"an unsigned integer or a decimal string",
+        ))
+    }
 }
 
 #[derive(Serialize, Deserialize)]
-pub struct PoseidonHashResponse {
+pub struct CommitmentHashResponse {
     pub commitment_hash: String,
 }
 
+#[derive(Deserialize, Debug)]
+pub struct FactHashRequest {
+    /// Hash of the proven Cairo program
+    pub program_hash: String,
+    /// Hash of the program output
+    pub output_hash: String,
+}
+
+#[derive(Serialize, Debug)]
+pub struct FactHashResponse {
+    pub fact_hash: String,
+}
+
 #[derive(Deserialize, Debug)]
 pub struct HashRequest {
     pub stark_pubkey: String,
@@ -78,29 +215,314 @@ pub struct InputData {
     pub timestamp: u64,
 }
 
+const DEFAULT_PROOF_JOBS_LIMIT: i64 = 50;
+const MAX_PROOF_JOBS_LIMIT: i64 = 500;
+/// Completed jobs averaged when estimating how long a running job has left
+const PROOF_JOB_ETA_SAMPLE_SIZE: i64 = 50;
+
+#[derive(Deserialize, Debug)]
+pub struct ProofJobsQuery {
+    pub status: Option<String>,
+    /// Only include jobs created at or after this RFC 3339 timestamp
+    pub after: Option<DateTime<Utc>>,
+    /// Only include jobs created before this RFC 3339 timestamp
+    pub before: Option<DateTime<Utc>>,
+    pub limit: Option<i64>,
+}
+
+const DEFAULT_WITHDRAWALS_PER_PAGE: i64 = 50;
+const MAX_WITHDRAWALS_PER_PAGE: i64 = 500;
+/// Oldest pending withdrawals listed by `GET /withdrawals`
+const PENDING_WITHDRAWALS_LIMIT: i64 = 10;
+
+#[derive(Deserialize, Debug)]
+pub struct WithdrawalsQuery {
+    pub status: Option<WithdrawalStatus>,
+    pub stark_pub_key: Option<String>,
+    /// Inclusive lower amount bound
+    pub min_amount: Option<i64>,
+    /// Inclusive upper amount bound
+    pub max_amount: Option<i64>,
+    pub sort_by: Option<SortField>,
+    /// One-based page number
+    pub page: Option<i64>,
+    pub per_page: Option<i64>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct UserWithdrawalsQuery {
+    pub limit: Option<i64>,
+    /// `next_after` of the previous page
+    pub after: Option<i32>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct WithdrawalCommitmentsQuery {
+    /// Inclusive lower block bound
+    pub from_block: Option<u64>,
+    /// Inclusive upper block bound
+    pub to_block: Option<u64>,
+}
+
+const ADMIN_TOKEN_HEADER: &str = "x-admin-token";
+
+const DEFAULT_DEAD_LETTER_LIMIT: i64 = 50;
+const MAX_DEAD_LETTER_LIMIT: i64 = 500;
+
+#[derive(Deserialize, Debug)]
+pub struct DeadLetterQuery {
+    pub limit: Option<i64>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct SimulateRelayRequest {
+    /// ID of the L2 transaction the relay would process
+    pub transaction_id: i64,
+    /// Proof data as stored on the L2 transaction, with `proof` and `merkle_root`
+    pub proof_data: serde_json::Value,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct RequeueResponse {
+    /// Id of the new `pending` L2 transaction
+    pub l2_transaction_id: i64,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct RequeueFailedRequest {
+    /// L2 transactions to requeue; every failed one when absent
+    pub ids: Option<Vec<i64>>,
+    /// Only requeue transactions that failed within this many hours
+    pub max_age_hours: Option<u32>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct RequeueFailedResponse {
+    pub requeued: u64,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ResetDepositsAfterReorgRequest {
+    /// First L1 block the reorg replaced
+    pub from_block: u64,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ResetDepositsAfterReorgResponse {
+    /// Unprocessed deposits marked `INVALIDATED`
+    pub affected: u64,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct CleanupProofJobsQuery {
+    /// Overrides `proof.retention_days` from the config
+    pub retention_days: Option<u32>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct CleanupProofJobsResponse {
+    pub archived: u64,
+}
+
+#[derive(Serialize, Debug)]
+pub struct ProofJobResponse {
+    #[serde(flatten)]
+    pub job: ProofJob,
+    /// `None` when the job failed or no job has completed yet to base an estimate on
+    pub estimated_remaining_seconds: Option<u64>,
+    /// Last stage the proof generation pipeline reported for the job, if it reported any
+    pub pipeline: Option<ProofPipelineProgress>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ProofJobFeeEstimatesResponse {
+    pub job_id: i64,
+    /// One entry per contract call left, in submission order
+    pub steps: Vec<StepFeeEstimate>,
+    pub total_fee: u128,
+    pub max_fee_per_step: Option<u128>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ResumeWithBudgetRequest {
+    /// Most the job's remaining submissions may cost together, in the fee token's smallest unit
+    pub max_total_fee: u128,
+}
+
 #[derive(Serialize, Debug)]
 pub struct ErrorResponse {
     pub error: String,
     pub details: Option<String>,
 }
 
+/// `ApiError` code for a deposit whose commitment hash is already taken
+pub const DUPLICATE_COMMITMENT: &str = "DUPLICATE_COMMITMENT";
+
+/// Unique index on `deposits.commitment_hash`
+const DEPOSIT_COMMITMENT_HASH_INDEX: &str = "idx_deposits_commitment_hash";
+
+/// Error returned when `stark_pub_key` is not a felt
+const INVALID_STARK_PUB_KEY: &str = "stark_pub_key must be a valid felt252 hex or decimal value";
+
+/// Error returned when `commitment_hash` cannot be normalized
+const INVALID_COMMITMENT_HASH: &str = "commitment_hash must be a hex value of at most 32 bytes";
+
+/// Error returned when `l2_tx_hash` is not a hex felt
+const INVALID_L2_TX_HASH: &str = "l2_tx_hash must be a 0x-prefixed felt252 hex value";
+
 pub async fn handle_deposit_post(
     Extension(pool): Extension<PgPool>,
-    Json(payload): Json<DepositRequest>,
-) -> Result<Json<DepositResponse>, (StatusCode, String)> {
-    if payload.amount <= 0 || payload.stark_pub_key.trim().is_empty() {
-        return Err((StatusCode::BAD_REQUEST, "Invalid input".to_string()));
+    format: ContentFormat,
+    FlexibleBody(payload): FlexibleBody<DepositRequest>,
+) -> Result<FlexibleResponse<DepositResponse>, Response> {
+    if payload.stark_pub_key.trim().is_empty() {
+        return Err((StatusCode::BAD_REQUEST, "Invalid input".to_string()).into_response());
     }
-    let deposit_id = insert_deposit(
-        &pool,
-        &payload.stark_pub_key,
-        payload.amount,
-        &payload.commitment_hash,
+    validate_amount_cents(payload.amount)
+        .map_err(|e| (StatusCode::BAD_REQUEST, e).into_response())?;
+
+    // Equal keys written differently must not end up as different rows
+    let stark_pub_key = parse_stark_pub_key(&payload.stark_pub_key)
+        .map(canonical_felt_hex)
+        .map_err(|_| {
+            (
+                StatusCode::UNPROCESSABLE_ENTITY,
+                INVALID_STARK_PUB_KEY.to_string(),
+            )
+                .into_response()
+        })?;
+    let commitment_hash = normalize_commitment_hash(&payload.commitment_hash).map_err(|_| {
+        (
+            StatusCode::UNPROCESSABLE_ENTITY,
+            INVALID_COMMITMENT_HASH.to_string(),
+        )
+            .into_response()
+    })?;
+
+    let mut headers = HeaderMap::new();
+
+    let deposit_id = match payload.idempotency_key.as_deref() {
+        // A replayed key must still return its deposit, so a clash with another key's
+        // commitment hash is only detected by the insert itself
+        Some(key) if !key.trim().is_empty() => {
+            let (deposit_id, created) = insert_deposit_idempotent(
+                &pool,
+                &stark_pub_key,
+                payload.amount,
+                &commitment_hash,
+                key,
+            )
+            .await
+            .map_err(deposit_insert_error)?;
+
+            if !created {
+                headers.insert("X-Idempotent-Replayed", HeaderValue::from_static("true"));
+                return Ok(
+                    FlexibleResponse::new(format, DepositResponse { deposit_id })
+                        .with_headers(headers),
+                );
+            }
+            deposit_id
+        }
+        _ => {
+            let unique = check_commitment_hash_unique(&pool, &commitment_hash)
+                .await
+                .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()).into_response())?;
+            if !unique {
+                return Err(duplicate_commitment_error());
+            }
+
+            insert_deposit(&pool, &stark_pub_key, payload.amount, &commitment_hash)
+                .await
+                .map_err(deposit_insert_error)?
+        }
+    };
+
+    let response = FlexibleResponse::new(format, DepositResponse { deposit_id })
+        .with_status(StatusCode::CREATED)
+        .with_headers(headers);
+    Ok(response)
+}
+
+/// 409 returned when a deposit with the same commitment hash already exists
+fn duplicate_commitment_error() -> Response {
+    (
+        StatusCode::CONFLICT,
+        Json(ApiError {
+            code: DUPLICATE_COMMITMENT.to_string(),
+            message: "A deposit with this commitment hash already exists".to_string(),
+        }),
     )
-    .await
-    .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+        .into_response()
+}
+
+/// Also catches a duplicate inserted concurrently, after the uniqueness check passed
+fn deposit_insert_error(e: sqlx::Error) -> Response {
+    let is_duplicate_commitment = e.as_database_error().is_some_and(|db_error| {
+        db_error.is_unique_violation()
+            && db_error.constraint() == Some(DEPOSIT_COMMITMENT_HASH_INDEX)
+    });
+
+    if is_duplicate_commitment {
+        duplicate_commitment_error()
+    } else {
+        (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()).into_response()
+    }
+}
+
+/// Creates up to [`MAX_BULK_DEPOSITS`] deposits atomically. Entries whose commitment hash
+/// already exists are skipped and listed in the response rather than failing the batch.
+pub async fn bulk_create_deposits(
+    Extension(pool): Extension<PgPool>,
+    Json(mut payload): Json<BulkDepositRequest>,
+) -> Result<(StatusCode, Json<BulkInsertDepositsResult>), (StatusCode, String)> {
+    if payload.deposits.is_empty() {
+        return Err((StatusCode::BAD_REQUEST, "No deposits given".to_string()));
+    }
+    if payload.deposits.len() > MAX_BULK_DEPOSITS {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            format!(
+                "At most {} deposits can be created at once",
+                MAX_BULK_DEPOSITS
+            ),
+        ));
+    }
+
+    for (index, deposit) in payload.deposits.iter_mut().enumerate() {
+        if deposit.stark_pub_key.trim().is_empty() || deposit.commitment_hash.trim().is_empty() {
+            return Err((
+                StatusCode::BAD_REQUEST,
+                format!("Invalid input in deposit {}", index),
+            ));
+        }
+        validate_amount_cents(deposit.amount).map_err(|e| {
+            (
+                StatusCode::BAD_REQUEST,
+                format!("{} (deposit {})", e, index),
+            )
+        })?;
+        deposit.stark_pub_key = parse_stark_pub_key(&deposit.stark_pub_key)
+            .map(canonical_felt_hex)
+            .map_err(|_| {
+                (
+                    StatusCode::UNPROCESSABLE_ENTITY,
+                    format!("{} (deposit {})", INVALID_STARK_PUB_KEY, index),
+                )
+            })?;
+        normalize_commitment_hash(&deposit.commitment_hash).map_err(|_| {
+            (
+                StatusCode::UNPROCESSABLE_ENTITY,
+                format!("{} (deposit {})", INVALID_COMMITMENT_HASH, index),
+            )
+        })?;
+    }
+
+    let result = insert_deposits_bulk(&pool, &payload.deposits)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
 
-    Ok(Json(DepositResponse { deposit_id }))
+    Ok((StatusCode::CREATED, Json(result)))
 }
 
 pub async fn handle_get_pending_deposits(
@@ -113,131 +535,1564 @@ pub async fn handle_get_pending_deposits(
     Ok(Json(deposit))
 }
 
+pub async fn get_deposit_proof(
+    Extension(pool): Extension<PgPool>,
+    Extension(deposit_tree): Extension<DepositTree>,
+    Path(id): Path<i32>,
+) -> Result<Json<MerkleProofJson>, (StatusCode, String)> {
+    let deposit = fetch_deposit_by_id(&pool, id)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+        .ok_or((StatusCode::NOT_FOUND, format!("Deposit {} not found", id)))?;
+
+    let not_in_tree = || {
+        (
+            StatusCode::CONFLICT,
+            format!("Deposit {} is not yet included in the Merkle tree", id),
+        )
+    };
+
+    if deposit.leaf_index.is_none() {
+        return Err(not_in_tree());
+    }
+
+    let proof = deposit_tree
+        .get_proof(&deposit.commitment_hash)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+        .ok_or_else(not_in_tree)?;
+
+    Ok(Json(proof))
+}
+
+/// Downloads a hex checkpoint of the withdrawal tree
+pub async fn get_merkle_checkpoint(
+    Extension(withdrawal_tree): Extension<WithdrawalTree>,
+) -> Result<Response, (StatusCode, String)> {
+    let checkpoint = withdrawal_tree
+        .checkpoint()
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    Ok(([(CONTENT_TYPE, "text/plain; charset=utf-8")], checkpoint).into_response())
+}
+
+/// Replaces the withdrawal tree with an uploaded `GET /merkle/checkpoint` blob
+pub async fn restore_merkle_checkpoint(
+    Extension(config): Extension<AppConfig>,
+    Extension(withdrawal_tree): Extension<WithdrawalTree>,
+    headers: HeaderMap,
+    checkpoint: String,
+) -> Result<Json<serde_json::Value>, (StatusCode, String)> {
+    require_admin_token(&config, &headers)?;
+
+    let root = withdrawal_tree
+        .restore(checkpoint.trim())
+        .await
+        .map_err(|e| (StatusCode::UNPROCESSABLE_ENTITY, e.to_string()))?;
+
+    Ok(Json(json!({ "root": format!("0x{}", hex::encode(root)) })))
+}
+
 pub async fn create_withdrawal(
     Extension(pool): Extension<PgPool>,
-    Json(payload): Json<CreateWithdrawalRequest>,
-) -> Result<Json<WithrawalResponse>, (StatusCode, String)> {
+    Extension(config): Extension<AppConfig>,
+    format: ContentFormat,
+    FlexibleBody(payload): FlexibleBody<CreateWithdrawalRequest>,
+) -> Result<FlexibleResponse<WithrawalResponse>, (StatusCode, String)> {
     // ADDED: Validation logic
-    if payload.amount <= 0
-        || payload.stark_pub_key.trim().is_empty()
+    if payload.stark_pub_key.trim().is_empty()
         || payload.commitment_hash.trim().is_empty()
         || payload.l1_token.trim().is_empty()
     {
         return Err((StatusCode::BAD_REQUEST, "Invalid input".to_string()));
     }
+    validate_amount_cents(payload.amount).map_err(|e| (StatusCode::BAD_REQUEST, e))?;
+
+    let stark_pub_key = parse_stark_pub_key(&payload.stark_pub_key)
+        .map(canonical_felt_hex)
+        .map_err(|_| {
+            (
+                StatusCode::UNPROCESSABLE_ENTITY,
+                INVALID_STARK_PUB_KEY.to_string(),
+            )
+        })?;
+    let commitment_hash = normalize_commitment_hash(&payload.commitment_hash).map_err(|_| {
+        (
+            StatusCode::UNPROCESSABLE_ENTITY,
+            INVALID_COMMITMENT_HASH.to_string(),
+        )
+    })?;
+
+    if let Some(precision) = payload.amount_precision {
+        if precision > MAX_AMOUNT_PRECISION {
+            return Err((
+                StatusCode::UNPROCESSABLE_ENTITY,
+                format!(
+                    "amount_precision must be at most {}, got {}",
+                    MAX_AMOUNT_PRECISION, precision
+                ),
+            ));
+        }
+    }
+
+    if !config.relayer.is_allowed_l1_token(&payload.l1_token) {
+        return Err((
+            StatusCode::UNPROCESSABLE_ENTITY,
+            "l1_token is not a whitelisted ERC-20".to_string(),
+        ));
+    }
+
+    // The USD value can only be checked, and the amount relayed, for tokens with known decimals
+    let token = fetch_token_metadata(&pool, &payload.l1_token)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+        .ok_or_else(|| {
+            (
+                StatusCode::UNPROCESSABLE_ENTITY,
+                "l1_token has no token metadata".to_string(),
+            )
+        })?;
+    let amount_precision = u8::try_from(token.decimals)
+        .ok()
+        .filter(|&decimals| decimals <= MAX_AMOUNT_PRECISION)
+        .ok_or_else(|| {
+            (
+                StatusCode::UNPROCESSABLE_ENTITY,
+                format!(
+                    "{} has {} decimals, more than the {} supported",
+                    token.symbol, token.decimals, MAX_AMOUNT_PRECISION
+                ),
+            )
+        })?;
+    if let Some(precision) = payload
+        .amount_precision
+        .filter(|&precision| precision != amount_precision)
+    {
+        return Err((
+            StatusCode::UNPROCESSABLE_ENTITY,
+            format!(
+                "amount_precision {} does not match the {} decimals of {}",
+                precision, amount_precision, token.symbol
+            ),
+        ));
+    }
+    let token_amount = check_withdrawal_usd_value(
+        &token,
+        payload.token_amount.as_deref(),
+        payload.amount,
+        config.relayer.amount_tolerance_bps,
+    )
+    .map_err(|e| (StatusCode::UNPROCESSABLE_ENTITY, e))?;
 
-    let withdrawal_id = insert_withdrawal(
+    let l2_tx_hash = payload
+        .l2_tx_hash
+        .as_deref()
+        .map(|hash| {
+            // Transaction hashes are always written in hex, so a decimal value is a mistake
+            if !hash.starts_with("0x") {
+                return Err(());
+            }
+            normalize_felt_hex(hash).map_err(|_| ())
+        })
+        .transpose()
+        .map_err(|_| {
+            (
+                StatusCode::UNPROCESSABLE_ENTITY,
+                INVALID_L2_TX_HASH.to_string(),
+            )
+        })?;
+
+    let (withdrawal_id, nonce) = insert_withdrawal_with_next_nonce(
         &pool,
-        &payload.stark_pub_key,
+        &stark_pub_key,
         payload.amount,
-        &payload.commitment_hash,
+        &payload.l1_token,
+        &commitment_hash,
+        l2_tx_hash.as_deref(),
+        amount_precision,
+        &token_amount.to_string(),
+        payload.nonce,
     )
     .await
-    .map_err(|err| {
-        (
+    .map_err(|err| match err {
+        WithdrawalNonceError::NonceMismatch { .. } => (StatusCode::CONFLICT, err.to_string()),
+        WithdrawalNonceError::Database(err) => (
             StatusCode::INTERNAL_SERVER_ERROR,
             format!("DB Error: {:?}", err),
-        )
+        ),
     })?;
 
-    Ok(Json(WithrawalResponse { withdrawal_id }))
+    Ok(FlexibleResponse::new(
+        format,
+        WithrawalResponse {
+            withdrawal_id,
+            nonce,
+        },
+    ))
 }
 
-pub async fn get_pending_withdrawals(
-    Extension(pool): Extension<PgPool>,
-) -> Result<Json<Vec<Withdrawal>>, (StatusCode, String)> {
-    match fetch_pending_withdrawals(&pool, 3).await {
-        Ok(withdrawals) => Ok(Json(withdrawals)),
-        Err(err) => Err((StatusCode::INTERNAL_SERVER_ERROR, err.to_string())),
+/// Checks that `token_amount` base units of `token` are worth `amount_cents`, within
+/// `tolerance_bps`, and returns the parsed amount
+pub fn check_withdrawal_usd_value(
+    token: &TokenMetadata,
+    token_amount: Option<&str>,
+    amount_cents: i64,
+    tolerance_bps: u32,
+) -> Result<u128, String> {
+    let symbol = &token.symbol;
+    let token_amount = token_amount
+        .ok_or_else(|| format!("token_amount is required for {} withdrawals", symbol))?;
+    let parsed_amount = token_amount.trim().parse::<u128>().map_err(|_| {
+        format!(
+            "token_amount must be a whole number of {} base units, got {:?}",
+            symbol, token_amount
+        )
+    })?;
+
+    let expected_cents = token_amount_to_usd_cents(
+        parsed_amount,
+        token.decimals as u8,
+        token.usd_price_cents as u64,
+    )
+    .ok_or_else(|| format!("token_amount of {} {} is too large", token_amount, symbol))?;
+
+    if !within_tolerance_bps(expected_cents, amount_cents as u128, tolerance_bps) {
+        return Err(format!(
+            "amount of {} USD cents does not match the {} cents {} {} base units are worth",
+            amount_cents, expected_cents, token_amount, symbol
+        ));
     }
+    Ok(parsed_amount)
 }
 
-pub async fn hello_world(
-    Extension(_): Extension<PgPool>,
-) -> Result<Json<serde_json::Value>, (StatusCode, String)> {
-    Ok(Json(json!({
-        "message": "hello world from zeroxbridge"
-    })))
+pub async fn get_allowed_tokens(Extension(config): Extension<AppConfig>) -> Json<Vec<String>> {
+    Json(config.relayer.allowed_l1_tokens)
 }
 
-/// Computes a Poseidon commitment hash for deposit transactions
-///
-/// This endpoint allows users to generate the same hash that the L2 contract
-/// will compute and verify using Cairo-native Poseidon logic. Users should call
-/// this endpoint before depositing to L1.
-///
-/// The hash is computed using the following fields:
-/// - recipient: Starknet address of the receiver
-/// - amount: USD amount to mint
-/// - nonce: Transaction nonce
-/// - timestamp: Block timestamp
-///
-/// Returns the commitment hash that should be used when making the deposit.
-pub async fn compute_poseidon_hash(
-    Json(payload): Json<PoseidonHashRequest>,
-) -> Result<Json<PoseidonHashResponse>, (StatusCode, String)> {
-    // Parse recipient address as Felt (felt252)
-    let recipient_felt = match Felt::from_hex(&payload.recipient) {
-        Ok(felt) => felt,
-        Err(_) => return Err((StatusCode::BAD_REQUEST,
-            "Invalid recipient address format. Must be a valid Starknet address in hex format (0x...).".
-            to_string())
-        ),
-    };
-
-    // Determine which hash method to use (default to sequential pairwise which is more common in Cairo contracts)
-    let method = match payload.hash_method.as_deref() {
-        Some("batch") => HashMethod::BatchHash,
-        Some("sequential") | None => HashMethod::SequentialPairwise,
-        Some(method) => {
+/// Withdrawals in any status, filtered, sorted and paginated by the query parameters. Served
+/// on `GET /v2/withdrawals`, since `GET /withdrawals` keeps returning the plain pending list.
+pub async fn get_withdrawals(
+    Extension(pool): Extension<PgPool>,
+    Query(params): Query<WithdrawalsQuery>,
+) -> Result<Json<PaginatedResult<Withdrawal>>, (StatusCode, String)> {
+    if let (Some(min_amount), Some(max_amount)) = (params.min_amount, params.max_amount) {
+        if min_amount > max_amount {
             return Err((
                 StatusCode::BAD_REQUEST,
-                format!(
-                    "Invalid hash method: '{}'. Valid options are 'batch' or 'sequential'",
-                    method
-                ),
-            ))
+                "'min_amount' must not be greater than 'max_amount'".to_string(),
+            ));
         }
+    }
+
+    let page = params.page.unwrap_or(1);
+    if page < 1 {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            "'page' must be at least 1".to_string(),
+        ));
+    }
+    let per_page = params.per_page.unwrap_or(DEFAULT_WITHDRAWALS_PER_PAGE);
+    if !(1..=MAX_WITHDRAWALS_PER_PAGE).contains(&per_page) {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            format!(
+                "'per_page' must be between 1 and {}",
+                MAX_WITHDRAWALS_PER_PAGE
+            ),
+        ));
+    }
+
+    // Keys are stored in canonical form, see `create_withdrawal`
+    let stark_pub_key = params
+        .stark_pub_key
+        .as_deref()
+        .map(|key| parse_stark_pub_key(key).map(canonical_felt_hex))
+        .transpose()
+        .map_err(|_| {
+            (
+                StatusCode::UNPROCESSABLE_ENTITY,
+                INVALID_STARK_PUB_KEY.to_string(),
+            )
+        })?;
+
+    let filter = WithdrawalFilter {
+        status: params.status,
+        stark_pub_key,
+        min_amount: params.min_amount,
+        max_amount: params.max_amount,
+        sort_by: params.sort_by.unwrap_or_default(),
+        page: PaginationParams { page, per_page },
     };
 
-    // Compute the Poseidon hash using the utility function
-    let hash = compute_poseidon_commitment_hash(
-        recipient_felt,
-        payload.amount,
-        payload.nonce,
-        payload.timestamp,
-        method,
-    );
+    list_withdrawals(&pool, filter)
+        .await
+        .map(Json)
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))
+}
 
-    // Convert hash to hex string format
-    let hash_hex = format!("0x{:x}", hash);
+/// Withdrawal history of one user, newest first, paginated by the `after` cursor
+pub async fn get_user_withdrawals(
+    Extension(pool): Extension<PgPool>,
+    Path(stark_pub_key): Path<String>,
+    Query(params): Query<UserWithdrawalsQuery>,
+) -> Result<Json<PaginatedResult<Withdrawal>>, (StatusCode, String)> {
+    let limit = params.limit.unwrap_or(DEFAULT_WITHDRAWALS_PER_PAGE);
+    if !(1..=MAX_WITHDRAWALS_PER_PAGE).contains(&limit) {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            format!("'limit' must be between 1 and {}", MAX_WITHDRAWALS_PER_PAGE),
+        ));
+    }
 
-    Ok(Json(PoseidonHashResponse {
-        commitment_hash: hash_hex,
-    }))
+    // Keys are stored in canonical form, see `create_withdrawal`
+    let stark_pub_key = parse_stark_pub_key(&stark_pub_key)
+        .map(canonical_felt_hex)
+        .map_err(|_| {
+            (
+                StatusCode::UNPROCESSABLE_ENTITY,
+                INVALID_STARK_PUB_KEY.to_string(),
+            )
+        })?;
+
+    fetch_withdrawals_by_stark_pub_key(&pool, &stark_pub_key, limit, params.after)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+        .map(Json)
+        .ok_or_else(|| {
+            (
+                StatusCode::BAD_REQUEST,
+                "'after' is not a withdrawal of this user".to_string(),
+            )
+        })
 }
 
-pub async fn compute_hash_handler(
-    Json(payload): Json<HashRequest>,
-) -> Result<Json<HashResponse>, impl IntoResponse> {
-    // Validate the Starknet public key format before hashing
-    let burn_data = BurnData {
-        caller: payload.stark_pubkey.clone(),
-        amount: payload.usd_val,
-        nonce: payload.nonce,
-        time_stamp: payload.timestamp,
+/// Nonce of the user's next withdrawal, to be hashed into its `commitment_hash`
+pub async fn get_user_next_nonce(
+    Extension(pool): Extension<PgPool>,
+    Path(stark_pub_key): Path<String>,
+) -> Result<Json<NextNonceResponse>, (StatusCode, String)> {
+    let stark_pub_key = parse_stark_pub_key(&stark_pub_key)
+        .map(canonical_felt_hex)
+        .map_err(|_| {
+            (
+                StatusCode::UNPROCESSABLE_ENTITY,
+                INVALID_STARK_PUB_KEY.to_string(),
+            )
+        })?;
+
+    let mut conn = pool
+        .acquire()
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    let nonce = next_nonce_for_user(&mut conn, &stark_pub_key)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    Ok(Json(NextNonceResponse { nonce }))
+}
+
+/// The oldest pending withdrawals
+pub async fn get_pending_withdrawals(
+    Extension(pool): Extension<PgPool>,
+) -> Result<Json<Vec<Withdrawal>>, (StatusCode, String)> {
+    let filter = WithdrawalFilter {
+        status: Some(WithdrawalStatus::Pending),
+        stark_pub_key: None,
+        min_amount: None,
+        max_amount: None,
+        sort_by: SortField::CreatedAt,
+        page: PaginationParams {
+            page: 1,
+            per_page: PENDING_WITHDRAWALS_LIMIT,
+        },
     };
-    if BurnData::hex_to_bytes32(&burn_data.caller).is_err() {
-        let error_response = ErrorResponse {
-            error: "Invalid stark_pubkey".to_string(),
-            details: Some("Invalid hex string for caller address".to_string()),
-        };
-        return Err((StatusCode::BAD_REQUEST, Json(error_response)));
+
+    match list_withdrawals(&pool, filter).await {
+        Ok(withdrawals) => Ok(Json(withdrawals.items)),
+        Err(err) => Err((StatusCode::INTERNAL_SERVER_ERROR, err.to_string())),
     }
-    // Compute the commitment hash
-    let hex_hash = burn_data.hash_to_hex_string();
-    // Create response
-    let response = HashResponse {
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct WithdrawalDetailsResponse {
+    #[serde(flatten)]
+    pub withdrawal: Withdrawal,
+    pub total_gas_spent_gwei: Option<i64>,
+}
+
+/// Withdrawal `id`, with the gas its relay transaction spent
+pub async fn get_withdrawal(
+    Extension(pool): Extension<PgPool>,
+    Path(id): Path<i32>,
+) -> Result<Json<WithdrawalDetailsResponse>, (StatusCode, String)> {
+    let withdrawal = fetch_withdrawal_by_id(&pool, id)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+        .ok_or((
+            StatusCode::NOT_FOUND,
+            format!("Withdrawal {} not found", id),
+        ))?;
+
+    Ok(Json(WithdrawalDetailsResponse {
+        total_gas_spent_gwei: withdrawal.total_gas_spent_gwei(),
+        withdrawal,
+    }))
+}
+
+/// Gas the Ethereum relayer has spent on relayed withdrawals
+pub async fn get_gas_metrics(
+    Extension(pool): Extension<PgPool>,
+) -> Result<Json<GasMetrics>, (StatusCode, String)> {
+    fetch_gas_metrics(&pool)
+        .await
+        .map(Json)
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))
+}
+
+/// How far a withdrawal's proof is from being claimable on L1
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WithdrawalProofStatus {
+    PendingProof,
+    ProofSubmitted,
+    ProofVerified,
+    ProofFailed,
+}
+
+impl WithdrawalProofStatus {
+    /// Reads the proof row's status, falling back to the withdrawal's own status for
+    /// withdrawals whose proof predates `withdrawal_proofs` tracking
+    pub fn from_row(row: &WithdrawalProofRow) -> Self {
+        match row.proof_status.as_deref() {
+            Some(PROOF_STATUS_READY) => Self::ProofVerified,
+            Some(STATUS_PROOF_SUBMITTED) => Self::ProofSubmitted,
+            Some(STATUS_PROOF_FAILED) => Self::ProofFailed,
+            _ => match row.withdrawal_status.as_str() {
+                STATUS_PROOF_SUBMITTED => Self::ProofSubmitted,
+                STATUS_READY_FOR_RELAY | "relayed" => Self::ProofVerified,
+                STATUS_PROOF_FAILED => Self::ProofFailed,
+                _ => Self::PendingProof,
+            },
+        }
+    }
+
+    /// Minutes until the proof is verified, given the average Atlantic job duration.
+    ///
+    /// `None` for failed proofs and while no job has completed to base an estimate on.
+    pub fn estimate_completion_minutes(
+        self,
+        submitted_at: Option<DateTime<Utc>>,
+        average_job_seconds: Option<f64>,
+        now: DateTime<Utc>,
+    ) -> Option<i64> {
+        let remaining_seconds = match self {
+            Self::ProofVerified => return Some(0),
+            Self::ProofFailed => return None,
+            Self::PendingProof => average_job_seconds?,
+            Self::ProofSubmitted => {
+                let elapsed = submitted_at
+                    .map(|submitted_at| (now - submitted_at).num_seconds() as f64)
+                    .unwrap_or(0.0);
+                (average_job_seconds? - elapsed).max(0.0)
+            }
+        };
+
+        Some((remaining_seconds / 60.0).ceil() as i64)
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct WithdrawalProofStatusResponse {
+    pub withdrawal_id: i32,
+    pub status: WithdrawalProofStatus,
+    pub atlantic_job_id: Option<String>,
+    pub estimated_completion_minutes: Option<i64>,
+}
+
+/// Whether withdrawal `id`'s proof is ready to claim on L1, with an estimate if it is not
+pub async fn get_withdrawal_proof_status(
+    Extension(pool): Extension<PgPool>,
+    Path(id): Path<i32>,
+) -> Result<Json<WithdrawalProofStatusResponse>, (StatusCode, String)> {
+    let row = fetch_withdrawal_proof(&pool, id)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+        .ok_or((
+            StatusCode::NOT_FOUND,
+            format!("Withdrawal {} not found", id),
+        ))?;
+
+    let average_job_seconds = fetch_system_stat(&pool, ATLANTIC_JOB_DURATION_STAT)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    let status = WithdrawalProofStatus::from_row(&row);
+    let estimated_completion_minutes =
+        status.estimate_completion_minutes(row.submitted_at, average_job_seconds, Utc::now());
+
+    Ok(Json(WithdrawalProofStatusResponse {
+        withdrawal_id: row.withdrawal_id,
+        status,
+        atlantic_job_id: row.atlantic_job_id,
+        estimated_completion_minutes,
+    }))
+}
+
+/// Lists every status transition of a withdrawal, oldest first
+pub async fn get_withdrawal_timeline(
+    Extension(pool): Extension<PgPool>,
+    Path(id): Path<i32>,
+) -> Result<Json<Vec<WithdrawalEvent>>, (StatusCode, String)> {
+    let events = fetch_withdrawal_events(&pool, id)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+        .ok_or((
+            StatusCode::NOT_FOUND,
+            format!("Withdrawal {} not found", id),
+        ))?;
+
+    Ok(Json(events))
+}
+
+/// Streams L2 burn events to the client as Server-Sent Events as they are fetched
+pub async fn stream_l2_events(
+    Extension(bus): Extension<EventBus<CommitmentLog>>,
+) -> Sse<impl Stream<Item = Result<Event, Infallible>>> {
+    sse_from_bus(bus, "commitment")
+}
+
+/// Streams a `completion` event for every withdrawal relayed to Ethereum by this sequencer.
+///
+/// Stays silent unless `relayer.run_in_sequencer` is set: the standalone `ethereum-relayer`
+/// reports its relays to the configured webhook instead.
+pub async fn stream_withdrawal_completions(
+    Extension(bus): Extension<EventBus<CompletedWithdrawalEvent>>,
+) -> Sse<impl Stream<Item = Result<Event, Infallible>>> {
+    sse_from_bus(bus, "completion")
+}
+
+/// Forwards every event published on `bus` as a JSON Server-Sent Event named `event_name`
+fn sse_from_bus<T>(
+    bus: EventBus<T>,
+    event_name: &'static str,
+) -> Sse<impl Stream<Item = Result<Event, Infallible>>>
+where
+    T: Clone + Serialize + Send + 'static,
+{
+    let events = stream::unfold(bus.subscribe(), move |mut receiver| async move {
+        loop {
+            match receiver.recv().await {
+                Ok(payload) => match Event::default().event(event_name).json_data(&payload) {
+                    Ok(event) => return Some((Ok(event), receiver)),
+                    Err(e) => warn!("Failed to serialize {} event: {}", event_name, e),
+                },
+                Err(RecvError::Lagged(skipped)) => {
+                    warn!("{} stream lagged, skipped {} events", event_name, skipped);
+                }
+                Err(RecvError::Closed) => return None,
+            }
+        }
+    });
+
+    Sse::new(events).keep_alive(KeepAlive::default())
+}
+
+pub async fn get_proof_jobs(
+    Extension(pool): Extension<PgPool>,
+    Query(params): Query<ProofJobsQuery>,
+) -> Result<Json<Vec<ProofJob>>, (StatusCode, String)> {
+    if let Some(status) = params.status.as_deref() {
+        if !PROOF_JOB_STATUSES.contains(&status) {
+            return Err((
+                StatusCode::BAD_REQUEST,
+                format!(
+                    "Invalid status: '{}'. Valid options are {}",
+                    status,
+                    PROOF_JOB_STATUSES.join(", ")
+                ),
+            ));
+        }
+    }
+
+    if let (Some(after), Some(before)) = (params.after, params.before) {
+        if after >= before {
+            return Err((
+                StatusCode::BAD_REQUEST,
+                "'after' must be earlier than 'before'".to_string(),
+            ));
+        }
+    }
+
+    let limit = params.limit.unwrap_or(DEFAULT_PROOF_JOBS_LIMIT);
+    if !(1..=MAX_PROOF_JOBS_LIMIT).contains(&limit) {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            format!("'limit' must be between 1 and {}", MAX_PROOF_JOBS_LIMIT),
+        ));
+    }
+
+    let filter = ProofJobFilter {
+        status: params.status,
+        created_after: params.after,
+        created_before: params.before,
+        limit,
+    };
+
+    let jobs = list_proof_jobs(&pool, filter)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    Ok(Json(jobs))
+}
+
+/// A single proof job by its `job_id`, with an ETA based on recently completed jobs
+pub async fn get_proof_job(
+    Extension(pool): Extension<PgPool>,
+    Path(job_id): Path<i64>,
+) -> Result<Json<ProofJobResponse>, (StatusCode, String)> {
+    let job = fetch_proof_job_by_job_id(&pool, job_id)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+        .ok_or((
+            StatusCode::NOT_FOUND,
+            format!("Proof job {} not found", job_id),
+        ))?;
+
+    let average_step_seconds = average_proof_step_duration(&pool, PROOF_JOB_ETA_SAMPLE_SIZE)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    let estimated_remaining_seconds = average_step_seconds.and_then(|average| {
+        let total_steps = count_proof_steps(std::path::Path::new(&job.calldata_dir));
+        job.estimate_completion_time(total_steps, average, Utc::now())
+    });
+
+    let pipeline = fetch_proof_pipeline_progress(&pool, job_id)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    Ok(Json(ProofJobResponse {
+        job,
+        estimated_remaining_seconds,
+        pipeline,
+    }))
+}
+
+/// Stage the proof generation pipeline is at for `job_id`. Available while the proof is still
+/// being generated, before the job itself is recorded.
+pub async fn get_proof_job_pipeline(
+    Extension(pool): Extension<PgPool>,
+    Path(job_id): Path<i64>,
+) -> Result<Json<ProofPipelineProgress>, (StatusCode, String)> {
+    let progress = fetch_proof_pipeline_progress(&pool, job_id)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+        .ok_or((
+            StatusCode::NOT_FOUND,
+            format!("No pipeline progress for proof job {}", job_id),
+        ))?;
+
+    Ok(Json(progress))
+}
+
+/// Every Starknet transaction submitted for proof job `job_id`, oldest first
+pub async fn get_proof_job_transactions(
+    Extension(pool): Extension<PgPool>,
+    Path(job_id): Path<i64>,
+) -> Result<Json<Vec<ProofJobTxHash>>, (StatusCode, String)> {
+    let job = fetch_proof_job_by_job_id(&pool, job_id)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+        .ok_or((
+            StatusCode::NOT_FOUND,
+            format!("Proof job {} not found", job_id),
+        ))?;
+
+    let transactions = fetch_proof_job_tx_hashes(&pool, job.id)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    Ok(Json(transactions))
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ChainTvlResponse {
+    pub tvl_wei: String,
+    pub block: i64,
+    pub captured_at: DateTime<Utc>,
+}
+
+impl From<TvlSnapshot> for ChainTvlResponse {
+    fn from(snapshot: TvlSnapshot) -> Self {
+        Self {
+            tvl_wei: snapshot.tvl_wei,
+            block: snapshot.block_number,
+            captured_at: snapshot.captured_at,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct OracleTvlResponse {
+    pub l1: ChainTvlResponse,
+    pub l2: ChainTvlResponse,
+    pub is_in_sync: bool,
+    pub diff_bps: u128,
+}
+
+/// Whether `snapshot` was captured more than two oracle polling intervals before `now`,
+/// i.e. the oracle missed at least one sync
+pub fn is_tvl_snapshot_stale(
+    snapshot: &TvlSnapshot,
+    polling_interval_seconds: u64,
+    now: DateTime<Utc>,
+) -> bool {
+    let max_age = chrono::Duration::seconds(polling_interval_seconds.saturating_mul(2) as i64);
+    now - snapshot.captured_at > max_age
+}
+
+/// Latest TVL the oracle read on each chain, summed over every vault on L1. They are in sync
+/// when both snapshots are fresh and differ by no more than `oracle.tolerance_bps`.
+pub async fn get_oracle_tvl(
+    Extension(pool): Extension<PgPool>,
+    Extension(config): Extension<AppConfig>,
+) -> Result<Json<OracleTvlResponse>, (StatusCode, String)> {
+    let mut snapshots = Vec::with_capacity(2);
+    for chain in [L1_TOTAL_TVL_CHAIN, L2_TVL_CHAIN] {
+        let snapshot = fetch_latest_tvl_snapshot(&pool, chain)
+            .await
+            .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+            .ok_or((
+                StatusCode::NOT_FOUND,
+                format!("No {} TVL has been recorded yet", chain),
+            ))?;
+        let tvl_wei = snapshot.tvl_wei.parse::<u128>().map_err(|e| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                format!("Invalid {} TVL {}: {}", chain, snapshot.tvl_wei, e),
+            )
+        })?;
+        snapshots.push((snapshot, tvl_wei));
+    }
+    let (l2, l2_tvl) = snapshots.pop().unwrap();
+    let (l1, l1_tvl) = snapshots.pop().unwrap();
+
+    let diff_bps = tvl_diff_bps(l1_tvl, l2_tvl);
+    let tolerance_bps = config.oracle.tolerance_bps.unwrap_or(DEFAULT_TOLERANCE_BPS);
+    let now = Utc::now();
+    let polling_interval = config.oracle.polling_interval_seconds;
+    let is_in_sync = !is_tvl_snapshot_stale(&l1, polling_interval, now)
+        && !is_tvl_snapshot_stale(&l2, polling_interval, now)
+        && diff_bps <= tolerance_bps as u128;
+
+    Ok(Json(OracleTvlResponse {
+        l1: l1.into(),
+        l2: l2.into(),
+        is_in_sync,
+        diff_bps,
+    }))
+}
+
+/// Proof job `job_id` as portable JSON, for resuming it on another sequencer instance
+pub async fn get_proof_job_handoff(
+    Extension(pool): Extension<PgPool>,
+    Extension(config): Extension<AppConfig>,
+    headers: HeaderMap,
+    Path(job_id): Path<i64>,
+) -> Result<Json<serde_json::Value>, (StatusCode, String)> {
+    require_admin_token(&config, &headers)?;
+
+    let job = fetch_proof_job_by_job_id(&pool, job_id)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+        .ok_or((
+            StatusCode::NOT_FOUND,
+            format!("Proof job {} not found", job_id),
+        ))?;
+
+    Ok(Json(job.to_handoff_json()))
+}
+
+/// Takes over a proof job exported by `GET /proof-jobs/{job_id}/handoff`, keeping its stage
+pub async fn import_proof_job_handoff(
+    Extension(pool): Extension<PgPool>,
+    Extension(config): Extension<AppConfig>,
+    headers: HeaderMap,
+    Json(handoff): Json<serde_json::Value>,
+) -> Result<(StatusCode, Json<ProofJob>), (StatusCode, String)> {
+    require_admin_token(&config, &headers)?;
+
+    let job = ProofJob::from_handoff_json(&handoff)
+        .map_err(|e| (StatusCode::UNPROCESSABLE_ENTITY, e.to_string()))?;
+
+    let imported = import_proof_job(&pool, &job).await.map_err(|e| {
+        if e.as_database_error()
+            .is_some_and(|db_error| db_error.is_unique_violation())
+        {
+            (
+                StatusCode::CONFLICT,
+                format!("Proof job {} already exists", job.job_id),
+            )
+        } else {
+            (StatusCode::INTERNAL_SERVER_ERROR, e.to_string())
+        }
+    })?;
+
+    Ok((StatusCode::CREATED, Json(imported)))
+}
+
+/// Size of the chunks `GET /proof-jobs/{job_id}/calldata/{stage}` streams a file in
+const CALLDATA_CHUNK_BYTES: usize = 64 * 1024;
+
+/// Streams the raw calldata file of one stage of proof job `job_id`, for debugging failed
+/// submissions
+pub async fn get_proof_job_calldata(
+    Extension(pool): Extension<PgPool>,
+    Extension(config): Extension<AppConfig>,
+    headers: HeaderMap,
+    Path((job_id, stage)): Path<(i64, String)>,
+) -> Result<Response, (StatusCode, String)> {
+    require_admin_token(&config, &headers)?;
+
+    if !is_calldata_stage(&stage) {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            format!(
+                "Invalid stage: '{}'. Expected initial, step<n> or final",
+                stage
+            ),
+        ));
+    }
+
+    let job = fetch_proof_job_by_job_id(&pool, job_id)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+        .ok_or((
+            StatusCode::NOT_FOUND,
+            format!("Proof job {} not found", job_id),
+        ))?;
+
+    let not_found = || {
+        (
+            StatusCode::NOT_FOUND,
+            format!("No {} calldata for proof job {}", stage, job_id),
+        )
+    };
+    // A symlink in the directory could otherwise point the file anywhere
+    let calldata_dir = std::path::Path::new(&job.calldata_dir)
+        .canonicalize()
+        .map_err(|_| not_found())?;
+    let file_path = calldata_dir
+        .join(&stage)
+        .canonicalize()
+        .map_err(|_| not_found())?;
+    if !file_path.starts_with(&calldata_dir) {
+        warn!(
+            "Rejected calldata file {:?} of proof job {}: outside of {:?}",
+            file_path, job_id, calldata_dir
+        );
+        return Err(not_found());
+    }
+    if !file_path.is_file() {
+        return Err(not_found());
+    }
+
+    let file = tokio::fs::File::open(&file_path)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    let chunks = stream::try_unfold(file, |mut file| async move {
+        let mut chunk = vec![0; CALLDATA_CHUNK_BYTES];
+        let read = file.read(&mut chunk).await?;
+        if read == 0 {
+            return Ok(None);
+        }
+        chunk.truncate(read);
+        Ok::<_, std::io::Error>(Some((Bytes::from(chunk), file)))
+    });
+
+    Ok((
+        [(CONTENT_TYPE, "text/plain; charset=utf-8")],
+        Body::from_stream(chunks),
+    )
+        .into_response())
+}
+
+/// Estimates the fee of every proof submission job `job_id` has left, without sending any
+pub async fn estimate_proof_job_fees(
+    Extension(pool): Extension<PgPool>,
+    Extension(relayer): Extension<Option<Arc<ProofSubmissionRelayer>>>,
+    Path(job_id): Path<i64>,
+) -> Result<Json<ProofJobFeeEstimatesResponse>, (StatusCode, String)> {
+    let relayer = relayer.ok_or((
+        StatusCode::SERVICE_UNAVAILABLE,
+        "Proof submission relayer is not configured".to_string(),
+    ))?;
+
+    let job = fetch_proof_job_by_job_id(&pool, job_id)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+        .ok_or((
+            StatusCode::NOT_FOUND,
+            format!("Proof job {} not found", job_id),
+        ))?;
+
+    let steps = relayer
+        .estimate_remaining_fees(&job)
+        .await
+        .map_err(|e| match e {
+            ProofSubmissionError::CalldataDirNotFound(_)
+            | ProofSubmissionError::CalldataFileMissing(_)
+            | ProofSubmissionError::InvalidCalldataFormat(_)
+            | ProofSubmissionError::CalldataParseError { .. } => {
+                (StatusCode::UNPROCESSABLE_ENTITY, e.to_string())
+            }
+            e => (StatusCode::BAD_GATEWAY, e.to_string()),
+        })?;
+
+    Ok(Json(ProofJobFeeEstimatesResponse {
+        job_id,
+        total_fee: steps
+            .iter()
+            .fold(0u128, |total, step| total.saturating_add(step.overall_fee)),
+        steps,
+        max_fee_per_step: relayer.max_fee_per_step(),
+    }))
+}
+
+/// Queues a proof job suspended for exceeding its fee budget again, for whatever it has left to
+/// be submitted under the new `max_total_fee`
+pub async fn resume_proof_job_with_budget(
+    Extension(config): Extension<AppConfig>,
+    Extension(relayer): Extension<Option<Arc<ProofSubmissionRelayer>>>,
+    headers: HeaderMap,
+    Path(job_id): Path<i64>,
+    Json(payload): Json<ResumeWithBudgetRequest>,
+) -> Result<Json<ProofJob>, (StatusCode, String)> {
+    require_admin_token(&config, &headers)?;
+    let relayer = relayer.ok_or((
+        StatusCode::SERVICE_UNAVAILABLE,
+        "Proof submission relayer is not configured".to_string(),
+    ))?;
+
+    let job = relayer
+        .resume_with_fee_budget(job_id as u64, payload.max_total_fee)
+        .await
+        .map_err(|e| match e {
+            ProofSubmissionError::ProofJobNotFound(_) => (StatusCode::NOT_FOUND, e.to_string()),
+            ProofSubmissionError::ProofJobNotSuspended { .. }
+            | ProofSubmissionError::CalldataModified { .. } => {
+                (StatusCode::CONFLICT, e.to_string())
+            }
+            ProofSubmissionError::CalldataDirNotFound(_)
+            | ProofSubmissionError::CalldataFileMissing(_)
+            | ProofSubmissionError::InvalidCalldataFormat(_)
+            | ProofSubmissionError::CalldataParseError { .. } => {
+                (StatusCode::UNPROCESSABLE_ENTITY, e.to_string())
+            }
+            e => (StatusCode::BAD_GATEWAY, e.to_string()),
+        })?;
+
+    Ok(Json(job))
+}
+
+/// Deep-merges a JSON object into a proof job's metadata: nested objects are merged, any other
+/// value is overwritten. Returns the merged metadata.
+pub async fn patch_proof_job_metadata(
+    Extension(pool): Extension<PgPool>,
+    Extension(config): Extension<AppConfig>,
+    headers: HeaderMap,
+    Path(job_id): Path<i64>,
+    Json(patch): Json<serde_json::Value>,
+) -> Result<Json<serde_json::Value>, (StatusCode, String)> {
+    require_admin_token(&config, &headers)?;
+    if !patch.is_object() {
+        return Err((
+            StatusCode::UNPROCESSABLE_ENTITY,
+            "Metadata patch must be a JSON object".to_string(),
+        ));
+    }
+
+    let metadata = merge_proof_job_metadata(&pool, job_id, patch)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+        .ok_or((
+            StatusCode::NOT_FOUND,
+            format!("Proof job {} not found", job_id),
+        ))?;
+
+    Ok(Json(metadata))
+}
+
+/// Longest `GET /block-trackers` waits for each chain's block number
+const CHAIN_TIP_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Latest block of each chain, `None` where its RPC could not be reached
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ChainTips {
+    pub ethereum: Option<u64>,
+    pub starknet: Option<u64>,
+}
+
+impl ChainTips {
+    /// Looks up both chain tips concurrently; a missing URL leaves that tip unknown
+    pub async fn fetch(ethereum_rpc_url: Option<&str>, starknet_rpc_url: Option<&str>) -> Self {
+        let (ethereum, starknet) = tokio::join!(
+            async {
+                match ethereum_rpc_url {
+                    Some(url) => ethereum_tip(url).await,
+                    None => None,
+                }
+            },
+            async {
+                match starknet_rpc_url {
+                    Some(url) => starknet_tip(url).await,
+                    None => None,
+                }
+            }
+        );
+        Self { ethereum, starknet }
+    }
+
+    /// Blocks `tracker` is behind its chain's tip, or -1 when that tip is unknown.
+    ///
+    /// `l1_` keys track Ethereum and `l2_` keys track Starknet.
+    pub fn lag(&self, tracker: &BlockTrackerRow) -> i64 {
+        let tip = if tracker.key.starts_with("l1_") {
+            self.ethereum
+        } else if tracker.key.starts_with("l2_") {
+            self.starknet
+        } else {
+            None
+        };
+        tip.map_or(-1, |tip| (tip as i64 - tracker.last_block).max(0))
+    }
+}
+
+async fn ethereum_tip(rpc_url: &str) -> Option<u64> {
+    let client = ClientBuilder::default().http(Url::parse(rpc_url).ok()?);
+    match timeout(CHAIN_TIP_TIMEOUT, ethereum_block_number(&client)).await {
+        Ok(Ok(block)) => Some(block),
+        Ok(Err(e)) => {
+            warn!("Failed to fetch the Ethereum block number: {}", e);
+            None
+        }
+        Err(_) => {
+            warn!("Timed out fetching the Ethereum block number");
+            None
+        }
+    }
+}
+
+async fn starknet_tip(rpc_url: &str) -> Option<u64> {
+    let provider = JsonRpcClient::new(HttpTransport::new(Url::parse(rpc_url).ok()?));
+    match timeout(CHAIN_TIP_TIMEOUT, provider.block_number()).await {
+        Ok(Ok(block)) => Some(block),
+        Ok(Err(e)) => {
+            warn!("Failed to fetch the Starknet block number: {}", e);
+            None
+        }
+        Err(_) => {
+            warn!("Timed out fetching the Starknet block number");
+            None
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BlockTrackerStatus {
+    pub key: String,
+    pub last_block: i64,
+    pub updated_at: DateTime<Utc>,
+    /// Blocks behind the chain tip, or -1 when the chain's RPC is unavailable
+    pub lag: i64,
+}
+
+impl BlockTrackerStatus {
+    pub fn new(tracker: BlockTrackerRow, tips: &ChainTips) -> Self {
+        Self {
+            lag: tips.lag(&tracker),
+            key: tracker.key,
+            last_block: tracker.last_block,
+            updated_at: tracker.updated_at,
+        }
+    }
+}
+
+/// Lists each event watcher's last processed block and how far it trails the chain tip
+pub async fn get_block_trackers(
+    Extension(pool): Extension<PgPool>,
+) -> Result<Json<Vec<BlockTrackerStatus>>, (StatusCode, String)> {
+    let trackers = list_block_trackers(&pool)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    // Read directly rather than through the config, whose getters panic when the URL is unset
+    let ethereum_rpc_url = std::env::var("ETHEREUM_RPC_URL").ok();
+    let starknet_rpc_url = std::env::var("STARKNET_RPC_URL").ok();
+    let tips = ChainTips::fetch(ethereum_rpc_url.as_deref(), starknet_rpc_url.as_deref()).await;
+
+    Ok(Json(
+        trackers
+            .into_iter()
+            .map(|tracker| BlockTrackerStatus::new(tracker, &tips))
+            .collect(),
+    ))
+}
+
+pub async fn get_dead_letter_l2(
+    Extension(pool): Extension<PgPool>,
+    Query(params): Query<DeadLetterQuery>,
+) -> Result<Json<Vec<DeadLetterL2Transaction>>, (StatusCode, String)> {
+    let limit = params.limit.unwrap_or(DEFAULT_DEAD_LETTER_LIMIT);
+    if !(1..=MAX_DEAD_LETTER_LIMIT).contains(&limit) {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            format!("'limit' must be between 1 and {}", MAX_DEAD_LETTER_LIMIT),
+        ));
+    }
+
+    let entries = fetch_dead_letter_l2_transactions(&pool, limit)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    Ok(Json(entries))
+}
+
+pub async fn requeue_dead_letter_l2(
+    Extension(pool): Extension<PgPool>,
+    Extension(config): Extension<AppConfig>,
+    headers: HeaderMap,
+    Path(id): Path<i64>,
+) -> Result<Json<RequeueResponse>, (StatusCode, String)> {
+    require_admin_token(&config, &headers)?;
+
+    let l2_transaction_id = requeue_dead_letter_l2_transaction(&pool, id)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+        .ok_or((
+            StatusCode::NOT_FOUND,
+            format!("Dead-letter entry {} not found or already requeued", id),
+        ))?;
+
+    Ok(Json(RequeueResponse { l2_transaction_id }))
+}
+
+/// Longest `GET /admin/snapshot` may spend querying the database
+const SNAPSHOT_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Most queued and most processing proof jobs included in a snapshot
+const SNAPSHOT_PROOF_JOB_LIMIT: i64 = 100;
+
+/// Point-in-time view of the sequencer's in-flight state, for diagnosing production issues
+#[derive(Debug, Serialize)]
+pub struct SequencerStateSnapshot {
+    pub timestamp: DateTime<Utc>,
+    pub pending_deposits_count: i64,
+    pub pending_withdrawals_count: i64,
+    pub l2_transactions_by_status: BTreeMap<String, i64>,
+    pub block_trackers: Vec<BlockTrackerRow>,
+    /// Queued and processing proof jobs
+    pub active_proof_jobs: Vec<ProofJob>,
+    /// The running configuration with secrets redacted
+    pub config_summary: serde_json::Value,
+}
+
+impl SequencerStateSnapshot {
+    /// Runs each query concurrently
+    async fn collect(pool: &PgPool, config: &AppConfig) -> Result<Self, sqlx::Error> {
+        let active_jobs = |status: &str| {
+            list_proof_jobs(
+                pool,
+                ProofJobFilter {
+                    status: Some(status.to_string()),
+                    created_after: None,
+                    created_before: None,
+                    limit: SNAPSHOT_PROOF_JOB_LIMIT,
+                },
+            )
+        };
+
+        let (
+            pending_deposits_count,
+            pending_withdrawals_count,
+            l2_transactions_by_status,
+            block_trackers,
+            processing_jobs,
+            queued_jobs,
+        ) = tokio::try_join!(
+            count_pending_deposits(pool),
+            count_pending_withdrawals(pool),
+            count_l2_transactions_by_status(pool),
+            list_block_trackers(pool),
+            active_jobs("processing"),
+            active_jobs("queued"),
+        )?;
+
+        Ok(Self {
+            timestamp: Utc::now(),
+            pending_deposits_count,
+            pending_withdrawals_count,
+            l2_transactions_by_status,
+            block_trackers,
+            active_proof_jobs: processing_jobs.into_iter().chain(queued_jobs).collect(),
+            config_summary: serde_json::to_value(config).unwrap_or_default(),
+        })
+    }
+}
+
+pub async fn get_admin_snapshot(
+    Extension(pool): Extension<PgPool>,
+    Extension(config): Extension<AppConfig>,
+    headers: HeaderMap,
+) -> Result<Json<SequencerStateSnapshot>, (StatusCode, String)> {
+    require_admin_token(&config, &headers)?;
+
+    timeout(
+        SNAPSHOT_TIMEOUT,
+        SequencerStateSnapshot::collect(&pool, &config),
+    )
+    .await
+    .map_err(|_| {
+        (
+            StatusCode::GATEWAY_TIMEOUT,
+            format!("Snapshot queries took longer than {:?}", SNAPSHOT_TIMEOUT),
+        )
+    })?
+    .map(Json)
+    .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))
+}
+
+/// Re-reads the config file and publishes it to running components, which apply it on their
+/// next cycle. An invalid file is rejected with 422 and the current config stays in effect.
+pub async fn reload_config(
+    Extension(config_updates): Extension<ConfigWatcher>,
+    headers: HeaderMap,
+) -> Result<Json<AppConfig>, (StatusCode, String)> {
+    require_admin_token(&config_updates.latest(), &headers)?;
+
+    config_updates.reload().map(Json).map_err(|e| match e {
+        ConfigReloadError::NoConfigPath => (StatusCode::SERVICE_UNAVAILABLE, e.to_string()),
+        ConfigReloadError::Load { .. } | ConfigReloadError::Invalid(_) => {
+            (StatusCode::UNPROCESSABLE_ENTITY, e.to_string())
+        }
+    })
+}
+
+/// Reject the request unless it carries the configured admin token
+fn require_admin_token(
+    config: &AppConfig,
+    headers: &HeaderMap,
+) -> Result<(), (StatusCode, String)> {
+    let expected = config.server.get_admin_token().ok_or((
+        StatusCode::FORBIDDEN,
+        "Admin endpoints are disabled".to_string(),
+    ))?;
+
+    match headers
+        .get(ADMIN_TOKEN_HEADER)
+        .and_then(|value| value.to_str().ok())
+    {
+        Some(token) if token == expected => Ok(()),
+        _ => Err((
+            StatusCode::UNAUTHORIZED,
+            "Missing or invalid admin token".to_string(),
+        )),
+    }
+}
+
+pub async fn cleanup_proof_jobs(
+    Extension(pool): Extension<PgPool>,
+    Extension(config): Extension<AppConfig>,
+    headers: HeaderMap,
+    Query(params): Query<CleanupProofJobsQuery>,
+) -> Result<Json<CleanupProofJobsResponse>, (StatusCode, String)> {
+    require_admin_token(&config, &headers)?;
+
+    let retention_days = params
+        .retention_days
+        .or(config.proof.retention_days)
+        .unwrap_or(0);
+    if retention_days == 0 {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            "'retention_days' must be greater than 0".to_string(),
+        ));
+    }
+
+    let archived = cleanup_old_proof_jobs(&pool, retention_days)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    Ok(Json(CleanupProofJobsResponse { archived }))
+}
+
+/// Requeues failed L2 transactions selected by id and/or age, e.g. after an upstream outage
+pub async fn requeue_failed_l2(
+    Extension(pool): Extension<PgPool>,
+    Extension(config): Extension<AppConfig>,
+    headers: HeaderMap,
+    Json(request): Json<RequeueFailedRequest>,
+) -> Result<Json<RequeueFailedResponse>, (StatusCode, String)> {
+    require_admin_token(&config, &headers)?;
+
+    // Requeueing every failed transaction ever is never what a bare request means
+    if request.ids.is_none() && request.max_age_hours.is_none() {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            "Either 'ids' or 'max_age_hours' is required".to_string(),
+        ));
+    }
+
+    let requeued = requeue_failed_l2_transactions(&pool, request.ids, request.max_age_hours)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    Ok(Json(RequeueFailedResponse { requeued }))
+}
+
+/// Invalidates the unprocessed deposits recorded from blocks a deep reorg replaced, see
+/// `reset_deposits_after_block`
+pub async fn reset_deposits_after_reorg(
+    Extension(pool): Extension<PgPool>,
+    Extension(config): Extension<AppConfig>,
+    headers: HeaderMap,
+    Json(request): Json<ResetDepositsAfterReorgRequest>,
+) -> Result<Json<ResetDepositsAfterReorgResponse>, (StatusCode, String)> {
+    require_admin_token(&config, &headers)?;
+
+    let affected = reset_deposits_after_block(&pool, request.from_block)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    warn!(
+        "Invalidated {} deposits from L1 block {} onwards after a reorg",
+        affected, request.from_block
+    );
+    Ok(Json(ResetDepositsAfterReorgResponse { affected }))
+}
+
+pub async fn get_withdrawal_commitments(
+    Extension(pool): Extension<PgPool>,
+    Query(params): Query<WithdrawalCommitmentsQuery>,
+) -> Result<Json<Vec<WithdrawalCommitmentLog>>, (StatusCode, String)> {
+    if let (Some(from_block), Some(to_block)) = (params.from_block, params.to_block) {
+        if from_block > to_block {
+            return Err((
+                StatusCode::BAD_REQUEST,
+                "'from_block' must not be greater than 'to_block'".to_string(),
+            ));
+        }
+    }
+
+    let logs = fetch_withdrawal_commitment_logs(
+        &pool,
+        params.from_block.map(|b| b as i64),
+        params.to_block.map(|b| b as i64),
+    )
+    .await
+    .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    Ok(Json(logs))
+}
+
+/// Error `GET /health` reports when the database cannot be queried
+const HEALTH_DATABASE_ERROR: &str = "Database unavailable";
+
+/// Reports `ok` while the database is reachable and this instance's heartbeat is fresh, and
+/// `degraded` (503) otherwise.
+pub async fn health_check(
+    Extension(pool): Extension<PgPool>,
+    Extension(config): Extension<AppConfig>,
+) -> (StatusCode, Json<serde_json::Value>) {
+    let instance_id = config.server.get_instance_id();
+
+    // The endpoint is public, so failures are only detailed in the logs
+    if let Err(e) = ping_database(&pool).await {
+        warn!("Health check could not reach the database: {}", e);
+        return (
+            StatusCode::SERVICE_UNAVAILABLE,
+            Json(json!({
+                "status": "degraded",
+                "instance_id": instance_id,
+                "error": HEALTH_DATABASE_ERROR,
+            })),
+        );
+    }
+
+    match fetch_heartbeat_status(&pool, &instance_id).await {
+        Ok(Some(heartbeat)) if heartbeat.is_alive => (
+            StatusCode::OK,
+            Json(json!({
+                "status": "ok",
+                "instance_id": instance_id,
+                "last_seen_at": heartbeat.last_seen_at,
+            })),
+        ),
+        Ok(heartbeat) => (
+            StatusCode::SERVICE_UNAVAILABLE,
+            Json(json!({
+                "status": "degraded",
+                "instance_id": instance_id,
+                "last_seen_at": heartbeat.map(|h| h.last_seen_at),
+            })),
+        ),
+        Err(e) => {
+            warn!("Health check could not read the heartbeat: {}", e);
+            (
+                StatusCode::SERVICE_UNAVAILABLE,
+                Json(json!({
+                    "status": "degraded",
+                    "instance_id": instance_id,
+                    "error": HEALTH_DATABASE_ERROR,
+                })),
+            )
+        }
+    }
+}
+
+pub async fn hello_world(
+    Extension(_): Extension<PgPool>,
+) -> Result<Json<serde_json::Value>, (StatusCode, String)> {
+    Ok(Json(json!({
+        "message": "hello world from zeroxbridge"
+    })))
+}
+
+/// Computes a Poseidon commitment hash for deposit transactions
+///
+/// This endpoint allows users to generate the same hash that the L2 contract
+/// will compute and verify using Cairo-native Poseidon logic. Users should call
+/// this endpoint before depositing to L1.
+///
+/// The hash is computed using the following fields:
+/// - recipient: Starknet address of the receiver
+/// - amount: USD amount to mint
+/// - nonce: Transaction nonce
+/// - timestamp: Block timestamp
+///
+/// Layouts using Blake2s instead of Poseidon can select it with `"algorithm": "blake2s"`.
+///
+/// Returns the commitment hash that should be used when making the deposit.
+pub async fn compute_commitment_hash(
+    Json(payload): Json<CommitmentHashRequest>,
+) -> Result<Json<CommitmentHashResponse>, (StatusCode, String)> {
+    // Parse recipient address as Felt (felt252)
+    let recipient = &payload.recipient;
+    let recipient_hex = recipient.strip_prefix("0x").unwrap_or(recipient);
+    let recipient_felt = match parse_felt_in_range(recipient_hex, 16) {
+        Ok(felt) => felt,
+        Err(e @ FeltRangeError::OutOfRange { .. }) => {
+            return Err((StatusCode::UNPROCESSABLE_ENTITY, format!("Invalid recipient: {}", e)))
+        }
+        Err(FeltRangeError::Malformed { .. }) => return Err((StatusCode::BAD_REQUEST,
+            "Invalid recipient address format. Must be a valid Starknet address in hex format (0x...).".
+            to_string())
+        ),
+    };
+
+    // Any u128 fits in a felt252, but checking the field order first names the real problem
+    // for amounts past it
+    parse_felt_in_range(&payload.amount, 10).map_err(|e| {
+        (
+            StatusCode::UNPROCESSABLE_ENTITY,
+            format!("Invalid amount: {}", e),
+        )
+    })?;
+    let amount: u128 = payload.amount.parse().map_err(|_| {
+        (
+            StatusCode::UNPROCESSABLE_ENTITY,
+            format!(
+                "Invalid amount: {} exceeds the u128 maximum of {}",
+                payload.amount,
+                u128::MAX
+            ),
+        )
+    })?;
+
+    // Determine which hash method to use (default to sequential pairwise which is more common in Cairo contracts)
+    // "batch" and "sequential" are still accepted for older clients
+    let method = match payload.algorithm.as_deref() {
+        Some("poseidon_batch") | Some("batch") => HashMethod::BatchHash,
+        Some("poseidon_sequential") | Some("sequential") | None => HashMethod::SequentialPairwise,
+        Some("blake2s") => HashMethod::Blake2s,
+        Some(algorithm) => {
+            return Err((
+                StatusCode::BAD_REQUEST,
+                format!(
+                    "Invalid algorithm: '{}'. Valid options are 'poseidon_batch', 'poseidon_sequential' or 'blake2s'",
+                    algorithm
+                ),
+            ))
+        }
+    };
+
+    // Compute the commitment hash using the utility function
+    let hash = compute_poseidon_commitment_hash(
+        recipient_felt,
+        amount,
+        payload.nonce,
+        payload.timestamp,
+        method,
+        payload.domain,
+    );
+
+    // Convert hash to hex string format
+    let hash_hex = format!("0x{:x}", hash);
+
+    Ok(Json(CommitmentHashResponse {
+        commitment_hash: hash_hex,
+    }))
+}
+
+pub async fn compute_fact_hash(
+    Json(payload): Json<FactHashRequest>,
+) -> Result<Json<FactHashResponse>, (StatusCode, String)> {
+    let program_hash = Felt::from_hex(&payload.program_hash).map_err(|_| {
+        (
+            StatusCode::BAD_REQUEST,
+            "Invalid program_hash. Must be a felt in hex format (0x...).".to_string(),
+        )
+    })?;
+    let output_hash = Felt::from_hex(&payload.output_hash).map_err(|_| {
+        (
+            StatusCode::BAD_REQUEST,
+            "Invalid output_hash. Must be a felt in hex format (0x...).".to_string(),
+        )
+    })?;
+
+    let fact_hash = calculate_fact_hash(program_hash, output_hash);
+
+    Ok(Json(FactHashResponse {
+        fact_hash: format!("0x{:x}", fact_hash),
+    }))
+}
+
+pub async fn compute_hash_handler(
+    format: ContentFormat,
+    FlexibleBody(payload): FlexibleBody<HashRequest>,
+) -> Result<FlexibleResponse<HashResponse>, impl IntoResponse> {
+    // Validate the Starknet public key format before hashing
+    let caller = match parse_stark_pub_key(&payload.stark_pubkey) {
+        Ok(caller) => canonical_felt_hex(caller),
+        Err(e) => {
+            let error_response = ErrorResponse {
+                error: "Invalid stark_pubkey".to_string(),
+                details: Some(e.message),
+            };
+            return Err((StatusCode::BAD_REQUEST, Json(error_response)));
+        }
+    };
+    let burn_data = BurnData {
+        caller,
+        amount: payload.usd_val,
+        nonce: payload.nonce,
+        time_stamp: payload.timestamp,
+    };
+    // Compute the commitment hash
+    let hex_hash = burn_data.hash_to_hex_string();
+    // Create response
+    let response = HashResponse {
         commitment_hash: hex_hash,
         input_data: InputData {
             stark_pubkey: burn_data.caller,
@@ -246,5 +2101,134 @@ pub async fn compute_hash_handler(
             timestamp: burn_data.time_stamp,
         },
     };
-    Ok(Json(response))
+    Ok(FlexibleResponse::new(format, response))
+}
+
+/// Simulates the Starknet relay of a withdrawal without sending it
+pub async fn simulate_relay(
+    Extension(relayer): Extension<Option<Arc<StarknetRelayer>>>,
+    Json(payload): Json<SimulateRelayRequest>,
+) -> Result<Json<SimulationResult>, (StatusCode, String)> {
+    let relayer = relayer.ok_or((
+        StatusCode::SERVICE_UNAVAILABLE,
+        "Starknet relayer is not configured".to_string(),
+    ))?;
+
+    let calls = relayer
+        .build_relay_calls(payload.transaction_id, &payload.proof_data.to_string())
+        .map_err(|e| (StatusCode::BAD_REQUEST, e.to_string()))?;
+
+    let result = relayer
+        .simulate_transaction(calls)
+        .await
+        .map_err(|e| (StatusCode::BAD_GATEWAY, e.to_string()))?;
+
+    Ok(Json(result))
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CreateUserMappingRequest {
+    /// Stark public key the signature is checked against
+    pub starknet_address: String,
+    pub ethereum_address: String,
+    /// `[r, s]` of the Stark key's signature over `ethereum_address_message` for this
+    /// sequencer's Starknet chain and L2 bridge
+    pub signature: Vec<String>,
+}
+
+/// Error returned when `starknet_address` is not a felt
+const INVALID_STARKNET_ADDRESS: &str =
+    "starknet_address must be a valid felt252 hex or decimal value";
+
+/// Maps a Stark key to an Ethereum address once the key's signature over that address checks out
+pub async fn create_user_mapping(
+    Extension(pool): Extension<PgPool>,
+    Extension(config): Extension<AppConfig>,
+    Json(payload): Json<CreateUserMappingRequest>,
+) -> Result<(StatusCode, Json<UserMapping>), (StatusCode, String)> {
+    let unprocessable = |message: &str| (StatusCode::UNPROCESSABLE_ENTITY, message.to_string());
+
+    let starknet_address = normalize_felt_hex(&payload.starknet_address)
+        .map_err(|_| unprocessable(INVALID_STARKNET_ADDRESS))?;
+    let stark_pub_key =
+        Felt::from_hex(&starknet_address).map_err(|_| unprocessable(INVALID_STARKNET_ADDRESS))?;
+    let ethereum_address = payload
+        .ethereum_address
+        .trim()
+        .parse::<Address>()
+        .map_err(|_| unprocessable("ethereum_address must be a 20-byte hex address"))?;
+    let (r, s) = match payload.signature.as_slice() {
+        [r, s] => (Felt::from_hex(r), Felt::from_hex(s)),
+        _ => return Err(unprocessable("signature must be an [r, s] pair")),
+    };
+    let (Ok(r), Ok(s)) = (r, s) else {
+        return Err(unprocessable("signature values must be hex felts"));
+    };
+
+    let (Ok(chain_id), Ok(bridge_address)) = (
+        Felt::from_hex(&config.starknet.chain_id),
+        Felt::from_hex(&config.contracts.l2_contract_address),
+    ) else {
+        return Err((
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "starknet.chain_id and contracts.l2_contract_address must be felts".to_string(),
+        ));
+    };
+
+    if !verify_ethereum_address_signature(
+        &stark_pub_key,
+        &chain_id,
+        &bridge_address,
+        &ethereum_address,
+        &r,
+        &s,
+    ) {
+        return Err((
+            StatusCode::UNAUTHORIZED,
+            "signature does not prove ownership of starknet_address".to_string(),
+        ));
+    }
+
+    let mapping = insert_user_mapping(
+        &pool,
+        &starknet_address,
+        &format!("0x{}", hex::encode(ethereum_address)),
+    )
+    .await
+    .map_err(|e| {
+        if e.as_database_error()
+            .is_some_and(|db_error| db_error.is_unique_violation())
+        {
+            (
+                StatusCode::CONFLICT,
+                "starknet_address or ethereum_address is already mapped".to_string(),
+            )
+        } else {
+            (StatusCode::INTERNAL_SERVER_ERROR, e.to_string())
+        }
+    })?;
+
+    Ok((StatusCode::CREATED, Json(mapping)))
+}
+
+pub async fn get_user_mapping(
+    Extension(pool): Extension<PgPool>,
+    Path(starknet_address): Path<String>,
+) -> Result<Json<UserMapping>, (StatusCode, String)> {
+    let starknet_address = normalize_felt_hex(&starknet_address).map_err(|_| {
+        (
+            StatusCode::UNPROCESSABLE_ENTITY,
+            INVALID_STARKNET_ADDRESS.to_string(),
+        )
+    })?;
+
+    let mapping = fetch_user_mapping(&pool, &starknet_address)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+        .ok_or((
+            StatusCode::NOT_FOUND,
+            format!("No Ethereum address mapped to {}", starknet_address),
+        ))?;
+
+    Ok(Json(mapping))
 }
@@ -1,2 +1,9 @@
+pub mod content;
+pub mod error;
 pub mod handlers;
+pub mod request_id;
 pub mod routes;
+
+/// Version reported in the `X-API-Version` header of every response; a major bump signals a
+/// breaking change
+pub const API_VERSION: &str = env!("CARGO_PKG_VERSION");
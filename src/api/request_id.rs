@@ -0,0 +1,84 @@
+use axum::{
+    body::Body,
+    http::{HeaderName, HeaderValue, Request},
+    response::Response,
+};
+use futures_util::future::BoxFuture;
+use std::fmt;
+use std::task::{Context, Poll};
+use tower::{Layer, Service};
+use tracing::{info_span, Instrument};
+use uuid::Uuid;
+
+pub const X_REQUEST_ID: HeaderName = HeaderName::from_static("x-request-id");
+
+/// Identifies one API request in logs and in its `X-Request-Id` response header; read it in a
+/// handler with `Extension(request_id): Extension<RequestId>`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RequestId(pub String);
+
+impl fmt::Display for RequestId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+/// Gives every request a `RequestId`, reusing the caller's `X-Request-Id` so traces can be
+/// followed across services, and runs the rest of the stack in a span carrying it
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RequestIdLayer;
+
+impl<S> Layer<S> for RequestIdLayer {
+    type Service = RequestIdService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        RequestIdService { inner }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct RequestIdService<S> {
+    inner: S,
+}
+
+impl<S> Service<Request<Body>> for RequestIdService<S>
+where
+    S: Service<Request<Body>, Response = Response> + Send + 'static,
+    S::Future: Send + 'static,
+{
+    type Response = Response;
+    type Error = S::Error;
+    type Future = BoxFuture<'static, Result<Response, S::Error>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, mut request: Request<Body>) -> Self::Future {
+        let header = request
+            .headers()
+            .get(&X_REQUEST_ID)
+            .filter(|value| !value.is_empty() && value.to_str().is_ok())
+            .cloned();
+        let header = header.unwrap_or_else(|| {
+            HeaderValue::from_str(&Uuid::new_v4().to_string())
+                .expect("a UUID is a valid header value")
+        });
+        let request_id = RequestId(header.to_str().unwrap_or_default().to_string());
+
+        let span = info_span!(
+            "request",
+            request_id = %request_id,
+            method = %request.method(),
+            uri = %request.uri(),
+        );
+        request.extensions_mut().insert(request_id);
+
+        let response = self.inner.call(request).instrument(span);
+        Box::pin(async move {
+            let mut response = response.await?;
+            response.headers_mut().insert(X_REQUEST_ID, header);
+            Ok(response)
+        })
+    }
+}
@@ -1,36 +1,336 @@
-use crate::{api::handlers::hello_world, config::AppConfig};
+use crate::{
+    api::{handlers::hello_world, request_id::RequestIdLayer, API_VERSION},
+    config::AppConfig,
+    db::{
+        database::{
+            count_l2_transactions_by_status, count_pending_deposits, count_processed_deposits,
+            BlockTracker,
+        },
+        redis_block_tracker::RedisBlockTracker,
+    },
+    events::{CommitmentHashRegistry, CommitmentLog, ConfigWatcher, EventBus, MerkleRootWatcher},
+    merkle::{DepositTree, WithdrawalTree},
+    relayer::{
+        ethereum_relayer::CompletedWithdrawalEvent, proof_submission::ProofSubmissionRelayer,
+        starknet_relayer::StarknetRelayer,
+    },
+    workers::registry::ServiceRegistry,
+};
 use axum::{
-    routing::{get, post},
+    extract::DefaultBodyLimit,
+    http::{header::CONTENT_TYPE, HeaderName, HeaderValue, Method},
+    routing::{get, patch, post, put},
     Extension, Router,
 };
 use sqlx::PgPool;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::{broadcast, watch};
+use tower_http::cors::{AllowOrigin, CorsLayer};
+use tower_http::limit::RequestBodyLimitLayer;
+use tower_http::set_header::SetResponseHeaderLayer;
+use tracing::{error, info, warn};
 
 use crate::api::handlers::{
-    compute_poseidon_hash, create_withdrawal, get_pending_withdrawals, handle_deposit_post,
-    handle_get_pending_deposits, compute_hash_handler,
+    compute_commitment_hash, create_withdrawal, get_pending_withdrawals, handle_deposit_post,
+    handle_get_pending_deposits, compute_hash_handler, stream_l2_events, get_proof_jobs,
+    get_allowed_tokens, compute_fact_hash, health_check, get_withdrawal_commitments,
+    cleanup_proof_jobs, get_deposit_proof, get_dead_letter_l2, requeue_dead_letter_l2,
+    get_proof_job, bulk_create_deposits, simulate_relay, get_block_trackers,
+    stream_withdrawal_completions, get_admin_snapshot, get_withdrawal_proof_status,
+    get_proof_job_handoff, import_proof_job_handoff, reload_config, estimate_proof_job_fees,
+    get_proof_job_calldata, create_user_mapping, get_user_mapping, get_merkle_checkpoint,
+    restore_merkle_checkpoint, resume_proof_job_with_budget, get_withdrawal_timeline,
+    patch_proof_job_metadata, get_withdrawal, get_gas_metrics, requeue_failed_l2,
+    get_proof_job_transactions, get_oracle_tvl, get_withdrawals, get_user_withdrawals,
+    reset_deposits_after_reorg, get_proof_job_pipeline, get_user_next_nonce,
 };
 
+pub const X_API_VERSION: HeaderName = HeaderName::from_static("x-api-version");
+pub const X_DEPRECATED: HeaderName = HeaderName::from_static("x-deprecated");
+
 #[derive(Clone)]
 pub struct AppState {
     pub db: PgPool,
     pub config: AppConfig,
+    /// Publishes reloaded configs; backs `PUT /admin/config/reload`
+    pub config_updates: ConfigWatcher,
+    pub l2_event_bus: EventBus<CommitmentLog>,
+    /// Pass to `EthereumRelayer::with_completion_bus` to feed `GET /events/completions`
+    pub withdrawal_completions: EventBus<CompletedWithdrawalEvent>,
+    pub merkle_root: MerkleRootWatcher,
+    pub deposit_tree: DepositTree,
+    /// Checkpointed and restored through `GET`/`POST /merkle/checkpoint`
+    pub withdrawal_tree: WithdrawalTree,
+    /// Share between `L1Queue::with_commitment_registry` and the L1 event watcher
+    pub commitment_registry: CommitmentHashRegistry,
+    /// Pass to `L2EventWatcher::with_block_tracker`; kept in Postgres unless `with_redis` is used
+    pub block_tracker: BlockTracker,
+    /// Backs `POST /relayer/simulate`, which answers 503 when unset
+    pub starknet_relayer: Option<Arc<StarknetRelayer>>,
+    /// Backs `POST /proof-jobs/{job_id}/estimate-fees`, which answers 503 when unset
+    pub proof_relayer: Option<Arc<ProofSubmissionRelayer>>,
+    /// Background tasks stopped by `shutdown_services`
+    pub services: ServiceRegistry,
+}
+
+/// Queue totals logged when the sequencer shuts down
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FinalStats {
+    /// Deposits and L2 transactions still waiting in the L1 and L2 queues
+    pub queue_depth: i64,
+    /// Deposits processed plus L2 transactions completed
+    pub processed_transactions: i64,
+}
+
+/// Outcome of `AppState::shutdown_services`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ShutdownReport {
+    /// Tasks aborted because they outlived the timeout
+    pub timed_out: Vec<String>,
+    /// `None` when the stats could not be read from the database
+    pub stats: Option<FinalStats>,
+}
+
+impl AppState {
+    pub fn new(db: PgPool, config: AppConfig) -> Self {
+        let merkle_root = MerkleRootWatcher::default();
+        let deposit_tree = DepositTree::with_root_notifier(merkle_root.sender());
+        let commitment_registry = CommitmentHashRegistry::new(Duration::from_secs(
+            config.queue.commitment_cache_ttl_seconds,
+        ));
+        Self {
+            block_tracker: BlockTracker::new(db.clone()),
+            db,
+            config_updates: ConfigWatcher::new(config.clone()),
+            config,
+            l2_event_bus: EventBus::default(),
+            withdrawal_completions: EventBus::default(),
+            merkle_root,
+            deposit_tree,
+            withdrawal_tree: WithdrawalTree::new(),
+            commitment_registry,
+            starknet_relayer: None,
+            proof_relayer: None,
+            services: ServiceRegistry::default(),
+        }
+    }
+
+    pub fn with_starknet_relayer(mut self, relayer: Arc<StarknetRelayer>) -> Self {
+        self.starknet_relayer = Some(relayer);
+        self
+    }
+
+    pub fn with_proof_relayer(mut self, relayer: Arc<ProofSubmissionRelayer>) -> Self {
+        self.proof_relayer = Some(relayer);
+        self
+    }
+
+    /// Keeps block trackers in the Redis server at `redis_url`, shared with other instances
+    pub fn with_redis(mut self, redis_url: &str) -> Result<Self, redis::RedisError> {
+        self.block_tracker = BlockTracker::Redis(RedisBlockTracker::new(redis_url)?);
+        Ok(self)
+    }
+
+    /// Reloads `config_updates` from `path` on `PUT /admin/config/reload` and SIGHUP
+    pub fn with_config_path(mut self, path: impl AsRef<std::path::Path>) -> Self {
+        self.config_updates = self.config_updates.with_config_path(path);
+        self
+    }
+
+    /// Receiver that resolves `changed()` whenever the config is reloaded
+    pub fn subscribe_config(&self) -> watch::Receiver<AppConfig> {
+        self.config_updates.subscribe()
+    }
+
+    /// Receiver that resolves `changed()` whenever a new Merkle root is built
+    pub fn subscribe_root(&self) -> watch::Receiver<Option<[u8; 32]>> {
+        self.merkle_root.subscribe()
+    }
+
+    /// Receives a `CompletedWithdrawalEvent` for every withdrawal relayed to Ethereum
+    pub fn subscribe_completions(&self) -> broadcast::Receiver<CompletedWithdrawalEvent> {
+        self.withdrawal_completions.subscribe()
+    }
+
+    /// Stops every registered service, waiting up to `timeout` for them, then logs final stats
+    pub async fn shutdown_services(&self, timeout: Duration) -> ShutdownReport {
+        let timed_out = self.services.shutdown(timeout).await;
+
+        let stats = match self.final_stats().await {
+            Ok(stats) => {
+                info!(
+                    "Final stats: queue depth {}, processed transactions {}",
+                    stats.queue_depth, stats.processed_transactions
+                );
+                Some(stats)
+            }
+            Err(e) => {
+                error!("Failed to read final stats: {:?}", e);
+                None
+            }
+        };
+
+        ShutdownReport { timed_out, stats }
+    }
+
+    async fn final_stats(&self) -> Result<FinalStats, sqlx::Error> {
+        let (pending_deposits, processed_deposits, l2_transactions) = tokio::try_join!(
+            count_pending_deposits(&self.db),
+            count_processed_deposits(&self.db),
+            count_l2_transactions_by_status(&self.db),
+        )?;
+        let l2_count = |status: &str| l2_transactions.get(status).copied().unwrap_or(0);
+
+        Ok(FinalStats {
+            queue_depth: pending_deposits + l2_count("pending"),
+            processed_transactions: processed_deposits + l2_count("completed"),
+        })
+    }
+}
+
+/// CORS policy for `cors_allowed_origins`, or `None` when no origin is allowed
+pub fn cors_layer(allowed_origins: &[String]) -> Option<CorsLayer> {
+    if allowed_origins.is_empty() {
+        return None;
+    }
+    if allowed_origins.iter().any(|origin| origin == "*") {
+        return Some(CorsLayer::permissive());
+    }
+
+    let origins: Vec<HeaderValue> = allowed_origins
+        .iter()
+        .filter_map(|origin| match HeaderValue::from_str(origin) {
+            Ok(value) => Some(value),
+            Err(_) => {
+                warn!("Ignoring invalid CORS origin {:?}", origin);
+                None
+            }
+        })
+        .collect();
+
+    Some(
+        CorsLayer::new()
+            .allow_origin(AllowOrigin::list(origins))
+            .allow_methods([Method::GET, Method::POST])
+            .allow_headers([CONTENT_TYPE]),
+    )
 }
 
-pub fn create_router(pool: PgPool) -> Router {
-    Router::new()
+/// Marks responses of a route as `X-Deprecated: true`; apply it with `route_layer` to routes
+/// that will be removed in the next major API version
+pub fn deprecated_layer() -> SetResponseHeaderLayer<HeaderValue> {
+    SetResponseHeaderLayer::overriding(X_DEPRECATED, HeaderValue::from_static("true"))
+}
+
+pub fn create_router(state: AppState) -> Router {
+    let max_body_bytes = state.config.server.max_body_bytes;
+    let cors = cors_layer(&state.config.server.cors_allowed_origins);
+
+    let router = Router::new()
         .route("/", get(hello_world))
+        .route("/health", get(health_check))
         .route(
             "/deposit",
             post(handle_deposit_post).get(handle_get_pending_deposits),
         )
+        .route("/deposits/bulk", post(bulk_create_deposits))
+        .route("/deposits/{id}/proof", get(get_deposit_proof))
         .route(
             "/withdrawals",
             post(create_withdrawal).get(get_pending_withdrawals),
         )
-        .route("/poseidon/hash", post(compute_poseidon_hash))
+        .route("/v2/withdrawals", get(get_withdrawals))
+        .route("/withdrawals/{id}", get(get_withdrawal))
+        .route(
+            "/withdrawals/{id}/proof-status",
+            get(get_withdrawal_proof_status),
+        )
+        .route("/withdrawals/{id}/timeline", get(get_withdrawal_timeline))
+        .route("/withdrawal-commitments", get(get_withdrawal_commitments))
+        .route("/users/{stark_pub_key}/withdrawals", get(get_user_withdrawals))
+        .route("/users/{stark_pub_key}/next-nonce", get(get_user_next_nonce))
+        .route("/user-mappings", post(create_user_mapping))
+        .route("/user-mappings/{starknet_address}", get(get_user_mapping))
+        .route(
+            "/merkle/checkpoint",
+            get(get_merkle_checkpoint).post(restore_merkle_checkpoint),
+        )
+        .route("/allowed-tokens", get(get_allowed_tokens))
+        .route("/compute-commitment-hash", post(compute_commitment_hash))
         .route(
             "/compute-hash",
             post(compute_hash_handler)
         )
-        .layer(Extension(pool))
+        .route("/fact-hash", post(compute_fact_hash))
+        .route("/l2-events", get(stream_l2_events))
+        .route("/events/completions", get(stream_withdrawal_completions))
+        .route("/proof-jobs", get(get_proof_jobs))
+        .route("/proof-jobs/{job_id}", get(get_proof_job))
+        .route("/proof-jobs/{job_id}/handoff", get(get_proof_job_handoff))
+        .route("/proof-jobs/{job_id}/pipeline", get(get_proof_job_pipeline))
+        .route(
+            "/proof-jobs/{job_id}/transactions",
+            get(get_proof_job_transactions),
+        )
+        .route("/oracle/tvl", get(get_oracle_tvl))
+        .route(
+            "/proof-jobs/{job_id}/calldata/{stage}",
+            get(get_proof_job_calldata),
+        )
+        .route(
+            "/proof-jobs/{job_id}/estimate-fees",
+            post(estimate_proof_job_fees),
+        )
+        .route(
+            "/proof-jobs/{job_id}/resume-with-budget",
+            patch(resume_proof_job_with_budget),
+        )
+        .route(
+            "/proof-jobs/{job_id}/metadata",
+            patch(patch_proof_job_metadata),
+        )
+        .route("/proof-jobs/handoff", post(import_proof_job_handoff))
+        .route("/admin/cleanup-proof-jobs", post(cleanup_proof_jobs))
+        .route("/admin/snapshot", get(get_admin_snapshot))
+        .route("/admin/config/reload", put(reload_config))
+        .route("/admin/relayer/requeue-failed", post(requeue_failed_l2))
+        .route(
+            "/admin/dead-letter/l2/{id}/requeue",
+            post(requeue_dead_letter_l2),
+        )
+        .route(
+            "/admin/deposits/reset-after-reorg",
+            post(reset_deposits_after_reorg),
+        )
+        .route("/block-trackers", get(get_block_trackers))
+        .route("/metrics/gas", get(get_gas_metrics))
+        .route("/dead-letter/l2", get(get_dead_letter_l2))
+        .route("/relayer/simulate", post(simulate_relay))
+        .layer(Extension(state.db))
+        .layer(Extension(state.config))
+        .layer(Extension(state.config_updates))
+        .layer(Extension(state.l2_event_bus))
+        .layer(Extension(state.withdrawal_completions))
+        .layer(Extension(state.deposit_tree))
+        .layer(Extension(state.withdrawal_tree))
+        .layer(Extension(state.starknet_relayer))
+        .layer(Extension(state.proof_relayer))
+        // `server.max_body_bytes` replaces axum's own 2 MB extractor limit
+        .layer(DefaultBodyLimit::disable())
+        .layer(RequestBodyLimitLayer::new(max_body_bytes));
+
+    let router = match cors {
+        Some(cors) => router.layer(cors),
+        None => router,
+    };
+
+    // Outside CORS and the body limit so the requests they reject are traced too
+    let router = router.layer(RequestIdLayer);
+
+    // Outermost, so rejections from the layers above carry the version too
+    router.layer(SetResponseHeaderLayer::overriding(
+        X_API_VERSION,
+        HeaderValue::from_static(API_VERSION),
+    ))
 }
@@ -1,7 +1,15 @@
 use config::{Config, Environment, File};
 use dotenv::dotenv;
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Serialize, Serializer};
+use sqlx::{Connection, PgConnection};
+use starknet::core::types::Felt;
+use starknet::core::utils::get_selector_from_name;
+use std::fmt;
 use std::path::Path;
+use thiserror::Error;
+use url::Url;
+
+use crate::utils::{strip_url_userinfo, SensitiveString};
 
 /// Loads configuration from a given config file or environment variables.
 pub fn load_config(config_file_path: Option<&Path>) -> anyhow::Result<AppConfig> {
@@ -19,10 +27,57 @@ pub fn load_config(config_file_path: Option<&Path>) -> anyhow::Result<AppConfig>
     settings = settings.add_source(Environment::with_prefix("HERODOTUS").separator("__"));
 
     let app_config = settings.build()?.try_deserialize::<AppConfig>()?;
+    // Paths are appended to these, so a malformed one would only fail on the first request
+    app_config.herodotus.herodotus_url()?;
+    app_config.herodotus.atlantic_url()?;
 
     Ok(app_config)
 }
 
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum ConfigError {
+    #[error("{field} is not a valid base URL ({reason}): {url}")]
+    InvalidUrl {
+        field: &'static str,
+        url: String,
+        reason: String,
+    },
+}
+
+/// Parses `value` as an `http`/`https` base URL that paths can be appended to, optionally under
+/// a base path such as `/api`. The returned path always ends in `/`, so `Url::join` appends to
+/// the base path instead of replacing its last segment.
+pub fn parse_base_url(field: &'static str, value: &str) -> Result<Url, ConfigError> {
+    let invalid = |reason: String| ConfigError::InvalidUrl {
+        field,
+        url: value.to_string(),
+        reason,
+    };
+
+    let mut url = Url::parse(value).map_err(|e| invalid(e.to_string()))?;
+    if !matches!(url.scheme(), "http" | "https") {
+        return Err(invalid(format!(
+            "scheme must be http or https, not {}",
+            url.scheme()
+        )));
+    }
+    if url.path().contains("//") {
+        return Err(invalid(format!("empty segment in path {}", url.path())));
+    }
+    if let Some(query) = url.query() {
+        return Err(invalid(format!("unexpected query ?{}", query)));
+    }
+    if let Some(fragment) = url.fragment() {
+        return Err(invalid(format!("unexpected fragment #{}", fragment)));
+    }
+    if !url.path().ends_with('/') {
+        let path = format!("{}/", url.path());
+        url.set_path(&path);
+    }
+
+    Ok(url)
+}
+
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct AppConfig {
     pub contract: ContractConfig,
@@ -37,11 +92,119 @@ pub struct AppConfig {
     pub logging: LoggingConfig,
     pub oracle: OracleConfig,
     pub herodotus: HerodotusConfig,
+    /// Proof job settings; every field falls back to its default when the section is missing
+    #[serde(default)]
+    pub proof: ProofConfig,
+    /// Endpoint notified of withdrawal and proof job state changes; none when unset
+    #[serde(default)]
+    pub webhook: Option<WebhookConfig>,
+}
+
+/// Outgoing webhook POSTed a JSON event on every subscribed state change
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WebhookConfig {
+    pub url: String,
+    /// Key of the HMAC-SHA256 body signature sent in `X-Signature-256`
+    pub secret: SensitiveField,
+    /// Event types sent to the endpoint; every other event is dropped
+    pub events: Vec<WebhookEventType>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WebhookEventType {
+    WithdrawalCompleted,
+    ProofJobCompleted,
+    ProofJobFailed,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct HerodotusConfig {
     pub herodotus_endpoint: String,
+    /// Base URL of the Atlantic proving API
+    #[serde(default = "default_atlantic_endpoint")]
+    pub atlantic_endpoint: String,
+    /// Seconds between proof generation polling cycles
+    #[serde(default = "default_herodotus_poll_interval_seconds")]
+    pub poll_interval_seconds: u64,
+    /// Compiled withdrawal proof program (sierra json)
+    #[serde(default = "default_program_path")]
+    pub program_path: String,
+    /// Directory holding per-withdrawal program inputs at `<id>/input.cairo1.txt`
+    #[serde(default = "default_inputs_dir")]
+    pub inputs_dir: String,
+    /// Retries of a failed Atlantic query submission
+    #[serde(default = "default_atlantic_max_retries")]
+    pub atlantic_max_retries: u32,
+    /// Wait before the first submission retry, doubled for each retry after it
+    #[serde(default = "default_atlantic_retry_delay_ms")]
+    pub atlantic_retry_delay_ms: u64,
+}
+
+impl HerodotusConfig {
+    pub fn herodotus_url(&self) -> Result<Url, ConfigError> {
+        parse_base_url("herodotus.herodotus_endpoint", &self.herodotus_endpoint)
+    }
+
+    pub fn atlantic_url(&self) -> Result<Url, ConfigError> {
+        parse_base_url("herodotus.atlantic_endpoint", &self.atlantic_endpoint)
+    }
+
+    /// `{atlantic_endpoint}/atlantic-query`, where new Atlantic queries are submitted
+    pub fn get_submit_url(&self) -> Result<Url, ConfigError> {
+        self.atlantic_query_url(&[])
+    }
+
+    /// `{atlantic_endpoint}/atlantic-query/{job_id}/status`
+    pub fn get_status_url(&self, job_id: &str) -> Result<Url, ConfigError> {
+        self.atlantic_query_url(&[job_id, "status"])
+    }
+
+    /// `{atlantic_endpoint}/atlantic-query/{job_id}/result`
+    pub fn get_result_url(&self, job_id: &str) -> Result<Url, ConfigError> {
+        self.atlantic_query_url(&[job_id, "result"])
+    }
+
+    /// `atlantic-query` under the Atlantic endpoint, followed by `segments`. Each segment is
+    /// percent-encoded, so a job id can't step out of its own path segment.
+    fn atlantic_query_url(&self, segments: &[&str]) -> Result<Url, ConfigError> {
+        let base = self.atlantic_url()?;
+        let mut url = base
+            .join("atlantic-query")
+            .map_err(|e| ConfigError::InvalidUrl {
+                field: "herodotus.atlantic_endpoint",
+                url: self.atlantic_endpoint.clone(),
+                reason: e.to_string(),
+            })?;
+        url.path_segments_mut()
+            .expect("http(s) URLs have a path")
+            .extend(segments);
+        Ok(url)
+    }
+}
+
+fn default_atlantic_endpoint() -> String {
+    "https://atlantic.api.herodotus.cloud".to_string()
+}
+
+fn default_herodotus_poll_interval_seconds() -> u64 {
+    30
+}
+
+fn default_program_path() -> String {
+    "target/dev/cairo1.sierra.json".to_string()
+}
+
+fn default_inputs_dir() -> String {
+    "proof_inputs".to_string()
+}
+
+fn default_atlantic_max_retries() -> u32 {
+    3
+}
+
+fn default_atlantic_retry_delay_ms() -> u64 {
+    1000
 }
 
 impl HerodotusConfig {
@@ -60,30 +223,208 @@ pub struct ContractConfig {
 pub struct Contracts {
     pub l1_contract_address: String,
     pub l2_contract_address: String,
+    /// Block the L2 contract was deployed in; event queries never start before it
+    #[serde(default)]
+    pub l2_contract_deploy_block: u64,
+    /// Key of the L2 contract's burn event, as a hex selector or the event name to derive it from
+    #[serde(default = "default_l2_burn_event_key")]
+    pub l2_burn_event_key: String,
+    /// Key of the L2 contract's withdrawal hash appended event, as a hex selector or event name
+    #[serde(default = "default_l2_withdrawal_event_key")]
+    pub l2_withdrawal_event_key: String,
+    /// L1 vault contracts (ETH vault, USDC vault, ...) each exposing `get_total_tvl()`, summed
+    /// into the TVL reported to the L2 oracle
+    #[serde(default)]
+    pub l1_tvl_contract_addresses: Vec<String>,
+}
+
+impl Contracts {
+    /// L1 vaults whose TVLs add up to the bridge's, `l1_contract_address` alone unless
+    /// `l1_tvl_contract_addresses` lists them
+    pub fn l1_tvl_contracts(&self) -> Vec<&str> {
+        if self.l1_tvl_contract_addresses.is_empty() {
+            vec![self.l1_contract_address.as_str()]
+        } else {
+            self.l1_tvl_contract_addresses
+                .iter()
+                .map(String::as_str)
+                .collect()
+        }
+    }
+}
+
+/// Burn event key emitted by the deployed L2 contract
+pub const DEFAULT_L2_BURN_EVENT_KEY: &str =
+    "0x0099de3f38fed0a76764f614c6bc2b958814813685abc1af6deedab612df44f3";
+/// Withdrawal hash appended event key emitted by the deployed L2 contract
+pub const DEFAULT_L2_WITHDRAWAL_EVENT_KEY: &str =
+    "0x01e3ad31c1ae0cf5ec9a8eaf3c540d6cf961c8f4e3bfe1d55a5b92a09e1c9c1e";
+
+fn default_l2_burn_event_key() -> String {
+    DEFAULT_L2_BURN_EVENT_KEY.to_string()
+}
+
+fn default_l2_withdrawal_event_key() -> String {
+    DEFAULT_L2_WITHDRAWAL_EVENT_KEY.to_string()
+}
+
+/// Resolves an event key setting: `0x`-prefixed values are the selector itself, anything else is
+/// the event name, hashed with `get_selector_from_name`
+pub fn resolve_event_key(value: &str) -> Result<Felt, String> {
+    if value.starts_with("0x") {
+        Felt::from_hex(value).map_err(|_| format!("is not a valid hex felt: {}", value))
+    } else {
+        get_selector_from_name(value).map_err(|_| format!("is not an ASCII event name: {}", value))
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct ServerConfig {
     pub host: String,
     pub server_url: String,
+    /// Identifies this sequencer instance in the heartbeat table (defaults to the hostname)
+    pub instance_id: Option<String>,
+    /// Token required in the `x-admin-token` header of `/admin` routes (falls back to ADMIN_TOKEN)
+    pub admin_token: Option<SensitiveField>,
+    /// Largest request body accepted by any route; bigger requests get 413
+    #[serde(default = "default_max_body_bytes")]
+    pub max_body_bytes: usize,
+    /// Origins browsers may call the API from; `"*"` allows any, empty disables CORS
+    #[serde(default)]
+    pub cors_allowed_origins: Vec<String>,
+}
+
+fn default_max_body_bytes() -> usize {
+    1024 * 1024
+}
+
+impl ServerConfig {
+    pub fn get_instance_id(&self) -> String {
+        self.instance_id
+            .clone()
+            .or_else(|| std::env::var("HOSTNAME").ok())
+            .unwrap_or_else(|| "sequencer".to_string())
+    }
+
+    /// Admin routes are disabled when no token is configured
+    pub fn get_admin_token(&self) -> Option<String> {
+        self.admin_token
+            .as_ref()
+            .map(|token| token.expose().to_string())
+            .or_else(|| std::env::var("ADMIN_TOKEN").ok())
+            .filter(|token| !token.is_empty())
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct DatabaseConfig {
     pub max_connections: u32,
+    /// Seconds to wait for migrations, which block while another instance holds their lock
+    #[serde(default = "default_migration_timeout_seconds")]
+    pub migration_timeout_seconds: u64,
+    /// Keeps block trackers in Redis so several sequencer instances can share them
+    #[serde(default)]
+    pub redis_url: Option<String>,
+}
+
+fn default_migration_timeout_seconds() -> u64 {
+    60
 }
 
 impl DatabaseConfig {
-    pub fn get_db_url(&self) -> String {
+    /// `DATABASE_URL`, masked whenever it is printed
+    pub fn get_db_url(&self) -> SensitiveString {
         std::env::var("DATABASE_URL")
+            .map(SensitiveString::from)
             .unwrap_or_else(|_| panic!("DATABASE_URL is not set in environment or .env file"))
     }
+
+    /// Opens and pings a single connection to `DATABASE_URL`, so a misconfigured database is
+    /// reported with the URL that was tried instead of a bare sqlx error.
+    pub async fn test_connection(&self) -> Result<(), ConnectionError> {
+        match std::env::var("DATABASE_URL") {
+            Ok(url) => test_database_connection(&url).await,
+            Err(_) => Err(ConnectionError::new(
+                "<unset>",
+                sqlx::Error::Configuration("DATABASE_URL is not set".into()),
+            )),
+        }
+    }
+}
+
+/// A failed database connection attempt, with enough context to act on
+#[derive(Debug, Error)]
+#[error("Failed to connect to database at {url}: {source}. {}", .suggestions.join(" "))]
+pub struct ConnectionError {
+    /// The attempted URL with its password redacted
+    pub url: String,
+    #[source]
+    pub source: sqlx::Error,
+    pub suggestions: Vec<&'static str>,
+}
+
+impl ConnectionError {
+    fn new(url: &str, source: sqlx::Error) -> Self {
+        let suggestions = match &source {
+            sqlx::Error::Configuration(_) => {
+                vec!["Is DATABASE_URL set correctly?"]
+            }
+            sqlx::Error::Io(_) | sqlx::Error::PoolTimedOut => vec![
+                "Is PostgreSQL running?",
+                "Are the host and port in DATABASE_URL reachable from here?",
+            ],
+            sqlx::Error::Tls(_) => {
+                vec!["Does the server's TLS setup match the sslmode in DATABASE_URL?"]
+            }
+            sqlx::Error::Database(_) => {
+                vec!["Do the user, password and database name in DATABASE_URL exist on the server?"]
+            }
+            _ => vec!["Is PostgreSQL running?", "Is DATABASE_URL set correctly?"],
+        };
+
+        Self {
+            url: redact_database_url(url),
+            source,
+            suggestions,
+        }
+    }
+}
+
+/// Connection check behind [`DatabaseConfig::test_connection`], for an explicit URL
+pub async fn test_database_connection(database_url: &str) -> Result<(), ConnectionError> {
+    let mut conn = PgConnection::connect(database_url)
+        .await
+        .map_err(|e| ConnectionError::new(database_url, e))?;
+    conn.ping()
+        .await
+        .map_err(|e| ConnectionError::new(database_url, e))?;
+    // Nothing to do if the close handshake fails; the connection is dropped either way
+    let _ = conn.close().await;
+    Ok(())
+}
+
+/// Drops the user and password from a database URL so it can be logged. URLs that don't parse
+/// are withheld entirely since there's no telling where a password might sit in them.
+pub fn redact_database_url(database_url: &str) -> String {
+    strip_url_userinfo(database_url)
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct EthereumConfig {
     pub chain_id: u64,
     pub confirmations: u32,
+    /// Blocks a submitted deposit proof must wait before its deposits become claimable
+    pub challenge_window_blocks: u64,
+    /// Blocks the deposit watcher rewinds when the last processed block was reorged out
+    #[serde(default = "default_reorg_depth")]
+    pub reorg_depth: u64,
+    /// Seconds the L1 event watcher waits between polls for new deposit events
+    #[serde(default = "default_polling_interval_seconds")]
+    pub polling_interval_seconds: u64,
+}
+
+fn default_reorg_depth() -> u64 {
+    10
 }
 
 impl EthereumConfig {
@@ -93,6 +434,48 @@ impl EthereumConfig {
     }
 }
 
+/// A secret config value (private key, API key) that serializes and debug-prints as `"***"`, so
+/// dumping a config never leaks it. Read the real value with [`SensitiveField::expose`].
+#[derive(Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(transparent)]
+pub struct SensitiveField(String);
+
+impl SensitiveField {
+    pub const REDACTED: &'static str = "***";
+
+    pub fn new(value: impl Into<String>) -> Self {
+        Self(value.into())
+    }
+
+    pub fn expose(&self) -> &str {
+        &self.0
+    }
+}
+
+impl From<String> for SensitiveField {
+    fn from(value: String) -> Self {
+        Self(value)
+    }
+}
+
+impl From<&str> for SensitiveField {
+    fn from(value: &str) -> Self {
+        Self(value.to_string())
+    }
+}
+
+impl Serialize for SensitiveField {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(Self::REDACTED)
+    }
+}
+
+impl fmt::Debug for SensitiveField {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?}", Self::REDACTED)
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct StarknetConfig {
     pub chain_id: String,
@@ -101,27 +484,107 @@ pub struct StarknetConfig {
     /// Account address for submitting transactions
     pub account_address: String,
     /// Private key for the account (should be set via environment variable in production)
-    pub private_key: String,
+    pub private_key: SensitiveField,
     /// Maximum number of retry attempts for failed transactions
     pub max_retries: Option<u32>,
     /// Delay between retry attempts in milliseconds
     pub retry_delay_ms: Option<u64>,
     /// Timeout for transaction confirmation in milliseconds
     pub transaction_timeout_ms: Option<u64>,
+    /// Timeout for each transaction receipt poll in milliseconds
+    pub receipt_poll_timeout_ms: Option<u64>,
+    /// RPC nodes the relayer falls back to, in order, when `STARKNET_RPC_URL` is unavailable
+    #[serde(default)]
+    pub fallback_rpc_urls: Vec<String>,
+    /// `FactRegistry` contract that proof jobs check their fact against before completing
+    #[serde(default)]
+    pub fact_registry_address: Option<String>,
+    /// Highest fee, in the fee token's smallest unit, a single proof submission may be
+    /// estimated at before the job is aborted
+    #[serde(default)]
+    pub max_fee_per_step: Option<u128>,
+    /// Highest total fee a proof job's remaining submissions may be estimated at before the
+    /// job is suspended
+    #[serde(default)]
+    pub max_total_fee: Option<u128>,
+    /// Seconds the L2 event watcher waits between polls for new contract events
+    #[serde(default = "default_polling_interval_seconds")]
+    pub polling_interval_seconds: u64,
+}
+
+fn default_polling_interval_seconds() -> u64 {
+    10
 }
 
 impl StarknetConfig {
+    pub const DEFAULT_MAX_RETRIES: u32 = 5;
+    pub const DEFAULT_RETRY_DELAY_MS: u64 = 5000;
+    pub const DEFAULT_TRANSACTION_TIMEOUT_MS: u64 = 300000;
+
+    pub fn max_retries(&self) -> u32 {
+        self.max_retries.unwrap_or(Self::DEFAULT_MAX_RETRIES)
+    }
+
+    pub fn retry_delay_ms(&self) -> u64 {
+        self.retry_delay_ms.unwrap_or(Self::DEFAULT_RETRY_DELAY_MS)
+    }
+
+    pub fn transaction_timeout_ms(&self) -> u64 {
+        self.transaction_timeout_ms
+            .unwrap_or(Self::DEFAULT_TRANSACTION_TIMEOUT_MS)
+    }
+
     pub fn get_rpc_url(&self) -> String {
         std::env::var("STARKNET_RPC_URL")
             .unwrap_or_else(|_| panic!("STARKNET_RPC_URL is not set in environment or .env file"))
     }
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct RelayerConfig {
     pub max_retries: u32,
     pub retry_delay_seconds: u32,
+    /// Fallback gas limit used when `eth_estimateGas` fails
     pub gas_limit: u64,
+    /// Safety buffer applied to the `eth_estimateGas` result
+    #[serde(default = "default_gas_estimation_multiplier")]
+    pub gas_estimation_multiplier: f64,
+    /// Relay cycles are skipped while `eth_gasPrice` is above this; unlimited when unset
+    #[serde(default)]
+    pub max_gas_price_gwei: Option<u64>,
+    /// ERC-20 token addresses on L1 that withdrawals may be requested for.
+    ///
+    /// The whitelist is deny-by-default: left empty or unset, every withdrawal request is
+    /// rejected. Services that never accept withdrawals can leave it out.
+    #[serde(default)]
+    pub allowed_l1_tokens: Vec<String>,
+    /// Simulate unlock transactions with `eth_call` instead of sending them
+    #[serde(default)]
+    pub dry_run: bool,
+    /// How far a withdrawal's `amount` may be from the USD value of its tokens, in basis points
+    #[serde(default = "default_amount_tolerance_bps")]
+    pub amount_tolerance_bps: u32,
+    /// Relay withdrawals from the sequencer itself, which is what `GET /events/completions`
+    /// reports. The `ethereum-relayer` binary can still run alongside it.
+    #[serde(default)]
+    pub run_in_sequencer: bool,
+}
+
+fn default_gas_estimation_multiplier() -> f64 {
+    1.2
+}
+
+fn default_amount_tolerance_bps() -> u32 {
+    DEFAULT_TOLERANCE_BPS
+}
+
+impl RelayerConfig {
+    /// Checks whether an L1 token address is whitelisted (case-insensitive)
+    pub fn is_allowed_l1_token(&self, token: &str) -> bool {
+        self.allowed_l1_tokens
+            .iter()
+            .any(|allowed| allowed.eq_ignore_ascii_case(token.trim()))
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -132,6 +595,28 @@ pub struct QueueConfig {
     pub initial_retry_delay_sec: u64,
     pub retry_delay_seconds: u32,
     pub merkle_update_confirmations: u32,
+    /// Items a queue or proof job takes per cycle; the Merkle cache must hold one batch per
+    /// concurrent proof job
+    #[serde(default = "default_queue_batch_size")]
+    pub batch_size: u32,
+    /// How long a cached L1 commitment check is trusted before the database is asked again
+    #[serde(default = "default_commitment_cache_ttl_seconds")]
+    pub commitment_cache_ttl_seconds: u64,
+    /// L2 transactions of a batch whose commitments are checked at the same time
+    #[serde(default = "default_max_concurrent_validations")]
+    pub max_concurrent_validations: usize,
+}
+
+fn default_queue_batch_size() -> u32 {
+    10
+}
+
+fn default_commitment_cache_ttl_seconds() -> u64 {
+    300
+}
+
+fn default_max_concurrent_validations() -> usize {
+    1
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -140,14 +625,319 @@ pub struct MerkleConfig {
     pub cache_size: u32,
 }
 
+impl MerkleConfig {
+    /// Shallowest tree accepted; a depth of 0 would produce an invalid MMR
+    pub const MIN_TREE_DEPTH: u32 = 10;
+    pub const MAX_TREE_DEPTH: u32 = 64;
+    pub const MIN_CACHE_SIZE: u32 = 100;
+    pub const MAX_CACHE_SIZE: u32 = 1_000_000;
+
+    /// Checks `tree_depth` and `cache_size` are within safe ranges, reporting every violation
+    pub fn validate(&self) -> Result<(), Vec<String>> {
+        let mut errors = Vec::new();
+
+        check_range(
+            &mut errors,
+            "merkle.tree_depth",
+            self.tree_depth as u64,
+            Self::MIN_TREE_DEPTH as u64,
+            Self::MAX_TREE_DEPTH as u64,
+        );
+        check_range(
+            &mut errors,
+            "merkle.cache_size",
+            self.cache_size as u64,
+            Self::MIN_CACHE_SIZE as u64,
+            Self::MAX_CACHE_SIZE as u64,
+        );
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct LoggingConfig {
     pub level: String, // "debug" | "info" | "warn" | "error"
     pub file: String,
 }
 
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+/// Basis points in a whole (100%)
+pub const BASIS_POINTS: u32 = 10_000;
+/// `oracle.tolerance_bps` when unset, 1%
+pub const DEFAULT_TOLERANCE_BPS: u32 = 100;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct OracleConfig {
-    pub tolerance_percent: Option<f64>, // e.g., 0.01 for 1%
-    pub polling_interval_seconds: u64,  // e.g., 60 seconds
+    pub tolerance_bps: Option<u32>,    // e.g., 100 for 1%
+    pub polling_interval_seconds: u64, // e.g., 60 seconds
+    /// Run the TVL sync, which records the snapshots `GET /oracle/tvl` reports
+    #[serde(default)]
+    pub enabled: bool,
+}
+
+impl OracleConfig {
+    /// Converts a fractional tolerance from the old `tolerance_percent` setting
+    /// (0.01 = 1%) to basis points, rounding to the nearest one
+    pub fn from_percent(pct: f64) -> u32 {
+        (pct * BASIS_POINTS as f64).round() as u32
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProofConfig {
+    /// Age in minutes after which a `processing` proof job is considered stuck
+    pub stale_job_age_minutes: Option<i64>,
+    /// Base directory calldata paths are resolved against (defaults to the working directory)
+    pub calldata_base_dir: Option<String>,
+    /// Maximum number of queued proof jobs submitted concurrently (defaults to
+    /// `DEFAULT_MAX_CONCURRENT_JOBS`)
+    pub max_concurrent_jobs: Option<usize>,
+    /// Completed and failed jobs older than this many days are archived (0 disables cleanup)
+    pub retention_days: Option<u32>,
+}
+
+impl ProofConfig {
+    pub const DEFAULT_MAX_CONCURRENT_JOBS: usize = 4;
+
+    pub fn max_concurrent_jobs(&self) -> usize {
+        self.max_concurrent_jobs
+            .unwrap_or(Self::DEFAULT_MAX_CONCURRENT_JOBS)
+    }
+}
+
+/// Environment variables read by the `get_*` accessors, which panic when they are missing
+const REQUIRED_ENV_VARS: [&str; 4] = [
+    "DATABASE_URL",
+    "ETHEREUM_RPC_URL",
+    "STARKNET_RPC_URL",
+    "HERODOTUS_API_KEY",
+];
+
+/// Startup checks that surface every configuration problem at once instead of panicking
+/// on the first missing value deep inside a running service.
+pub struct ConfigValidator;
+
+impl ConfigValidator {
+    pub fn validate(cfg: &AppConfig) -> Result<(), Vec<String>> {
+        Self::validate_with_env(cfg, |key| std::env::var(key).ok())
+    }
+
+    /// Same as `validate`, reading environment variables through `env`
+    pub fn validate_with_env<F>(cfg: &AppConfig, env: F) -> Result<(), Vec<String>>
+    where
+        F: Fn(&str) -> Option<String>,
+    {
+        let mut errors = Vec::new();
+
+        for key in REQUIRED_ENV_VARS {
+            match env(key) {
+                Some(value) if !value.trim().is_empty() => {
+                    if key.ends_with("_URL") {
+                        check_url(&mut errors, key, &value);
+                    }
+                }
+                _ => errors.push(format!("{} is not set in environment or .env file", key)),
+            }
+        }
+
+        check_url(&mut errors, "server.server_url", &cfg.server.server_url);
+        for origin in &cfg.server.cors_allowed_origins {
+            if origin != "*" {
+                check_url(&mut errors, "server.cors_allowed_origins", origin);
+            }
+        }
+        if let Err(e) = cfg.herodotus.herodotus_url() {
+            errors.push(e.to_string());
+        }
+        if let Err(e) = cfg.herodotus.atlantic_url() {
+            errors.push(e.to_string());
+        }
+
+        check_felt(
+            &mut errors,
+            "contracts.l1_contract_address",
+            &cfg.contracts.l1_contract_address,
+        );
+        check_felt(
+            &mut errors,
+            "contracts.l2_contract_address",
+            &cfg.contracts.l2_contract_address,
+        );
+        for address in &cfg.contracts.l1_tvl_contract_addresses {
+            check_felt(&mut errors, "contracts.l1_tvl_contract_addresses", address);
+        }
+        for (field, value) in [
+            (
+                "contracts.l2_burn_event_key",
+                &cfg.contracts.l2_burn_event_key,
+            ),
+            (
+                "contracts.l2_withdrawal_event_key",
+                &cfg.contracts.l2_withdrawal_event_key,
+            ),
+        ] {
+            if let Err(e) = resolve_event_key(value) {
+                errors.push(format!("{} {}", field, e));
+            }
+        }
+        for url in &cfg.starknet.fallback_rpc_urls {
+            check_url(&mut errors, "starknet.fallback_rpc_urls", url);
+        }
+        if let Some(url) = &cfg.database.redis_url {
+            check_url(&mut errors, "database.redis_url", url);
+        }
+        if let Some(webhook) = &cfg.webhook {
+            check_url(&mut errors, "webhook.url", &webhook.url);
+            if webhook.secret.expose().is_empty() {
+                errors.push("webhook.secret must not be empty".to_string());
+            }
+        }
+
+        check_felt(&mut errors, "starknet.chain_id", &cfg.starknet.chain_id);
+        check_felt(
+            &mut errors,
+            "starknet.contract_address",
+            &cfg.starknet.contract_address,
+        );
+        check_felt(
+            &mut errors,
+            "starknet.account_address",
+            &cfg.starknet.account_address,
+        );
+        if let Some(address) = &cfg.starknet.fact_registry_address {
+            check_felt(&mut errors, "starknet.fact_registry_address", address);
+        }
+        // The key itself is left out of the message so it never ends up in logs
+        if Felt::from_hex(cfg.starknet.private_key.expose()).is_err() {
+            errors.push("starknet.private_key is not a valid hex felt".to_string());
+        }
+
+        check_min(
+            &mut errors,
+            "server.max_body_bytes",
+            cfg.server.max_body_bytes as u64,
+            1,
+        );
+        check_min(
+            &mut errors,
+            "database.max_connections",
+            cfg.database.max_connections as u64,
+            1,
+        );
+        check_min(
+            &mut errors,
+            "database.migration_timeout_seconds",
+            cfg.database.migration_timeout_seconds,
+            1,
+        );
+        check_min(
+            &mut errors,
+            "relayer.max_retries",
+            cfg.relayer.max_retries as u64,
+            1,
+        );
+        check_min(&mut errors, "relayer.gas_limit", cfg.relayer.gas_limit, 1);
+        if cfg.relayer.gas_estimation_multiplier < 1.0 {
+            errors.push(format!(
+                "relayer.gas_estimation_multiplier must be at least 1.0, got {}",
+                cfg.relayer.gas_estimation_multiplier
+            ));
+        }
+        check_min(
+            &mut errors,
+            "queue.max_retries",
+            cfg.queue.max_retries as u64,
+            1,
+        );
+        check_min(
+            &mut errors,
+            "queue.process_interval_sec",
+            cfg.queue.process_interval_sec,
+            1,
+        );
+        if let Some(max_retries) = cfg.starknet.max_retries {
+            check_min(&mut errors, "starknet.max_retries", max_retries as u64, 1);
+        }
+        check_min(
+            &mut errors,
+            "oracle.polling_interval_seconds",
+            cfg.oracle.polling_interval_seconds,
+            1,
+        );
+        if let Some(tolerance) = cfg.oracle.tolerance_bps {
+            if tolerance > BASIS_POINTS {
+                errors.push(format!(
+                    "oracle.tolerance_bps must be at most {}, got {}",
+                    BASIS_POINTS, tolerance
+                ));
+            }
+        }
+        check_min(
+            &mut errors,
+            "herodotus.poll_interval_seconds",
+            cfg.herodotus.poll_interval_seconds,
+            1,
+        );
+        if let Some(max_concurrent_jobs) = cfg.proof.max_concurrent_jobs {
+            check_min(
+                &mut errors,
+                "proof.max_concurrent_jobs",
+                max_concurrent_jobs as u64,
+                1,
+            );
+        }
+
+        if let Err(merkle_errors) = cfg.merkle.validate() {
+            errors.extend(merkle_errors);
+        }
+        // Each concurrent proof job works through one batch, which must fit in the cache
+        let cache_floor = cfg.proof.max_concurrent_jobs() as u64 * cfg.queue.batch_size as u64;
+        if (cfg.merkle.cache_size as u64) < cache_floor {
+            errors.push(format!(
+                "merkle.cache_size must be at least proof.max_concurrent_jobs * queue.batch_size ({} * {} = {}), got {}",
+                cfg.proof.max_concurrent_jobs(),
+                cfg.queue.batch_size,
+                cache_floor,
+                cfg.merkle.cache_size
+            ));
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+}
+
+fn check_range(errors: &mut Vec<String>, field: &str, value: u64, min: u64, max: u64) {
+    if value < min || value > max {
+        errors.push(format!(
+            "{} must be between {} and {}, got {}",
+            field, min, max, value
+        ));
+    }
+}
+
+fn check_url(errors: &mut Vec<String>, field: &str, value: &str) {
+    if let Err(e) = Url::parse(value) {
+        // The value itself is left out, since credentials in it can't be told apart
+        errors.push(format!("{} is not a valid URL ({})", field, e));
+    }
+}
+
+fn check_felt(errors: &mut Vec<String>, field: &str, value: &str) {
+    if Felt::from_hex(value).is_err() {
+        errors.push(format!("{} is not a valid hex felt: {}", field, value));
+    }
+}
+
+fn check_min(errors: &mut Vec<String>, field: &str, value: u64, min: u64) {
+    if value < min {
+        errors.push(format!("{} must be at least {}, got {}", field, min, value));
+    }
 }
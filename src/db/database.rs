@@ -1,6 +1,17 @@
 use chrono::{DateTime, NaiveDateTime, Utc};
 use serde::{Deserialize, Serialize};
-use sqlx::{postgres::PgPoolOptions, FromRow, PgConnection, PgPool};
+use sqlx::{postgres::PgPoolOptions, FromRow, PgConnection, PgPool, Postgres, QueryBuilder, Row};
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use thiserror::Error;
+
+use crate::db::redis_block_tracker::{RedisBlockTracker, RedisBlockTrackerError};
+use crate::events::l2_event_watcher::{CommitmentLog, WithdrawalCommitmentLog};
+use crate::relayer::proof_submission::ProofJob;
+use crate::utils::{
+    deep_merge_json, normalize_commitment_hash, serialize_optional_prefixed_commitment_hash,
+    serialize_prefixed_commitment_hash,
+};
 
 #[derive(Debug, Serialize, Deserialize, sqlx::FromRow)]
 pub struct Withdrawal {
@@ -9,11 +20,32 @@ pub struct Withdrawal {
     pub amount: i64,
     pub l1_token: String,
     pub l2_tx_id: Option<i32>,
+    #[serde(serialize_with = "serialize_prefixed_commitment_hash")]
     pub commitment_hash: String,
     pub status: String,
     pub retry_count: i32,
     pub created_at: Option<NaiveDateTime>,
     pub updated_at: Option<NaiveDateTime>,
+    pub atlantic_job_id: Option<String>,
+    /// Hash of the L2 transaction that initiated the withdrawal, when it came from L2
+    pub l2_tx_hash: Option<String>,
+    /// Decimals of the withdrawn token, see `convert_to_base_units`
+    pub amount_precision: i16,
+    /// Gas used by the relay transaction, once relayed
+    pub gas_used: Option<i64>,
+    /// Effective gas price of the relay transaction, in gwei
+    pub gas_price_gwei: Option<i64>,
+    /// Position in the user's withdrawal sequence, see [`next_nonce_for_user`]
+    pub nonce: Option<i64>,
+    /// Withdrawn amount in base units of `l1_token`, as a decimal string
+    pub token_amount: Option<String>,
+}
+
+impl Withdrawal {
+    /// Gas used times the price paid per unit, once both are known
+    pub fn total_gas_spent_gwei(&self) -> Option<i64> {
+        self.gas_used?.checked_mul(self.gas_price_gwei?)
+    }
 }
 
 #[derive(Debug, FromRow, Serialize, Deserialize)]
@@ -21,11 +53,25 @@ pub struct Deposit {
     pub id: i32,
     pub stark_pub_key: String,
     pub amount: i64,
+    #[serde(serialize_with = "serialize_prefixed_commitment_hash")]
     pub commitment_hash: String,
     pub status: String, // "pending", "processed", etc.
     pub retry_count: i32,
     pub created_at: Option<DateTime<Utc>>,
     pub updated_at: Option<DateTime<Utc>>,
+    pub idempotency_key: Option<String>,
+    /// Position of the commitment in the deposit Merkle tree, once it has been added
+    pub leaf_index: Option<i64>,
+    /// Ethereum block at which the deposit's proof clears the challenge window
+    pub finalization_block: Option<i64>,
+    /// `depositId` of the L1 `DepositEvent` the deposit was recorded from
+    pub l1_deposit_id: Option<String>,
+    /// Proof job whose proof covers the deposit
+    pub proof_job_id: Option<i64>,
+    /// L1 block the deposit's `DepositEvent` was emitted in
+    pub block_number: Option<i64>,
+    /// `elementCount` of the L1 deposit tree after the deposit's `DepositEvent`
+    pub l1_element_count: Option<i64>,
 }
 
 //Added DepositHashAppended struct with fields matching the event and database schema.
@@ -41,21 +87,34 @@ pub struct DepositHashAppended {
     pub updated_at: Option<DateTime<Utc>>,
 }
 
+/// `commitment_hash` as stored, see `normalize_commitment_hash`; a malformed hash fails like
+/// any other argument that cannot be encoded
+fn commitment_hash_arg(commitment_hash: &str) -> Result<String, sqlx::Error> {
+    normalize_commitment_hash(commitment_hash).map_err(|e| sqlx::Error::Encode(Box::new(e)))
+}
+
 pub async fn insert_withdrawal(
     conn: &PgPool,
     stark_pub_key: &str,
     amount: i64,
+    l1_token: &str,
     commitment_hash: &str,
+    l2_tx_hash: Option<&str>,
+    amount_precision: u8,
 ) -> Result<i32, sqlx::Error> {
+    let commitment_hash = commitment_hash_arg(commitment_hash)?;
     let row_id = sqlx::query_scalar!(
         r#"
-        INSERT INTO withdrawals (stark_pub_key, amount, commitment_hash, status)
-        VALUES ($1, $2, $3, 'pending')
+        INSERT INTO withdrawals (stark_pub_key, amount, l1_token, commitment_hash, status, l2_tx_hash, amount_precision)
+        VALUES ($1, $2, $3, $4, 'pending', $5, $6)
         RETURNING id
         "#,
         stark_pub_key,
         amount,
-        commitment_hash
+        l1_token,
+        commitment_hash,
+        l2_tx_hash,
+        amount_precision as i16
     )
     .fetch_one(conn)
     .await?;
@@ -63,12 +122,104 @@ pub async fn insert_withdrawal(
     Ok(row_id)
 }
 
+/// Nonce for `stark_pub_key`'s next withdrawal: one past the highest it has used, 0 for its first.
+///
+/// The user's sequence is locked until `conn`'s transaction ends, so a concurrent call for the
+/// same user waits for the withdrawal created here instead of reading the same nonce.
+pub async fn next_nonce_for_user(
+    conn: &mut PgConnection,
+    stark_pub_key: &str,
+) -> Result<u64, sqlx::Error> {
+    // Row locks cannot cover a user's first withdrawal, so the sequence is locked as a whole
+    sqlx::query("SELECT pg_advisory_xact_lock(hashtext('withdrawal_nonce:' || $1))")
+        .bind(stark_pub_key)
+        .execute(&mut *conn)
+        .await?;
+
+    let next = sqlx::query_scalar!(
+        r#"
+        SELECT COALESCE(MAX(nonce) + 1, 0) AS "next!"
+        FROM withdrawals
+        WHERE stark_pub_key = $1
+        "#,
+        stark_pub_key
+    )
+    .fetch_one(conn)
+    .await?;
+
+    Ok(next as u64)
+}
+
+#[derive(Debug, Error)]
+pub enum WithdrawalNonceError {
+    #[error("Database error: {0}")]
+    Database(#[from] sqlx::Error),
+
+    #[error(
+        "nonce {provided} {}, the next nonce of this user is {expected}",
+        if .provided < .expected { "was already used" } else { "is ahead of the sequence" }
+    )]
+    NonceMismatch { provided: u64, expected: u64 },
+}
+
+/// [`insert_withdrawal`] with the next nonce of `stark_pub_key` assigned, returning the new id
+/// and its nonce.
+///
+/// `token_amount` is the withdrawn amount in base units of `l1_token`, as a decimal string.
+/// `nonce` is the nonce the client hashed into `commitment_hash`; the withdrawal is refused
+/// unless it is the user's next one, so the stored nonce always matches the hash.
+#[allow(clippy::too_many_arguments)]
+pub async fn insert_withdrawal_with_next_nonce(
+    pool: &PgPool,
+    stark_pub_key: &str,
+    amount: i64,
+    l1_token: &str,
+    commitment_hash: &str,
+    l2_tx_hash: Option<&str>,
+    amount_precision: u8,
+    token_amount: &str,
+    nonce: u64,
+) -> Result<(i32, u64), WithdrawalNonceError> {
+    let commitment_hash = commitment_hash_arg(commitment_hash)?;
+    let mut tx = pool.begin().await?;
+
+    let expected = next_nonce_for_user(&mut tx, stark_pub_key).await?;
+    if nonce != expected {
+        return Err(WithdrawalNonceError::NonceMismatch {
+            provided: nonce,
+            expected,
+        });
+    }
+    let row_id = sqlx::query_scalar!(
+        r#"
+        INSERT INTO withdrawals (stark_pub_key, amount, l1_token, commitment_hash, status, l2_tx_hash, amount_precision, token_amount, nonce)
+        VALUES ($1, $2, $3, $4, 'pending', $5, $6, $7, $8)
+        RETURNING id
+        "#,
+        stark_pub_key,
+        amount,
+        l1_token,
+        commitment_hash,
+        l2_tx_hash,
+        amount_precision as i16,
+        token_amount,
+        nonce as i64
+    )
+    .fetch_one(&mut *tx)
+    .await?;
+
+    tx.commit().await?;
+
+    Ok((row_id, nonce))
+}
+
 pub async fn insert_deposit(
     conn: &PgPool,
     stark_pub_key: &str,
     amount: i64,
     commitment_hash: &str,
 ) -> Result<i32, sqlx::Error> {
+    let commitment_hash = commitment_hash_arg(commitment_hash)?;
     let row_id = sqlx::query_scalar!(
         r#"
         INSERT INTO deposits (stark_pub_key, amount, commitment_hash, status)
@@ -85,32 +236,249 @@ pub async fn insert_deposit(
     Ok(row_id)
 }
 
+/// Whether no deposit has been recorded with `commitment_hash` yet
+pub async fn check_commitment_hash_unique(
+    conn: &PgPool,
+    commitment_hash: &str,
+) -> Result<bool, sqlx::Error> {
+    let commitment_hash = commitment_hash_arg(commitment_hash)?;
+    let exists = sqlx::query_scalar!(
+        r#"
+        SELECT EXISTS(SELECT 1 FROM deposits WHERE commitment_hash = $1) AS "exists!"
+        "#,
+        commitment_hash
+    )
+    .fetch_one(conn)
+    .await?;
+
+    Ok(!exists)
+}
+
+/// A deposit to create through [`insert_deposits_bulk`]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NewDeposit {
+    pub stark_pub_key: String,
+    pub amount: i64,
+    pub commitment_hash: String,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct BulkInsertDepositsResult {
+    /// Ids of the inserted deposits, in the order they were given
+    pub created_ids: Vec<i32>,
+    /// Commitment hashes that already existed, or repeated an earlier entry in the batch
+    pub skipped: Vec<String>,
+}
+
+/// Inserts all `deposits` with a single statement in one transaction, skipping any whose
+/// `commitment_hash` is already taken instead of failing the whole batch.
+pub async fn insert_deposits_bulk(
+    conn: &PgPool,
+    deposits: &[NewDeposit],
+) -> Result<BulkInsertDepositsResult, sqlx::Error> {
+    if deposits.is_empty() {
+        return Ok(BulkInsertDepositsResult::default());
+    }
+
+    let commitment_hashes = deposits
+        .iter()
+        .map(|deposit| commitment_hash_arg(&deposit.commitment_hash))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let mut tx = conn.begin().await?;
+
+    let mut builder: QueryBuilder<Postgres> =
+        QueryBuilder::new("INSERT INTO deposits (stark_pub_key, amount, commitment_hash, status) ");
+    builder.push_values(
+        deposits.iter().zip(&commitment_hashes),
+        |mut row, (deposit, commitment_hash)| {
+            row.push_bind(&deposit.stark_pub_key)
+                .push_bind(deposit.amount)
+                .push_bind(commitment_hash)
+                .push_bind("pending");
+        },
+    );
+    builder.push(" ON CONFLICT (commitment_hash) DO NOTHING RETURNING id, commitment_hash");
+
+    let rows = builder.build().fetch_all(&mut *tx).await?;
+
+    tx.commit().await?;
+
+    let mut inserted: HashMap<String, i32> = HashMap::with_capacity(rows.len());
+    for row in rows {
+        inserted.insert(row.try_get("commitment_hash")?, row.try_get("id")?);
+    }
+
+    // RETURNING order isn't guaranteed, so map the ids back onto the request order. Taking each
+    // id out of the map also marks later repeats of the same hash as skipped.
+    let mut result = BulkInsertDepositsResult::default();
+    for (deposit, commitment_hash) in deposits.iter().zip(&commitment_hashes) {
+        match inserted.remove(commitment_hash) {
+            Some(id) => result.created_ids.push(id),
+            None => result.skipped.push(deposit.commitment_hash.clone()),
+        }
+    }
+
+    Ok(result)
+}
+
+/// Inserts a deposit at most once per idempotency key.
+///
+/// Returns the deposit id and whether it was newly created (`false` when the key was replayed).
+pub async fn insert_deposit_idempotent(
+    conn: &PgPool,
+    stark_pub_key: &str,
+    amount: i64,
+    commitment_hash: &str,
+    idempotency_key: &str,
+) -> Result<(i32, bool), sqlx::Error> {
+    let commitment_hash = commitment_hash_arg(commitment_hash)?;
+    let inserted = sqlx::query_scalar!(
+        r#"
+        INSERT INTO deposits (stark_pub_key, amount, commitment_hash, status, idempotency_key)
+        VALUES ($1, $2, $3, 'pending', $4)
+        ON CONFLICT (idempotency_key) DO NOTHING
+        RETURNING id
+        "#,
+        stark_pub_key,
+        amount,
+        commitment_hash,
+        idempotency_key
+    )
+    .fetch_optional(conn)
+    .await?;
+
+    if let Some(row_id) = inserted {
+        return Ok((row_id, true));
+    }
+
+    let existing_id = sqlx::query_scalar!(
+        r#"
+        SELECT id FROM deposits
+        WHERE idempotency_key = $1
+        "#,
+        idempotency_key
+    )
+    .fetch_one(conn)
+    .await?;
+
+    Ok((existing_id, false))
+}
+
+/// Stores a deposit seen on L1, returning whether it was new rather than a replayed event
+#[allow(clippy::too_many_arguments)]
 pub async fn upsert_deposit(
-    conn:&PgPool,
+    conn: &PgPool,
     stark_pub_key: &str,
     amount: i64,
     commitment_hash: &str,
-    status: &str
-) -> Result<(), sqlx::Error> {
-    sqlx::query!(
+    status: &str,
+    l1_deposit_id: &str,
+    block_number: Option<i64>,
+    l1_element_count: Option<i64>,
+) -> Result<bool, sqlx::Error> {
+    let commitment_hash = commitment_hash_arg(commitment_hash)?;
+    // A replayed event carries the same commitment hash, so it updates the existing row. After a
+    // reorg the event may land in a different block, so the block number is overwritten too.
+    // `xmax` is only zero on a freshly inserted row, which tells replays apart.
+    let inserted = sqlx::query_scalar!(
         r#"
-        INSERT INTO deposits (stark_pub_key, amount, commitment_hash, status)
-        VALUES ($1, $2, $3, $4)
+        INSERT INTO deposits (stark_pub_key, amount, commitment_hash, status, l1_deposit_id, block_number, l1_element_count)
+        VALUES ($1, $2, $3, $4, $5, $6, $7)
         ON CONFLICT (commitment_hash) DO UPDATE
         SET status = EXCLUDED.status,
+        l1_deposit_id = COALESCE(deposits.l1_deposit_id, EXCLUDED.l1_deposit_id),
+        block_number = COALESCE(EXCLUDED.block_number, deposits.block_number),
+        l1_element_count = COALESCE(EXCLUDED.l1_element_count, deposits.l1_element_count),
         updated_at = NOW()
+        RETURNING (xmax = 0) AS "inserted!"
         "#,
         stark_pub_key,
         amount,
         commitment_hash,
         status,
-    ).execute(conn).await?;
+        l1_deposit_id,
+        block_number,
+        l1_element_count,
+    ).fetch_one(conn).await?;
 
-    Ok(())
+    Ok(inserted)
+}
+
+/// Marks the deposits emitted after `block_number` that nothing has acted on yet as
+/// `INVALIDATED`, returning how many changed.
+///
+/// Used after a reorg; deposits still on the canonical chain are re-recorded when their events
+/// are fetched again. Only deposits still waiting to be processed and not yet covered by a proof
+/// job are touched. Deposits that were processed, proved or became claimable are left as they
+/// are, since replaying them could mint twice, and deposits already `INVALIDATED` stay so.
+pub async fn invalidate_deposits_after_block(
+    conn: &PgPool,
+    block_number: u64,
+) -> Result<u64, sqlx::Error> {
+    invalidate_unprocessed_deposits(conn, block_number as i64).await
+}
+
+/// Invalidates the deposits emitted in `from_block` or later that nothing has acted on yet,
+/// returning how many changed.
+///
+/// The same deposits are left alone as in [`invalidate_deposits_after_block`], and deposits
+/// still on the canonical chain are likewise re-recorded when their events are fetched again.
+pub async fn reset_deposits_after_block(
+    pool: &PgPool,
+    from_block: u64,
+) -> Result<u64, sqlx::Error> {
+    invalidate_unprocessed_deposits(pool, from_block as i64 - 1).await
+}
+
+async fn invalidate_unprocessed_deposits(
+    conn: &PgPool,
+    after_block: i64,
+) -> Result<u64, sqlx::Error> {
+    let result = sqlx::query!(
+        r#"
+        UPDATE deposits
+        SET status = 'INVALIDATED', updated_at = NOW()
+        WHERE block_number > $1
+        AND status IN ('pending', 'PENDING_TREE_INCLUSION')
+        AND proof_job_id IS NULL
+        "#,
+        after_block
+    )
+    .execute(conn)
+    .await?;
+
+    Ok(result.rows_affected())
+}
+
+/// Block of the L1 `DepositEvent` carrying `commitment_hash`, or `None` if it has not been
+/// seen on L1 or was invalidated by a reorg
+pub async fn fetch_commitment_block_number(
+    conn: &PgPool,
+    commitment_hash: &str,
+) -> Result<Option<u64>, sqlx::Error> {
+    let commitment_hash = commitment_hash_arg(commitment_hash)?;
+    let block_number = sqlx::query_scalar!(
+        r#"
+        SELECT block_number AS "block_number!"
+        FROM deposits
+        WHERE commitment_hash = $1
+          AND block_number IS NOT NULL
+          AND status <> 'INVALIDATED'
+        "#,
+        commitment_hash
+    )
+    .fetch_optional(conn)
+    .await?;
+
+    Ok(block_number.map(|block_number| block_number as u64))
 }
 
-// new function
-pub async fn insert_deposit_hash_event(
+/// Stores a `DepositHashAppended` event, returning its row id.
+///
+/// Reprocessed events hit the same Merkle tree `index`, so they update that row's root and
+/// element count instead of inserting a duplicate, and the existing row's id is returned.
+pub async fn upsert_deposit_hash_event(
     conn: &PgPool,
     event: &DepositHashAppended,
 ) -> Result<i32, sqlx::Error> {
@@ -118,6 +486,10 @@ pub async fn insert_deposit_hash_event(
         r#"
         INSERT INTO deposit_hashes (index, commitment_hash, root_hash, elements_count, block_number)
         VALUES ($1, $2, $3, $4, $5)
+        ON CONFLICT (index) DO UPDATE
+        SET root_hash = EXCLUDED.root_hash,
+        elements_count = EXCLUDED.elements_count,
+        updated_at = NOW()
         RETURNING id
         "#,
         event.index,
@@ -132,20 +504,215 @@ pub async fn insert_deposit_hash_event(
     Ok(row_id)
 }
 
-pub async fn fetch_pending_withdrawals(
+/// Lifecycle status of a withdrawal, as stored in `withdrawals.status`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WithdrawalStatus {
+    Pending,
+    PendingProof,
+    ProofSubmitted,
+    ProofFailed,
+    ReadyForRelay,
+    Relayed,
+    Failed,
+}
+
+impl WithdrawalStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            WithdrawalStatus::Pending => "pending",
+            WithdrawalStatus::PendingProof => "pending_proof",
+            WithdrawalStatus::ProofSubmitted => "proof_submitted",
+            WithdrawalStatus::ProofFailed => "proof_failed",
+            WithdrawalStatus::ReadyForRelay => "ready_for_relay",
+            WithdrawalStatus::Relayed => "relayed",
+            WithdrawalStatus::Failed => "failed",
+        }
+    }
+}
+
+/// Column a listing is ordered by, ascending, ties broken by id
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SortField {
+    #[default]
+    CreatedAt,
+    Amount,
+}
+
+/// One-based page of `per_page` rows
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PaginationParams {
+    pub page: i64,
+    pub per_page: i64,
+}
+
+impl PaginationParams {
+    fn offset(&self) -> i64 {
+        (self.page - 1).max(0) * self.per_page
+    }
+}
+
+/// A page of rows, with the number of rows matching across every page
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PaginatedResult<T> {
+    pub items: Vec<T>,
+    pub total: i64,
+    pub page: i64,
+    pub per_page: i64,
+    /// Cursor to pass as `after` for the next page of a cursor-paginated listing, `None` on
+    /// the last page and for listings paginated by page number
+    #[serde(default)]
+    pub next_after: Option<i32>,
+}
+
+/// Filters for listing withdrawals; `None` fields are not applied and amount bounds are
+/// inclusive
+#[derive(Debug, Clone)]
+pub struct WithdrawalFilter {
+    pub status: Option<WithdrawalStatus>,
+    pub stark_pub_key: Option<String>,
+    pub min_amount: Option<i64>,
+    pub max_amount: Option<i64>,
+    pub sort_by: SortField,
+    pub page: PaginationParams,
+}
+
+pub async fn list_withdrawals(
     conn: &PgPool,
-    max_retries: u32,
+    filter: WithdrawalFilter,
+) -> Result<PaginatedResult<Withdrawal>, sqlx::Error> {
+    let status = filter.status.map(|status| status.as_str());
+    let sort_by_amount = filter.sort_by == SortField::Amount;
+
+    let total = sqlx::query_scalar!(
+        r#"
+        SELECT COUNT(*) AS "count!" FROM withdrawals
+        WHERE ($1::TEXT IS NULL OR status = $1)
+        AND ($2::TEXT IS NULL OR stark_pub_key = $2)
+        AND ($3::BIGINT IS NULL OR amount >= $3)
+        AND ($4::BIGINT IS NULL OR amount <= $4)
+        "#,
+        status,
+        filter.stark_pub_key,
+        filter.min_amount,
+        filter.max_amount
+    )
+    .fetch_one(conn)
+    .await?;
+
+    let items = sqlx::query_as!(
+        Withdrawal,
+        r#"
+        SELECT * FROM withdrawals
+        WHERE ($1::TEXT IS NULL OR status = $1)
+        AND ($2::TEXT IS NULL OR stark_pub_key = $2)
+        AND ($3::BIGINT IS NULL OR amount >= $3)
+        AND ($4::BIGINT IS NULL OR amount <= $4)
+        ORDER BY CASE WHEN $5 THEN amount END ASC, created_at ASC, id ASC
+        LIMIT $6 OFFSET $7
+        "#,
+        status,
+        filter.stark_pub_key,
+        filter.min_amount,
+        filter.max_amount,
+        sort_by_amount,
+        filter.page.per_page,
+        filter.page.offset()
+    )
+    .fetch_all(conn)
+    .await?;
+
+    Ok(PaginatedResult {
+        items,
+        total,
+        page: filter.page.page,
+        per_page: filter.page.per_page,
+        next_after: None,
+    })
+}
+
+/// Withdrawals of `stark_pub_key`, newest first, `limit` at a time. `after_id` continues the
+/// listing after that withdrawal, as given by the previous page's `next_after`.
+///
+/// Returns `None` when `after_id` is not one of the user's withdrawals, so a bad cursor isn't
+/// mistaken for the end of the listing.
+pub async fn fetch_withdrawals_by_stark_pub_key(
+    conn: &PgPool,
+    stark_pub_key: &str,
+    limit: i64,
+    after_id: Option<i32>,
+) -> Result<Option<PaginatedResult<Withdrawal>>, sqlx::Error> {
+    // `seen` counts the withdrawals listed on earlier pages, up to and including the cursor
+    let counts = sqlx::query!(
+        r#"
+        SELECT
+            COUNT(*) AS "total!",
+            COUNT(*) FILTER (
+                WHERE (created_at, id) >= (SELECT created_at, id FROM withdrawals WHERE id = $2)
+            ) AS "seen!",
+            COUNT(*) FILTER (WHERE id = $2) > 0 AS "cursor_found!"
+        FROM withdrawals
+        WHERE stark_pub_key = $1
+        "#,
+        stark_pub_key,
+        after_id
+    )
+    .fetch_one(conn)
+    .await?;
+    if after_id.is_some() && !counts.cursor_found {
+        return Ok(None);
+    }
+
+    let items = sqlx::query_as!(
+        Withdrawal,
+        r#"
+        SELECT * FROM withdrawals
+        WHERE stark_pub_key = $1
+        AND (
+            $2::INT IS NULL
+            OR (created_at, id) < (SELECT created_at, id FROM withdrawals WHERE id = $2)
+        )
+        ORDER BY created_at DESC, id DESC
+        LIMIT $3
+        "#,
+        stark_pub_key,
+        after_id,
+        limit
+    )
+    .fetch_all(conn)
+    .await?;
+
+    let next_after = if counts.seen + (items.len() as i64) < counts.total {
+        items.last().map(|withdrawal| withdrawal.id)
+    } else {
+        None
+    };
+
+    Ok(Some(PaginatedResult {
+        items,
+        total: counts.total,
+        page: counts.seen / limit + 1,
+        per_page: limit,
+        next_after,
+    }))
+}
+
+pub async fn fetch_withdrawals_by_status(
+    conn: &PgPool,
+    status: &str,
+    limit: i64,
 ) -> Result<Vec<Withdrawal>, sqlx::Error> {
     let withdrawals = sqlx::query_as!(
         Withdrawal,
         r#"
         SELECT * FROM withdrawals
-        WHERE status = 'pending'
-        AND retry_count < $1
+        WHERE status = $1
         ORDER BY created_at ASC
-        LIMIT 10
+        LIMIT $2
         "#,
-        max_retries as i32
+        status,
+        limit
     )
     .fetch_all(conn)
     .await?;
@@ -174,92 +741,887 @@ pub async fn fetch_pending_deposits(
     Ok(deposits)
 }
 
-pub async fn update_deposit_status(
+/// Ids of up to `limit` pending deposits below `max_retries`, oldest first. Nothing is locked;
+/// each deposit is claimed with [`claim_pending_deposit`] before it is processed.
+pub async fn fetch_pending_deposit_ids(
+    conn: &PgPool,
+    max_retries: u32,
+    limit: i64,
+) -> Result<Vec<i32>, sqlx::Error> {
+    sqlx::query_scalar!(
+        r#"
+        SELECT id
+        FROM deposits
+        WHERE status = 'pending' AND retry_count < $1
+        ORDER BY created_at ASC
+        LIMIT $2
+        "#,
+        max_retries as i32,
+        limit
+    )
+    .fetch_all(conn)
+    .await
+}
+
+/// Deposit `id`, if it is still pending below `max_retries`.
+///
+/// The row is locked with `FOR UPDATE SKIP LOCKED` until `conn`'s transaction ends, so another
+/// queue claiming it at the same time gets `None`.
+pub async fn claim_pending_deposit(
     conn: &mut PgConnection,
     id: i32,
-    status: &str,
-) -> Result<(), sqlx::Error> {
-    sqlx::query!(
+    max_retries: u32,
+) -> Result<Option<Deposit>, sqlx::Error> {
+    sqlx::query_as!(
+        Deposit,
         r#"
-        UPDATE deposits
-        SET status = $2, updated_at = NOW()
-        WHERE id = $1
+        SELECT *
+        FROM deposits
+        WHERE id = $1 AND status = 'pending' AND retry_count < $2
+        FOR UPDATE SKIP LOCKED
         "#,
         id,
-        status
+        max_retries as i32
     )
-    .execute(conn)
-    .await?;
-
-    Ok(())
+    .fetch_optional(conn)
+    .await
 }
 
-pub async fn process_deposit_retry(conn: &mut PgConnection, id: i32) -> Result<(), sqlx::Error> {
-    sqlx::query!(
+pub async fn fetch_deposit_by_id(conn: &PgPool, id: i32) -> Result<Option<Deposit>, sqlx::Error> {
+    let deposit = sqlx::query_as!(
+        Deposit,
         r#"
-        UPDATE deposits
-        SET retry_count = retry_count + 1, updated_at = NOW()
+        SELECT *
+        FROM deposits
         WHERE id = $1
         "#,
         id
     )
-    .execute(conn)
+    .fetch_optional(conn)
     .await?;
 
-    Ok(())
+    Ok(deposit)
 }
 
-pub async fn process_withdrawal_retry(conn: &mut PgConnection, id: i32) -> Result<(), sqlx::Error> {
-    sqlx::query!(
+/// Links deposits to the proof job whose proof covers them. Returns the number of deposits
+/// that were linked.
+pub async fn link_deposits_to_proof_job(
+    conn: &PgPool,
+    proof_job_id: i64,
+    deposit_ids: &[i32],
+) -> Result<u64, sqlx::Error> {
+    let result = sqlx::query!(
         r#"
-        UPDATE withdrawals
-        SET retry_count = retry_count + 1,
-        updated_at = NOW()
-        WHERE id = $1
+        UPDATE deposits
+        SET proof_job_id = $1, updated_at = NOW()
+        WHERE id = ANY($2)
         "#,
-        id
+        proof_job_id,
+        deposit_ids
     )
     .execute(conn)
     .await?;
 
-    Ok(())
+    Ok(result.rows_affected())
 }
 
-pub async fn update_withdrawal_status(
-    conn: &mut PgConnection,
-    id: i32,
-    status: &str,
-) -> Result<(), sqlx::Error> {
-    sqlx::query!(
+/// Moves the pending deposits linked to `proof_job_id` to `PROOF_SUBMITTED`, to become
+/// claimable once Ethereum reaches `finalization_block`. Returns the ids of the deposits that
+/// were moved.
+pub async fn schedule_deposit_finalization(
+    conn: &PgPool,
+    proof_job_id: i64,
+    finalization_block: u64,
+) -> Result<Vec<i32>, sqlx::Error> {
+    let ids = sqlx::query_scalar!(
         r#"
-        UPDATE withdrawals
-        SET status = $2,
-        updated_at = NOW()
-        WHERE id = $1
+        UPDATE deposits
+        SET status = 'PROOF_SUBMITTED', finalization_block = $2, updated_at = NOW()
+        WHERE proof_job_id = $1 AND status = 'pending'
+        RETURNING id
         "#,
-        id,
-        status
+        proof_job_id,
+        finalization_block as i64
     )
-    .execute(conn)
+    .fetch_all(conn)
     .await?;
 
-    Ok(())
+    Ok(ids)
 }
 
-pub async fn update_last_processed_block(
-    conn: &PgPool,
+/// Marks `PROOF_SUBMITTED` deposits whose challenge window has passed at `current_block` as
+/// `READY_TO_CLAIM`. Returns the ids of the deposits that were released.
+pub async fn finalize_deposits(conn: &PgPool, current_block: u64) -> Result<Vec<i32>, sqlx::Error> {
+    let ids = sqlx::query_scalar!(
+        r#"
+        UPDATE deposits
+        SET status = 'READY_TO_CLAIM', updated_at = NOW()
+        WHERE status = 'PROOF_SUBMITTED' AND finalization_block <= $1
+        RETURNING id
+        "#,
+        current_block as i64
+    )
+    .fetch_all(conn)
+    .await?;
+
+    Ok(ids)
+}
+
+pub async fn set_deposit_leaf_index(
+    conn: &mut PgConnection,
+    id: i32,
+    leaf_index: i64,
+) -> Result<(), sqlx::Error> {
+    sqlx::query!(
+        r#"
+        UPDATE deposits
+        SET leaf_index = $2, updated_at = NOW()
+        WHERE id = $1
+        "#,
+        id,
+        leaf_index
+    )
+    .execute(conn)
+    .await?;
+
+    Ok(())
+}
+
+pub async fn update_deposit_status(
+    conn: &mut PgConnection,
+    id: i32,
+    status: &str,
+) -> Result<(), sqlx::Error> {
+    sqlx::query!(
+        r#"
+        UPDATE deposits
+        SET status = $2, updated_at = NOW()
+        WHERE id = $1
+        "#,
+        id,
+        status
+    )
+    .execute(conn)
+    .await?;
+
+    Ok(())
+}
+
+pub async fn process_deposit_retry(conn: &mut PgConnection, id: i32) -> Result<(), sqlx::Error> {
+    sqlx::query!(
+        r#"
+        UPDATE deposits
+        SET retry_count = retry_count + 1, updated_at = NOW()
+        WHERE id = $1
+        "#,
+        id
+    )
+    .execute(conn)
+    .await?;
+
+    Ok(())
+}
+
+pub async fn process_withdrawal_retry(conn: &mut PgConnection, id: i32) -> Result<(), sqlx::Error> {
+    sqlx::query!(
+        r#"
+        UPDATE withdrawals
+        SET retry_count = retry_count + 1,
+        updated_at = NOW()
+        WHERE id = $1
+        "#,
+        id
+    )
+    .execute(conn)
+    .await?;
+
+    Ok(())
+}
+
+/// Moves withdrawal `id` to `status`, recording the transition in `withdrawal_events` under
+/// `actor`, the component that made the change (e.g. "l2_queue", "ethereum_relayer", "api")
+pub async fn update_withdrawal_status(
+    conn: &mut PgConnection,
+    id: i32,
+    status: &str,
+    actor: &str,
+) -> Result<(), sqlx::Error> {
+    sqlx::query!(
+        r#"
+        WITH previous AS (
+            SELECT id, status FROM withdrawals WHERE id = $1 FOR UPDATE
+        ),
+        updated AS (
+            UPDATE withdrawals w
+            SET status = $2,
+            updated_at = NOW()
+            FROM previous
+            WHERE w.id = previous.id
+            RETURNING w.id, previous.status AS old_status
+        )
+        INSERT INTO withdrawal_events (withdrawal_id, old_status, new_status, actor)
+        SELECT id, old_status, $2, $3 FROM updated
+        "#,
+        id,
+        status,
+        actor
+    )
+    .execute(conn)
+    .await?;
+
+    Ok(())
+}
+
+/// One status transition of a withdrawal, as listed by `GET /withdrawals/{id}/timeline`
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct WithdrawalEvent {
+    pub old_status: String,
+    pub new_status: String,
+    pub actor: String,
+    pub occurred_at: DateTime<Utc>,
+}
+
+/// Status transitions of withdrawal `withdrawal_id` in the order they happened, or `None` if
+/// there is no such withdrawal
+pub async fn fetch_withdrawal_events(
+    conn: &PgPool,
+    withdrawal_id: i32,
+) -> Result<Option<Vec<WithdrawalEvent>>, sqlx::Error> {
+    let exists = sqlx::query_scalar!(
+        r#"SELECT EXISTS(SELECT 1 FROM withdrawals WHERE id = $1) AS "exists!""#,
+        withdrawal_id
+    )
+    .fetch_one(conn)
+    .await?;
+    if !exists {
+        return Ok(None);
+    }
+
+    let events = sqlx::query_as!(
+        WithdrawalEvent,
+        r#"
+        SELECT old_status, new_status, actor, occurred_at
+        FROM withdrawal_events
+        WHERE withdrawal_id = $1
+        ORDER BY occurred_at, id
+        "#,
+        withdrawal_id
+    )
+    .fetch_all(conn)
+    .await?;
+
+    Ok(Some(events))
+}
+
+/// Records the gas the relay transaction of withdrawal `id` used and the price paid per unit
+pub async fn update_withdrawal_gas_stats(
+    conn: &mut PgConnection,
+    id: i32,
+    gas_used: Option<i64>,
+    gas_price_gwei: Option<i64>,
+) -> Result<(), sqlx::Error> {
+    sqlx::query!(
+        r#"
+        UPDATE withdrawals
+        SET gas_used = $2,
+        gas_price_gwei = $3,
+        updated_at = NOW()
+        WHERE id = $1
+        "#,
+        id,
+        gas_used,
+        gas_price_gwei
+    )
+    .execute(conn)
+    .await?;
+
+    Ok(())
+}
+
+pub async fn fetch_withdrawal_by_id(
+    conn: &PgPool,
+    id: i32,
+) -> Result<Option<Withdrawal>, sqlx::Error> {
+    sqlx::query_as!(
+        Withdrawal,
+        r#"
+        SELECT *
+        FROM withdrawals
+        WHERE id = $1
+        "#,
+        id
+    )
+    .fetch_optional(conn)
+    .await
+}
+
+/// Gas spent relaying withdrawals, aggregated over relayed withdrawals with gas stats
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GasMetrics {
+    pub average_gas_used: Option<i64>,
+    pub total_eth_spent_wei: u128,
+}
+
+pub async fn fetch_gas_metrics(conn: &PgPool) -> Result<GasMetrics, sqlx::Error> {
+    // The wei total outgrows BIGINT after a few ETH, so it leaves Postgres as text
+    let row = sqlx::query!(
+        r#"
+        SELECT AVG(gas_used)::BIGINT AS average_gas_used,
+            COALESCE(SUM(gas_used::NUMERIC * gas_price_gwei * 1000000000), 0)::TEXT
+                AS "total_eth_spent_wei!"
+        FROM withdrawals
+        WHERE status = 'relayed' AND gas_used IS NOT NULL
+        "#
+    )
+    .fetch_one(conn)
+    .await?;
+
+    let total_eth_spent_wei = row
+        .total_eth_spent_wei
+        .parse()
+        .map_err(|e| sqlx::Error::Decode(Box::new(e)))?;
+
+    Ok(GasMetrics {
+        average_gas_used: row.average_gas_used,
+        total_eth_spent_wei,
+    })
+}
+
+/// Records Atlantic job `atlantic_job_id` on withdrawal `id` and moves it to `status` through
+/// [`update_withdrawal_status`], so the transition shows up in its timeline
+pub async fn set_withdrawal_atlantic_job(
+    conn: &mut PgConnection,
+    id: i32,
+    atlantic_job_id: &str,
+    status: &str,
+    actor: &str,
+) -> Result<(), sqlx::Error> {
+    sqlx::query!(
+        r#"
+        UPDATE withdrawals
+        SET atlantic_job_id = $2,
+        updated_at = NOW()
+        WHERE id = $1
+        "#,
+        id,
+        atlantic_job_id
+    )
+    .execute(&mut *conn)
+    .await?;
+
+    update_withdrawal_status(conn, id, status, actor).await
+}
+
+/// Starts tracking withdrawal `withdrawal_id`'s proof as Atlantic job `atlantic_job_id`,
+/// replacing any earlier attempt
+pub async fn record_withdrawal_proof_submission(
+    conn: &mut PgConnection,
+    withdrawal_id: i32,
+    atlantic_job_id: &str,
+    status: &str,
+) -> Result<(), sqlx::Error> {
+    sqlx::query!(
+        r#"
+        INSERT INTO withdrawal_proofs (withdrawal_id, atlantic_job_id, status, submitted_at)
+        VALUES ($1, $2, $3, NOW())
+        ON CONFLICT (withdrawal_id) DO UPDATE
+        SET atlantic_job_id = EXCLUDED.atlantic_job_id,
+        status = EXCLUDED.status,
+        submitted_at = EXCLUDED.submitted_at,
+        updated_at = NOW()
+        "#,
+        withdrawal_id,
+        atlantic_job_id,
+        status
+    )
+    .execute(conn)
+    .await?;
+
+    Ok(())
+}
+
+/// Sets the status of withdrawal `withdrawal_id`'s proof, returning the seconds since it was
+/// submitted to Atlantic, if it was
+pub async fn update_withdrawal_proof_status(
+    conn: &mut PgConnection,
+    withdrawal_id: i32,
+    status: &str,
+) -> Result<Option<f64>, sqlx::Error> {
+    let elapsed = sqlx::query_scalar!(
+        r#"
+        UPDATE withdrawal_proofs
+        SET status = $2, updated_at = NOW()
+        WHERE withdrawal_id = $1
+        RETURNING EXTRACT(EPOCH FROM NOW() - submitted_at)::FLOAT8 AS "elapsed_seconds"
+        "#,
+        withdrawal_id,
+        status
+    )
+    .fetch_optional(conn)
+    .await?;
+
+    Ok(elapsed.flatten())
+}
+
+/// Stores the proof of withdrawal `withdrawal_id` with `status`, returning the seconds since it
+/// was submitted to Atlantic, if it was
+pub async fn store_withdrawal_proof(
+    conn: &mut PgConnection,
+    withdrawal_id: i32,
+    proof_params: &[u8],
+    proof_data: &[u8],
+    status: &str,
+) -> Result<Option<f64>, sqlx::Error> {
+    let elapsed = sqlx::query_scalar!(
+        r#"
+        INSERT INTO withdrawal_proofs (withdrawal_id, proof_params, proof_data, status)
+        VALUES ($1, $2, $3, $4)
+        ON CONFLICT (withdrawal_id) DO UPDATE
+        SET proof_params = EXCLUDED.proof_params,
+        proof_data = EXCLUDED.proof_data,
+        status = EXCLUDED.status,
+        updated_at = NOW()
+        RETURNING EXTRACT(EPOCH FROM NOW() - submitted_at)::FLOAT8 AS "elapsed_seconds"
+        "#,
+        withdrawal_id,
+        proof_params,
+        proof_data,
+        status
+    )
+    .fetch_one(conn)
+    .await?;
+
+    Ok(elapsed)
+}
+
+/// Proof of the withdrawal committed to by `commitment_hash`, once its proof job has stored it
+pub async fn fetch_ready_withdrawal_proof(
+    conn: &PgPool,
+    commitment_hash: &str,
+) -> Result<Option<Vec<u8>>, sqlx::Error> {
+    let commitment_hash = commitment_hash_arg(commitment_hash)?;
+    let proof = sqlx::query_scalar!(
+        r#"
+        SELECT wp.proof_data
+        FROM withdrawals w
+        JOIN withdrawal_proofs wp ON wp.withdrawal_id = w.id
+        WHERE w.commitment_hash = $1 AND wp.status = 'ready'
+        "#,
+        commitment_hash
+    )
+    .fetch_optional(conn)
+    .await?;
+
+    Ok(proof.flatten())
+}
+
+/// A withdrawal joined with its proof row, backing `GET /withdrawals/{id}/proof-status`
+#[derive(Debug, Clone, FromRow)]
+pub struct WithdrawalProofRow {
+    pub withdrawal_id: i32,
+    pub withdrawal_status: String,
+    /// `None` until a proof job has been submitted
+    pub proof_status: Option<String>,
+    pub atlantic_job_id: Option<String>,
+    pub submitted_at: Option<DateTime<Utc>>,
+}
+
+pub async fn fetch_withdrawal_proof(
+    conn: &PgPool,
+    withdrawal_id: i32,
+) -> Result<Option<WithdrawalProofRow>, sqlx::Error> {
+    sqlx::query_as!(
+        WithdrawalProofRow,
+        r#"
+        SELECT w.id AS withdrawal_id,
+            w.status AS withdrawal_status,
+            wp.status AS "proof_status?",
+            COALESCE(wp.atlantic_job_id, w.atlantic_job_id) AS atlantic_job_id,
+            wp.submitted_at AS "submitted_at?"
+        FROM withdrawals w
+        LEFT JOIN withdrawal_proofs wp ON wp.withdrawal_id = w.id
+        WHERE w.id = $1
+        "#,
+        withdrawal_id
+    )
+    .fetch_optional(conn)
+    .await
+}
+
+/// Folds `sample` into the running average stored under `key` in `system_stats`
+pub async fn record_system_stat_sample(
+    conn: &mut PgConnection,
     key: &str,
+    sample: f64,
+) -> Result<(), sqlx::Error> {
+    sqlx::query!(
+        r#"
+        INSERT INTO system_stats (key, value)
+        VALUES ($1, $2)
+        ON CONFLICT (key) DO UPDATE
+        SET value = (system_stats.value * system_stats.sample_count + EXCLUDED.value)
+            / (system_stats.sample_count + 1),
+        sample_count = system_stats.sample_count + 1,
+        updated_at = NOW()
+        "#,
+        key,
+        sample
+    )
+    .execute(conn)
+    .await?;
+
+    Ok(())
+}
+
+/// Running average stored under `key`, or `None` before its first sample
+pub async fn fetch_system_stat(conn: &PgPool, key: &str) -> Result<Option<f64>, sqlx::Error> {
+    sqlx::query_scalar!("SELECT value FROM system_stats WHERE key = $1", key)
+        .fetch_optional(conn)
+        .await
+}
+
+/// Identifies one watcher's row in `block_trackers`
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum BlockTrackerKey {
+    L1DepositEvents,
+    L1DepositHashEvents,
+    L2BurnEvents,
+    L2WithdrawalEvents,
+}
+
+impl BlockTrackerKey {
+    /// Key stored in the `block_trackers.key` column
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            BlockTrackerKey::L1DepositEvents => "l1_deposit_events_last_block",
+            BlockTrackerKey::L1DepositHashEvents => "l1_deposit_hash_events_last_block",
+            // Burn events were tracked alone under this key before withdrawals had their own
+            BlockTrackerKey::L2BurnEvents => "l2_events_last_block",
+            BlockTrackerKey::L2WithdrawalEvents => "l2_withdrawal_events_last_block",
+        }
+    }
+}
+
+impl fmt::Display for BlockTrackerKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+pub async fn update_last_processed_block(
+    conn: &PgPool,
+    key: BlockTrackerKey,
     block_number: u64,
+) -> Result<(), sqlx::Error> {
+    set_last_processed_block(conn, key, block_number, None).await
+}
+
+/// Moves tracker `key` to `block_number`, storing that block's hash for reorg checks.
+///
+/// A `None` hash clears the stored one, since it would belong to a different block.
+pub async fn set_last_processed_block(
+    conn: &PgPool,
+    key: BlockTrackerKey,
+    block_number: u64,
+    block_hash: Option<&str>,
+) -> Result<(), sqlx::Error> {
+    sqlx::query!(
+        r#"
+        INSERT INTO block_trackers (key, last_block, last_processed_block_hash)
+        VALUES ($1, $2, $3)
+        ON CONFLICT (key) DO UPDATE
+        SET last_block = $2, last_processed_block_hash = $3, updated_at = NOW()
+        "#,
+        key.as_str(),
+        block_number as i64,
+        block_hash
+    )
+    .execute(conn)
+    .await?;
+
+    Ok(())
+}
+
+pub async fn get_last_processed_block(
+    conn: &PgPool,
+    key: BlockTrackerKey,
+) -> Result<Option<u64>, sqlx::Error> {
+    let record = sqlx::query!(
+        r#"
+        SELECT last_block FROM block_trackers
+        WHERE key = $1
+        "#,
+        key.as_str()
+    )
+    .fetch_optional(conn)
+    .await?;
+
+    Ok(record.map(|r| r.last_block as u64))
+}
+
+/// Last processed block of tracker `key` along with its stored hash, if one was recorded
+pub async fn get_last_processed_block_with_hash(
+    conn: &PgPool,
+    key: BlockTrackerKey,
+) -> Result<Option<(u64, Option<String>)>, sqlx::Error> {
+    let record = sqlx::query!(
+        r#"
+        SELECT last_block, last_processed_block_hash FROM block_trackers
+        WHERE key = $1
+        "#,
+        key.as_str()
+    )
+    .fetch_optional(conn)
+    .await?;
+
+    Ok(record.map(|r| (r.last_block as u64, r.last_processed_block_hash)))
+}
+
+/// One row of `block_trackers`
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+pub struct BlockTrackerRow {
+    pub key: String,
+    pub last_block: i64,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Every watcher's progress, ordered by key
+pub async fn list_block_trackers(conn: &PgPool) -> Result<Vec<BlockTrackerRow>, sqlx::Error> {
+    sqlx::query_as!(
+        BlockTrackerRow,
+        r#"
+        SELECT key, last_block, updated_at
+        FROM block_trackers
+        ORDER BY key
+        "#
+    )
+    .fetch_all(conn)
+    .await
+}
+
+/// Number of deposits waiting to be processed
+pub async fn count_pending_deposits(conn: &PgPool) -> Result<i64, sqlx::Error> {
+    sqlx::query_scalar!(r#"SELECT COUNT(*) AS "count!" FROM deposits WHERE status = 'pending'"#)
+        .fetch_one(conn)
+        .await
+}
+
+/// Number of deposits the L1 queue has finished with
+pub async fn count_processed_deposits(conn: &PgPool) -> Result<i64, sqlx::Error> {
+    sqlx::query_scalar!(r#"SELECT COUNT(*) AS "count!" FROM deposits WHERE status = 'processed'"#)
+        .fetch_one(conn)
+        .await
+}
+
+/// Number of withdrawals waiting to be processed
+pub async fn count_pending_withdrawals(conn: &PgPool) -> Result<i64, sqlx::Error> {
+    sqlx::query_scalar!(r#"SELECT COUNT(*) AS "count!" FROM withdrawals WHERE status = 'pending'"#)
+        .fetch_one(conn)
+        .await
+}
+
+/// Number of `l2_transactions` in each status
+pub async fn count_l2_transactions_by_status(
+    conn: &PgPool,
+) -> Result<BTreeMap<String, i64>, sqlx::Error> {
+    let rows = sqlx::query!(
+        r#"
+        SELECT status, COUNT(*) AS "count!"
+        FROM l2_transactions
+        GROUP BY status
+        "#
+    )
+    .fetch_all(conn)
+    .await?;
+
+    Ok(rows
+        .into_iter()
+        .map(|row| (row.status, row.count))
+        .collect())
+}
+
+#[derive(Debug, Error)]
+pub enum BlockTrackerError {
+    #[error("Database error: {0}")]
+    Database(#[from] sqlx::Error),
+
+    #[error(transparent)]
+    Redis(#[from] RedisBlockTrackerError),
+}
+
+/// Last processed block of each event watcher, kept in Postgres unless a Redis server is
+/// shared between sequencer instances
+#[derive(Clone)]
+pub enum BlockTracker {
+    Postgres(PgBlockTracker),
+    Redis(RedisBlockTracker),
+}
+
+impl BlockTracker {
+    /// Tracker backed by the `block_trackers` table
+    pub fn new(pool: PgPool) -> Self {
+        BlockTracker::Postgres(PgBlockTracker::new(pool))
+    }
+
+    /// Last block recorded for `key`, or `None` if the watcher has never run
+    pub async fn get(&self, key: BlockTrackerKey) -> Result<Option<u64>, BlockTrackerError> {
+        match self {
+            BlockTracker::Postgres(tracker) => Ok(tracker.get(key).await?),
+            BlockTracker::Redis(tracker) => Ok(tracker.get(key).await?),
+        }
+    }
+
+    /// Records `block` as processed for `key`. The Redis tracker never moves back this way;
+    /// use [`BlockTracker::rewind`] for that.
+    pub async fn set(&self, key: BlockTrackerKey, block: u64) -> Result<(), BlockTrackerError> {
+        match self {
+            BlockTracker::Postgres(tracker) => Ok(tracker.set(key, block).await?),
+            BlockTracker::Redis(tracker) => Ok(tracker.set(key, block).await?),
+        }
+    }
+
+    /// Moves `key` back to `block`, so the blocks after it are read again
+    pub async fn rewind(&self, key: BlockTrackerKey, block: u64) -> Result<(), BlockTrackerError> {
+        match self {
+            BlockTracker::Postgres(tracker) => Ok(tracker.set(key, block).await?),
+            BlockTracker::Redis(tracker) => Ok(tracker.rewind(key, block).await?),
+        }
+    }
+}
+
+/// Last processed block of each event watcher, stored in `block_trackers`
+#[derive(Debug, Clone)]
+pub struct PgBlockTracker {
+    pool: PgPool,
+}
+
+impl PgBlockTracker {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Last block recorded for `key`, or `None` if the watcher has never run
+    pub async fn get(&self, key: BlockTrackerKey) -> Result<Option<u64>, sqlx::Error> {
+        get_last_processed_block(&self.pool, key).await
+    }
+
+    pub async fn set(&self, key: BlockTrackerKey, block: u64) -> Result<(), sqlx::Error> {
+        update_last_processed_block(&self.pool, key, block).await
+    }
+}
+
+/// Starknet transaction submitted for a proof job, one per contract call
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ProofJobTxHash {
+    pub id: i64,
+    pub proof_job_id: i64,
+    /// Call the transaction was submitted for, e.g. `initial`, `step3` or `final`
+    pub stage: String,
+    pub tx_hash: String,
+    pub submitted_at: DateTime<Utc>,
+}
+
+/// Records the transaction submitted for `stage` of proof job `proof_job_id` (its row id)
+pub async fn insert_proof_job_tx_hash(
+    conn: &PgPool,
+    proof_job_id: i64,
+    stage: &str,
+    tx_hash: &str,
+) -> Result<ProofJobTxHash, sqlx::Error> {
+    let mut tx = conn.begin().await?;
+
+    let row = sqlx::query_as!(
+        ProofJobTxHash,
+        r#"
+        INSERT INTO proof_job_tx_hashes (proof_job_id, stage, tx_hash)
+        VALUES ($1, $2, $3)
+        RETURNING id, proof_job_id, stage, tx_hash, submitted_at
+        "#,
+        proof_job_id,
+        stage,
+        tx_hash
+    )
+    .fetch_one(&mut *tx)
+    .await?;
+
+    sqlx::query!(
+        "UPDATE proof_jobs SET updated_at = NOW() WHERE id = $1",
+        proof_job_id
+    )
+    .execute(&mut *tx)
+    .await?;
+    tx.commit().await?;
+
+    Ok(row)
+}
+
+/// Transactions submitted for proof job `proof_job_id` (its row id), oldest first
+pub async fn fetch_proof_job_tx_hashes(
+    conn: &PgPool,
+    proof_job_id: i64,
+) -> Result<Vec<ProofJobTxHash>, sqlx::Error> {
+    sqlx::query_as!(
+        ProofJobTxHash,
+        r#"
+        SELECT id, proof_job_id, stage, tx_hash, submitted_at
+        FROM proof_job_tx_hashes
+        WHERE proof_job_id = $1
+        ORDER BY submitted_at, id
+        "#,
+        proof_job_id
+    )
+    .fetch_all(conn)
+    .await
+}
+
+/// Decimals and USD price of an L1 token, for checking what its withdrawals are worth
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, FromRow)]
+pub struct TokenMetadata {
+    /// Lowercase token address
+    pub address: String,
+    pub symbol: String,
+    pub decimals: i16,
+    /// Price of one whole token
+    pub usd_price_cents: i64,
+}
+
+/// Metadata of the token at `address`, matched case-insensitively
+pub async fn fetch_token_metadata(
+    conn: &PgPool,
+    address: &str,
+) -> Result<Option<TokenMetadata>, sqlx::Error> {
+    sqlx::query_as!(
+        TokenMetadata,
+        r#"
+        SELECT address, symbol, decimals, usd_price_cents
+        FROM token_metadata
+        WHERE address = LOWER($1)
+        "#,
+        address.trim()
+    )
+    .fetch_optional(conn)
+    .await
+}
+
+/// Inserts or replaces the metadata of `token.address`
+pub async fn upsert_token_metadata(
+    conn: &PgPool,
+    token: &TokenMetadata,
 ) -> Result<(), sqlx::Error> {
     sqlx::query!(
         r#"
-        INSERT INTO block_trackers (key, last_block)
-        VALUES ($1, $2)
-        ON CONFLICT (key) DO UPDATE
-        SET last_block = $2, updated_at = NOW()
+        INSERT INTO token_metadata (address, symbol, decimals, usd_price_cents)
+        VALUES (LOWER($1), $2, $3, $4)
+        ON CONFLICT (address) DO UPDATE
+        SET symbol = EXCLUDED.symbol,
+            decimals = EXCLUDED.decimals,
+            usd_price_cents = EXCLUDED.usd_price_cents
         "#,
-        key,
-        block_number as i64
+        token.address.trim(),
+        token.symbol,
+        token.decimals,
+        token.usd_price_cents
     )
     .execute(conn)
     .await?;
@@ -267,21 +1629,651 @@ pub async fn update_last_processed_block(
     Ok(())
 }
 
-pub async fn get_last_processed_block(
+/// `tvl_snapshots.chain` of the summed TVL of every L1 vault
+pub const L1_TOTAL_TVL_CHAIN: &str = "l1-total";
+/// `tvl_snapshots.chain` of the TVL reported by the L2 oracle
+pub const L2_TVL_CHAIN: &str = "l2";
+
+/// `tvl_snapshots.chain` of the L1 vault at `address`
+pub fn l1_vault_tvl_chain(address: &str) -> String {
+    format!("l1-{}", address)
+}
+
+/// TVL of one chain as read by the oracle service
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TvlSnapshot {
+    /// `l2`, `l1-total`, or `l1-<address>` for a single L1 vault
+    pub chain: String,
+    /// Decimal string, TVLs can exceed an `i64`
+    pub tvl_wei: String,
+    pub block_number: i64,
+    pub captured_at: DateTime<Utc>,
+}
+
+/// Records `tvl_wei` (a decimal string) read from `chain` at `block_number`
+pub async fn insert_tvl_snapshot(
     conn: &PgPool,
-    key: &str,
-) -> Result<Option<u64>, sqlx::Error> {
-    let record = sqlx::query!(
+    chain: &str,
+    tvl_wei: &str,
+    block_number: i64,
+) -> Result<(), sqlx::Error> {
+    sqlx::query!(
         r#"
-        SELECT last_block FROM block_trackers
-        WHERE key = $1
+        INSERT INTO tvl_snapshots (chain, tvl_wei, block_number)
+        VALUES ($1, $2::TEXT::NUMERIC, $3)
+        "#,
+        chain,
+        tvl_wei,
+        block_number
+    )
+    .execute(conn)
+    .await?;
+
+    Ok(())
+}
+
+/// Most recently captured TVL of `chain`, if any
+pub async fn fetch_latest_tvl_snapshot(
+    conn: &PgPool,
+    chain: &str,
+) -> Result<Option<TvlSnapshot>, sqlx::Error> {
+    sqlx::query_as!(
+        TvlSnapshot,
+        r#"
+        SELECT chain, tvl_wei::TEXT AS "tvl_wei!", block_number, captured_at
+        FROM tvl_snapshots
+        WHERE chain = $1
+        ORDER BY captured_at DESC, id DESC
+        LIMIT 1
+        "#,
+        chain
+    )
+    .fetch_optional(conn)
+    .await
+}
+
+/// Filters for listing proof jobs; `None` fields are not applied
+#[derive(Debug, Clone)]
+pub struct ProofJobFilter {
+    pub status: Option<String>,
+    pub created_after: Option<DateTime<Utc>>,
+    pub created_before: Option<DateTime<Utc>>,
+    pub limit: i64,
+}
+
+pub async fn list_proof_jobs(
+    conn: &PgPool,
+    filter: ProofJobFilter,
+) -> Result<Vec<ProofJob>, sqlx::Error> {
+    let rows = sqlx::query!(
+        r#"
+        SELECT id, job_id, calldata_dir, layout, hasher, stone_version, memory_verification, status, current_stage, retry_count, error_message,
+            (SELECT jsonb_object_agg(h.stage, h.tx_hash ORDER BY h.submitted_at, h.id) FROM proof_job_tx_hashes h WHERE h.proof_job_id = proof_jobs.id) AS tx_hashes,
+            stage_started_at, fact_hash, calldata_hash, metadata
+        FROM proof_jobs
+        WHERE ($1::TEXT IS NULL OR status = $1)
+        AND ($2::TIMESTAMPTZ IS NULL OR created_at >= $2)
+        AND ($3::TIMESTAMPTZ IS NULL OR created_at < $3)
+        ORDER BY created_at DESC
+        LIMIT $4
+        "#,
+        filter.status,
+        filter.created_after,
+        filter.created_before,
+        filter.limit
+    )
+    .fetch_all(conn)
+    .await?;
+
+    let jobs = rows
+        .into_iter()
+        .map(|row| ProofJob {
+            id: row.id,
+            job_id: row.job_id,
+            calldata_dir: row.calldata_dir,
+            layout: row.layout,
+            hasher: row.hasher,
+            stone_version: row.stone_version,
+            memory_verification: row.memory_verification,
+            status: row.status,
+            current_stage: row.current_stage,
+            retry_count: row.retry_count,
+            error_message: row.error_message,
+            tx_hashes: row.tx_hashes.unwrap_or_else(|| serde_json::json!({})),
+            stage_started_at: row.stage_started_at,
+            fact_hash: row.fact_hash,
+            calldata_hash: row.calldata_hash,
+            metadata: row.metadata,
+        })
+        .collect();
+
+    Ok(jobs)
+}
+
+pub async fn fetch_proof_job_by_job_id(
+    conn: &PgPool,
+    job_id: i64,
+) -> Result<Option<ProofJob>, sqlx::Error> {
+    let row = sqlx::query!(
+        r#"
+        SELECT id, job_id, calldata_dir, layout, hasher, stone_version, memory_verification, status, current_stage, retry_count, error_message,
+            (SELECT jsonb_object_agg(h.stage, h.tx_hash ORDER BY h.submitted_at, h.id) FROM proof_job_tx_hashes h WHERE h.proof_job_id = proof_jobs.id) AS tx_hashes,
+            stage_started_at, fact_hash, calldata_hash, metadata
+        FROM proof_jobs
+        WHERE job_id = $1
         "#,
-        key
+        job_id
     )
     .fetch_optional(conn)
     .await?;
 
-    Ok(record.map(|r| r.last_block as u64))
+    Ok(row.map(|row| ProofJob {
+        id: row.id,
+        job_id: row.job_id,
+        calldata_dir: row.calldata_dir,
+        layout: row.layout,
+        hasher: row.hasher,
+        stone_version: row.stone_version,
+        memory_verification: row.memory_verification,
+        status: row.status,
+        current_stage: row.current_stage,
+        retry_count: row.retry_count,
+        error_message: row.error_message,
+        tx_hashes: row.tx_hashes.unwrap_or_else(|| serde_json::json!({})),
+        stage_started_at: row.stage_started_at,
+        fact_hash: row.fact_hash,
+        calldata_hash: row.calldata_hash,
+        metadata: row.metadata,
+    }))
+}
+
+/// Stage the proof generation pipeline last reported for a job, written by the pipeline as it
+/// runs
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, FromRow)]
+pub struct ProofPipelineProgress {
+    pub job_id: i64,
+    /// `execution`, `proof_generation`, `verification`, `calldata_preparation` or `completed`
+    pub stage: String,
+    pub stage_started_at: DateTime<Utc>,
+}
+
+pub async fn fetch_proof_pipeline_progress(
+    conn: &PgPool,
+    job_id: i64,
+) -> Result<Option<ProofPipelineProgress>, sqlx::Error> {
+    sqlx::query_as!(
+        ProofPipelineProgress,
+        r#"
+        SELECT job_id, stage, stage_started_at
+        FROM proof_pipeline_progress
+        WHERE job_id = $1
+        "#,
+        job_id
+    )
+    .fetch_optional(conn)
+    .await
+}
+
+/// Deep-merges `patch` into the metadata of proof job `job_id` (see `deep_merge_json`),
+/// returning the merged metadata, or `None` if there is no such job
+pub async fn merge_proof_job_metadata(
+    conn: &PgPool,
+    job_id: i64,
+    patch: serde_json::Value,
+) -> Result<Option<serde_json::Value>, sqlx::Error> {
+    let mut tx = conn.begin().await?;
+
+    let Some(mut metadata) = sqlx::query_scalar!(
+        "SELECT metadata FROM proof_jobs WHERE job_id = $1 FOR UPDATE",
+        job_id
+    )
+    .fetch_optional(&mut *tx)
+    .await?
+    else {
+        return Ok(None);
+    };
+    deep_merge_json(&mut metadata, patch);
+
+    sqlx::query!(
+        "UPDATE proof_jobs SET metadata = $2, updated_at = NOW() WHERE job_id = $1",
+        job_id,
+        metadata
+    )
+    .execute(&mut *tx)
+    .await?;
+    tx.commit().await?;
+
+    Ok(Some(metadata))
+}
+
+/// Stores a proof job handed over from another sequencer instance, keeping its stage and
+/// progress, and the transaction hashes in its `tx_hashes` object; a `tx_hashes` that is not an
+/// object records none. `job.id` is ignored; the row gets a new id here. Fails with a unique violation when a job with the same `job_id` already
+/// exists.
+pub async fn import_proof_job(conn: &PgPool, job: &ProofJob) -> Result<ProofJob, sqlx::Error> {
+    let mut tx = conn.begin().await?;
+
+    let row = sqlx::query!(
+        r#"
+        INSERT INTO proof_jobs (job_id, calldata_dir, layout, hasher, stone_version, memory_verification, status, current_stage, retry_count, error_message, stage_started_at, fact_hash, calldata_hash, metadata)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14)
+        RETURNING id
+        "#,
+        job.job_id,
+        job.calldata_dir,
+        job.layout,
+        job.hasher,
+        job.stone_version,
+        job.memory_verification,
+        job.status,
+        job.current_stage,
+        job.retry_count,
+        job.error_message,
+        job.stage_started_at,
+        job.fact_hash,
+        job.calldata_hash,
+        job.metadata
+    )
+    .fetch_one(&mut *tx)
+    .await?;
+
+    sqlx::query!(
+        r#"
+        INSERT INTO proof_job_tx_hashes (proof_job_id, stage, tx_hash)
+        SELECT $1, key, value
+        FROM jsonb_each_text(CASE WHEN jsonb_typeof($2) = 'object' THEN $2 ELSE '{}' END)
+        "#,
+        row.id,
+        job.tx_hashes
+    )
+    .execute(&mut *tx)
+    .await?;
+    tx.commit().await?;
+
+    Ok(ProofJob {
+        id: row.id,
+        ..job.clone()
+    })
+}
+
+/// Average seconds per contract call over the `sample_size` most recently completed proof jobs,
+/// or `None` when no job has completed yet
+pub async fn average_proof_step_duration(
+    conn: &PgPool,
+    sample_size: i64,
+) -> Result<Option<f64>, sqlx::Error> {
+    let average = sqlx::query_scalar!(
+        r#"
+        SELECT AVG(average_step_duration_seconds)
+        FROM (
+            SELECT average_step_duration_seconds
+            FROM proof_job_stats
+            ORDER BY completed_at DESC
+            LIMIT $1
+        ) recent
+        "#,
+        sample_size
+    )
+    .fetch_one(conn)
+    .await?;
+
+    Ok(average)
+}
+
+/// Move `completed` and `failed` proof jobs last updated more than `retention_days` ago into
+/// `proof_jobs_archive`. Returns the number of archived rows.
+pub async fn cleanup_old_proof_jobs(
+    pool: &PgPool,
+    retention_days: u32,
+) -> Result<u64, sqlx::Error> {
+    let mut tx = pool.begin().await?;
+
+    let archived = sqlx::query!(
+        r#"
+        INSERT INTO proof_jobs_archive (id, job_id, calldata_dir, layout, hasher, stone_version, memory_verification, status, current_stage, retry_count, error_message, tx_hashes, stage_started_at, fact_hash, calldata_hash, max_total_fee, metadata, created_at, updated_at)
+        SELECT id, job_id, calldata_dir, layout, hasher, stone_version, memory_verification, status, current_stage, retry_count, error_message,
+            COALESCE((SELECT jsonb_object_agg(h.stage, h.tx_hash ORDER BY h.submitted_at, h.id) FROM proof_job_tx_hashes h WHERE h.proof_job_id = proof_jobs.id), '{}'),
+            stage_started_at, fact_hash, calldata_hash, max_total_fee, metadata, created_at, updated_at
+        FROM proof_jobs
+        WHERE status IN ('completed', 'failed')
+        AND updated_at < NOW() - make_interval(days => $1)
+        RETURNING id
+        "#,
+        retention_days as i32
+    )
+    .fetch_all(&mut *tx)
+    .await?;
+
+    let ids: Vec<i64> = archived.into_iter().map(|row| row.id).collect();
+
+    sqlx::query!("DELETE FROM proof_jobs WHERE id = ANY($1)", &ids)
+        .execute(&mut *tx)
+        .await?;
+
+    tx.commit().await?;
+
+    Ok(ids.len() as u64)
+}
+
+/// Stores a `WithdrawalHashAppended` event, returning whether it was new. Replayed events are
+/// ignored.
+pub async fn upsert_withdrawal_commitment_log(
+    conn: &PgPool,
+    log: &WithdrawalCommitmentLog,
+) -> Result<bool, sqlx::Error> {
+    let commitment_hash = commitment_hash_arg(&log.commitment_hash)?;
+    let result = sqlx::query!(
+        r#"
+        INSERT INTO withdrawal_commitment_logs (index, commitment_hash, root_hash, elements_count, block_number, transaction_hash)
+        VALUES ($1, $2, $3, $4, $5, $6)
+        ON CONFLICT (transaction_hash) DO NOTHING
+        "#,
+        log.index,
+        commitment_hash,
+        log.root_hash,
+        log.elements_count,
+        log.block_number as i64,
+        log.transaction_hash
+    )
+    .execute(conn)
+    .await?;
+
+    Ok(result.rows_affected() == 1)
+}
+
+/// Stores an L2 burn event for `WithdrawalMatcher`; replayed events are ignored. Returns whether
+/// the event was new.
+pub async fn insert_l2_burn_event(
+    conn: &PgPool,
+    log: &CommitmentLog,
+    amount: i64,
+) -> Result<bool, sqlx::Error> {
+    let commitment_hash = commitment_hash_arg(&log.commitment_hash)?;
+    let result = sqlx::query!(
+        r#"
+        INSERT INTO l2_burn_events (stark_pub_key, amount, commitment_hash, block_number, transaction_hash)
+        VALUES ($1, $2, $3, $4, $5)
+        ON CONFLICT (commitment_hash) DO NOTHING
+        "#,
+        log.user,
+        amount,
+        commitment_hash,
+        log.block_number as i64,
+        log.transaction_hash
+    )
+    .execute(conn)
+    .await?;
+
+    Ok(result.rows_affected() == 1)
+}
+
+/// Links each pending withdrawal to the L2 burn event with the same stark key, amount and
+/// commitment hash, moving it to `matched` under `actor`. Returns `(withdrawal_id,
+/// burn_event_id)` pairs.
+///
+/// A burn is linked to at most one withdrawal, the oldest, and never to a second one later.
+pub async fn match_pending_withdrawals(
+    conn: &PgPool,
+    actor: &str,
+) -> Result<Vec<(i32, i32)>, sqlx::Error> {
+    let mut tx = conn.begin().await?;
+    let rows = sqlx::query!(
+        r#"
+        UPDATE withdrawals
+        SET l2_tx_id = matches.burn_id, updated_at = NOW()
+        FROM (
+            SELECT DISTINCT ON (b.id) b.id AS burn_id, w.id AS withdrawal_id
+            FROM l2_burn_events b
+            JOIN withdrawals w
+              ON w.stark_pub_key = b.stark_pub_key
+             AND w.amount = b.amount
+             AND w.commitment_hash = b.commitment_hash
+            WHERE w.status = 'pending'
+              AND w.l2_tx_id IS NULL
+              AND NOT EXISTS (SELECT 1 FROM withdrawals linked WHERE linked.l2_tx_id = b.id)
+            ORDER BY b.id, w.created_at, w.id
+        ) matches
+        WHERE withdrawals.id = matches.withdrawal_id
+        RETURNING withdrawals.id AS "withdrawal_id!", matches.burn_id AS "burn_id!"
+        "#
+    )
+    .fetch_all(&mut *tx)
+    .await?;
+
+    for row in &rows {
+        update_withdrawal_status(&mut tx, row.withdrawal_id, "matched", actor).await?;
+    }
+    tx.commit().await?;
+
+    Ok(rows
+        .into_iter()
+        .map(|row| (row.withdrawal_id, row.burn_id))
+        .collect())
+}
+
+/// Fetches withdrawal commitment logs within an inclusive block range; `None` bounds are open
+pub async fn fetch_withdrawal_commitment_logs(
+    conn: &PgPool,
+    from_block: Option<i64>,
+    to_block: Option<i64>,
+) -> Result<Vec<WithdrawalCommitmentLog>, sqlx::Error> {
+    let rows = sqlx::query!(
+        r#"
+        SELECT index, commitment_hash, root_hash, elements_count, block_number, transaction_hash
+        FROM withdrawal_commitment_logs
+        WHERE ($1::BIGINT IS NULL OR block_number >= $1)
+        AND ($2::BIGINT IS NULL OR block_number <= $2)
+        ORDER BY block_number ASC, id ASC
+        "#,
+        from_block,
+        to_block
+    )
+    .fetch_all(conn)
+    .await?;
+
+    let logs = rows
+        .into_iter()
+        .map(|row| WithdrawalCommitmentLog {
+            index: row.index,
+            commitment_hash: row.commitment_hash,
+            root_hash: row.root_hash,
+            elements_count: row.elements_count,
+            block_number: row.block_number as u64,
+            transaction_hash: row.transaction_hash,
+        })
+        .collect();
+
+    Ok(logs)
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DeadLetterL2Transaction {
+    pub id: i64,
+    /// Id of the `l2_transactions` row this entry was copied from
+    pub l2_transaction_id: i64,
+    pub stark_pub_key: String,
+    pub amount: i64,
+    pub token_address: String,
+    pub status: String,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    pub retry_count: i32,
+    pub tx_hash: Option<String>,
+    pub error: Option<String>,
+    pub proof_data: Option<String>,
+    pub priority: i16,
+    pub failed_reason: Option<String>,
+    pub failed_at: DateTime<Utc>,
+    pub requeued_at: Option<DateTime<Utc>>,
+    #[serde(serialize_with = "serialize_optional_prefixed_commitment_hash")]
+    pub commitment_hash: Option<String>,
+}
+
+/// Copies a failed L2 transaction into the dead-letter table
+pub async fn insert_dead_letter_l2_transaction(
+    conn: &PgPool,
+    l2_transaction_id: i64,
+    failed_reason: Option<&str>,
+) -> Result<(), sqlx::Error> {
+    sqlx::query!(
+        r#"
+        INSERT INTO dead_letter_l2_transactions (l2_transaction_id, stark_pub_key, amount, token_address, status, created_at, updated_at, retry_count, tx_hash, error, proof_data, priority, failed_reason, commitment_hash)
+        SELECT id, stark_pub_key, amount, token_address, status, created_at, updated_at, retry_count, tx_hash, error, proof_data, priority, $2, commitment_hash
+        FROM l2_transactions
+        WHERE id = $1
+        "#,
+        l2_transaction_id,
+        failed_reason
+    )
+    .execute(conn)
+    .await?;
+
+    Ok(())
+}
+
+pub async fn fetch_dead_letter_l2_transactions(
+    conn: &PgPool,
+    limit: i64,
+) -> Result<Vec<DeadLetterL2Transaction>, sqlx::Error> {
+    let entries = sqlx::query_as!(
+        DeadLetterL2Transaction,
+        r#"
+        SELECT *
+        FROM dead_letter_l2_transactions
+        ORDER BY failed_at DESC
+        LIMIT $1
+        "#,
+        limit
+    )
+    .fetch_all(conn)
+    .await?;
+
+    Ok(entries)
+}
+
+/// Re-enqueues a dead-letter entry as a fresh `pending` L2 transaction.
+///
+/// Returns the new `l2_transactions` id, or `None` if the entry does not exist or was already
+/// requeued.
+pub async fn requeue_dead_letter_l2_transaction(
+    pool: &PgPool,
+    id: i64,
+) -> Result<Option<i64>, sqlx::Error> {
+    let mut tx = pool.begin().await?;
+
+    let requeued = sqlx::query!(
+        r#"
+        UPDATE dead_letter_l2_transactions
+        SET requeued_at = NOW()
+        WHERE id = $1 AND requeued_at IS NULL
+        RETURNING stark_pub_key, amount, token_address, priority, commitment_hash
+        "#,
+        id
+    )
+    .fetch_optional(&mut *tx)
+    .await?;
+
+    let Some(entry) = requeued else {
+        return Ok(None);
+    };
+
+    let new_id = sqlx::query_scalar!(
+        r#"
+        INSERT INTO l2_transactions (stark_pub_key, amount, token_address, status, retry_count, priority, commitment_hash)
+        VALUES ($1, $2, $3, 'pending', 0, $4, $5)
+        RETURNING id
+        "#,
+        entry.stark_pub_key,
+        entry.amount,
+        entry.token_address,
+        entry.priority,
+        entry.commitment_hash
+    )
+    .fetch_one(&mut *tx)
+    .await?;
+
+    tx.commit().await?;
+
+    Ok(Some(new_id))
+}
+
+/// Puts failed L2 transactions back in the queue with a fresh retry budget. Only transactions
+/// in `ids`, when given, that failed within the last `max_age_hours`, when given, are reset.
+///
+/// Returns the number of transactions requeued.
+pub async fn requeue_failed_l2_transactions(
+    pool: &PgPool,
+    ids: Option<Vec<i64>>,
+    max_age_hours: Option<u32>,
+) -> Result<u64, sqlx::Error> {
+    let result = sqlx::query!(
+        r#"
+        UPDATE l2_transactions
+        SET status = 'pending',
+        retry_count = 0,
+        error = NULL,
+        updated_at = NOW()
+        WHERE status = 'failed'
+        AND ($1::BIGINT[] IS NULL OR id = ANY($1))
+        AND ($2::INT IS NULL OR updated_at >= NOW() - make_interval(hours => $2))
+        "#,
+        ids.as_deref(),
+        max_age_hours.map(|hours| hours as i32)
+    )
+    .execute(pool)
+    .await?;
+
+    Ok(result.rows_affected())
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct HeartbeatStatus {
+    pub instance_id: String,
+    pub last_seen_at: DateTime<Utc>,
+    /// Whether the instance has reported within the liveness window
+    pub is_alive: bool,
+}
+
+/// Runs a trivial query on `conn`, failing when the database cannot be reached through it
+pub async fn ping_database(conn: &PgPool) -> Result<(), sqlx::Error> {
+    sqlx::query!("SELECT 1 AS one").fetch_one(conn).await?;
+
+    Ok(())
+}
+
+pub async fn upsert_heartbeat(conn: &PgPool, instance_id: &str) -> Result<(), sqlx::Error> {
+    sqlx::query!(
+        r#"
+        INSERT INTO sequencer_heartbeat (instance_id)
+        VALUES ($1)
+        ON CONFLICT (instance_id) DO UPDATE SET last_seen_at = NOW()
+        "#,
+        instance_id
+    )
+    .execute(conn)
+    .await?;
+
+    Ok(())
+}
+
+pub async fn fetch_heartbeat_status(
+    conn: &PgPool,
+    instance_id: &str,
+) -> Result<Option<HeartbeatStatus>, sqlx::Error> {
+    let status = sqlx::query_as!(
+        HeartbeatStatus,
+        r#"
+        SELECT instance_id, last_seen_at,
+        last_seen_at > NOW() - INTERVAL '2 minutes' AS "is_alive!"
+        FROM sequencer_heartbeat
+        WHERE instance_id = $1
+        "#,
+        instance_id
+    )
+    .fetch_optional(conn)
+    .await?;
+
+    Ok(status)
 }
 
 pub async fn get_db_pool(database_url: &str) -> Result<PgPool, sqlx::Error> {
@@ -290,3 +2282,49 @@ pub async fn get_db_pool(database_url: &str) -> Result<PgPool, sqlx::Error> {
         .connect(database_url)
         .await
 }
+
+/// Ethereum address a Stark key has proved ownership of
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+pub struct UserMapping {
+    pub starknet_address: String,
+    pub ethereum_address: String,
+    pub verified_at: Option<DateTime<Utc>>,
+}
+
+/// Records a verified mapping; fails with a unique violation if either address is already
+/// mapped
+pub async fn insert_user_mapping(
+    conn: &PgPool,
+    starknet_address: &str,
+    ethereum_address: &str,
+) -> Result<UserMapping, sqlx::Error> {
+    sqlx::query_as!(
+        UserMapping,
+        r#"
+        INSERT INTO user_mappings (starknet_address, ethereum_address, verified_at)
+        VALUES ($1, $2, NOW())
+        RETURNING starknet_address, ethereum_address, verified_at
+        "#,
+        starknet_address,
+        ethereum_address
+    )
+    .fetch_one(conn)
+    .await
+}
+
+pub async fn fetch_user_mapping(
+    conn: &PgPool,
+    starknet_address: &str,
+) -> Result<Option<UserMapping>, sqlx::Error> {
+    sqlx::query_as!(
+        UserMapping,
+        r#"
+        SELECT starknet_address, ethereum_address, verified_at
+        FROM user_mappings
+        WHERE starknet_address = $1
+        "#,
+        starknet_address
+    )
+    .fetch_optional(conn)
+    .await
+}
@@ -0,0 +1,41 @@
+use sqlx::migrate::MigrateError;
+use sqlx::PgPool;
+use std::time::Duration;
+use thiserror::Error;
+use tokio::time::timeout;
+
+#[derive(Debug, Error)]
+pub enum MigrationError {
+    #[error(
+        "Database migrations timed out after {0}s — is another instance holding the migration lock?"
+    )]
+    TimedOut(u64),
+
+    #[error("Database migrations failed: {0}")]
+    Failed(#[from] MigrateError),
+}
+
+/// Applies the embedded `migrations/` directory, giving up instead of waiting forever when
+/// another process holds the migration lock
+pub struct SqlxMigrationRunner {
+    pool: PgPool,
+    timeout_seconds: u64,
+}
+
+impl SqlxMigrationRunner {
+    pub fn new(pool: PgPool, timeout_seconds: u64) -> Self {
+        Self {
+            pool,
+            timeout_seconds,
+        }
+    }
+
+    pub async fn run_with_timeout(&self) -> Result<(), MigrationError> {
+        let migrations = sqlx::migrate!("./migrations").run(&self.pool);
+
+        match timeout(Duration::from_secs(self.timeout_seconds), migrations).await {
+            Ok(result) => Ok(result?),
+            Err(_) => Err(MigrationError::TimedOut(self.timeout_seconds)),
+        }
+    }
+}
@@ -1,2 +1,4 @@
 pub mod client;
 pub mod database;
+pub mod migrations;
+pub mod redis_block_tracker;
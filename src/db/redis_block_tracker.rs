@@ -0,0 +1,129 @@
+use redis::{AsyncCommands, Client, RedisError, Script};
+use std::time::Duration;
+use thiserror::Error;
+use tokio::time::{sleep, Instant};
+use uuid::Uuid;
+
+use crate::db::database::BlockTrackerKey;
+
+/// How long a holder keeps a tracker's lock before Redis expires it
+const LOCK_TTL: Duration = Duration::from_secs(5);
+/// How long `set` waits for another instance to release the lock
+const LOCK_TIMEOUT: Duration = Duration::from_secs(10);
+const LOCK_RETRY_DELAY: Duration = Duration::from_millis(20);
+
+/// Deletes the lock only while it still holds our token, so an expired lock taken over by
+/// another instance is left alone
+const RELEASE_LOCK_SCRIPT: &str = r#"
+if redis.call("GET", KEYS[1]) == ARGV[1] then
+    return redis.call("DEL", KEYS[1])
+end
+return 0
+"#;
+
+#[derive(Debug, Error)]
+pub enum RedisBlockTrackerError {
+    #[error("Redis error: {0}")]
+    Redis(#[from] RedisError),
+
+    #[error("Timed out waiting for the lock on block tracker {0}")]
+    LockTimeout(BlockTrackerKey),
+}
+
+/// Block trackers kept in Redis, shared by every sequencer instance pointed at the same server.
+///
+/// Updates take a `SET NX` lock on the tracker and `set` only ever moves it forward, so instances
+/// racing on the same key cannot move it back to a block another one has already passed. Moving
+/// it back after a reorg takes an explicit `rewind`.
+#[derive(Clone)]
+pub struct RedisBlockTracker {
+    client: Client,
+}
+
+impl RedisBlockTracker {
+    pub fn new(redis_url: &str) -> Result<Self, RedisError> {
+        Ok(Self {
+            client: Client::open(redis_url)?,
+        })
+    }
+
+    /// Last block recorded for `key`, or `None` if the watcher has never run
+    pub async fn get(&self, key: BlockTrackerKey) -> Result<Option<u64>, RedisBlockTrackerError> {
+        let mut conn = self.client.get_multiplexed_async_connection().await?;
+        Ok(conn.get(block_key(key)).await?)
+    }
+
+    /// Moves `key` to `block`, leaving it untouched if it is already at or past `block`
+    pub async fn set(
+        &self,
+        key: BlockTrackerKey,
+        block: u64,
+    ) -> Result<(), RedisBlockTrackerError> {
+        self.update(key, block, false).await
+    }
+
+    /// Moves `key` to `block` even when it is already past it, e.g. to re-read reorged blocks
+    pub async fn rewind(
+        &self,
+        key: BlockTrackerKey,
+        block: u64,
+    ) -> Result<(), RedisBlockTrackerError> {
+        self.update(key, block, true).await
+    }
+
+    async fn update(
+        &self,
+        key: BlockTrackerKey,
+        block: u64,
+        backwards: bool,
+    ) -> Result<(), RedisBlockTrackerError> {
+        let mut conn = self.client.get_multiplexed_async_connection().await?;
+        let lock = lock_key(key);
+        let token = Uuid::new_v4().to_string();
+
+        let deadline = Instant::now() + LOCK_TIMEOUT;
+        loop {
+            let acquired: bool = redis::cmd("SET")
+                .arg(&lock)
+                .arg(&token)
+                .arg("NX")
+                .arg("PX")
+                .arg(LOCK_TTL.as_millis() as u64)
+                .query_async::<Option<String>>(&mut conn)
+                .await?
+                .is_some();
+            if acquired {
+                break;
+            }
+            if Instant::now() >= deadline {
+                return Err(RedisBlockTrackerError::LockTimeout(key));
+            }
+            sleep(LOCK_RETRY_DELAY).await;
+        }
+
+        let result = async {
+            let current: Option<u64> = conn.get(block_key(key)).await?;
+            if backwards || current.is_none_or(|current| block > current) {
+                let _: () = conn.set(block_key(key), block).await?;
+            }
+            Ok::<_, RedisError>(())
+        }
+        .await;
+
+        let _: i32 = Script::new(RELEASE_LOCK_SCRIPT)
+            .key(&lock)
+            .arg(&token)
+            .invoke_async(&mut conn)
+            .await?;
+
+        Ok(result?)
+    }
+}
+
+fn block_key(key: BlockTrackerKey) -> String {
+    format!("block_trackers:{}", key.as_str())
+}
+
+fn lock_key(key: BlockTrackerKey) -> String {
+    format!("block_trackers:{}:lock", key.as_str())
+}
@@ -0,0 +1,40 @@
+use tokio::sync::broadcast;
+
+/// Default number of events buffered per subscriber before it starts lagging
+pub const DEFAULT_EVENT_BUS_CAPACITY: usize = 1024;
+
+/// Fan-out event dispatcher so multiple services can react to the same events.
+///
+/// Cloning the bus is cheap and every clone publishes to the same set of subscribers.
+#[derive(Debug, Clone)]
+pub struct EventBus<T> {
+    sender: broadcast::Sender<T>,
+}
+
+impl<T: Clone> EventBus<T> {
+    pub fn new(capacity: usize) -> Self {
+        let (sender, _) = broadcast::channel(capacity);
+        Self { sender }
+    }
+
+    /// Publishes an event to all current subscribers, returning how many received it
+    pub fn publish(&self, event: T) -> usize {
+        // Sending only fails when there are no subscribers, which is not an error for us
+        self.sender.send(event).unwrap_or(0)
+    }
+
+    /// Creates a new subscription that receives every event published from now on
+    pub fn subscribe(&self) -> broadcast::Receiver<T> {
+        self.sender.subscribe()
+    }
+
+    pub fn subscriber_count(&self) -> usize {
+        self.sender.receiver_count()
+    }
+}
+
+impl<T: Clone> Default for EventBus<T> {
+    fn default() -> Self {
+        Self::new(DEFAULT_EVENT_BUS_CAPACITY)
+    }
+}
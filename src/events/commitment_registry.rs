@@ -0,0 +1,93 @@
+use dashmap::DashMap;
+use sqlx::PgPool;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use crate::db::database::fetch_commitment_block_number;
+use crate::utils::normalize_commitment_hash;
+
+/// What the registry last learned about a commitment hash
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ConfirmationStatus {
+    /// Whether a `DepositEvent` with the hash has been seen on L1
+    pub confirmed: bool,
+    /// Block of that event; 0 while unconfirmed
+    pub block_number: u64,
+    pub cached_at: Instant,
+}
+
+/// In-memory cache of L1 commitment confirmations, so each queue cycle does not query the
+/// database again for hashes it has already checked.
+///
+/// Entries older than the TTL are looked up again. Only confirmations are cached, so a deposit
+/// whose event arrives after a miss is seen on the next lookup; the L1 event watcher also records
+/// hashes as their events are fetched. Hashes are keyed in their normalized form, whatever
+/// padding or prefix callers use. Cloning the registry is cheap and every clone shares the same
+/// entries.
+#[derive(Debug, Clone)]
+pub struct CommitmentHashRegistry {
+    entries: Arc<DashMap<String, ConfirmationStatus>>,
+    ttl: Duration,
+    db_lookups: Arc<AtomicU64>,
+}
+
+impl CommitmentHashRegistry {
+    pub fn new(ttl: Duration) -> Self {
+        Self {
+            entries: Arc::new(DashMap::new()),
+            ttl,
+            db_lookups: Arc::new(AtomicU64::new(0)),
+        }
+    }
+
+    /// Records that the event carrying `commitment_hash` was seen in `block_number`
+    pub fn record_confirmed(&self, commitment_hash: &str, block_number: u64) {
+        self.entries.insert(
+            entry_key(commitment_hash),
+            ConfirmationStatus {
+                confirmed: true,
+                block_number,
+                cached_at: Instant::now(),
+            },
+        );
+    }
+
+    /// The cached status of `commitment_hash`, unless it is missing or older than the TTL
+    pub fn get(&self, commitment_hash: &str) -> Option<ConfirmationStatus> {
+        self.entries
+            .get(&entry_key(commitment_hash))
+            .map(|entry| *entry)
+            .filter(|status| status.cached_at.elapsed() <= self.ttl)
+    }
+
+    /// Whether `commitment_hash` is confirmed on L1, answered from the cache when it is fresh
+    /// and from `db_pool` otherwise
+    pub async fn is_confirmed(
+        &self,
+        db_pool: &PgPool,
+        commitment_hash: &str,
+    ) -> Result<bool, sqlx::Error> {
+        if let Some(status) = self.get(commitment_hash) {
+            return Ok(status.confirmed);
+        }
+
+        self.db_lookups.fetch_add(1, Ordering::Relaxed);
+        let block_number = fetch_commitment_block_number(db_pool, commitment_hash).await?;
+        if let Some(block_number) = block_number {
+            self.record_confirmed(commitment_hash, block_number);
+        }
+
+        Ok(block_number.is_some())
+    }
+
+    /// Database lookups made by `is_confirmed` so far, i.e. its cache misses
+    pub fn db_lookups(&self) -> u64 {
+        self.db_lookups.load(Ordering::Relaxed)
+    }
+}
+
+/// `commitment_hash` in the form the database stores, or as given when it is not valid hex
+fn entry_key(commitment_hash: &str) -> String {
+    normalize_commitment_hash(commitment_hash).unwrap_or_else(|_| commitment_hash.to_string())
+}
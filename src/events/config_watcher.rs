@@ -0,0 +1,114 @@
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tokio::sync::watch;
+use tracing::{error, info};
+
+use crate::config::{load_config, AppConfig, ConfigValidator};
+
+#[derive(Debug, thiserror::Error)]
+pub enum ConfigReloadError {
+    #[error("Config reloading is not enabled: no config file path was set")]
+    NoConfigPath,
+
+    #[error("Failed to load config file {path}: {message}")]
+    Load { path: String, message: String },
+
+    #[error("Invalid configuration: {}", .0.join("; "))]
+    Invalid(Vec<String>),
+}
+
+/// Latest-value channel for `AppConfig`, so long-running components can pick up a reloaded
+/// config at the start of their next cycle instead of needing a restart.
+///
+/// A reload only publishes configs that pass `ConfigValidator`; an invalid file leaves the
+/// current config in place. Cloning the watcher is cheap and every clone publishes to the same
+/// subscribers.
+#[derive(Debug, Clone)]
+pub struct ConfigWatcher {
+    sender: Arc<watch::Sender<AppConfig>>,
+    /// File re-read by `reload`; `None` disables reloading
+    path: Option<PathBuf>,
+}
+
+impl ConfigWatcher {
+    pub fn new(config: AppConfig) -> Self {
+        let (sender, _) = watch::channel(config);
+        Self {
+            sender: Arc::new(sender),
+            path: None,
+        }
+    }
+
+    pub fn with_config_path(mut self, path: impl AsRef<Path>) -> Self {
+        self.path = Some(path.as_ref().to_path_buf());
+        self
+    }
+
+    pub fn subscribe(&self) -> watch::Receiver<AppConfig> {
+        self.sender.subscribe()
+    }
+
+    pub fn latest(&self) -> AppConfig {
+        self.sender.borrow().clone()
+    }
+
+    /// Validates `config` and publishes it to every subscriber
+    pub fn publish(&self, config: AppConfig) -> Result<(), ConfigReloadError> {
+        self.publish_with_env(config, |key| std::env::var(key).ok())
+    }
+
+    /// Same as `publish`, validating environment variables read through `env`
+    pub fn publish_with_env<F>(&self, config: AppConfig, env: F) -> Result<(), ConfigReloadError>
+    where
+        F: Fn(&str) -> Option<String>,
+    {
+        ConfigValidator::validate_with_env(&config, env).map_err(ConfigReloadError::Invalid)?;
+        self.sender.send_replace(config);
+        Ok(())
+    }
+
+    /// Re-reads the config file and publishes it, returning the config now in effect
+    pub fn reload(&self) -> Result<AppConfig, ConfigReloadError> {
+        self.reload_with_env(|key| std::env::var(key).ok())
+    }
+
+    /// Same as `reload`, validating environment variables read through `env`
+    pub fn reload_with_env<F>(&self, env: F) -> Result<AppConfig, ConfigReloadError>
+    where
+        F: Fn(&str) -> Option<String>,
+    {
+        let path = self
+            .path
+            .as_deref()
+            .ok_or(ConfigReloadError::NoConfigPath)?;
+        let config = load_config(Some(path)).map_err(|e| ConfigReloadError::Load {
+            path: path.display().to_string(),
+            message: e.to_string(),
+        })?;
+
+        self.publish_with_env(config.clone(), env)?;
+        info!("Reloaded configuration from {}", path.display());
+        Ok(config)
+    }
+
+    /// Reloads the config file every time the process receives SIGHUP
+    #[cfg(unix)]
+    pub async fn run_sighup_listener(self) {
+        use tokio::signal::unix::{signal, SignalKind};
+
+        let mut hangups = match signal(SignalKind::hangup()) {
+            Ok(hangups) => hangups,
+            Err(e) => {
+                error!("Failed to listen for SIGHUP: {:?}", e);
+                return;
+            }
+        };
+
+        while hangups.recv().await.is_some() {
+            info!("Received SIGHUP, reloading configuration");
+            if let Err(e) = self.reload() {
+                error!("Keeping the current configuration: {}", e);
+            }
+        }
+    }
+}
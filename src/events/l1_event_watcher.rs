@@ -1,23 +1,33 @@
-use crate::db::database::{get_last_processed_block, update_last_processed_block, upsert_deposit};
+use crate::config::AppConfig;
+use crate::db::database::{
+    get_last_processed_block, get_last_processed_block_with_hash, invalidate_deposits_after_block,
+    set_last_processed_block, upsert_deposit, BlockTrackerKey,
+};
+use crate::events::{CommitmentHashRegistry, EventMetrics};
+use crate::queue::commitment_verifier::CommitmentHashVerifier;
 use anyhow::Result;
+use futures_util::future::BoxFuture;
 use sqlx::PgPool;
-use tracing::log::{debug, warn};
+use tracing::log::{debug, error, info, warn};
 
+use std::collections::HashMap;
+use std::future::Future;
 use std::str::FromStr;
+use std::sync::Arc;
+use tokio::sync::mpsc;
+use tokio_stream::{wrappers::ReceiverStream, Stream};
 
 use alloy::{
     primitives::{Address},
     providers::{Provider, ProviderBuilder},
-    rpc::types::{Filter, Log},
+    rpc::types::{BlockNumberOrTag, Filter, Log},
     sol,
     sol_types::SolEvent,
 };
 
-// Name of the table for storing block tracker
-pub const BLOCK_TRACKER_KEY: &str = "l1_deposit_events_last_block";
-pub const DEPOSIT_HASH_BLOCK_TRACKER_KEY: &str = "l1_deposit_hash_events_last_block";
-
 sol! {
+    // The generated `DepositEvent` constructor takes one argument per event field
+    #[allow(clippy::too_many_arguments)]
     #[derive(Debug, PartialEq)]
     contract ZeroXBridge {
         enum AssetType {
@@ -40,14 +50,260 @@ sol! {
     }
 }
 
+/// `DepositEvent` logs fetched by a poll, with what was done with them
+pub struct L1EventResults {
+    pub deposit_events: Vec<Log<ZeroXBridge::DepositEvent>>,
+    pub metrics: EventMetrics,
+}
+
+/// Outcome of fetching `DepositEvent` logs through a `TestEthereumProvider`
+pub type DepositLogsResult =
+    Result<Vec<Log<ZeroXBridge::DepositEvent>>, Box<dyn std::error::Error + Send + Sync>>;
+
+/// L1 chain access needed by the deposit watcher, so tests can stand in for the RPC node
+pub trait TestEthereumProvider {
+    /// Decoded `DepositEvent` logs emitted by `contract_addr` from `from_block` onwards
+    fn get_deposit_logs<'a>(
+        &'a self,
+        from_block: u64,
+        contract_addr: &'a str,
+    ) -> BoxFuture<'a, DepositLogsResult>;
+
+    /// Current hash of `block_number` on the chain, `None` if there is no such block
+    fn get_block_hash(
+        &self,
+        block_number: u64,
+    ) -> BoxFuture<'_, Result<Option<String>, Box<dyn std::error::Error + Send + Sync>>>;
+
+    /// Timestamp of `block_number`, `None` if there is no such block or it can't be looked up
+    fn get_block_timestamp(
+        &self,
+        _block_number: u64,
+    ) -> BoxFuture<'_, Result<Option<u64>, Box<dyn std::error::Error + Send + Sync>>> {
+        Box::pin(async { Ok(None) })
+    }
+}
+
+/// `TestEthereumProvider` backed by an Ethereum JSON-RPC endpoint
+pub struct RpcEthereumProvider {
+    rpc_url: String,
+}
+
+impl RpcEthereumProvider {
+    pub fn new(rpc_url: impl Into<String>) -> Self {
+        Self {
+            rpc_url: rpc_url.into(),
+        }
+    }
+}
+
+impl TestEthereumProvider for RpcEthereumProvider {
+    fn get_deposit_logs<'a>(
+        &'a self,
+        from_block: u64,
+        contract_addr: &'a str,
+    ) -> BoxFuture<'a, DepositLogsResult> {
+        Box::pin(async move {
+            fetch_events_logs_at_address(
+                &self.rpc_url,
+                from_block,
+                contract_addr,
+                ZeroXBridge::DepositEvent::SIGNATURE,
+            )
+            .await
+            .map_err(|e| e.to_string().into())
+        })
+    }
+
+    fn get_block_hash(
+        &self,
+        block_number: u64,
+    ) -> BoxFuture<'_, Result<Option<String>, Box<dyn std::error::Error + Send + Sync>>> {
+        Box::pin(async move {
+            let provider = ProviderBuilder::new().connect(&self.rpc_url).await?;
+            let block = provider
+                .get_block_by_number(BlockNumberOrTag::Number(block_number))
+                .await?;
+            Ok(block.map(|block| block.header.hash.to_string()))
+        })
+    }
+
+    fn get_block_timestamp(
+        &self,
+        block_number: u64,
+    ) -> BoxFuture<'_, Result<Option<u64>, Box<dyn std::error::Error + Send + Sync>>> {
+        Box::pin(async move {
+            let provider = ProviderBuilder::new().connect(&self.rpc_url).await?;
+            let block = provider
+                .get_block_by_number(BlockNumberOrTag::Number(block_number))
+                .await?;
+            Ok(block.map(|block| block.header.timestamp))
+        })
+    }
+}
+
+/// Long-running watcher of the L1 contract's `DepositEvent` logs.
+///
+/// Every poll resumes after the last processed block in the block trackers, rewinding it when
+/// that block was reorged out, and stores each deposit as pending tree inclusion. Failed polls
+/// are logged and retried after the next polling interval.
+pub struct L1EventWatcher {
+    db_pool: PgPool,
+    config: AppConfig,
+    provider: Arc<dyn TestEthereumProvider + Send + Sync>,
+    commitment_registry: Option<CommitmentHashRegistry>,
+    commitment_verifier: Option<CommitmentHashVerifier>,
+    /// Block to start from when no block tracker has been written yet
+    start_block: u64,
+}
+
+impl L1EventWatcher {
+    pub fn new(
+        config: AppConfig,
+        db_pool: PgPool,
+        provider: Arc<dyn TestEthereumProvider + Send + Sync>,
+    ) -> Self {
+        Self {
+            db_pool,
+            config,
+            provider,
+            commitment_registry: None,
+            commitment_verifier: None,
+            start_block: 0,
+        }
+    }
+
+    /// Marks the commitment hash of every stored deposit confirmed in `commitment_registry`
+    pub fn with_commitment_registry(mut self, commitment_registry: CommitmentHashRegistry) -> Self {
+        self.commitment_registry = Some(commitment_registry);
+        self
+    }
+
+    /// Records every fetched deposit event in `commitment_verifier`, for the L1 queue to check
+    /// its deposit against
+    pub fn with_commitment_verifier(mut self, commitment_verifier: CommitmentHashVerifier) -> Self {
+        self.commitment_verifier = Some(commitment_verifier);
+        self
+    }
+
+    pub fn with_start_block(mut self, start_block: u64) -> Self {
+        self.start_block = start_block;
+        self
+    }
+
+    pub async fn run(&self) {
+        loop {
+            match self.poll().await {
+                Ok(results) => info!("L1 deposit events: {}", results.metrics),
+                Err(e) => error!("L1 event poll failed: {}", e),
+            }
+            sleep(Duration::from_secs(
+                self.config.ethereum.polling_interval_seconds,
+            ))
+            .await;
+        }
+    }
+
+    /// Fetches and stores every `DepositEvent` since the last processed block
+    pub async fn poll(&self) -> Result<L1EventResults, Box<dyn std::error::Error>> {
+        let results = fetch_l1_deposit_events_with_provider(
+            &self.db_pool,
+            self.provider.as_ref(),
+            self.start_block,
+            &self.config.contracts.l1_contract_address,
+            self.config.ethereum.reorg_depth,
+            self.commitment_registry.as_ref(),
+        )
+        .await?;
+
+        if let Some(verifier) = &self.commitment_verifier {
+            self.record_for_verification(verifier, &results.deposit_events)
+                .await;
+        }
+        Ok(results)
+    }
+
+    /// Records `logs` in `verifier` along with their block timestamp, which the L1 commitment
+    /// covers; logs whose timestamp can't be found are left out
+    async fn record_for_verification(
+        &self,
+        verifier: &CommitmentHashVerifier,
+        logs: &[Log<ZeroXBridge::DepositEvent>],
+    ) {
+        let mut block_timestamps = HashMap::new();
+        for log in logs {
+            let timestamp = match (log.block_timestamp, log.block_number) {
+                (Some(timestamp), _) => Some(timestamp),
+                (None, Some(block_number)) => match block_timestamps.get(&block_number) {
+                    Some(timestamp) => *timestamp,
+                    None => {
+                        let timestamp = self
+                            .provider
+                            .get_block_timestamp(block_number)
+                            .await
+                            .unwrap_or_else(|e| {
+                                warn!("Failed to get timestamp of block {}: {}", block_number, e);
+                                None
+                            });
+                        block_timestamps.insert(block_number, timestamp);
+                        timestamp
+                    }
+                },
+                (None, None) => None,
+            };
+
+            match timestamp {
+                Some(timestamp) => verifier.record_l1_event(log.data(), timestamp),
+                None => warn!(
+                    "Not verifying deposit {}: its block timestamp is unknown",
+                    log.data().depositId
+                ),
+            }
+        }
+    }
+}
+
 pub async fn fetch_l1_deposit_events(
     db_pool: &mut PgPool,
     rpc_url: &str,
     from_block: u64,
     contract_addr: &str,
-) -> Result<Vec<Log<ZeroXBridge::DepositEvent>>, Box<dyn std::error::Error>> {
-    // Load last processed block for DepositEvent
-    let from_block_deposit = match get_last_processed_block(db_pool, BLOCK_TRACKER_KEY).await {
+    reorg_depth: u64,
+    commitment_registry: Option<&CommitmentHashRegistry>,
+) -> Result<L1EventResults, Box<dyn std::error::Error>> {
+    let provider = RpcEthereumProvider::new(rpc_url);
+    fetch_l1_deposit_events_with_provider(
+        db_pool,
+        &provider,
+        from_block,
+        contract_addr,
+        reorg_depth,
+        commitment_registry,
+    )
+    .await
+}
+
+/// Fetches `DepositEvent` logs through `provider` and stores them, advancing the block tracker
+/// to the block of the last log.
+///
+/// Logs reach this function already decoded, so `failed_decode` is always zero here; a log the
+/// provider cannot decode fails the whole fetch instead.
+pub async fn fetch_l1_deposit_events_with_provider(
+    db_pool: &PgPool,
+    provider: &(dyn TestEthereumProvider + Send + Sync),
+    from_block: u64,
+    contract_addr: &str,
+    reorg_depth: u64,
+    commitment_registry: Option<&CommitmentHashRegistry>,
+) -> Result<L1EventResults, Box<dyn std::error::Error>> {
+    // Load last processed block for DepositEvent, rewinding it if that block was reorged out
+    let canonical_hash = |block_number| async move {
+        provider
+            .get_block_hash(block_number)
+            .await
+            .map_err(|e| e as Box<dyn std::error::Error>)
+    };
+    let from_block_deposit = match check_for_reorg(db_pool, reorg_depth, canonical_hash).await {
         Ok(Some(last_block)) => last_block + 1,
         Ok(None) => from_block,
         Err(e) => {
@@ -57,15 +313,22 @@ pub async fn fetch_l1_deposit_events(
     };
 
     // Fetch DepositEvent logs
-    let event_name = ZeroXBridge::DepositEvent::SIGNATURE;
-    let deposit_logs =
-        fetch_events_logs_at_address(rpc_url, from_block_deposit, contract_addr, event_name)
-            .await?;
+    let deposit_logs = provider
+        .get_deposit_logs(from_block_deposit, contract_addr)
+        .await
+        .map_err(|e| e as Box<dyn std::error::Error>)?;
 
     // Update last processed block for DepositEvent
     if let Some(last_log) = deposit_logs.last() {
         let block_number = last_log.block_number.ok_or("Block number not found")?;
-        if let Err(e) = update_last_processed_block(db_pool, BLOCK_TRACKER_KEY, block_number).await
+        let block_hash = last_log.block_hash.map(|hash| hash.to_string());
+        if let Err(e) = set_last_processed_block(
+            db_pool,
+            BlockTrackerKey::L1DepositEvents,
+            block_number,
+            block_hash.as_deref(),
+        )
+        .await
         {
             warn!(
                 "Failed to update last processed block for DepositEvent: {}",
@@ -74,37 +337,119 @@ pub async fn fetch_l1_deposit_events(
         }
     }
 
+    let mut metrics = EventMetrics {
+        fetched: deposit_logs.len() as u64,
+        ..Default::default()
+    };
     for log in &deposit_logs {
-        let event: &ZeroXBridge::DepositEvent = log.data();
-
-        debug!(
-            "Recieved DepositEvent: depositId={}, token={:?}, assetType={:?}, usdVal={}, user={:?}, nonce={}, leafIndex={}, commitmentHash={:x}, newRoot={:x}, elementCount={}",
-            event.depositId,
-            event.token,
-            event.assetType,
-            event.usdVal,
-            event.user,
-            event.nonce,
-            event.leafIndex,
-            event.commitmentHash,
-            event.newRoot,
-            event.elementCount
-        );
-
-        if let Err(e) = upsert_deposit(
-            db_pool,
-            &event.user.to_string(),
-            event.usdVal.to_string().parse::<i64>().unwrap_or(0),
-            &format!("{:x}", event.commitmentHash),
-            "PENDING_TREE_INCLUSION",
-        )
-        .await
-        {
-            warn!("Failed to upsert deposit: {}", e)
+        match record_deposit_event(db_pool, log).await {
+            Ok(inserted) => {
+                if inserted {
+                    metrics.stored += 1;
+                } else {
+                    metrics.skipped_duplicate += 1;
+                }
+                if let (Some(registry), Some(block_number)) =
+                    (commitment_registry, log.block_number)
+                {
+                    let commitment_hash = format!("{:x}", log.data().commitmentHash);
+                    registry.record_confirmed(&commitment_hash, block_number);
+                }
+            }
+            Err(e) => warn!("Failed to upsert deposit: {}", e),
         }
     }
 
-    Ok(deposit_logs)
+    Ok(L1EventResults {
+        deposit_events: deposit_logs,
+        metrics,
+    })
+}
+
+/// Checks the stored hash of the last processed DepositEvent block against `canonical_hash`,
+/// which returns the chain's current hash for a block number (`None` if the block is gone).
+///
+/// On a mismatch the block was reorged out: the tracker is rewound `reorg_depth` blocks and
+/// deposits recorded past the new last block are invalidated, so the next fetch re-reads them.
+/// Returns the block to resume after, or `None` when nothing has been processed yet. Trackers
+/// without a stored hash are trusted as-is.
+pub async fn check_for_reorg<F, Fut>(
+    db_pool: &PgPool,
+    reorg_depth: u64,
+    canonical_hash: F,
+) -> Result<Option<u64>, Box<dyn std::error::Error>>
+where
+    F: FnOnce(u64) -> Fut,
+    Fut: Future<Output = Result<Option<String>, Box<dyn std::error::Error>>>,
+{
+    let tracker =
+        get_last_processed_block_with_hash(db_pool, BlockTrackerKey::L1DepositEvents).await?;
+    let (last_block, stored_hash) = match tracker {
+        Some((last_block, Some(stored_hash))) => (last_block, stored_hash),
+        Some((last_block, None)) => return Ok(Some(last_block)),
+        None => return Ok(None),
+    };
+
+    let canonical = canonical_hash(last_block).await?;
+    if canonical.as_deref() == Some(stored_hash.as_str()) {
+        return Ok(Some(last_block));
+    }
+
+    let new_last_block = last_block.saturating_sub(reorg_depth);
+    warn!(
+        "Reorg detected at block {}: stored hash {}, chain has {:?}. Rolling back to block {}",
+        last_block, stored_hash, canonical, new_last_block
+    );
+
+    set_last_processed_block(
+        db_pool,
+        BlockTrackerKey::L1DepositEvents,
+        new_last_block,
+        None,
+    )
+    .await?;
+    let invalidated = invalidate_deposits_after_block(db_pool, new_last_block).await?;
+    warn!(
+        "Invalidated {} deposits after block {}",
+        invalidated, new_last_block
+    );
+
+    Ok(Some(new_last_block))
+}
+
+/// Stores the deposit carried by a `DepositEvent` log as pending tree inclusion, returning
+/// whether it was new rather than a replay of a stored event
+pub async fn record_deposit_event(
+    db_pool: &PgPool,
+    log: &Log<ZeroXBridge::DepositEvent>,
+) -> Result<bool, sqlx::Error> {
+    let event: &ZeroXBridge::DepositEvent = log.data();
+
+    debug!(
+        "Recieved DepositEvent: depositId={}, token={:?}, assetType={:?}, usdVal={}, user={:?}, nonce={}, leafIndex={}, commitmentHash={:x}, newRoot={:x}, elementCount={}",
+        event.depositId,
+        event.token,
+        event.assetType,
+        event.usdVal,
+        event.user,
+        event.nonce,
+        event.leafIndex,
+        event.commitmentHash,
+        event.newRoot,
+        event.elementCount
+    );
+
+    upsert_deposit(
+        db_pool,
+        &event.user.to_string(),
+        event.usdVal.to_string().parse::<i64>().unwrap_or(0),
+        &format!("{:x}", event.commitmentHash),
+        "PENDING_TREE_INCLUSION",
+        &event.depositId.to_string(),
+        log.block_number.map(|block_number| block_number as i64),
+        i64::try_from(event.elementCount).ok(),
+    )
+    .await
 }
 
 use std::time::Duration;
@@ -113,6 +458,9 @@ use tokio::time::sleep;
 const MAX_RETRIES: usize = 5; // we can update this. i'm not sure if 10 (retries) would be too much
 const INITIAL_BACKOFF_MS: u64 = 500;
 
+/// Fetches `event_name` logs emitted by `contract_addr` from `from_block` up to the current head,
+/// requesting `DEPOSIT_EVENT_CHUNK_SIZE` blocks per `eth_getLogs` call so nodes capping the size
+/// of a response still answer
 async fn fetch_events_logs_at_address<T>(
     rpc_url: &str,
     from_block: u64,
@@ -125,17 +473,37 @@ where
     let contract_addr = Address::from_str(contract_addr)?;
 
     let provider = ProviderBuilder::new().connect(rpc_url).await?;
+    let to_block = provider.get_block_number().await?;
 
-    let filter = Filter::new()
-        .address(contract_addr)
-        .event(event_name)
-        .from_block(from_block);
+    fetch_logs_chunked(
+        from_block,
+        to_block,
+        DEPOSIT_EVENT_CHUNK_SIZE,
+        |start, end| {
+            let filter = Filter::new()
+                .address(contract_addr)
+                .event(event_name)
+                .from_block(start)
+                .to_block(end);
+            let provider = &provider;
+            async move { fetch_logs_with_retry::<T>(provider, &filter).await }
+        },
+    )
+    .await
+}
 
+async fn fetch_logs_with_retry<T>(
+    provider: &impl Provider,
+    filter: &Filter,
+) -> Result<Vec<Log<T>>, Box<dyn std::error::Error>>
+where
+    T: alloy::sol_types::SolEvent,
+{
     let mut retries = 0;
     let mut backoff = INITIAL_BACKOFF_MS;
 
     loop {
-        match provider.get_logs(&filter).await {
+        match provider.get_logs(filter).await {
             Ok(logs) => {
                 let decoded_logs = logs
                     .into_iter()
@@ -167,6 +535,204 @@ where
     }
 }
 
+#[derive(Debug, thiserror::Error, PartialEq, Eq)]
+pub enum RangeValidationError {
+    #[error("to_block {to_block} is before from_block {from_block}")]
+    ToBlockBeforeFromBlock { from_block: u64, to_block: u64 },
+}
+
+/// Splits the inclusive range `from_block..=to_block` into consecutive ranges of at most
+/// `chunk_size` blocks
+pub fn block_ranges(
+    from_block: u64,
+    to_block: u64,
+    chunk_size: u64,
+) -> Result<Vec<(u64, u64)>, RangeValidationError> {
+    if to_block < from_block {
+        return Err(RangeValidationError::ToBlockBeforeFromBlock {
+            from_block,
+            to_block,
+        });
+    }
+
+    let chunk_size = chunk_size.max(1);
+    let mut ranges = Vec::new();
+    let mut start = from_block;
+    loop {
+        let end = start.saturating_add(chunk_size - 1).min(to_block);
+        ranges.push((start, end));
+        match end.checked_add(1) {
+            Some(next) if next <= to_block => start = next,
+            _ => return Ok(ranges),
+        }
+    }
+}
+
+/// Collects what `fetch_chunk` returns for every `chunk_size` block range of
+/// `from_block..=to_block`, in order, stopping at the first error.
+///
+/// A range ending before it starts holds no blocks, e.g. when `from_block` is one past a head
+/// that has already been processed, so nothing is fetched for it.
+pub async fn fetch_logs_chunked<T, E, F, Fut>(
+    from_block: u64,
+    to_block: u64,
+    chunk_size: u64,
+    mut fetch_chunk: F,
+) -> Result<Vec<T>, E>
+where
+    F: FnMut(u64, u64) -> Fut,
+    Fut: Future<Output = Result<Vec<T>, E>>,
+{
+    let ranges = match block_ranges(from_block, to_block, chunk_size) {
+        Ok(ranges) => ranges,
+        Err(e) => {
+            debug!("Nothing to fetch: {}", e);
+            return Ok(Vec::new());
+        }
+    };
+
+    let mut items = Vec::new();
+    for (start, end) in ranges {
+        debug!("fetching blocks {}..{}", start, end);
+        items.extend(fetch_chunk(start, end).await?);
+    }
+
+    Ok(items)
+}
+
+/// Blocks requested per `eth_getLogs` call when streaming deposit events
+pub const DEPOSIT_EVENT_CHUNK_SIZE: u64 = 2_000;
+/// Decoded events buffered ahead of a slow consumer before fetching pauses
+pub const DEPOSIT_EVENT_CHANNEL_CAPACITY: usize = 256;
+
+#[derive(Debug, thiserror::Error)]
+pub enum L1EventStreamError {
+    #[error("Invalid contract address: {0}")]
+    InvalidAddress(String),
+
+    #[error("RPC error: {0}")]
+    Rpc(String),
+
+    #[error("Failed to decode log: {0}")]
+    Decode(String),
+
+    #[error("Database error: {0}")]
+    Database(#[from] sqlx::Error),
+}
+
+/// Streams decoded `DepositEvent` logs from the last processed block up to the current head.
+///
+/// Logs are fetched in `DEPOSIT_EVENT_CHUNK_SIZE` block ranges and handed over through a bounded
+/// channel, so the next range is only requested once the consumer has made room for it. The
+/// block tracker is left untouched; consumers advance it as they persist each event.
+pub async fn stream_l1_deposit_events(
+    db_pool: &PgPool,
+    rpc_url: &str,
+    from_block: u64,
+    contract_addr: &str,
+) -> Result<
+    impl Stream<Item = Result<Log<ZeroXBridge::DepositEvent>, L1EventStreamError>>,
+    L1EventStreamError,
+> {
+    let from_block = match get_last_processed_block(db_pool, BlockTrackerKey::L1DepositEvents).await
+    {
+        Ok(Some(last_block)) => last_block + 1,
+        Ok(None) => from_block,
+        Err(e) => {
+            warn!("Failed to get last processed block for DepositEvent: {}", e);
+            from_block
+        }
+    };
+
+    let contract_addr = Address::from_str(contract_addr)
+        .map_err(|_| L1EventStreamError::InvalidAddress(contract_addr.to_string()))?;
+    let provider = ProviderBuilder::new()
+        .connect(rpc_url)
+        .await
+        .map_err(|e| L1EventStreamError::Rpc(e.to_string()))?;
+    let to_block = provider
+        .get_block_number()
+        .await
+        .map_err(|e| L1EventStreamError::Rpc(e.to_string()))?;
+
+    Ok(stream_chunked_logs(
+        from_block,
+        to_block,
+        DEPOSIT_EVENT_CHUNK_SIZE,
+        DEPOSIT_EVENT_CHANNEL_CAPACITY,
+        move |start, end| {
+            let provider = provider.clone();
+            async move {
+                let filter = Filter::new()
+                    .address(contract_addr)
+                    .event(ZeroXBridge::DepositEvent::SIGNATURE)
+                    .from_block(start)
+                    .to_block(end);
+                let logs = provider
+                    .get_logs(&filter)
+                    .await
+                    .map_err(|e| L1EventStreamError::Rpc(e.to_string()))?;
+
+                logs.into_iter()
+                    .map(|log| {
+                        log.log_decode::<ZeroXBridge::DepositEvent>()
+                            .map_err(|e| L1EventStreamError::Decode(e.to_string()))
+                    })
+                    .collect::<Result<Vec<_>, _>>()
+            }
+        },
+    ))
+}
+
+/// Walks `from_block..=to_block` in `chunk_size` ranges on a background task, forwarding every
+/// item `fetch_chunk` returns through a channel holding at most `capacity` items.
+///
+/// The task stops at the first error, after forwarding it, or once the receiver is dropped.
+pub fn stream_chunked_logs<T, F, Fut>(
+    from_block: u64,
+    to_block: u64,
+    chunk_size: u64,
+    capacity: usize,
+    mut fetch_chunk: F,
+) -> ReceiverStream<Result<T, L1EventStreamError>>
+where
+    T: Send + 'static,
+    F: FnMut(u64, u64) -> Fut + Send + 'static,
+    Fut: Future<Output = Result<Vec<T>, L1EventStreamError>> + Send,
+{
+    let (tx, rx) = mpsc::channel(capacity.max(1));
+    let chunk_size = chunk_size.max(1);
+
+    tokio::spawn(async move {
+        let mut start = from_block;
+        while start <= to_block {
+            let end = start.saturating_add(chunk_size - 1).min(to_block);
+
+            match fetch_chunk(start, end).await {
+                Ok(items) => {
+                    for item in items {
+                        // Waits here while the channel is full, so fetching never runs ahead
+                        if tx.send(Ok(item)).await.is_err() {
+                            return;
+                        }
+                    }
+                }
+                Err(e) => {
+                    let _ = tx.send(Err(e)).await;
+                    return;
+                }
+            }
+
+            start = match end.checked_add(1) {
+                Some(next) => next,
+                None => return,
+            };
+        }
+    });
+
+    ReceiverStream::new(rx)
+}
+
 
 #[cfg(test)]
 mod tests {
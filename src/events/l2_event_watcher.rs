@@ -1,23 +1,28 @@
-use crate::config::AppConfig;
-use crate::db::database::{get_last_processed_block, update_last_processed_block};
+use crate::config::{resolve_event_key, AppConfig, Contracts};
+use crate::db::database::{
+    insert_l2_burn_event, upsert_withdrawal_commitment_log, BlockTracker, BlockTrackerKey,
+};
+use crate::events::bus::EventBus;
+use crate::events::EventMetrics;
+use crate::merkle::WithdrawalTree;
+use crate::queue::l2_queue::parse_u128_from_hex;
+use crate::utils::{normalize_felt_hex, serialize_prefixed_commitment_hash};
 use anyhow::{anyhow, Result};
 use serde::{Deserialize, Serialize};
 use sqlx::PgPool;
 use starknet::core::types::{BlockId, EventFilter, EventsPage, Felt};
+use starknet::providers::jsonrpc::HttpTransport;
+use starknet::providers::{JsonRpcClient, Provider, Url};
+use std::future::Future;
+use std::sync::{Arc, Mutex};
 use std::time::Duration;
 use tokio::time::sleep;
-use tracing::log::warn;
+use tracing::log::{error, info, warn};
 
 const MAX_RETRIES: u32 = 3;
 const RETRY_DELAY_MS: u64 = 1000;
 const DEFAULT_PAGE_SIZE: u64 = 100;
 
-// Event key for BurnEvent (calculated from event name "BurnEvent")
-const BURN_EVENT_KEY: &str = "0x0099de3f38fed0a76764f614c6bc2b958814813685abc1af6deedab612df44f3";
-// Event key for WithdrawalHashAppended
-const WITHDRAWAL_HASH_APPENDED_EVENT_KEY: &str =
-    "0x01e3ad31c1ae0cf5ec9a8eaf3c540d6cf961c8f4e3bfe1d55a5b92a09e1c9c1e";
-
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CommitmentLog {
     pub commitment_hash: String,
@@ -31,6 +36,7 @@ pub struct CommitmentLog {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct WithdrawalCommitmentLog {
     pub index: String,
+    #[serde(serialize_with = "serialize_prefixed_commitment_hash")]
     pub commitment_hash: String,
     pub root_hash: String,
     pub elements_count: String,
@@ -38,9 +44,30 @@ pub struct WithdrawalCommitmentLog {
     pub transaction_hash: String,
 }
 
+/// Keys identifying the L2 contract's events, the first key of every event it emits
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EventKeyRegistry {
+    pub burn_event_key: Felt,
+    pub withdrawal_appended_key: Felt,
+}
+
+impl EventKeyRegistry {
+    /// Resolves `l2_burn_event_key` and `l2_withdrawal_event_key`, each a hex selector or an
+    /// event name
+    pub fn from_config(contracts: &Contracts) -> Result<Self> {
+        Ok(Self {
+            burn_event_key: resolve_event_key(&contracts.l2_burn_event_key)
+                .map_err(|e| anyhow!("contracts.l2_burn_event_key {}", e))?,
+            withdrawal_appended_key: resolve_event_key(&contracts.l2_withdrawal_event_key)
+                .map_err(|e| anyhow!("contracts.l2_withdrawal_event_key {}", e))?,
+        })
+    }
+}
+
 pub struct L2EventResults {
     pub burn_events: Vec<CommitmentLog>,
     pub withdrawal_events: Vec<WithdrawalCommitmentLog>,
+    pub metrics: EventMetrics,
 }
 
 pub trait TestProvider {
@@ -53,106 +80,303 @@ pub trait TestProvider {
     ) -> Result<EventsPage, Box<dyn std::error::Error + Send + Sync>>;
 }
 
-/// This function queries the L2 contract for events and parses both:
+/// `TestProvider` backed by a Starknet JSON-RPC endpoint.
+///
+/// `TestProvider` is synchronous, so each call blocks its worker thread until the endpoint
+/// answers; it must be used from the multi-threaded Tokio runtime.
+pub struct RpcStarknetProvider {
+    client: JsonRpcClient<HttpTransport>,
+}
+
+impl RpcStarknetProvider {
+    pub fn new(rpc_url: &str) -> Result<Self, url::ParseError> {
+        Ok(Self {
+            client: JsonRpcClient::new(HttpTransport::new(Url::parse(rpc_url)?)),
+        })
+    }
+
+    fn block_on<F: Future>(future: F) -> F::Output {
+        tokio::task::block_in_place(|| tokio::runtime::Handle::current().block_on(future))
+    }
+}
+
+impl TestProvider for RpcStarknetProvider {
+    fn block_number(&self) -> Result<u64, Box<dyn std::error::Error + Send + Sync>> {
+        Ok(Self::block_on(self.client.block_number())?)
+    }
+
+    fn get_events(
+        &self,
+        filter: EventFilter,
+        continuation_token: Option<String>,
+        chunk_size: u64,
+    ) -> Result<EventsPage, Box<dyn std::error::Error + Send + Sync>> {
+        Ok(Self::block_on(self.client.get_events(
+            filter,
+            continuation_token,
+            chunk_size,
+        ))?)
+    }
+}
+
+/// Long-running watcher of the L2 contract's events, parsing both:
 /// - Burn events into `CommitmentLog`
 /// - WithdrawalHashAppended events into `WithdrawalCommitmentLog`
 ///
-/// Events are returned together in a unified `L2EventResults` struct.
-/// Pagination and block tracking are handled to ensure no events are missed.
-pub async fn fetch_l2_events<P: TestProvider>(
-    config: &AppConfig,
-    db_pool: &PgPool,
-    from_block: u64,
-    provider: &P,
-) -> Result<L2EventResults> {
-    let start_block = match get_last_processed_block(&db_pool, "l2_events_last_block").await {
-        Ok(Some(last)) => last + 1,
-        _ => from_block,
-    };
-
-    let latest_block = get_latest_block_with_retry(provider).await?;
-    let contract_address = Felt::from_hex(&config.contracts.l2_contract_address)?;
-
-    let burn_event_key = Felt::from_hex(BURN_EVENT_KEY)?;
-    let withdrawal_event_key = Felt::from_hex(WITHDRAWAL_HASH_APPENDED_EVENT_KEY)?;
-
-    let event_filter = EventFilter {
-        from_block: Some(BlockId::Number(start_block)),
-        to_block: Some(BlockId::Number(latest_block)),
-        address: Some(contract_address),
-        keys: Some(vec![vec![burn_event_key, withdrawal_event_key]]),
-    };
-
-    let mut burn_events = Vec::new();
-    let mut withdrawal_events = Vec::new();
-    let mut continuation_token = None;
-
-    loop {
-        let page = fetch_events_with_retry(
+/// Each poll resumes after the last block it processed, kept in memory between polls and read
+/// from the block trackers on the first one. Pagination is handled to ensure no events are
+/// missed. When an event bus is set, every burn event is also published to its subscribers.
+/// Burn events are persisted to `l2_burn_events` and withdrawal events to
+/// `withdrawal_commitment_logs` as they are parsed, and appended to the withdrawal tree when
+/// one is set.
+pub struct L2EventWatcher {
+    config: AppConfig,
+    db_pool: PgPool,
+    provider: Arc<dyn TestProvider + Send + Sync>,
+    event_bus: Option<EventBus<CommitmentLog>>,
+    withdrawal_tree: Option<WithdrawalTree>,
+    block_tracker: BlockTracker,
+    /// Block to start from when no block tracker has been written yet
+    start_block: u64,
+    /// Last block processed by this watcher
+    last_block: Mutex<Option<u64>>,
+}
+
+impl L2EventWatcher {
+    pub fn new(
+        config: AppConfig,
+        db_pool: PgPool,
+        provider: Arc<dyn TestProvider + Send + Sync>,
+    ) -> Self {
+        Self {
+            block_tracker: BlockTracker::new(db_pool.clone()),
+            config,
+            db_pool,
             provider,
-            &event_filter,
-            continuation_token.clone(),
-            DEFAULT_PAGE_SIZE,
-        )
-        .await?;
-
-        for event in &page.events {
-            let block_number = event.block_number.unwrap_or_else(|| {
-                warn!("Missing block number for event: {:?}", event);
-                0
-            });
-
-            if event.keys.contains(&burn_event_key) && event.data.len() >= 4 {
-                burn_events.push(CommitmentLog {
-                    block_number,
-                    user: event.data[0].to_hex_string(),
-                    amount_low: event.data[1].to_hex_string(),
-                    amount_high: event.data[2].to_hex_string(),
-                    commitment_hash: event.data[3].to_hex_string(),
-                    transaction_hash: event.transaction_hash.to_hex_string(),
-                });
-            } else if event.keys.contains(&withdrawal_event_key) && event.data.len() >= 4 {
-                withdrawal_events.push(WithdrawalCommitmentLog {
-                    block_number,
-                    index: event.data[0].to_hex_string(),
-                    commitment_hash: event.data[1].to_hex_string(),
-                    root_hash: event.data[2].to_hex_string(),
-                    elements_count: event.data[3].to_hex_string(),
-                    transaction_hash: event.transaction_hash.to_hex_string(),
-                });
-            } else {
-                warn!("Unknown or malformed event: {:?}", event);
-            }
+            event_bus: None,
+            withdrawal_tree: None,
+            start_block: 0,
+            last_block: Mutex::new(None),
         }
+    }
 
-        continuation_token = page.continuation_token;
-        if continuation_token.is_none() {
-            break;
+    pub fn with_event_bus(mut self, event_bus: EventBus<CommitmentLog>) -> Self {
+        self.event_bus = Some(event_bus);
+        self
+    }
+
+    /// Tree to append each withdrawal commitment to, in the order the L2 contract added them
+    pub fn with_withdrawal_tree(mut self, withdrawal_tree: WithdrawalTree) -> Self {
+        self.withdrawal_tree = Some(withdrawal_tree);
+        self
+    }
+
+    /// Tracker to resume from and record progress in, instead of the `block_trackers` table
+    pub fn with_block_tracker(mut self, block_tracker: BlockTracker) -> Self {
+        self.block_tracker = block_tracker;
+        self
+    }
+
+    pub fn with_start_block(mut self, start_block: u64) -> Self {
+        self.start_block = start_block;
+        self
+    }
+
+    /// Last block a poll has processed, `None` before the first one
+    pub fn last_processed_block(&self) -> Option<u64> {
+        *self.last_block.lock().unwrap()
+    }
+
+    pub async fn run(&self) {
+        loop {
+            match self.poll().await {
+                Ok(results) => info!(
+                    "Fetched {} burn and {} withdrawal events ({})",
+                    results.burn_events.len(),
+                    results.withdrawal_events.len(),
+                    results.metrics
+                ),
+                Err(e) => error!("L2 event poll failed: {:?}", e),
+            }
+            sleep(Duration::from_secs(
+                self.config.starknet.polling_interval_seconds,
+            ))
+            .await;
         }
     }
 
-    let max_block = std::cmp::max(
-        burn_events
-            .iter()
-            .map(|e| e.block_number)
-            .max()
-            .unwrap_or(start_block),
-        withdrawal_events
-            .iter()
-            .map(|e| e.block_number)
-            .max()
-            .unwrap_or(start_block),
-    );
-
-    update_last_processed_block(&db_pool, "l2_events_last_block", max_block).await?;
-
-    Ok(L2EventResults {
-        burn_events,
-        withdrawal_events,
-    })
+    /// Fetches and stores every event since the last processed block, returning them together
+    /// in a unified `L2EventResults`
+    pub async fn poll(&self) -> Result<L2EventResults> {
+        let config = &self.config;
+        let db_pool = &self.db_pool;
+        let provider = self.provider.as_ref();
+        let event_bus = self.event_bus.as_ref();
+
+        let tracker = &self.block_tracker;
+        let start_block = match self.last_processed_block() {
+            Some(last) => last + 1,
+            // Both event types come from the same query, so resume after the block both have
+            // reached
+            None => match (
+                tracker.get(BlockTrackerKey::L2BurnEvents).await,
+                tracker.get(BlockTrackerKey::L2WithdrawalEvents).await,
+            ) {
+                (Ok(Some(burn)), Ok(Some(withdrawal))) => burn.min(withdrawal) + 1,
+                (Ok(Some(last)), Ok(None)) | (Ok(None), Ok(Some(last))) => last + 1,
+                _ => self.start_block,
+            },
+        };
+        // Nothing before the deployment block can hold contract events
+        let start_block = start_block.max(config.contracts.l2_contract_deploy_block);
+
+        let latest_block = get_latest_block_with_retry(provider).await?;
+        let contract_address = Felt::from_hex(&config.contracts.l2_contract_address)?;
+
+        let event_keys = EventKeyRegistry::from_config(&config.contracts)?;
+        let burn_event_key = event_keys.burn_event_key;
+        let withdrawal_event_key = event_keys.withdrawal_appended_key;
+
+        // Each inner vec of `keys` lists the values accepted at that key position, so this matches
+        // events whose selector (first key) is either event key. One query covers both event types.
+        let event_filter = EventFilter {
+            from_block: Some(BlockId::Number(start_block)),
+            to_block: Some(BlockId::Number(latest_block)),
+            address: Some(contract_address),
+            keys: Some(vec![vec![burn_event_key, withdrawal_event_key]]),
+        };
+
+        let mut burn_events = Vec::new();
+        let mut withdrawal_events = Vec::new();
+        let mut metrics = EventMetrics::default();
+        let mut continuation_token = None;
+
+        loop {
+            let page = fetch_events_with_retry(
+                provider,
+                &event_filter,
+                continuation_token.clone(),
+                DEFAULT_PAGE_SIZE,
+            )
+            .await?;
+
+            metrics.fetched += page.events.len() as u64;
+            for event in &page.events {
+                let block_number = event.block_number.unwrap_or_else(|| {
+                    warn!("Missing block number for event: {:?}", event);
+                    0
+                });
+
+                // Only the first key is the event selector; later keys are indexed event members
+                let selector = event.keys.first();
+
+                if selector == Some(&burn_event_key) && event.data.len() >= 4 {
+                    let log = CommitmentLog {
+                        block_number,
+                        user: event.data[0].to_hex_string(),
+                        amount_low: event.data[1].to_hex_string(),
+                        amount_high: event.data[2].to_hex_string(),
+                        commitment_hash: event.data[3].to_hex_string(),
+                        transaction_hash: event.transaction_hash.to_hex_string(),
+                    };
+                    match parse_u128_from_hex(&log.amount_low, &log.amount_high) {
+                        Ok(amount) => {
+                            // Withdrawals store their stark key in canonical form
+                            let stored = CommitmentLog {
+                                user: normalize_felt_hex(&log.user)
+                                    .unwrap_or_else(|_| log.user.clone()),
+                                ..log.clone()
+                            };
+                            if insert_l2_burn_event(db_pool, &stored, amount).await? {
+                                metrics.stored += 1;
+                            } else {
+                                metrics.skipped_duplicate += 1;
+                            }
+                        }
+                        Err(e) => {
+                            warn!("Not storing burn {}: {}", log.commitment_hash, e);
+                            metrics.failed_decode += 1;
+                        }
+                    }
+                    if let Some(bus) = event_bus {
+                        bus.publish(log.clone());
+                    }
+                    burn_events.push(log);
+                } else if selector == Some(&withdrawal_event_key) && event.data.len() >= 4 {
+                    let log = WithdrawalCommitmentLog {
+                        block_number,
+                        index: event.data[0].to_hex_string(),
+                        commitment_hash: event.data[1].to_hex_string(),
+                        root_hash: event.data[2].to_hex_string(),
+                        elements_count: event.data[3].to_hex_string(),
+                        transaction_hash: event.transaction_hash.to_hex_string(),
+                    };
+                    if upsert_withdrawal_commitment_log(db_pool, &log).await? {
+                        metrics.stored += 1;
+                    } else {
+                        metrics.skipped_duplicate += 1;
+                    }
+                    if let Some(tree) = &self.withdrawal_tree {
+                        match u64::try_from(event.data[0]) {
+                            Ok(index) => {
+                                if let Err(e) =
+                                    tree.append(index, event.data[1].to_bytes_be()).await
+                                {
+                                    warn!(
+                                        "Withdrawal commitment {} not added to the tree: {}",
+                                        log.commitment_hash, e
+                                    );
+                                }
+                            }
+                            Err(_) => warn!(
+                                "Withdrawal commitment {} has out of range index {}",
+                                log.commitment_hash, log.index
+                            ),
+                        }
+                    }
+                    withdrawal_events.push(log);
+                } else {
+                    warn!("Unknown or malformed event: {:?}", event);
+                    metrics.failed_decode += 1;
+                }
+            }
+
+            continuation_token = page.continuation_token;
+            if continuation_token.is_none() {
+                break;
+            }
+        }
+
+        let max_block = std::cmp::max(
+            burn_events
+                .iter()
+                .map(|e| e.block_number)
+                .max()
+                .unwrap_or(start_block),
+            withdrawal_events
+                .iter()
+                .map(|e| e.block_number)
+                .max()
+                .unwrap_or(start_block),
+        );
+
+        tracker
+            .set(BlockTrackerKey::L2BurnEvents, max_block)
+            .await?;
+        tracker
+            .set(BlockTrackerKey::L2WithdrawalEvents, max_block)
+            .await?;
+        *self.last_block.lock().unwrap() = Some(max_block);
+
+        Ok(L2EventResults {
+            burn_events,
+            withdrawal_events,
+            metrics,
+        })
+    }
 }
 
-async fn get_latest_block_with_retry<P: TestProvider>(provider: &P) -> Result<u64> {
+async fn get_latest_block_with_retry(provider: &(dyn TestProvider + Send + Sync)) -> Result<u64> {
     for attempt in 1..=MAX_RETRIES {
         match provider.block_number() {
             Ok(block) => return Ok(block),
@@ -175,8 +399,8 @@ async fn get_latest_block_with_retry<P: TestProvider>(provider: &P) -> Result<u6
     ))
 }
 
-async fn fetch_events_with_retry<P: TestProvider>(
-    provider: &P,
+async fn fetch_events_with_retry(
+    provider: &(dyn TestProvider + Send + Sync),
     filter: &EventFilter,
     continuation_token: Option<String>,
     chunk_size: u64,
@@ -0,0 +1,53 @@
+use std::sync::Arc;
+use tokio::sync::watch;
+
+/// Shared handle the Merkle tree builders use to announce a new root
+pub type RootSender = Arc<watch::Sender<Option<[u8; 32]>>>;
+
+/// Latest-value channel for the Merkle root so dependants can await changes instead of polling.
+///
+/// Holds `None` until the first root is built. Cloning the watcher is cheap and every clone
+/// publishes to the same subscribers.
+#[derive(Debug, Clone)]
+pub struct MerkleRootWatcher {
+    sender: RootSender,
+}
+
+impl MerkleRootWatcher {
+    pub fn new() -> Self {
+        let (sender, _) = watch::channel(None);
+        Self {
+            sender: Arc::new(sender),
+        }
+    }
+
+    /// Publishes a new root, waking subscribers only when it differs from the current one
+    pub fn publish(&self, root: [u8; 32]) {
+        self.sender.send_if_modified(|current| {
+            if *current == Some(root) {
+                return false;
+            }
+            *current = Some(root);
+            true
+        });
+    }
+
+    pub fn subscribe(&self) -> watch::Receiver<Option<[u8; 32]>> {
+        self.sender.subscribe()
+    }
+
+    pub fn latest(&self) -> Option<[u8; 32]> {
+        *self.sender.borrow()
+    }
+
+    /// Sender to hand to a tree builder via `with_root_notifier`
+    pub fn sender(&self) -> RootSender {
+        Arc::clone(&self.sender)
+    }
+}
+
+impl Default for MerkleRootWatcher {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,25 @@
+use serde::Serialize;
+use std::fmt;
+
+/// Counts of what a watcher poll did with the events it fetched
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct EventMetrics {
+    /// Events returned by the node
+    pub fetched: u64,
+    /// Events that created a new row
+    pub stored: u64,
+    /// Events whose row already existed, e.g. replays after a restart or reorg
+    pub skipped_duplicate: u64,
+    /// Events that could not be parsed and were not stored
+    pub failed_decode: u64,
+}
+
+impl fmt::Display for EventMetrics {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "fetched={} stored={} skipped_duplicate={} failed_decode={}",
+            self.fetched, self.stored, self.skipped_duplicate, self.failed_decode
+        )
+    }
+}
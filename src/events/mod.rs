@@ -1,4 +1,15 @@
+pub mod bus;
+pub mod commitment_registry;
+pub mod config_watcher;
 pub mod l1_event_watcher;
 pub mod l2_event_watcher;
+pub mod merkle_watcher;
+pub mod metrics;
 
-pub use l2_event_watcher::{fetch_l2_events, CommitmentLog};
+pub use bus::EventBus;
+pub use commitment_registry::{CommitmentHashRegistry, ConfirmationStatus};
+pub use config_watcher::{ConfigReloadError, ConfigWatcher};
+pub use l1_event_watcher::L1EventWatcher;
+pub use l2_event_watcher::{CommitmentLog, L2EventWatcher, WithdrawalCommitmentLog};
+pub use merkle_watcher::MerkleRootWatcher;
+pub use metrics::EventMetrics;
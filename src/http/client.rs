@@ -1,54 +1,200 @@
-use anyhow::Result;
+use alloy_primitives::U256;
+use anyhow::{anyhow, Result};
 use reqwest::multipart::{Form, Part};
-use reqwest::Client;
-use std::env;
+use reqwest::{Client, StatusCode};
+use serde::Deserialize;
 use std::fs;
+use std::io;
+use std::time::Duration;
+use thiserror::Error;
+use tokio::time::sleep;
+use tracing::warn;
+use url::Url;
 
+#[derive(Debug, Error)]
+pub enum AtlanticError {
+    #[error("Atlantic responded with {0}: {1}")]
+    HttpError(StatusCode, String),
+
+    #[error("Network error: {0}")]
+    NetworkError(#[from] reqwest::Error),
+
+    #[error("Atlantic query submission kept failing after all retries")]
+    MaxRetriesExceeded,
+
+    #[error("Failed to read proof job file: {0}")]
+    Io(#[from] io::Error),
+
+    #[error("Unexpected Atlantic response: {0}")]
+    InvalidResponse(#[from] serde_json::Error),
+
+    #[error("Invalid Atlantic URL: {0}")]
+    InvalidUrl(#[from] url::ParseError),
+}
+
+impl AtlanticError {
+    /// Server errors, rate limits and network failures may clear up; other client errors won't
+    pub fn is_retriable(&self) -> bool {
+        match self {
+            AtlanticError::HttpError(status, _) => {
+                status.is_server_error() || *status == StatusCode::TOO_MANY_REQUESTS
+            }
+            AtlanticError::NetworkError(_) => true,
+            _ => false,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct AtlanticQueryResponse {
+    atlantic_query_id: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct AtlanticQueryStatusResponse {
+    atlantic_query: AtlanticQuery,
+}
+
+#[derive(Debug, Deserialize)]
+struct AtlanticQuery {
+    status: String,
+}
+
+/// Proof of a completed Atlantic query, in the form the bridge's `unlock_funds_with_proof` takes
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AtlanticProof {
+    pub proof_params: Vec<U256>,
+    pub proof: Vec<U256>,
+}
+
+/// Submits a proof generation job to Atlantic and returns the Atlantic query id.
+///
+/// Failed submissions are retried up to `max_retries` times, waiting `retry_delay_ms` before
+/// the first retry and twice as long before each one after it. Client errors other than
+/// 429 Too Many Requests are returned straight away. `submit_url` is the Atlantic query
+/// endpoint, as given by `HerodotusConfig::get_submit_url`.
 pub async fn submit_sharp_proof_job(
+    submit_url: &Url,
     api_key: String,
     result: String,
     program_path: String,
     input_path: String,
-) -> Result<()> {
+    max_retries: u32,
+    retry_delay_ms: u64,
+) -> Result<String, AtlanticError> {
     let program_bytes = fs::read(program_path)?;
     let input_bytes = fs::read(input_path)?;
 
-    let form = Form::new()
-        .text("layout", "auto")
-        .text("cairoVm", "rust")
-        .text("cairoVersion", "cairo1")
-        .text("mockFactHash", "false")
-        .text("declaredJobSize", "S")
-        .text("direction", result)
-        .part(
-            "program",
-            Part::bytes(program_bytes).file_name("cairo1.sierra.json"),
-        )
-        .part(
-            "input",
-            Part::bytes(input_bytes).file_name("input.cairo1.txt"),
-        );
-
     let client = Client::new();
+    let mut url = submit_url.clone();
+    url.query_pairs_mut().append_pair("apiKey", &api_key);
 
-    let url = if let Ok(endpoint) = env::var("ATLANTIC_API_ENDPOINT") {
-        format!("{}?apiKey={}", endpoint, api_key)
-    } else {
-        format!(
-            "https://staging.atlantic.api.herodotus.cloud/atlantic-query?apiKey={}",
-            api_key
-        )
-    };
-
-    let response = client.post(&url).multipart(form).send().await?;
-
-    if response.status().is_success() {
-        let resp_text = response.text().await?;
-        println!("Success: {}", resp_text);
-    } else {
-        let status = response.status();
-        let resp_text = response.text().await?;
-        eprintln!("Error ({}): {}", status, resp_text);
+    let mut delay = Duration::from_millis(retry_delay_ms);
+    for attempt in 0..=max_retries {
+        // A multipart form is consumed by the request, so each attempt builds its own
+        let form = Form::new()
+            .text("layout", "auto")
+            .text("cairoVm", "rust")
+            .text("cairoVersion", "cairo1")
+            .text("mockFactHash", "false")
+            .text("declaredJobSize", "S")
+            .text("direction", result.clone())
+            .part(
+                "program",
+                Part::bytes(program_bytes.clone()).file_name("cairo1.sierra.json"),
+            )
+            .part(
+                "input",
+                Part::bytes(input_bytes.clone()).file_name("input.cairo1.txt"),
+            );
+
+        match submit_atlantic_query(&client, url.as_str(), form).await {
+            Ok(query_id) => return Ok(query_id),
+            Err(e) if e.is_retriable() && attempt < max_retries => {
+                warn!(
+                    "Atlantic query submission failed (attempt {}/{}), retrying in {:?}: {}",
+                    attempt + 1,
+                    max_retries + 1,
+                    delay,
+                    e
+                );
+                sleep(delay).await;
+                delay *= 2;
+            }
+            Err(e) if e.is_retriable() => {
+                warn!(
+                    "Atlantic query submission failed on the last attempt: {}",
+                    e
+                );
+            }
+            Err(e) => return Err(e),
+        }
+    }
+
+    Err(AtlanticError::MaxRetriesExceeded)
+}
+
+async fn submit_atlantic_query(
+    client: &Client,
+    url: &str,
+    form: Form,
+) -> Result<String, AtlanticError> {
+    let response = client.post(url).multipart(form).send().await?;
+
+    let status = response.status();
+    let resp_text = response.text().await?;
+    if !status.is_success() {
+        return Err(AtlanticError::HttpError(status, resp_text));
     }
-    Ok(())
+
+    let parsed: AtlanticQueryResponse = serde_json::from_str(&resp_text)?;
+    Ok(parsed.atlantic_query_id)
+}
+
+/// Fetches the current status of an Atlantic query, e.g. `IN_PROGRESS`, `DONE` or `FAILED`.
+///
+/// `status_url` is the query's status endpoint, as given by `HerodotusConfig::get_status_url`.
+pub async fn atlantic_job_status(status_url: &Url, api_key: &str) -> Result<String> {
+    let mut url = status_url.clone();
+    url.query_pairs_mut().append_pair("apiKey", api_key);
+
+    let response = Client::new().get(url).send().await?;
+
+    let status = response.status();
+    let resp_text = response.text().await?;
+    if !status.is_success() {
+        return Err(anyhow!(
+            "Atlantic status request failed ({}): {}",
+            status,
+            resp_text
+        ));
+    }
+
+    let parsed: AtlanticQueryStatusResponse = serde_json::from_str(&resp_text)?;
+    Ok(parsed.atlantic_query.status)
+}
+
+/// Fetches the proof of a completed Atlantic query.
+///
+/// `result_url` is the query's result endpoint, as given by `HerodotusConfig::get_result_url`.
+pub async fn atlantic_job_result(result_url: &Url, api_key: &str) -> Result<AtlanticProof> {
+    let mut url = result_url.clone();
+    url.query_pairs_mut().append_pair("apiKey", api_key);
+
+    let response = Client::new().get(url).send().await?;
+
+    let status = response.status();
+    let resp_text = response.text().await?;
+    if !status.is_success() {
+        return Err(anyhow!(
+            "Atlantic result request failed ({}): {}",
+            status,
+            resp_text
+        ));
+    }
+
+    Ok(serde_json::from_str(&resp_text)?)
 }
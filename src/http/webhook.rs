@@ -0,0 +1,166 @@
+use crate::config::{WebhookConfig, WebhookEventType};
+use crate::events::EventBus;
+use crate::relayer::ethereum_relayer::CompletedWithdrawalEvent;
+use hmac::{Hmac, Mac};
+use reqwest::header::CONTENT_TYPE;
+use reqwest::{Client, StatusCode};
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+use std::sync::Arc;
+use std::time::Duration;
+use thiserror::Error;
+use tokio::sync::broadcast::error::RecvError;
+use tokio::task::JoinHandle;
+use tracing::{debug, warn};
+
+/// Longest a webhook request may take, so a hung endpoint can't stall event delivery
+pub const WEBHOOK_REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Header carrying `sha256=<hex>`, the HMAC-SHA256 of the request body keyed with the secret
+pub const SIGNATURE_HEADER: &str = "X-Signature-256";
+
+#[derive(Debug, Error)]
+pub enum WebhookError {
+    #[error("Webhook endpoint responded with {0}: {1}")]
+    HttpError(StatusCode, String),
+
+    #[error("Network error: {0}")]
+    NetworkError(#[from] reqwest::Error),
+
+    #[error("Failed to serialize webhook event: {0}")]
+    Serialization(#[from] serde_json::Error),
+}
+
+/// Body of a webhook request, `{"type": "<event type>", "data": {...}}`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", content = "data", rename_all = "snake_case")]
+pub enum WebhookEvent {
+    WithdrawalCompleted(CompletedWithdrawalEvent),
+    ProofJobCompleted {
+        job_id: u64,
+        fact_hash: Option<String>,
+    },
+    ProofJobFailed {
+        job_id: u64,
+        error: String,
+    },
+}
+
+impl WebhookEvent {
+    pub fn event_type(&self) -> WebhookEventType {
+        match self {
+            WebhookEvent::WithdrawalCompleted(_) => WebhookEventType::WithdrawalCompleted,
+            WebhookEvent::ProofJobCompleted { .. } => WebhookEventType::ProofJobCompleted,
+            WebhookEvent::ProofJobFailed { .. } => WebhookEventType::ProofJobFailed,
+        }
+    }
+}
+
+/// `X-Signature-256` value of `body`: `sha256=` followed by its hex HMAC-SHA256 under `secret`
+pub fn sign_payload(secret: &str, body: &[u8]) -> String {
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC accepts keys of any size");
+    mac.update(body);
+    format!("sha256={}", hex::encode(mac.finalize().into_bytes()))
+}
+
+/// POSTs signed JSON events to the configured webhook endpoint
+pub struct WebhookNotifier {
+    client: Client,
+    config: WebhookConfig,
+}
+
+impl WebhookNotifier {
+    pub fn new(config: WebhookConfig) -> Self {
+        let client = Client::builder()
+            .timeout(WEBHOOK_REQUEST_TIMEOUT)
+            .build()
+            .expect("Failed to build the webhook HTTP client");
+        Self { client, config }
+    }
+
+    /// Sends `event` unless its type is not subscribed to. A 5xx response is retried once.
+    pub async fn send(&self, event: WebhookEvent) -> Result<(), WebhookError> {
+        if !self.config.events.contains(&event.event_type()) {
+            return Ok(());
+        }
+
+        let body = serde_json::to_vec(&event)?;
+        let signature = sign_payload(self.config.secret.expose(), &body);
+
+        match self.post(&body, &signature).await {
+            Err(WebhookError::HttpError(status, message)) if status.is_server_error() => {
+                warn!(
+                    "Webhook endpoint responded with {}: {}, retrying once",
+                    status, message
+                );
+                self.post(&body, &signature).await
+            }
+            result => result,
+        }
+    }
+
+    async fn post(&self, body: &[u8], signature: &str) -> Result<(), WebhookError> {
+        let response = self
+            .client
+            .post(&self.config.url)
+            .header(CONTENT_TYPE, "application/json")
+            .header(SIGNATURE_HEADER, signature)
+            .body(body.to_vec())
+            .send()
+            .await?;
+
+        let status = response.status();
+        if !status.is_success() {
+            let message = response.text().await.unwrap_or_default();
+            return Err(WebhookError::HttpError(status, message));
+        }
+
+        debug!("Webhook endpoint accepted event ({})", status);
+        Ok(())
+    }
+
+    /// Sends `event` in the background, so a slow endpoint never holds up the caller. Failures
+    /// are logged.
+    pub fn notify(self: &Arc<Self>, event: WebhookEvent) -> JoinHandle<()> {
+        let notifier = Arc::clone(self);
+        tokio::spawn(async move {
+            let event_type = event.event_type();
+            if let Err(e) = notifier.send(event).await {
+                warn!("Failed to send {:?} webhook: {}", event_type, e);
+            }
+        })
+    }
+
+    /// Sends a `WithdrawalCompleted` event for every completion published on `bus`, until all
+    /// of its publishers are dropped
+    pub fn subscribe_completions(
+        self: Arc<Self>,
+        bus: &EventBus<CompletedWithdrawalEvent>,
+    ) -> JoinHandle<()> {
+        let mut completions = bus.subscribe();
+        tokio::spawn(async move {
+            loop {
+                match completions.recv().await {
+                    Ok(completion) => {
+                        let withdrawal_id = completion.withdrawal_id;
+                        let event = WebhookEvent::WithdrawalCompleted(completion);
+                        if let Err(e) = self.send(event).await {
+                            warn!(
+                                "Failed to send completion webhook for withdrawal {}: {}",
+                                withdrawal_id, e
+                            );
+                        }
+                    }
+                    Err(RecvError::Lagged(skipped)) => {
+                        warn!(
+                            "Webhook notifier skipped {} withdrawal completions",
+                            skipped
+                        )
+                    }
+                    Err(RecvError::Closed) => break,
+                }
+            }
+        })
+    }
+}
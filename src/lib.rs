@@ -3,7 +3,10 @@ pub mod config;
 pub mod db;
 pub mod events;
 pub mod http;
+pub mod merkle;
+pub mod oracle_service;
 pub mod proof_client;
 pub mod queue;
 pub mod relayer;
 pub mod utils;
+pub mod workers;
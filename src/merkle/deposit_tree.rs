@@ -0,0 +1,207 @@
+use serde::{Deserialize, Serialize};
+use sqlx::PgConnection;
+use starknet::core::types::Felt;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use tokio::sync::RwLock;
+use tree_builder::{
+    error::TreeBuilderError,
+    l1_tree::L1MerkleTreeBuilder,
+    types::{Proof, RootSender},
+};
+
+use crate::db::database::{set_deposit_leaf_index, Deposit};
+use crate::events::l1_event_watcher::ZeroXBridge::DepositEvent;
+
+#[derive(Debug, thiserror::Error)]
+pub enum DepositTreeError {
+    #[error("Database error: {0}")]
+    Database(#[from] sqlx::Error),
+
+    #[error("Merkle tree error: {0}")]
+    Tree(#[from] TreeBuilderError),
+
+    #[error("Invalid commitment hash: {0}")]
+    InvalidCommitment(String),
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum TreeSyncError {
+    #[error("Local deposit tree has {actual} elements, the L1 contract reports {expected}")]
+    ElementCountMismatch { expected: u64, actual: u64 },
+
+    #[error("Merkle tree error: {0}")]
+    Tree(#[from] TreeBuilderError),
+}
+
+/// Checks that `builder` holds as many MMR elements as the L1 contract reported in `event`'s
+/// `elementCount`, i.e. that the local tree has caught up with the deposit
+pub async fn verify_tree_sync(
+    builder: &L1MerkleTreeBuilder,
+    event: &DepositEvent,
+) -> Result<(), TreeSyncError> {
+    // No local tree grows past u64 elements, so such a count is always a mismatch
+    verify_element_count(
+        builder,
+        u64::try_from(event.elementCount).unwrap_or(u64::MAX),
+    )
+    .await
+}
+
+/// Checks that `builder` holds `expected` MMR elements
+async fn verify_element_count(
+    builder: &L1MerkleTreeBuilder,
+    expected: u64,
+) -> Result<(), TreeSyncError> {
+    let actual = builder.elements_count().await?;
+    if actual != expected {
+        return Err(TreeSyncError::ElementCountMismatch { expected, actual });
+    }
+    Ok(())
+}
+
+/// Inclusion proof for a deposit commitment, serialized for API clients
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MerkleProofJson {
+    pub element_index: usize,
+    pub element_hash: String,
+    pub siblings_hashes: Vec<String>,
+    pub peaks_hashes: Vec<String>,
+    pub elements_count: usize,
+    /// Root the proof was generated against
+    pub root: String,
+}
+
+impl MerkleProofJson {
+    pub fn new(proof: Proof, root: [u8; 32]) -> Self {
+        Self {
+            element_index: proof.element_index,
+            element_hash: proof.element_hash,
+            siblings_hashes: proof.siblings_hashes,
+            peaks_hashes: proof.peaks_hashes,
+            elements_count: proof.elements_count,
+            root: format!("0x{}", hex::encode(root)),
+        }
+    }
+}
+
+impl From<MerkleProofJson> for Proof {
+    fn from(proof: MerkleProofJson) -> Self {
+        Proof {
+            element_index: proof.element_index,
+            element_hash: proof.element_hash,
+            siblings_hashes: proof.siblings_hashes,
+            peaks_hashes: proof.peaks_hashes,
+            elements_count: proof.elements_count,
+        }
+    }
+}
+
+/// Deposit commitment tree shared between the deposit queue, which appends to it, and the API,
+/// which serves proofs from it.
+#[derive(Clone, Default)]
+pub struct DepositTree {
+    builder: Arc<RwLock<L1MerkleTreeBuilder>>,
+    /// `tree_sync_lag`: elements between the local tree and the L1 contract's at the last check
+    sync_lag: Arc<AtomicU64>,
+}
+
+impl DepositTree {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Publishes the new root to `notifier` whenever deposits are added
+    pub fn with_root_notifier(notifier: RootSender) -> Self {
+        Self {
+            builder: Arc::new(RwLock::new(
+                L1MerkleTreeBuilder::new().with_root_notifier(notifier),
+            )),
+            sync_lag: Arc::default(),
+        }
+    }
+
+    /// Appends the deposits' commitments to the tree and records each one's `leaf_index`
+    pub async fn add_deposits(
+        &self,
+        conn: &mut PgConnection,
+        deposits: &[Deposit],
+    ) -> Result<(), DepositTreeError> {
+        let leaves = deposits
+            .iter()
+            .map(|deposit| commitment_to_leaf(&deposit.commitment_hash))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let mut builder = self.builder.write().await;
+        builder.build_merkle(leaves.clone()).await?;
+
+        for (deposit, leaf) in deposits.iter().zip(leaves) {
+            if let Some(leaf_index) = builder.find_leaf_by_commitment_hash(leaf) {
+                set_deposit_leaf_index(&mut *conn, deposit.id, leaf_index as i64).await?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Proof for a commitment, or `None` if it has not been added to the tree
+    pub async fn get_proof(
+        &self,
+        commitment_hash: &str,
+    ) -> Result<Option<MerkleProofJson>, DepositTreeError> {
+        let leaf = commitment_to_leaf(commitment_hash)?;
+        let builder = self.builder.read().await;
+
+        match builder.get_proof(leaf).await? {
+            Some(proof) => {
+                let root = builder.get_root().await?;
+                Ok(Some(MerkleProofJson::new(proof, root)))
+            }
+            None => Ok(None),
+        }
+    }
+
+    /// Checks that the tree holds the `expected` elements the L1 contract reported, updating
+    /// the `tree_sync_lag` gauge
+    pub async fn verify_sync(&self, expected: u64) -> Result<(), TreeSyncError> {
+        let result = verify_element_count(&*self.builder.read().await, expected).await;
+        match &result {
+            Ok(()) => self.sync_lag.store(0, Ordering::Relaxed),
+            Err(TreeSyncError::ElementCountMismatch { expected, actual }) => self
+                .sync_lag
+                .store(expected.abs_diff(*actual), Ordering::Relaxed),
+            Err(TreeSyncError::Tree(_)) => {}
+        }
+        result
+    }
+
+    /// Elements the tree was off from the L1 contract's by at the last `verify_sync`
+    pub fn tree_sync_lag(&self) -> u64 {
+        self.sync_lag.load(Ordering::Relaxed)
+    }
+
+    pub async fn get_root(&self) -> Result<[u8; 32], DepositTreeError> {
+        Ok(self.builder.read().await.get_root().await?)
+    }
+
+    pub async fn verify_proof(
+        &self,
+        proof: MerkleProofJson,
+        commitment_hash: &str,
+    ) -> Result<bool, DepositTreeError> {
+        let leaf = commitment_to_leaf(commitment_hash)?;
+        Ok(self
+            .builder
+            .read()
+            .await
+            .verify_proof(proof.into(), leaf)
+            .await?)
+    }
+}
+
+/// Commitment hashes are stored as hex felts; the tree works on their 32-byte big-endian form
+fn commitment_to_leaf(commitment_hash: &str) -> Result<[u8; 32], DepositTreeError> {
+    Felt::from_hex(commitment_hash)
+        .map(|felt| felt.to_bytes_be())
+        .map_err(|_| DepositTreeError::InvalidCommitment(commitment_hash.to_string()))
+}
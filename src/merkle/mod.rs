@@ -0,0 +1,7 @@
+pub mod deposit_tree;
+pub mod withdrawal_tree;
+
+pub use deposit_tree::{
+    verify_tree_sync, DepositTree, DepositTreeError, MerkleProofJson, TreeSyncError,
+};
+pub use withdrawal_tree::{WithdrawalTree, WithdrawalTreeError};
@@ -0,0 +1,70 @@
+use std::sync::Arc;
+use tokio::sync::RwLock;
+use tree_builder::{error::TreeBuilderError, l2_tree::L2MerkleTreeBuilder};
+
+#[derive(Debug, thiserror::Error)]
+pub enum WithdrawalTreeError {
+    #[error("Withdrawal commitment {index} is past the tree's {elements_count} leaves")]
+    MissingLeaves { index: u64, elements_count: u64 },
+
+    #[error("Merkle tree error: {0}")]
+    Tree(#[from] TreeBuilderError),
+}
+
+/// L2 withdrawal commitment tree, fed by the L2 event watcher as the contract appends
+/// commitments. It can be checkpointed and restored through `GET`/`POST /merkle/checkpoint` so a
+/// restarted sequencer does not rebuild it from scratch.
+#[derive(Clone, Default)]
+pub struct WithdrawalTree {
+    builder: Arc<RwLock<L2MerkleTreeBuilder>>,
+}
+
+impl WithdrawalTree {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Hex-encoded checkpoint of every node in the tree
+    pub async fn checkpoint(&self) -> Result<String, TreeBuilderError> {
+        self.builder.read().await.serialize_to_hex().await
+    }
+
+    /// Replaces the tree with the one in `checkpoint`, leaving it untouched if the checkpoint
+    /// is invalid, and returns the restored root
+    pub async fn restore(&self, checkpoint: &str) -> Result<[u8; 32], TreeBuilderError> {
+        let restored = L2MerkleTreeBuilder::deserialize_from_hex(checkpoint).await?;
+        let root = restored.get_root().await?;
+        *self.builder.write().await = restored;
+        Ok(root)
+    }
+
+    /// Appends the commitment the L2 contract added at `index`, returning whether it was new.
+    ///
+    /// Commitments the tree already holds are skipped, so refetched events are harmless. An
+    /// index past the next free one fails with `MissingLeaves`, since appending it would misplace
+    /// every later leaf; restoring a checkpoint that covers the gap fixes that.
+    pub async fn append(
+        &self,
+        index: u64,
+        commitment: [u8; 32],
+    ) -> Result<bool, WithdrawalTreeError> {
+        let mut builder = self.builder.write().await;
+        let elements_count = builder.current_elements_count();
+        if index < elements_count {
+            return Ok(false);
+        }
+        if index > elements_count {
+            return Err(WithdrawalTreeError::MissingLeaves {
+                index,
+                elements_count,
+            });
+        }
+
+        builder.build_merkle(vec![commitment]).await?;
+        Ok(true)
+    }
+
+    pub async fn get_root(&self) -> Result<[u8; 32], TreeBuilderError> {
+        self.builder.read().await.get_root().await
+    }
+}
@@ -0,0 +1,3 @@
+// The service predates this module file and keeps its own file name
+#[allow(clippy::module_inception)]
+pub mod oracle_service;
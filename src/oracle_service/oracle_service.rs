@@ -1,33 +1,210 @@
-use crate::config::AppConfig;
+use crate::config::{AppConfig, BASIS_POINTS, DEFAULT_TOLERANCE_BPS};
+use crate::db::database::{
+    insert_tvl_snapshot, l1_vault_tvl_chain, L1_TOTAL_TVL_CHAIN, L2_TVL_CHAIN,
+};
+use crate::utils::tvl_diff_bps;
+use ethers::abi::{parse_abi, Abi};
+use ethers::contract::AbiError;
 use ethers::prelude::*;
+use sqlx::PgPool;
+use std::collections::HashMap;
+use std::sync::Arc;
 use std::time::Duration;
 use tokio::time::sleep;
 
-// Constants
-const DEFAULT_TOLERANCE_PERCENT: f64 = 0.01; // 1%
-
-pub async fn initializer() {
-    // let l1_provider =
-    //     Provider::<Http>::try_from(config.ethereum.rpc_url.clone()).expect("Invalid L1 RPC URL");
-    // let l2_provider =
-    //     Provider::<Http>::try_from(config.starknet.rpc_url.clone()).expect("Invalid L2 RPC URL");
-
-    // let l1_contract = Contract::new(
-    //     config.contracts.l1_contract_address.parse().unwrap(),
-    //     l1_abi(),
-    //     Arc::new(l1_provider),
-    // );
-    // let l2_contract = Contract::new(
-    //     config.contracts.l2_contract_address.parse().unwrap(),
-    //     l2_abi(),
-    //     Arc::new(l2_provider),
-    // );
-
-    // tokio::spawn(async move {
-    //     sync_tvl(l1_contract, l2_contract, &config)
-    //         .await
-    //         .expect("TVL sync failed");
-    // });
+/// Converted amounts are in USD cents, i.e. two decimals
+const USD_CENTS_DECIMALS: u32 = 2;
+
+#[derive(Debug, thiserror::Error)]
+pub enum OracleError {
+    #[error("No price feed configured for token {0:?}")]
+    UnknownToken(Address),
+
+    #[error("Price feed ABI error: {0}")]
+    Abi(#[from] AbiError),
+
+    #[error("Price feed call failed: {0}")]
+    Feed(#[from] ContractError<Provider<Http>>),
+
+    #[error("Price feed returned a non-positive answer: {0}")]
+    InvalidPrice(I256),
+
+    #[error("Converting {amount} of token {token:?} to USD overflowed")]
+    Overflow { amount: U256, token: Address },
+
+    #[error("Deposit of {expected_cents} USD cents does not match the {actual_cents} cents of tokens sent")]
+    AmountMismatch {
+        expected_cents: U256,
+        actual_cents: U256,
+    },
+
+    #[error("Summing the TVL of {0} L1 vaults overflowed")]
+    TvlOverflow(usize),
+
+    #[error("{0} does not fit in 128 bits")]
+    TooLarge(U256),
+}
+
+fn to_u128(value: U256) -> Result<u128, OracleError> {
+    u128::try_from(value).map_err(|_| OracleError::TooLarge(value))
+}
+
+/// Chainlink aggregator reporting the USD price of one token
+#[derive(Debug, Clone)]
+pub struct ChainlinkFeed {
+    contract: Contract<Provider<Http>>,
+    /// Decimals of the token whose amounts are converted with this feed
+    token_decimals: u8,
+}
+
+impl ChainlinkFeed {
+    pub fn new(feed_address: Address, token_decimals: u8, client: Arc<Provider<Http>>) -> Self {
+        let abi = parse_abi(&[
+            "function decimals() external view returns (uint8)",
+            "function latestRoundData() external view returns (uint80 roundId, int256 answer, uint256 startedAt, uint256 updatedAt, uint80 answeredInRound)",
+        ])
+        .expect("Chainlink aggregator ABI is valid");
+
+        Self {
+            contract: Contract::new(feed_address, abi, client),
+            token_decimals,
+        }
+    }
+
+    /// Latest answer of the feed along with the number of decimals it carries
+    async fn latest_answer(&self) -> Result<(U256, u32), OracleError> {
+        let decimals = self
+            .contract
+            .method::<_, u8>("decimals", ())?
+            .call()
+            .await?;
+        let (_, answer, _, _, _) = self
+            .contract
+            .method::<_, (U256, I256, U256, U256, U256)>("latestRoundData", ())?
+            .call()
+            .await?;
+
+        if answer <= I256::zero() {
+            return Err(OracleError::InvalidPrice(answer));
+        }
+        Ok((answer.into_raw(), decimals as u32))
+    }
+}
+
+/// Converts token amounts to USD using one Chainlink feed per token
+#[derive(Debug, Clone, Default)]
+pub struct TokenPriceOracle {
+    feeds: HashMap<Address, ChainlinkFeed>,
+}
+
+impl TokenPriceOracle {
+    pub fn new(feeds: HashMap<Address, ChainlinkFeed>) -> Self {
+        Self { feeds }
+    }
+
+    fn feed(&self, token: Address) -> Result<&ChainlinkFeed, OracleError> {
+        self.feeds
+            .get(&token)
+            .ok_or(OracleError::UnknownToken(token))
+    }
+
+    /// Current USD price of one whole `token`
+    pub async fn get_price_usd(&self, token: Address) -> Result<f64, OracleError> {
+        let (answer, decimals) = self.feed(token)?.latest_answer().await?;
+        Ok(to_u128(answer)? as f64 / 10f64.powi(decimals as i32))
+    }
+
+    /// Value of `amount` (in the token's smallest unit) in USD cents, rounded down
+    pub async fn convert_to_usd(&self, amount: U256, token: Address) -> Result<U256, OracleError> {
+        let feed = self.feed(token)?;
+        let (answer, price_decimals) = feed.latest_answer().await?;
+
+        let scale = U256::exp10(feed.token_decimals as usize + price_decimals as usize);
+        amount
+            .checked_mul(answer)
+            .and_then(|value| value.checked_mul(U256::exp10(USD_CENTS_DECIMALS as usize)))
+            .map(|value| value / scale)
+            .ok_or(OracleError::Overflow { amount, token })
+    }
+
+    /// Checks that `token_amount` of `token` is worth `expected_cents`, within `tolerance_bps`
+    pub async fn verify_usd_amount(
+        &self,
+        expected_cents: U256,
+        token_amount: U256,
+        token: Address,
+        tolerance_bps: u32,
+    ) -> Result<(), OracleError> {
+        let actual_cents = self.convert_to_usd(token_amount, token).await?;
+
+        if !within_tolerance(expected_cents, actual_cents, tolerance_bps) {
+            return Err(OracleError::AmountMismatch {
+                expected_cents,
+                actual_cents,
+            });
+        }
+        Ok(())
+    }
+}
+
+/// Whether `actual` is within `tolerance_bps` of `expected`
+fn within_tolerance(expected: U256, actual: U256, tolerance_bps: u32) -> bool {
+    let diff = if expected > actual {
+        expected - actual
+    } else {
+        actual - expected
+    };
+    diff.saturating_mul(U256::from(BASIS_POINTS))
+        <= expected.saturating_mul(U256::from(tolerance_bps))
+}
+
+/// `get_total_tvl()` of an L1 vault
+fn l1_abi() -> Abi {
+    parse_abi(&["function get_total_tvl() external view returns (uint256)"])
+        .expect("L1 vault ABI is valid")
+}
+
+/// `get_total_tvl()` and `update_tvl(uint256)` of the L2 oracle
+fn l2_abi() -> Abi {
+    parse_abi(&[
+        "function get_total_tvl() external view returns (uint256)",
+        "function update_tvl(uint256 new_tvl) external",
+    ])
+    .expect("L2 oracle ABI is valid")
+}
+
+/// Runs [`sync_tvl`] on the L1 vaults and L2 oracle in `config`, until it fails
+pub async fn initializer(
+    config: AppConfig,
+    db_pool: PgPool,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let l1_provider = Arc::new(Provider::<Http>::try_from(config.ethereum.get_rpc_url())?);
+    let l2_provider = Arc::new(Provider::<Http>::try_from(config.starknet.get_rpc_url())?);
+
+    let l1_contracts = config
+        .contracts
+        .l1_tvl_contracts()
+        .into_iter()
+        .map(|address| {
+            let address = address
+                .parse::<Address>()
+                .map_err(|e| format!("Invalid L1 TVL contract {}: {}", address, e))?;
+            Ok(Contract::new(address, l1_abi(), l1_provider.clone()))
+        })
+        .collect::<Result<Vec<_>, Box<dyn std::error::Error>>>()?;
+    let l2_address = config
+        .contracts
+        .l2_contract_address
+        .parse::<Address>()
+        .map_err(|e| {
+            format!(
+                "Invalid L2 oracle contract {}: {}",
+                config.contracts.l2_contract_address, e
+            )
+        })?;
+    let l2_contract = Contract::new(l2_address, l2_abi(), l2_provider);
+
+    sync_tvl(l1_contracts, l2_contract, &db_pool, &config).await
 }
 
 /// Fetch TVL from the L1 contract
@@ -40,6 +217,35 @@ async fn fetch_l1_tvl(
         .await
 }
 
+/// Fetch the TVL of every L1 vault, along with its address
+async fn fetch_l1_vault_tvls(
+    l1_contracts: &[Contract<Provider<Http>>],
+) -> Result<Vec<(Address, U256)>, ContractError<Provider<Http>>> {
+    let mut vault_tvls = Vec::with_capacity(l1_contracts.len());
+    for contract in l1_contracts {
+        vault_tvls.push((contract.address(), fetch_l1_tvl(contract).await?));
+    }
+    Ok(vault_tvls)
+}
+
+/// Snapshot rows of one L1 read: each vault's TVL under `l1-<address>`, then their sum under
+/// `l1-total`. Returns the rows and the sum.
+fn l1_tvl_snapshots(
+    vault_tvls: &[(Address, U256)],
+) -> Result<(Vec<(String, U256)>, U256), OracleError> {
+    let total = vault_tvls
+        .iter()
+        .try_fold(U256::zero(), |total, (_, tvl)| total.checked_add(*tvl))
+        .ok_or(OracleError::TvlOverflow(vault_tvls.len()))?;
+
+    let snapshots = vault_tvls
+        .iter()
+        .map(|(address, tvl)| (l1_vault_tvl_chain(&format!("{:?}", address)), *tvl))
+        .chain(std::iter::once((L1_TOTAL_TVL_CHAIN.to_string(), total)))
+        .collect();
+    Ok((snapshots, total))
+}
+
 /// Fetch TVL from the L2 Oracle contract
 async fn fetch_l2_tvl(
     l2_contract: &Contract<Provider<Http>>,
@@ -62,30 +268,51 @@ async fn update_l2_tvl(
     Ok(())
 }
 
-/// Sync TVL between L1 and L2
+/// Records the TVL of each chain in `snapshots` as read at the contract's latest block
+async fn record_tvl_snapshots(
+    db_pool: &PgPool,
+    snapshots: &[(String, U256)],
+    contract: &Contract<Provider<Http>>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let block_number = contract.client().get_block_number().await?;
+    for (chain, tvl) in snapshots {
+        insert_tvl_snapshot(
+            db_pool,
+            chain,
+            &tvl.to_string(),
+            block_number.as_u64() as i64,
+        )
+        .await?;
+    }
+    Ok(())
+}
+
+/// Sync TVL between L1 and L2, recording every fetched value in `tvl_snapshots`. The L1 TVL
+/// is the sum of every vault in `l1_contracts`, all read through the same L1 provider.
 pub async fn sync_tvl(
-    l1_contract: Contract<Provider<Http>>,
+    l1_contracts: Vec<Contract<Provider<Http>>>,
     l2_contract: Contract<Provider<Http>>,
+    db_pool: &PgPool,
     config: &AppConfig,
 ) -> Result<(), Box<dyn std::error::Error>> {
-    let tolerance_percent = config
-        .oracle
-        .tolerance_percent
-        .unwrap_or(DEFAULT_TOLERANCE_PERCENT);
+    let Some(first_vault) = l1_contracts.first() else {
+        return Err("No L1 TVL contracts to sync".into());
+    };
+    let tolerance_bps = config.oracle.tolerance_bps.unwrap_or(DEFAULT_TOLERANCE_BPS);
     let polling_interval = Duration::from_secs(config.oracle.polling_interval_seconds);
 
     loop {
         // Fetch TVL values
-        let l1_tvl = fetch_l1_tvl(&l1_contract).await?;
+        let vault_tvls = fetch_l1_vault_tvls(&l1_contracts).await?;
+        let (l1_snapshots, l1_tvl) = l1_tvl_snapshots(&vault_tvls)?;
         let l2_tvl = fetch_l2_tvl(&l2_contract).await?;
+        record_tvl_snapshots(db_pool, &l1_snapshots, first_vault).await?;
+        record_tvl_snapshots(db_pool, &[(L2_TVL_CHAIN.to_string(), l2_tvl)], &l2_contract).await?;
 
-        // Calculate percentage difference
-        let l1_tvl_f64 = l1_tvl.as_u128() as f64;
-        let l2_tvl_f64 = l2_tvl.as_u128() as f64;
-        let diff = ((l1_tvl_f64 - l2_tvl_f64).abs() / l1_tvl_f64).max(0.0);
+        let diff_bps = tvl_diff_bps(to_u128(l1_tvl)?, to_u128(l2_tvl)?);
 
         // Check if update is needed
-        if diff > tolerance_percent {
+        if diff_bps > tolerance_bps as u128 {
             println!(
                 "Significant TVL difference detected: L1 = {}, L2 = {}, updating L2...",
                 l1_tvl, l2_tvl
@@ -102,3 +329,197 @@ pub async fn sync_tvl(
         sleep(polling_interval).await;
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ONE_ETHER: u128 = 1_000_000_000_000_000_000;
+
+    const DECIMALS_SELECTOR: &str = "0x313ce567";
+    const LATEST_ROUND_DATA_SELECTOR: &str = "0xfeaf968c";
+
+    fn word(value: u128) -> String {
+        format!("{:064x}", value)
+    }
+
+    /// Serves `decimals()` and `latestRoundData()` of the aggregator at `feed`
+    fn mock_chainlink_feed(feed: Address, decimals: u8, answer: u128) -> Vec<mockito::Mock> {
+        let call_to = |selector: &str| {
+            mockito::Matcher::AllOf(vec![
+                mockito::Matcher::Regex("eth_call".to_string()),
+                mockito::Matcher::Regex(format!("{:?}", feed)),
+                mockito::Matcher::Regex(selector.to_string()),
+            ])
+        };
+        let reply = |words: String| format!(r#"{{"jsonrpc":"2.0","id":1,"result":"0x{}"}}"#, words);
+
+        vec![
+            mockito::mock("POST", "/")
+                .match_body(call_to(DECIMALS_SELECTOR))
+                .with_status(200)
+                .with_header("content-type", "application/json")
+                .with_body(reply(word(decimals as u128)))
+                .create(),
+            mockito::mock("POST", "/")
+                .match_body(call_to(LATEST_ROUND_DATA_SELECTOR))
+                .with_status(200)
+                .with_header("content-type", "application/json")
+                .with_body(reply(
+                    [1, answer, 1_700_000_000, 1_700_000_000, 1]
+                        .map(word)
+                        .concat(),
+                ))
+                .create(),
+        ]
+    }
+
+    fn oracle_for(token: Address, feed: Address, token_decimals: u8) -> TokenPriceOracle {
+        let provider = Arc::new(Provider::<Http>::try_from(mockito::server_url()).unwrap());
+        TokenPriceOracle::new(HashMap::from([(
+            token,
+            ChainlinkFeed::new(feed, token_decimals, provider),
+        )]))
+    }
+
+    #[tokio::test]
+    async fn test_get_price_usd_scales_feed_answer() {
+        let (token, feed) = (Address::repeat_byte(0x11), Address::repeat_byte(0xa1));
+        // 2000.50 USD with Chainlink's usual 8 decimals
+        let _mocks = mock_chainlink_feed(feed, 8, 200_050_000_000);
+
+        let price = oracle_for(token, feed, 18)
+            .get_price_usd(token)
+            .await
+            .unwrap();
+
+        assert_eq!(price, 2000.5);
+    }
+
+    #[tokio::test]
+    async fn test_convert_to_usd_returns_cents() {
+        let (token, feed) = (Address::repeat_byte(0x12), Address::repeat_byte(0xa2));
+        let _mocks = mock_chainlink_feed(feed, 8, 200_000_000_000);
+        let oracle = oracle_for(token, feed, 18);
+        // 1.5 tokens at 2000 USD
+        let amount = U256::from(15 * ONE_ETHER / 10);
+
+        let cents = oracle.convert_to_usd(amount, token).await.unwrap();
+        assert_eq!(cents, U256::from(300_000));
+
+        // Within 1% of the oracle value, but not 3%
+        let close = oracle.verify_usd_amount(U256::from(301_000), amount, token, 100);
+        assert!(close.await.is_ok());
+        let far = oracle.verify_usd_amount(U256::from(310_000), amount, token, 100);
+        assert!(matches!(far.await, Err(OracleError::AmountMismatch { .. })));
+    }
+
+    #[tokio::test]
+    async fn test_non_positive_answer_is_rejected() {
+        let (token, feed) = (Address::repeat_byte(0x13), Address::repeat_byte(0xa3));
+        let _mocks = mock_chainlink_feed(feed, 8, 0);
+
+        let result = oracle_for(token, feed, 18).get_price_usd(token).await;
+
+        assert!(matches!(result, Err(OracleError::InvalidPrice(_))));
+    }
+
+    #[tokio::test]
+    async fn test_unknown_token_is_rejected() {
+        let oracle = TokenPriceOracle::default();
+
+        let result = oracle
+            .convert_to_usd(U256::one(), Address::repeat_byte(0x14))
+            .await;
+
+        assert!(matches!(result, Err(OracleError::UnknownToken(_))));
+    }
+
+    const GET_TOTAL_TVL_SELECTOR: &str = "0x27e003db";
+
+    /// Serves `get_total_tvl()` of the vault at `vault`
+    fn mock_vault(vault: Address, tvl: u128) -> mockito::Mock {
+        mockito::mock("POST", "/")
+            .match_body(mockito::Matcher::AllOf(vec![
+                mockito::Matcher::Regex("eth_call".to_string()),
+                mockito::Matcher::Regex(format!("{:?}", vault)),
+                mockito::Matcher::Regex(GET_TOTAL_TVL_SELECTOR.to_string()),
+            ]))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(format!(
+                r#"{{"jsonrpc":"2.0","id":1,"result":"0x{}"}}"#,
+                word(tvl)
+            ))
+            .create()
+    }
+
+    fn vault_contract(vault: Address) -> Contract<Provider<Http>> {
+        let provider = Arc::new(Provider::<Http>::try_from(mockito::server_url()).unwrap());
+        Contract::new(vault, l1_abi(), provider)
+    }
+
+    #[tokio::test]
+    async fn test_l1_tvl_sums_every_vault() {
+        let eth_vault = Address::repeat_byte(0xb1);
+        let usdc_vault = Address::repeat_byte(0xb2);
+        let _mocks = [
+            mock_vault(eth_vault, 3 * ONE_ETHER),
+            mock_vault(usdc_vault, 2 * ONE_ETHER),
+        ];
+
+        let vault_tvls =
+            fetch_l1_vault_tvls(&[vault_contract(eth_vault), vault_contract(usdc_vault)])
+                .await
+                .unwrap();
+        let (snapshots, total) = l1_tvl_snapshots(&vault_tvls).unwrap();
+
+        assert_eq!(total, U256::from(5 * ONE_ETHER));
+        assert_eq!(
+            snapshots,
+            vec![
+                (
+                    "l1-0xb1b1b1b1b1b1b1b1b1b1b1b1b1b1b1b1b1b1b1b1".to_string(),
+                    U256::from(3 * ONE_ETHER)
+                ),
+                (
+                    "l1-0xb2b2b2b2b2b2b2b2b2b2b2b2b2b2b2b2b2b2b2b2".to_string(),
+                    U256::from(2 * ONE_ETHER)
+                ),
+                ("l1-total".to_string(), U256::from(5 * ONE_ETHER)),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_l1_tvl_overflow_is_rejected() {
+        let vault_tvls = [
+            (Address::repeat_byte(0xb3), U256::MAX),
+            (Address::repeat_byte(0xb4), U256::one()),
+        ];
+
+        assert!(matches!(
+            l1_tvl_snapshots(&vault_tvls),
+            Err(OracleError::TvlOverflow(2))
+        ));
+    }
+
+    #[test]
+    fn test_values_past_u128_are_rejected() {
+        assert_eq!(to_u128(U256::from(u128::MAX)).unwrap(), u128::MAX);
+        assert!(matches!(
+            to_u128(U256::from(u128::MAX) + 1),
+            Err(OracleError::TooLarge(_))
+        ));
+    }
+
+    #[test]
+    fn test_within_tolerance() {
+        let expected = U256::from(10_000);
+
+        assert!(within_tolerance(expected, U256::from(10_100), 100));
+        assert!(within_tolerance(expected, U256::from(9_900), 100));
+        assert!(!within_tolerance(expected, U256::from(10_101), 100));
+        assert!(within_tolerance(U256::zero(), U256::zero(), 0));
+    }
+}
@@ -1,27 +1,96 @@
 use serde::Serialize;
+use starknet::core::types::Felt;
 use std::fs::File;
-use std::io::Write;
+use std::io::{Error, ErrorKind, Write};
 use std::path::Path;
 
+use crate::merkle::MerkleProofJson;
+
 #[derive(Serialize)]
 struct Cairo1Input {
-    data: Vec<Vec<u64>>,
+    data: Vec<Vec<String>>,
+}
+
+fn parse_felt(name: &str, value: &str) -> Result<Felt, Error> {
+    Felt::from_hex(value).map_err(|_| {
+        Error::new(
+            ErrorKind::InvalidInput,
+            format!("{} is not a valid felt252 hex string: {}", name, value),
+        )
+    })
 }
 
+fn felt_to_u64(name: &str, value: &str) -> Result<u64, Error> {
+    let felt = parse_felt(name, value)?;
+    u64::try_from(felt).map_err(|_| {
+        Error::new(
+            ErrorKind::InvalidInput,
+            format!("{} does not fit in a u64: {}", name, value),
+        )
+    })
+}
+
+/// Inputs of the Cairo1 bridge verification program
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct CairoProofInputs {
+    pub commitment_hash: u64,
+    pub proof: Vec<u64>,
+    pub new_root: u64,
+}
+
+impl CairoProofInputs {
+    /// Writes the inputs to `output_dir` through [`generate_cairo1_inputs`].
+    pub fn write(&self, output_dir: &str) -> Result<(), Error> {
+        generate_cairo1_inputs(
+            format!("{:#x}", self.commitment_hash),
+            self.proof.iter().map(|x| format!("{:#x}", x)).collect(),
+            format!("{:#x}", self.new_root),
+            output_dir,
+        )
+    }
+}
+
+/// Builds the verification program inputs for a commitment from its Merkle inclusion proof.
+///
+/// The program takes `u64` values, so the commitment, every sibling hash and the new root
+/// are rejected rather than truncated when they are wider than that.
+pub fn cairo_inputs_from_merkle_proof(
+    proof: &MerkleProofJson,
+    commitment_hash: &str,
+    new_root: &str,
+) -> Result<CairoProofInputs, Error> {
+    let siblings = proof
+        .siblings_hashes
+        .iter()
+        .map(|sibling| felt_to_u64("proof sibling", sibling))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(CairoProofInputs {
+        commitment_hash: felt_to_u64("commitment_hash", commitment_hash)?,
+        proof: siblings,
+        new_root: felt_to_u64("new_root", new_root)?,
+    })
+}
+
+/// Writes the Cairo1 program inputs as `input.cairo1.json` (hex) and `input.cairo1.txt` (decimal).
+///
+/// All values are felt252 hex strings, so inputs wider than `u64` are preserved exactly.
 pub fn generate_cairo1_inputs(
-    commitment_hash: u64,
-    proof_array: Vec<u64>,
-    new_root: u64,
+    commitment_hash: String,
+    proof_array: Vec<String>,
+    new_root: String,
     output_dir: &str,
-) -> Result<(), std::io::Error> {
+) -> Result<(), Error> {
     // Combine inputs into a single array
-    let mut input_data = vec![commitment_hash];
-    input_data.extend(proof_array);
-    input_data.push(new_root);
+    let mut input_data = vec![parse_felt("commitment_hash", &commitment_hash)?];
+    for element in &proof_array {
+        input_data.push(parse_felt("proof_array element", element)?);
+    }
+    input_data.push(parse_felt("new_root", &new_root)?);
 
     // Generate JSON file
     let json_data = Cairo1Input {
-        data: vec![input_data.clone()],
+        data: vec![input_data.iter().map(|x| format!("{:#x}", x)).collect()],
     };
     let json_string = serde_json::to_string_pretty(&json_data)?;
     let json_path = Path::new(output_dir).join("input.cairo1.json");
@@ -48,9 +117,9 @@ mod tests {
 
     #[test]
     fn test_generate_cairo1_inputs() {
-        let commitment_hash = 12345;
-        let proof_array = vec![67890, 111213];
-        let new_root = 141516;
+        let commitment_hash = "0x3039".to_string();
+        let proof_array = vec!["0x10932".to_string(), "0x1b26d".to_string()];
+        let new_root = "0x228cc".to_string();
         let output_dir = "test_output";
 
         // Create temporary output directory
@@ -63,12 +132,11 @@ mod tests {
         // Verify JSON file
         let json_path = Path::new(output_dir).join("input.cairo1.json");
         let json_content = fs::read_to_string(&json_path).unwrap();
-        let expected_json = r#"{
-  "data": [
-    [12345, 67890, 111213, 141516]
-  ]
-}"#;
-        assert_eq!(json_content.trim(), expected_json.trim());
+        let parsed: serde_json::Value = serde_json::from_str(&json_content).unwrap();
+        assert_eq!(
+            parsed,
+            serde_json::json!({ "data": [["0x3039", "0x10932", "0x1b26d", "0x228cc"]] })
+        );
 
         // Verify TXT file
         let txt_path = Path::new(output_dir).join("input.cairo1.txt");
@@ -79,4 +147,99 @@ mod tests {
         // Clean up
         fs::remove_dir_all(output_dir).unwrap();
     }
+
+    #[test]
+    fn test_generate_cairo1_inputs_preserves_values_above_u64() {
+        // 2^128 does not fit in a u64 and must round-trip unchanged
+        let two_pow_128 = "0x100000000000000000000000000000000".to_string();
+        let output_dir = "test_output_u128";
+        fs::create_dir_all(output_dir).unwrap();
+
+        generate_cairo1_inputs(
+            two_pow_128.clone(),
+            vec![two_pow_128.clone()],
+            "0x1".to_string(),
+            output_dir,
+        )
+        .expect("Failed to generate files");
+
+        let json_content =
+            fs::read_to_string(Path::new(output_dir).join("input.cairo1.json")).unwrap();
+        let parsed: serde_json::Value = serde_json::from_str(&json_content).unwrap();
+        assert_eq!(parsed["data"][0][0], two_pow_128);
+        assert_eq!(parsed["data"][0][1], two_pow_128);
+
+        let txt_content =
+            fs::read_to_string(Path::new(output_dir).join("input.cairo1.txt")).unwrap();
+        assert_eq!(
+            txt_content,
+            "[340282366920938463463374607431768211456 340282366920938463463374607431768211456 1]"
+        );
+
+        fs::remove_dir_all(output_dir).unwrap();
+    }
+
+    #[test]
+    fn test_generate_cairo1_inputs_rejects_invalid_hex() {
+        let result = generate_cairo1_inputs(
+            "not-a-felt".to_string(),
+            vec![],
+            "0x1".to_string(),
+            "test_output_invalid",
+        );
+        assert_eq!(result.unwrap_err().kind(), ErrorKind::InvalidInput);
+    }
+
+    fn merkle_proof(siblings: &[&str]) -> MerkleProofJson {
+        MerkleProofJson {
+            element_index: 1,
+            element_hash: "0x3039".to_string(),
+            siblings_hashes: siblings.iter().map(|s| s.to_string()).collect(),
+            peaks_hashes: vec![],
+            elements_count: 3,
+            root: "0x228cc".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_cairo_inputs_from_merkle_proof() {
+        let proof = merkle_proof(&["0x10932", "0x1b26d"]);
+
+        let inputs = cairo_inputs_from_merkle_proof(&proof, "0x3039", "0x228cc").unwrap();
+        assert_eq!(
+            inputs,
+            CairoProofInputs {
+                commitment_hash: 12345,
+                proof: vec![67890, 111213],
+                new_root: 141516,
+            }
+        );
+
+        let output_dir = "test_output_merkle_proof";
+        fs::create_dir_all(output_dir).unwrap();
+        inputs.write(output_dir).expect("Failed to generate files");
+        let txt_content =
+            fs::read_to_string(Path::new(output_dir).join("input.cairo1.txt")).unwrap();
+        assert_eq!(txt_content, "[12345 67890 111213 141516]");
+        fs::remove_dir_all(output_dir).unwrap();
+    }
+
+    #[test]
+    fn test_cairo_inputs_from_merkle_proof_rejects_values_above_u64() {
+        let proof = merkle_proof(&["0x10932", "0x10000000000000000"]);
+
+        let err = cairo_inputs_from_merkle_proof(&proof, "0x3039", "0x228cc").unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidInput);
+        assert_eq!(
+            err.to_string(),
+            "proof sibling does not fit in a u64: 0x10000000000000000"
+        );
+
+        let proof = merkle_proof(&[]);
+        assert!(cairo_inputs_from_merkle_proof(&proof, "0x3039", "0xffffffffffffffffff").is_err());
+        assert!(
+            cairo_inputs_from_merkle_proof(&proof, "0xffffffffffffffff", "0x1").is_ok(),
+            "u64::MAX must still be accepted"
+        );
+    }
 }
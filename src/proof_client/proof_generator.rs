@@ -1,50 +1,90 @@
+use serde::Deserialize;
 use std::fs;
+use std::io;
 use std::path::{Path, PathBuf};
 use std::process::Command;
+use thiserror::Error;
 
-pub fn run_scarb_build(project_path: &str) -> Result<PathBuf, String> {
+#[derive(Debug, Error)]
+pub enum ScarbBuildError {
+    #[error("No Scarb project in this directory: {path}")]
+    ProjectNotFound { path: String },
+
+    #[error("Scarb build failed (exit code: {exit_code:?}): {stderr}")]
+    BuildFailed {
+        exit_code: Option<i32>,
+        stderr: String,
+    },
+
+    #[error("Output file not found: {expected:?}")]
+    OutputMissing { expected: PathBuf },
+
+    #[error("Failed to read Scarb.toml: {0}")]
+    ManifestReadError(#[source] io::Error),
+
+    #[error("Failed to parse Scarb.toml: {0}")]
+    ManifestParseError(#[source] toml::de::Error),
+}
+
+#[derive(Debug, Deserialize)]
+struct ScarbManifest {
+    package: ScarbPackage,
+}
+
+#[derive(Debug, Deserialize)]
+struct ScarbPackage {
+    name: String,
+}
+
+pub fn run_scarb_build(project_path: &str) -> Result<PathBuf, ScarbBuildError> {
     let target_dir = Path::new(project_path);
 
     if !target_dir.exists() {
-        return Err(format!("No Scarb project in this directory: {}", project_path));
+        return Err(ScarbBuildError::ProjectNotFound {
+            path: project_path.to_string(),
+        });
     }
 
     println!("Building Scarb project at {:?}", target_dir);
 
-    let status = Command::new("scarb")
+    let output = Command::new("scarb")
         .arg("build")
-        .current_dir(&target_dir)
-        .status();
-
-    match status {
-        Ok(status) if status.success() => {
-            // we need to check the .toml file of the project
-            // so we can get the package name and compute its file/out folder
-            let scarb_toml_path = target_dir.join("Scarb.toml");
-            let toml_str = fs::read_to_string(&scarb_toml_path)
-                .map_err(|e| format!("Failed to read Scarb.toml: {}", e))?;
-            let parsed: toml::Value = toml_str
-                .parse()
-                .map_err(|e| format!("Failed to parse Scarb.toml: {}", e))?;
-
-            let package_name = parsed
-                .get("package")
-                .and_then(|pkg| pkg.get("name"))
-                .and_then(|name| name.as_str())
-                .ok_or("Could not find package.name in Scarb.toml")?;
-
-            let output_file = target_dir
-                .join("target/dev")
-                .join(format!("{}.sierra.json", package_name));
-
-            if output_file.exists() {
-                println!("Output file found: {:?}", output_file);
-                Ok(output_file)
-            } else {
-                Err(format!("❌ Output file not found: {:?}", output_file))
-            }
-        }
-        Ok(status) => Err(format!("❌ Build failed. Exit code: {:?}", status.code())),
-        Err(err) => Err(format!("❌ Failed to execute Scarb: {}", err)),
+        .current_dir(target_dir)
+        .output()
+        .map_err(|err| ScarbBuildError::BuildFailed {
+            exit_code: None,
+            stderr: format!("Failed to execute Scarb: {}", err),
+        })?;
+
+    if !output.status.success() {
+        return Err(ScarbBuildError::BuildFailed {
+            exit_code: output.status.code(),
+            stderr: String::from_utf8_lossy(&output.stderr).into_owned(),
+        });
+    }
+
+    locate_build_output(target_dir)
+}
+
+/// Path of the Sierra file `scarb build` writes for the package in `project_dir`
+pub fn locate_build_output(project_dir: &Path) -> Result<PathBuf, ScarbBuildError> {
+    // we need to check the .toml file of the project
+    // so we can get the package name and compute its file/out folder
+    let toml_str = fs::read_to_string(project_dir.join("Scarb.toml"))
+        .map_err(ScarbBuildError::ManifestReadError)?;
+    let manifest: ScarbManifest =
+        toml::from_str(&toml_str).map_err(ScarbBuildError::ManifestParseError)?;
+
+    let output_file = project_dir
+        .join("target/dev")
+        .join(format!("{}.sierra.json", manifest.package.name));
+
+    if output_file.exists() {
+        println!("Output file found: {:?}", output_file);
+        Ok(output_file)
+    } else {
+        Err(ScarbBuildError::OutputMissing {
+            expected: output_file,
+        })
     }
 }
@@ -0,0 +1,474 @@
+use alloy::primitives::U256;
+use dashmap::DashMap;
+use starknet::core::types::Felt;
+use std::future::Future;
+use std::hash::Hash;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::broadcast::error::RecvError;
+use tracing::warn;
+
+use crate::{
+    events::{l1_event_watcher::ZeroXBridge::DepositEvent, CommitmentLog, EventBus},
+    queue::l1_queue::ValidationError,
+    utils::{compute_poseidon_commitment_hash, parse_stark_pub_key, BurnData, HashMethod},
+};
+
+/// How long a recorded event waits for its counterpart before it is dropped
+pub const DEFAULT_EVENT_TTL: Duration = Duration::from_secs(24 * 60 * 60);
+/// Most events of each side kept at once; the oldest are dropped first past it
+pub const DEFAULT_MAX_EVENTS: usize = 10_000;
+
+/// An event kept until the other side of its deposit is checked against it
+#[derive(Debug, Clone)]
+struct Recorded<T> {
+    value: T,
+    recorded_at: Instant,
+}
+
+/// A recorded L1 event with the timestamp of the block that emitted it
+#[derive(Debug, Clone)]
+struct L1Deposit {
+    event: DepositEvent,
+    timestamp: u64,
+}
+
+/// Cross-checks the L1 `DepositEvent` of a deposit against the L2 `CommitmentLog` relayed for it.
+///
+/// L1 commits to a deposit with a Keccak256 hash and L2 with a Poseidon hash over the same
+/// fields, so each side's hash is recomputed from the fields its event carries, and the two
+/// events must agree on the amount. L1 events are keyed by their commitment hash and L2 logs by
+/// theirs; a deposit is only checked against the sides that have been recorded.
+///
+/// Events are kept for at most `ttl` and `max_events` of each side, and dropped once their
+/// deposit has been checked against both. Cloning the verifier is cheap and every clone shares
+/// the same events.
+#[derive(Debug, Clone)]
+pub struct CommitmentHashVerifier {
+    l1_events: Arc<DashMap<U256, Recorded<L1Deposit>>>,
+    l2_logs: Arc<DashMap<Felt, Recorded<CommitmentLog>>>,
+    ttl: Duration,
+    max_events: usize,
+}
+
+impl Default for CommitmentHashVerifier {
+    fn default() -> Self {
+        Self::with_limits(DEFAULT_EVENT_TTL, DEFAULT_MAX_EVENTS)
+    }
+}
+
+impl CommitmentHashVerifier {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_limits(ttl: Duration, max_events: usize) -> Self {
+        Self {
+            l1_events: Arc::new(DashMap::new()),
+            l2_logs: Arc::new(DashMap::new()),
+            ttl,
+            max_events,
+        }
+    }
+
+    /// Records `event`, emitted in a block with timestamp `timestamp`
+    pub fn record_l1_event(&self, event: &DepositEvent, timestamp: u64) {
+        self.insert(
+            &self.l1_events,
+            event.commitmentHash,
+            L1Deposit {
+                event: event.clone(),
+                timestamp,
+            },
+        );
+    }
+
+    /// Records the L2 log of a deposit, keyed by the L2 commitment hash it carries
+    pub fn record_l2_log(&self, log: CommitmentLog) -> Result<(), ValidationError> {
+        let key = parse_l2_commitment(&log.commitment_hash)?;
+        self.insert(&self.l2_logs, key, log);
+        Ok(())
+    }
+
+    /// Records every L2 log published on `bus` from now on, until all of its publishers are
+    /// dropped
+    pub fn record_l2_logs(&self, bus: &EventBus<CommitmentLog>) -> impl Future<Output = ()> {
+        let verifier = self.clone();
+        let mut logs = bus.subscribe();
+        async move {
+            loop {
+                match logs.recv().await {
+                    Ok(log) => {
+                        let commitment_hash = log.commitment_hash.clone();
+                        if let Err(e) = verifier.record_l2_log(log) {
+                            warn!("Not verifying L2 log {}: {}", commitment_hash, e);
+                        }
+                    }
+                    Err(RecvError::Lagged(skipped)) => {
+                        warn!("Commitment verifier skipped {} L2 logs", skipped)
+                    }
+                    Err(RecvError::Closed) => break,
+                }
+            }
+        }
+    }
+
+    /// Number of L1 events and L2 logs currently kept
+    pub fn recorded(&self) -> (usize, usize) {
+        (self.l1_events.len(), self.l2_logs.len())
+    }
+
+    /// The commitment the L1 bridge computes for `event`, emitted in a block with timestamp
+    /// `timestamp`: see [`BurnData::compute_commitment_hash`]
+    pub fn verify_l1_hash(
+        event: &DepositEvent,
+        timestamp: u64,
+    ) -> Result<[u8; 32], ValidationError> {
+        let invalid = |reason: String| ValidationError::ProofMismatch {
+            commitment_hash: format!("0x{:x}", event.commitmentHash),
+            reason,
+        };
+        // `abi.encodePacked` widens the address to a left-padded 32-byte word
+        let caller = format!("0x{:0>64}", hex::encode(event.user));
+        let amount = u64::try_from(event.usdVal)
+            .map_err(|_| invalid(format!("usdVal {} does not fit in 64 bits", event.usdVal)))?;
+        let nonce = u64::try_from(event.nonce)
+            .map_err(|_| invalid(format!("nonce {} does not fit in 64 bits", event.nonce)))?;
+
+        Ok(BurnData::new(caller, amount, nonce, timestamp).compute_commitment_hash())
+    }
+
+    /// The commitment the L2 bridge computes for `log`, minted with the `nonce` and `timestamp`
+    /// of its L1 deposit: see [`compute_poseidon_commitment_hash`]
+    pub fn verify_l2_hash(
+        log: &CommitmentLog,
+        nonce: u64,
+        timestamp: u64,
+    ) -> Result<Felt, ValidationError> {
+        let invalid = |reason: String| ValidationError::ProofMismatch {
+            commitment_hash: log.commitment_hash.clone(),
+            reason,
+        };
+        let recipient = Felt::from_hex(&log.user)
+            .map_err(|_| invalid(format!("{:?} is not a valid felt", log.user)))?;
+        let amount = parse_u256_halves(&log.amount_low, &log.amount_high)
+            .and_then(|amount| u128::try_from(amount).ok())
+            .ok_or_else(|| {
+                invalid(format!(
+                    "amount {}:{} is not a valid u128",
+                    log.amount_high, log.amount_low
+                ))
+            })?;
+
+        Ok(compute_poseidon_commitment_hash(
+            recipient,
+            amount,
+            nonce,
+            timestamp,
+            HashMethod::BatchHash,
+            None,
+        ))
+    }
+
+    /// Checks that the L1 hash matches its event and, when given, that the L2 hash matches its
+    /// log and that both events refer to the same deposit
+    pub fn verify(
+        event: &DepositEvent,
+        timestamp: u64,
+        log: Option<&CommitmentLog>,
+    ) -> Result<(), ValidationError> {
+        let mismatch = |reason: String| ValidationError::ProofMismatch {
+            commitment_hash: format!("0x{:x}", event.commitmentHash),
+            reason,
+        };
+
+        let l1_hash = U256::from_be_bytes(Self::verify_l1_hash(event, timestamp)?);
+        if l1_hash != event.commitmentHash {
+            return Err(mismatch(format!(
+                "L1 event commits to 0x{:x} but its fields hash to 0x{:x}",
+                event.commitmentHash, l1_hash
+            )));
+        }
+
+        let Some(log) = log else {
+            return Ok(());
+        };
+        let l2_amount = parse_u256_halves(&log.amount_low, &log.amount_high);
+        if l2_amount != Some(event.usdVal) {
+            return Err(mismatch(format!(
+                "L1 deposited {} but L2 recorded {}:{}",
+                event.usdVal, log.amount_high, log.amount_low
+            )));
+        }
+
+        // The L1 hash only matched if the nonce fits in 64 bits
+        let nonce = u64::try_from(event.nonce).unwrap_or_default();
+        let l2_hash = Self::verify_l2_hash(log, nonce, timestamp)?;
+        if Felt::from_hex(&log.commitment_hash).ok() != Some(l2_hash) {
+            return Err(mismatch(format!(
+                "L2 log commits to {} but its fields hash to {}",
+                log.commitment_hash,
+                l2_hash.to_hex_string()
+            )));
+        }
+
+        Ok(())
+    }
+
+    /// Verifies the recorded events of the deposit committed on L1 as `commitment_hash` and
+    /// minted on L2 to `recipient`.
+    ///
+    /// Deposits without a recorded L1 event pass, and so does the L2 side while no log has
+    /// been recorded for it. Both events are dropped once checked.
+    pub fn check(&self, commitment_hash: &str, recipient: &str) -> Result<(), ValidationError> {
+        let key = parse_l1_commitment(commitment_hash)?;
+        let Some(l1) = self
+            .l1_events
+            .get(&key)
+            .filter(|entry| entry.recorded_at.elapsed() <= self.ttl)
+            .map(|entry| entry.value.clone())
+        else {
+            return Ok(());
+        };
+
+        // The L2 log is found under the hash L2 must have computed for this deposit
+        let l2_key = parse_stark_pub_key(recipient)
+            .ok()
+            .zip(u128::try_from(l1.event.usdVal).ok())
+            .zip(u64::try_from(l1.event.nonce).ok())
+            .map(|((recipient, amount), nonce)| {
+                compute_poseidon_commitment_hash(
+                    recipient,
+                    amount,
+                    nonce,
+                    l1.timestamp,
+                    HashMethod::BatchHash,
+                    None,
+                )
+            });
+        let log = l2_key.and_then(|l2_key| {
+            self.l2_logs
+                .get(&l2_key)
+                .filter(|entry| entry.recorded_at.elapsed() <= self.ttl)
+                .map(|entry| entry.value.clone())
+        });
+
+        Self::verify(&l1.event, l1.timestamp, log.as_ref())?;
+        if let (Some(l2_key), Some(_)) = (l2_key, log) {
+            self.l1_events.remove(&key);
+            self.l2_logs.remove(&l2_key);
+        }
+        Ok(())
+    }
+
+    /// Inserts into `map`, first dropping expired entries and then the oldest ones when it is
+    /// full
+    fn insert<K, T>(&self, map: &DashMap<K, Recorded<T>>, key: K, value: T)
+    where
+        K: Eq + Hash + Clone,
+    {
+        if map.len() >= self.max_events && !map.contains_key(&key) {
+            map.retain(|_, entry| entry.recorded_at.elapsed() <= self.ttl);
+
+            let excess = (map.len() + 1).saturating_sub(self.max_events);
+            if excess > 0 {
+                let mut by_age: Vec<(K, Instant)> = map
+                    .iter()
+                    .map(|entry| (entry.key().clone(), entry.recorded_at))
+                    .collect();
+                by_age.sort_by_key(|(_, recorded_at)| *recorded_at);
+                for (key, _) in by_age.into_iter().take(excess) {
+                    map.remove(&key);
+                }
+            }
+        }
+
+        map.insert(
+            key,
+            Recorded {
+                value,
+                recorded_at: Instant::now(),
+            },
+        );
+    }
+}
+
+/// Deposit commitment hashes are stored as hex, with or without a `0x` prefix
+fn parse_l1_commitment(commitment_hash: &str) -> Result<U256, ValidationError> {
+    U256::from_str_radix(
+        commitment_hash
+            .strip_prefix("0x")
+            .unwrap_or(commitment_hash),
+        16,
+    )
+    .map_err(|_| ValidationError::InvalidCommitment)
+}
+
+fn parse_l2_commitment(commitment_hash: &str) -> Result<Felt, ValidationError> {
+    Felt::from_hex(commitment_hash).map_err(|_| ValidationError::InvalidCommitment)
+}
+
+fn parse_u256_halves(low: &str, high: &str) -> Option<U256> {
+    let parse_half = |half: &str| u128::from_str_radix(half.strip_prefix("0x").unwrap_or(half), 16);
+    let (low, high) = (parse_half(low).ok()?, parse_half(high).ok()?);
+    Some((U256::from(high) << 128) | U256::from(low))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::events::l1_event_watcher::ZeroXBridge::AssetType;
+    use alloy::primitives::Address;
+
+    const TIMESTAMP: u64 = 1_700_000_000;
+    const RECIPIENT: &str = "0x5a11ce";
+
+    fn deposit_event(usd_val: u64) -> DepositEvent {
+        let mut event = DepositEvent {
+            assetType: AssetType::ERC20,
+            usdVal: U256::from(usd_val),
+            nonce: U256::from(7),
+            leafIndex: U256::from(0),
+            depositId: U256::from(1),
+            token: Address::from([0xaa; 20]),
+            user: Address::from([0xbb; 20]),
+            commitmentHash: U256::ZERO,
+            newRoot: U256::ZERO,
+            elementCount: U256::from(1),
+        };
+        event.commitmentHash =
+            U256::from_be_bytes(CommitmentHashVerifier::verify_l1_hash(&event, TIMESTAMP).unwrap());
+        event
+    }
+
+    fn commitment_log(amount: u64) -> CommitmentLog {
+        let mut log = CommitmentLog {
+            commitment_hash: String::new(),
+            block_number: 10,
+            transaction_hash: "0x1".to_string(),
+            user: RECIPIENT.to_string(),
+            amount_low: format!("0x{:x}", amount),
+            amount_high: "0x0".to_string(),
+        };
+        log.commitment_hash = CommitmentHashVerifier::verify_l2_hash(&log, 7, TIMESTAMP)
+            .unwrap()
+            .to_hex_string();
+        log
+    }
+
+    #[test]
+    fn test_l1_hash_matches_burn_data() {
+        let event = deposit_event(1000);
+        let burn_data = BurnData::new(
+            format!("0x{:0>64}", hex::encode(event.user)),
+            1000,
+            7,
+            TIMESTAMP,
+        );
+
+        assert_eq!(
+            CommitmentHashVerifier::verify_l1_hash(&event, TIMESTAMP).unwrap(),
+            burn_data.compute_commitment_hash()
+        );
+    }
+
+    #[test]
+    fn test_matching_events_verify_and_are_dropped() {
+        let verifier = CommitmentHashVerifier::new();
+        let event = deposit_event(1000);
+        let commitment_hash = format!("{:x}", event.commitmentHash);
+        verifier.record_l1_event(&event, TIMESTAMP);
+        verifier.record_l2_log(commitment_log(1000)).unwrap();
+
+        assert!(verifier
+            .check(&format!("0x{}", commitment_hash), RECIPIENT)
+            .is_ok());
+        assert_eq!(verifier.recorded(), (0, 0));
+    }
+
+    #[test]
+    fn test_amount_mismatch_is_reported() {
+        let result = CommitmentHashVerifier::verify(
+            &deposit_event(1000),
+            TIMESTAMP,
+            Some(&commitment_log(999)),
+        );
+
+        assert!(matches!(
+            result,
+            Err(ValidationError::ProofMismatch { reason, .. }) if reason.contains("deposited")
+        ));
+    }
+
+    #[test]
+    fn test_tampered_hashes_are_reported() {
+        let mut event = deposit_event(1000);
+        event.commitmentHash += U256::from(1);
+        assert!(matches!(
+            CommitmentHashVerifier::verify(&event, TIMESTAMP, None),
+            Err(ValidationError::ProofMismatch { .. })
+        ));
+
+        // The timestamp is part of the L1 hash
+        assert!(matches!(
+            CommitmentHashVerifier::verify(&deposit_event(1000), TIMESTAMP + 1, None),
+            Err(ValidationError::ProofMismatch { .. })
+        ));
+
+        let mut log = commitment_log(1000);
+        log.commitment_hash = "0x1234".to_string();
+        assert!(matches!(
+            CommitmentHashVerifier::verify(&deposit_event(1000), TIMESTAMP, Some(&log)),
+            Err(ValidationError::ProofMismatch { .. })
+        ));
+    }
+
+    #[test]
+    fn test_deposits_missing_a_side_pass() {
+        let verifier = CommitmentHashVerifier::new();
+        let event = deposit_event(1000);
+        verifier.record_l1_event(&event, TIMESTAMP);
+
+        assert!(verifier
+            .check(&format!("{:x}", event.commitmentHash), RECIPIENT)
+            .is_ok());
+        // Kept until its L2 log arrives
+        assert_eq!(verifier.recorded(), (1, 0));
+        assert!(verifier.check("0x1234", RECIPIENT).is_ok());
+        assert!(matches!(
+            verifier.check("not-hex", RECIPIENT),
+            Err(ValidationError::InvalidCommitment)
+        ));
+    }
+
+    #[test]
+    fn test_oldest_events_are_dropped_past_the_limit() {
+        let verifier = CommitmentHashVerifier::with_limits(DEFAULT_EVENT_TTL, 2);
+        for amount in [1000, 2000, 3000] {
+            verifier.record_l1_event(&deposit_event(amount), TIMESTAMP);
+        }
+
+        assert_eq!(verifier.recorded(), (2, 0));
+        assert!(!verifier
+            .l1_events
+            .contains_key(&deposit_event(1000).commitmentHash));
+    }
+
+    #[test]
+    fn test_expired_events_are_ignored_and_pruned() {
+        let verifier = CommitmentHashVerifier::with_limits(Duration::ZERO, 1);
+        let mut event = deposit_event(1000);
+        verifier.record_l1_event(&event, TIMESTAMP);
+
+        // Expired, so its tampered hash is never checked
+        event.commitmentHash += U256::from(1);
+        verifier.record_l1_event(&event, TIMESTAMP);
+        std::thread::sleep(Duration::from_millis(1));
+        assert!(verifier
+            .check(&format!("{:x}", event.commitmentHash), RECIPIENT)
+            .is_ok());
+
+        verifier.record_l2_log(commitment_log(1000)).unwrap();
+        assert_eq!(verifier.recorded(), (1, 1));
+    }
+}
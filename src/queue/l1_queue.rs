@@ -1,11 +1,25 @@
+use alloy::rpc::types::Log;
+use futures_util::future::BoxFuture;
 use sqlx::PgPool;
+use std::sync::Arc;
 use std::time::Duration;
+use tokio::sync::watch;
 use tokio::time::sleep;
+use tokio_stream::{Stream, StreamExt};
 use tracing::{error, info, trace, warn};
 
 use crate::{
-    config::QueueConfig,
-    db::database::{fetch_pending_deposits, process_deposit_retry, update_deposit_status, Deposit},
+    config::{AppConfig, QueueConfig},
+    db::database::{
+        claim_pending_deposit, fetch_pending_deposit_ids, process_deposit_retry,
+        update_deposit_status, update_last_processed_block, BlockTrackerKey, Deposit,
+    },
+    events::{
+        l1_event_watcher::{record_deposit_event, L1EventStreamError, ZeroXBridge},
+        CommitmentHashRegistry,
+    },
+    merkle::DepositTree,
+    queue::commitment_verifier::CommitmentHashVerifier,
 };
 
 #[derive(Debug, thiserror::Error)]
@@ -24,50 +38,192 @@ pub enum ValidationError {
 
     #[error("Commitment not found after max retries")]
     MaxRetriesExceeded,
+
+    #[error("L1 and L2 commitments for {commitment_hash} do not correspond: {reason}")]
+    ProofMismatch {
+        commitment_hash: String,
+        reason: String,
+    },
+}
+
+/// Action run for each deposit whose commitment has been confirmed on L1, before the queue
+/// marks it `processed`.
+///
+/// An error sends the deposit back for a retry, as with any other validation failure. The
+/// deposit's row stays locked by the queue until its cycle commits, so hooks must not update it
+/// through another connection.
+pub type CommitmentFoundHook =
+    Arc<dyn Fn(&Deposit) -> BoxFuture<'static, Result<(), ValidationError>> + Send + Sync>;
+
+/// The hook `L1Queue` uses unless another is set, which does nothing
+pub fn default_commitment_hook() -> CommitmentFoundHook {
+    Arc::new(
+        |_: &Deposit| -> BoxFuture<'static, Result<(), ValidationError>> {
+            Box::pin(async { Ok(()) })
+        },
+    )
 }
 
 /// L1 Queue structure to process deposits.
 pub struct L1Queue {
     db_pool: PgPool,
     config: QueueConfig,
+    /// Reloaded configs, whose `queue` section replaces `config` from the next cycle on
+    config_updates: Option<watch::Receiver<AppConfig>>,
+    /// Processed deposits are appended here so their Merkle proofs can be served
+    deposit_tree: Option<DepositTree>,
+    /// Answers commitment checks; without it every commitment is trusted
+    commitment_registry: Option<CommitmentHashRegistry>,
+    /// Cross-checks each deposit's L1 and L2 commitments before it is marked processed
+    commitment_verifier: Option<CommitmentHashVerifier>,
+    on_commitment_found: CommitmentFoundHook,
 }
 
 impl L1Queue {
     pub fn new(db_pool: PgPool, config: QueueConfig) -> Self {
-        Self { db_pool, config }
+        Self {
+            on_commitment_found: default_commitment_hook(),
+            db_pool,
+            config,
+            config_updates: None,
+            deposit_tree: None,
+            commitment_registry: None,
+            commitment_verifier: None,
+        }
+    }
+
+    /// Follows config reloads, e.g. from `AppState::subscribe_config`
+    pub fn with_config_updates(mut self, config_updates: watch::Receiver<AppConfig>) -> Self {
+        self.config_updates = Some(config_updates);
+        self
+    }
+
+    pub fn with_deposit_tree(mut self, deposit_tree: DepositTree) -> Self {
+        self.deposit_tree = Some(deposit_tree);
+        self
+    }
+
+    /// Checks deposit commitments against the L1 events recorded in the database, caching the
+    /// answers in `registry`
+    pub fn with_commitment_registry(mut self, registry: CommitmentHashRegistry) -> Self {
+        self.commitment_registry = Some(registry);
+        self
+    }
+
+    /// Records ingested deposit events in `verifier` and fails deposits whose L1 and L2
+    /// commitments do not correspond
+    pub fn with_commitment_verifier(mut self, verifier: CommitmentHashVerifier) -> Self {
+        self.commitment_verifier = Some(verifier);
+        self
+    }
+
+    /// Sets the action taken once a deposit's commitment is confirmed, e.g. to notify a batcher
+    /// or publish to an event bus.
+    pub fn on_commitment_found(mut self, hook: CommitmentFoundHook) -> Self {
+        self.on_commitment_found = hook;
+        self
+    }
+
+    /// Queue settings the next processing cycle runs with
+    pub fn queue_config(&self) -> QueueConfig {
+        match &self.config_updates {
+            Some(config_updates) => config_updates.borrow().queue.clone(),
+            None => self.config.clone(),
+        }
     }
 
     /// Runs the L1 queue processor in an infinite loop.
     pub async fn run(&self) {
         loop {
-            match self.process_deposits().await {
+            let config = self.queue_config();
+            match self.process_deposits(&config).await {
                 Ok(_) => info!("Completed deposit processing cycle"),
                 Err(e) => error!("Deposit processing cycle failed: {:?}", e),
             }
-            sleep(Duration::from_secs(self.config.process_interval_sec)).await;
+            sleep(Duration::from_secs(config.process_interval_sec)).await;
         }
     }
 
-    /// Processes pending deposit requests.
-    async fn process_deposits(&self) -> Result<(), sqlx::Error> {
-        let deposits = fetch_pending_deposits(&self.db_pool, self.config.max_retries).await?;
+    /// Persists each streamed `DepositEvent` as soon as it arrives and advances the L1 block
+    /// tracker past it, returning the number of deposits recorded.
+    ///
+    /// Events are pulled one at a time, so a stream from `stream_l1_deposit_events` never
+    /// buffers more than its channel capacity while deposits are being written.
+    pub async fn ingest_deposit_events<S>(&self, events: S) -> Result<u64, L1EventStreamError>
+    where
+        S: Stream<Item = Result<Log<ZeroXBridge::DepositEvent>, L1EventStreamError>>,
+    {
+        tokio::pin!(events);
+        let mut recorded = 0;
+        let mut current_block = None;
 
-        for deposit in deposits {
-            let mut tx = self.db_pool.begin().await?;
+        while let Some(log) = events.next().await {
+            let log = log?;
 
+            // A block only counts as processed once every event in it has been recorded
+            if let Some(finished) = current_block.filter(|block| Some(*block) != log.block_number) {
+                update_last_processed_block(
+                    &self.db_pool,
+                    BlockTrackerKey::L1DepositEvents,
+                    finished,
+                )
+                .await?;
+            }
+            current_block = log.block_number.or(current_block);
+
+            record_deposit_event(&self.db_pool, &log).await?;
+            // The L1 commitment covers the block timestamp, so events without one can't be checked
+            if let (Some(verifier), Some(timestamp)) =
+                (&self.commitment_verifier, log.block_timestamp)
+            {
+                verifier.record_l1_event(log.data(), timestamp);
+            }
+            recorded += 1;
+        }
+
+        if let Some(finished) = current_block {
+            update_last_processed_block(&self.db_pool, BlockTrackerKey::L1DepositEvents, finished)
+                .await?;
+        }
+
+        Ok(recorded)
+    }
+
+    /// Processes one batch of pending deposit requests.
+    ///
+    /// Each deposit is claimed with `FOR UPDATE SKIP LOCKED` in its own short transaction that
+    /// records its outcome, so queues running against the same database never pick up the same
+    /// deposit, and an error only loses the outcome of the deposit it happened on. Deposits are
+    /// appended to the Merkle tree only once their `processed` status is committed.
+    pub async fn process_deposits(&self, config: &QueueConfig) -> Result<(), sqlx::Error> {
+        let ids =
+            fetch_pending_deposit_ids(&self.db_pool, config.max_retries, config.batch_size as i64)
+                .await?;
+
+        for id in ids {
             // Small delay to prevent hammering chain for each deposit
-            sleep(Duration::from_secs(self.config.initial_retry_delay_sec)).await;
+            sleep(Duration::from_secs(config.initial_retry_delay_sec)).await;
 
-            match self.validate_deposit(&deposit).await {
+            let mut tx = self.db_pool.begin().await?;
+            let Some(deposit) = claim_pending_deposit(&mut tx, id, config.max_retries).await?
+            else {
+                // Processed or claimed by another queue since the batch was read
+                continue;
+            };
+
+            let mut retry_later = false;
+            let mut processed = false;
+            match self.validate_deposit(&deposit, config.max_retries).await {
                 Ok(()) => {
                     info!("Deposit {} validated successfully", deposit.id);
                     update_deposit_status(&mut tx, deposit.id, "processed").await?;
+                    processed = true;
                 }
 
                 Err(ValidationError::CommitmentPending) => {
                     warn!("Deposit {} not yet found on L1. Will retry.", deposit.id);
                     process_deposit_retry(&mut tx, deposit.id).await?;
-                    sleep(Duration::from_secs(self.config.retry_delay_seconds.into())).await;
+                    retry_later = true;
                 }
 
                 Err(ValidationError::MaxRetriesExceeded) => {
@@ -78,26 +234,76 @@ impl L1Queue {
                     update_deposit_status(&mut tx, deposit.id, "failed").await?;
                 }
 
+                // Retrying cannot make mismatched commitments agree
+                Err(e @ ValidationError::ProofMismatch { .. }) => {
+                    error!("Deposit {} failed verification: {}", deposit.id, e);
+                    update_deposit_status(&mut tx, deposit.id, "failed").await?;
+                }
+
                 Err(e) => {
                     warn!("Deposit {} hit an error: {:?}. Will retry.", deposit.id, e);
                     process_deposit_retry(&mut tx, deposit.id).await?;
-                    sleep(Duration::from_secs(self.config.retry_delay_seconds.into())).await;
+                    retry_later = true;
                 }
             }
-
             tx.commit().await?;
+
+            if processed {
+                self.append_to_tree(&deposit).await;
+            }
+            if retry_later {
+                sleep(Duration::from_secs(config.retry_delay_seconds.into())).await;
+            }
         }
 
         Ok(())
     }
 
-    /// Validates the deposit by verifying commitment existence
-    async fn validate_deposit(&self, deposit: &Deposit) -> Result<(), ValidationError> {
+    /// Appends a processed deposit to the Merkle tree, if the queue keeps one, then checks the
+    /// tree against the element count the L1 contract reported for the deposit
+    async fn append_to_tree(&self, deposit: &Deposit) {
+        let Some(deposit_tree) = &self.deposit_tree else {
+            return;
+        };
+
+        let result = match self.db_pool.acquire().await {
+            Ok(mut conn) => deposit_tree
+                .add_deposits(&mut conn, std::slice::from_ref(deposit))
+                .await
+                .map_err(|e| format!("{:?}", e)),
+            Err(e) => Err(e.to_string()),
+        };
+        if let Err(e) = result {
+            error!(
+                "Failed to add deposit {} to the Merkle tree: {}",
+                deposit.id, e
+            );
+            return;
+        }
+
+        let Some(element_count) = deposit.l1_element_count else {
+            return;
+        };
+        if let Err(e) = deposit_tree.verify_sync(element_count as u64).await {
+            warn!(
+                "Deposit tree out of sync after deposit {}: {}",
+                deposit.id, e
+            );
+        }
+    }
+
+    /// Validates the deposit by verifying commitment existence and that its L1 and L2
+    /// commitments correspond, then runs the commitment-found hook on it
+    async fn validate_deposit(
+        &self,
+        deposit: &Deposit,
+        max_retries: u32,
+    ) -> Result<(), ValidationError> {
         let commitment_exists = self
             .check_l1_commitment(deposit.commitment_hash.clone())
             .await?;
 
-        let max_retries_i32 = self.config.max_retries as i32;
+        let max_retries_i32 = max_retries as i32;
 
         if !commitment_exists {
             if deposit.retry_count + 1 >= max_retries_i32 {
@@ -107,15 +313,156 @@ impl L1Queue {
             }
         }
 
-        Ok(())
+        if let Some(verifier) = &self.commitment_verifier {
+            verifier.check(&deposit.commitment_hash, &deposit.stark_pub_key)?;
+        }
+
+        (self.on_commitment_found)(deposit).await
     }
 
     async fn check_l1_commitment(&self, commitment_hash: String) -> Result<bool, ValidationError> {
-        // Check l1 event logs for commitment hash
-        // Assuming we have a function `get_event_logs` that fetches event logs from L1
-        // we store last index so we don't have to fetch all logs every time
-
         trace!("Checking L1 commitment for hash: {}", commitment_hash);
-        Ok(true)
+
+        match &self.commitment_registry {
+            Some(registry) => Ok(registry
+                .is_confirmed(&self.db_pool, &commitment_hash)
+                .await?),
+            None => Ok(true),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Mutex;
+
+    fn test_config() -> QueueConfig {
+        QueueConfig {
+            process_interval_sec: 1,
+            wait_time_seconds: 0,
+            max_retries: 3,
+            initial_retry_delay_sec: 0,
+            retry_delay_seconds: 0,
+            merkle_update_confirmations: 1,
+            batch_size: 10,
+            commitment_cache_ttl_seconds: 300,
+            max_concurrent_validations: 1,
+        }
+    }
+
+    fn test_deposit(id: i32) -> Deposit {
+        Deposit {
+            id,
+            stark_pub_key: "0x123".to_string(),
+            amount: 1000,
+            commitment_hash: format!("0x{:x}", id),
+            status: "pending".to_string(),
+            retry_count: 0,
+            created_at: None,
+            updated_at: None,
+            idempotency_key: None,
+            leaf_index: None,
+            finalization_block: None,
+            l1_deposit_id: None,
+            proof_job_id: None,
+            block_number: None,
+            l1_element_count: None,
+        }
+    }
+
+    fn test_queue() -> L1Queue {
+        // The custom hooks below never touch the database
+        let db_pool = PgPool::connect_lazy("postgres://localhost/unused").unwrap();
+        L1Queue::new(db_pool, test_config())
+    }
+
+    #[tokio::test]
+    async fn test_custom_hook_called_once_per_validated_deposit() {
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let hook_seen = seen.clone();
+        let queue = test_queue().on_commitment_found(Arc::new(
+            move |deposit: &Deposit| -> BoxFuture<'static, Result<(), ValidationError>> {
+                hook_seen.lock().unwrap().push(deposit.id);
+                Box::pin(async { Ok(()) })
+            },
+        ));
+
+        for id in [1, 2, 3] {
+            queue
+                .validate_deposit(&test_deposit(id), test_config().max_retries)
+                .await
+                .unwrap();
+        }
+
+        assert_eq!(*seen.lock().unwrap(), vec![1, 2, 3]);
+    }
+
+    #[tokio::test]
+    async fn test_hook_error_fails_validation() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let hook_calls = calls.clone();
+        let queue = test_queue().on_commitment_found(Arc::new(
+            move |_: &Deposit| -> BoxFuture<'static, Result<(), ValidationError>> {
+                hook_calls.fetch_add(1, Ordering::SeqCst);
+                Box::pin(async { Err(ValidationError::Rpc("batcher unavailable".to_string())) })
+            },
+        ));
+
+        let result = queue
+            .validate_deposit(&test_deposit(1), test_config().max_retries)
+            .await;
+
+        assert!(matches!(result, Err(ValidationError::Rpc(_))));
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_mismatched_commitments_fail_before_hook() {
+        use crate::events::l1_event_watcher::ZeroXBridge;
+        use alloy::primitives::{Address, U256};
+
+        let verifier = CommitmentHashVerifier::new();
+        verifier.record_l1_event(
+            &ZeroXBridge::DepositEvent {
+                assetType: ZeroXBridge::AssetType::ETH,
+                usdVal: U256::from(1000),
+                nonce: U256::from(1),
+                leafIndex: U256::ZERO,
+                depositId: U256::from(1),
+                token: Address::ZERO,
+                user: Address::from([0xbb; 20]),
+                commitmentHash: U256::from(0xc0),
+                newRoot: U256::ZERO,
+                elementCount: U256::from(1),
+            },
+            1_700_000_000,
+        );
+
+        let calls = Arc::new(AtomicUsize::new(0));
+        let hook_calls = calls.clone();
+        let queue = test_queue()
+            .with_commitment_verifier(verifier)
+            .on_commitment_found(Arc::new(
+                move |_: &Deposit| -> BoxFuture<'static, Result<(), ValidationError>> {
+                    hook_calls.fetch_add(1, Ordering::SeqCst);
+                    Box::pin(async { Ok(()) })
+                },
+            ));
+
+        let result = queue
+            .validate_deposit(&test_deposit(0xc0), test_config().max_retries)
+            .await;
+
+        assert!(matches!(result, Err(ValidationError::ProofMismatch { .. })));
+        assert_eq!(calls.load(Ordering::SeqCst), 0);
+
+        // Deposits without recorded events are not held back
+        queue
+            .validate_deposit(&test_deposit(2), test_config().max_retries)
+            .await
+            .unwrap();
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
     }
 }
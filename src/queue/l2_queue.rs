@@ -1,11 +1,26 @@
 use chrono::{DateTime, Utc};
+use futures_util::future::BoxFuture;
+use futures_util::stream::{FuturesUnordered, StreamExt};
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
 use sqlx::{Pool, Postgres};
+use starknet::core::types::{BlockId, BlockTag, Felt, FunctionCall};
+use starknet::macros::selector;
+use starknet::providers::jsonrpc::{HttpTransport, JsonRpcClient};
+use starknet::providers::Provider;
+use std::sync::Arc;
 use std::time::Duration;
 use thiserror::Error;
+use tokio::sync::broadcast::{self, error::RecvError};
+use tokio::sync::{Mutex, Semaphore};
 use tokio::time::sleep;
 use tracing::{error, info, trace, warn};
 
+use crate::db::database::{fetch_ready_withdrawal_proof, insert_dead_letter_l2_transaction};
+use crate::events::{CommitmentLog, EventBus};
+use crate::queue::withdrawal_matcher::WithdrawalMatcher;
+use crate::utils::{normalize_commitment_hash, serialize_optional_prefixed_commitment_hash};
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct L2Transaction {
     pub id: i64,
@@ -19,6 +34,97 @@ pub struct L2Transaction {
     pub error: Option<String>,
     pub proof_data: Option<String>,
     pub retry_count: i32,
+    pub priority: i16,
+    /// Commitment of the burn on L2, checked against the bridge contract before relaying
+    #[serde(serialize_with = "serialize_optional_prefixed_commitment_hash")]
+    pub commitment_hash: Option<String>,
+    /// SHA-256 of `proof_data`, set once the transaction leaves the pending state
+    pub proof_data_hash: Option<String>,
+}
+
+impl L2Transaction {
+    /// Pending, not yet stored transaction for the burn in `log`; `id` is 0 until it is
+    /// inserted
+    pub fn from_burn_event(
+        log: &CommitmentLog,
+        token_address: &str,
+    ) -> Result<L2Transaction, ConversionError> {
+        let now = Utc::now();
+        Ok(L2Transaction {
+            id: 0,
+            stark_pub_key: log.user.clone(),
+            amount: parse_u128_from_hex(&log.amount_low, &log.amount_high)?,
+            token_address: token_address.to_string(),
+            status: "pending".to_string(),
+            created_at: now,
+            updated_at: now,
+            tx_hash: None,
+            error: None,
+            proof_data: None,
+            retry_count: 0,
+            priority: PriorityLevel::Normal.as_i16(),
+            commitment_hash: Some(log.commitment_hash.clone()),
+            proof_data_hash: None,
+        })
+    }
+}
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum ConversionError {
+    #[error("Invalid hex value: {0}")]
+    InvalidHex(String),
+
+    #[error("Amount with low half {low} and high half {high} does not fit in an i64")]
+    Overflow { low: String, high: String },
+}
+
+/// Rebuilds an amount from the low and high `u128` halves of a Starknet `u256`.
+///
+/// Amounts are stored as `BIGINT`, so anything above `i64::MAX` is an overflow.
+pub fn parse_u128_from_hex(low: &str, high: &str) -> Result<i64, ConversionError> {
+    let parse_half = |half: &str| {
+        u128::from_str_radix(half.strip_prefix("0x").unwrap_or(half), 16)
+            .map_err(|_| ConversionError::InvalidHex(half.to_string()))
+    };
+    let overflow = || ConversionError::Overflow {
+        low: low.to_string(),
+        high: high.to_string(),
+    };
+
+    let (low_value, high_value) = (parse_half(low)?, parse_half(high)?);
+    if high_value != 0 {
+        return Err(overflow());
+    }
+    i64::try_from(low_value).map_err(|_| overflow())
+}
+
+/// Order in which pending L2 transactions are picked up; higher levels are dequeued first and
+/// transactions within a level stay in FIFO order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PriorityLevel {
+    Low,
+    Normal,
+    High,
+}
+
+impl PriorityLevel {
+    /// Value stored in the `l2_transactions.priority` column
+    pub fn as_i16(self) -> i16 {
+        match self {
+            PriorityLevel::Low => 0,
+            PriorityLevel::Normal => 1,
+            PriorityLevel::High => 2,
+        }
+    }
+
+    /// Priority for a transaction of `amount` USD. Without a threshold every transaction is
+    /// `Normal`, which keeps the queue strictly FIFO.
+    pub fn for_amount(amount: i64, high_priority_threshold_usd: Option<u64>) -> Self {
+        match high_priority_threshold_usd {
+            Some(threshold) if amount >= 0 && amount as u64 >= threshold => PriorityLevel::High,
+            _ => PriorityLevel::Normal,
+        }
+    }
 }
 
 #[derive(Debug, Error)]
@@ -40,47 +146,202 @@ pub enum L2QueueError {
 
     #[error("Max retries exceeded")]
     MaxRetriesExceeded,
+
+    #[error("Invalid commitment hash: {0}")]
+    InvalidCommitmentHash(String),
+
+    #[error("Starknet provider error: {0}")]
+    Provider(#[from] starknet::providers::ProviderError),
 }
 
+/// Partial unique index allowing one relayed, or about to be relayed, transaction per commitment
+const RELAYED_COMMITMENT_INDEX: &str = "l2_transactions_relayed_commitment_hash";
+
 pub struct QueueConfig {
-    pub process_interval_sec: u64,
+    /// Polling interval after a cycle that processed transactions
+    pub min_interval_sec: u64,
+    /// Upper bound the interval backs off to while the queue stays empty
+    pub max_interval_sec: u64,
+    /// Factor the interval grows by after each empty cycle
+    pub backoff_factor: f64,
     pub initial_retry_delay_sec: u64,
     pub max_retries: u32,
     pub batch_size: i64,
+    /// Transactions of at least this many USD jump ahead of the rest of the queue
+    pub high_priority_threshold_usd: Option<u64>,
+    /// Transactions of a batch whose commitments are checked at the same time
+    pub max_concurrent_validations: usize,
 }
 
+impl QueueConfig {
+    /// Interval to wait after a cycle that processed `processed` transactions, given the
+    /// interval waited after the previous one
+    pub fn next_poll_interval(&self, current_interval: Duration, processed: usize) -> Duration {
+        let min = Duration::from_secs(self.min_interval_sec);
+        if processed > 0 {
+            return min;
+        }
+
+        let max = Duration::from_secs(self.max_interval_sec).max(min);
+        current_interval
+            .mul_f64(self.backoff_factor.max(1.0))
+            .clamp(min, max)
+    }
+}
+
+impl From<&crate::config::QueueConfig> for QueueConfig {
+    /// Polls every `process_interval_sec` without backing off
+    fn from(config: &crate::config::QueueConfig) -> Self {
+        Self {
+            min_interval_sec: config.process_interval_sec,
+            max_interval_sec: config.process_interval_sec,
+            backoff_factor: 1.0,
+            initial_retry_delay_sec: config.initial_retry_delay_sec,
+            max_retries: config.max_retries,
+            batch_size: config.batch_size as i64,
+            high_priority_threshold_usd: None,
+            max_concurrent_validations: config.max_concurrent_validations,
+        }
+    }
+}
+
+/// Answers a transaction's commitment check in place of the bridge contract, with its proof
+/// data once the commitment is recorded
+pub type CommitmentCheck = Arc<
+    dyn Fn(&L2Transaction) -> BoxFuture<'static, Result<Option<String>, L2QueueError>>
+        + Send
+        + Sync,
+>;
+
 pub struct L2Queue {
     db_pool: Pool<Postgres>,
     config: QueueConfig,
+    commitment_events: Option<Mutex<broadcast::Receiver<CommitmentLog>>>,
+    withdrawal_matcher: WithdrawalMatcher,
+    starknet_provider: Option<Arc<JsonRpcClient<HttpTransport>>>,
+    l2_contract_address: Option<Felt>,
+    commitment_check: Option<CommitmentCheck>,
 }
 
 impl L2Queue {
     pub fn new(db_pool: Pool<Postgres>, config: QueueConfig) -> Self {
-        Self { db_pool, config }
+        Self {
+            withdrawal_matcher: WithdrawalMatcher::new(db_pool.clone()),
+            db_pool,
+            config,
+            commitment_events: None,
+            starknet_provider: None,
+            l2_contract_address: None,
+            commitment_check: None,
+        }
+    }
+
+    /// Checks commitments against the bridge contract at `l2_contract_address` through
+    /// `provider`. Without a provider no commitment is ever found on L2.
+    pub fn with_starknet_provider(
+        mut self,
+        provider: Arc<JsonRpcClient<HttpTransport>>,
+        l2_contract_address: Felt,
+    ) -> Self {
+        self.starknet_provider = Some(provider);
+        self.l2_contract_address = Some(l2_contract_address);
+        self
+    }
+
+    /// Checks commitments with `check` instead of the bridge contract
+    pub fn with_commitment_check(mut self, check: CommitmentCheck) -> Self {
+        self.commitment_check = Some(check);
+        self
+    }
+
+    /// Subscribes to L2 burn events so a new commitment triggers a processing cycle
+    /// immediately instead of waiting for the next polling interval.
+    pub fn with_event_bus(mut self, bus: &EventBus<CommitmentLog>) -> Self {
+        self.commitment_events = Some(Mutex::new(bus.subscribe()));
+        self
     }
 
     pub async fn run(&self) {
+        let mut current_interval = Duration::from_secs(self.config.min_interval_sec);
         loop {
-            match self.process_transactions().await {
-                Ok(_) => info!("Processing cycle completed."),
-                Err(e) => error!("Processing failed: {:?}", e),
+            if let Err(e) = self.withdrawal_matcher.match_pending().await {
+                error!("Withdrawal matching failed: {:?}", e);
             }
-            sleep(Duration::from_secs(self.config.process_interval_sec)).await;
+
+            let processed = match self.process_transactions().await {
+                Ok(processed) => {
+                    info!("Processing cycle completed.");
+                    processed
+                }
+                Err(e) => {
+                    error!("Processing failed: {:?}", e);
+                    0
+                }
+            };
+            current_interval = self.config.next_poll_interval(current_interval, processed);
+            self.wait_for_next_cycle(current_interval).await;
+        }
+    }
+
+    /// Sleeps for `interval`, waking early when a commitment event arrives
+    pub async fn wait_for_next_cycle(&self, interval: Duration) {
+        let Some(events) = &self.commitment_events else {
+            sleep(interval).await;
+            return;
+        };
+
+        let mut events = events.lock().await;
+        tokio::select! {
+            _ = sleep(interval) => {}
+            received = events.recv() => match received {
+                Ok(log) => trace!("Commitment {} observed, processing early", log.commitment_hash),
+                Err(RecvError::Lagged(skipped)) => {
+                    warn!("L2 queue lagged behind event bus, skipped {} events", skipped)
+                }
+                Err(RecvError::Closed) => sleep(interval).await,
+            },
         }
     }
 
-    async fn process_transactions(&self) -> Result<(), L2QueueError> {
+    /// Runs a single processing cycle over the pending transactions, returning how many
+    /// were in the batch.
+    ///
+    /// Up to `max_concurrent_validations` transactions are validated at once, each starting as
+    /// soon as an earlier one finishes. Their status updates are then applied in batch order, so
+    /// the first of two transactions with the same commitment is the one relayed.
+    pub async fn process_transactions(&self) -> Result<usize, L2QueueError> {
         let transactions = self
             .get_pending_transactions_for_proof(self.config.batch_size)
             .await?;
+        let processed = transactions.len();
 
-        for tx in transactions {
-            let tx_handle = self.db_pool.begin().await?;
+        let semaphore = Semaphore::new(self.config.max_concurrent_validations.max(1));
+        let validations: FuturesUnordered<_> = transactions
+            .iter()
+            .enumerate()
+            .map(|(index, tx)| {
+                let semaphore = &semaphore;
+                async move {
+                    let _permit = semaphore
+                        .acquire()
+                        .await
+                        .expect("semaphore is never closed");
 
-            // Optional delay
-            sleep(Duration::from_secs(self.config.initial_retry_delay_sec)).await;
+                    // Optional delay
+                    sleep(Duration::from_secs(self.config.initial_retry_delay_sec)).await;
 
-            match self.validate_transaction(&tx).await {
+                    (index, self.validate_transaction(tx).await)
+                }
+            })
+            .collect();
+
+        let mut results: Vec<_> = validations.collect().await;
+        results.sort_by_key(|(index, _)| *index);
+
+        for (tx, (_, result)) in transactions.iter().zip(results) {
+            let tx_handle = self.db_pool.begin().await?;
+
+            match result {
                 Ok(proof) => {
                     self.mark_transaction_ready_for_relay(tx.id, &proof).await?;
                     tx_handle.commit().await?;
@@ -105,13 +366,25 @@ impl L2Queue {
             }
         }
 
-        Ok(())
+        Ok(processed)
     }
 
     async fn validate_transaction(&self, tx: &L2Transaction) -> Result<String, L2QueueError> {
         trace!("Validating tx: {}", tx.id);
 
-        let proof_data = self.check_l2_commitment(tx).await?;
+        let checked = match &self.commitment_check {
+            Some(check) => check(tx).await,
+            None => self.check_l2_commitment(tx).await,
+        };
+
+        // A failed view call counts as a retry rather than failing the transaction outright
+        let proof_data = match checked {
+            Err(L2QueueError::Provider(e)) => {
+                warn!("Commitment check for tx {} failed: {}", tx.id, e);
+                None
+            }
+            result => result?,
+        };
 
         if let Some(proof) = proof_data {
             Ok(proof)
@@ -125,19 +398,67 @@ impl L2Queue {
         }
     }
 
-    async fn check_l2_commitment(
+    /// Proof data for `tx` once the bridge contract's `is_commitment_recorded` view reports its
+    /// commitment and the withdrawal's proof job has stored its proof, or `None` until both
+    pub async fn check_l2_commitment(
         &self,
         tx: &L2Transaction,
     ) -> Result<Option<String>, L2QueueError> {
-        // Mocked success
         trace!("Checking commitment for tx {}", tx.id);
 
-        // Example logic - replace with real L2 check
-        if tx.id % 2 == 0 {
-            Ok(Some("mocked_proof_data".into()))
-        } else {
-            Ok(None)
+        let (Some(provider), Some(contract_address), Some(commitment_hash)) = (
+            &self.starknet_provider,
+            self.l2_contract_address,
+            tx.commitment_hash.as_deref(),
+        ) else {
+            return Ok(None);
+        };
+        let commitment = Felt::from_hex(commitment_hash)
+            .map_err(|_| L2QueueError::InvalidCommitmentHash(commitment_hash.to_string()))?;
+
+        let recorded = provider
+            .call(
+                FunctionCall {
+                    contract_address,
+                    entry_point_selector: selector!("is_commitment_recorded"),
+                    calldata: vec![commitment],
+                },
+                BlockId::Tag(BlockTag::Latest),
+            )
+            .await?;
+        if recorded.first() != Some(&Felt::ONE) {
+            return Ok(None);
         }
+
+        let stored_hash = normalize_commitment_hash(commitment_hash)
+            .map_err(|_| L2QueueError::InvalidCommitmentHash(commitment_hash.to_string()))?;
+        let Some(proof) = fetch_ready_withdrawal_proof(&self.db_pool, &stored_hash).await? else {
+            trace!("Proof for tx {} is not ready yet", tx.id);
+            return Ok(None);
+        };
+
+        let root = provider
+            .call(
+                FunctionCall {
+                    contract_address,
+                    entry_point_selector: selector!("get_root"),
+                    calldata: vec![],
+                },
+                BlockId::Tag(BlockTag::Latest),
+            )
+            .await?;
+        let merkle_root = root.first().copied().unwrap_or(Felt::ZERO);
+
+        let proof_data = serde_json::json!({
+            "commitment_hash": format!("{:#x}", commitment),
+            "merkle_root": format!("{:#x}", merkle_root),
+            // One 32-byte big-endian word per element, as the proof generation worker stores it
+            "proof": proof
+                .chunks(32)
+                .map(|word| format!("0x{}", hex::encode(word)))
+                .collect::<Vec<_>>(),
+        });
+        Ok(Some(proof_data.to_string()))
     }
 
     async fn increment_retry_count(&self, id: i64) -> Result<(), L2QueueError> {
@@ -156,29 +477,69 @@ impl L2Queue {
         Ok(())
     }
 
-    async fn mark_transaction_ready_for_relay(
+    /// Hands pending transaction `id` to the relayer with `proof_data`.
+    ///
+    /// A transaction whose commitment is already ready for relay, being relayed or relayed is
+    /// marked `duplicate` instead, so the same withdrawal is never relayed twice. A partial
+    /// unique index on the commitment settles concurrent calls.
+    pub async fn mark_transaction_ready_for_relay(
         &self,
         id: i64,
         proof_data: &str,
     ) -> Result<(), L2QueueError> {
+        let proof_data_hash = hex::encode(Sha256::digest(proof_data.as_bytes()));
+
+        let marked = self
+            .set_pending_status(id, "ready_for_relay", proof_data, &proof_data_hash)
+            .await;
+        let is_relayed_commitment = marked.as_ref().is_err_and(|e| {
+            e.as_database_error().is_some_and(|db_error| {
+                db_error.is_unique_violation()
+                    && db_error.constraint() == Some(RELAYED_COMMITMENT_INDEX)
+            })
+        });
+
+        let updated = if is_relayed_commitment {
+            warn!(
+                "Tx {} carries the commitment of a relayed transaction, marked duplicate",
+                id
+            );
+            self.set_pending_status(id, "duplicate", proof_data, &proof_data_hash)
+                .await?
+        } else {
+            marked?
+        };
+        if !updated {
+            return Err(L2QueueError::TransactionNotFound(id));
+        }
+
+        Ok(())
+    }
+
+    /// Moves pending transaction `id` to `status` with its proof data, returning whether it
+    /// was still pending
+    async fn set_pending_status(
+        &self,
+        id: i64,
+        status: &str,
+        proof_data: &str,
+        proof_data_hash: &str,
+    ) -> Result<bool, sqlx::Error> {
         let result = sqlx::query!(
             r#"
             UPDATE l2_transactions
-            SET status = 'ready_for_relay', proof_data = $1, updated_at = NOW()
-            WHERE id = $2 AND status = 'pending'
+            SET status = $1, proof_data = $2, proof_data_hash = $3, updated_at = NOW()
+            WHERE id = $4 AND status = 'pending'
             "#,
+            status,
             proof_data,
+            proof_data_hash,
             id
         )
         .execute(&self.db_pool)
-        .await
-        .map_err(L2QueueError::Database)?;
-
-        if result.rows_affected() == 0 {
-            return Err(L2QueueError::TransactionNotFound(id));
-        }
+        .await?;
 
-        Ok(())
+        Ok(result.rows_affected() > 0)
     }
 
     async fn update_transaction_status(
@@ -221,10 +582,55 @@ impl L2Queue {
             return Err(L2QueueError::TransactionNotFound(id));
         }
 
+        // Keep a copy of failed transactions so operators can inspect and requeue them
+        if status == "failed" {
+            insert_dead_letter_l2_transaction(&self.db_pool, id, error).await?;
+        }
+
         Ok(())
     }
 
-    async fn get_pending_transactions_for_proof(
+    /// Adds a pending transaction for the burn with `commitment_hash` to the queue, prioritised
+    /// by its amount
+    pub async fn enqueue_transaction(
+        &self,
+        stark_pub_key: &str,
+        amount: i64,
+        token_address: &str,
+        commitment_hash: Option<&str>,
+    ) -> Result<i64, L2QueueError> {
+        let priority = PriorityLevel::for_amount(amount, self.config.high_priority_threshold_usd);
+        // Stored normalized, like every other commitment hash, so the relayed commitment index
+        // sees two spellings of one hash as the same commitment
+        let commitment_hash = commitment_hash
+            .map(|hash| {
+                normalize_commitment_hash(hash)
+                    .map_err(|_| L2QueueError::InvalidCommitmentHash(hash.to_string()))
+            })
+            .transpose()?;
+
+        let id = sqlx::query_scalar!(
+            r#"
+            INSERT INTO l2_transactions (stark_pub_key, amount, token_address, status, retry_count, priority, commitment_hash)
+            VALUES ($1, $2, $3, 'pending', 0, $4, $5)
+            RETURNING id
+            "#,
+            stark_pub_key,
+            amount,
+            token_address,
+            priority.as_i16(),
+            commitment_hash
+        )
+        .fetch_one(&self.db_pool)
+        .await
+        .map_err(L2QueueError::Database)?;
+
+        Ok(id)
+    }
+
+    /// Next batch of pending transactions, highest priority first and oldest first within a
+    /// priority level
+    pub async fn get_pending_transactions_for_proof(
         &self,
         limit: i64,
     ) -> Result<Vec<L2Transaction>, L2QueueError> {
@@ -233,7 +639,7 @@ impl L2Queue {
             r#"
             SELECT * FROM l2_transactions
             WHERE status = 'pending'
-            ORDER BY created_at ASC
+            ORDER BY priority DESC, created_at ASC
             LIMIT $1
             "#,
             limit
@@ -1,2 +1,4 @@
+pub mod commitment_verifier;
 pub mod l1_queue;
 pub mod l2_queue;
+pub mod withdrawal_matcher;
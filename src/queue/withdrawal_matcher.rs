@@ -0,0 +1,34 @@
+use sqlx::PgPool;
+use tracing::info;
+
+use crate::db::database::match_pending_withdrawals;
+
+/// Actor recorded in the withdrawal timeline for matches
+const MATCHER_ACTOR: &str = "withdrawal_matcher";
+
+/// Links withdrawals created through the API to the L2 burn that funds them.
+///
+/// A pending withdrawal matches a burn event with the same stark key, amount and commitment
+/// hash; it then records the burn's id in `l2_tx_id` and moves to `matched`.
+#[derive(Debug, Clone)]
+pub struct WithdrawalMatcher {
+    db_pool: PgPool,
+}
+
+impl WithdrawalMatcher {
+    pub fn new(db_pool: PgPool) -> Self {
+        Self { db_pool }
+    }
+
+    /// Matches every pending withdrawal whose burn has been seen, returning how many were linked
+    pub async fn match_pending(&self) -> Result<usize, sqlx::Error> {
+        let matches = match_pending_withdrawals(&self.db_pool, MATCHER_ACTOR).await?;
+        for (withdrawal_id, burn_id) in &matches {
+            info!(
+                "Withdrawal {} matched to L2 burn event {}",
+                withdrawal_id, burn_id
+            );
+        }
+        Ok(matches.len())
+    }
+}
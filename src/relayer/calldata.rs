@@ -0,0 +1,177 @@
+use crate::relayer::proof_submission::ProofSubmissionError;
+use starknet::core::types::Felt;
+use std::fmt;
+use std::fs;
+use std::io::{self, Read, Write};
+use std::path::Path;
+use std::str::FromStr;
+use tracing::debug;
+
+/// Bytes per field element in the binary format
+const FELT_BYTES: usize = 32;
+
+/// Encodings calldata can be written in
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CalldataFormat {
+    /// JSON array of `0x`-prefixed hex strings
+    Json,
+    /// Whitespace-separated hex values, as in the calldata files themselves
+    HexText,
+    /// Raw big-endian 32-byte field elements
+    Binary,
+}
+
+impl CalldataFormat {
+    pub const ALL: [CalldataFormat; 3] = [
+        CalldataFormat::Json,
+        CalldataFormat::HexText,
+        CalldataFormat::Binary,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CalldataFormat::Json => "json",
+            CalldataFormat::HexText => "hex-text",
+            CalldataFormat::Binary => "binary",
+        }
+    }
+
+    pub fn to_writer(&self, calldata: &[Felt], mut writer: impl Write) -> io::Result<()> {
+        match self {
+            CalldataFormat::Json => {
+                let hex: Vec<String> = calldata.iter().map(|felt| format!("{:#x}", felt)).collect();
+                serde_json::to_writer(&mut writer, &hex)?;
+                writeln!(writer)?;
+            }
+            CalldataFormat::HexText => {
+                for felt in calldata {
+                    writeln!(writer, "{:#x}", felt)?;
+                }
+            }
+            CalldataFormat::Binary => {
+                for felt in calldata {
+                    writer.write_all(&felt.to_bytes_be())?;
+                }
+            }
+        }
+        writer.flush()
+    }
+
+    /// Reads calldata written by [`CalldataFormat::to_writer`] in the same format
+    pub fn from_reader(&self, mut reader: impl Read) -> Result<Vec<Felt>, ProofSubmissionError> {
+        match self {
+            CalldataFormat::Json => {
+                let hex: Vec<String> = serde_json::from_reader(reader)?;
+                hex.iter().map(|value| parse_hex_felt(value)).collect()
+            }
+            CalldataFormat::HexText => {
+                let mut content = String::new();
+                reader.read_to_string(&mut content)?;
+                content.split_whitespace().map(parse_hex_felt).collect()
+            }
+            CalldataFormat::Binary => {
+                let mut bytes = Vec::new();
+                reader.read_to_end(&mut bytes)?;
+                if bytes.len() % FELT_BYTES != 0 {
+                    return Err(ProofSubmissionError::InvalidCalldataFormat(format!(
+                        "Binary calldata length {} is not a multiple of {} bytes",
+                        bytes.len(),
+                        FELT_BYTES
+                    )));
+                }
+                Ok(bytes
+                    .chunks_exact(FELT_BYTES)
+                    .map(Felt::from_bytes_be_slice)
+                    .collect())
+            }
+        }
+    }
+}
+
+impl fmt::Display for CalldataFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for CalldataFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        CalldataFormat::ALL
+            .into_iter()
+            .find(|format| format.as_str() == s)
+            .ok_or_else(|| {
+                format!(
+                    "Unknown calldata format '{}', expected json, hex-text or binary",
+                    s
+                )
+            })
+    }
+}
+
+fn parse_hex_felt(value: &str) -> Result<Felt, ProofSubmissionError> {
+    Felt::from_hex(value).map_err(|e| {
+        ProofSubmissionError::InvalidCalldataFormat(format!("Invalid hex value '{}': {}", value, e))
+    })
+}
+
+/// Read calldata from a file of whitespace-separated hex values
+pub fn read_calldata_file(file_path: &Path) -> Result<Vec<Felt>, ProofSubmissionError> {
+    let content = fs::read_to_string(file_path)?;
+    let mut calldata = Vec::new();
+
+    for (line_index, line) in content.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+
+        // Split by whitespace and parse each hex value
+        for (column_index, hex_str) in line.split_whitespace().enumerate() {
+            let felt =
+                Felt::from_hex(hex_str).map_err(|e| ProofSubmissionError::CalldataParseError {
+                    file: file_path.to_path_buf(),
+                    line: line_index + 1,
+                    column: column_index + 1,
+                    value: hex_str.to_string(),
+                    cause: e.to_string(),
+                })?;
+            calldata.push(felt);
+        }
+    }
+
+    debug!(
+        "Read {} calldata elements from {:?}",
+        calldata.len(),
+        file_path
+    );
+    Ok(calldata)
+}
+
+/// Calldata of a whole proof directory in submission order: `initial`, `step1`..`stepN`, `final`
+pub fn read_calldata_dir(calldata_dir: &Path) -> Result<Vec<Felt>, ProofSubmissionError> {
+    if !calldata_dir.is_dir() {
+        return Err(ProofSubmissionError::CalldataDirNotFound(
+            calldata_dir.display().to_string(),
+        ));
+    }
+
+    let mut files = vec!["initial".to_string()];
+    files.extend(
+        (1..)
+            .map(|step| format!("step{}", step))
+            .take_while(|name| calldata_dir.join(name).exists()),
+    );
+    files.push("final".to_string());
+
+    let mut calldata = Vec::new();
+    for name in files {
+        let file = calldata_dir.join(&name);
+        if !file.exists() {
+            return Err(ProofSubmissionError::CalldataFileMissing(name));
+        }
+        calldata.extend(read_calldata_file(&file)?);
+    }
+    Ok(calldata)
+}
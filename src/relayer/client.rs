@@ -3,11 +3,16 @@ use crate::relayer::proof_submission::{
     ProofSubmissionConfig, ProofSubmissionError, ProofSubmissionRelayer,
 };
 use sqlx::{Pool, Postgres};
+use std::future::Future;
 use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::time::sleep;
+use tracing::{error, info};
 
 /// High-level client for proof submission operations
 pub struct ProofSubmissionClient {
-    relayer: ProofSubmissionRelayer,
+    relayer: Arc<ProofSubmissionRelayer>,
 }
 
 impl ProofSubmissionClient {
@@ -16,10 +21,19 @@ impl ProofSubmissionClient {
         db_pool: Pool<Postgres>,
         config: AppConfig,
     ) -> Result<Self, ProofSubmissionError> {
-        let proof_config = ProofSubmissionConfig::from(config);
+        Self::from_config(db_pool, ProofSubmissionConfig::from(config)).await
+    }
+
+    /// Create a new ProofSubmissionClient from an already resolved proof submission config
+    pub async fn from_config(
+        db_pool: Pool<Postgres>,
+        proof_config: ProofSubmissionConfig,
+    ) -> Result<Self, ProofSubmissionError> {
         let relayer = ProofSubmissionRelayer::new(db_pool, proof_config).await?;
 
-        Ok(Self { relayer })
+        Ok(Self {
+            relayer: Arc::new(relayer),
+        })
     }
 
     /// Submit a proof from a calldata directory
@@ -29,7 +43,10 @@ impl ProofSubmissionClient {
     /// - Submitting proofs in the correct order (initial -> steps -> final)
     /// - Updating database records at each stage
     /// - Retrying failed transactions with exponential backoff
-    /// - Resuming from interruptions
+    /// - Resuming from interruptions when `resume` is set
+    /// - Scheduling the finalization of `deposit_ids` once the proof is accepted
+    /// - Checking that `fact_hash`, when given, was registered before completing the job
+    #[allow(clippy::too_many_arguments)]
     pub async fn submit_proof(
         &self,
         calldata_dir: PathBuf,
@@ -38,6 +55,9 @@ impl ProofSubmissionClient {
         hasher: String,
         stone_version: String,
         memory_verification: String,
+        resume: bool,
+        deposit_ids: Vec<i32>,
+        fact_hash: Option<String>,
     ) -> Result<(), ProofSubmissionError> {
         self.relayer
             .submit_proof_from_calldata(
@@ -47,10 +67,56 @@ impl ProofSubmissionClient {
                 hasher,
                 stone_version,
                 memory_verification,
+                resume,
+                deposit_ids,
+                fact_hash,
             )
             .await
     }
 
+    /// Submit all queued proof jobs concurrently, returning the jobs that failed
+    pub async fn process_all_queued_jobs(
+        &self,
+    ) -> Result<Vec<(u64, ProofSubmissionError)>, ProofSubmissionError> {
+        Arc::clone(&self.relayer).process_all_queued_jobs().await
+    }
+
+    /// Submit queued proof jobs every `poll_interval` until `shutdown` resolves.
+    ///
+    /// Jobs that fail are marked `failed` so they are not retried on every poll.
+    pub async fn watch_queued_jobs<F>(&self, poll_interval: Duration, shutdown: F)
+    where
+        F: Future<Output = ()>,
+    {
+        tokio::pin!(shutdown);
+
+        loop {
+            match self.process_all_queued_jobs().await {
+                Ok(failures) => {
+                    for (job_id, e) in failures {
+                        error!("Queued proof job {} failed: {:?}", job_id, e);
+                        if let Err(e) = self
+                            .relayer
+                            .mark_proof_job_failed(job_id, &e.to_string())
+                            .await
+                        {
+                            error!("Failed to mark proof job {} as failed: {:?}", job_id, e);
+                        }
+                    }
+                }
+                Err(e) => error!("Failed to process queued proof jobs: {:?}", e),
+            }
+
+            tokio::select! {
+                _ = &mut shutdown => {
+                    info!("Shutdown requested, stopping proof job watcher");
+                    return;
+                }
+                _ = sleep(poll_interval) => {}
+            }
+        }
+    }
+
     /// Get the underlying relayer instance (for advanced usage)
     pub fn relayer(&self) -> &ProofSubmissionRelayer {
         &self.relayer
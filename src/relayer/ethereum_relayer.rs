@@ -1,9 +1,16 @@
 use crate::config::RelayerConfig;
-use alloy_json_rpc::RpcError;
-use alloy_primitives::{hex, Address, U256};
+use crate::db::database;
+use crate::events::EventBus;
+use crate::utils::{convert_to_base_units, DEFAULT_AMOUNT_PRECISION};
+use alloy_json_rpc::{ErrorPayload, RpcError};
+use alloy_primitives::{hex, Address, Bytes, U256};
 use alloy_rpc_client::{ClientBuilder, RpcClient};
-use alloy_sol_types::{sol, SolCall};
+use alloy_sol_types::{decode_revert_reason, sol, SolCall};
+use chrono::{DateTime, Utc};
+use futures_util::future::BoxFuture;
+use serde::{de, Deserialize, Deserializer, Serialize};
 use sqlx::{PgConnection, PgPool};
+use std::fmt;
 use std::time::Duration;
 use thiserror::Error;
 use tokio::time::sleep;
@@ -37,29 +44,347 @@ pub enum RelayerError {
 
     #[error("Failed to fetch proof: {0}")]
     ProofFetchFailed(String),
+
+    #[error("Gas price {current_gwei} gwei exceeds the maximum of {max_gwei} gwei")]
+    GasPriceTooHigh { current_gwei: u64, max_gwei: u64 },
 }
 
+const WEI_PER_GWEI: u64 = 1_000_000_000;
+
+/// Most withdrawals relayed in one processing cycle
+const RELAY_BATCH_SIZE: usize = 10;
+
 /// Data structure for withdrawal with proof
 #[derive(Debug)]
 pub struct WithdrawalWithProof {
     pub withdrawal_id: i32,
+    pub retry_count: i32,
     pub stark_pub_key: String,
     pub amount: i64,
     pub l2_tx_id: String,
+    /// Hash of the L2 transaction that initiated the withdrawal, when it came from L2
+    pub l2_tx_hash: Option<String>,
     pub commitment_hash: String,
     pub proof_params: Vec<u8>,
     pub proof_data: Vec<u8>,
+    /// Decimals of the withdrawn token; `amount` is scaled to its base units when relayed
+    pub amount_precision: u8,
+}
+
+/// Published once a withdrawal's unlock transaction is confirmed on Ethereum
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CompletedWithdrawalEvent {
+    pub withdrawal_id: i32,
+    pub tx_hash: String,
+    pub amount: i64,
+    pub stark_pub_key: String,
+    pub timestamp: DateTime<Utc>,
+}
+
+/// A quantity that nodes may encode either as a `0x`-prefixed hex string or as a JSON integer
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct U64OrHex(pub u64);
+
+impl<'de> Deserialize<'de> for U64OrHex {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct U64OrHexVisitor;
+
+        impl de::Visitor<'_> for U64OrHexVisitor {
+            type Value = U64OrHex;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str("an unsigned integer or a 0x-prefixed hex string")
+            }
+
+            fn visit_u64<E: de::Error>(self, value: u64) -> Result<U64OrHex, E> {
+                Ok(U64OrHex(value))
+            }
+
+            fn visit_i64<E: de::Error>(self, value: i64) -> Result<U64OrHex, E> {
+                u64::try_from(value)
+                    .map(U64OrHex)
+                    .map_err(|_| E::invalid_value(de::Unexpected::Signed(value), &self))
+            }
+
+            fn visit_str<E: de::Error>(self, value: &str) -> Result<U64OrHex, E> {
+                let parsed = match value.strip_prefix("0x") {
+                    Some(hex) => u64::from_str_radix(hex, 16),
+                    None => value.parse(),
+                };
+                parsed
+                    .map(U64OrHex)
+                    .map_err(|_| E::invalid_value(de::Unexpected::Str(value), &self))
+            }
+        }
+
+        deserializer.deserialize_any(U64OrHexVisitor)
+    }
+}
+
+fn deserialize_optional_u64<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Option<u64>, D::Error> {
+    Ok(Option::<U64OrHex>::deserialize(deserializer)?.map(|value| value.0))
+}
+
+/// The fields of an `eth_getTransactionReceipt` result the relayer acts on
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EthereumReceipt {
+    /// `1` on success and `0` on revert; absent on pre-Byzantium receipts
+    #[serde(default)]
+    pub status: Option<U64OrHex>,
+    pub transaction_hash: String,
+    #[serde(default, deserialize_with = "deserialize_optional_u64")]
+    pub block_number: Option<u64>,
+    #[serde(default)]
+    pub gas_used: Option<U256>,
+    /// Price per unit of gas actually paid, in wei
+    #[serde(default)]
+    pub effective_gas_price: Option<U256>,
+}
+
+/// Gas a relayed withdrawal's transaction spent, as stored on the withdrawal
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GasStats {
+    pub gas_used: Option<i64>,
+    /// Effective gas price rounded to the nearest gwei
+    pub gas_price_gwei: Option<i64>,
+}
+
+impl EthereumReceipt {
+    /// `gasUsed` and `effectiveGasPrice`, each `None` when missing or too large to store
+    pub fn gas_stats(&self) -> GasStats {
+        let gwei = U256::from(WEI_PER_GWEI);
+        GasStats {
+            gas_used: self.gas_used.and_then(|gas| i64::try_from(gas).ok()),
+            gas_price_gwei: self
+                .effective_gas_price
+                .and_then(|price| i64::try_from((price + gwei / U256::from(2)) / gwei).ok()),
+        }
+    }
+
+    /// Turns a reverted or status-less receipt into an error
+    pub fn ensure_success(&self) -> Result<(), RelayerError> {
+        let gas_used = self
+            .gas_used
+            .map(|gas| gas.to_string())
+            .unwrap_or_else(|| "unknown".to_string());
+
+        match self.status {
+            Some(U64OrHex(1)) => {
+                info!(
+                    "Transaction {} confirmed in block {:?}, gas used {}",
+                    self.transaction_hash, self.block_number, gas_used
+                );
+                Ok(())
+            }
+            Some(U64OrHex(0)) => Err(RelayerError::TransactionFailed(format!(
+                "Transaction reverted: {} (gas used {})",
+                self.transaction_hash, gas_used
+            ))),
+            Some(U64OrHex(status)) => Err(RelayerError::RpcError(format!(
+                "Unexpected receipt status {} for {}",
+                status, self.transaction_hash
+            ))),
+            None => Err(RelayerError::RpcError(format!(
+                "Receipt for {} has no status",
+                self.transaction_hash
+            ))),
+        }
+    }
+}
+
+/// Withdrawals that are ready to be relayed, with their proofs, oldest first.
+///
+/// The returned rows are locked with `FOR UPDATE SKIP LOCKED` until `conn`'s transaction ends,
+/// so another relayer fetching at the same time gets different withdrawals. `skip_ids` are
+/// left out.
+pub async fn fetch_ready_for_relay_withdrawals(
+    conn: &mut PgConnection,
+    max_retries: i32,
+    skip_ids: &[i32],
+    limit: i64,
+) -> Result<Vec<WithdrawalWithProof>, sqlx::Error> {
+    let records = sqlx::query!(
+        r#"
+        SELECT d.id, d.stark_pub_key, d.amount, d.l2_tx_id, d.l2_tx_hash, d.commitment_hash,
+              d.retry_count, d.amount_precision, dp.proof_params, dp.proof_data
+        FROM withdrawals d
+        JOIN withdrawal_proofs dp ON d.id = dp.withdrawal_id
+        WHERE d.status = 'ready_for_relay' AND d.retry_count < $1 AND dp.status = 'ready'
+        AND NOT (d.id = ANY($2))
+        ORDER BY d.created_at ASC
+        LIMIT $3
+        FOR UPDATE OF d SKIP LOCKED
+        "#,
+        max_retries,
+        skip_ids,
+        limit
+    )
+    .fetch_all(conn)
+    .await?;
+
+    let withdrawals_with_proofs = records
+        .into_iter()
+        .map(|row| WithdrawalWithProof {
+            withdrawal_id: row.id,
+            retry_count: row.retry_count,
+            stark_pub_key: row.stark_pub_key,
+            amount: row.amount,
+            l2_tx_id: row.l2_tx_id.map_or(String::new(), |id| id.to_string()),
+            l2_tx_hash: row.l2_tx_hash,
+            commitment_hash: row.commitment_hash,
+            proof_params: row.proof_params.unwrap_or_default(),
+            proof_data: row.proof_data.unwrap_or_default(),
+            amount_precision: u8::try_from(row.amount_precision)
+                .unwrap_or(DEFAULT_AMOUNT_PRECISION),
+        })
+        .collect();
+
+    Ok(withdrawals_with_proofs)
+}
+
+/// Parameters of a transaction sent, simulated or estimated against the bridge contract
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TxParams {
+    pub from: Address,
+    pub to: Address,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub gas: Option<U256>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub gas_price: Option<U256>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub nonce: Option<U256>,
+    pub data: Bytes,
+}
+
+/// Ethereum node access needed by the relayer, so tests can stand in for the RPC node
+pub trait EthereumProvider {
+    /// Broadcasts a transaction signed by the node, returning its hash
+    fn send_transaction(&self, params: TxParams) -> BoxFuture<'_, Result<String, RelayerError>>;
+
+    /// Receipt of transaction `hash`, `None` while it is not mined
+    fn get_receipt<'a>(
+        &'a self,
+        hash: &'a str,
+    ) -> BoxFuture<'a, Result<Option<EthereumReceipt>, RelayerError>>;
+
+    /// Accounts the node can sign for
+    fn get_accounts(&self) -> BoxFuture<'_, Result<Vec<Address>, RelayerError>>;
+
+    /// Current gas price, in wei
+    fn gas_price(&self) -> BoxFuture<'_, Result<U256, RelayerError>>;
+
+    /// Nonce of the next transaction from `address`
+    fn get_transaction_count(&self, address: Address) -> BoxFuture<'_, Result<U256, RelayerError>>;
+
+    /// Gas the node expects `params` to use
+    fn estimate_gas(&self, params: TxParams) -> BoxFuture<'_, Result<U256, RelayerError>>;
+
+    /// Runs `params` through `eth_call`, returning its return data. A revert is a
+    /// `ContractError` carrying the revert reason.
+    fn call(&self, params: TxParams) -> BoxFuture<'_, Result<String, RelayerError>>;
+}
+
+/// `EthereumProvider` backed by an Ethereum JSON-RPC endpoint
+pub struct AlloyEthereumProvider {
+    client: RpcClient,
+}
+
+impl AlloyEthereumProvider {
+    pub fn new(ethereum_rpc_url: Url) -> Self {
+        Self {
+            client: ClientBuilder::default().http(ethereum_rpc_url),
+        }
+    }
+}
+
+impl EthereumProvider for AlloyEthereumProvider {
+    fn send_transaction(&self, params: TxParams) -> BoxFuture<'_, Result<String, RelayerError>> {
+        Box::pin(async move {
+            self.client
+                .request("eth_sendTransaction", [params])
+                .await
+                .map_err(rpc_error)
+        })
+    }
+
+    fn get_receipt<'a>(
+        &'a self,
+        hash: &'a str,
+    ) -> BoxFuture<'a, Result<Option<EthereumReceipt>, RelayerError>> {
+        Box::pin(async move {
+            self.client
+                .request("eth_getTransactionReceipt", [hash])
+                .await
+                .map_err(|e| RelayerError::RpcError(e.to_string()))
+        })
+    }
+
+    fn get_accounts(&self) -> BoxFuture<'_, Result<Vec<Address>, RelayerError>> {
+        Box::pin(async move {
+            self.client
+                .request_noparams("eth_accounts")
+                .await
+                .map_err(|e| RelayerError::RpcError(e.to_string()))
+        })
+    }
+
+    fn gas_price(&self) -> BoxFuture<'_, Result<U256, RelayerError>> {
+        Box::pin(async move {
+            self.client
+                .request_noparams("eth_gasPrice")
+                .await
+                .map_err(|e| RelayerError::RpcError(e.to_string()))
+        })
+    }
+
+    fn get_transaction_count(&self, address: Address) -> BoxFuture<'_, Result<U256, RelayerError>> {
+        Box::pin(async move {
+            self.client
+                .request("eth_getTransactionCount", (address, "latest"))
+                .await
+                .map_err(|e| RelayerError::RpcError(e.to_string()))
+        })
+    }
+
+    fn estimate_gas(&self, params: TxParams) -> BoxFuture<'_, Result<U256, RelayerError>> {
+        Box::pin(async move {
+            self.client
+                .request("eth_estimateGas", [params])
+                .await
+                .map_err(rpc_error)
+        })
+    }
+
+    fn call(&self, params: TxParams) -> BoxFuture<'_, Result<String, RelayerError>> {
+        Box::pin(async move {
+            self.client
+                .request("eth_call", (params, "latest"))
+                .await
+                .map_err(|e| match e {
+                    RpcError::ErrorResp(payload) if is_revert(&payload) => {
+                        RelayerError::ContractError(revert_reason(&payload))
+                    }
+                    e => rpc_error(e),
+                })
+        })
+    }
 }
 
 /// Relayer for sending L1 transactions to Ethereum
-pub struct EthereumRelayer {
+pub struct EthereumRelayer<P = AlloyEthereumProvider> {
     db_pool: PgPool,
-    client: RpcClient,
+    provider: P,
     contract_address: Address,
     config: RelayerConfig,
+    /// Receives a `CompletedWithdrawalEvent` for every confirmed relay
+    completions: Option<EventBus<CompletedWithdrawalEvent>>,
 }
 
-impl EthereumRelayer {
+impl EthereumRelayer<AlloyEthereumProvider> {
     /// Create a new Ethereum relayer
     pub async fn new(
         db_pool: PgPool,
@@ -67,20 +392,42 @@ impl EthereumRelayer {
         contract_address: &str,
         config: RelayerConfig,
     ) -> Result<Self, RelayerError> {
-        let client = ClientBuilder::default().http(ethereum_rpc_url);
+        Self::with_provider(
+            db_pool,
+            AlloyEthereumProvider::new(ethereum_rpc_url),
+            contract_address,
+            config,
+        )
+    }
+}
 
+impl<P: EthereumProvider> EthereumRelayer<P> {
+    /// Create a relayer that reaches Ethereum through `provider`
+    pub fn with_provider(
+        db_pool: PgPool,
+        provider: P,
+        contract_address: &str,
+        config: RelayerConfig,
+    ) -> Result<Self, RelayerError> {
         let contract_address = contract_address
             .parse::<Address>()
             .map_err(|e| RelayerError::ContractError(format!("Invalid contract address: {}", e)))?;
 
         Ok(Self {
             db_pool,
-            client,
+            provider,
             contract_address,
             config,
+            completions: None,
         })
     }
 
+    /// Publishes a `CompletedWithdrawalEvent` to `bus` after every confirmed relay
+    pub fn with_completion_bus(mut self, bus: EventBus<CompletedWithdrawalEvent>) -> Self {
+        self.completions = Some(bus);
+        self
+    }
+
     /// Run the relayer in an infinite loop
     pub async fn run(&self) {
         loop {
@@ -94,42 +441,27 @@ impl EthereumRelayer {
 
     /// Process transactions that are ready to be relayed
     async fn process_relay_transactions(&self) -> Result<(), RelayerError> {
-        let withdrawals_to_relay = self.fetch_ready_for_relay_withdrawals().await?;
-
-        for withdrawal in withdrawals_to_relay {
-            let mut tx = self.db_pool.begin().await?;
+        let mut attempted = Vec::new();
 
-            match self.relay_transaction(&withdrawal).await {
-                Ok(_) => {
-                    info!(
-                        "Successfully relayed transaction for withdrawal {}",
-                        withdrawal.withdrawal_id
+        while attempted.len() < RELAY_BATCH_SIZE {
+            // Withdrawals that failed earlier in this cycle wait for the next one
+            match self.relay_next_withdrawal(&attempted).await {
+                Ok(Some(withdrawal_id)) => attempted.push(withdrawal_id),
+                Ok(None) => break,
+                Err(RelayerError::GasPriceTooHigh {
+                    current_gwei,
+                    max_gwei,
+                }) => {
+                    warn!(
+                        "Gas price {} gwei is above the {} gwei limit, skipping this relay cycle",
+                        current_gwei, max_gwei
                     );
-                    self.update_withdrawal_status(&mut tx, withdrawal.withdrawal_id, "relayed")
-                        .await?;
-                }
-                Err(e) => {
-                    let retry_count = self.get_retry_count(&withdrawal.withdrawal_id).await?;
-                    if retry_count >= self.config.max_retries as i32 - 1 {
-                        error!(
-                            "Max retries reached for withdrawal {}. Marking as failed: {:?}",
-                            withdrawal.withdrawal_id, e
-                        );
-                        self.update_withdrawal_status(&mut tx, withdrawal.withdrawal_id, "failed")
-                            .await?;
-                    } else {
-                        warn!(
-                            "Failed to relay transaction for withdrawal {}. Will retry: {:?}",
-                            withdrawal.withdrawal_id, e
-                        );
-                        self.increment_retry_count(&mut tx, withdrawal.withdrawal_id)
-                            .await?;
-                    }
+                    sleep(Duration::from_secs(self.config.retry_delay_seconds.into())).await;
+                    return Ok(());
                 }
+                Err(e) => return Err(e),
             }
 
-            tx.commit().await?;
-
             // small delay between sending transactions to avoid nonce issues
             sleep(Duration::from_millis(500)).await;
         }
@@ -137,55 +469,98 @@ impl EthereumRelayer {
         Ok(())
     }
 
-    /// Get the retry count for a withdrawal
-    async fn get_retry_count(&self, withdrawal_id: &i32) -> Result<i32, RelayerError> {
-        let retry_count = sqlx::query!(
-            r#"
-            SELECT retry_count FROM withdrawals
-            WHERE id = $1
-            "#,
-            withdrawal_id
-        )
-        .fetch_one(&self.db_pool)
-        .await?
-        .retry_count;
+    /// Relays the oldest ready withdrawal not in `skip_ids` and records the outcome, returning
+    /// its id, or `None` when no withdrawal is ready.
+    ///
+    /// The withdrawal is claimed in the transaction that records the outcome, which keeps its
+    /// row locked so concurrent relayers skip it. A `GasPriceTooHigh` error leaves it untouched.
+    pub async fn relay_next_withdrawal(
+        &self,
+        skip_ids: &[i32],
+    ) -> Result<Option<i32>, RelayerError> {
+        let mut tx = self.db_pool.begin().await?;
 
-        Ok(retry_count)
-    }
+        let Some(withdrawal) =
+            fetch_ready_for_relay_withdrawals(&mut tx, self.config.max_retries as i32, skip_ids, 1)
+                .await?
+                .pop()
+        else {
+            tx.rollback().await?;
+            return Ok(None);
+        };
 
-    /// Fetch withdrawals that are ready to be relayed with their proofs
-    async fn fetch_ready_for_relay_withdrawals(
-        &self,
-    ) -> Result<Vec<WithdrawalWithProof>, RelayerError> {
-        let records = sqlx::query!(
-            r#"
-            SELECT d.id, d.stark_pub_key, d.amount, d.l2_tx_id, d.commitment_hash, 
-                  dp.proof_params, dp.proof_data 
-            FROM withdrawals d
-            JOIN withdrawal_proofs dp ON d.id = dp.withdrawal_id
-            WHERE d.status = 'ready_for_relay' AND d.retry_count < $1 AND dp.status = 'ready'
-            ORDER BY d.created_at ASC
-            LIMIT 10
-            "#,
-            self.config.max_retries as i32
-        )
-        .fetch_all(&self.db_pool)
-        .await?;
+        match self.relay_transaction(&withdrawal).await {
+            Err(e @ RelayerError::GasPriceTooHigh { .. }) => {
+                // Not the withdrawal's fault, so leave its retry count alone
+                tx.rollback().await?;
+                return Err(e);
+            }
+            Ok(Some(receipt)) => {
+                info!(
+                    "Successfully relayed transaction for withdrawal {}",
+                    withdrawal.withdrawal_id
+                );
+                let gas = receipt.gas_stats();
+                database::update_withdrawal_gas_stats(
+                    &mut tx,
+                    withdrawal.withdrawal_id,
+                    gas.gas_used,
+                    gas.gas_price_gwei,
+                )
+                .await?;
+                self.update_withdrawal_status(&mut tx, withdrawal.withdrawal_id, "relayed")
+                    .await?;
+                tx.commit().await?;
+                self.publish_completion(&withdrawal, receipt.transaction_hash);
+            }
+            Ok(None) => {
+                // Dry runs leave the withdrawal ready for a real relay
+                info!(
+                    "Dry run for withdrawal {} succeeded",
+                    withdrawal.withdrawal_id
+                );
+                tx.rollback().await?;
+            }
+            Err(e) if self.config.dry_run => {
+                warn!(
+                    "Dry run for withdrawal {} failed: {:?}",
+                    withdrawal.withdrawal_id, e
+                );
+                tx.rollback().await?;
+            }
+            Err(e) => {
+                if withdrawal.retry_count >= self.config.max_retries as i32 - 1 {
+                    error!(
+                        "Max retries reached for withdrawal {}. Marking as failed: {:?}",
+                        withdrawal.withdrawal_id, e
+                    );
+                    self.update_withdrawal_status(&mut tx, withdrawal.withdrawal_id, "failed")
+                        .await?;
+                } else {
+                    warn!(
+                        "Failed to relay transaction for withdrawal {}. Will retry: {:?}",
+                        withdrawal.withdrawal_id, e
+                    );
+                    self.increment_retry_count(&mut tx, withdrawal.withdrawal_id)
+                        .await?;
+                }
+                tx.commit().await?;
+            }
+        }
 
-        let withdrawals_with_proofs = records
-            .into_iter()
-            .map(|row| WithdrawalWithProof {
-                withdrawal_id: row.id,
-                stark_pub_key: row.stark_pub_key,
-                amount: row.amount,
-                l2_tx_id: row.l2_tx_id.map_or(String::new(), |id| id.to_string()),
-                commitment_hash: row.commitment_hash,
-                proof_params: row.proof_params.unwrap_or_default(),
-                proof_data: row.proof_data.unwrap_or_default(),
-            })
-            .collect();
+        Ok(Some(withdrawal.withdrawal_id))
+    }
 
-        Ok(withdrawals_with_proofs)
+    fn publish_completion(&self, withdrawal: &WithdrawalWithProof, tx_hash: String) {
+        if let Some(bus) = &self.completions {
+            bus.publish(CompletedWithdrawalEvent {
+                withdrawal_id: withdrawal.withdrawal_id,
+                tx_hash,
+                amount: withdrawal.amount,
+                stark_pub_key: withdrawal.stark_pub_key.clone(),
+                timestamp: Utc::now(),
+            });
+        }
     }
 
     /// Update the status of a withdrawal
@@ -195,17 +570,7 @@ impl EthereumRelayer {
         id: i32,
         status: &str,
     ) -> Result<(), RelayerError> {
-        sqlx::query!(
-            r#"
-            UPDATE withdrawals 
-            SET status = $2, updated_at = NOW()
-            WHERE id = $1
-            "#,
-            id,
-            status
-        )
-        .execute(conn)
-        .await?;
+        database::update_withdrawal_status(conn, id, status, "ethereum_relayer").await?;
 
         Ok(())
     }
@@ -230,11 +595,12 @@ impl EthereumRelayer {
         Ok(())
     }
 
-    /// Relay a transaction to Ethereum
+    /// Relay a transaction to Ethereum, returning the receipt of the confirmed transaction, or
+    /// `None` after a successful dry run
     async fn relay_transaction(
         &self,
         withdrawal: &WithdrawalWithProof,
-    ) -> Result<(), RelayerError> {
+    ) -> Result<Option<EthereumReceipt>, RelayerError> {
         // Try to send the transaction with retry logic
         let mut retry_count = 0;
         while retry_count < self.config.max_retries {
@@ -246,13 +612,18 @@ impl EthereumRelayer {
             );
 
             match self.send_unlock_funds_transaction(withdrawal).await {
-                Ok(_) => {
-                    info!(
-                        "Transaction for withdrawal {} successfully sent",
-                        withdrawal.withdrawal_id
-                    );
-                    return Ok(());
+                Ok(receipt) => {
+                    if receipt.is_some() {
+                        info!(
+                            "Transaction for withdrawal {} successfully sent",
+                            withdrawal.withdrawal_id
+                        );
+                    }
+                    return Ok(receipt);
                 }
+                Err(e @ RelayerError::GasPriceTooHigh { .. }) => return Err(e),
+                // A simulated revert would only revert again
+                Err(e @ RelayerError::ContractError(_)) if self.config.dry_run => return Err(e),
                 Err(e) => {
                     warn!("Failed to send transaction for withdrawal {}: {:?}. Retrying in {} seconds...", 
                           withdrawal.withdrawal_id, e, self.config.retry_delay_seconds);
@@ -271,16 +642,16 @@ impl EthereumRelayer {
         )))
     }
 
-    /// Send an Ethereum transaction to the unlock_funds_with_proof function
-    async fn send_unlock_funds_transaction(
+    /// Send an Ethereum transaction to the unlock_funds_with_proof function and wait for it to
+    /// be confirmed, returning its receipt.
+    ///
+    /// With `dry_run` set, the transaction is simulated with `eth_call` instead and `None` is
+    /// returned unless it reverts.
+    pub async fn send_unlock_funds_transaction(
         &self,
         withdrawal: &WithdrawalWithProof,
-    ) -> Result<(), RelayerError> {
-        let accounts: Vec<Address> = self
-            .client
-            .request_noparams("eth_accounts")
-            .await
-            .map_err(|e| RelayerError::RpcError(e.to_string()))?;
+    ) -> Result<Option<EthereumReceipt>, RelayerError> {
+        let accounts = self.provider.get_accounts().await?;
 
         if accounts.is_empty() {
             return Err(RelayerError::RpcError("No accounts available".to_string()));
@@ -288,17 +659,10 @@ impl EthereumRelayer {
 
         let from = accounts[0];
 
-        let gas_price: U256 = self
-            .client
-            .request_noparams("eth_gasPrice")
-            .await
-            .map_err(|e| RelayerError::RpcError(e.to_string()))?;
+        let gas_price = self.provider.gas_price().await?;
+        check_gas_price(gas_price, self.config.max_gas_price_gwei)?;
 
-        let nonce: U256 = self
-            .client
-            .request("eth_getTransactionCount", (from, "latest"))
-            .await
-            .map_err(|e| RelayerError::RpcError(e.to_string()))?;
+        let nonce = self.provider.get_transaction_count(from).await?;
 
         // Parse user pub key
         let stark_pub_key = withdrawal
@@ -306,8 +670,11 @@ impl EthereumRelayer {
             .parse::<U256>()
             .map_err(|e| RelayerError::ContractError(format!("Invalid stark pub key: {}", e)))?;
 
-        // Parse amount
-        let amount = U256::from(withdrawal.amount);
+        // Amounts are stored in USD cents; the contract expects the token's base units
+        let amount = U256::from(convert_to_base_units(
+            withdrawal.amount,
+            withdrawal.amount_precision,
+        ));
 
         // Parse L2 TX ID, defaulting to 0 if empty
         let l2_tx_id = if withdrawal.l2_tx_id.is_empty() {
@@ -344,35 +711,83 @@ impl EthereumRelayer {
             commitmentHash: alloy_primitives::FixedBytes(commitment_bytes32),
         };
 
-        let call_data = call.abi_encode();
+        let call_data = Bytes::from(call.abi_encode());
+        let gas_limit = self.estimate_gas_limit(from, &call_data).await;
 
-        let tx_params = serde_json::json!({
-            "from": from,
-            "to": self.contract_address,
-            "gas": format!("0x{:x}", self.config.gas_limit),
-            "gasPrice": format!("0x{:x}", gas_price),
-            "nonce": format!("0x{:x}", nonce),
-            "data": format!("0x{}", hex::encode(&call_data)),
-        });
+        let tx_params = TxParams {
+            from,
+            to: self.contract_address,
+            gas: Some(U256::from(gas_limit)),
+            gas_price: Some(gas_price),
+            nonce: Some(nonce),
+            data: call_data,
+        };
 
-        let tx_hash: String = self
-            .client
-            .request("eth_sendTransaction", [tx_params])
-            .await
-            .map_err(|e| match e {
-                RpcError::ErrorResp(payload) => RelayerError::RpcError(format!(
-                    "RPC error {} - {}",
-                    payload.code, payload.message
-                )),
-                RpcError::Transport(e) => RelayerError::RpcError(format!("Transport error: {}", e)),
-                _ => RelayerError::RpcError(e.to_string()),
-            })?;
+        if self.config.dry_run {
+            self.simulate_transaction(tx_params, withdrawal).await?;
+            return Ok(None);
+        }
+
+        let tx_hash = self.provider.send_transaction(tx_params).await?;
+        info!(
+            "Sent unlock transaction {} for withdrawal {} (L2 tx: {})",
+            tx_hash,
+            withdrawal.withdrawal_id,
+            withdrawal.l2_tx_hash.as_deref().unwrap_or("unknown")
+        );
 
-        self.wait_for_transaction_receipt(&tx_hash).await?;
+        let receipt = self.wait_for_transaction_receipt(&tx_hash).await?;
 
+        Ok(Some(receipt))
+    }
+
+    /// Run `tx_params` through `eth_call` without spending gas, logging the return data
+    async fn simulate_transaction(
+        &self,
+        tx_params: TxParams,
+        withdrawal: &WithdrawalWithProof,
+    ) -> Result<(), RelayerError> {
+        let return_data = self.provider.call(tx_params).await?;
+
+        info!(
+            "Dry run of unlock transaction for withdrawal {} succeeded, returning {}",
+            withdrawal.withdrawal_id, return_data
+        );
         Ok(())
     }
 
+    /// Estimate the gas for a call to the bridge contract, padded by `gas_estimation_multiplier`.
+    ///
+    /// Falls back to the configured `gas_limit` when the node cannot estimate the call.
+    async fn estimate_gas_limit(&self, from: Address, call_data: &Bytes) -> u64 {
+        let call_params = TxParams {
+            from,
+            to: self.contract_address,
+            gas: None,
+            gas_price: None,
+            nonce: None,
+            data: call_data.clone(),
+        };
+
+        let estimate = self.provider.estimate_gas(call_params).await;
+
+        match estimate {
+            Ok(estimate) => {
+                let estimate = u64::try_from(estimate).unwrap_or(u64::MAX);
+                let padded = pad_gas_estimate(estimate, self.config.gas_estimation_multiplier);
+                trace!("Estimated gas {} (padded to {})", estimate, padded);
+                padded
+            }
+            Err(e) => {
+                warn!(
+                    "Gas estimation failed, falling back to gas_limit {}: {}",
+                    self.config.gas_limit, e
+                );
+                self.config.gas_limit
+            }
+        }
+    }
+
     /// Decode bytes to an array of U256 integers
     fn decode_uint_array_from_bytes(&self, bytes: &[u8]) -> Result<Vec<U256>, RelayerError> {
         let mut result = Vec::new();
@@ -390,7 +805,10 @@ impl EthereumRelayer {
     }
 
     /// Wait for a transaction receipt
-    async fn wait_for_transaction_receipt(&self, tx_hash: &str) -> Result<(), RelayerError> {
+    async fn wait_for_transaction_receipt(
+        &self,
+        tx_hash: &str,
+    ) -> Result<EthereumReceipt, RelayerError> {
         // Ensure tx_hash starts with 0x
         let tx_hash = if !tx_hash.starts_with("0x") {
             format!("0x{}", tx_hash)
@@ -401,30 +819,11 @@ impl EthereumRelayer {
         for _ in 0..60 {
             // Try for up to 5 minutes (60 * 5s)
             // Poll for receipt
-            let receipt: Option<serde_json::Value> = self
-                .client
-                .request("eth_getTransactionReceipt", [&tx_hash])
-                .await
-                .map_err(|e| RelayerError::RpcError(e.to_string()))?;
+            let receipt = self.provider.get_receipt(&tx_hash).await?;
 
             if let Some(receipt) = receipt {
-                // Check status (1 = success, 0 = failure)
-                let status = receipt["status"]
-                    .as_str()
-                    .unwrap_or("0x0")
-                    .trim_start_matches("0x");
-
-                let status_value = u64::from_str_radix(status, 16)
-                    .map_err(|_| RelayerError::RpcError("Invalid status format".to_string()))?;
-
-                if status_value == 1 {
-                    return Ok(());
-                } else {
-                    return Err(RelayerError::TransactionFailed(format!(
-                        "Transaction failed: {}",
-                        tx_hash
-                    )));
-                }
+                receipt.ensure_success()?;
+                return Ok(receipt);
             }
 
             sleep(Duration::from_secs(5)).await;
@@ -437,5 +836,407 @@ impl EthereumRelayer {
     }
 }
 
+fn rpc_error<E: fmt::Display>(e: RpcError<E>) -> RelayerError {
+    match e {
+        RpcError::ErrorResp(payload) => {
+            RelayerError::RpcError(format!("RPC error {} - {}", payload.code, payload.message))
+        }
+        RpcError::Transport(e) => RelayerError::RpcError(format!("Transport error: {}", e)),
+        _ => RelayerError::RpcError(e.to_string()),
+    }
+}
+
+/// Whether an `eth_call` error means the call reverted, as opposed to the node failing
+fn is_revert(payload: &ErrorPayload) -> bool {
+    payload.code == 3 || payload.message.contains("revert")
+}
+
+/// The `Error(string)` reason in the revert data when the node includes it, or else its message
+fn revert_reason(payload: &ErrorPayload) -> String {
+    payload
+        .try_data_as::<String>()
+        .and_then(Result::ok)
+        .and_then(|data| hex::decode(data).ok())
+        .and_then(|data| decode_revert_reason(&data))
+        .unwrap_or_else(|| payload.message.to_string())
+}
+
+/// Rejects a gas price (in wei) above `max_gwei`
+fn check_gas_price(gas_price: U256, max_gwei: Option<u64>) -> Result<(), RelayerError> {
+    let Some(max_gwei) = max_gwei else {
+        return Ok(());
+    };
+
+    if gas_price <= U256::from(max_gwei) * U256::from(WEI_PER_GWEI) {
+        return Ok(());
+    }
+
+    // Round up so a price just over the limit is not reported as equal to it
+    let current_gwei = gas_price.div_ceil(U256::from(WEI_PER_GWEI));
+    Err(RelayerError::GasPriceTooHigh {
+        current_gwei: current_gwei.saturating_to(),
+        max_gwei,
+    })
+}
+
+/// Apply the safety multiplier to a gas estimate, rounding up
+fn pad_gas_estimate(estimate: u64, multiplier: f64) -> u64 {
+    (estimate as f64 * multiplier).ceil() as u64
+}
+
 #[cfg(test)]
-mod tests {}
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+
+    /// Answers every call from its fields and records which methods were called
+    struct MockEthereumProvider {
+        accounts: Vec<Address>,
+        gas_price: U256,
+        /// `None` makes gas estimation fail
+        gas_estimate: Option<U256>,
+        tx_hash: String,
+        receipt: Option<EthereumReceipt>,
+        calls: Mutex<Vec<&'static str>>,
+        sent: Mutex<Vec<TxParams>>,
+    }
+
+    impl Default for MockEthereumProvider {
+        fn default() -> Self {
+            Self {
+                accounts: vec!["0x00000000000000000000000000000000000000cc"
+                    .parse()
+                    .unwrap()],
+                gas_price: U256::from(WEI_PER_GWEI),
+                gas_estimate: Some(U256::from(21_000)),
+                tx_hash: "0xabc".to_string(),
+                receipt: None,
+                calls: Mutex::new(Vec::new()),
+                sent: Mutex::new(Vec::new()),
+            }
+        }
+    }
+
+    impl MockEthereumProvider {
+        fn record(&self, method: &'static str) {
+            self.calls.lock().unwrap().push(method);
+        }
+
+        fn calls(&self) -> Vec<&'static str> {
+            self.calls.lock().unwrap().clone()
+        }
+    }
+
+    impl EthereumProvider for MockEthereumProvider {
+        fn send_transaction(
+            &self,
+            params: TxParams,
+        ) -> BoxFuture<'_, Result<String, RelayerError>> {
+            self.record("eth_sendTransaction");
+            self.sent.lock().unwrap().push(params);
+            Box::pin(async move { Ok(self.tx_hash.clone()) })
+        }
+
+        fn get_receipt<'a>(
+            &'a self,
+            _hash: &'a str,
+        ) -> BoxFuture<'a, Result<Option<EthereumReceipt>, RelayerError>> {
+            self.record("eth_getTransactionReceipt");
+            Box::pin(async move { Ok(self.receipt.clone()) })
+        }
+
+        fn get_accounts(&self) -> BoxFuture<'_, Result<Vec<Address>, RelayerError>> {
+            self.record("eth_accounts");
+            Box::pin(async move { Ok(self.accounts.clone()) })
+        }
+
+        fn gas_price(&self) -> BoxFuture<'_, Result<U256, RelayerError>> {
+            self.record("eth_gasPrice");
+            Box::pin(async move { Ok(self.gas_price) })
+        }
+
+        fn get_transaction_count(
+            &self,
+            _address: Address,
+        ) -> BoxFuture<'_, Result<U256, RelayerError>> {
+            self.record("eth_getTransactionCount");
+            Box::pin(async move { Ok(U256::from(7)) })
+        }
+
+        fn estimate_gas(&self, _params: TxParams) -> BoxFuture<'_, Result<U256, RelayerError>> {
+            self.record("eth_estimateGas");
+            Box::pin(async move {
+                self.gas_estimate
+                    .ok_or_else(|| RelayerError::RpcError("execution reverted".to_string()))
+            })
+        }
+
+        fn call(&self, _params: TxParams) -> BoxFuture<'_, Result<String, RelayerError>> {
+            self.record("eth_call");
+            Box::pin(async move { Ok("0x".to_string()) })
+        }
+    }
+
+    fn test_config() -> RelayerConfig {
+        RelayerConfig {
+            max_retries: 1,
+            retry_delay_seconds: 0,
+            gas_limit: 300_000,
+            gas_estimation_multiplier: 1.2,
+            max_gas_price_gwei: None,
+            allowed_l1_tokens: vec![],
+            dry_run: false,
+            amount_tolerance_bps: 100,
+            run_in_sequencer: false,
+        }
+    }
+
+    fn test_relayer(provider: MockEthereumProvider) -> EthereumRelayer<MockEthereumProvider> {
+        // The database is never touched by sending a transaction
+        let db_pool = PgPool::connect_lazy("postgres://localhost/unused").unwrap();
+        EthereumRelayer::with_provider(
+            db_pool,
+            provider,
+            "0x0000000000000000000000000000000000000001",
+            test_config(),
+        )
+        .unwrap()
+    }
+
+    fn test_withdrawal() -> WithdrawalWithProof {
+        WithdrawalWithProof {
+            withdrawal_id: 1,
+            retry_count: 0,
+            stark_pub_key: "0x1".to_string(),
+            amount: 100,
+            l2_tx_id: String::new(),
+            l2_tx_hash: None,
+            commitment_hash: "0x01".to_string(),
+            proof_params: vec![],
+            proof_data: vec![],
+            amount_precision: DEFAULT_AMOUNT_PRECISION,
+        }
+    }
+
+    fn parse_receipt(json: &str) -> EthereumReceipt {
+        serde_json::from_str(json).unwrap()
+    }
+
+    #[test]
+    fn test_receipt_status_accepts_hex_and_integer() {
+        let hex = parse_receipt(
+            r#"{"status":"0x1","transactionHash":"0xabc","blockNumber":"0x10","gasUsed":"0x5208"}"#,
+        );
+        assert_eq!(hex.status, Some(U64OrHex(1)));
+        assert_eq!(hex.block_number, Some(16));
+        assert_eq!(hex.gas_used, Some(U256::from(21_000)));
+        assert!(hex.ensure_success().is_ok());
+
+        let integer = parse_receipt(r#"{"status":1,"transactionHash":"0xabc","blockNumber":16}"#);
+        assert_eq!(integer.status, Some(U64OrHex(1)));
+        assert_eq!(integer.block_number, Some(16));
+        assert_eq!(integer.gas_used, None);
+        assert!(integer.ensure_success().is_ok());
+    }
+
+    #[test]
+    fn test_reverted_receipt_reports_gas_used() {
+        for json in [
+            r#"{"status":"0x0","transactionHash":"0xdead","gasUsed":"0x5208"}"#,
+            r#"{"status":0,"transactionHash":"0xdead","gasUsed":"0x5208"}"#,
+        ] {
+            match parse_receipt(json).ensure_success() {
+                Err(RelayerError::TransactionFailed(message)) => {
+                    assert!(message.contains("0xdead"), "{}", message);
+                    assert!(message.contains("21000"), "{}", message);
+                }
+                other => panic!("expected TransactionFailed, got {:?}", other),
+            }
+        }
+    }
+
+    #[test]
+    fn test_receipt_without_status_is_rejected() {
+        let receipt = parse_receipt(r#"{"root":"0x01","transactionHash":"0xabc"}"#);
+        assert!(matches!(
+            receipt.ensure_success(),
+            Err(RelayerError::RpcError(_))
+        ));
+        assert!(serde_json::from_str::<EthereumReceipt>(
+            r#"{"status":"0xzz","transactionHash":"0xabc"}"#
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_receipt_gas_stats() {
+        let receipt = parse_receipt(
+            r#"{"status":"0x1","transactionHash":"0xabc","gasUsed":"0x5208","effectiveGasPrice":"0x4a817c800"}"#,
+        );
+        assert_eq!(
+            receipt.effective_gas_price,
+            Some(U256::from(20_000_000_000u64))
+        );
+        assert_eq!(
+            receipt.gas_stats(),
+            GasStats {
+                gas_used: Some(21_000),
+                gas_price_gwei: Some(20),
+            }
+        );
+
+        // 1.5 gwei rounds up, and a receipt missing both fields stores neither
+        let receipt = parse_receipt(
+            r#"{"status":"0x1","transactionHash":"0xabc","effectiveGasPrice":"0x59682f00"}"#,
+        );
+        assert_eq!(
+            receipt.gas_stats(),
+            GasStats {
+                gas_used: None,
+                gas_price_gwei: Some(2),
+            }
+        );
+        let receipt = parse_receipt(r#"{"status":"0x1","transactionHash":"0xabc"}"#);
+        assert_eq!(
+            receipt.gas_stats(),
+            GasStats {
+                gas_used: None,
+                gas_price_gwei: None,
+            }
+        );
+    }
+
+    #[test]
+    fn test_pad_gas_estimate() {
+        assert_eq!(pad_gas_estimate(21_000, 1.2), 25_200);
+        assert_eq!(pad_gas_estimate(100_001, 1.5), 150_002);
+        assert_eq!(pad_gas_estimate(0, 1.2), 0);
+    }
+
+    #[test]
+    fn test_check_gas_price() {
+        let gwei = U256::from(WEI_PER_GWEI);
+
+        assert!(check_gas_price(U256::from(500) * gwei, None).is_ok());
+        assert!(check_gas_price(U256::from(50) * gwei, Some(50)).is_ok());
+        assert!(matches!(
+            check_gas_price(U256::from(50) * gwei + U256::from(1), Some(50)),
+            Err(RelayerError::GasPriceTooHigh {
+                current_gwei: 51,
+                max_gwei: 50
+            })
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_high_gas_price_skips_sending() {
+        let mut relayer = test_relayer(MockEthereumProvider {
+            gas_price: U256::from(100) * U256::from(WEI_PER_GWEI),
+            ..Default::default()
+        });
+        relayer.config.max_gas_price_gwei = Some(50);
+
+        let result = relayer
+            .send_unlock_funds_transaction(&test_withdrawal())
+            .await;
+
+        assert!(matches!(
+            result,
+            Err(RelayerError::GasPriceTooHigh {
+                current_gwei: 100,
+                max_gwei: 50
+            })
+        ));
+        assert_eq!(
+            relayer.provider.calls(),
+            vec!["eth_accounts", "eth_gasPrice"]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_send_returns_confirmed_receipt() {
+        let receipt = parse_receipt(
+            r#"{"status":"0x1","transactionHash":"0xabc","gasUsed":"0x5208","effectiveGasPrice":"0x3b9aca00"}"#,
+        );
+        let relayer = test_relayer(MockEthereumProvider {
+            receipt: Some(receipt.clone()),
+            ..Default::default()
+        });
+
+        let result = relayer
+            .send_unlock_funds_transaction(&test_withdrawal())
+            .await
+            .unwrap();
+
+        assert_eq!(result, Some(receipt));
+        let sent = relayer.provider.sent.lock().unwrap().clone();
+        assert_eq!(sent.len(), 1);
+        assert_eq!(sent[0].gas, Some(U256::from(25_200)));
+        assert_eq!(sent[0].gas_price, Some(U256::from(WEI_PER_GWEI)));
+        assert_eq!(sent[0].nonce, Some(U256::from(7)));
+        assert_eq!(sent[0].data[..4], unlock_funds_with_proofCall::SELECTOR[..]);
+    }
+
+    #[tokio::test]
+    async fn test_reverted_receipt_fails_send() {
+        let relayer = test_relayer(MockEthereumProvider {
+            receipt: Some(parse_receipt(
+                r#"{"status":"0x0","transactionHash":"0xabc","gasUsed":"0x5208"}"#,
+            )),
+            ..Default::default()
+        });
+
+        let result = relayer
+            .send_unlock_funds_transaction(&test_withdrawal())
+            .await;
+
+        assert!(matches!(result, Err(RelayerError::TransactionFailed(_))));
+    }
+
+    #[tokio::test]
+    async fn test_dry_run_simulates_instead_of_sending() {
+        let mut relayer = test_relayer(MockEthereumProvider::default());
+        relayer.config.dry_run = true;
+
+        let result = relayer
+            .send_unlock_funds_transaction(&test_withdrawal())
+            .await
+            .unwrap();
+
+        assert_eq!(result, None);
+        let calls = relayer.provider.calls();
+        assert!(calls.contains(&"eth_call"));
+        assert!(!calls.contains(&"eth_sendTransaction"));
+    }
+
+    #[tokio::test]
+    async fn test_estimate_gas_limit_uses_padded_estimate() {
+        let relayer = test_relayer(MockEthereumProvider::default());
+        let from: Address = "0x00000000000000000000000000000000000000aa"
+            .parse()
+            .unwrap();
+
+        let gas_limit = relayer
+            .estimate_gas_limit(from, &Bytes::from_static(&[0xde, 0xad]))
+            .await;
+
+        assert_eq!(gas_limit, 25_200);
+    }
+
+    #[tokio::test]
+    async fn test_estimate_gas_limit_falls_back_to_configured_limit() {
+        let relayer = test_relayer(MockEthereumProvider {
+            gas_estimate: None,
+            ..Default::default()
+        });
+        let from: Address = "0x00000000000000000000000000000000000000bb"
+            .parse()
+            .unwrap();
+
+        let gas_limit = relayer
+            .estimate_gas_limit(from, &Bytes::from_static(&[0xde, 0xad]))
+            .await;
+
+        assert_eq!(gas_limit, 300_000);
+    }
+}
@@ -1,4 +1,7 @@
+pub mod calldata;
 pub mod client;
 pub mod ethereum_relayer;
 pub mod proof_submission;
+pub mod provider_pool;
+pub mod round_robin_provider;
 pub mod starknet_relayer;
@@ -1,19 +1,37 @@
-use crate::config::AppConfig;
+use crate::config::{AppConfig, StarknetConfig, WebhookConfig};
+use crate::db::database::{
+    insert_proof_job_tx_hash, link_deposits_to_proof_job, schedule_deposit_finalization,
+};
+use crate::http::webhook::{WebhookEvent, WebhookNotifier};
+use crate::relayer::calldata::read_calldata_file;
+use crate::workers::finalization::ethereum_block_number;
+use alloy_rpc_client::{ClientBuilder, RpcClient};
+use chrono::{DateTime, Utc};
+use futures_util::future::join_all;
+use serde::{Deserialize, Serialize};
 use serde_json::Value;
+use sha2::{Digest, Sha256};
 use sqlx::{Pool, Postgres};
-use starknet::accounts::{Account, ConnectedAccount, ExecutionEncoding, SingleOwnerAccount};
+use starknet::accounts::{
+    Account, AccountError, ConnectedAccount, ExecutionEncoding, SingleOwnerAccount,
+};
 use starknet::core::chain_id::MAINNET;
-use starknet::core::types::{Call, ExecutionResult, Felt, TransactionReceipt};
+use starknet::core::types::{
+    BlockId, BlockTag, Call, ExecutionResult, FeeEstimate, Felt, FunctionCall, TransactionReceipt,
+};
 use starknet::providers::jsonrpc::{HttpTransport, JsonRpcClient};
 use starknet::providers::{Provider, ProviderError};
 use starknet::signers::{LocalWallet, SigningKey};
 use std::collections::HashMap;
 use std::fs;
+use std::future::Future;
 use std::path::{Path, PathBuf};
-use std::time::Duration;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 use thiserror::Error;
-use tokio::time::sleep;
-use tracing::{debug, error, info, warn};
+use tokio::sync::{Mutex, MutexGuard, Semaphore};
+use tokio::time::{sleep, timeout};
+use tracing::{debug, error, info, info_span, warn, Instrument};
 use url::Url;
 
 #[derive(Error, Debug)]
@@ -53,17 +71,98 @@ pub enum ProofSubmissionError {
 
     #[error("Invalid calldata format: {0}")]
     InvalidCalldataFormat(String),
+
+    #[error(
+        "Invalid calldata value '{value}' in {file:?} at line {line}, column {column}: {cause}"
+    )]
+    CalldataParseError {
+        file: PathBuf,
+        /// 1-based line of the file
+        line: usize,
+        /// 1-based position of the value within its line
+        column: usize,
+        value: String,
+        cause: String,
+    },
+
+    #[error("Proof submission task failed: {0}")]
+    TaskFailed(String),
+
+    #[error("Dry run call reverted: {0}")]
+    DryRunReverted(String),
+
+    #[error(
+        "Proof job {job_id} already exists with status '{status}'; use --resume to continue it"
+    )]
+    ProofJobAlreadyExists { job_id: u64, status: String },
+
+    #[error("Invalid proof job stage transition from '{from}' to '{to}'")]
+    InvalidStageTransition { from: String, to: String },
+
+    #[error("Ethereum RPC error: {0}")]
+    EthereumRpc(String),
+
+    #[error("Fact registry address is not configured")]
+    FactRegistryNotConfigured,
+
+    #[error("Invalid proof job handoff: {0}")]
+    InvalidHandoff(String),
+
+    #[error("Fee estimation failed: {0}")]
+    FeeEstimationFailed(String),
+
+    #[error("Estimated fee {estimated} exceeds the maximum of {max} per step")]
+    FeeTooHigh { estimated: u128, max: u128 },
+
+    #[error("Invalid {field}: {reason}")]
+    InvalidField { field: String, reason: String },
+
+    #[error("Proof job {job_id} is '{status}', only suspended jobs can be resumed")]
+    ProofJobNotSuspended { job_id: u64, status: String },
+
+    #[error("Proof job {job_id} has corrupted tx_hashes: {details}")]
+    TxHashesCorrupted { job_id: u64, details: String },
+
+    #[error("Next submission would take the job past its fee budget of {limit} ({spent} spent)")]
+    BudgetExceeded { spent: u128, limit: u128 },
+
+    #[error(
+        "Calldata changed since the proof job was created: hash {current_hash}, expected {stored_hash}"
+    )]
+    CalldataModified {
+        stored_hash: String,
+        current_hash: String,
+    },
 }
 
 #[derive(Debug, Clone)]
 pub struct ProofSubmissionConfig {
     pub contract_address: String,
     pub rpc_url: String,
-    pub account_address: String,
-    pub private_key: String,
+    /// Starknet settings the proofs are submitted with; the account is its `account_address`
+    /// and `private_key`
+    pub starknet: StarknetConfig,
     pub max_retries: u32,
     pub retry_delay_ms: u64,
     pub transaction_timeout_ms: u64,
+    /// Longest a single receipt poll may take before it is abandoned and retried
+    pub receipt_poll_timeout_ms: u64,
+    pub calldata_base_dir: PathBuf,
+    pub max_concurrent_jobs: usize,
+    /// Used to look up the block a completed job's challenge window starts from
+    pub ethereum_rpc_url: String,
+    /// Blocks deposits wait after their proof job completes before they can be claimed
+    pub challenge_window_blocks: u64,
+    /// Simulate each call with a view call instead of sending transactions; the database is not touched
+    pub dry_run: bool,
+    /// `FactRegistry` contract queried to confirm a job's fact was registered
+    pub fact_registry_address: Option<String>,
+    /// Abort a job whose next contract call is estimated to cost more than this
+    pub max_fee_per_step: Option<u128>,
+    /// Suspend a job once its remaining calls would cost more than this in total
+    pub max_total_fee: Option<u128>,
+    /// Notified when a job completes or fails
+    pub webhook: Option<WebhookConfig>,
 }
 
 impl From<AppConfig> for ProofSubmissionConfig {
@@ -71,16 +170,30 @@ impl From<AppConfig> for ProofSubmissionConfig {
         Self {
             contract_address: config.starknet.contract_address.clone(),
             rpc_url: config.starknet.get_rpc_url(),
-            account_address: config.starknet.account_address.clone(),
-            private_key: config.starknet.private_key.clone(),
-            max_retries: config.starknet.max_retries.unwrap_or(5),
-            retry_delay_ms: config.starknet.retry_delay_ms.unwrap_or(5000),
-            transaction_timeout_ms: config.starknet.transaction_timeout_ms.unwrap_or(300000),
+            max_retries: config.starknet.max_retries(),
+            retry_delay_ms: config.starknet.retry_delay_ms(),
+            transaction_timeout_ms: config.starknet.transaction_timeout_ms(),
+            receipt_poll_timeout_ms: config.starknet.receipt_poll_timeout_ms.unwrap_or(30000),
+            calldata_base_dir: config
+                .proof
+                .calldata_base_dir
+                .as_ref()
+                .map(PathBuf::from)
+                .unwrap_or_else(|| std::env::current_dir().unwrap_or_else(|_| PathBuf::from("."))),
+            max_concurrent_jobs: config.proof.max_concurrent_jobs(),
+            ethereum_rpc_url: config.ethereum.get_rpc_url(),
+            challenge_window_blocks: config.ethereum.challenge_window_blocks,
+            dry_run: false,
+            fact_registry_address: config.starknet.fact_registry_address.clone(),
+            max_fee_per_step: config.starknet.max_fee_per_step,
+            max_total_fee: config.starknet.max_total_fee,
+            webhook: config.webhook,
+            starknet: config.starknet,
         }
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ProofJob {
     pub id: i64,
     pub job_id: i64,
@@ -94,6 +207,524 @@ pub struct ProofJob {
     pub retry_count: i32,
     pub error_message: Option<String>,
     pub tx_hashes: Value,
+    /// When the job entered `current_stage`
+    pub stage_started_at: Option<DateTime<Utc>>,
+    /// Fact the final proof registers, verified on-chain before the job is completed
+    pub fact_hash: Option<String>,
+    /// SHA-256 of the calldata directory when the job was created, see [`compute_calldata_hash`]
+    pub calldata_hash: Option<String>,
+    /// Free-form JSON object of extra per-job context, e.g. the deposits covered or operator
+    /// notes; `PATCH /proof-jobs/{job_id}/metadata` merges into it
+    pub metadata: Value,
+}
+
+impl ProofJob {
+    /// Contract calls already made for this job, out of `total_steps`
+    fn submitted_steps(&self, total_steps: u32) -> Option<u32> {
+        match ResumePoint::from_stage(self.current_stage.as_deref()) {
+            ResumePoint::Initial => Some(0),
+            ResumePoint::Step(next_step) => Some(next_step.min(total_steps)),
+            ResumePoint::MarkCompleted | ResumePoint::Completed => Some(total_steps),
+            ResumePoint::RetryFailed => None,
+        }
+    }
+
+    /// Seconds until the job is expected to finish, given the total number of contract calls it
+    /// needs and the historical `average_step_seconds`.
+    ///
+    /// Time already spent on the in-flight call counts against the estimate. Failed jobs have
+    /// no ETA.
+    pub fn estimate_completion_time(
+        &self,
+        total_steps: u32,
+        average_step_seconds: f64,
+        now: DateTime<Utc>,
+    ) -> Option<u64> {
+        match self.status.as_str() {
+            "completed" => return Some(0),
+            "queued" | "processing" => {}
+            _ => return None,
+        }
+
+        let remaining = total_steps.saturating_sub(self.submitted_steps(total_steps)?);
+        if remaining == 0 {
+            return Some(0);
+        }
+
+        let elapsed_in_stage = self
+            .stage_started_at
+            .map(|started| (now - started).num_milliseconds().max(0) as f64 / 1000.0)
+            .unwrap_or(0.0)
+            // The current call can't be further along than a full step
+            .min(average_step_seconds);
+
+        let remaining_seconds = remaining as f64 * average_step_seconds - elapsed_in_stage;
+        Some(remaining_seconds.max(0.0).round() as u64)
+    }
+
+    /// Self-contained JSON of every field, for handing the job to another sequencer instance
+    /// with [`ProofJob::from_handoff_json`]
+    pub fn to_handoff_json(&self) -> Value {
+        serde_json::json!({
+            "handoff_version": PROOF_JOB_HANDOFF_VERSION,
+            "id": self.id,
+            "job_id": self.job_id,
+            "calldata_dir": self.calldata_dir,
+            "layout": self.layout,
+            "hasher": self.hasher,
+            "stone_version": self.stone_version,
+            "memory_verification": self.memory_verification,
+            "status": self.status,
+            "current_stage": self.current_stage,
+            "retry_count": self.retry_count,
+            "error_message": self.error_message,
+            "tx_hashes": self.tx_hashes,
+            "stage_started_at": self.stage_started_at,
+            "fact_hash": self.fact_hash,
+            "calldata_hash": self.calldata_hash,
+            "metadata": self.metadata,
+        })
+    }
+
+    /// Reads a job written by [`ProofJob::to_handoff_json`].
+    ///
+    /// Every field must be present (optional ones as `null`), no unknown fields are allowed,
+    /// and the status and stage must be ones this instance knows how to resume from.
+    pub fn from_handoff_json(value: &Value) -> Result<Self, ProofSubmissionError> {
+        let handoff = ProofJobHandoff::deserialize(value)
+            .map_err(|e| ProofSubmissionError::InvalidHandoff(e.to_string()))?;
+
+        if handoff.handoff_version != PROOF_JOB_HANDOFF_VERSION {
+            return Err(ProofSubmissionError::InvalidHandoff(format!(
+                "unsupported handoff_version {}, expected {}",
+                handoff.handoff_version, PROOF_JOB_HANDOFF_VERSION
+            )));
+        }
+        if !PROOF_JOB_STATUSES.contains(&handoff.status.as_str()) {
+            return Err(ProofSubmissionError::InvalidHandoff(format!(
+                "unknown status '{}'",
+                handoff.status
+            )));
+        }
+        if let Some(stage) = &handoff.current_stage {
+            if ProofJobStage::parse(stage).is_none() {
+                return Err(ProofSubmissionError::InvalidHandoff(format!(
+                    "unknown stage '{}'",
+                    stage
+                )));
+            }
+        }
+        if !handoff.tx_hashes.is_object() {
+            return Err(ProofSubmissionError::InvalidHandoff(
+                "tx_hashes must be an object".to_string(),
+            ));
+        }
+        if !handoff.metadata.is_object() {
+            return Err(ProofSubmissionError::InvalidHandoff(
+                "metadata must be an object".to_string(),
+            ));
+        }
+
+        Ok(ProofJob {
+            id: handoff.id,
+            job_id: handoff.job_id,
+            calldata_dir: handoff.calldata_dir,
+            layout: handoff.layout,
+            hasher: handoff.hasher,
+            stone_version: handoff.stone_version,
+            memory_verification: handoff.memory_verification,
+            status: handoff.status,
+            current_stage: handoff.current_stage,
+            retry_count: handoff.retry_count,
+            error_message: handoff.error_message,
+            tx_hashes: handoff.tx_hashes,
+            stage_started_at: handoff.stage_started_at,
+            fact_hash: handoff.fact_hash,
+            calldata_hash: handoff.calldata_hash,
+            metadata: handoff.metadata,
+        })
+    }
+}
+
+/// Bumped whenever the handoff layout changes, so older instances refuse newer jobs
+pub const PROOF_JOB_HANDOFF_VERSION: u32 = 3;
+
+/// Values of `proof_jobs.status`; `suspended` jobs ran out of fee budget and wait to be resumed
+pub const PROOF_JOB_STATUSES: [&str; 5] =
+    ["queued", "processing", "completed", "failed", "suspended"];
+
+/// Wire form of [`ProofJob::to_handoff_json`]. `Option::deserialize` makes the nullable fields
+/// required instead of defaulting to `None` when missing.
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ProofJobHandoff {
+    handoff_version: u32,
+    id: i64,
+    job_id: i64,
+    calldata_dir: String,
+    layout: String,
+    hasher: String,
+    stone_version: String,
+    memory_verification: String,
+    status: String,
+    #[serde(deserialize_with = "Option::deserialize")]
+    current_stage: Option<String>,
+    retry_count: i32,
+    #[serde(deserialize_with = "Option::deserialize")]
+    error_message: Option<String>,
+    tx_hashes: Value,
+    #[serde(deserialize_with = "Option::deserialize")]
+    stage_started_at: Option<DateTime<Utc>>,
+    #[serde(deserialize_with = "Option::deserialize")]
+    fact_hash: Option<String>,
+    #[serde(deserialize_with = "Option::deserialize")]
+    calldata_hash: Option<String>,
+    metadata: Value,
+}
+
+/// Number of contract calls a calldata directory needs: initial, each consecutive `step<n>`
+/// file and final
+pub fn count_proof_steps(calldata_dir: &Path) -> u32 {
+    let step_files = (1..)
+        .take_while(|step| calldata_dir.join(format!("step{}", step)).exists())
+        .count() as u32;
+    step_files + 2
+}
+
+/// SHA-256 of a calldata directory, as hex: the bytes of `initial`, each consecutive
+/// `step<n>` file and `final`, concatenated in submission order
+pub fn compute_calldata_hash(dir: &Path) -> Result<String, ProofSubmissionError> {
+    let mut hasher = Sha256::new();
+    let steps = (1..).map(|step| format!("step{}", step));
+    let stages = std::iter::once("initial".to_string())
+        .chain(steps.take_while(|step| dir.join(step).exists()))
+        .chain(std::iter::once("final".to_string()));
+
+    for stage in stages {
+        let bytes = fs::read(dir.join(&stage)).map_err(|e| match e.kind() {
+            std::io::ErrorKind::NotFound => ProofSubmissionError::CalldataFileMissing(stage),
+            _ => ProofSubmissionError::Io(e),
+        })?;
+        hasher.update(&bytes);
+    }
+
+    Ok(hex::encode(hasher.finalize()))
+}
+
+/// Whether `stage` names a calldata file: `initial`, `step<n>` or `final`
+pub fn is_calldata_stage(stage: &str) -> bool {
+    match stage {
+        "initial" | "final" => true,
+        stage => stage
+            .strip_prefix("step")
+            .is_some_and(|n| !n.is_empty() && n.bytes().all(|b| b.is_ascii_digit())),
+    }
+}
+
+/// Where a proof job picks up, based on the last stage recorded for it
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ResumePoint {
+    /// Submit everything, starting with the initial proof
+    Initial,
+    /// Submit step proofs starting at this step number, then the final proof
+    Step(u32),
+    /// All proofs are on-chain; only the completion bookkeeping is left
+    MarkCompleted,
+    /// Nothing left to do
+    Completed,
+    /// The previous attempt failed; start over from the initial proof
+    RetryFailed,
+}
+
+impl ResumePoint {
+    pub fn from_stage(current_stage: Option<&str>) -> Self {
+        match current_stage {
+            None | Some("processing") => ResumePoint::Initial,
+            Some("initial_submitted") => ResumePoint::Step(1),
+            Some(stage) if stage.starts_with("step") => {
+                let step_num: u32 = stage
+                    .strip_prefix("step")
+                    .and_then(|s| s.strip_suffix("_submitted"))
+                    .and_then(|s| s.parse().ok())
+                    .unwrap_or(0);
+                ResumePoint::Step(step_num + 1)
+            }
+            Some("final_submitted") => ResumePoint::MarkCompleted,
+            Some("completed") => ResumePoint::Completed,
+            Some("failed") => ResumePoint::RetryFailed,
+            Some(stage) => {
+                warn!("Unknown stage: {:?}, restarting from beginning", stage);
+                ResumePoint::Initial
+            }
+        }
+    }
+}
+
+/// Stage recorded in `proof_jobs.current_stage`; step stages carry their step number only in
+/// the stored string (`step<n>_submitted`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProofJobStage {
+    Processing,
+    InitialSubmitted,
+    StepSubmitted,
+    FinalSubmitted,
+    Completed,
+    Failed,
+}
+
+impl ProofJobStage {
+    pub fn parse(stage: &str) -> Option<Self> {
+        match stage {
+            "processing" => Some(ProofJobStage::Processing),
+            "initial_submitted" => Some(ProofJobStage::InitialSubmitted),
+            "final_submitted" => Some(ProofJobStage::FinalSubmitted),
+            "completed" => Some(ProofJobStage::Completed),
+            "failed" => Some(ProofJobStage::Failed),
+            stage => stage
+                .strip_prefix("step")
+                .and_then(|s| s.strip_suffix("_submitted"))
+                .and_then(|s| s.parse::<u32>().ok())
+                .map(|_| ProofJobStage::StepSubmitted),
+        }
+    }
+}
+
+/// Directed edges a proof job may take between stages
+pub const VALID_TRANSITIONS: &[(ProofJobStage, ProofJobStage)] = &[
+    (ProofJobStage::Processing, ProofJobStage::InitialSubmitted),
+    (
+        ProofJobStage::InitialSubmitted,
+        ProofJobStage::StepSubmitted,
+    ),
+    // Proofs without step files go straight to the final submission
+    (
+        ProofJobStage::InitialSubmitted,
+        ProofJobStage::FinalSubmitted,
+    ),
+    (ProofJobStage::StepSubmitted, ProofJobStage::StepSubmitted),
+    (ProofJobStage::StepSubmitted, ProofJobStage::FinalSubmitted),
+    (ProofJobStage::FinalSubmitted, ProofJobStage::Completed),
+    (ProofJobStage::Processing, ProofJobStage::Failed),
+    (ProofJobStage::InitialSubmitted, ProofJobStage::Failed),
+    (ProofJobStage::StepSubmitted, ProofJobStage::Failed),
+    (ProofJobStage::FinalSubmitted, ProofJobStage::Failed),
+    // A failed job is retried from the beginning
+    (ProofJobStage::Failed, ProofJobStage::Processing),
+];
+
+/// Check that a job may move from `current_stage` to `new_stage`.
+///
+/// A job without a recorded stage has just been created and is treated as `processing`.
+pub fn validate_transition(
+    current_stage: Option<&str>,
+    new_stage: &str,
+) -> Result<(), ProofSubmissionError> {
+    let from = current_stage.unwrap_or("processing");
+    let allowed = match (ProofJobStage::parse(from), ProofJobStage::parse(new_stage)) {
+        (Some(from), Some(to)) => VALID_TRANSITIONS.contains(&(from, to)),
+        _ => false,
+    };
+
+    if allowed {
+        Ok(())
+    } else {
+        Err(ProofSubmissionError::InvalidStageTransition {
+            from: from.to_string(),
+            to: new_stage.to_string(),
+        })
+    }
+}
+
+/// Reads a proof job's `tx_hashes`, which must map stage names to transaction hashes
+pub fn parse_tx_hashes(
+    job_id: u64,
+    tx_hashes: &Value,
+) -> Result<HashMap<String, String>, ProofSubmissionError> {
+    serde_json::from_value(tx_hashes.clone()).map_err(|e| ProofSubmissionError::TxHashesCorrupted {
+        job_id,
+        details: e.to_string(),
+    })
+}
+
+/// Resolve a calldata directory against `base` and ensure it stays inside it.
+///
+/// Relative paths are joined onto `base`; absolute paths are accepted as-is. In both cases
+/// the canonical path must live under `base` and be a readable directory.
+pub fn validate_calldata_path(base: &Path, input: &Path) -> Result<PathBuf, ProofSubmissionError> {
+    let not_found = || ProofSubmissionError::CalldataDirNotFound(input.display().to_string());
+
+    let base = base.canonicalize().map_err(|_| not_found())?;
+    let candidate = if input.is_absolute() {
+        input.to_path_buf()
+    } else {
+        base.join(input)
+    };
+    let resolved = candidate.canonicalize().map_err(|_| not_found())?;
+
+    if !resolved.starts_with(&base) {
+        warn!(
+            "Rejected calldata directory {:?}: outside of base {:?}",
+            input, base
+        );
+        return Err(not_found());
+    }
+
+    if !resolved.is_dir() || fs::read_dir(&resolved).is_err() {
+        return Err(not_found());
+    }
+
+    Ok(resolved)
+}
+
+/// Estimated cost of one contract call a proof job has left to make
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StepFeeEstimate {
+    /// `tx_hashes` key the call's transaction will be recorded under, e.g. `step3`
+    pub stage: String,
+    pub function_name: String,
+    /// In the smallest unit of the fee token
+    pub overall_fee: u128,
+    /// Whether submitting the call would be refused for exceeding `max_fee_per_step`
+    pub exceeds_max: bool,
+}
+
+/// `0x` plus up to 64 hex digits, the most a felt252 can take
+const MAX_FELT_HEX_LEN: usize = 66;
+
+/// Checks that `input` is a `0x`-prefixed hex string short enough to hold a felt; the value is
+/// left out of the error so a malformed private key is not logged.
+///
+/// Starknet addresses carry no checksum, so this is as far as they can be validated offline.
+pub fn validate_hex_felt(input: &str, field_name: &str) -> Result<(), ProofSubmissionError> {
+    let invalid = |reason: String| ProofSubmissionError::InvalidField {
+        field: field_name.to_string(),
+        reason,
+    };
+
+    let digits = input
+        .strip_prefix("0x")
+        .ok_or_else(|| invalid("must start with 0x".to_string()))?;
+    if digits.is_empty() {
+        return Err(invalid("has no hex digits after 0x".to_string()));
+    }
+    if let Some(position) = digits.find(|c: char| !c.is_ascii_hexdigit()) {
+        return Err(invalid(format!(
+            "contains a non-hex character at position {}",
+            position + 2
+        )));
+    }
+    if input.len() > MAX_FELT_HEX_LEN {
+        return Err(invalid(format!(
+            "is {} characters long, at most {} fit in a felt",
+            input.len(),
+            MAX_FELT_HEX_LEN
+        )));
+    }
+
+    Ok(())
+}
+
+/// `FeeTooHigh` when `estimated` is above `max`; no `max` allows any fee
+pub fn check_step_fee(estimated: u128, max: Option<u128>) -> Result<(), ProofSubmissionError> {
+    match max {
+        Some(max) if estimated > max => Err(ProofSubmissionError::FeeTooHigh { estimated, max }),
+        _ => Ok(()),
+    }
+}
+
+/// Overall fee of `estimate`; one too large for a `u128` is above any limit anyway
+fn overall_fee(estimate: &FeeEstimate) -> u128 {
+    u128::try_from(estimate.overall_fee).unwrap_or(u128::MAX)
+}
+
+/// How long a fetched account nonce is trusted before it is read from the node again
+pub const NONCE_CACHE_TTL: Duration = Duration::from_secs(5);
+
+/// Account nonce last read from the node, and when it was read
+#[derive(Debug, Clone, Copy)]
+pub struct CachedNonce {
+    pub value: Felt,
+    pub fetched_at: Instant,
+}
+
+/// Account nonce cache, so back-to-back submissions skip the nonce lookup `execute_v3` would
+/// otherwise make for every transaction.
+#[derive(Debug, Clone)]
+pub struct NonceCache {
+    cached: Arc<Mutex<Option<CachedNonce>>>,
+    ttl: Duration,
+}
+
+impl Default for NonceCache {
+    fn default() -> Self {
+        Self::new(NONCE_CACHE_TTL)
+    }
+}
+
+impl NonceCache {
+    pub fn new(ttl: Duration) -> Self {
+        Self {
+            cached: Arc::new(Mutex::new(None)),
+            ttl,
+        }
+    }
+
+    /// Hand out the next nonce, calling `fetch` only when the cache is empty or stale.
+    ///
+    /// The cache is advanced before returning, so concurrent submissions never share a nonce.
+    /// Further reservations wait until the returned one is dropped, so the account sends one
+    /// transaction at a time and no later nonce is in flight when a send fails.
+    pub async fn reserve<F, Fut, E>(&self, fetch: F) -> Result<NonceReservation<'_>, E>
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = Result<Felt, E>>,
+    {
+        let mut cached = self.cached.lock().await;
+
+        let (nonce, fetched_at) = match *cached {
+            Some(entry) if entry.fetched_at.elapsed() < self.ttl => (entry.value, entry.fetched_at),
+            _ => (fetch().await?, Instant::now()),
+        };
+
+        *cached = Some(CachedNonce {
+            value: nonce + Felt::ONE,
+            fetched_at,
+        });
+        Ok(NonceReservation { cached, nonce })
+    }
+
+    /// Forget the cached nonce, so the next reservation reads it from the node
+    pub async fn invalidate(&self) {
+        *self.cached.lock().await = None;
+    }
+}
+
+/// A nonce handed out by [`NonceCache::reserve`], held while its transaction is sent
+pub struct NonceReservation<'a> {
+    cached: MutexGuard<'a, Option<CachedNonce>>,
+    nonce: Felt,
+}
+
+impl NonceReservation<'_> {
+    pub fn nonce(&self) -> Felt {
+        self.nonce
+    }
+
+    /// Forget the cached nonce after a failed send, so the next reservation reads it from the
+    /// node. Sends are serialized, so this reservation is the latest and no later nonce is lost.
+    pub fn invalidate(mut self) {
+        *self.cached = None;
+    }
+}
+
+/// Await `future`, failing with `TransactionTimeout` if it has not finished after `duration`
+pub async fn with_timeout<F: Future>(
+    duration: Duration,
+    future: F,
+) -> Result<F::Output, ProofSubmissionError> {
+    timeout(duration, future)
+        .await
+        .map_err(|_| ProofSubmissionError::TransactionTimeout)
 }
 
 /// Main struct for handling proof submission to Starknet
@@ -101,6 +732,9 @@ pub struct ProofSubmissionRelayer {
     db_pool: Pool<Postgres>,
     config: ProofSubmissionConfig,
     account: SingleOwnerAccount<JsonRpcClient<HttpTransport>, LocalWallet>,
+    nonce_cache: NonceCache,
+    ethereum_client: RpcClient,
+    webhook: Option<Arc<WebhookNotifier>>,
 }
 
 impl ProofSubmissionRelayer {
@@ -109,23 +743,64 @@ impl ProofSubmissionRelayer {
         db_pool: Pool<Postgres>,
         config: ProofSubmissionConfig,
     ) -> Result<Self, ProofSubmissionError> {
+        validate_hex_felt(&config.contract_address, "contract_address")?;
+        validate_hex_felt(&config.starknet.account_address, "account_address")?;
+        validate_hex_felt(config.starknet.private_key.expose(), "private_key")?;
+        // 64 hex digits can still be above the field prime
+        let parse_felt = |input: &str, field: &str| {
+            Felt::from_hex(input).map_err(|_| ProofSubmissionError::InvalidField {
+                field: field.to_string(),
+                reason: "is not a valid felt".to_string(),
+            })
+        };
+
         let provider = JsonRpcClient::new(HttpTransport::new(Url::parse(&config.rpc_url).unwrap()));
-        let signer: LocalWallet = LocalWallet::from(SigningKey::from_secret_scalar(
-            Felt::from_hex(&config.private_key).unwrap(),
-        ));
+        let signer: LocalWallet = LocalWallet::from(SigningKey::from_secret_scalar(parse_felt(
+            config.starknet.private_key.expose(),
+            "private_key",
+        )?));
         let chain_id = MAINNET;
-        let address = Felt::from_hex(&config.account_address).unwrap();
+        let address = parse_felt(&config.starknet.account_address, "account_address")?;
         let account =
             SingleOwnerAccount::new(provider, signer, address, chain_id, ExecutionEncoding::New);
 
+        let ethereum_rpc_url = Url::parse(&config.ethereum_rpc_url).map_err(|e| {
+            ProofSubmissionError::EthereumRpc(format!("Invalid Ethereum RPC URL: {}", e))
+        })?;
+        let ethereum_client = ClientBuilder::default().http(ethereum_rpc_url);
+        let webhook = config
+            .webhook
+            .clone()
+            .map(|webhook| Arc::new(WebhookNotifier::new(webhook)));
+
         Ok(Self {
             db_pool,
             config,
             account,
+            nonce_cache: NonceCache::default(),
+            ethereum_client,
+            webhook,
         })
     }
 
+    /// Fee limit each contract call is checked against before it is sent
+    pub fn max_fee_per_step(&self) -> Option<u128> {
+        self.config.max_fee_per_step
+    }
+
     /// Main entry point for submitting proofs from a calldata directory
+    ///
+    /// An existing job is only picked up again when `resume` is set (or it previously failed);
+    /// it then continues from the first stage that has not been submitted yet.
+    ///
+    /// `deposit_ids` are linked to the job and moved to `PROOF_SUBMITTED` when it completes.
+    ///
+    /// When `fact_hash` is given, the job only completes once the fact registry reports it as
+    /// valid.
+    ///
+    /// Everything logged while the job runs is recorded inside a `proof_submission` span
+    /// carrying its `job_id`.
+    #[allow(clippy::too_many_arguments)]
     pub async fn submit_proof_from_calldata(
         &self,
         calldata_dir: PathBuf,
@@ -134,78 +809,157 @@ impl ProofSubmissionRelayer {
         hasher: String,
         stone_version: String,
         memory_verification: String,
+        resume: bool,
+        deposit_ids: Vec<i32>,
+        fact_hash: Option<String>,
+    ) -> Result<(), ProofSubmissionError> {
+        let span = info_span!("proof_submission", job_id = %job_id);
+        self.run_proof_submission(
+            calldata_dir,
+            job_id,
+            layout,
+            hasher,
+            stone_version,
+            memory_verification,
+            resume,
+            deposit_ids,
+            fact_hash,
+        )
+        .instrument(span)
+        .await
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    async fn run_proof_submission(
+        &self,
+        calldata_dir: PathBuf,
+        job_id: u64,
+        layout: String,
+        hasher: String,
+        stone_version: String,
+        memory_verification: String,
+        resume: bool,
+        deposit_ids: Vec<i32>,
+        fact_hash: Option<String>,
     ) -> Result<(), ProofSubmissionError> {
         info!(
             "Starting proof submission for job_id: {}, calldata_dir: {:?}",
             job_id, calldata_dir
         );
 
-        // Validate calldata directory exists
-        if !calldata_dir.exists() {
-            return Err(ProofSubmissionError::CalldataDirNotFound(
-                calldata_dir.display().to_string(),
-            ));
+        // Resolve the calldata directory against the configured base and make sure it is readable
+        let calldata_dir = validate_calldata_path(&self.config.calldata_base_dir, &calldata_dir)?;
+        // Catch a malformed fact before any proof is sent
+        if let Some(fact_hash) = &fact_hash {
+            Felt::from_hex(fact_hash)?;
         }
 
-        // Create or get existing proof job
-        let mut proof_job = self
-            .create_or_get_proof_job(
-                job_id,
-                &calldata_dir,
-                &layout,
-                &hasher,
-                &stone_version,
-                &memory_verification,
-            )
-            .await?;
+        // Dry runs use an in-memory job so nothing is persisted
+        if self.config.dry_run {
+            let mut proof_job = ProofJob {
+                id: 0,
+                job_id: job_id as i64,
+                calldata_dir: calldata_dir.display().to_string(),
+                layout,
+                hasher,
+                stone_version,
+                memory_verification,
+                status: "dry_run".to_string(),
+                current_stage: None,
+                retry_count: 0,
+                error_message: None,
+                tx_hashes: serde_json::json!({}),
+                stage_started_at: None,
+                fact_hash,
+                calldata_hash: None,
+                metadata: serde_json::json!({}),
+            };
+            self.execute_full_proof_flow(&mut proof_job).await?;
+            info!("Dry run succeeded for job_id: {}", job_id);
+            return Ok(());
+        }
+
+        let mut proof_job = match self.get_proof_job_by_job_id(job_id).await {
+            Ok(existing) => {
+                if !resume && existing.status != "failed" {
+                    return Err(ProofSubmissionError::ProofJobAlreadyExists {
+                        job_id,
+                        status: existing.status,
+                    });
+                }
+                self.verify_calldata_unchanged(&existing).await?;
+                info!(
+                    "Resuming proof job {} from stage: {}",
+                    job_id,
+                    existing.current_stage.as_deref().unwrap_or("start")
+                );
+                existing
+            }
+            Err(_) => {
+                self.create_or_get_proof_job(
+                    job_id,
+                    &calldata_dir,
+                    &layout,
+                    &hasher,
+                    &stone_version,
+                    &memory_verification,
+                    deposit_ids,
+                    fact_hash.as_deref(),
+                )
+                .await?
+            }
+        };
+        // A fact given when resuming replaces the one the job was created with
+        if fact_hash.is_some() {
+            proof_job.fact_hash = fact_hash;
+        }
 
         info!(
             "Processing proof job {} (DB ID: {}), current status: {}",
             proof_job.job_id, proof_job.id, proof_job.status
         );
 
-        // Resume from current stage if interrupted
-        match proof_job.current_stage.as_deref() {
-            None | Some("processing") => {
+        let max_total_fee = self
+            .resumed_fee_budget(&proof_job)
+            .await?
+            .or(self.config.max_total_fee);
+        if let Some(max_total_fee) = max_total_fee {
+            self.submit_with_fee_budget(&mut proof_job, max_total_fee)
+                .await?;
+            info!(
+                "Proof submission completed successfully for job_id: {}",
+                job_id
+            );
+            return Ok(());
+        }
+
+        match ResumePoint::from_stage(proof_job.current_stage.as_deref()) {
+            ResumePoint::Initial => {
                 self.execute_full_proof_flow(&mut proof_job).await?;
             }
-            Some("initial_submitted") => {
-                self.submit_step_proofs(&mut proof_job).await?;
-                self.submit_final_proof(&mut proof_job).await?;
-            }
-            Some(stage) if stage.starts_with("step") => {
-                let step_num: u32 = stage
-                    .strip_prefix("step")
-                    .and_then(|s| s.strip_suffix("_submitted"))
-                    .and_then(|s| s.parse().ok())
-                    .unwrap_or(0);
-                self.submit_step_proofs_from(&mut proof_job, step_num + 1)
+            ResumePoint::Step(step_num) => {
+                self.submit_step_proofs_from(&mut proof_job, step_num)
+                    .instrument(info_span!("step_proofs", start_step = step_num))
+                    .await?;
+                self.submit_final_proof(&mut proof_job)
+                    .instrument(info_span!("final_proof"))
                     .await?;
-                self.submit_final_proof(&mut proof_job).await?;
             }
-            Some("final_submitted") => {
+            ResumePoint::MarkCompleted => {
                 info!("All proofs already submitted, marking as completed");
                 self.mark_proof_job_completed(&mut proof_job).await?;
             }
-            Some("completed") => {
+            ResumePoint::Completed => {
                 info!("Proof job already completed");
                 return Ok(());
             }
-            Some("failed") => {
+            ResumePoint::RetryFailed => {
                 warn!("Proof job previously failed, retrying from beginning");
-                proof_job.current_stage = Some("processing".to_string());
                 proof_job.retry_count += 1;
                 self.update_proof_job_stage(&mut proof_job, "processing")
                     .await?;
                 self.execute_full_proof_flow(&mut proof_job).await?;
             }
-            _ => {
-                warn!(
-                    "Unknown stage: {:?}, restarting from beginning",
-                    proof_job.current_stage
-                );
-                self.execute_full_proof_flow(&mut proof_job).await?;
-            }
         }
 
         info!(
@@ -215,14 +969,142 @@ impl ProofSubmissionRelayer {
         Ok(())
     }
 
+    /// Reset proof jobs left in `processing` by a crashed run so they can be picked up again
+    pub async fn recover_stale_jobs(
+        &self,
+        max_age_minutes: i64,
+    ) -> Result<u64, ProofSubmissionError> {
+        let recovered = sqlx::query!(
+            r#"
+            UPDATE proof_jobs
+            SET status = 'queued', current_stage = NULL, updated_at = NOW()
+            WHERE status = 'processing'
+            AND updated_at < NOW() - $1::BIGINT * INTERVAL '1 minute'
+            RETURNING job_id
+            "#,
+            max_age_minutes
+        )
+        .fetch_all(&self.db_pool)
+        .await?;
+
+        for job in &recovered {
+            warn!("Recovered stale proof job {}, reset to queued", job.job_id);
+        }
+
+        Ok(recovered.len() as u64)
+    }
+
+    /// Mark a queued or in-flight job as `failed`, recording why
+    pub async fn mark_proof_job_failed(
+        &self,
+        job_id: u64,
+        error_message: &str,
+    ) -> Result<(), ProofSubmissionError> {
+        if self.config.dry_run {
+            return Ok(());
+        }
+
+        let result = sqlx::query!(
+            r#"
+            UPDATE proof_jobs
+            SET status = 'failed', error_message = $2, updated_at = NOW()
+            WHERE job_id = $1 AND status IN ('queued', 'processing')
+            "#,
+            job_id as i64,
+            error_message
+        )
+        .execute(&self.db_pool)
+        .await?;
+
+        warn!("Marked proof job {} as failed: {}", job_id, error_message);
+        // A job that was already finished did not change state
+        if result.rows_affected() > 0 {
+            self.notify_webhook(WebhookEvent::ProofJobFailed {
+                job_id,
+                error: error_message.to_string(),
+            });
+        }
+        Ok(())
+    }
+
+    /// Submit every `queued` proof job, running at most `max_concurrent_jobs` at once.
+    ///
+    /// A failing job does not stop the others; each failure is returned with its job_id for the
+    /// caller to log. The jobs share one account, whose sends [`NonceCache`] serializes.
+    pub async fn process_all_queued_jobs(
+        self: Arc<Self>,
+    ) -> Result<Vec<(u64, ProofSubmissionError)>, ProofSubmissionError> {
+        let queued = sqlx::query!(
+            r#"
+            SELECT job_id, calldata_dir, layout, hasher, stone_version, memory_verification, fact_hash
+            FROM proof_jobs
+            WHERE status = 'queued'
+            ORDER BY created_at ASC
+            "#
+        )
+        .fetch_all(&self.db_pool)
+        .await?;
+
+        info!(
+            "Processing {} queued proof jobs with up to {} concurrent submissions",
+            queued.len(),
+            self.config.max_concurrent_jobs
+        );
+
+        let semaphore = Arc::new(Semaphore::new(self.config.max_concurrent_jobs.max(1)));
+        let (job_ids, handles): (Vec<u64>, Vec<_>) = queued
+            .into_iter()
+            .map(|job| {
+                let job_id = job.job_id as u64;
+                let relayer = Arc::clone(&self);
+                let semaphore = Arc::clone(&semaphore);
+                let handle = tokio::spawn(async move {
+                    let _permit = semaphore
+                        .acquire_owned()
+                        .await
+                        .map_err(|e| ProofSubmissionError::TaskFailed(e.to_string()))?;
+                    relayer
+                        .submit_proof_from_calldata(
+                            PathBuf::from(job.calldata_dir),
+                            job_id,
+                            job.layout,
+                            job.hasher,
+                            job.stone_version,
+                            job.memory_verification,
+                            true,
+                            Vec::new(),
+                            job.fact_hash,
+                        )
+                        .await
+                });
+                (job_id, handle)
+            })
+            .unzip();
+
+        let mut failures = Vec::new();
+        for (job_id, result) in job_ids.into_iter().zip(join_all(handles).await) {
+            let outcome =
+                result.unwrap_or_else(|e| Err(ProofSubmissionError::TaskFailed(e.to_string())));
+            if let Err(e) = outcome {
+                failures.push((job_id, e));
+            }
+        }
+
+        Ok(failures)
+    }
+
     /// Execute the full proof submission flow (initial -> steps -> final)
     async fn execute_full_proof_flow(
         &self,
         proof_job: &mut ProofJob,
     ) -> Result<(), ProofSubmissionError> {
-        self.submit_initial_proof(proof_job).await?;
+        self.submit_initial_proof(proof_job)
+            .instrument(info_span!("initial_proof"))
+            .await?;
         self.submit_step_proofs(proof_job).await?;
-        self.submit_final_proof(proof_job).await?;
+        self.submit_final_proof(proof_job)
+            .instrument(info_span!("final_proof"))
+            .await?;
         Ok(())
     }
 
@@ -233,8 +1115,111 @@ impl ProofSubmissionRelayer {
     ) -> Result<(), ProofSubmissionError> {
         info!("Submitting initial proof for job_id: {}", proof_job.job_id);
 
-        let calldata_dir = PathBuf::from(&proof_job.calldata_dir);
-        let initial_file = calldata_dir.join("initial");
+        let calldata = self.initial_calldata(proof_job)?;
+        let tx_hash = self
+            .submit_contract_call("verify_proof_initial", calldata, proof_job)
+            .await?;
+
+        self.update_proof_job_stage(proof_job, "initial_submitted")
+            .await?;
+        self.add_tx_hash(proof_job, "initial", &tx_hash.to_string())
+            .await?;
+
+        info!(
+            "Initial proof submitted successfully for job_id: {}, tx_hash: {}",
+            proof_job.job_id, tx_hash
+        );
+
+        Ok(())
+    }
+
+    /// Submit all step proofs
+    async fn submit_step_proofs(
+        &self,
+        proof_job: &mut ProofJob,
+    ) -> Result<(), ProofSubmissionError> {
+        self.submit_step_proofs_from(proof_job, 1)
+            .instrument(info_span!("step_proofs", start_step = 1))
+            .await
+    }
+
+    /// Submit step proofs starting from a specific step number
+    async fn submit_step_proofs_from(
+        &self,
+        proof_job: &mut ProofJob,
+        start_step: u32,
+    ) -> Result<(), ProofSubmissionError> {
+        let mut step_num = start_step;
+
+        loop {
+            let Some(calldata) = self.step_calldata(proof_job, step_num)? else {
+                info!(
+                    "No more step files found after step{}, proceeding to final",
+                    step_num - 1
+                );
+                break;
+            };
+
+            info!(
+                "Submitting step{} proof for job_id: {}",
+                step_num, proof_job.job_id
+            );
+
+            let tx_hash = self
+                .submit_contract_call("verify_proof_step", calldata, proof_job)
+                .await?;
+
+            let stage_name = format!("step{}_submitted", step_num);
+            self.update_proof_job_stage(proof_job, &stage_name).await?;
+            self.add_tx_hash(
+                proof_job,
+                &format!("step{}", step_num),
+                &tx_hash.to_string(),
+            )
+            .await?;
+
+            info!(
+                "Step{} proof submitted successfully for job_id: {}, tx_hash: {}",
+                step_num, proof_job.job_id, tx_hash
+            );
+
+            step_num += 1;
+        }
+
+        Ok(())
+    }
+
+    /// Submit the final proof and register fact
+    async fn submit_final_proof(
+        &self,
+        proof_job: &mut ProofJob,
+    ) -> Result<(), ProofSubmissionError> {
+        info!("Submitting final proof for job_id: {}", proof_job.job_id);
+
+        let calldata = self.final_calldata(proof_job)?;
+        let tx_hash = self
+            .submit_contract_call("verify_proof_final_and_register_fact", calldata, proof_job)
+            .await?;
+
+        self.update_proof_job_stage(proof_job, "final_submitted")
+            .await?;
+        self.add_tx_hash(proof_job, "final", &tx_hash.to_string())
+            .await?;
+
+        info!(
+            "Final proof submitted successfully for job_id: {}, tx_hash: {}",
+            proof_job.job_id, tx_hash
+        );
+
+        // Mark as completed and update deposits
+        self.mark_proof_job_completed(proof_job).await?;
+
+        Ok(())
+    }
+
+    /// Calldata for `verify_proof_initial`
+    fn initial_calldata(&self, proof_job: &ProofJob) -> Result<Vec<Felt>, ProofSubmissionError> {
+        let initial_file = PathBuf::from(&proof_job.calldata_dir).join("initial");
 
         if !initial_file.exists() {
             return Err(ProofSubmissionError::CalldataFileMissing(
@@ -242,134 +1227,357 @@ impl ProofSubmissionRelayer {
             ));
         }
 
-        let initial_calldata = self.read_calldata_file(&initial_file)?;
+        let initial_calldata = read_calldata_file(&initial_file)?;
 
-        // Build calldata for verify_proof_initial
         let mut calldata = vec![Felt::from(proof_job.job_id as u64)];
         calldata.push(self.string_to_felt(&proof_job.layout));
         calldata.push(self.string_to_felt(&proof_job.hasher));
         calldata.push(self.string_to_felt(&proof_job.stone_version));
         calldata.push(self.string_to_felt(&proof_job.memory_verification));
         calldata.extend(initial_calldata);
+        Ok(calldata)
+    }
 
-        let tx_hash = self
-            .submit_contract_call("verify_proof_initial", calldata, proof_job)
-            .await?;
+    /// Calldata for `verify_proof_step` from the `step<step_num>` file, `None` when there is
+    /// no such file
+    fn step_calldata(
+        &self,
+        proof_job: &ProofJob,
+        step_num: u32,
+    ) -> Result<Option<Vec<Felt>>, ProofSubmissionError> {
+        let step_file = PathBuf::from(&proof_job.calldata_dir).join(format!("step{}", step_num));
 
-        self.update_proof_job_stage(proof_job, "initial_submitted")
-            .await?;
-        self.add_tx_hash(proof_job, "initial", &tx_hash.to_string())
-            .await?;
+        if !step_file.exists() {
+            return Ok(None);
+        }
 
-        info!(
-            "Initial proof submitted successfully for job_id: {}, tx_hash: {}",
-            proof_job.job_id, tx_hash
-        );
+        let mut calldata = vec![Felt::from(proof_job.job_id as u64)];
+        calldata.extend(read_calldata_file(&step_file)?);
+        Ok(Some(calldata))
+    }
 
-        Ok(())
+    /// Calldata for `verify_proof_final_and_register_fact`
+    fn final_calldata(&self, proof_job: &ProofJob) -> Result<Vec<Felt>, ProofSubmissionError> {
+        let final_file = PathBuf::from(&proof_job.calldata_dir).join("final");
+
+        if !final_file.exists() {
+            return Err(ProofSubmissionError::CalldataFileMissing(
+                "final".to_string(),
+            ));
+        }
+
+        let mut calldata = vec![Felt::from(proof_job.job_id as u64)];
+        calldata.extend(read_calldata_file(&final_file)?);
+        Ok(calldata)
     }
 
-    /// Submit all step proofs
-    async fn submit_step_proofs(
+    /// Call of `function_name` on the verifier contract
+    fn build_call(
+        &self,
+        function_name: &str,
+        calldata: Vec<Felt>,
+    ) -> Result<Call, ProofSubmissionError> {
+        let contract_address = Felt::from_hex(&self.config.contract_address)
+            .map_err(|_| ProofSubmissionError::InvalidContractAddress)?;
+
+        let selector = match function_name {
+            "verify_proof_initial" => starknet::macros::selector!("verify_proof_initial"),
+            "verify_proof_step" => starknet::macros::selector!("verify_proof_step"),
+            "verify_proof_final_and_register_fact" => {
+                starknet::macros::selector!("verify_proof_final_and_register_fact")
+            }
+            _ => {
+                return Err(ProofSubmissionError::TransactionFailed(format!(
+                    "Unknown function: {}",
+                    function_name
+                )))
+            }
+        };
+
+        Ok(Call {
+            to: contract_address,
+            selector,
+            calldata,
+        })
+    }
+
+    /// Contract calls `proof_job` has left to make, each labelled with the `tx_hashes` key its
+    /// transaction is recorded under
+    fn remaining_calls(
+        &self,
+        proof_job: &ProofJob,
+    ) -> Result<Vec<(String, &'static str, Call)>, ProofSubmissionError> {
+        let first_step = match ResumePoint::from_stage(proof_job.current_stage.as_deref()) {
+            ResumePoint::Initial | ResumePoint::RetryFailed => None,
+            ResumePoint::Step(step_num) => Some(step_num),
+            ResumePoint::MarkCompleted | ResumePoint::Completed => return Ok(Vec::new()),
+        };
+
+        let mut calls = Vec::new();
+        if first_step.is_none() {
+            let call =
+                self.build_call("verify_proof_initial", self.initial_calldata(proof_job)?)?;
+            calls.push(("initial".to_string(), "verify_proof_initial", call));
+        }
+
+        let mut step_num = first_step.unwrap_or(1);
+        while let Some(calldata) = self.step_calldata(proof_job, step_num)? {
+            let call = self.build_call("verify_proof_step", calldata)?;
+            calls.push((format!("step{}", step_num), "verify_proof_step", call));
+            step_num += 1;
+        }
+
+        let call = self.build_call(
+            "verify_proof_final_and_register_fact",
+            self.final_calldata(proof_job)?,
+        )?;
+        calls.push((
+            "final".to_string(),
+            "verify_proof_final_and_register_fact",
+            call,
+        ));
+
+        Ok(calls)
+    }
+
+    /// Estimate what executing `calls` from the relayer's account would cost, without sending
+    /// them
+    pub async fn estimate_l3_fee(
+        &self,
+        calls: Vec<Call>,
+    ) -> Result<FeeEstimate, ProofSubmissionError> {
+        self.account
+            .execute_v3(calls)
+            .estimate_fee()
+            .await
+            .map_err(|e| match e {
+                AccountError::Provider(e) => ProofSubmissionError::Provider(e),
+                e => ProofSubmissionError::FeeEstimationFailed(e.to_string()),
+            })
+    }
+
+    /// Estimate the fee of every contract call `proof_job` has left, without submitting any.
+    ///
+    /// Each call is estimated on its own, as it will be sent; nothing is aborted for exceeding
+    /// `max_fee_per_step`, the estimates are only flagged.
+    pub async fn estimate_remaining_fees(
         &self,
-        proof_job: &mut ProofJob,
-    ) -> Result<(), ProofSubmissionError> {
-        self.submit_step_proofs_from(proof_job, 1).await
+        proof_job: &ProofJob,
+    ) -> Result<Vec<StepFeeEstimate>, ProofSubmissionError> {
+        // Jobs can be imported from other instances, so their directory is checked again
+        let calldata_dir = validate_calldata_path(
+            &self.config.calldata_base_dir,
+            Path::new(&proof_job.calldata_dir),
+        )?;
+        let proof_job = ProofJob {
+            calldata_dir: calldata_dir.display().to_string(),
+            ..proof_job.clone()
+        };
+
+        let mut estimates = Vec::new();
+        for (stage, function_name, call) in self.remaining_calls(&proof_job)? {
+            let estimate = self.estimate_l3_fee(vec![call]).await?;
+            let overall_fee = overall_fee(&estimate);
+            estimates.push(StepFeeEstimate {
+                stage,
+                function_name: function_name.to_string(),
+                overall_fee,
+                exceeds_max: check_step_fee(overall_fee, self.config.max_fee_per_step).is_err(),
+            });
+        }
+
+        Ok(estimates)
     }
 
-    /// Submit step proofs starting from a specific step number
-    async fn submit_step_proofs_from(
+    /// Submit every contract call `proof_job` has left, suspending the job instead of making a
+    /// call that would take the fees estimated so far past `max_total_fee`.
+    ///
+    /// Only calls made by this run count towards the budget, so a job resumed after being
+    /// suspended starts with the whole budget again.
+    pub async fn submit_with_fee_budget(
         &self,
         proof_job: &mut ProofJob,
-        start_step: u32,
+        max_total_fee: u128,
     ) -> Result<(), ProofSubmissionError> {
-        let calldata_dir = PathBuf::from(&proof_job.calldata_dir);
-        let mut step_num = start_step;
-
-        loop {
-            let step_file = calldata_dir.join(format!("step{}", step_num));
-
-            if !step_file.exists() {
-                info!(
-                    "No more step files found after step{}, proceeding to final",
-                    step_num - 1
-                );
-                break;
+        match ResumePoint::from_stage(proof_job.current_stage.as_deref()) {
+            ResumePoint::Completed => return Ok(()),
+            ResumePoint::RetryFailed => {
+                warn!("Proof job previously failed, retrying from beginning");
+                proof_job.retry_count += 1;
+                self.update_proof_job_stage(proof_job, "processing").await?;
             }
+            _ => {}
+        }
 
+        let mut spent: u128 = 0;
+        for (stage, function_name, call) in self.remaining_calls(proof_job)? {
+            let estimated = overall_fee(&self.estimate_l3_fee(vec![call.clone()]).await?);
             info!(
-                "Submitting step{} proof for job_id: {}",
-                step_num, proof_job.job_id
+                "Estimated fee of {} for job_id: {}: {} ({} of {} spent)",
+                function_name, proof_job.job_id, estimated, spent, max_total_fee
             );
-
-            let step_calldata = self.read_calldata_file(&step_file)?;
-
-            // Build calldata for verify_proof_step
-            let mut calldata = vec![Felt::from(proof_job.job_id as u64)];
-            calldata.extend(step_calldata);
+            check_step_fee(estimated, self.config.max_fee_per_step)?;
+
+            if spent.saturating_add(estimated) > max_total_fee {
+                let e = ProofSubmissionError::BudgetExceeded {
+                    spent,
+                    limit: max_total_fee,
+                };
+                error!(
+                    "Suspending job_id: {} before {}: {}",
+                    proof_job.job_id, stage, e
+                );
+                self.suspend_proof_job(proof_job, &e.to_string()).await?;
+                return Err(e);
+            }
+            spent += estimated;
 
             let tx_hash = self
-                .submit_contract_call("verify_proof_step", calldata, proof_job)
+                .send_contract_call(function_name, call, proof_job)
+                .await?;
+            self.update_proof_job_stage(proof_job, &format!("{}_submitted", stage))
                 .await?;
+            self.add_tx_hash(proof_job, &stage, &tx_hash.to_string())
+                .await?;
+        }
 
-            let stage_name = format!("step{}_submitted", step_num);
-            self.update_proof_job_stage(proof_job, &stage_name).await?;
-            self.add_tx_hash(
-                proof_job,
-                &format!("step{}", step_num),
-                &tx_hash.to_string(),
-            )
-            .await?;
+        self.mark_proof_job_completed(proof_job).await
+    }
 
-            info!(
-                "Step{} proof submitted successfully for job_id: {}, tx_hash: {}",
-                step_num, proof_job.job_id, tx_hash
-            );
+    /// Queue a `suspended` job again under a new fee budget. The next
+    /// [`Self::process_all_queued_jobs`] run submits whatever it has left.
+    pub async fn resume_with_fee_budget(
+        &self,
+        job_id: u64,
+        max_total_fee: u128,
+    ) -> Result<ProofJob, ProofSubmissionError> {
+        let mut proof_job = self.get_proof_job_by_job_id(job_id).await?;
+        if proof_job.status != "suspended" {
+            return Err(ProofSubmissionError::ProofJobNotSuspended {
+                job_id,
+                status: proof_job.status,
+            });
+        }
+        // Jobs can be imported from other instances, so their directory is checked again
+        proof_job.calldata_dir = validate_calldata_path(
+            &self.config.calldata_base_dir,
+            Path::new(&proof_job.calldata_dir),
+        )?
+        .display()
+        .to_string();
+        self.verify_calldata_unchanged(&proof_job).await?;
+
+        // Only one of several concurrent resumes of the same job gets to queue it
+        let queued = sqlx::query_scalar!(
+            r#"
+            UPDATE proof_jobs
+            SET status = 'queued', error_message = NULL, max_total_fee = $2::TEXT::NUMERIC,
+                updated_at = NOW()
+            WHERE id = $1 AND status = 'suspended'
+            RETURNING id
+            "#,
+            proof_job.id,
+            max_total_fee.to_string()
+        )
+        .fetch_optional(&self.db_pool)
+        .await?;
 
-            step_num += 1;
+        let proof_job = self.get_proof_job_by_job_id(job_id).await?;
+        if queued.is_none() {
+            return Err(ProofSubmissionError::ProofJobNotSuspended {
+                job_id,
+                status: proof_job.status,
+            });
         }
+        info!(
+            "Queued suspended proof job {} with a fee budget of {}",
+            job_id, max_total_fee
+        );
+        Ok(proof_job)
+    }
 
-        Ok(())
+    /// Fee budget `proof_job` was last resumed with, if it was
+    async fn resumed_fee_budget(
+        &self,
+        proof_job: &ProofJob,
+    ) -> Result<Option<u128>, ProofSubmissionError> {
+        let budget = sqlx::query_scalar!(
+            r#"SELECT max_total_fee::TEXT AS "max_total_fee" FROM proof_jobs WHERE id = $1"#,
+            proof_job.id
+        )
+        .fetch_one(&self.db_pool)
+        .await?;
+
+        budget
+            .map(|budget| {
+                budget
+                    .parse()
+                    .map_err(|_| ProofSubmissionError::InvalidField {
+                        field: "max_total_fee".to_string(),
+                        reason: format!("{:?} is not a fee", budget),
+                    })
+            })
+            .transpose()
     }
 
-    /// Submit the final proof and register fact
-    async fn submit_final_proof(
+    /// Park a job that ran out of fee budget; it is not picked up again until resumed
+    async fn suspend_proof_job(
         &self,
         proof_job: &mut ProofJob,
+        reason: &str,
     ) -> Result<(), ProofSubmissionError> {
-        info!("Submitting final proof for job_id: {}", proof_job.job_id);
-
-        let calldata_dir = PathBuf::from(&proof_job.calldata_dir);
-        let final_file = calldata_dir.join("final");
-
-        if !final_file.exists() {
-            return Err(ProofSubmissionError::CalldataFileMissing(
-                "final".to_string(),
-            ));
+        if !self.config.dry_run {
+            sqlx::query!(
+                r#"
+                UPDATE proof_jobs
+                SET status = 'suspended', error_message = $2, updated_at = NOW()
+                WHERE id = $1
+                "#,
+                proof_job.id,
+                reason
+            )
+            .execute(&self.db_pool)
+            .await?;
         }
 
-        let final_calldata = self.read_calldata_file(&final_file)?;
-
-        // Build calldata for verify_proof_final_and_register_fact
-        let mut calldata = vec![Felt::from(proof_job.job_id as u64)];
-        calldata.extend(final_calldata);
-
-        let tx_hash = self
-            .submit_contract_call("verify_proof_final_and_register_fact", calldata, proof_job)
-            .await?;
+        proof_job.status = "suspended".to_string();
+        proof_job.error_message = Some(reason.to_string());
+        Ok(())
+    }
 
-        self.update_proof_job_stage(proof_job, "final_submitted")
-            .await?;
-        self.add_tx_hash(proof_job, "final", &tx_hash.to_string())
-            .await?;
+    /// Log the estimated fee of `call` and refuse it when it is above `max_fee_per_step`
+    async fn enforce_step_fee(
+        &self,
+        function_name: &str,
+        call: &Call,
+        proof_job: &ProofJob,
+    ) -> Result<(), ProofSubmissionError> {
+        let estimate = match self.estimate_l3_fee(vec![call.clone()]).await {
+            Ok(estimate) => estimate,
+            // Without a limit the estimate is only informational, so don't hold the job up
+            Err(e) if self.config.max_fee_per_step.is_none() => {
+                warn!(
+                    "Fee estimation of {} failed for job_id: {}: {}",
+                    function_name, proof_job.job_id, e
+                );
+                return Ok(());
+            }
+            Err(e) => return Err(e),
+        };
 
+        let estimated = overall_fee(&estimate);
         info!(
-            "Final proof submitted successfully for job_id: {}, tx_hash: {}",
-            proof_job.job_id, tx_hash
+            "Estimated fee of {} for job_id: {}: {} ({:?})",
+            function_name, proof_job.job_id, estimated, estimate.unit
         );
 
-        // Mark as completed and update deposits
-        self.mark_proof_job_completed(proof_job).await?;
+        if let Err(e) = check_step_fee(estimated, self.config.max_fee_per_step) {
+            error!(
+                "Aborting {} for job_id: {}: {}",
+                function_name, proof_job.job_id, e
+            );
+            return Err(e);
+        }
 
         Ok(())
     }
@@ -381,28 +1589,31 @@ impl ProofSubmissionRelayer {
         calldata: Vec<Felt>,
         proof_job: &ProofJob,
     ) -> Result<Felt, ProofSubmissionError> {
-        let contract_address = Felt::from_hex(&self.config.contract_address)
-            .map_err(|_| ProofSubmissionError::InvalidContractAddress)?;
+        let call = self.build_call(function_name, calldata)?;
 
-        let selector = match function_name {
-            "verify_proof_initial" => starknet::macros::selector!("verify_proof_initial"),
-            "verify_proof_step" => starknet::macros::selector!("verify_proof_step"),
-            "verify_proof_final_and_register_fact" => {
-                starknet::macros::selector!("verify_proof_final_and_register_fact")
-            }
-            _ => {
-                return Err(ProofSubmissionError::TransactionFailed(format!(
-                    "Unknown function: {}",
-                    function_name
-                )))
-            }
-        };
+        // Estimated once per stage; the retries below resend the same call
+        if !self.config.dry_run {
+            self.enforce_step_fee(function_name, &call, proof_job)
+                .await?;
+        }
 
-        let call = Call {
-            to: contract_address,
-            selector,
-            calldata,
-        };
+        self.send_contract_call(function_name, call, proof_job)
+            .await
+    }
+
+    /// Send `call` and wait for its confirmation, retrying up to `max_retries` times; dry runs
+    /// only simulate it
+    async fn send_contract_call(
+        &self,
+        function_name: &str,
+        call: Call,
+        proof_job: &ProofJob,
+    ) -> Result<Felt, ProofSubmissionError> {
+        if self.config.dry_run {
+            return self
+                .dry_run_contract_call(function_name, call, proof_job)
+                .await;
+        }
 
         let mut attempts = 0;
         let max_retries = self.config.max_retries;
@@ -415,8 +1626,34 @@ impl ProofSubmissionRelayer {
                 function_name, attempts, max_retries, proof_job.job_id
             );
 
-            match self.account.execute_v3(vec![call.clone()]).send().await {
-                Ok(result) => {
+            let reservation = self
+                .nonce_cache
+                .reserve(|| self.account.get_nonce())
+                .await?;
+
+            // A hung node would otherwise block the relayer task forever
+            let send_result = with_timeout(
+                Duration::from_millis(self.config.transaction_timeout_ms),
+                self.account
+                    .execute_v3(vec![call.clone()])
+                    .nonce(reservation.nonce())
+                    .send(),
+            )
+            .await;
+
+            match send_result {
+                Err(e) => {
+                    error!(
+                        "Transaction submission timed out: {} for job_id: {} (attempt {}/{})",
+                        function_name, proof_job.job_id, attempts, max_retries
+                    );
+                    // Whether the transaction reached the node is unknown, so re-read the nonce
+                    reservation.invalidate();
+                    return Err(e);
+                }
+                Ok(Ok(result)) => {
+                    // Other submissions may send while this one waits for confirmation
+                    drop(reservation);
                     info!(
                         "Transaction submitted successfully: {} for job_id: {}, tx_hash: {}",
                         function_name, proof_job.job_id, result.transaction_hash
@@ -446,11 +1683,13 @@ impl ProofSubmissionRelayer {
                         }
                     }
                 }
-                Err(e) => {
+                Ok(Err(e)) => {
                     error!(
                         "Transaction submission failed: {} for job_id: {} (attempt {}/{}), error: {:?}",
                         function_name, proof_job.job_id, attempts, max_retries, e
                     );
+                    // The reserved nonce was never used, so re-read it from the node next time
+                    reservation.invalidate();
 
                     if attempts >= max_retries {
                         return Err(ProofSubmissionError::TransactionFailed(format!(
@@ -471,26 +1710,102 @@ impl ProofSubmissionRelayer {
         }
     }
 
+    /// Simulate a contract call with a view call; a revert is reported as `DryRunReverted`
+    async fn dry_run_contract_call(
+        &self,
+        function_name: &str,
+        call: Call,
+        proof_job: &ProofJob,
+    ) -> Result<Felt, ProofSubmissionError> {
+        let request = FunctionCall {
+            contract_address: call.to,
+            entry_point_selector: call.selector,
+            calldata: call.calldata,
+        };
+
+        match self
+            .account
+            .provider()
+            .call(request, BlockId::Tag(BlockTag::Latest))
+            .await
+        {
+            Ok(result) => {
+                info!(
+                    "Dry run of {} succeeded for job_id: {}, result: {:?}",
+                    function_name, proof_job.job_id, result
+                );
+                // There is no transaction, so report a zero hash
+                Ok(Felt::ZERO)
+            }
+            Err(ProviderError::StarknetError(e)) => {
+                warn!(
+                    "Dry run of {} reverted for job_id: {}: {}",
+                    function_name, proof_job.job_id, e
+                );
+                Err(ProofSubmissionError::DryRunReverted(format!(
+                    "{}: {}",
+                    function_name, e
+                )))
+            }
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Whether the fact registry's `is_valid` view reports `fact_hash` as registered
+    pub async fn verify_fact_registered(
+        &self,
+        fact_hash: &str,
+    ) -> Result<bool, ProofSubmissionError> {
+        let registry = self
+            .config
+            .fact_registry_address
+            .as_deref()
+            .ok_or(ProofSubmissionError::FactRegistryNotConfigured)?;
+        let request = FunctionCall {
+            contract_address: Felt::from_hex(registry)
+                .map_err(|_| ProofSubmissionError::InvalidContractAddress)?,
+            entry_point_selector: starknet::macros::selector!("is_valid"),
+            calldata: vec![Felt::from_hex(fact_hash)?],
+        };
+
+        let result = self
+            .account
+            .provider()
+            .call(request, BlockId::Tag(BlockTag::Latest))
+            .await?;
+
+        Ok(result.first() == Some(&Felt::ONE))
+    }
+
     /// Wait for transaction confirmation
     async fn wait_for_transaction_confirmation(
         &self,
         tx_hash: Felt,
     ) -> Result<(), ProofSubmissionError> {
-        let timeout = Duration::from_millis(self.config.transaction_timeout_ms);
-        let start_time = std::time::Instant::now();
+        let confirmation_timeout = Duration::from_millis(self.config.transaction_timeout_ms);
+        let poll_timeout = Duration::from_millis(self.config.receipt_poll_timeout_ms);
+        let start_time = tokio::time::Instant::now();
 
         loop {
-            if start_time.elapsed() > timeout {
+            if start_time.elapsed() > confirmation_timeout {
                 return Err(ProofSubmissionError::TransactionTimeout);
             }
 
-            match self
-                .account
-                .provider()
-                .get_transaction_receipt(tx_hash)
-                .await
-            {
-                Ok(receipt) => {
+            let poll = with_timeout(
+                poll_timeout,
+                self.account.provider().get_transaction_receipt(tx_hash),
+            )
+            .await;
+
+            match poll {
+                Err(_) => {
+                    // A single hung poll is retried; the overall timeout above still applies
+                    warn!(
+                        "Receipt poll for tx_hash: {} timed out after {:?}",
+                        tx_hash, poll_timeout
+                    );
+                }
+                Ok(Ok(receipt)) => {
                     match receipt.receipt {
                         TransactionReceipt::Invoke(receipt) => match receipt.execution_result {
                             ExecutionResult::Succeeded => return Ok(()),
@@ -505,49 +1820,18 @@ impl ProofSubmissionRelayer {
                         }
                     }
                 }
-                Err(ProviderError::StarknetError(
+                Ok(Err(ProviderError::StarknetError(
                     starknet::core::types::StarknetError::TransactionHashNotFound,
-                )) => {
+                ))) => {
                     // Transaction not found yet, keep polling
                 }
-                Err(e) => return Err(ProofSubmissionError::Provider(e)),
+                Ok(Err(e)) => return Err(ProofSubmissionError::Provider(e)),
             }
 
             sleep(Duration::from_secs(2)).await;
         }
     }
 
-    /// Read calldata from a file and parse it
-    fn read_calldata_file(&self, file_path: &Path) -> Result<Vec<Felt>, ProofSubmissionError> {
-        let content = fs::read_to_string(file_path)?;
-        let mut calldata = Vec::new();
-
-        for line in content.lines() {
-            let line = line.trim();
-            if line.is_empty() {
-                continue;
-            }
-
-            // Split by whitespace and parse each hex value
-            for hex_str in line.split_whitespace() {
-                let felt = Felt::from_hex(hex_str).map_err(|e| {
-                    ProofSubmissionError::InvalidCalldataFormat(format!(
-                        "Invalid hex value '{}' in file {:?}: {}",
-                        hex_str, file_path, e
-                    ))
-                })?;
-                calldata.push(felt);
-            }
-        }
-
-        debug!(
-            "Read {} calldata elements from {:?}",
-            calldata.len(),
-            file_path
-        );
-        Ok(calldata)
-    }
-
     /// Convert string to Felt (hex encoding)
     fn string_to_felt(&self, input: &str) -> Felt {
         let hex_string = self.string_to_hex(input);
@@ -563,7 +1847,9 @@ impl ProofSubmissionRelayer {
         hex_string
     }
 
-    /// Create or get existing proof job from database
+    /// Create or get existing proof job from database, linking `deposit_ids` to it and
+    /// recording `fact_hash` on a new job
+    #[allow(clippy::too_many_arguments)]
     async fn create_or_get_proof_job(
         &self,
         job_id: u64,
@@ -572,32 +1858,38 @@ impl ProofSubmissionRelayer {
         hasher: &str,
         stone_version: &str,
         memory_verification: &str,
+        deposit_ids: Vec<i32>,
+        fact_hash: Option<&str>,
     ) -> Result<ProofJob, ProofSubmissionError> {
         // Try to get existing job
         if let Ok(existing_job) = self.get_proof_job_by_job_id(job_id).await {
             info!("Found existing proof job for job_id: {}", job_id);
+            self.link_deposits(&existing_job, &deposit_ids).await?;
             return Ok(existing_job);
         }
 
         // Create new job
         info!("Creating new proof job for job_id: {}", job_id);
+        let calldata_hash = compute_calldata_hash(calldata_dir)?;
         let row = sqlx::query!(
             r#"
-            INSERT INTO proof_jobs (job_id, calldata_dir, layout, hasher, stone_version, memory_verification, status, current_stage, tx_hashes)
-            VALUES ($1, $2, $3, $4, $5, $6, 'processing', 'processing', '{}')
-            RETURNING id, job_id, calldata_dir, layout, hasher, stone_version, memory_verification, status, current_stage, retry_count, error_message, tx_hashes
+            INSERT INTO proof_jobs (job_id, calldata_dir, layout, hasher, stone_version, memory_verification, status, current_stage, stage_started_at, fact_hash, calldata_hash)
+            VALUES ($1, $2, $3, $4, $5, $6, 'processing', 'processing', NOW(), $7, $8)
+            RETURNING id, job_id, calldata_dir, layout, hasher, stone_version, memory_verification, status, current_stage, retry_count, error_message, stage_started_at, fact_hash, calldata_hash, metadata
             "#,
             job_id as i64,
             calldata_dir.display().to_string(),
             layout,
             hasher,
             stone_version,
-            memory_verification
+            memory_verification,
+            fact_hash,
+            calldata_hash
         )
         .fetch_one(&self.db_pool)
         .await?;
 
-        Ok(ProofJob {
+        let proof_job = ProofJob {
             id: row.id,
             job_id: row.job_id,
             calldata_dir: row.calldata_dir,
@@ -609,15 +1901,75 @@ impl ProofSubmissionRelayer {
             current_stage: row.current_stage,
             retry_count: row.retry_count,
             error_message: row.error_message,
-            tx_hashes: row.tx_hashes.unwrap_or_else(|| serde_json::json!({})),
-        })
+            tx_hashes: serde_json::json!({}),
+            stage_started_at: row.stage_started_at,
+            fact_hash: row.fact_hash,
+            calldata_hash: row.calldata_hash,
+            metadata: row.metadata,
+        };
+        self.link_deposits(&proof_job, &deposit_ids).await?;
+
+        Ok(proof_job)
+    }
+
+    /// Fails with `CalldataModified` if the job's calldata no longer hashes to what was stored
+    /// when it was created.
+    ///
+    /// Jobs without a stored hash, created before hashes were stored or imported without one,
+    /// get the current hash stored instead, so every later submission is checked against it.
+    async fn verify_calldata_unchanged(
+        &self,
+        proof_job: &ProofJob,
+    ) -> Result<(), ProofSubmissionError> {
+        let current_hash = compute_calldata_hash(Path::new(&proof_job.calldata_dir))?;
+        // A concurrent caller may store its hash first, which is then the one checked
+        let stored_hash = sqlx::query_scalar!(
+            r#"
+            UPDATE proof_jobs
+            SET calldata_hash = COALESCE(calldata_hash, $2)
+            WHERE id = $1
+            RETURNING calldata_hash AS "calldata_hash!"
+            "#,
+            proof_job.id,
+            current_hash
+        )
+        .fetch_one(&self.db_pool)
+        .await?;
+
+        if current_hash != stored_hash {
+            return Err(ProofSubmissionError::CalldataModified {
+                stored_hash,
+                current_hash,
+            });
+        }
+        Ok(())
+    }
+
+    /// Link the deposits covered by this proof so they are finalized when it completes
+    async fn link_deposits(
+        &self,
+        proof_job: &ProofJob,
+        deposit_ids: &[i32],
+    ) -> Result<(), ProofSubmissionError> {
+        if deposit_ids.is_empty() {
+            return Ok(());
+        }
+
+        let linked = link_deposits_to_proof_job(&self.db_pool, proof_job.id, deposit_ids).await?;
+        info!(
+            "Linked {} deposits to proof job {}",
+            linked, proof_job.job_id
+        );
+        Ok(())
     }
 
     /// Get proof job by job_id
     async fn get_proof_job_by_job_id(&self, job_id: u64) -> Result<ProofJob, ProofSubmissionError> {
         let row = sqlx::query!(
             r#"
-            SELECT id, job_id, calldata_dir, layout, hasher, stone_version, memory_verification, status, current_stage, retry_count, error_message, tx_hashes
+            SELECT id, job_id, calldata_dir, layout, hasher, stone_version, memory_verification, status, current_stage, retry_count, error_message,
+                (SELECT jsonb_object_agg(h.stage, h.tx_hash ORDER BY h.submitted_at, h.id) FROM proof_job_tx_hashes h WHERE h.proof_job_id = proof_jobs.id) AS tx_hashes,
+                stage_started_at, fact_hash, calldata_hash, metadata
             FROM proof_jobs
             WHERE job_id = $1
             "#,
@@ -640,58 +1992,70 @@ impl ProofSubmissionRelayer {
             retry_count: row.retry_count,
             error_message: row.error_message,
             tx_hashes: row.tx_hashes.unwrap_or_else(|| serde_json::json!({})),
+            stage_started_at: row.stage_started_at,
+            fact_hash: row.fact_hash,
+            calldata_hash: row.calldata_hash,
+            metadata: row.metadata,
         })
     }
 
-    /// Update proof job stage
+    /// Update proof job stage, rejecting transitions not listed in `VALID_TRANSITIONS`
     async fn update_proof_job_stage(
         &self,
         proof_job: &mut ProofJob,
         stage: &str,
     ) -> Result<(), ProofSubmissionError> {
-        sqlx::query!(
+        validate_transition(proof_job.current_stage.as_deref(), stage)?;
+
+        if self.config.dry_run {
+            proof_job.current_stage = Some(stage.to_string());
+            debug!(
+                "Dry run: reached stage {} for job {}",
+                stage, proof_job.job_id
+            );
+            return Ok(());
+        }
+
+        let row = sqlx::query!(
             r#"
             UPDATE proof_jobs
-            SET current_stage = $1, updated_at = NOW()
+            SET current_stage = $1, stage_started_at = NOW(), updated_at = NOW()
             WHERE id = $2
+            RETURNING stage_started_at
             "#,
             stage,
             proof_job.id
         )
-        .execute(&self.db_pool)
+        .fetch_one(&self.db_pool)
         .await?;
 
         proof_job.current_stage = Some(stage.to_string());
+        proof_job.stage_started_at = row.stage_started_at;
         info!("Updated proof job {} stage to: {}", proof_job.job_id, stage);
         Ok(())
     }
 
-    /// Add transaction hash to proof job
+    /// Record the transaction submitted for `stage` in `proof_job_tx_hashes`
     async fn add_tx_hash(
         &self,
         proof_job: &mut ProofJob,
         stage: &str,
         tx_hash: &str,
     ) -> Result<(), ProofSubmissionError> {
-        let mut tx_hashes: HashMap<String, String> =
-            serde_json::from_value(proof_job.tx_hashes.clone())?;
-        tx_hashes.insert(stage.to_string(), tx_hash.to_string());
-
-        let updated_tx_hashes = serde_json::to_value(&tx_hashes)?;
+        if self.config.dry_run {
+            return Ok(());
+        }
 
-        sqlx::query!(
-            r#"
-            UPDATE proof_jobs
-            SET tx_hashes = $1, updated_at = NOW()
-            WHERE id = $2
-            "#,
-            updated_tx_hashes,
-            proof_job.id
-        )
-        .execute(&self.db_pool)
-        .await?;
+        insert_proof_job_tx_hash(&self.db_pool, proof_job.id, stage, tx_hash).await?;
 
-        proof_job.tx_hashes = updated_tx_hashes;
+        let mut tx_hashes = parse_tx_hashes(proof_job.job_id as u64, &proof_job.tx_hashes)
+            .unwrap_or_else(|e| {
+                // Every hash is also in `proof_job_tx_hashes`, so only the in-memory copy is lost
+                warn!("{}, starting over from an empty map", e);
+                HashMap::new()
+            });
+        tx_hashes.insert(stage.to_string(), tx_hash.to_string());
+        proof_job.tx_hashes = serde_json::to_value(&tx_hashes)?;
         info!(
             "Added tx_hash for job {} stage {}: {}",
             proof_job.job_id, stage, tx_hash
@@ -704,42 +2068,85 @@ impl ProofSubmissionRelayer {
         &self,
         proof_job: &mut ProofJob,
     ) -> Result<(), ProofSubmissionError> {
+        validate_transition(proof_job.current_stage.as_deref(), "completed")?;
+
+        if self.config.dry_run {
+            info!(
+                "Dry run: all proof calls for job {} succeeded",
+                proof_job.job_id
+            );
+            return Ok(());
+        }
+
         info!("Marking proof job {} as completed", proof_job.job_id);
 
+        // The challenge window starts from the current L1 block, so look it up before changing
+        // anything; a failed lookup leaves the job at `final_submitted` to be retried.
+        let current_block = ethereum_block_number(&self.ethereum_client)
+            .await
+            .map_err(|e| ProofSubmissionError::EthereumRpc(e.to_string()))?;
+        let finalization_block = current_block + self.config.challenge_window_blocks;
+
+        // The final transaction was confirmed, but only the registry can say the fact stuck
+        if let Some(fact_hash) = &proof_job.fact_hash {
+            if !self.verify_fact_registered(fact_hash).await? {
+                error!(
+                    "Fact {} for proof job {} is not registered",
+                    fact_hash, proof_job.job_id
+                );
+                return Err(ProofSubmissionError::TransactionFailed(
+                    "Fact not registered".to_string(),
+                ));
+            }
+            info!(
+                "Fact {} for proof job {} is registered",
+                fact_hash, proof_job.job_id
+            );
+        }
+
         // Update proof job status
         sqlx::query!(
             r#"
             UPDATE proof_jobs
-            SET status = 'completed', current_stage = 'completed', updated_at = NOW()
+            SET status = 'completed', current_stage = 'completed', fact_hash = $2, updated_at = NOW()
             WHERE id = $1
             "#,
-            proof_job.id
+            proof_job.id,
+            proof_job.fact_hash
         )
         .execute(&self.db_pool)
         .await?;
 
-        // Update related deposits to READY_TO_CLAIM
-        let updated_deposits = sqlx::query!(
-            r#"
-            UPDATE deposits
-            SET status = 'READY_TO_CLAIM', updated_at = NOW()
-            WHERE id IN (
-                SELECT DISTINCT d.id
-                FROM deposits d
-                WHERE d.status = 'pending'
-                -- Add additional criteria to match deposits with this proof job
-                -- This depends on your specific business logic
+        // Record how long each contract call took so running jobs can be given an ETA
+        let num_steps = proof_job
+            .tx_hashes
+            .as_object()
+            .map_or(0, |hashes| hashes.len()) as i32;
+        if num_steps > 0 {
+            sqlx::query!(
+                r#"
+                INSERT INTO proof_job_stats (job_id, num_steps, total_duration_seconds, average_step_duration_seconds)
+                SELECT job_id, $2::INT, EXTRACT(EPOCH FROM NOW() - created_at)::DOUBLE PRECISION, EXTRACT(EPOCH FROM NOW() - created_at)::DOUBLE PRECISION / $2::INT
+                FROM proof_jobs
+                WHERE id = $1
+                "#,
+                proof_job.id,
+                num_steps
             )
-            RETURNING id
-            "#
-        )
-        .fetch_all(&self.db_pool)
-        .await?;
+            .execute(&self.db_pool)
+            .await?;
+        }
+
+        // Deposits linked to this job become claimable once the challenge window has passed;
+        // the finalization watcher moves them to READY_TO_CLAIM
+        let scheduled_deposits =
+            schedule_deposit_finalization(&self.db_pool, proof_job.id, finalization_block).await?;
 
         info!(
-            "Marked {} deposits as READY_TO_CLAIM for proof job {}",
-            updated_deposits.len(),
-            proof_job.job_id
+            "Marked {} deposits as PROOF_SUBMITTED for proof job {}, claimable from block {}",
+            scheduled_deposits.len(),
+            proof_job.job_id,
+            finalization_block
         );
 
         proof_job.status = "completed".to_string();
@@ -749,6 +2156,16 @@ impl ProofSubmissionRelayer {
             "Proof job {} marked as completed successfully",
             proof_job.job_id
         );
+        self.notify_webhook(WebhookEvent::ProofJobCompleted {
+            job_id: proof_job.job_id as u64,
+            fact_hash: proof_job.fact_hash.clone(),
+        });
         Ok(())
     }
+
+    fn notify_webhook(&self, event: WebhookEvent) {
+        if let Some(webhook) = &self.webhook {
+            webhook.notify(event);
+        }
+    }
 }
@@ -0,0 +1,73 @@
+use starknet::providers::jsonrpc::{HttpTransport, JsonRpcClient};
+use std::ops::Deref;
+use std::sync::Arc;
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+use url::Url;
+
+/// Shared Starknet JSON-RPC client with a bound on the number of in-flight requests.
+///
+/// Cloning the inner `Arc` is cheap, so every relayer built from the same pool reuses a single
+/// HTTP connection pool instead of opening its own.
+#[derive(Debug)]
+pub struct ProviderPool {
+    provider: Arc<JsonRpcClient<HttpTransport>>,
+    permits: Arc<Semaphore>,
+    max_connections: usize,
+}
+
+/// A provider handle that holds one of the pool's connection slots until dropped
+pub struct PooledProvider {
+    provider: Arc<JsonRpcClient<HttpTransport>>,
+    _permit: OwnedSemaphorePermit,
+}
+
+impl Deref for PooledProvider {
+    type Target = JsonRpcClient<HttpTransport>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.provider
+    }
+}
+
+impl ProviderPool {
+    pub fn new(rpc_url: &str, max_connections: usize) -> Result<Self, url::ParseError> {
+        let provider = JsonRpcClient::new(HttpTransport::new(Url::parse(rpc_url)?));
+        // A pool that can never hand out a slot would deadlock every caller
+        let max_connections = max_connections.max(1);
+
+        Ok(Self {
+            provider: Arc::new(provider),
+            permits: Arc::new(Semaphore::new(max_connections)),
+            max_connections,
+        })
+    }
+
+    /// Wait for a free connection slot and return the shared provider
+    pub async fn acquire(&self) -> PooledProvider {
+        let permit = self
+            .permits
+            .clone()
+            .acquire_owned()
+            .await
+            .expect("provider pool semaphore is never closed");
+
+        PooledProvider {
+            provider: self.provider.clone(),
+            _permit: permit,
+        }
+    }
+
+    /// The shared provider, without reserving a connection slot
+    pub fn provider(&self) -> Arc<JsonRpcClient<HttpTransport>> {
+        self.provider.clone()
+    }
+
+    pub fn max_connections(&self) -> usize {
+        self.max_connections
+    }
+
+    /// Number of connection slots not currently in use
+    pub fn available_connections(&self) -> usize {
+        self.permits.available_permits()
+    }
+}
@@ -0,0 +1,124 @@
+use crate::utils::strip_url_userinfo;
+use starknet::providers::jsonrpc::{
+    HttpTransport, HttpTransportError, JsonRpcClient, JsonRpcClientError,
+};
+use starknet::providers::ProviderError;
+use std::future::Future;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use tracing::warn;
+use url::Url;
+
+/// Starknet JSON-RPC clients for a primary node and its fallbacks.
+///
+/// Calls go to the active node until it fails with a transient error, after which the next
+/// node in the list becomes active, wrapping around to the primary after the last fallback.
+#[derive(Debug)]
+pub struct RoundRobinProvider {
+    urls: Vec<Url>,
+    providers: Vec<Arc<JsonRpcClient<HttpTransport>>>,
+    active: AtomicUsize,
+}
+
+impl RoundRobinProvider {
+    pub fn new(primary_url: &str, fallback_urls: &[String]) -> Result<Self, url::ParseError> {
+        let urls = std::iter::once(primary_url)
+            .chain(fallback_urls.iter().map(String::as_str))
+            .map(Url::parse)
+            .collect::<Result<Vec<_>, _>>()?;
+        let providers = urls
+            .iter()
+            .map(|url| Arc::new(JsonRpcClient::new(HttpTransport::new(url.clone()))))
+            .collect();
+
+        Ok(Self {
+            urls,
+            providers,
+            active: AtomicUsize::new(0),
+        })
+    }
+
+    /// Index of the active node, `0` being the primary
+    pub fn active_index(&self) -> usize {
+        self.active.load(Ordering::SeqCst)
+    }
+
+    pub fn active_url(&self) -> &Url {
+        &self.urls[self.active_index()]
+    }
+
+    /// The active node's client along with its index, which `fail_over` expects back
+    pub fn active(&self) -> (usize, Arc<JsonRpcClient<HttpTransport>>) {
+        let index = self.active_index();
+        (index, self.providers[index].clone())
+    }
+
+    pub fn len(&self) -> usize {
+        self.providers.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.providers.is_empty()
+    }
+
+    /// Moves on from node `failed`, unless another caller has already done so
+    pub fn fail_over(&self, failed: usize) {
+        let next = (failed + 1) % self.providers.len();
+        if self
+            .active
+            .compare_exchange(failed, next, Ordering::SeqCst, Ordering::SeqCst)
+            .is_ok()
+            && next != failed
+        {
+            warn!(
+                "Starknet RPC {} failed, switching to {}",
+                self.urls[failed], self.urls[next]
+            );
+        }
+    }
+
+    /// Runs `call` against the active node, failing over to the next node on transient errors
+    /// until every node has been tried once
+    pub async fn call<T, F, Fut>(&self, call: F) -> Result<T, ProviderError>
+    where
+        F: Fn(Arc<JsonRpcClient<HttpTransport>>) -> Fut,
+        Fut: Future<Output = Result<T, ProviderError>>,
+    {
+        let mut attempts = 0;
+        loop {
+            let (index, provider) = self.active();
+            attempts += 1;
+
+            match call(provider).await {
+                Err(e) if is_transient(&e) => {
+                    warn!(
+                        "Starknet RPC {} call failed: {}",
+                        strip_url_userinfo(self.urls[index].as_str()),
+                        e
+                    );
+                    self.fail_over(index);
+                    if attempts >= self.providers.len() {
+                        return Err(e);
+                    }
+                }
+                result => return result,
+            }
+        }
+    }
+}
+
+/// Whether `err` says the node itself is unavailable, so another node could answer.
+///
+/// Errors the node reports about the request, like a missing transaction, would come back the
+/// same from any node.
+pub fn is_transient(err: &ProviderError) -> bool {
+    match err {
+        ProviderError::RateLimited => true,
+        ProviderError::Other(e) => matches!(
+            e.as_any()
+                .downcast_ref::<JsonRpcClientError<HttpTransportError>>(),
+            Some(JsonRpcClientError::TransportError(_))
+        ),
+        _ => false,
+    }
+}
@@ -1,12 +1,21 @@
+use crate::config::StarknetConfig;
 use crate::queue::l2_queue::L2Transaction;
+use crate::relayer::provider_pool::ProviderPool;
+use crate::relayer::round_robin_provider::{is_transient, RoundRobinProvider};
+use serde::Serialize;
 use sqlx::{Pool, Postgres};
 use starknet::accounts::Account;
-use starknet::accounts::ConnectedAccount;
+use starknet::accounts::AccountError;
 use starknet::accounts::ExecutionEncoding;
 use starknet::core::chain_id::MAINNET;
 use starknet::core::types::ExecutionResult;
 use starknet::core::types::StarknetError;
+use starknet::core::types::{
+    Call, EmittedEvent, ExecuteInvocation, FunctionInvocation, SimulatedTransaction,
+    TransactionTrace,
+};
 use starknet::core::types::{Felt, TransactionReceipt};
+use starknet::core::utils::get_contract_address;
 use starknet::providers::jsonrpc::HttpTransport;
 use starknet::providers::jsonrpc::JsonRpcClient;
 use starknet::providers::Provider;
@@ -14,11 +23,11 @@ use starknet::providers::ProviderError;
 use starknet::signers::SigningKey;
 use starknet::{accounts::SingleOwnerAccount, signers::LocalWallet};
 use std::str::FromStr;
+use std::sync::Arc;
 use std::time::Duration;
 use thiserror::Error;
 use tokio::time::sleep;
 use tracing::{debug, error, info, warn};
-use url::Url;
 
 // Define custom error types for the Starknet Relayer
 #[derive(Error, Debug)]
@@ -41,12 +50,19 @@ pub enum StarknetRelayerError {
     #[error("Invalid contract address")]
     InvalidContractAddress,
 
+    #[error("Invalid RPC URL: {0}")]
+    InvalidRpcUrl(#[from] url::ParseError),
+
     #[error("Transaction failed: {0}")]
     TransactionFailed(String),
 
     #[error("Transaction timeout")]
     TransactionTimeout,
 
+    /// The batch may still land, so its transactions must not be resubmitted one by one
+    #[error("Batch transaction {tx_hash} was submitted but not confirmed: {reason}")]
+    BatchUnconfirmed { tx_hash: Felt, reason: String },
+
     // ✅ Add these if they're used
     #[error("Selector parse failed")]
     SelectorParseFailed,
@@ -58,47 +74,378 @@ pub enum StarknetRelayerError {
     TimeoutError(String),
 }
 
+/// Whether retrying the operation that produced `err` could succeed
+///
+/// Errors the node reports about the account, the contract or the call itself (a missing
+/// entry point surfaces as a contract error) fail the same way on every attempt; rate limits,
+/// transport errors and timeouts are worth retrying.
+pub fn is_retriable(err: &StarknetRelayerError) -> bool {
+    match err {
+        StarknetRelayerError::Provider(ProviderError::StarknetError(e)) => !matches!(
+            e,
+            StarknetError::ContractNotFound
+                | StarknetError::NonAccount
+                | StarknetError::InsufficientAccountBalance
+                | StarknetError::ValidationFailure(_)
+                | StarknetError::ContractError(_)
+                | StarknetError::TransactionExecutionError(_)
+                | StarknetError::UnsupportedTxVersion
+        ),
+        StarknetRelayerError::Provider(ProviderError::ArrayLengthMismatch) => false,
+        StarknetRelayerError::ParseError(_)
+        | StarknetRelayerError::ProofDataMissing
+        | StarknetRelayerError::InvalidContractAddress
+        | StarknetRelayerError::InvalidRpcUrl(_)
+        | StarknetRelayerError::SelectorParseFailed => false,
+        _ => true,
+    }
+}
+
+/// Class hash of the OpenZeppelin account contract used when deriving account addresses
+pub const OZ_ACCOUNT_CLASS_HASH: &str =
+    "0x061dac032f228abef9c6626f995015233097ae253a7f72d68552db02f2971b8f";
+
+/// Class hash of the Argent X v0.3.0 account contract
+pub const ARGENT_X_ACCOUNT_CLASS_HASH: &str =
+    "0x01a736d6ed154502257f02b1ccdf4d9d1089f80811cd6acad48e6b6a9d1f2003";
+
+/// Account contract behind the relayer's address, which decides how it is derived
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum AccountType {
+    #[default]
+    OpenZeppelin,
+    ArgentX,
+}
+
+impl AccountType {
+    pub fn class_hash(&self) -> Result<Felt, StarknetRelayerError> {
+        let class_hash = match self {
+            AccountType::OpenZeppelin => OZ_ACCOUNT_CLASS_HASH,
+            AccountType::ArgentX => ARGENT_X_ACCOUNT_CLASS_HASH,
+        };
+        Ok(Felt::from_hex(class_hash)?)
+    }
+
+    /// Constructor arguments the wallet deploys the account with
+    pub fn constructor_calldata(&self, public_key: Felt) -> Vec<Felt> {
+        match self {
+            AccountType::OpenZeppelin => vec![public_key],
+            // Argent X takes the owner key followed by an (unset) guardian key
+            AccountType::ArgentX => vec![public_key, Felt::ZERO],
+        }
+    }
+}
+
+impl FromStr for AccountType {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "openzeppelin" | "oz" => Ok(AccountType::OpenZeppelin),
+            "argentx" | "argent" => Ok(AccountType::ArgentX),
+            other => Err(format!(
+                "Unknown account type '{}', expected openzeppelin or argentx",
+                other
+            )),
+        }
+    }
+}
+
 // Configuration for the Starknet Relayer
 #[derive(Debug, Clone)]
 pub struct StarknetRelayerConfig {
     pub bridge_contract_address: String,
     pub rpc_url: String,
+    /// Nodes tried in order once `rpc_url` stops answering
+    pub fallback_rpc_urls: Vec<String>,
     pub account_address: String,
+    /// Used to derive the address when `account_address` is empty
+    pub account_type: AccountType,
     pub private_key: String,
     pub max_retries: u32,
     pub retry_delay_ms: u64,
     pub transaction_timeout_ms: u64,
+    /// Transactions sent together in one multicall; 1 sends each on its own
+    pub max_batch_size: usize,
+}
+
+impl StarknetRelayerConfig {
+    /// Returns the configured `account_address`, or derives the address of an `account_type`
+    /// account for `private_key` when none is set.
+    ///
+    /// The derivation matches a counterfactual wallet deployment: the public key is the salt,
+    /// the deployer address is zero, and the constructor arguments depend on the account type.
+    pub fn effective_account_address(&self) -> Result<Felt, StarknetRelayerError> {
+        let account_address = self.account_address.trim();
+        if !account_address.is_empty() {
+            return Ok(Felt::from_hex(account_address)?);
+        }
+
+        let signing_key = SigningKey::from_secret_scalar(Felt::from_hex(&self.private_key)?);
+        let public_key = signing_key.verifying_key().scalar();
+
+        Ok(get_contract_address(
+            public_key,
+            self.account_type.class_hash()?,
+            &self.account_type.constructor_calldata(public_key),
+            Felt::ZERO,
+        ))
+    }
+}
+
+impl From<StarknetConfig> for StarknetRelayerConfig {
+    /// Reads the node from `STARKNET_RPC_URL`, panicking when it is not set
+    fn from(config: StarknetConfig) -> Self {
+        Self {
+            rpc_url: config.get_rpc_url(),
+            max_retries: config.max_retries(),
+            retry_delay_ms: config.retry_delay_ms(),
+            transaction_timeout_ms: config.transaction_timeout_ms(),
+            bridge_contract_address: config.contract_address,
+            fallback_rpc_urls: config.fallback_rpc_urls,
+            account_address: config.account_address,
+            account_type: AccountType::default(),
+            private_key: config.private_key.expose().to_string(),
+            max_batch_size: 1,
+        }
+    }
+}
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum BuilderError {
+    #[error("Missing required field: {0}")]
+    MissingField(&'static str),
+}
+
+/// Builds a `StarknetRelayerConfig`, defaulting every field except the contract, node, account
+/// and key
+#[derive(Debug, Clone, Default)]
+pub struct StarknetRelayerConfigBuilder {
+    bridge_contract_address: Option<String>,
+    rpc_url: Option<String>,
+    fallback_rpc_urls: Vec<String>,
+    account_address: Option<String>,
+    account_type: AccountType,
+    private_key: Option<String>,
+    max_retries: Option<u32>,
+    retry_delay_ms: Option<u64>,
+    transaction_timeout_ms: Option<u64>,
+    max_batch_size: Option<usize>,
+}
+
+impl StarknetRelayerConfig {
+    pub fn builder() -> StarknetRelayerConfigBuilder {
+        StarknetRelayerConfigBuilder::default()
+    }
+}
+
+impl StarknetRelayerConfigBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn bridge_contract_address(mut self, address: impl Into<String>) -> Self {
+        self.bridge_contract_address = Some(address.into());
+        self
+    }
+
+    pub fn rpc_url(mut self, rpc_url: impl Into<String>) -> Self {
+        self.rpc_url = Some(rpc_url.into());
+        self
+    }
+
+    pub fn fallback_rpc_urls(mut self, urls: Vec<String>) -> Self {
+        self.fallback_rpc_urls = urls;
+        self
+    }
+
+    /// An empty address derives it from `private_key` and `account_type`
+    pub fn account_address(mut self, address: impl Into<String>) -> Self {
+        self.account_address = Some(address.into());
+        self
+    }
+
+    pub fn account_type(mut self, account_type: AccountType) -> Self {
+        self.account_type = account_type;
+        self
+    }
+
+    pub fn private_key(mut self, private_key: impl Into<String>) -> Self {
+        self.private_key = Some(private_key.into());
+        self
+    }
+
+    pub fn max_retries(mut self, max_retries: u32) -> Self {
+        self.max_retries = Some(max_retries);
+        self
+    }
+
+    pub fn retry_delay_ms(mut self, retry_delay_ms: u64) -> Self {
+        self.retry_delay_ms = Some(retry_delay_ms);
+        self
+    }
+
+    pub fn transaction_timeout_ms(mut self, transaction_timeout_ms: u64) -> Self {
+        self.transaction_timeout_ms = Some(transaction_timeout_ms);
+        self
+    }
+
+    pub fn max_batch_size(mut self, max_batch_size: usize) -> Self {
+        self.max_batch_size = Some(max_batch_size);
+        self
+    }
+
+    pub fn build(self) -> Result<StarknetRelayerConfig, BuilderError> {
+        Ok(StarknetRelayerConfig {
+            bridge_contract_address: self
+                .bridge_contract_address
+                .ok_or(BuilderError::MissingField("bridge_contract_address"))?,
+            rpc_url: self.rpc_url.ok_or(BuilderError::MissingField("rpc_url"))?,
+            fallback_rpc_urls: self.fallback_rpc_urls,
+            account_address: self
+                .account_address
+                .ok_or(BuilderError::MissingField("account_address"))?,
+            account_type: self.account_type,
+            private_key: self
+                .private_key
+                .ok_or(BuilderError::MissingField("private_key"))?,
+            max_retries: self.max_retries.unwrap_or(3),
+            retry_delay_ms: self.retry_delay_ms.unwrap_or(5000),
+            transaction_timeout_ms: self.transaction_timeout_ms.unwrap_or(60000),
+            max_batch_size: self.max_batch_size.unwrap_or(1),
+        })
+    }
+}
+
+/// Outcome of running relay calls through `starknet_simulateTransactions`
+#[derive(Debug, Clone, Serialize)]
+pub struct SimulationResult {
+    /// Overall fee the transaction would be charged
+    pub fee_estimate: u64,
+    pub execution_result: ExecutionResult,
+    /// Events the calls would emit, in emission order; they carry no block or transaction hash
+    pub events: Vec<EmittedEvent>,
+}
+
+impl TryFrom<SimulatedTransaction> for SimulationResult {
+    type Error = StarknetRelayerError;
+
+    fn try_from(simulated: SimulatedTransaction) -> Result<Self, Self::Error> {
+        let fee_estimate = u64::try_from(simulated.fee_estimation.overall_fee).map_err(|_| {
+            StarknetRelayerError::TransactionFailed(format!(
+                "Fee estimate {} does not fit in a u64",
+                simulated.fee_estimation.overall_fee
+            ))
+        })?;
+
+        let execute_invocation = match simulated.transaction_trace {
+            TransactionTrace::Invoke(trace) => trace.execute_invocation,
+            _ => {
+                return Err(StarknetRelayerError::TransactionFailed(
+                    "Simulation returned a non-invoke trace".to_string(),
+                ))
+            }
+        };
+
+        let (execution_result, events) = match execute_invocation {
+            ExecuteInvocation::Success(invocation) => {
+                let mut ordered = Vec::new();
+                collect_events(&invocation, &mut ordered);
+                ordered.sort_by_key(|(order, _)| *order);
+                (
+                    ExecutionResult::Succeeded,
+                    ordered.into_iter().map(|(_, event)| event).collect(),
+                )
+            }
+            ExecuteInvocation::Reverted(reverted) => (
+                ExecutionResult::Reverted {
+                    reason: reverted.revert_reason,
+                },
+                Vec::new(),
+            ),
+        };
+
+        Ok(Self {
+            fee_estimate,
+            execution_result,
+            events,
+        })
+    }
+}
+
+/// Flattens the events of `invocation` and its nested calls, keyed by their order in the
+/// transaction
+fn collect_events(invocation: &FunctionInvocation, events: &mut Vec<(u64, EmittedEvent)>) {
+    for event in &invocation.events {
+        events.push((
+            event.order,
+            EmittedEvent {
+                from_address: invocation.contract_address,
+                keys: event.keys.clone(),
+                data: event.data.clone(),
+                block_hash: None,
+                block_number: None,
+                transaction_hash: Felt::ZERO,
+            },
+        ));
+    }
+    for call in &invocation.calls {
+        collect_events(call, events);
+    }
 }
 
 // The main Starknet Relayer struct
 pub struct StarknetRelayer {
     db_pool: Pool<Postgres>,
     config: StarknetRelayerConfig,
-    account: SingleOwnerAccount<JsonRpcClient<HttpTransport>, LocalWallet>,
+    provider_pool: Arc<ProviderPool>,
+    providers: RoundRobinProvider,
+    signer: LocalWallet,
+    address: Felt,
 }
 
 impl StarknetRelayer {
+    /// `provider_pool` can be shared between relayers to bound their in-flight RPC requests.
+    /// Requests go to `rpc_url`, falling back to `fallback_rpc_urls` when it is unavailable.
     pub async fn new(
         db_pool: Pool<Postgres>,
         config: StarknetRelayerConfig,
+        provider_pool: Arc<ProviderPool>,
     ) -> Result<Self, StarknetRelayerError> {
-        let provider = JsonRpcClient::new(HttpTransport::new(
-            Url::parse(&config.rpc_url.clone()).unwrap(),
-        ));
+        let providers = RoundRobinProvider::new(&config.rpc_url, &config.fallback_rpc_urls)?;
         let signer: LocalWallet = LocalWallet::from(SigningKey::from_secret_scalar(
             Felt::from_hex(&config.private_key).unwrap(),
         ));
-        let chain_id = MAINNET;
-        let address = Felt::from_hex(&config.account_address).unwrap();
-        let account =
-            SingleOwnerAccount::new(provider, signer, address, chain_id, ExecutionEncoding::New);
+        let address = config.effective_account_address()?;
         Ok(Self {
             db_pool,
             config,
-            account,
+            provider_pool,
+            providers,
+            signer,
+            address,
         })
     }
 
+    /// The RPC nodes this relayer sends requests to
+    pub fn providers(&self) -> &RoundRobinProvider {
+        &self.providers
+    }
+
+    /// The relayer's account, sending through `provider`
+    fn account(
+        &self,
+        provider: Arc<JsonRpcClient<HttpTransport>>,
+    ) -> SingleOwnerAccount<Arc<JsonRpcClient<HttpTransport>>, LocalWallet> {
+        SingleOwnerAccount::new(
+            provider,
+            self.signer.clone(),
+            self.address,
+            MAINNET,
+            ExecutionEncoding::New,
+        )
+    }
+
     // Main function to start the relayer process
     pub async fn start(&self) -> Result<(), StarknetRelayerError> {
         info!("Starting Starknet Relayer service");
@@ -129,14 +476,39 @@ impl StarknetRelayer {
         // Fetch all transactions marked as "ready for relay"
         let transactions = self.fetch_ready_transactions().await?;
 
-        for mut tx in transactions {
-            match self.process_transaction(&mut tx).await {
-                Ok(_) => {
-                    processed_count += 1;
+        for batch in transactions.chunks(self.config.max_batch_size.max(1)) {
+            if batch.len() > 1 {
+                match self.batch_process_transactions(batch).await {
+                    Ok(_) => {
+                        processed_count += batch.len();
+                        continue;
+                    }
+                    Err(e @ StarknetRelayerError::BatchUnconfirmed { .. }) => {
+                        error!("Failed to process batch of {}: {:?}", batch.len(), e);
+                        for tx in batch {
+                            self.mark_transaction_failed(tx, &e.to_string()).await?;
+                        }
+                        continue;
+                    }
+                    // Nothing landed, so each transaction is retried alone to find the bad one
+                    Err(e) => warn!(
+                        "Batch of {} transactions failed, resubmitting individually: {:?}",
+                        batch.len(),
+                        e
+                    ),
                 }
-                Err(e) => {
-                    error!("Failed to process transaction {}: {:?}", tx.id, e);
-                    self.mark_transaction_failed(&tx, &e.to_string()).await?;
+            }
+
+            for tx in batch {
+                let mut tx = tx.clone();
+                match self.process_transaction(&mut tx).await {
+                    Ok(_) => {
+                        processed_count += 1;
+                    }
+                    Err(e) => {
+                        error!("Failed to process transaction {}: {:?}", tx.id, e);
+                        self.mark_transaction_failed(&tx, &e.to_string()).await?;
+                    }
                 }
             }
         }
@@ -144,6 +516,56 @@ impl StarknetRelayer {
         Ok(processed_count)
     }
 
+    /// Relays `txs` in a single multicall and marks them all completed with its hash.
+    ///
+    /// Fails before sending if any transaction lacks valid proof data. A revert is returned as
+    /// `TransactionFailed`, meaning none of the calls took effect; any other failure after the
+    /// batch was sent is `BatchUnconfirmed`.
+    pub async fn batch_process_transactions(
+        &self,
+        txs: &[L2Transaction],
+    ) -> Result<Felt, StarknetRelayerError> {
+        info!("Processing batch of {} L2 transactions", txs.len());
+
+        let mut calls = Vec::new();
+        for tx in txs {
+            let proof_data = tx
+                .proof_data
+                .as_deref()
+                .ok_or(StarknetRelayerError::ProofDataMissing)?;
+            calls.extend(self.build_relay_calls(tx.id, proof_data)?);
+        }
+
+        for tx in txs {
+            self.mark_transaction_processing(tx).await?;
+        }
+
+        let tx_hash = self.send_calls(calls).await?;
+
+        match self.wait_for_transaction_confirmation(tx_hash).await {
+            Ok(()) => {}
+            Err(e @ StarknetRelayerError::TransactionFailed(_)) => return Err(e),
+            Err(e) => {
+                return Err(StarknetRelayerError::BatchUnconfirmed {
+                    tx_hash,
+                    reason: e.to_string(),
+                })
+            }
+        }
+
+        for tx in txs {
+            self.mark_transaction_completed(tx, &tx_hash.to_string())
+                .await?;
+        }
+        info!(
+            "Batch of {} transactions processed on Starknet (hash: {})",
+            txs.len(),
+            tx_hash
+        );
+
+        Ok(tx_hash)
+    }
+
     // Fetch transactions marked as "ready for relay"
     pub async fn fetch_ready_transactions(
         &self,
@@ -153,7 +575,7 @@ impl StarknetRelayer {
             r#"
                 SELECT * FROM l2_transactions
                 WHERE status = 'ready_for_relay'
-                ORDER BY created_at ASC
+                ORDER BY priority DESC, created_at ASC
                 LIMIT 10
                 "#
         )
@@ -172,7 +594,7 @@ impl StarknetRelayer {
         info!("Processing L2 transaction {}", &tx.id);
 
         // Mark transaction as processing
-        self.mark_transaction_processing(&tx).await?;
+        self.mark_transaction_processing(tx).await?;
 
         // Extract proof data from the transaction
         let proof_data = tx
@@ -193,7 +615,7 @@ impl StarknetRelayer {
                     match self.wait_for_transaction_confirmation(tx_hash).await {
                         Ok(_) => {
                             // Mark transaction as completed
-                            self.mark_transaction_completed(&tx, &tx_hash.to_string())
+                            self.mark_transaction_completed(tx, &tx_hash.to_string())
                                 .await?;
                             info!(
                                 "Transaction {} successfully processed on Starknet (hash: {})",
@@ -207,7 +629,7 @@ impl StarknetRelayer {
                                 tx.id, e
                             );
 
-                            if attempts >= max_retries {
+                            if attempts >= max_retries || !is_retriable(&e) {
                                 return Err(e);
                             }
                         }
@@ -219,6 +641,13 @@ impl StarknetRelayer {
                         tx.id, attempts, max_retries, e
                     );
 
+                    if !is_retriable(&e) {
+                        warn!(
+                            "Transaction {} failed with a permanent error, not retrying",
+                            tx.id
+                        );
+                        return Err(e);
+                    }
                     if attempts >= max_retries {
                         return Err(e);
                     }
@@ -231,20 +660,17 @@ impl StarknetRelayer {
         }
     }
 
-    // Relay transaction to Starknet
-    pub async fn relay_to_starknet(
+    /// Builds the `process_withdrawal` call for withdrawal `withdrawal_id` from its JSON proof data
+    pub fn build_relay_calls(
         &self,
-        tx: &L2Transaction,
+        withdrawal_id: i64,
         proof_data: &str,
-    ) -> Result<Felt, StarknetRelayerError> {
+    ) -> Result<Vec<Call>, StarknetRelayerError> {
         // Parse proof data from JSON
         let proof: serde_json::Value = serde_json::from_str(proof_data).map_err(|e| {
             StarknetRelayerError::TransactionFailed(format!("Invalid proof data: {}", e))
         })?;
 
-        // Extract withdrawal ID from transaction
-        let withdrawal_id = tx.id.clone();
-
         // Extract proof array and merkle root from proof data
         let proof_array = match proof.get("proof") {
             Some(array) if array.is_array() => {
@@ -300,15 +726,27 @@ impl StarknetRelayer {
             .map_err(|_| StarknetRelayerError::InvalidContractAddress)?;
 
         // Create the call
-        use starknet::core::types::Call;
         use starknet::macros::selector;
 
-        let calls = vec![Call {
+        Ok(vec![Call {
             to: contract_address,
             selector: selector!("process_withdrawal"),
             calldata,
-        }];
+        }])
+    }
+
+    // Relay transaction to Starknet
+    pub async fn relay_to_starknet(
+        &self,
+        tx: &L2Transaction,
+        proof_data: &str,
+    ) -> Result<Felt, StarknetRelayerError> {
+        let calls = self.build_relay_calls(tx.id, proof_data)?;
+        self.send_calls(calls).await
+    }
 
+    /// Sends `calls` from the relayer's account in one transaction, returning its hash
+    async fn send_calls(&self, calls: Vec<Call>) -> Result<Felt, StarknetRelayerError> {
         // Execute the transaction
         info!(
             "Sending transaction to Starknet contract: {}",
@@ -316,7 +754,9 @@ impl StarknetRelayer {
         );
 
         // Execute the call and get the transaction hash
-        let result = match self.account.execute_v3(calls).send().await {
+        let _connection = self.provider_pool.acquire().await;
+        let (provider_index, provider) = self.providers.active();
+        let result = match self.account(provider).execute_v3(calls).send().await {
             Ok(result) => {
                 info!(
                     "Transaction sent successfully with hash: {}",
@@ -324,6 +764,15 @@ impl StarknetRelayer {
                 );
                 result.transaction_hash
             }
+            // Keep the node's error so the retry loop can tell permanent failures apart
+            Err(AccountError::Provider(e)) => {
+                error!("Failed to send transaction: {:?}", e);
+                // Resending blindly could submit twice, so the retry loop picks up the next node
+                if is_transient(&e) {
+                    self.providers.fail_over(provider_index);
+                }
+                return Err(StarknetRelayerError::Provider(e));
+            }
             Err(e) => {
                 error!("Failed to send transaction: {:?}", e);
                 return Err(StarknetRelayerError::TransactionFailed(format!(
@@ -336,6 +785,38 @@ impl StarknetRelayer {
         Ok(result)
     }
 
+    /// Runs `calls` through `starknet_simulateTransactions` with `SKIP_VALIDATE`, so operators
+    /// can see the fee and outcome of a relay without sending it
+    pub async fn simulate_transaction(
+        &self,
+        calls: Vec<Call>,
+    ) -> Result<SimulationResult, StarknetRelayerError> {
+        let _connection = self.provider_pool.acquire().await;
+        let (provider_index, provider) = self.providers.active();
+        let simulated = match self
+            .account(provider)
+            .execute_v3(calls)
+            .simulate(true, false)
+            .await
+        {
+            Ok(simulated) => simulated,
+            Err(AccountError::Provider(e)) => {
+                if is_transient(&e) {
+                    self.providers.fail_over(provider_index);
+                }
+                return Err(StarknetRelayerError::Provider(e));
+            }
+            Err(e) => {
+                return Err(StarknetRelayerError::TransactionFailed(format!(
+                    "Failed to simulate transaction: {}",
+                    e
+                )))
+            }
+        };
+
+        simulated.try_into()
+    }
+
     // Wait for transaction confirmation
 
     pub async fn wait_for_transaction_confirmation(
@@ -353,12 +834,14 @@ impl StarknetRelayer {
                 ));
             }
 
-            match self
-                .account
-                .provider()
-                .get_transaction_receipt(tx_hash)
-                .await
-            {
+            let receipt = {
+                let _connection = self.provider_pool.acquire().await;
+                self.providers
+                    .call(|provider| async move { provider.get_transaction_receipt(tx_hash).await })
+                    .await
+            };
+
+            match receipt {
                 Ok(receipt) => {
                     match receipt.receipt {
                         TransactionReceipt::Invoke(receipt) => match receipt.execution_result {
@@ -1,5 +1,9 @@
-use starknet_crypto::{poseidon_hash, Felt, PoseidonHasher};
+use alloy_primitives::U256;
+use blake2::Blake2s256;
+use starknet_crypto::{pedersen_hash, poseidon_hash, Felt, PoseidonHasher};
+use serde::Serializer;
 use sha3::{Digest, Keccak256};
+use thiserror::Error;
 
 /// Data structure representing the burn data to be hashed
 #[derive(Debug, Clone)]
@@ -20,6 +24,26 @@ impl BurnData {
         }
     }
 
+    /// Maps the `user`, `usdVal` and `nonce` of an L1 `DepositEvent` onto the hashed fields.
+    /// The event does not carry the block timestamp, so `time_stamp` is 0 until set with
+    /// [`BurnData::with_timestamp`].
+    pub fn from_deposit(user: &[u8], usd_val: U256, nonce: U256) -> Result<Self, BurnDataError> {
+        // `abi.encodePacked` widens the address to a left-padded 32-byte word
+        let caller = format!("0x{:0>64}", hex::encode(user));
+        let amount = u64::try_from(usd_val)
+            .map_err(|_| BurnDataError::AmountOverflow(usd_val.to_string()))?;
+        let nonce =
+            u64::try_from(nonce).map_err(|_| BurnDataError::NonceOverflow(nonce.to_string()))?;
+
+        Ok(Self::new(caller, amount, nonce, 0))
+    }
+
+    /// Sets the timestamp of the block the event was emitted in
+    pub fn with_timestamp(mut self, time_stamp: u64) -> Self {
+        self.time_stamp = time_stamp;
+        self
+    }
+
     /// Computes the Keccak256 commitment hash for burn/withdrawal data
     /// This replicates Solidity's keccak256(abi.encodePacked(...)) behavior
     ///
@@ -112,7 +136,12 @@ impl MintData {
     }
 }
 
-/// Poseidon hash methods that can be used for computing commitment hashes
+/// Domain prefixed to deposit commitment hashes
+pub const DEPOSIT_HASH_DOMAIN: u64 = 1;
+/// Domain prefixed to withdrawal commitment hashes
+pub const WITHDRAWAL_HASH_DOMAIN: u64 = 2;
+
+/// Hash methods that can be used for computing commitment hashes
 pub enum HashMethod {
     /// Uses the stateful hasher to hash all elements at once (recommended for efficiency)
     BatchHash,
@@ -120,6 +149,10 @@ pub enum HashMethod {
     /// Uses sequential pairwise hashing, similar to:
     /// poseidon_hash(poseidon_hash(poseidon_hash(a, b), c), d)
     SequentialPairwise,
+
+    /// Blake2s-256 over the elements packed as little-endian 32-byte words,
+    /// keeping the 248 least significant bits (as in the `blake2s_248_lsb` hasher)
+    Blake2s,
 }
 
 /// Computes a Poseidon hash over the given inputs to create a deposit commitment hash
@@ -135,6 +168,9 @@ pub enum HashMethod {
 /// * `nonce` - Transaction nonce
 /// * `timestamp` - Block timestamp
 /// * `method` - The hashing method to use (batch or sequential pairwise)
+/// * `domain` - Prepended as the first hashed element so hashes for different use cases
+///   (e.g. [`DEPOSIT_HASH_DOMAIN`], [`WITHDRAWAL_HASH_DOMAIN`]) never collide; `None`
+///   hashes the inputs alone, as the deployed bridge contracts do
 ///
 /// # Returns
 /// A `Felt` (felt252) representing the Poseidon hash of the input values
@@ -144,9 +180,13 @@ pub fn compute_poseidon_commitment_hash(
     nonce: u64,
     timestamp: u64,
     method: HashMethod,
+    domain: Option<u64>,
 ) -> Felt {
     let mint_data = MintData::new(recipient, amount, nonce, timestamp);
-    let field_elements = mint_data.to_field_elements();
+    let mut field_elements = mint_data.to_field_elements();
+    if let Some(domain) = domain {
+        field_elements.insert(0, Felt::from(domain));
+    }
 
     match method {
         HashMethod::BatchHash => {
@@ -171,12 +211,128 @@ pub fn compute_poseidon_commitment_hash(
 
             result
         }
+        HashMethod::Blake2s => blake2s_hash_elements(&field_elements),
+    }
+}
+
+/// Computes a Blake2s deposit commitment hash for layouts that use Blake2s instead of Poseidon.
+///
+/// Hashes `recipient ++ amount ++ nonce ++ timestamp`, each packed as a little-endian
+/// 32-byte array, and truncates the digest to 248 bits so it always fits in a felt252.
+pub fn compute_blake2s_commitment_hash(
+    recipient: Felt,
+    amount: u128,
+    nonce: u64,
+    timestamp: u64,
+) -> Felt {
+    let mint_data = MintData::new(recipient, amount, nonce, timestamp);
+    blake2s_hash_elements(&mint_data.to_field_elements())
+}
+
+fn blake2s_hash_elements(elements: &[Felt]) -> Felt {
+    let mut hasher = Blake2s256::new();
+    for element in elements {
+        hasher.update(element.to_bytes_le());
+    }
+
+    let mut digest: [u8; 32] = hasher.finalize().into();
+    // Drop the most significant byte of the little-endian digest
+    digest[31] = 0;
+
+    Felt::from_bytes_le(&digest)
+}
+
+/// Computes the hash of a Cairo program from its bytecode.
+///
+/// Mirrors Cairo's `compute_hash_on_elements`: the elements are folded with Pedersen
+/// starting from zero, and the result is hashed once more with the element count.
+pub fn compute_program_hash(bytecode: &[Felt]) -> Felt {
+    let chained = bytecode
+        .iter()
+        .fold(Felt::ZERO, |acc, element| pedersen_hash(&acc, element));
+
+    pedersen_hash(&chained, &Felt::from(bytecode.len()))
+}
+
+/// Computes the fact hash registered by the Cairo Verifier for a proven program.
+///
+/// Matches the verifier's `compute_fact`: `pedersen(program_hash, output_hash)`.
+pub fn calculate_fact_hash(program_hash: Felt, output_hash: Felt) -> Felt {
+    pedersen_hash(&program_hash, &output_hash)
+}
+
+/// Hex digits of a normalized commitment hash, one 32-byte word
+pub const COMMITMENT_HASH_HEX_LEN: usize = 64;
+
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum BurnDataError {
+    #[error("Amount {0} does not fit in a u64")]
+    AmountOverflow(String),
+
+    #[error("Nonce {0} does not fit in a u64")]
+    NonceOverflow(String),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum HashNormalizationError {
+    /// Not hex, empty, or longer than 32 bytes
+    #[error("Invalid commitment hash: {0}")]
+    InvalidHex(String),
+}
+
+/// Form commitment hashes are stored and looked up in: no `0x` prefix, lowercase and
+/// left-padded with zeros to [`COMMITMENT_HASH_HEX_LEN`] digits, so one hash always matches
+/// itself however it was written.
+pub fn normalize_commitment_hash(hash: &str) -> Result<String, HashNormalizationError> {
+    let digits = hash
+        .strip_prefix("0x")
+        .or_else(|| hash.strip_prefix("0X"))
+        .unwrap_or(hash);
+    if digits.is_empty()
+        || digits.len() > COMMITMENT_HASH_HEX_LEN
+        || !digits.chars().all(|c| c.is_ascii_hexdigit())
+    {
+        return Err(HashNormalizationError::InvalidHex(hash.to_string()));
+    }
+
+    Ok(format!(
+        "{:0>width$}",
+        digits.to_ascii_lowercase(),
+        width = COMMITMENT_HASH_HEX_LEN
+    ))
+}
+
+/// Form commitment hashes are returned in by the API: the stored form with a `0x` prefix.
+/// Values that don't normalize are returned unchanged.
+pub fn prefixed_commitment_hash(hash: &str) -> String {
+    normalize_commitment_hash(hash)
+        .map(|normalized| format!("0x{}", normalized))
+        .unwrap_or_else(|_| hash.to_string())
+}
+
+/// Serializes a stored commitment hash in its API form, see [`prefixed_commitment_hash`]
+pub fn serialize_prefixed_commitment_hash<S: Serializer>(
+    hash: &str,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    serializer.serialize_str(&prefixed_commitment_hash(hash))
+}
+
+/// [`serialize_prefixed_commitment_hash`] for a hash that may be missing
+pub fn serialize_optional_prefixed_commitment_hash<S: Serializer>(
+    hash: &Option<String>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    match hash {
+        Some(hash) => serialize_prefixed_commitment_hash(hash, serializer),
+        None => serializer.serialize_none(),
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use alloy::primitives::Address;
     use starknet_crypto::poseidon_hash_many;
 
     #[test]
@@ -192,6 +348,7 @@ mod tests {
             nonce,
             timestamp,
             HashMethod::BatchHash,
+            None,
         );
         let hash2 = compute_poseidon_commitment_hash(
             recipient,
@@ -199,6 +356,7 @@ mod tests {
             nonce,
             timestamp,
             HashMethod::BatchHash,
+            None,
         );
 
         assert_eq!(hash1, hash2, "Hash function should be deterministic");
@@ -217,6 +375,7 @@ mod tests {
             nonce,
             timestamp,
             HashMethod::BatchHash,
+            None,
         );
 
         let elements = vec![
@@ -246,6 +405,7 @@ mod tests {
             nonce,
             timestamp,
             HashMethod::BatchHash,
+            None,
         );
 
         let elements = vec![
@@ -275,6 +435,7 @@ mod tests {
             nonce,
             timestamp,
             HashMethod::SequentialPairwise,
+            None,
         );
 
         let a = recipient;
@@ -297,6 +458,7 @@ mod tests {
             nonce,
             timestamp,
             HashMethod::BatchHash,
+            None,
         );
 
         assert_ne!(
@@ -305,6 +467,71 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_hash_domains_are_distinct() {
+        let recipient = Felt::from_dec_str("123456789012345678901234567890").unwrap();
+        // Domain 0 is prepended like any other, so it differs from hashing without one
+        let domains = [
+            None,
+            Some(0),
+            Some(DEPOSIT_HASH_DOMAIN),
+            Some(WITHDRAWAL_HASH_DOMAIN),
+            Some(3),
+            Some(u64::MAX),
+        ];
+
+        let methods: [fn() -> HashMethod; 3] = [
+            || HashMethod::BatchHash,
+            || HashMethod::SequentialPairwise,
+            || HashMethod::Blake2s,
+        ];
+
+        for method in methods {
+            let hashes: Vec<Felt> = domains
+                .iter()
+                .map(|&domain| {
+                    compute_poseidon_commitment_hash(
+                        recipient,
+                        1000000,
+                        42,
+                        1650000000,
+                        method(),
+                        domain,
+                    )
+                })
+                .collect();
+
+            for (i, a) in hashes.iter().enumerate() {
+                for b in &hashes[i + 1..] {
+                    assert_ne!(a, b, "hashes for different domains must differ");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_hash_domain_is_first_element() {
+        let recipient = Felt::from_dec_str("123456789012345678901234567890").unwrap();
+
+        let hash = compute_poseidon_commitment_hash(
+            recipient,
+            1000000,
+            42,
+            1650000000,
+            HashMethod::BatchHash,
+            Some(WITHDRAWAL_HASH_DOMAIN),
+        );
+
+        let expected = poseidon_hash_many(&[
+            Felt::from(WITHDRAWAL_HASH_DOMAIN),
+            recipient,
+            Felt::from(1000000u128),
+            Felt::from(42u64),
+            Felt::from(1650000000u64),
+        ]);
+        assert_eq!(hash, expected);
+    }
+
     #[test]
     fn test_commitment_hash_from_burn_data() {
         let data = BurnData {
@@ -331,4 +558,149 @@ mod tests {
         let expected = "0x2b6876060a11edcc5dde925cda8fad185f34564e35802fa40ee8ead2f9acb06f";
         assert_eq!(hex_hash, expected);
     }
+
+    fn burn_data(usd_val: U256, nonce: U256) -> Result<BurnData, BurnDataError> {
+        BurnData::from_deposit(Address::from([0xbb; 20]).as_slice(), usd_val, nonce)
+    }
+
+    #[test]
+    fn test_burn_data_from_deposit() {
+        let data = burn_data(U256::from(50000u64), U256::from(123u64)).unwrap();
+        assert_eq!(
+            data.caller,
+            "0x000000000000000000000000bbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbb"
+        );
+        assert_eq!((data.amount, data.nonce, data.time_stamp), (50000, 123, 0));
+
+        // keccak256(abi.encodePacked(uint256(uint160(user)), usdVal, nonce, block.timestamp))
+        let data = data.with_timestamp(1672531200);
+        assert_eq!(
+            data.hash_to_hex_string(),
+            "0xb6ed03aef8976c9e98546770b6e4582cc0e58818d67334d611f999515290d064"
+        );
+    }
+
+    #[test]
+    fn test_burn_data_from_deposit_rejects_overflow() {
+        let too_large = U256::from(u64::MAX) + U256::from(1u8);
+
+        assert_eq!(
+            burn_data(too_large, U256::from(1u8)).unwrap_err(),
+            BurnDataError::AmountOverflow("18446744073709551616".to_string())
+        );
+        assert_eq!(
+            burn_data(U256::from(1u8), too_large).unwrap_err(),
+            BurnDataError::NonceOverflow("18446744073709551616".to_string())
+        );
+        assert!(burn_data(U256::from(u64::MAX), U256::from(u64::MAX)).is_ok());
+    }
+
+    #[test]
+    fn test_calculate_fact_hash_known_value() {
+        // Pedersen reference vector used by the Starknet fact registry tooling
+        let program_hash =
+            Felt::from_hex("0x03d937c035c878245caf64531a5756109c53068da139362728feb561405371cb")
+                .unwrap();
+        let output_hash =
+            Felt::from_hex("0x0208a0a10250e382e1e4bbe2880906c2791bf6275695e02fbbc6aeff9cd8b31a")
+                .unwrap();
+
+        let fact_hash = calculate_fact_hash(program_hash, output_hash);
+
+        assert_eq!(
+            fact_hash,
+            Felt::from_hex("0x030e480bed5fe53fa909cc0f8c4d99b8f9f2c016be4c41e13a4848797979c662")
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn test_compute_program_hash_chains_elements() {
+        // An empty program hashes to pedersen(0, 0)
+        assert_eq!(
+            compute_program_hash(&[]),
+            Felt::from_hex("0x049ee3eba8c1600700ee1b87eb599f16716b0b1022947733551fde4050ca6804")
+                .unwrap()
+        );
+
+        let bytecode = vec![Felt::from(1u8), Felt::from(2u8), Felt::from(3u8)];
+        let expected = pedersen_hash(
+            &pedersen_hash(
+                &pedersen_hash(&pedersen_hash(&Felt::ZERO, &bytecode[0]), &bytecode[1]),
+                &bytecode[2],
+            ),
+            &Felt::from(3u8),
+        );
+        assert_eq!(compute_program_hash(&bytecode), expected);
+    }
+
+    #[test]
+    fn test_blake2s_commitment_hash_reference_value() {
+        let recipient = Felt::from_dec_str("123456789012345678901234567890").unwrap();
+
+        let hash = compute_poseidon_commitment_hash(
+            recipient,
+            1000000,
+            42,
+            1650000000,
+            HashMethod::Blake2s,
+            None,
+        );
+
+        // Computed independently with Python's hashlib.blake2s over the four elements as
+        // 32-byte little-endian words, clearing the last byte of the digest before reading it
+        // back as a little-endian integer
+        let expected =
+            Felt::from_hex("0x1baf371ab03a72c6dfde0ab4ef215dc955ad806ac6e6cdd43e4df87d20d1dd")
+                .unwrap();
+        assert_eq!(hash, expected);
+        assert_eq!(
+            compute_blake2s_commitment_hash(recipient, 1000000, 42, 1650000000),
+            expected
+        );
+    }
+
+    #[test]
+    fn test_normalize_commitment_hash_variants_agree() {
+        let canonical = format!("{}abc123", "0".repeat(58));
+
+        for input in [
+            "0xabc123",
+            "0XABC123",
+            "abc123",
+            "ABC123",
+            "0x0000abc123",
+            canonical.as_str(),
+        ] {
+            assert_eq!(
+                normalize_commitment_hash(input).unwrap(),
+                canonical,
+                "{}",
+                input
+            );
+        }
+    }
+
+    #[test]
+    fn test_normalize_commitment_hash_rejects_non_hex() {
+        let too_long = "1".repeat(COMMITMENT_HASH_HEX_LEN + 1);
+        for input in ["", "0x", "0xcommitment123", "0x12 34", too_long.as_str()] {
+            assert_eq!(
+                normalize_commitment_hash(input),
+                Err(HashNormalizationError::InvalidHex(input.to_string()))
+            );
+        }
+
+        let full = "f".repeat(COMMITMENT_HASH_HEX_LEN);
+        assert_eq!(normalize_commitment_hash(&full).unwrap(), full);
+    }
+
+    #[test]
+    fn test_prefixed_commitment_hash_adds_prefix_to_stored_form() {
+        let prefixed = format!("0x{}abc123", "0".repeat(58));
+
+        assert_eq!(prefixed_commitment_hash(&prefixed[2..]), prefixed);
+        assert_eq!(prefixed_commitment_hash("0XABC123"), prefixed);
+        assert_eq!(prefixed_commitment_hash("not hex"), "not hex");
+    }
 }
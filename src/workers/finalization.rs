@@ -0,0 +1,70 @@
+use alloy_primitives::U64;
+use alloy_rpc_client::{ClientBuilder, RpcClient};
+use sqlx::PgPool;
+use std::time::Duration;
+use thiserror::Error;
+use tokio::time::sleep;
+use tracing::{debug, error, info};
+use url::Url;
+
+use crate::db::database::finalize_deposits;
+
+pub const FINALIZATION_POLL_INTERVAL: Duration = Duration::from_secs(15);
+
+#[derive(Debug, Error)]
+pub enum FinalizationError {
+    #[error("Database error: {0}")]
+    Database(#[from] sqlx::Error),
+
+    #[error("Ethereum RPC error: {0}")]
+    Rpc(String),
+}
+
+/// Latest Ethereum block number as reported by `eth_blockNumber`
+pub async fn ethereum_block_number(client: &RpcClient) -> Result<u64, FinalizationError> {
+    let block: U64 = client
+        .request_noparams("eth_blockNumber")
+        .await
+        .map_err(|e| FinalizationError::Rpc(e.to_string()))?;
+    Ok(block.to::<u64>())
+}
+
+/// Releases deposits whose proof has cleared the challenge window.
+///
+/// Deposits move `PROOF_SUBMITTED` -> `READY_TO_CLAIM` once Ethereum reaches the
+/// `finalization_block` recorded when their proof job completed.
+pub struct FinalizationWatcher {
+    db_pool: PgPool,
+    client: RpcClient,
+    interval: Duration,
+}
+
+impl FinalizationWatcher {
+    pub fn new(db_pool: PgPool, ethereum_rpc_url: Url) -> Self {
+        Self {
+            db_pool,
+            client: ClientBuilder::default().http(ethereum_rpc_url),
+            interval: FINALIZATION_POLL_INTERVAL,
+        }
+    }
+
+    /// Runs the polling loop until the task is dropped.
+    pub async fn run(&self) {
+        loop {
+            match self.finalize_ready_deposits().await {
+                Ok(ids) if !ids.is_empty() => {
+                    info!("Marked {} deposits as READY_TO_CLAIM", ids.len())
+                }
+                Ok(_) => debug!("No deposits past their challenge window"),
+                Err(e) => error!("Failed to finalize deposits: {:?}", e),
+            }
+            sleep(self.interval).await;
+        }
+    }
+
+    /// Runs a single check against the current Ethereum block, returning the released deposit ids.
+    pub async fn finalize_ready_deposits(&self) -> Result<Vec<i32>, FinalizationError> {
+        let current_block = ethereum_block_number(&self.client).await?;
+        Ok(finalize_deposits(&self.db_pool, current_block).await?)
+    }
+}
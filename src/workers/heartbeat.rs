@@ -0,0 +1,46 @@
+use sqlx::PgPool;
+use std::time::Duration;
+use tokio::time::sleep;
+use tracing::{debug, error};
+
+use crate::db::database::upsert_heartbeat;
+
+pub const HEARTBEAT_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Periodically records that this sequencer instance is alive in `sequencer_heartbeat`.
+///
+/// `GET /health` reports the instance as degraded once the row stops being refreshed.
+pub struct HeartbeatWriter {
+    db_pool: PgPool,
+    instance_id: String,
+    interval: Duration,
+}
+
+impl HeartbeatWriter {
+    pub fn new(db_pool: PgPool, instance_id: String) -> Self {
+        Self {
+            db_pool,
+            instance_id,
+            interval: HEARTBEAT_INTERVAL,
+        }
+    }
+
+    /// Runs the heartbeat loop until the task is dropped.
+    pub async fn run(&self) {
+        loop {
+            match self.beat().await {
+                Ok(()) => debug!("Heartbeat recorded for instance {}", self.instance_id),
+                Err(e) => error!(
+                    "Failed to record heartbeat for instance {}: {:?}",
+                    self.instance_id, e
+                ),
+            }
+            sleep(self.interval).await;
+        }
+    }
+
+    /// Records a single heartbeat, creating the instance row on first use.
+    pub async fn beat(&self) -> Result<(), sqlx::Error> {
+        upsert_heartbeat(&self.db_pool, &self.instance_id).await
+    }
+}
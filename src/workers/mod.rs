@@ -0,0 +1,4 @@
+pub mod finalization;
+pub mod heartbeat;
+pub mod proof_generation;
+pub mod registry;
@@ -0,0 +1,287 @@
+use alloy_primitives::U256;
+use sqlx::PgPool;
+use std::path::Path;
+use std::time::Duration;
+use thiserror::Error;
+use tokio::time::sleep;
+use tracing::{error, info, warn};
+
+use crate::{
+    config::{ConfigError, HerodotusConfig},
+    db::database::{
+        fetch_withdrawals_by_status, record_system_stat_sample, record_withdrawal_proof_submission,
+        set_withdrawal_atlantic_job, store_withdrawal_proof, update_withdrawal_proof_status,
+        update_withdrawal_status, Withdrawal,
+    },
+    http::client::{
+        atlantic_job_result, atlantic_job_status, submit_sharp_proof_job, AtlanticError,
+        AtlanticProof,
+    },
+};
+
+pub const STATUS_PENDING_PROOF: &str = "pending_proof";
+pub const STATUS_PROOF_SUBMITTED: &str = "proof_submitted";
+pub const STATUS_READY_FOR_RELAY: &str = "ready_for_relay";
+pub const STATUS_PROOF_FAILED: &str = "proof_failed";
+
+/// `withdrawal_proofs` status of a verified proof; the Ethereum relayer only relays these
+pub const PROOF_STATUS_READY: &str = "ready";
+/// `system_stats` key of the average seconds from Atlantic submission to a verified proof
+pub const ATLANTIC_JOB_DURATION_STAT: &str = "atlantic_job_duration_seconds";
+
+/// Proofs generated for withdrawals are verified on L1
+const PROOF_DIRECTION: &str = "PROOF_VERIFICATION_ON_L1";
+const BATCH_SIZE: i64 = 10;
+/// Recorded as the actor of the withdrawal status changes this worker makes
+const WORKER_ACTOR: &str = "proof_generation_worker";
+
+#[derive(Debug, Error)]
+pub enum ProofGenerationError {
+    #[error("Database error: {0}")]
+    Database(#[from] sqlx::Error),
+
+    #[error("Atlantic API error: {0}")]
+    Atlantic(#[from] anyhow::Error),
+
+    #[error("Atlantic query submission failed: {0}")]
+    AtlanticSubmission(#[from] AtlanticError),
+
+    #[error("Withdrawal {0} has no Atlantic job id")]
+    MissingJobId(i32),
+
+    #[error("Invalid configuration: {0}")]
+    Config(#[from] ConfigError),
+}
+
+/// Generates withdrawal proofs through the Herodotus Atlantic API.
+///
+/// Withdrawals move `pending_proof` -> `proof_submitted` -> `ready_for_relay`,
+/// or to `proof_failed` when Atlantic reports the query as failed. A withdrawal only becomes
+/// ready for relay once its proof has been fetched from Atlantic and stored.
+pub struct ProofGenerationWorker {
+    db_pool: PgPool,
+    config: HerodotusConfig,
+    api_key: String,
+}
+
+impl ProofGenerationWorker {
+    pub fn new(db_pool: PgPool, config: HerodotusConfig) -> Self {
+        let api_key = config.get_api_key();
+        Self {
+            db_pool,
+            config,
+            api_key,
+        }
+    }
+
+    /// Runs the submission and status polling loops until the task is dropped.
+    pub async fn run(&self) {
+        tokio::join!(self.run_submission_loop(), self.run_status_loop());
+    }
+
+    async fn run_submission_loop(&self) {
+        loop {
+            match self.submit_pending_proofs().await {
+                Ok(count) => info!("Submitted {} withdrawal proof jobs to Atlantic", count),
+                Err(e) => error!("Proof submission cycle failed: {:?}", e),
+            }
+            sleep(self.poll_interval()).await;
+        }
+    }
+
+    async fn run_status_loop(&self) {
+        loop {
+            match self.poll_submitted_proofs().await {
+                Ok(count) => info!("{} withdrawal proofs ready for relay", count),
+                Err(e) => error!("Proof status polling cycle failed: {:?}", e),
+            }
+            sleep(self.poll_interval()).await;
+        }
+    }
+
+    fn poll_interval(&self) -> Duration {
+        Duration::from_secs(self.config.poll_interval_seconds)
+    }
+
+    /// Submits a proof job for every withdrawal awaiting a proof.
+    /// Returns the number of jobs submitted.
+    pub async fn submit_pending_proofs(&self) -> Result<usize, ProofGenerationError> {
+        let withdrawals =
+            fetch_withdrawals_by_status(&self.db_pool, STATUS_PENDING_PROOF, BATCH_SIZE).await?;
+
+        let mut submitted = 0;
+        for withdrawal in withdrawals {
+            match self.submit_proof(&withdrawal).await {
+                Ok(job_id) => {
+                    let mut conn = self.db_pool.acquire().await?;
+                    set_withdrawal_atlantic_job(
+                        &mut conn,
+                        withdrawal.id,
+                        &job_id,
+                        STATUS_PROOF_SUBMITTED,
+                        WORKER_ACTOR,
+                    )
+                    .await?;
+                    record_withdrawal_proof_submission(
+                        &mut conn,
+                        withdrawal.id,
+                        &job_id,
+                        STATUS_PROOF_SUBMITTED,
+                    )
+                    .await?;
+                    info!(
+                        "Withdrawal {} proof submitted as Atlantic job {}",
+                        withdrawal.id, job_id
+                    );
+                    submitted += 1;
+                }
+                // Leave the withdrawal in `pending_proof` so the next cycle retries it
+                Err(e) => warn!(
+                    "Failed to submit proof for withdrawal {}: {:?}",
+                    withdrawal.id, e
+                ),
+            }
+        }
+
+        Ok(submitted)
+    }
+
+    /// Checks the Atlantic status of every submitted proof job.
+    /// Returns the number of withdrawals that became ready for relay.
+    pub async fn poll_submitted_proofs(&self) -> Result<usize, ProofGenerationError> {
+        let withdrawals =
+            fetch_withdrawals_by_status(&self.db_pool, STATUS_PROOF_SUBMITTED, BATCH_SIZE).await?;
+
+        let mut ready = 0;
+        for withdrawal in withdrawals {
+            let status = match self.check_proof_status(&withdrawal).await {
+                Ok(status) => status,
+                Err(e) => {
+                    warn!(
+                        "Failed to fetch proof status for withdrawal {}: {:?}",
+                        withdrawal.id, e
+                    );
+                    continue;
+                }
+            };
+
+            match status.as_str() {
+                "DONE" => {
+                    // Leave the withdrawal in `proof_submitted` so the next cycle fetches it again
+                    let proof = match self.fetch_proof(&withdrawal).await {
+                        Ok(proof) => proof,
+                        Err(e) => {
+                            warn!(
+                                "Failed to fetch the proof of withdrawal {}: {:?}",
+                                withdrawal.id, e
+                            );
+                            continue;
+                        }
+                    };
+
+                    let mut tx = self.db_pool.begin().await?;
+                    let elapsed = store_withdrawal_proof(
+                        &mut tx,
+                        withdrawal.id,
+                        &encode_uint_array(&proof.proof_params),
+                        &encode_uint_array(&proof.proof),
+                        PROOF_STATUS_READY,
+                    )
+                    .await?;
+                    update_withdrawal_status(
+                        &mut tx,
+                        withdrawal.id,
+                        STATUS_READY_FOR_RELAY,
+                        WORKER_ACTOR,
+                    )
+                    .await?;
+                    if let Some(seconds) = elapsed {
+                        record_system_stat_sample(&mut tx, ATLANTIC_JOB_DURATION_STAT, seconds)
+                            .await?;
+                    }
+                    tx.commit().await?;
+                    info!("Withdrawal {} proof ready for relay", withdrawal.id);
+                    ready += 1;
+                }
+                "FAILED" => {
+                    let mut conn = self.db_pool.acquire().await?;
+                    update_withdrawal_status(
+                        &mut conn,
+                        withdrawal.id,
+                        STATUS_PROOF_FAILED,
+                        WORKER_ACTOR,
+                    )
+                    .await?;
+                    update_withdrawal_proof_status(&mut conn, withdrawal.id, STATUS_PROOF_FAILED)
+                        .await?;
+                    error!("Atlantic proof job failed for withdrawal {}", withdrawal.id);
+                }
+                other => info!(
+                    "Withdrawal {} proof still in progress ({})",
+                    withdrawal.id, other
+                ),
+            }
+        }
+
+        Ok(ready)
+    }
+
+    async fn submit_proof(&self, withdrawal: &Withdrawal) -> Result<String, ProofGenerationError> {
+        let input_path = Path::new(&self.config.inputs_dir)
+            .join(withdrawal.id.to_string())
+            .join("input.cairo1.txt");
+
+        let job_id = submit_sharp_proof_job(
+            &self.config.get_submit_url()?,
+            self.api_key.clone(),
+            PROOF_DIRECTION.to_string(),
+            self.config.program_path.clone(),
+            input_path.to_string_lossy().into_owned(),
+            self.config.atlantic_max_retries,
+            self.config.atlantic_retry_delay_ms,
+        )
+        .await?;
+
+        Ok(job_id)
+    }
+
+    async fn check_proof_status(
+        &self,
+        withdrawal: &Withdrawal,
+    ) -> Result<String, ProofGenerationError> {
+        let job_id = Self::job_id(withdrawal)?;
+
+        let status =
+            atlantic_job_status(&self.config.get_status_url(job_id)?, &self.api_key).await?;
+
+        Ok(status)
+    }
+
+    async fn fetch_proof(
+        &self,
+        withdrawal: &Withdrawal,
+    ) -> Result<AtlanticProof, ProofGenerationError> {
+        let job_id = Self::job_id(withdrawal)?;
+
+        let proof =
+            atlantic_job_result(&self.config.get_result_url(job_id)?, &self.api_key).await?;
+
+        Ok(proof)
+    }
+
+    fn job_id(withdrawal: &Withdrawal) -> Result<&str, ProofGenerationError> {
+        withdrawal
+            .atlantic_job_id
+            .as_deref()
+            .ok_or(ProofGenerationError::MissingJobId(withdrawal.id))
+    }
+}
+
+/// `values` as consecutive 32-byte big-endian words, the layout the Ethereum relayer decodes
+/// `withdrawal_proofs.proof_params` and `proof_data` from
+fn encode_uint_array(values: &[U256]) -> Vec<u8> {
+    values
+        .iter()
+        .flat_map(|value| value.to_be_bytes::<32>())
+        .collect()
+}
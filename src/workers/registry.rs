@@ -0,0 +1,132 @@
+use futures_util::future::join_all;
+use std::future::Future;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::watch;
+use tokio::task::JoinHandle;
+use tracing::{error, info, warn};
+
+/// Tells long-running services that the sequencer is shutting down.
+///
+/// Cloning the signal is cheap and every clone observes the same trigger, including clones
+/// made after it fired.
+#[derive(Debug, Clone)]
+pub struct ShutdownSignal {
+    sender: Arc<watch::Sender<bool>>,
+}
+
+impl ShutdownSignal {
+    pub fn new() -> Self {
+        let (sender, _) = watch::channel(false);
+        Self {
+            sender: Arc::new(sender),
+        }
+    }
+
+    pub fn trigger(&self) {
+        self.sender.send_replace(true);
+    }
+
+    pub fn is_triggered(&self) -> bool {
+        *self.sender.borrow()
+    }
+
+    /// Resolves once `trigger` has been called
+    pub async fn wait(&self) {
+        let mut receiver = self.sender.subscribe();
+        // The sender lives as long as `self`, so this only returns once the flag is set
+        let _ = receiver.wait_for(|triggered| *triggered).await;
+    }
+}
+
+impl Default for ShutdownSignal {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A registered task with the service name it is reported under
+type NamedTask = (String, JoinHandle<()>);
+
+/// Background tasks started by the sequencer, so they can be stopped together on shutdown.
+#[derive(Debug, Clone, Default)]
+pub struct ServiceRegistry {
+    signal: ShutdownSignal,
+    tasks: Arc<Mutex<Vec<NamedTask>>>,
+}
+
+impl ServiceRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn shutdown_signal(&self) -> ShutdownSignal {
+        self.signal.clone()
+    }
+
+    /// Spawns `service` as task `name`, dropping it at its next await point once shutdown starts
+    pub fn spawn<F>(&self, name: impl Into<String>, service: F)
+    where
+        F: Future<Output = ()> + Send + 'static,
+    {
+        let signal = self.signal.clone();
+        let handle = tokio::spawn(async move {
+            tokio::select! {
+                _ = service => {}
+                _ = signal.wait() => {}
+            }
+        });
+        self.register(name, handle);
+    }
+
+    /// Tracks a task that was spawned elsewhere and watches `shutdown_signal` itself
+    pub fn register(&self, name: impl Into<String>, handle: JoinHandle<()>) {
+        self.tasks.lock().unwrap().push((name.into(), handle));
+    }
+
+    /// Number of tasks that have not been shut down yet
+    pub fn len(&self) -> usize {
+        self.tasks.lock().unwrap().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Triggers the shutdown signal and waits up to `timeout` for every task to finish.
+    ///
+    /// Tasks still running after `timeout` are aborted. Returns their names.
+    pub async fn shutdown(&self, timeout: Duration) -> Vec<String> {
+        self.signal.trigger();
+
+        let tasks = std::mem::take(&mut *self.tasks.lock().unwrap());
+        let (names, handles): (Vec<String>, Vec<JoinHandle<()>>) = tasks.into_iter().unzip();
+        let aborts: Vec<_> = handles.iter().map(JoinHandle::abort_handle).collect();
+        let finished: Vec<AtomicBool> = names.iter().map(|_| AtomicBool::new(false)).collect();
+
+        let tracked = names.iter().zip(&finished);
+        let joins = handles
+            .into_iter()
+            .zip(tracked)
+            .map(|(handle, (name, done))| async move {
+                if let Err(e) = handle.await {
+                    error!("task {} stopped abnormally: {}", name, e);
+                }
+                done.store(true, Ordering::SeqCst);
+            });
+        let _ = tokio::time::timeout(timeout, join_all(joins)).await;
+
+        let mut timed_out = Vec::new();
+        for ((name, done), abort) in names.into_iter().zip(&finished).zip(aborts) {
+            if done.load(Ordering::SeqCst) {
+                info!("task {} shut down cleanly", name);
+            } else {
+                warn!("task {} timed out", name);
+                abort.abort();
+                timed_out.push(name);
+            }
+        }
+        timed_out
+    }
+}
@@ -0,0 +1,104 @@
+#[path = "utils.rs"]
+mod utils;
+
+use axum::{
+    body::Body,
+    http::{Method, Request},
+    response::Response,
+    routing::get,
+    Router,
+};
+use tower::ServiceExt;
+use utils::create_test_app;
+use zeroxbridge_sequencer::api::{
+    routes::{create_router, deprecated_layer, X_API_VERSION, X_DEPRECATED},
+    API_VERSION,
+};
+
+async fn send(method: Method, uri: &str, body: &str) -> Response {
+    let app = create_test_app().await;
+    let router = create_router(app.as_ref().clone());
+
+    let request = Request::builder()
+        .method(method)
+        .uri(uri)
+        .header("content-type", "application/json")
+        .body(Body::from(body.to_string()))
+        .unwrap();
+
+    router.oneshot(request).await.unwrap()
+}
+
+fn api_version(response: &Response) -> Option<&str> {
+    response
+        .headers()
+        .get(X_API_VERSION)
+        .map(|value| value.to_str().unwrap())
+}
+
+#[tokio::test]
+async fn test_every_route_reports_api_version() {
+    let requests = [
+        (Method::GET, "/", ""),
+        (Method::GET, "/health", ""),
+        (Method::GET, "/deposit", ""),
+        (
+            Method::POST,
+            "/deposit",
+            r#"{"stark_pub_key":"0x123","amount":100,"commitment_hash":"0x1234"}"#,
+        ),
+        (Method::GET, "/withdrawals", ""),
+        (Method::GET, "/allowed-tokens", ""),
+        (Method::GET, "/proof-jobs", ""),
+        (Method::GET, "/dead-letter/l2", ""),
+        (Method::POST, "/compute-hash", "{}"),
+    ];
+
+    for (method, uri, body) in requests {
+        let response = send(method.clone(), uri, body).await;
+
+        assert_eq!(
+            api_version(&response),
+            Some(API_VERSION),
+            "{} {} ({})",
+            method,
+            uri,
+            response.status()
+        );
+        assert!(response.headers().get(X_DEPRECATED).is_none());
+    }
+}
+
+#[tokio::test]
+async fn test_rejected_requests_report_api_version() {
+    // Unknown routes and malformed bodies are answered before reaching a handler
+    let not_found = send(Method::GET, "/no-such-route", "").await;
+    assert_eq!(api_version(&not_found), Some(API_VERSION));
+
+    let malformed = send(Method::POST, "/deposit", "not json").await;
+    assert!(malformed.status().is_client_error());
+    assert_eq!(api_version(&malformed), Some(API_VERSION));
+}
+
+#[tokio::test]
+async fn test_deprecated_layer_marks_only_its_route() {
+    let router = Router::new()
+        .route(
+            "/old",
+            get(|| async { "old" }).route_layer(deprecated_layer()),
+        )
+        .route("/new", get(|| async { "new" }));
+
+    let old = router
+        .clone()
+        .oneshot(Request::get("/old").body(Body::empty()).unwrap())
+        .await
+        .unwrap();
+    let new = router
+        .oneshot(Request::get("/new").body(Body::empty()).unwrap())
+        .await
+        .unwrap();
+
+    assert_eq!(old.headers().get(X_DEPRECATED).unwrap(), "true");
+    assert!(new.headers().get(X_DEPRECATED).is_none());
+}
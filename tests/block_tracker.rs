@@ -0,0 +1,52 @@
+use sqlx::PgPool;
+use std::collections::HashSet;
+use zeroxbridge_sequencer::db::database::{BlockTracker, BlockTrackerKey};
+
+const ALL_KEYS: [BlockTrackerKey; 4] = [
+    BlockTrackerKey::L1DepositEvents,
+    BlockTrackerKey::L1DepositHashEvents,
+    BlockTrackerKey::L2BurnEvents,
+    BlockTrackerKey::L2WithdrawalEvents,
+];
+
+#[test]
+fn test_block_tracker_keys_are_distinct() {
+    let stored: HashSet<&str> = ALL_KEYS.iter().map(|key| key.as_str()).collect();
+
+    assert_eq!(stored.len(), ALL_KEYS.len());
+}
+
+#[test]
+fn test_block_tracker_keys_match_existing_rows() {
+    // Renaming these would make every watcher rescan from its configured start block
+    assert_eq!(
+        BlockTrackerKey::L1DepositEvents.as_str(),
+        "l1_deposit_events_last_block"
+    );
+    assert_eq!(
+        BlockTrackerKey::L1DepositHashEvents.as_str(),
+        "l1_deposit_hash_events_last_block"
+    );
+    assert_eq!(
+        BlockTrackerKey::L2BurnEvents.as_str(),
+        "l2_events_last_block"
+    );
+}
+
+#[test]
+fn test_block_tracker_key_display_matches_stored_key() {
+    for key in ALL_KEYS {
+        assert_eq!(key.to_string(), key.as_str());
+    }
+}
+
+#[sqlx::test]
+async fn test_postgres_tracker_rewinds(pool: PgPool) {
+    let tracker = BlockTracker::new(pool);
+    let key = BlockTrackerKey::L1DepositEvents;
+
+    tracker.set(key, 120).await.unwrap();
+    tracker.rewind(key, 100).await.unwrap();
+
+    assert_eq!(tracker.get(key).await.unwrap(), Some(100));
+}
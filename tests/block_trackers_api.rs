@@ -0,0 +1,141 @@
+#[path = "utils.rs"]
+mod utils;
+
+use axum::Extension;
+use chrono::Utc;
+use utils::create_test_app;
+use zeroxbridge_sequencer::api::handlers::{get_block_trackers, BlockTrackerStatus, ChainTips};
+use zeroxbridge_sequencer::db::database::{list_block_trackers, BlockTrackerRow};
+
+fn tracker(key: &str, last_block: i64) -> BlockTrackerRow {
+    BlockTrackerRow {
+        key: key.to_string(),
+        last_block,
+        updated_at: Utc::now(),
+    }
+}
+
+/// Inserts a tracker row under a unique key so tests don't touch the watchers' own rows
+async fn insert_tracker(pool: &sqlx::PgPool, chain: &str, last_block: i64) -> String {
+    let key = format!(
+        "{}_test_{}_last_block",
+        chain,
+        uuid::Uuid::new_v4().simple()
+    );
+    sqlx::query!(
+        "INSERT INTO block_trackers (key, last_block) VALUES ($1, $2)",
+        key,
+        last_block
+    )
+    .execute(pool)
+    .await
+    .unwrap();
+    key
+}
+
+#[tokio::test]
+async fn test_list_block_trackers_returns_every_row() {
+    let app = create_test_app().await;
+    let l1_key = insert_tracker(&app.db, "l1", 18_000_000).await;
+    let l2_key = insert_tracker(&app.db, "l2", 650_000).await;
+
+    let trackers = list_block_trackers(&app.db).await.unwrap();
+
+    let l1 = trackers.iter().find(|t| t.key == l1_key).unwrap();
+    let l2 = trackers.iter().find(|t| t.key == l2_key).unwrap();
+    assert_eq!(l1.last_block, 18_000_000);
+    assert_eq!(l2.last_block, 650_000);
+
+    sqlx::query!(
+        "DELETE FROM block_trackers WHERE key = ANY($1)",
+        &[l1_key, l2_key][..]
+    )
+    .execute(&app.db)
+    .await
+    .unwrap();
+}
+
+#[test]
+fn test_lag_uses_the_tip_of_the_trackers_chain() {
+    let tips = ChainTips {
+        ethereum: Some(18_000_100),
+        starknet: Some(650_020),
+    };
+
+    assert_eq!(
+        tips.lag(&tracker("l1_deposit_events_last_block", 18_000_000)),
+        100
+    );
+    assert_eq!(tips.lag(&tracker("l2_events_last_block", 650_000)), 20);
+    assert_eq!(tips.lag(&tracker("unknown_last_block", 0)), -1);
+}
+
+#[test]
+fn test_lag_is_negative_one_when_tip_is_unknown() {
+    let tips = ChainTips {
+        ethereum: None,
+        starknet: Some(650_020),
+    };
+
+    assert_eq!(
+        tips.lag(&tracker("l1_deposit_events_last_block", 18_000_000)),
+        -1
+    );
+    assert_eq!(tips.lag(&tracker("l2_events_last_block", 650_000)), 20);
+}
+
+#[tokio::test]
+async fn test_chain_tips_are_fetched_from_both_rpcs() {
+    let _eth_mock = mockito::mock("POST", "/")
+        .match_body(mockito::Matcher::Regex("eth_blockNumber".to_string()))
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"jsonrpc":"2.0","id":0,"result":"0x112a8a4"}"#)
+        .create();
+    let _starknet_mock = mockito::mock("POST", "/")
+        .match_body(mockito::Matcher::Regex("starknet_blockNumber".to_string()))
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"jsonrpc":"2.0","id":1,"result":650020}"#)
+        .create();
+    let url = mockito::server_url();
+
+    let tips = ChainTips::fetch(Some(&url), Some(&url)).await;
+
+    assert_eq!(
+        tips,
+        ChainTips {
+            ethereum: Some(18_000_036),
+            starknet: Some(650_020),
+        }
+    );
+}
+
+#[tokio::test]
+async fn test_unreachable_rpc_leaves_tip_unknown() {
+    // Nothing listens on port 1
+    let tips = ChainTips::fetch(Some("http://127.0.0.1:1"), None).await;
+
+    assert_eq!(tips, ChainTips::default());
+}
+
+#[tokio::test]
+async fn test_get_block_trackers_reports_lag_for_each_row() {
+    let app = create_test_app().await;
+    let key = insert_tracker(&app.db, "l1", 18_000_000).await;
+
+    let response = get_block_trackers(Extension(app.db.clone()))
+        .await
+        .expect("Listing block trackers failed");
+    let trackers: &Vec<BlockTrackerStatus> = &response.0;
+
+    let status = trackers.iter().find(|t| t.key == key).unwrap();
+    assert_eq!(status.last_block, 18_000_000);
+    // -1 unless the test environment points ETHEREUM_RPC_URL at a live node
+    assert!(status.lag >= -1);
+
+    sqlx::query!("DELETE FROM block_trackers WHERE key = $1", key)
+        .execute(&app.db)
+        .await
+        .unwrap();
+}
@@ -0,0 +1,138 @@
+use starknet::core::types::Felt;
+use tempfile::tempdir;
+use zeroxbridge_sequencer::relayer::calldata::{
+    read_calldata_dir, read_calldata_file, CalldataFormat,
+};
+use zeroxbridge_sequencer::relayer::proof_submission::ProofSubmissionError;
+
+fn sample_calldata() -> Vec<Felt> {
+    vec![
+        Felt::ZERO,
+        Felt::ONE,
+        Felt::from_hex("0x123abc").unwrap(),
+        // Largest field element, so every byte of the encodings is exercised
+        Felt::MAX,
+    ]
+}
+
+#[test]
+fn test_every_format_round_trips() {
+    let calldata = sample_calldata();
+
+    for format in CalldataFormat::ALL {
+        let mut encoded = Vec::new();
+        format.to_writer(&calldata, &mut encoded).unwrap();
+
+        let decoded = format.from_reader(encoded.as_slice()).unwrap();
+        assert_eq!(decoded, calldata, "{} did not round-trip", format);
+    }
+}
+
+#[test]
+fn test_json_format_is_an_array_of_hex_strings() {
+    let mut encoded = Vec::new();
+    CalldataFormat::Json
+        .to_writer(&[Felt::ONE, Felt::from(255u64)], &mut encoded)
+        .unwrap();
+
+    let parsed: Vec<String> = serde_json::from_slice(&encoded).unwrap();
+    assert_eq!(parsed, vec!["0x1", "0xff"]);
+}
+
+#[test]
+fn test_binary_format_is_big_endian_32_byte_words() {
+    let mut encoded = Vec::new();
+    CalldataFormat::Binary
+        .to_writer(&[Felt::ONE, Felt::from(0x0102u64)], &mut encoded)
+        .unwrap();
+
+    assert_eq!(encoded.len(), 64);
+    assert_eq!(encoded[31], 1);
+    assert_eq!(&encoded[62..], &[1, 2]);
+    assert!(encoded[32..62].iter().all(|byte| *byte == 0));
+}
+
+#[test]
+fn test_binary_format_rejects_truncated_input() {
+    let result = CalldataFormat::Binary.from_reader(&[0u8; 33][..]);
+
+    assert!(matches!(
+        result,
+        Err(ProofSubmissionError::InvalidCalldataFormat(_))
+    ));
+}
+
+#[test]
+fn test_hex_text_reads_calldata_files() {
+    // The calldata files mix spaces and newlines
+    let decoded = CalldataFormat::HexText
+        .from_reader("0x1 0x2\n0x3\n\n".as_bytes())
+        .unwrap();
+
+    assert_eq!(
+        decoded,
+        vec![Felt::from(1u64), Felt::from(2u64), Felt::from(3u64)]
+    );
+}
+
+#[test]
+fn test_format_names_parse() {
+    for format in CalldataFormat::ALL {
+        assert_eq!(format.as_str().parse::<CalldataFormat>(), Ok(format));
+    }
+    assert!("yaml".parse::<CalldataFormat>().is_err());
+}
+
+#[test]
+fn test_read_calldata_dir_combines_files_in_submission_order() {
+    let dir = tempdir().unwrap();
+    std::fs::write(dir.path().join("initial"), "0x1 0x2").unwrap();
+    std::fs::write(dir.path().join("step1"), "0x3").unwrap();
+    std::fs::write(dir.path().join("step2"), "0x4\n0x5").unwrap();
+    std::fs::write(dir.path().join("final"), "0x6").unwrap();
+
+    let calldata = read_calldata_dir(dir.path()).unwrap();
+
+    assert_eq!(calldata, (1..=6u64).map(Felt::from).collect::<Vec<_>>());
+}
+
+#[test]
+fn test_read_calldata_dir_requires_final_file() {
+    let dir = tempdir().unwrap();
+    std::fs::write(dir.path().join("initial"), "0x1").unwrap();
+
+    let result = read_calldata_dir(dir.path());
+
+    assert!(matches!(
+        result,
+        Err(ProofSubmissionError::CalldataFileMissing(ref name)) if name == "final"
+    ));
+}
+
+#[test]
+fn test_read_calldata_file_reports_position_of_invalid_value() {
+    let dir = tempdir().unwrap();
+    let file = dir.path().join("step1");
+    std::fs::write(&file, "0x1 0x2\n0x3\n0x4 0xzz 0x5\n").unwrap();
+
+    let result = read_calldata_file(&file);
+
+    match result {
+        Err(ProofSubmissionError::CalldataParseError {
+            file: ref error_file,
+            line,
+            column,
+            ref value,
+            ..
+        }) => {
+            assert_eq!(error_file, &file);
+            assert_eq!((line, column), (3, 2));
+            assert_eq!(value, "0xzz");
+        }
+        other => panic!("expected a CalldataParseError, got {:?}", other),
+    }
+    assert!(read_calldata_file(&file)
+        .unwrap_err()
+        .to_string()
+        .contains("line 3, column 2"));
+}
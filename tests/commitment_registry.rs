@@ -0,0 +1,120 @@
+#[path = "utils.rs"]
+mod utils;
+
+use std::time::Duration;
+use utils::create_test_app;
+use zeroxbridge_sequencer::db::database::upsert_deposit;
+use zeroxbridge_sequencer::events::CommitmentHashRegistry;
+
+// Far below the blocks the reorg tests invalidate
+const DEPOSIT_BLOCK: i64 = 42;
+
+/// Records a deposit seen on L1 under a random commitment hash
+async fn insert_confirmed_deposit(pool: &sqlx::PgPool) -> String {
+    let commitment_hash = hex::encode(rand::random::<[u8; 32]>());
+    upsert_deposit(
+        pool,
+        "0xcc",
+        100,
+        &commitment_hash,
+        "PENDING_TREE_INCLUSION",
+        &rand::random::<u64>().to_string(),
+        Some(DEPOSIT_BLOCK),
+        None,
+    )
+    .await
+    .expect("Failed to insert deposit");
+    commitment_hash
+}
+
+#[tokio::test]
+async fn test_cache_hits_skip_database() {
+    let app = create_test_app().await;
+    let commitment_hash = insert_confirmed_deposit(&app.db).await;
+    let registry = CommitmentHashRegistry::new(Duration::from_secs(60));
+
+    for _ in 0..3 {
+        assert!(registry
+            .is_confirmed(&app.db, &commitment_hash)
+            .await
+            .unwrap());
+    }
+
+    assert_eq!(registry.db_lookups(), 1);
+    let status = registry.get(&commitment_hash).unwrap();
+    assert!(status.confirmed);
+    assert_eq!(status.block_number, DEPOSIT_BLOCK as u64);
+}
+
+#[tokio::test]
+async fn test_expired_entries_are_looked_up_again() {
+    let app = create_test_app().await;
+    let commitment_hash = insert_confirmed_deposit(&app.db).await;
+    let registry = CommitmentHashRegistry::new(Duration::ZERO);
+
+    registry
+        .is_confirmed(&app.db, &commitment_hash)
+        .await
+        .unwrap();
+    tokio::time::sleep(Duration::from_millis(5)).await;
+    registry
+        .is_confirmed(&app.db, &commitment_hash)
+        .await
+        .unwrap();
+
+    assert_eq!(registry.db_lookups(), 2);
+}
+
+#[tokio::test]
+async fn test_misses_are_not_cached() {
+    let app = create_test_app().await;
+    let commitment_hash = hex::encode(rand::random::<[u8; 32]>());
+    let registry = CommitmentHashRegistry::new(Duration::from_secs(60));
+
+    for _ in 0..2 {
+        assert!(!registry
+            .is_confirmed(&app.db, &commitment_hash)
+            .await
+            .unwrap());
+    }
+    assert_eq!(registry.db_lookups(), 2);
+    assert!(registry.get(&commitment_hash).is_none());
+
+    // As the L1 event watcher does when it fetches the deposit's event
+    registry.record_confirmed(&commitment_hash, 77);
+
+    assert!(registry
+        .is_confirmed(&app.db, &commitment_hash)
+        .await
+        .unwrap());
+    assert_eq!(registry.db_lookups(), 2);
+    assert_eq!(registry.get(&commitment_hash).unwrap().block_number, 77);
+}
+
+#[test]
+fn test_hashes_are_keyed_normalized() {
+    let registry = CommitmentHashRegistry::new(Duration::from_secs(60));
+    let padded = format!("0x{}", "00ab".repeat(16));
+
+    // The watcher formats event hashes without their leading zeros
+    registry.record_confirmed(padded.trim_start_matches("0x00"), 5);
+
+    assert_eq!(registry.get(&padded).unwrap().block_number, 5);
+    assert_eq!(
+        registry
+            .get(&padded.to_uppercase().replace("0X", ""))
+            .unwrap()
+            .block_number,
+        5
+    );
+}
+
+#[tokio::test]
+async fn test_clones_share_entries() {
+    let registry = CommitmentHashRegistry::new(Duration::from_secs(60));
+    let watcher_handle = registry.clone();
+
+    watcher_handle.record_confirmed("abc", 1);
+
+    assert!(registry.get("abc").unwrap().confirmed);
+}
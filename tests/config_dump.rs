@@ -0,0 +1,56 @@
+#[path = "utils.rs"]
+mod utils;
+
+use utils::create_test_config;
+use zeroxbridge_sequencer::config::SensitiveField;
+
+const SECRET: &str = "0x0123456789abcdef0123456789abcdef";
+
+#[test]
+fn test_config_json_redacts_private_key() {
+    let mut config = create_test_config();
+    config.starknet.private_key = SECRET.into();
+
+    let json = serde_json::to_string_pretty(&config).unwrap();
+    let parsed: serde_json::Value = serde_json::from_str(&json).unwrap();
+
+    assert!(!json.contains(SECRET), "{}", json);
+    assert_eq!(parsed["starknet"]["private_key"], "***");
+    assert_eq!(
+        parsed["starknet"]["account_address"],
+        config.starknet.account_address.as_str()
+    );
+}
+
+#[test]
+fn test_config_toml_redacts_private_key() {
+    let mut config = create_test_config();
+    config.starknet.private_key = SECRET.into();
+
+    let rendered = toml::to_string_pretty(&config).unwrap();
+
+    assert!(!rendered.contains(SECRET), "{}", rendered);
+    assert!(rendered.contains(r#"private_key = "***""#), "{}", rendered);
+}
+
+#[test]
+fn test_config_dump_redacts_admin_token() {
+    let mut config = create_test_config();
+    config.server.admin_token = Some(SECRET.into());
+
+    let json = serde_json::to_string(&config).unwrap();
+    let debug = format!("{:?}", config);
+
+    assert!(!json.contains(SECRET), "{}", json);
+    assert!(!debug.contains(SECRET), "{}", debug);
+    assert_eq!(config.server.get_admin_token().as_deref(), Some(SECRET));
+}
+
+#[test]
+fn test_sensitive_field_keeps_value_but_hides_it() {
+    let field: SensitiveField = serde_json::from_str(&format!("\"{}\"", SECRET)).unwrap();
+
+    assert_eq!(field.expose(), SECRET);
+    assert_eq!(format!("{:?}", field), "\"***\"");
+    assert_eq!(serde_json::to_string(&field).unwrap(), "\"***\"");
+}
@@ -0,0 +1,158 @@
+#[path = "utils.rs"]
+mod utils;
+
+use axum::{
+    body::Body,
+    http::{Request, StatusCode},
+};
+use tower::ServiceExt;
+use utils::create_test_app;
+use zeroxbridge_sequencer::api::routes::create_router;
+use zeroxbridge_sequencer::queue::l2_queue::{L2Queue, QueueConfig};
+
+const ADMIN_TOKEN: &str = "test-admin-token";
+
+#[tokio::test]
+async fn test_failed_l2_transaction_is_dead_lettered_and_requeued() {
+    let app = create_test_app().await;
+    // Without a Starknet provider the commitment is never found on L2, so one retry fails the tx
+    let tx_id: i64 = 9_600_001;
+
+    sqlx::query!(
+        "DELETE FROM dead_letter_l2_transactions WHERE l2_transaction_id = $1",
+        tx_id
+    )
+    .execute(&app.db)
+    .await
+    .unwrap();
+    sqlx::query!("DELETE FROM l2_transactions WHERE id = $1", tx_id)
+        .execute(&app.db)
+        .await
+        .unwrap();
+    sqlx::query!(
+        r#"
+        INSERT INTO l2_transactions (id, stark_pub_key, amount, token_address, status)
+        VALUES ($1, '0xdeadletter', 500, '0xtoken123', 'pending')
+        "#,
+        tx_id
+    )
+    .execute(&app.db)
+    .await
+    .unwrap();
+
+    let queue = L2Queue::new(
+        app.db.clone(),
+        QueueConfig {
+            min_interval_sec: 1,
+            max_interval_sec: 60,
+            backoff_factor: 2.0,
+            initial_retry_delay_sec: 0,
+            max_retries: 1,
+            batch_size: 1000,
+            high_priority_threshold_usd: None,
+            max_concurrent_validations: 4,
+        },
+    );
+    queue.process_transactions().await.unwrap();
+
+    // The failed transaction shows up in the dead-letter listing
+    let router = create_router(app.as_ref().clone());
+    let request = Request::builder()
+        .method("GET")
+        .uri("/dead-letter/l2?limit=500")
+        .body(Body::empty())
+        .unwrap();
+    let response = router.oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let parsed: Vec<serde_json::Value> = serde_json::from_slice(&body).unwrap();
+    let entry = parsed
+        .iter()
+        .find(|entry| entry["l2_transaction_id"] == tx_id)
+        .expect("Failed transaction should be dead-lettered");
+    assert_eq!(entry["status"], "failed");
+    assert_eq!(entry["failed_reason"], "Max retries exceeded");
+    let entry_id = entry["id"].as_i64().unwrap();
+
+    // Requeueing is an admin action
+    let router = create_router(app.as_ref().clone());
+    let request = Request::builder()
+        .method("POST")
+        .uri(format!("/admin/dead-letter/l2/{}/requeue", entry_id))
+        .body(Body::empty())
+        .unwrap();
+    let response = router.oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+    // Requeueing creates a fresh pending transaction
+    let router = create_router(app.as_ref().clone());
+    let request = Request::builder()
+        .method("POST")
+        .uri(format!("/admin/dead-letter/l2/{}/requeue", entry_id))
+        .header("x-admin-token", ADMIN_TOKEN)
+        .body(Body::empty())
+        .unwrap();
+    let response = router.oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let parsed: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    let new_id = parsed["l2_transaction_id"].as_i64().unwrap();
+    assert_ne!(new_id, tx_id);
+
+    let requeued = sqlx::query!(
+        "SELECT stark_pub_key, amount, status, retry_count FROM l2_transactions WHERE id = $1",
+        new_id
+    )
+    .fetch_one(&app.db)
+    .await
+    .unwrap();
+    assert_eq!(requeued.stark_pub_key, "0xdeadletter");
+    assert_eq!(requeued.amount, 500);
+    assert_eq!(requeued.status, "pending");
+    assert_eq!(requeued.retry_count, 0);
+
+    // An entry can only be requeued once
+    let router = create_router(app.as_ref().clone());
+    let request = Request::builder()
+        .method("POST")
+        .uri(format!("/admin/dead-letter/l2/{}/requeue", entry_id))
+        .header("x-admin-token", ADMIN_TOKEN)
+        .body(Body::empty())
+        .unwrap();
+    let response = router.oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+    sqlx::query!("DELETE FROM dead_letter_l2_transactions WHERE id = $1", entry_id)
+        .execute(&app.db)
+        .await
+        .unwrap();
+    sqlx::query!(
+        "DELETE FROM l2_transactions WHERE id = $1 OR id = $2",
+        tx_id,
+        new_id
+    )
+    .execute(&app.db)
+    .await
+    .unwrap();
+}
+
+#[tokio::test]
+async fn test_dead_letter_rejects_invalid_limit() {
+    let app = create_test_app().await;
+    let router = create_router(app.as_ref().clone());
+
+    let request = Request::builder()
+        .method("GET")
+        .uri("/dead-letter/l2?limit=0")
+        .body(Body::empty())
+        .unwrap();
+
+    let response = router.oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+}
@@ -1,8 +1,6 @@
 #[path = "utils.rs"]
 mod utils;
 
-use std::usize;
-
 use axum::{
     body::Body,
     http::{Request, StatusCode},
@@ -10,12 +8,15 @@ use axum::{
 use serde_json::json;
 use tower::ServiceExt;
 use utils::create_test_app;
-use zeroxbridge_sequencer::api::routes::create_router;
+use zeroxbridge_sequencer::api::handlers::DUPLICATE_COMMITMENT;
+use zeroxbridge_sequencer::api::routes::{create_router, AppState};
+use zeroxbridge_sequencer::db::database::{check_commitment_hash_unique, insert_deposit};
+use zeroxbridge_sequencer::utils::normalize_commitment_hash;
 
 #[tokio::test]
 async fn test_hello_world() {
     let app = create_test_app().await;
-    let router = create_router(app.db.clone());
+    let router = create_router(app.as_ref().clone());
 
     let request = Request::builder()
         .method("GET")
@@ -40,7 +41,7 @@ async fn test_hello_world() {
 #[tokio::test]
 async fn test_post_valid_deposit() {
     let app = create_test_app().await;
-    let router = create_router(app.db.clone());
+    let router = create_router(app.as_ref().clone());
 
     let request = Request::builder()
         .method("POST")
@@ -48,9 +49,9 @@ async fn test_post_valid_deposit() {
         .header("content-type", "application/json")
         .body(Body::from(
             json!({
-                "stark_pub_key": "0xuser123",
+                "stark_pub_key": "0x123",
                 "amount": 1000,
-                "commitment_hash": "0xcommitment123"
+                "commitment_hash": format!("0x{}", uuid::Uuid::new_v4().simple())
             })
             .to_string(),
         ))
@@ -58,7 +59,7 @@ async fn test_post_valid_deposit() {
 
     let response = router.oneshot(request).await.unwrap();
     println!("Status: {:?}", response.status());
-    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.status(), StatusCode::CREATED);
 
     let body = axum::body::to_bytes(response.into_body(), usize::MAX)
         .await
@@ -71,7 +72,7 @@ async fn test_post_valid_deposit() {
 #[tokio::test]
 async fn test_deposit_missing_commitment_hash() {
     let app = create_test_app().await;
-    let router = create_router(app.db.clone());
+    let router = create_router(app.as_ref().clone());
 
     let request = Request::builder()
         .method("POST")
@@ -104,7 +105,7 @@ async fn test_deposit_missing_commitment_hash() {
 #[tokio::test]
 async fn test_semantic_invalid_deposit() {
     let app = create_test_app().await;
-    let router = create_router(app.db.clone());
+    let router = create_router(app.as_ref().clone());
 
     let request = Request::builder()
         .method("POST")
@@ -141,7 +142,7 @@ async fn test_semantic_invalid_deposit() {
 #[tokio::test]
 async fn test_get_pending_deposits() {
     let app = create_test_app().await;
-    let router = create_router(app.db.clone());
+    let router = create_router(app.as_ref().clone());
 
     // First create a test deposit
     let post_request = Request::builder()
@@ -150,7 +151,7 @@ async fn test_get_pending_deposits() {
         .header("content-type", "application/json")
         .body(Body::from(
             json!({
-                "stark_pub_key": "0xtest123",
+                "stark_pub_key": "0x7e57123",
                 "amount": 500
             })
             .to_string(),
@@ -176,3 +177,402 @@ async fn test_get_pending_deposits() {
     assert!(!parsed.is_empty());
     assert_eq!(parsed[0]["status"], "pending");
 }
+
+#[tokio::test]
+async fn test_deposit_idempotency_key_replay() {
+    let app = create_test_app().await;
+    let router = create_router(app.as_ref().clone());
+
+    let idempotency_key = format!("test-key-{}", uuid::Uuid::new_v4());
+    let commitment_hash = format!("0x{}", uuid::Uuid::new_v4().simple());
+    let build_request = || {
+        Request::builder()
+            .method("POST")
+            .uri("/deposit")
+            .header("content-type", "application/json")
+            .body(Body::from(
+                json!({
+                    "stark_pub_key": "0x456",
+                    "amount": 2500,
+                    "commitment_hash": commitment_hash,
+                    "idempotency_key": idempotency_key
+                })
+                .to_string(),
+            ))
+            .unwrap()
+    };
+
+    let first = router.clone().oneshot(build_request()).await.unwrap();
+    assert_eq!(first.status(), StatusCode::CREATED);
+    assert!(first.headers().get("X-Idempotent-Replayed").is_none());
+    let first_body = axum::body::to_bytes(first.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let first_parsed: serde_json::Value = serde_json::from_slice(&first_body).unwrap();
+
+    let second = router.oneshot(build_request()).await.unwrap();
+    assert_eq!(second.status(), StatusCode::OK);
+    assert_eq!(
+        second.headers().get("X-Idempotent-Replayed").unwrap(),
+        "true"
+    );
+    let second_body = axum::body::to_bytes(second.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let second_parsed: serde_json::Value = serde_json::from_slice(&second_body).unwrap();
+
+    assert_eq!(first_parsed["deposit_id"], second_parsed["deposit_id"]);
+}
+
+#[tokio::test]
+async fn test_deposit_stark_pub_key_validation() {
+    let app = create_test_app().await;
+
+    let cases = [
+        ("0x04a3b1c2d3e4f5", StatusCode::CREATED),
+        (
+            "2087021424722619777119509474943472645767659996348769578120564519014510906823",
+            StatusCode::CREATED,
+        ),
+        ("", StatusCode::BAD_REQUEST),
+        ("garbage", StatusCode::UNPROCESSABLE_ENTITY),
+    ];
+
+    for (stark_pub_key, expected) in cases {
+        let router = create_router(app.as_ref().clone());
+        let request = Request::builder()
+            .method("POST")
+            .uri("/deposit")
+            .header("content-type", "application/json")
+            .body(Body::from(
+                json!({
+                    "stark_pub_key": stark_pub_key,
+                    "amount": 1000,
+                    "commitment_hash": format!("0x{}", uuid::Uuid::new_v4().simple())
+                })
+                .to_string(),
+            ))
+            .unwrap();
+
+        let response = router.oneshot(request).await.unwrap();
+        let status = response.status();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let body_str = String::from_utf8_lossy(&body);
+
+        assert_eq!(
+            status, expected,
+            "stark_pub_key {:?}: {}",
+            stark_pub_key, body_str
+        );
+        if expected == StatusCode::UNPROCESSABLE_ENTITY {
+            assert_eq!(
+                body_str,
+                "stark_pub_key must be a valid felt252 hex or decimal value"
+            );
+        }
+    }
+}
+
+async fn post_bulk_deposits(
+    app: &AppState,
+    deposits: serde_json::Value,
+) -> (StatusCode, serde_json::Value) {
+    let router = create_router(app.clone());
+    let request = Request::builder()
+        .method("POST")
+        .uri("/deposits/bulk")
+        .header("content-type", "application/json")
+        .body(Body::from(json!({ "deposits": deposits }).to_string()))
+        .unwrap();
+
+    let response = router.oneshot(request).await.unwrap();
+    let status = response.status();
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let parsed = serde_json::from_slice(&body).unwrap_or(serde_json::Value::Null);
+    (status, parsed)
+}
+
+#[tokio::test]
+async fn test_bulk_deposits_skip_duplicate_commitment_hashes() {
+    let app = create_test_app().await;
+    let existing = format!("0x{}", uuid::Uuid::new_v4().simple());
+    let first = format!("0x{}", uuid::Uuid::new_v4().simple());
+    let second = format!("0x{}", uuid::Uuid::new_v4().simple());
+
+    let (status, _) = post_bulk_deposits(
+        &app,
+        json!([{ "stark_pub_key": "0x123", "amount": 100, "commitment_hash": existing }]),
+    )
+    .await;
+    assert_eq!(status, StatusCode::CREATED);
+
+    let (status, body) = post_bulk_deposits(
+        &app,
+        json!([
+            { "stark_pub_key": "0x123", "amount": 100, "commitment_hash": existing },
+            { "stark_pub_key": "0x123", "amount": 200, "commitment_hash": first },
+            { "stark_pub_key": "0x456", "amount": 300, "commitment_hash": second },
+            { "stark_pub_key": "0x456", "amount": 400, "commitment_hash": first },
+        ]),
+    )
+    .await;
+
+    assert_eq!(status, StatusCode::CREATED, "{}", body);
+    let created_ids: Vec<i32> = serde_json::from_value(body["created_ids"].clone()).unwrap();
+    let skipped: Vec<String> = serde_json::from_value(body["skipped"].clone()).unwrap();
+    assert_eq!(created_ids.len(), 2);
+    assert_eq!(skipped, vec![existing.clone(), first.clone()]);
+
+    // Ids line up with the request order
+    let amounts: Vec<i64> = sqlx::query_scalar!(
+        "SELECT amount FROM deposits WHERE id = ANY($1) ORDER BY array_position($1, id)",
+        &created_ids[..]
+    )
+    .fetch_all(&app.db)
+    .await
+    .unwrap();
+    assert_eq!(amounts, vec![200, 300]);
+
+    sqlx::query!(
+        "DELETE FROM deposits WHERE commitment_hash = ANY($1)",
+        &[existing, first, second].map(|hash| normalize_commitment_hash(&hash).unwrap())[..]
+    )
+    .execute(&app.db)
+    .await
+    .unwrap();
+}
+
+#[tokio::test]
+async fn test_bulk_deposits_rejects_oversized_or_invalid_batches() {
+    let app = create_test_app().await;
+
+    let too_many: Vec<serde_json::Value> = (0..101)
+        .map(|_| {
+            json!({
+                "stark_pub_key": "0x123",
+                "amount": 100,
+                "commitment_hash": format!("0x{}", uuid::Uuid::new_v4().simple())
+            })
+        })
+        .collect();
+    let (status, _) = post_bulk_deposits(&app, json!(too_many)).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+
+    let (status, _) = post_bulk_deposits(&app, json!([])).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+
+    // One bad entry rejects the whole batch before anything is inserted
+    let valid = format!("0x{}", uuid::Uuid::new_v4().simple());
+    let (status, _) = post_bulk_deposits(
+        &app,
+        json!([
+            { "stark_pub_key": "0x123", "amount": 100, "commitment_hash": valid },
+            { "stark_pub_key": "garbage", "amount": 100, "commitment_hash": "0xbad" },
+        ]),
+    )
+    .await;
+    assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+
+    let count = sqlx::query_scalar!(
+        "SELECT COUNT(*) FROM deposits WHERE commitment_hash = $1",
+        normalize_commitment_hash(&valid).unwrap()
+    )
+    .fetch_one(&app.db)
+    .await
+    .unwrap();
+    assert_eq!(count, Some(0));
+}
+
+async fn post_deposit(app: &AppState, body: serde_json::Value) -> (StatusCode, serde_json::Value) {
+    let request = Request::builder()
+        .method("POST")
+        .uri("/deposit")
+        .header("content-type", "application/json")
+        .body(Body::from(body.to_string()))
+        .unwrap();
+
+    let response = create_router(app.clone()).oneshot(request).await.unwrap();
+    let status = response.status();
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    (status, serde_json::from_slice(&body).unwrap_or_default())
+}
+
+#[tokio::test]
+async fn test_duplicate_commitment_hash_is_rejected() {
+    let app = create_test_app().await;
+    let commitment_hash = format!("0x{}", uuid::Uuid::new_v4().simple());
+
+    insert_deposit(&app.db, "0x123", 1000, &commitment_hash)
+        .await
+        .unwrap();
+
+    let (status, body) = post_deposit(
+        &app,
+        json!({
+            "stark_pub_key": "0x456",
+            "amount": 1000,
+            "commitment_hash": commitment_hash
+        }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::CONFLICT);
+    assert_eq!(body["code"], DUPLICATE_COMMITMENT);
+
+    // A fresh idempotency key does not get around the check
+    let (status, body) = post_deposit(
+        &app,
+        json!({
+            "stark_pub_key": "0x456",
+            "amount": 1000,
+            "commitment_hash": commitment_hash,
+            "idempotency_key": format!("test-key-{}", uuid::Uuid::new_v4())
+        }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::CONFLICT);
+    assert_eq!(body["code"], DUPLICATE_COMMITMENT);
+
+    let count = sqlx::query_scalar!(
+        "SELECT COUNT(*) FROM deposits WHERE commitment_hash = $1",
+        normalize_commitment_hash(&commitment_hash).unwrap()
+    )
+    .fetch_one(&app.db)
+    .await
+    .unwrap();
+    assert_eq!(count, Some(1));
+}
+
+#[tokio::test]
+async fn test_check_commitment_hash_unique() {
+    let app = create_test_app().await;
+    let commitment_hash = format!("0x{}", uuid::Uuid::new_v4().simple());
+
+    assert!(check_commitment_hash_unique(&app.db, &commitment_hash)
+        .await
+        .unwrap());
+
+    insert_deposit(&app.db, "0x123", 1000, &commitment_hash)
+        .await
+        .unwrap();
+
+    assert!(!check_commitment_hash_unique(&app.db, &commitment_hash)
+        .await
+        .unwrap());
+}
+
+#[tokio::test]
+async fn test_deposit_stark_pub_key_is_stored_normalized() {
+    let app = create_test_app().await;
+
+    for stark_pub_key in ["0xABC", "0x000abc"] {
+        let (status, body) = post_deposit(
+            &app,
+            json!({
+                "stark_pub_key": stark_pub_key,
+                "amount": 1000,
+                "commitment_hash": format!("0x{}", uuid::Uuid::new_v4().simple())
+            }),
+        )
+        .await;
+        assert_eq!(status, StatusCode::CREATED);
+
+        let stored = sqlx::query_scalar!(
+            "SELECT stark_pub_key FROM deposits WHERE id = $1",
+            body["deposit_id"].as_i64().unwrap() as i32
+        )
+        .fetch_one(&app.db)
+        .await
+        .unwrap();
+        assert_eq!(
+            stored,
+            "0x0000000000000000000000000000000000000000000000000000000000000abc"
+        );
+    }
+}
+
+#[tokio::test]
+async fn test_deposit_amount_bounds() {
+    let app = create_test_app().await;
+    let cases = [
+        (0i64, "amount must be at least 1 cent"),
+        (
+            1_000_000_000_001,
+            "amount exceeds maximum of 1,000,000,000,000 USD cents",
+        ),
+    ];
+
+    for (amount, expected) in cases {
+        let request = Request::builder()
+            .method("POST")
+            .uri("/deposit")
+            .header("content-type", "application/json")
+            .body(Body::from(
+                json!({
+                    "stark_pub_key": "0x123",
+                    "amount": amount,
+                    "commitment_hash": format!("0x{}", uuid::Uuid::new_v4().simple())
+                })
+                .to_string(),
+            ))
+            .unwrap();
+
+        let response = create_router(app.as_ref().clone())
+            .oneshot(request)
+            .await
+            .unwrap();
+        let status = response.status();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+
+        assert_eq!(status, StatusCode::BAD_REQUEST, "amount {}", amount);
+        assert_eq!(String::from_utf8_lossy(&body), expected);
+    }
+}
+
+#[tokio::test]
+async fn test_commitment_hash_is_normalized_before_storage() {
+    let app = create_test_app().await;
+    let digits = uuid::Uuid::new_v4().simple().to_string();
+
+    let (status, body) = post_deposit(
+        &app,
+        json!({
+            "stark_pub_key": "0x123",
+            "amount": 1000,
+            "commitment_hash": format!("0X{}", digits.to_uppercase())
+        }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::CREATED);
+
+    let stored = sqlx::query_scalar!(
+        "SELECT commitment_hash FROM deposits WHERE id = $1",
+        body["deposit_id"].as_i64().unwrap() as i32
+    )
+    .fetch_one(&app.db)
+    .await
+    .unwrap();
+    assert_eq!(stored, format!("{:0>64}", digits));
+
+    // The same hash written differently is still a duplicate
+    let (status, _) = post_deposit(
+        &app,
+        json!({ "stark_pub_key": "0x123", "amount": 1000, "commitment_hash": digits }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::CONFLICT);
+
+    let (status, _) = post_deposit(
+        &app,
+        json!({ "stark_pub_key": "0x123", "amount": 1000, "commitment_hash": "0xnothex" }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+}
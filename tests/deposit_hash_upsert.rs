@@ -0,0 +1,43 @@
+#[path = "utils.rs"]
+mod utils;
+
+use utils::create_test_app;
+use zeroxbridge_sequencer::db::database::{upsert_deposit_hash_event, DepositHashAppended};
+
+fn deposit_hash_event(index: i64, root_hash: u8, elements_count: i64) -> DepositHashAppended {
+    DepositHashAppended {
+        id: 0,
+        index,
+        commitment_hash: vec![0xcc; 32],
+        root_hash: vec![root_hash; 32],
+        elements_count,
+        block_number: 100,
+        created_at: None,
+        updated_at: None,
+    }
+}
+
+#[tokio::test]
+async fn test_reprocessed_deposit_hash_event_updates_existing_row() {
+    let app = create_test_app().await;
+    // Random index so concurrent runs never share a row
+    let index = (rand::random::<u32>() as i64) + 1_000_000;
+
+    let first_id = upsert_deposit_hash_event(&app.db, &deposit_hash_event(index, 0x01, 1))
+        .await
+        .expect("Failed to insert deposit hash event");
+    let second_id = upsert_deposit_hash_event(&app.db, &deposit_hash_event(index, 0x02, 2))
+        .await
+        .expect("Failed to upsert deposit hash event");
+
+    assert_eq!(first_id, second_id);
+
+    let rows: Vec<(Vec<u8>, i64)> =
+        sqlx::query_as("SELECT root_hash, elements_count FROM deposit_hashes WHERE index = $1")
+            .bind(index)
+            .fetch_all(&app.db)
+            .await
+            .unwrap();
+
+    assert_eq!(rows, vec![(vec![0x02; 32], 2)]);
+}
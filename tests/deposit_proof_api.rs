@@ -0,0 +1,102 @@
+#[path = "utils.rs"]
+mod utils;
+
+use axum::{
+    body::Body,
+    http::{Request, StatusCode},
+};
+use tower::ServiceExt;
+use utils::create_test_app;
+use zeroxbridge_sequencer::api::routes::create_router;
+use zeroxbridge_sequencer::db::database::{fetch_deposit_by_id, insert_deposit};
+use zeroxbridge_sequencer::merkle::MerkleProofJson;
+
+fn random_commitment() -> String {
+    format!("0x{}", uuid::Uuid::new_v4().simple())
+}
+
+fn proof_request(id: i32) -> Request<Body> {
+    Request::builder()
+        .method("GET")
+        .uri(format!("/deposits/{}/proof", id))
+        .body(Body::empty())
+        .unwrap()
+}
+
+#[tokio::test]
+async fn test_deposit_proof_validates_against_root() {
+    let app = create_test_app().await;
+
+    let commitments: Vec<String> = (0..3).map(|_| random_commitment()).collect();
+    let mut deposits = Vec::new();
+    for commitment in &commitments {
+        let id = insert_deposit(&app.db, "0xabc", 100, commitment)
+            .await
+            .unwrap();
+        deposits.push(fetch_deposit_by_id(&app.db, id).await.unwrap().unwrap());
+    }
+
+    let mut conn = app.db.acquire().await.unwrap();
+    app.deposit_tree
+        .add_deposits(&mut conn, &deposits)
+        .await
+        .expect("Failed to add deposits to the tree");
+
+    let stored = fetch_deposit_by_id(&app.db, deposits[1].id)
+        .await
+        .unwrap()
+        .unwrap();
+    assert!(stored.leaf_index.is_some());
+
+    let router = create_router(app.as_ref().clone());
+    let response = router.oneshot(proof_request(deposits[1].id)).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let proof: MerkleProofJson = serde_json::from_slice(&body).unwrap();
+
+    let root = app.deposit_tree.get_root().await.unwrap();
+    assert_eq!(proof.root, format!("0x{}", hex::encode(root)));
+    assert!(proof.elements_count >= 3);
+    assert!(app
+        .deposit_tree
+        .verify_proof(proof, &commitments[1])
+        .await
+        .unwrap());
+
+    for deposit in &deposits {
+        sqlx::query!("DELETE FROM deposits WHERE id = $1", deposit.id)
+            .execute(&app.db)
+            .await
+            .unwrap();
+    }
+}
+
+#[tokio::test]
+async fn test_deposit_proof_not_in_tree_returns_conflict() {
+    let app = create_test_app().await;
+
+    let id = insert_deposit(&app.db, "0xabc", 100, &random_commitment())
+        .await
+        .unwrap();
+
+    let router = create_router(app.as_ref().clone());
+    let response = router.oneshot(proof_request(id)).await.unwrap();
+    assert_eq!(response.status(), StatusCode::CONFLICT);
+
+    sqlx::query!("DELETE FROM deposits WHERE id = $1", id)
+        .execute(&app.db)
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn test_deposit_proof_unknown_deposit_returns_not_found() {
+    let app = create_test_app().await;
+    let router = create_router(app.as_ref().clone());
+
+    let response = router.oneshot(proof_request(-1)).await.unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+}
@@ -0,0 +1,127 @@
+#[path = "utils.rs"]
+mod utils;
+
+use utils::create_test_app;
+use zeroxbridge_sequencer::db::database::{
+    link_deposits_to_proof_job, schedule_deposit_finalization,
+};
+
+async fn insert_pending_deposit(pool: &sqlx::PgPool) -> i32 {
+    sqlx::query_scalar!(
+        r#"
+        INSERT INTO deposits (stark_pub_key, amount, commitment_hash, status)
+        VALUES ('0x123', 1000, $1, 'pending')
+        RETURNING id
+        "#,
+        format!("0x{}", uuid::Uuid::new_v4().simple())
+    )
+    .fetch_one(pool)
+    .await
+    .unwrap()
+}
+
+async fn insert_proof_job(pool: &sqlx::PgPool) -> i64 {
+    sqlx::query_scalar!(
+        r#"
+        INSERT INTO proof_jobs (job_id, calldata_dir, layout, hasher, stone_version, memory_verification, status, current_stage)
+        VALUES ($1, '/tmp/calldata', 'recursive_with_poseidon', 'keccak_160_lsb', 'stone6', 'true', 'processing', 'final_submitted')
+        RETURNING id
+        "#,
+        rand::random::<u32>() as i64 + 10_000_000
+    )
+    .fetch_one(pool)
+    .await
+    .unwrap()
+}
+
+async fn deposit_status(pool: &sqlx::PgPool, id: i32) -> (String, Option<i64>) {
+    let row = sqlx::query!(
+        "SELECT status, finalization_block FROM deposits WHERE id = $1",
+        id
+    )
+    .fetch_one(pool)
+    .await
+    .unwrap();
+    (row.status, row.finalization_block)
+}
+
+#[tokio::test]
+async fn test_only_linked_deposits_are_scheduled_for_finalization() {
+    let app = create_test_app().await;
+    let job = insert_proof_job(&app.db).await;
+    let other_job = insert_proof_job(&app.db).await;
+
+    let linked = insert_pending_deposit(&app.db).await;
+    let other = insert_pending_deposit(&app.db).await;
+    let unlinked = insert_pending_deposit(&app.db).await;
+
+    assert_eq!(
+        link_deposits_to_proof_job(&app.db, job, &[linked])
+            .await
+            .unwrap(),
+        1
+    );
+    link_deposits_to_proof_job(&app.db, other_job, &[other])
+        .await
+        .unwrap();
+
+    let scheduled = schedule_deposit_finalization(&app.db, job, 500)
+        .await
+        .unwrap();
+
+    assert_eq!(scheduled, vec![linked]);
+    assert_eq!(
+        deposit_status(&app.db, linked).await,
+        ("PROOF_SUBMITTED".to_string(), Some(500))
+    );
+    assert_eq!(
+        deposit_status(&app.db, other).await,
+        ("pending".to_string(), None)
+    );
+    assert_eq!(
+        deposit_status(&app.db, unlinked).await,
+        ("pending".to_string(), None)
+    );
+
+    sqlx::query!(
+        "DELETE FROM deposits WHERE id = ANY($1)",
+        &[linked, other, unlinked][..]
+    )
+    .execute(&app.db)
+    .await
+    .unwrap();
+    sqlx::query!(
+        "DELETE FROM proof_jobs WHERE id = ANY($1)",
+        &[job, other_job][..]
+    )
+    .execute(&app.db)
+    .await
+    .unwrap();
+}
+
+#[tokio::test]
+async fn test_deleting_proof_job_unlinks_deposits() {
+    let app = create_test_app().await;
+    let job = insert_proof_job(&app.db).await;
+    let deposit = insert_pending_deposit(&app.db).await;
+    link_deposits_to_proof_job(&app.db, job, &[deposit])
+        .await
+        .unwrap();
+
+    sqlx::query!("DELETE FROM proof_jobs WHERE id = $1", job)
+        .execute(&app.db)
+        .await
+        .unwrap();
+
+    let proof_job_id: Option<i64> =
+        sqlx::query_scalar!("SELECT proof_job_id FROM deposits WHERE id = $1", deposit)
+            .fetch_one(&app.db)
+            .await
+            .unwrap();
+    assert_eq!(proof_job_id, None);
+
+    sqlx::query!("DELETE FROM deposits WHERE id = $1", deposit)
+        .execute(&app.db)
+        .await
+        .unwrap();
+}
@@ -0,0 +1,145 @@
+#[path = "utils.rs"]
+mod utils;
+
+use axum::{
+    body::{to_bytes, Body},
+    http::{Request, StatusCode},
+};
+use serde_json::{json, Value};
+use tower::ServiceExt;
+use utils::create_test_app;
+use zeroxbridge_sequencer::api::routes::{create_router, AppState};
+
+const ADMIN_TOKEN: &str = "test-admin-token";
+
+/// Inserts a deposit emitted in `block_number` with `status`, returning its id
+async fn insert_deposit(pool: &sqlx::PgPool, block_number: i64, status: &str) -> i32 {
+    sqlx::query_scalar!(
+        r#"
+        INSERT INTO deposits (stark_pub_key, amount, commitment_hash, status, block_number, retry_count, finalization_block)
+        VALUES ('0xreorg', 1000, $1, $2, $3, 2, $4)
+        RETURNING id
+        "#,
+        format!("0x{}", uuid::Uuid::new_v4().simple()),
+        status,
+        block_number,
+        (status != "pending").then_some(block_number + 100)
+    )
+    .fetch_one(pool)
+    .await
+    .unwrap()
+}
+
+/// Status, retry count and finalization block of deposit `id`, `None` once it is deleted
+async fn fetch_state(pool: &sqlx::PgPool, id: i32) -> Option<(String, i32, Option<i64>)> {
+    sqlx::query!(
+        "SELECT status, retry_count, finalization_block FROM deposits WHERE id = $1",
+        id
+    )
+    .fetch_optional(pool)
+    .await
+    .unwrap()
+    .map(|row| (row.status, row.retry_count, row.finalization_block))
+}
+
+async fn reset_after_reorg(
+    app: &AppState,
+    from_block: i64,
+    token: Option<&str>,
+) -> (StatusCode, Option<Value>) {
+    let mut builder = Request::builder()
+        .method("POST")
+        .uri("/admin/deposits/reset-after-reorg")
+        .header("content-type", "application/json");
+    if let Some(token) = token {
+        builder = builder.header("x-admin-token", token);
+    }
+    let body = json!({ "from_block": from_block }).to_string();
+    let request = builder.body(Body::from(body)).unwrap();
+    let response = create_router(app.clone()).oneshot(request).await.unwrap();
+    let status = response.status();
+    let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    (status, serde_json::from_slice(&body).ok())
+}
+
+#[tokio::test]
+async fn test_reset_after_reorg_only_invalidates_unprocessed_deposits_from_block_onwards() {
+    let app = create_test_app().await;
+    // Far above any block other tests record, so every deposit from `from_block` on is ours
+    let base = 1_000_000_000_000 + rand::random::<u32>() as i64;
+    let from_block = base + 10;
+    sqlx::query!("DELETE FROM deposits WHERE block_number >= $1", base)
+        .execute(&app.db)
+        .await
+        .unwrap();
+
+    let before = insert_deposit(&app.db, base, "pending").await;
+    let at_fork = insert_deposit(&app.db, from_block, "pending").await;
+    let after_fork = insert_deposit(&app.db, base + 20, "pending").await;
+
+    let (status, body) = reset_after_reorg(&app, from_block, Some(ADMIN_TOKEN)).await;
+
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body.unwrap()["affected"], 2);
+    for id in [at_fork, after_fork] {
+        assert_eq!(
+            fetch_state(&app.db, id).await,
+            Some(("INVALIDATED".to_string(), 2, None))
+        );
+    }
+    assert_eq!(
+        fetch_state(&app.db, before).await,
+        Some(("pending".to_string(), 2, None))
+    );
+
+    sqlx::query!("DELETE FROM deposits WHERE block_number >= $1", base)
+        .execute(&app.db)
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn test_reset_after_reorg_leaves_processed_and_invalidated_deposits_alone() {
+    let app = create_test_app().await;
+    let base = 1_000_000_000_000 + rand::random::<u32>() as i64;
+    sqlx::query!("DELETE FROM deposits WHERE block_number >= $1", base)
+        .execute(&app.db)
+        .await
+        .unwrap();
+
+    // Replaying any of these could mint twice, or bring back a deposit from an orphaned block
+    let mut untouched = Vec::new();
+    for status in [
+        "processed",
+        "PROOF_SUBMITTED",
+        "READY_TO_CLAIM",
+        "INVALIDATED",
+    ] {
+        let id = insert_deposit(&app.db, base, status).await;
+        untouched.push((id, fetch_state(&app.db, id).await));
+    }
+
+    let (status, body) = reset_after_reorg(&app, base, Some(ADMIN_TOKEN)).await;
+
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body.unwrap()["affected"], 0);
+    for (id, state) in untouched {
+        assert_eq!(fetch_state(&app.db, id).await, state);
+    }
+
+    sqlx::query!("DELETE FROM deposits WHERE block_number >= $1", base)
+        .execute(&app.db)
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn test_reset_after_reorg_requires_admin_token() {
+    let app = create_test_app().await;
+
+    let (status, _) = reset_after_reorg(&app, 0, None).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+
+    let (status, _) = reset_after_reorg(&app, 0, Some("wrong-token")).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+}
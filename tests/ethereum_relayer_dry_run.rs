@@ -0,0 +1,127 @@
+#[path = "utils.rs"]
+mod utils;
+
+use mockito::{mock, Matcher, Mock};
+use sqlx::PgPool;
+use url::Url;
+use utils::create_test_config;
+use zeroxbridge_sequencer::relayer::ethereum_relayer::{
+    EthereumRelayer, RelayerError, WithdrawalWithProof,
+};
+
+/// ABI-encoded `Error("Invalid proof")`
+const INVALID_PROOF_REVERT: &str = "0x08c379a00000000000000000000000000000000000000000000000000000000000000020000000000000000000000000000000000000000000000000000000000000000d496e76616c69642070726f6f6600000000000000000000000000000000000000";
+
+fn rpc_mock(method: &str, result: &str) -> Mock {
+    mock("POST", "/")
+        .match_body(Matcher::Regex(method.to_string()))
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(format!(r#"{{"jsonrpc":"2.0","id":0,"result":{}}}"#, result))
+        .create()
+}
+
+/// Matches a `method` request whose calldata carries `commitment`, so other tests' calls
+/// never hit it
+fn commitment_matcher(method: &str, commitment: &str) -> Matcher {
+    Matcher::Regex(format!(
+        "(?s)({method}.*{commitment}|{commitment}.*{method})"
+    ))
+}
+
+fn transaction_setup_mocks() -> Vec<Mock> {
+    vec![
+        rpc_mock(
+            "eth_accounts",
+            r#"["0x00000000000000000000000000000000000000cc"]"#,
+        ),
+        rpc_mock("eth_gasPrice", r#""0x3b9aca00""#),
+        rpc_mock("eth_getTransactionCount", r#""0x1""#),
+        rpc_mock("eth_estimateGas", r#""0x5208""#),
+    ]
+}
+
+async fn dry_run_relayer() -> EthereumRelayer {
+    // Dry runs never touch the database
+    let db_pool = PgPool::connect_lazy("postgres://localhost/unused").unwrap();
+    let mut config = create_test_config().relayer;
+    config.dry_run = true;
+
+    EthereumRelayer::new(
+        db_pool,
+        Url::parse(&mockito::server_url()).unwrap(),
+        "0x0000000000000000000000000000000000000001",
+        config,
+    )
+    .await
+    .unwrap()
+}
+
+fn withdrawal(commitment: &str) -> WithdrawalWithProof {
+    WithdrawalWithProof {
+        withdrawal_id: 1,
+        retry_count: 0,
+        stark_pub_key: "0x123".to_string(),
+        amount: 1000,
+        l2_tx_id: String::new(),
+        l2_tx_hash: None,
+        commitment_hash: format!("0x{}", commitment),
+        proof_params: vec![],
+        proof_data: vec![],
+        amount_precision: 2,
+    }
+}
+
+#[tokio::test]
+async fn test_dry_run_calls_instead_of_sending() {
+    let commitment = "d7".repeat(32);
+    let _setup = transaction_setup_mocks();
+    let call = mock("POST", "/")
+        .match_body(commitment_matcher("eth_call", &commitment))
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"jsonrpc":"2.0","id":0,"result":"0x"}"#)
+        .expect(1)
+        .create();
+    let send = mock("POST", "/")
+        .match_body(commitment_matcher("eth_sendTransaction", &commitment))
+        .expect(0)
+        .create();
+
+    let relayer = dry_run_relayer().await;
+    let result = relayer
+        .send_unlock_funds_transaction(&withdrawal(&commitment))
+        .await
+        .unwrap();
+
+    assert_eq!(result, None);
+    call.assert();
+    send.assert();
+}
+
+#[tokio::test]
+async fn test_dry_run_reports_revert_reason() {
+    let commitment = "d8".repeat(32);
+    let _setup = transaction_setup_mocks();
+    let _call = mock("POST", "/")
+        .match_body(commitment_matcher("eth_call", &commitment))
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(format!(
+            r#"{{"jsonrpc":"2.0","id":0,"error":{{"code":3,"message":"execution reverted","data":"{}"}}}}"#,
+            INVALID_PROOF_REVERT
+        ))
+        .create();
+
+    let relayer = dry_run_relayer().await;
+    let result = relayer
+        .send_unlock_funds_transaction(&withdrawal(&commitment))
+        .await;
+
+    match result {
+        Err(RelayerError::ContractError(reason)) => {
+            assert!(reason.contains("Invalid proof"), "{}", reason)
+        }
+        other => panic!("expected ContractError, got {:?}", other),
+    }
+}
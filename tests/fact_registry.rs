@@ -0,0 +1,176 @@
+#[path = "utils.rs"]
+mod utils;
+
+use mockito::{mock, Matcher, Mock};
+use sqlx::PgPool;
+use tempfile::tempdir;
+use utils::{create_test_app, create_test_config};
+use zeroxbridge_sequencer::relayer::proof_submission::{
+    ProofSubmissionConfig, ProofSubmissionError, ProofSubmissionRelayer,
+};
+
+const FACT_REGISTRY: &str = "0xfac7";
+
+/// `is_valid` answers `valid` for `fact`
+fn is_valid_mock(fact: &str, valid: bool) -> Mock {
+    mock("POST", "/")
+        .match_body(Matcher::Regex(format!(
+            r#"starknet_call.*"{}".*"{}""#,
+            FACT_REGISTRY, fact
+        )))
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(format!(
+            r#"{{"jsonrpc":"2.0","id":1,"result":["{}"]}}"#,
+            if valid { "0x1" } else { "0x0" }
+        ))
+        .create()
+}
+
+fn block_number_mock() -> Mock {
+    mock("POST", "/")
+        .match_body(Matcher::Regex("eth_blockNumber".to_string()))
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"jsonrpc":"2.0","id":0,"result":"0x10"}"#)
+        .create()
+}
+
+async fn test_relayer(pool: PgPool, calldata_base_dir: &std::path::Path) -> ProofSubmissionRelayer {
+    std::env::set_var("STARKNET_RPC_URL", mockito::server_url());
+    std::env::set_var("ETHEREUM_RPC_URL", mockito::server_url());
+
+    let mut config = ProofSubmissionConfig::from(create_test_config());
+    config.rpc_url = mockito::server_url();
+    config.ethereum_rpc_url = mockito::server_url();
+    config.calldata_base_dir = calldata_base_dir.to_path_buf();
+    config.fact_registry_address = Some(FACT_REGISTRY.to_string());
+    ProofSubmissionRelayer::new(pool, config)
+        .await
+        .expect("Failed to create relayer")
+}
+
+/// Proof job whose final proof was already submitted, so resuming it only completes it
+async fn insert_final_submitted_job(pool: &PgPool, job_id: i64, calldata_dir: &std::path::Path) {
+    // Every call was already made, so the calldata is only hashed before resuming
+    for file in ["initial", "final"] {
+        std::fs::write(calldata_dir.join(file), "0x1").unwrap();
+    }
+    sqlx::query!("DELETE FROM proof_jobs WHERE job_id = $1", job_id)
+        .execute(pool)
+        .await
+        .unwrap();
+    sqlx::query!(
+        r#"
+        INSERT INTO proof_jobs (job_id, calldata_dir, layout, hasher, stone_version, memory_verification, status, current_stage)
+        VALUES ($1, $2, 'recursive_with_poseidon', 'keccak_160_lsb', 'stone6', 'true', 'processing', 'final_submitted')
+        "#,
+        job_id,
+        calldata_dir.display().to_string()
+    )
+    .execute(pool)
+    .await
+    .unwrap();
+}
+
+async fn resume_job(
+    relayer: &ProofSubmissionRelayer,
+    calldata_dir: &std::path::Path,
+    job_id: i64,
+    fact_hash: &str,
+) -> Result<(), ProofSubmissionError> {
+    relayer
+        .submit_proof_from_calldata(
+            calldata_dir.to_path_buf(),
+            job_id as u64,
+            "recursive_with_poseidon".to_string(),
+            "keccak_160_lsb".to_string(),
+            "stone6".to_string(),
+            "true".to_string(),
+            true,
+            Vec::new(),
+            Some(fact_hash.to_string()),
+        )
+        .await
+}
+
+#[tokio::test]
+async fn test_verify_fact_registered_reads_registry() {
+    let app = create_test_app().await;
+    let base_dir = tempdir().unwrap();
+    let _registered = is_valid_mock("0xabc1", true);
+    let _unregistered = is_valid_mock("0xabc2", false);
+
+    let relayer = test_relayer(app.db.clone(), base_dir.path()).await;
+
+    assert!(relayer.verify_fact_registered("0xabc1").await.unwrap());
+    assert!(!relayer.verify_fact_registered("0xabc2").await.unwrap());
+}
+
+#[tokio::test]
+async fn test_unregistered_fact_keeps_job_open() {
+    let app = create_test_app().await;
+    let base_dir = tempdir().unwrap();
+    let job_id: i64 = 9_500_001;
+    insert_final_submitted_job(&app.db, job_id, base_dir.path()).await;
+    let _block = block_number_mock();
+    let registry = is_valid_mock("0xabc3", false).expect(1);
+
+    let relayer = test_relayer(app.db.clone(), base_dir.path()).await;
+    let result = resume_job(&relayer, base_dir.path(), job_id, "0xabc3").await;
+
+    registry.assert();
+    match result {
+        Err(ProofSubmissionError::TransactionFailed(message)) => {
+            assert_eq!(message, "Fact not registered")
+        }
+        other => panic!("expected TransactionFailed, got {:?}", other),
+    }
+
+    let job = sqlx::query!(
+        "SELECT status, current_stage, fact_hash FROM proof_jobs WHERE job_id = $1",
+        job_id
+    )
+    .fetch_one(&app.db)
+    .await
+    .unwrap();
+    assert_eq!(job.status, "processing");
+    assert_eq!(job.current_stage.as_deref(), Some("final_submitted"));
+    assert_eq!(job.fact_hash, None);
+
+    sqlx::query!("DELETE FROM proof_jobs WHERE job_id = $1", job_id)
+        .execute(&app.db)
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn test_registered_fact_completes_job_and_is_stored() {
+    let app = create_test_app().await;
+    let base_dir = tempdir().unwrap();
+    let job_id: i64 = 9_500_002;
+    insert_final_submitted_job(&app.db, job_id, base_dir.path()).await;
+    let _block = block_number_mock();
+    let registry = is_valid_mock("0xabc4", true).expect(1);
+
+    let relayer = test_relayer(app.db.clone(), base_dir.path()).await;
+    resume_job(&relayer, base_dir.path(), job_id, "0xabc4")
+        .await
+        .expect("A registered fact should complete the job");
+
+    registry.assert();
+    let job = sqlx::query!(
+        "SELECT status, fact_hash FROM proof_jobs WHERE job_id = $1",
+        job_id
+    )
+    .fetch_one(&app.db)
+    .await
+    .unwrap();
+    assert_eq!(job.status, "completed");
+    assert_eq!(job.fact_hash.as_deref(), Some("0xabc4"));
+
+    sqlx::query!("DELETE FROM proof_jobs WHERE job_id = $1", job_id)
+        .execute(&app.db)
+        .await
+        .unwrap();
+}
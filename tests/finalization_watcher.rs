@@ -0,0 +1,77 @@
+#[path = "utils.rs"]
+mod utils;
+
+use url::Url;
+use utils::create_test_app;
+use zeroxbridge_sequencer::workers::finalization::FinalizationWatcher;
+
+async fn insert_submitted_deposit(pool: &sqlx::PgPool, finalization_block: i64) -> i32 {
+    sqlx::query_scalar!(
+        r#"
+        INSERT INTO deposits (stark_pub_key, amount, commitment_hash, status, finalization_block)
+        VALUES ('0x123', 1000, $1, 'PROOF_SUBMITTED', $2)
+        RETURNING id
+        "#,
+        format!("0x{}", uuid::Uuid::new_v4().simple()),
+        finalization_block
+    )
+    .fetch_one(pool)
+    .await
+    .unwrap()
+}
+
+async fn deposit_status(pool: &sqlx::PgPool, id: i32) -> String {
+    sqlx::query_scalar!("SELECT status FROM deposits WHERE id = $1", id)
+        .fetch_one(pool)
+        .await
+        .unwrap()
+}
+
+#[tokio::test]
+async fn test_deposits_become_claimable_after_challenge_window() {
+    let app = create_test_app().await;
+    let finalized = insert_submitted_deposit(&app.db, 100).await;
+    let waiting = insert_submitted_deposit(&app.db, 200).await;
+
+    // Ethereum is at block 150 (0x96)
+    let _mock = mockito::mock("POST", "/")
+        .match_body(mockito::Matcher::Regex("eth_blockNumber".to_string()))
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"jsonrpc":"2.0","id":0,"result":"0x96"}"#)
+        .create();
+
+    let watcher =
+        FinalizationWatcher::new(app.db.clone(), Url::parse(&mockito::server_url()).unwrap());
+    let released = watcher.finalize_ready_deposits().await.unwrap();
+
+    assert!(released.contains(&finalized));
+    assert!(!released.contains(&waiting));
+    assert_eq!(deposit_status(&app.db, finalized).await, "READY_TO_CLAIM");
+    assert_eq!(deposit_status(&app.db, waiting).await, "PROOF_SUBMITTED");
+
+    sqlx::query!(
+        "DELETE FROM deposits WHERE id = ANY($1)",
+        &[finalized, waiting][..]
+    )
+    .execute(&app.db)
+    .await
+    .unwrap();
+}
+
+#[tokio::test]
+async fn test_rpc_failure_leaves_deposits_untouched() {
+    let app = create_test_app().await;
+    let id = insert_submitted_deposit(&app.db, 0).await;
+
+    // Nothing listens on port 1, so the block number lookup fails
+    let watcher =
+        FinalizationWatcher::new(app.db.clone(), Url::parse("http://127.0.0.1:1").unwrap());
+    assert!(watcher.finalize_ready_deposits().await.is_err());
+    assert_eq!(deposit_status(&app.db, id).await, "PROOF_SUBMITTED");
+
+    sqlx::query!("DELETE FROM deposits WHERE id = $1", id)
+        .execute(&app.db)
+        .await
+        .unwrap();
+}
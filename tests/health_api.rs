@@ -0,0 +1,124 @@
+#[path = "utils.rs"]
+mod utils;
+
+use axum::{
+    body::Body,
+    http::{Request, StatusCode},
+};
+use tower::ServiceExt;
+use utils::{create_test_app, create_test_config};
+use zeroxbridge_sequencer::api::routes::{create_router, AppState};
+use zeroxbridge_sequencer::workers::heartbeat::HeartbeatWriter;
+
+async fn fetch_last_seen(pool: &sqlx::PgPool, instance_id: &str) -> chrono::DateTime<chrono::Utc> {
+    sqlx::query_scalar!(
+        "SELECT last_seen_at FROM sequencer_heartbeat WHERE instance_id = $1",
+        instance_id
+    )
+    .fetch_one(pool)
+    .await
+    .unwrap()
+}
+
+#[tokio::test]
+async fn test_heartbeat_row_created_and_updated() {
+    let app = create_test_app().await;
+    let instance_id = format!("heartbeat-{}", uuid::Uuid::new_v4());
+    let writer = HeartbeatWriter::new(app.db.clone(), instance_id.clone());
+
+    writer.beat().await.unwrap();
+    let first_seen = fetch_last_seen(&app.db, &instance_id).await;
+
+    tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+    writer.beat().await.unwrap();
+    let second_seen = fetch_last_seen(&app.db, &instance_id).await;
+
+    assert!(second_seen > first_seen);
+
+    sqlx::query!(
+        "DELETE FROM sequencer_heartbeat WHERE instance_id = $1",
+        instance_id
+    )
+    .execute(&app.db)
+    .await
+    .unwrap();
+}
+
+#[tokio::test]
+async fn test_health_reports_ok_with_fresh_heartbeat() {
+    let app = create_test_app().await;
+    let instance_id = format!("health-ok-{}", uuid::Uuid::new_v4());
+    HeartbeatWriter::new(app.db.clone(), instance_id.clone())
+        .beat()
+        .await
+        .unwrap();
+
+    let mut config = create_test_config();
+    config.server.instance_id = Some(instance_id.clone());
+    let router = create_router(AppState::new(app.db.clone(), config));
+
+    let request = Request::builder()
+        .method("GET")
+        .uri("/health")
+        .body(Body::empty())
+        .unwrap();
+    let response = router.oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let parsed: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(parsed["status"], "ok");
+    assert_eq!(parsed["instance_id"], instance_id);
+
+    sqlx::query!(
+        "DELETE FROM sequencer_heartbeat WHERE instance_id = $1",
+        instance_id
+    )
+    .execute(&app.db)
+    .await
+    .unwrap();
+}
+
+#[tokio::test]
+async fn test_health_reports_degraded_with_stale_heartbeat() {
+    let app = create_test_app().await;
+    let instance_id = format!("health-stale-{}", uuid::Uuid::new_v4());
+    sqlx::query!(
+        r#"
+        INSERT INTO sequencer_heartbeat (instance_id, last_seen_at)
+        VALUES ($1, NOW() - INTERVAL '10 minutes')
+        "#,
+        instance_id
+    )
+    .execute(&app.db)
+    .await
+    .unwrap();
+
+    let mut config = create_test_config();
+    config.server.instance_id = Some(instance_id.clone());
+    let router = create_router(AppState::new(app.db.clone(), config));
+
+    let request = Request::builder()
+        .method("GET")
+        .uri("/health")
+        .body(Body::empty())
+        .unwrap();
+    let response = router.oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let parsed: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(parsed["status"], "degraded");
+
+    sqlx::query!(
+        "DELETE FROM sequencer_heartbeat WHERE instance_id = $1",
+        instance_id
+    )
+    .execute(&app.db)
+    .await
+    .unwrap();
+}
@@ -1,9 +1,10 @@
 use anyhow::Result;
 use mockito::{mock, Matcher};
-use std::env;
 use std::fs;
-use tokio;
-use zeroxbridge_sequencer::http::client::submit_sharp_proof_job;
+use url::Url;
+use zeroxbridge_sequencer::http::client::{
+    atlantic_job_status, submit_sharp_proof_job, AtlanticError,
+};
 
 fn setup_dummy_files() -> Result<()> {
     fs::create_dir_all("tmp/target/dev")?;
@@ -13,27 +14,30 @@ fn setup_dummy_files() -> Result<()> {
     Ok(())
 }
 
+fn submit_url() -> Result<Url> {
+    Ok(Url::parse(&mockito::server_url())?.join("atlantic-query")?)
+}
+
 #[tokio::test]
 async fn test_submit_sharp_proof_job_positive_l1() -> Result<()> {
     setup_dummy_files()?;
-    env::set_var(
-        "ATLANTIC_API_ENDPOINT",
-        format!("{}/atlantic-query", mockito::server_url()),
-    );
     let m = mock("POST", Matcher::Any)
         .match_query(Matcher::UrlEncoded("apiKey".into(), "test_api".into()))
         .with_status(200)
-        .with_body("Job submitted successfully")
+        .with_body(r#"{"atlanticQueryId":"01JQ0000000000000000000000"}"#)
         .create();
 
     let res = submit_sharp_proof_job(
+        &submit_url()?,
         "test_api".into(),
         "PROOF_VERIFICATION_ON_L1".into(),
         "tmp/target/dev/cairo1.sierra.json".into(),
         "tmp/input.cairo1.txt".into(),
+        3,
+        1,
     )
     .await;
-    assert!(res.is_ok());
+    assert_eq!(res?, "01JQ0000000000000000000000");
     m.assert();
     Ok(())
 }
@@ -41,24 +45,23 @@ async fn test_submit_sharp_proof_job_positive_l1() -> Result<()> {
 #[tokio::test]
 async fn test_submit_sharp_proof_job_positive_l2() -> Result<()> {
     setup_dummy_files()?;
-    env::set_var(
-        "ATLANTIC_API_ENDPOINT",
-        format!("{}/atlantic-query", mockito::server_url()),
-    );
     let m = mock("POST", Matcher::Any)
         .match_query(Matcher::UrlEncoded("apiKey".into(), "test_api".into()))
         .with_status(200)
-        .with_body("Job submitted successfully")
+        .with_body(r#"{"atlanticQueryId":"01JQ0000000000000000000000"}"#)
         .create();
 
     let res = submit_sharp_proof_job(
+        &submit_url()?,
         "test_api".into(),
         "PROOF_VERIFICATION_ON_L2".into(),
         "tmp/target/dev/cairo1.sierra.json".into(),
         "tmp/input.cairo1.txt".into(),
+        3,
+        1,
     )
     .await;
-    assert!(res.is_ok());
+    assert_eq!(res?, "01JQ0000000000000000000000");
     m.assert();
     Ok(())
 }
@@ -66,10 +69,6 @@ async fn test_submit_sharp_proof_job_positive_l2() -> Result<()> {
 #[tokio::test]
 async fn test_submit_sharp_proof_job_negative_l1() -> Result<()> {
     setup_dummy_files()?;
-    env::set_var(
-        "ATLANTIC_API_ENDPOINT",
-        format!("{}/atlantic-query", mockito::server_url()),
-    );
     let m = mock("POST", Matcher::Any)
         .match_query(Matcher::UrlEncoded("apiKey".into(), "bad_api".into()))
         .with_status(400)
@@ -77,13 +76,16 @@ async fn test_submit_sharp_proof_job_negative_l1() -> Result<()> {
         .create();
 
     let res = submit_sharp_proof_job(
+        &submit_url()?,
         "bad_api".into(),
         "PROOF_VERIFICATION_ON_L1".into(),
         "tmp/target/dev/cairo1.sierra.json".into(),
         "tmp/input.cairo1.txt".into(),
+        3,
+        1,
     )
     .await;
-    assert!(res.is_ok());
+    assert!(res.is_err());
     m.assert();
     Ok(())
 }
@@ -91,10 +93,6 @@ async fn test_submit_sharp_proof_job_negative_l1() -> Result<()> {
 #[tokio::test]
 async fn test_submit_sharp_proof_job_negative_l2() -> Result<()> {
     setup_dummy_files()?;
-    env::set_var(
-        "ATLANTIC_API_ENDPOINT",
-        format!("{}/atlantic-query", mockito::server_url()),
-    );
     let m = mock("POST", Matcher::Any)
         .match_query(Matcher::UrlEncoded("apiKey".into(), "bad_api".into()))
         .with_status(400)
@@ -102,13 +100,115 @@ async fn test_submit_sharp_proof_job_negative_l2() -> Result<()> {
         .create();
 
     let res = submit_sharp_proof_job(
+        &submit_url()?,
         "bad_api".into(),
         "PROOF_VERIFICATION_ON_L2".into(),
         "tmp/target/dev/cairo1.sierra.json".into(),
         "tmp/input.cairo1.txt".into(),
+        3,
+        1,
+    )
+    .await;
+    assert!(res.is_err());
+    m.assert();
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_submit_sharp_proof_job_retries_server_errors() -> Result<()> {
+    setup_dummy_files()?;
+    let m = mock("POST", Matcher::Any)
+        .match_query(Matcher::UrlEncoded("apiKey".into(), "flaky_api".into()))
+        .with_status(503)
+        .with_body("Service Unavailable")
+        .expect(3)
+        .create();
+
+    let res = submit_sharp_proof_job(
+        &submit_url()?,
+        "flaky_api".into(),
+        "PROOF_VERIFICATION_ON_L1".into(),
+        "tmp/target/dev/cairo1.sierra.json".into(),
+        "tmp/input.cairo1.txt".into(),
+        2,
+        1,
+    )
+    .await;
+    assert!(matches!(res, Err(AtlanticError::MaxRetriesExceeded)));
+    m.assert();
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_submit_sharp_proof_job_retries_rate_limits() -> Result<()> {
+    setup_dummy_files()?;
+    let m = mock("POST", Matcher::Any)
+        .match_query(Matcher::UrlEncoded("apiKey".into(), "busy_api".into()))
+        .with_status(429)
+        .with_body("Too Many Requests")
+        .expect(2)
+        .create();
+
+    let res = submit_sharp_proof_job(
+        &submit_url()?,
+        "busy_api".into(),
+        "PROOF_VERIFICATION_ON_L1".into(),
+        "tmp/target/dev/cairo1.sierra.json".into(),
+        "tmp/input.cairo1.txt".into(),
+        1,
+        1,
+    )
+    .await;
+    assert!(matches!(res, Err(AtlanticError::MaxRetriesExceeded)));
+    m.assert();
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_submit_sharp_proof_job_does_not_retry_client_errors() -> Result<()> {
+    setup_dummy_files()?;
+    let m = mock("POST", Matcher::Any)
+        .match_query(Matcher::UrlEncoded("apiKey".into(), "forbidden_api".into()))
+        .with_status(403)
+        .with_body("Forbidden")
+        .expect(1)
+        .create();
+
+    let res = submit_sharp_proof_job(
+        &submit_url()?,
+        "forbidden_api".into(),
+        "PROOF_VERIFICATION_ON_L1".into(),
+        "tmp/target/dev/cairo1.sierra.json".into(),
+        "tmp/input.cairo1.txt".into(),
+        3,
+        1,
     )
     .await;
-    assert!(res.is_ok());
+    match res {
+        Err(AtlanticError::HttpError(status, body)) => {
+            assert_eq!(status.as_u16(), 403);
+            assert_eq!(body, "Forbidden");
+        }
+        other => panic!("expected HttpError, got {:?}", other),
+    }
+    m.assert();
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_atlantic_job_status() -> Result<()> {
+    let m = mock("GET", "/atlantic-query/01JQSTATUS00000000000000000/status")
+        .match_query(Matcher::UrlEncoded("apiKey".into(), "test_api".into()))
+        .with_status(200)
+        .with_body(r#"{"atlanticQuery":{"id":"01JQSTATUS00000000000000000","status":"DONE"}}"#)
+        .create();
+
+    let status_url = Url::parse(&format!(
+        "{}/atlantic-query/01JQSTATUS00000000000000000/status",
+        mockito::server_url()
+    ))?;
+    let status = atlantic_job_status(&status_url, "test_api").await?;
+    assert_eq!(status, "DONE");
     m.assert();
     Ok(())
 }
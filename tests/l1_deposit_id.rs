@@ -0,0 +1,80 @@
+#[path = "utils.rs"]
+mod utils;
+
+use alloy::primitives::{Address, U256};
+use alloy::rpc::types::Log;
+use utils::create_test_app;
+use zeroxbridge_sequencer::events::l1_event_watcher::{record_deposit_event, ZeroXBridge};
+use zeroxbridge_sequencer::utils::normalize_commitment_hash;
+
+/// `DepositEvent` log with a random deposit id and commitment hash
+fn deposit_log() -> Log<ZeroXBridge::DepositEvent> {
+    let deposit_id = U256::from(rand::random::<u64>());
+    let commitment_hash = U256::from_be_bytes(rand::random::<[u8; 32]>());
+
+    Log {
+        inner: alloy::primitives::Log {
+            address: Address::from([0x11; 20]),
+            data: ZeroXBridge::DepositEvent {
+                depositId: deposit_id,
+                token: Address::from([0xaa; 20]),
+                assetType: ZeroXBridge::AssetType::ERC20,
+                usdVal: U256::from(2_500),
+                user: Address::from([0xbb; 20]),
+                nonce: U256::from(1),
+                leafIndex: U256::from(0),
+                commitmentHash: commitment_hash,
+                newRoot: U256::from(0),
+                elementCount: U256::from(1),
+            },
+        },
+        block_hash: None,
+        block_number: None,
+        block_timestamp: None,
+        transaction_hash: None,
+        transaction_index: None,
+        log_index: None,
+        removed: false,
+    }
+}
+
+#[tokio::test]
+async fn test_deposit_event_stores_l1_deposit_id() {
+    let app = create_test_app().await;
+    let log = deposit_log();
+    let event = log.data();
+
+    record_deposit_event(&app.db, &log)
+        .await
+        .expect("Failed to record deposit event");
+
+    let stored: Option<String> =
+        sqlx::query_scalar("SELECT l1_deposit_id FROM deposits WHERE commitment_hash = $1")
+            .bind(normalize_commitment_hash(&format!("{:x}", event.commitmentHash)).unwrap())
+            .fetch_one(&app.db)
+            .await
+            .expect("Deposit was not stored");
+
+    assert_eq!(stored, Some(event.depositId.to_string()));
+}
+
+#[tokio::test]
+async fn test_duplicate_deposit_event_keeps_one_row() {
+    let app = create_test_app().await;
+    let log = deposit_log();
+    let l1_deposit_id = log.data().depositId.to_string();
+
+    for _ in 0..2 {
+        record_deposit_event(&app.db, &log)
+            .await
+            .expect("Failed to record deposit event");
+    }
+
+    let rows: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM deposits WHERE l1_deposit_id = $1")
+        .bind(&l1_deposit_id)
+        .fetch_one(&app.db)
+        .await
+        .unwrap();
+
+    assert_eq!(rows, 1);
+}
@@ -0,0 +1,166 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio_stream::StreamExt;
+use zeroxbridge_sequencer::events::l1_event_watcher::{
+    block_ranges, fetch_logs_chunked, stream_chunked_logs, L1EventStreamError, RangeValidationError,
+};
+
+const CHUNK_SIZE: u64 = 10;
+const CAPACITY: usize = 16;
+
+/// Fetcher yielding one item per block, counting every item it hands to the stream
+fn counting_fetcher(
+    produced: Arc<AtomicUsize>,
+) -> impl FnMut(u64, u64) -> std::future::Ready<Result<Vec<u64>, L1EventStreamError>> + Send + 'static
+{
+    move |start, end| {
+        let items: Vec<u64> = (start..=end).collect();
+        produced.fetch_add(items.len(), Ordering::SeqCst);
+        std::future::ready(Ok(items))
+    }
+}
+
+#[tokio::test]
+async fn test_slow_consumer_bounds_buffered_events() {
+    let produced = Arc::new(AtomicUsize::new(0));
+    let mut stream = stream_chunked_logs(
+        0,
+        999,
+        CHUNK_SIZE,
+        CAPACITY,
+        counting_fetcher(produced.clone()),
+    );
+
+    let mut consumed = 0;
+    let mut max_in_flight = 0;
+    while let Some(item) = stream.next().await {
+        assert_eq!(item.unwrap(), consumed as u64);
+        consumed += 1;
+        max_in_flight = max_in_flight.max(produced.load(Ordering::SeqCst) - consumed);
+        tokio::time::sleep(Duration::from_millis(1)).await;
+    }
+
+    assert_eq!(consumed, 1000);
+    // Fetching stalls on a full channel, so at most one chunk sits beyond its capacity
+    assert!(
+        max_in_flight <= CAPACITY + CHUNK_SIZE as usize,
+        "{} events buffered ahead of the consumer",
+        max_in_flight
+    );
+}
+
+#[tokio::test]
+async fn test_fetch_error_ends_stream() {
+    let mut stream = stream_chunked_logs(0, 99, CHUNK_SIZE, CAPACITY, |start, end| async move {
+        if start >= 20 {
+            Err(L1EventStreamError::Rpc("boom".to_string()))
+        } else {
+            Ok((start..=end).collect::<Vec<u64>>())
+        }
+    });
+
+    let mut items = Vec::new();
+    while let Some(item) = stream.next().await {
+        items.push(item);
+    }
+
+    assert_eq!(items.len(), 21);
+    assert!(items[..20].iter().all(|item| item.is_ok()));
+    assert!(matches!(items[20], Err(L1EventStreamError::Rpc(_))));
+}
+
+#[tokio::test]
+async fn test_dropping_stream_stops_fetching() {
+    let produced = Arc::new(AtomicUsize::new(0));
+    let mut stream = stream_chunked_logs(
+        0,
+        u64::MAX,
+        CHUNK_SIZE,
+        CAPACITY,
+        counting_fetcher(produced.clone()),
+    );
+
+    stream.next().await.unwrap().unwrap();
+    drop(stream);
+    tokio::time::sleep(Duration::from_millis(50)).await;
+
+    let after_drop = produced.load(Ordering::SeqCst);
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    assert_eq!(produced.load(Ordering::SeqCst), after_drop);
+    assert!(after_drop <= CAPACITY + 2 * CHUNK_SIZE as usize);
+}
+
+/// Fetcher recording every range it is asked for and yielding one item per block
+fn recording_fetcher(
+    requested: &std::sync::Mutex<Vec<(u64, u64)>>,
+) -> impl FnMut(u64, u64) -> std::future::Ready<Result<Vec<u64>, L1EventStreamError>> + '_ {
+    move |start, end| {
+        requested.lock().unwrap().push((start, end));
+        std::future::ready(Ok((start..=end).collect()))
+    }
+}
+
+#[test]
+fn test_block_ranges_split_into_chunks() {
+    assert_eq!(
+        block_ranges(0, 24, CHUNK_SIZE),
+        Ok(vec![(0, 9), (10, 19), (20, 24)])
+    );
+    assert_eq!(block_ranges(7, 7, CHUNK_SIZE), Ok(vec![(7, 7)]));
+    assert_eq!(
+        block_ranges(u64::MAX - 1, u64::MAX, CHUNK_SIZE),
+        Ok(vec![(u64::MAX - 1, u64::MAX)])
+    );
+}
+
+#[test]
+fn test_block_ranges_reject_reversed_range() {
+    assert_eq!(
+        block_ranges(100, 50, CHUNK_SIZE),
+        Err(RangeValidationError::ToBlockBeforeFromBlock {
+            from_block: 100,
+            to_block: 50,
+        })
+    );
+}
+
+#[tokio::test]
+async fn test_chunked_fetch_covers_range_in_order() {
+    let requested = std::sync::Mutex::new(Vec::new());
+
+    let items = fetch_logs_chunked(5, 30, CHUNK_SIZE, recording_fetcher(&requested))
+        .await
+        .unwrap();
+
+    assert_eq!(items, (5..=30).collect::<Vec<u64>>());
+    assert_eq!(
+        *requested.lock().unwrap(),
+        vec![(5, 14), (15, 24), (25, 30)]
+    );
+}
+
+#[tokio::test]
+async fn test_chunked_fetch_of_zero_block_range_is_empty() {
+    let requested = std::sync::Mutex::new(Vec::new());
+
+    // Already caught up: the next block to fetch is one past the head
+    let items = fetch_logs_chunked(101, 100, CHUNK_SIZE, recording_fetcher(&requested))
+        .await
+        .unwrap();
+
+    assert!(items.is_empty());
+    assert!(requested.lock().unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn test_chunked_fetch_of_reversed_range_is_empty() {
+    let requested = std::sync::Mutex::new(Vec::new());
+
+    let items = fetch_logs_chunked(500, 20, CHUNK_SIZE, recording_fetcher(&requested))
+        .await
+        .unwrap();
+
+    assert!(items.is_empty());
+    assert!(requested.lock().unwrap().is_empty());
+}
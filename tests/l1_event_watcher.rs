@@ -0,0 +1,186 @@
+#[path = "utils.rs"]
+mod utils;
+
+use alloy::primitives::{Address, U256};
+use alloy::rpc::types::Log;
+use futures_util::future::BoxFuture;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use utils::{create_test_app, create_test_config};
+use zeroxbridge_sequencer::events::l1_event_watcher::{
+    L1EventWatcher, TestEthereumProvider, ZeroXBridge,
+};
+use zeroxbridge_sequencer::events::EventMetrics;
+
+/// Provider whose RPC node is unreachable, counting every `get_deposit_logs` call
+struct UnreachableProvider {
+    calls: Arc<AtomicUsize>,
+}
+
+impl TestEthereumProvider for UnreachableProvider {
+    fn get_deposit_logs<'a>(
+        &'a self,
+        _from_block: u64,
+        _contract_addr: &'a str,
+    ) -> BoxFuture<
+        'a,
+        Result<Vec<Log<ZeroXBridge::DepositEvent>>, Box<dyn std::error::Error + Send + Sync>>,
+    > {
+        self.calls.fetch_add(1, Ordering::SeqCst);
+        Box::pin(async { Err("connection refused".into()) })
+    }
+
+    fn get_block_hash(
+        &self,
+        _block_number: u64,
+    ) -> BoxFuture<'_, Result<Option<String>, Box<dyn std::error::Error + Send + Sync>>> {
+        Box::pin(async { Err("connection refused".into()) })
+    }
+}
+
+/// Provider returning the same `DepositEvent` logs on every call
+struct ReplayingProvider {
+    logs: Vec<Log<ZeroXBridge::DepositEvent>>,
+}
+
+impl TestEthereumProvider for ReplayingProvider {
+    fn get_deposit_logs<'a>(
+        &'a self,
+        _from_block: u64,
+        _contract_addr: &'a str,
+    ) -> BoxFuture<
+        'a,
+        Result<Vec<Log<ZeroXBridge::DepositEvent>>, Box<dyn std::error::Error + Send + Sync>>,
+    > {
+        Box::pin(async move { Ok(self.logs.clone()) })
+    }
+
+    fn get_block_hash(
+        &self,
+        _block_number: u64,
+    ) -> BoxFuture<'_, Result<Option<String>, Box<dyn std::error::Error + Send + Sync>>> {
+        Box::pin(async { Ok(None) })
+    }
+}
+
+/// `DepositEvent` log with a random commitment hash
+fn deposit_log() -> Log<ZeroXBridge::DepositEvent> {
+    Log {
+        inner: alloy::primitives::Log {
+            address: Address::from([0x11; 20]),
+            data: ZeroXBridge::DepositEvent {
+                depositId: U256::from(rand::random::<u64>()),
+                token: Address::from([0xaa; 20]),
+                assetType: ZeroXBridge::AssetType::ERC20,
+                usdVal: U256::from(2_500),
+                user: Address::from([0xbb; 20]),
+                nonce: U256::from(1),
+                leafIndex: U256::from(0),
+                commitmentHash: U256::from_be_bytes(rand::random::<[u8; 32]>()),
+                newRoot: U256::from(0),
+                elementCount: U256::from(1),
+            },
+        },
+        block_hash: None,
+        block_number: Some(1),
+        block_timestamp: None,
+        transaction_hash: None,
+        transaction_index: None,
+        log_index: None,
+        removed: false,
+    }
+}
+
+#[tokio::test]
+async fn test_duplicate_event_counts_as_skipped_not_stored() {
+    let app = create_test_app().await;
+    let log = deposit_log();
+    let watcher = L1EventWatcher::new(
+        create_test_config(),
+        app.db.clone(),
+        Arc::new(ReplayingProvider {
+            logs: vec![log.clone(), log],
+        }),
+    );
+
+    let results = watcher.poll().await.unwrap();
+
+    assert_eq!(results.deposit_events.len(), 2);
+    assert_eq!(
+        results.metrics,
+        EventMetrics {
+            fetched: 2,
+            stored: 1,
+            skipped_duplicate: 1,
+            failed_decode: 0,
+        }
+    );
+}
+
+#[tokio::test]
+async fn test_poll_records_element_count() {
+    let app = create_test_app().await;
+    let mut log = deposit_log();
+    log.inner.data.elementCount = U256::from(7);
+    let deposit_id = log.inner.data.depositId.to_string();
+    let watcher = L1EventWatcher::new(
+        create_test_config(),
+        app.db.clone(),
+        Arc::new(ReplayingProvider { logs: vec![log] }),
+    );
+
+    watcher.poll().await.unwrap();
+
+    let element_count: Option<i64> =
+        sqlx::query_scalar("SELECT l1_element_count FROM deposits WHERE l1_deposit_id = $1")
+            .bind(deposit_id)
+            .fetch_one(&app.db)
+            .await
+            .unwrap();
+    assert_eq!(element_count, Some(7));
+}
+
+#[tokio::test]
+async fn test_poll_surfaces_provider_error() {
+    let app = create_test_app().await;
+    let calls = Arc::new(AtomicUsize::new(0));
+    let watcher = L1EventWatcher::new(
+        create_test_config(),
+        app.db.clone(),
+        Arc::new(UnreachableProvider {
+            calls: calls.clone(),
+        }),
+    );
+
+    let err = watcher.poll().await.err().expect("poll should fail");
+
+    assert!(err.to_string().contains("connection refused"));
+    assert_eq!(calls.load(Ordering::SeqCst), 1);
+}
+
+#[tokio::test]
+async fn test_run_keeps_polling_after_errors() {
+    let app = create_test_app().await;
+    let mut config = create_test_config();
+    config.ethereum.polling_interval_seconds = 0;
+    let calls = Arc::new(AtomicUsize::new(0));
+    let watcher = L1EventWatcher::new(
+        config,
+        app.db.clone(),
+        Arc::new(UnreachableProvider {
+            calls: calls.clone(),
+        }),
+    );
+
+    let handle = tokio::spawn(async move { watcher.run().await });
+    let polled = tokio::time::timeout(Duration::from_secs(10), async {
+        while calls.load(Ordering::SeqCst) < 3 {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+    })
+    .await;
+    handle.abort();
+
+    assert!(polled.is_ok(), "Watcher stopped polling after a failure");
+}
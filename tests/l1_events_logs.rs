@@ -23,7 +23,7 @@ mock! {
 mod tests {
     use super::*;
     use alloy::primitives::{Address, B256, U256};
-    use zeroxbridge_sequencer::events::l1_event_watcher::BLOCK_TRACKER_KEY;
+    use zeroxbridge_sequencer::db::database::BlockTrackerKey;
     // Helper function to create test database pool
     async fn setup_test_db() -> PgPool {
         let database_url = std::env::var("DATABASE_URL")
@@ -131,6 +131,8 @@ mod tests {
             "http://localhost:8545",
             95u64,
             "0x1234567890123456789012345678901234567890",
+            10u64,
+            None,
         )
         .await?;
 
@@ -147,10 +149,12 @@ mod tests {
          assert_eq!(second_event.data().commitment, U256::from(2_000_000));// amount
 
         // Verify block tracker was updated
-        let last_block =
-            sqlx::query!("SELECT last_block FROM block_trackers WHERE key = $1", BLOCK_TRACKER_KEY)
-                .fetch_one(&pool)
-                .await?;
+        let last_block = sqlx::query!(
+            "SELECT last_block FROM block_trackers WHERE key = $1",
+            BlockTrackerKey::L1DepositEvents.as_str()
+        )
+        .fetch_one(&pool)
+        .await?;
 
         assert_eq!(last_block.last_block, 101);
 
@@ -231,7 +235,7 @@ mod tests {
         // Verify block tracker was updated
         let last_block = sqlx::query!(
             "SELECT last_block FROM block_trackers WHERE key = $1",
-            zeroxbridge_sequencer::events::l1_event_watcher::DEPOSIT_HASH_BLOCK_TRACKER_KEY
+            BlockTrackerKey::L1DepositHashEvents.as_str()
         )
         .fetch_one(&pool)
         .await?;
@@ -256,6 +260,8 @@ mod tests {
             "http://localhost:8545",
             95u64,
             "0x1234567890123456789012345678901234567890",
+            10u64,
+            None,
         )
         .await;
 
@@ -0,0 +1,147 @@
+#[path = "utils.rs"]
+mod utils;
+
+use futures_util::future::BoxFuture;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use utils::{create_test_app, create_test_config};
+use zeroxbridge_sequencer::config::QueueConfig;
+use zeroxbridge_sequencer::db::database::Deposit;
+use zeroxbridge_sequencer::queue::l1_queue::{L1Queue, ValidationError};
+
+const DEPOSITS: usize = 12;
+
+fn queue_config() -> QueueConfig {
+    QueueConfig {
+        initial_retry_delay_sec: 0,
+        retry_delay_seconds: 0,
+        // Small batches so each queue claims again while the other still holds its batch
+        batch_size: 3,
+        ..create_test_config().queue
+    }
+}
+
+async fn insert_pending_deposit(pool: &sqlx::PgPool) -> i32 {
+    sqlx::query_scalar!(
+        r#"
+        INSERT INTO deposits (stark_pub_key, amount, commitment_hash, status)
+        VALUES ('0x123', 1000, $1, 'pending')
+        RETURNING id
+        "#,
+        format!("0x{}", uuid::Uuid::new_v4().simple())
+    )
+    .fetch_one(pool)
+    .await
+    .unwrap()
+}
+
+/// Queue counting every deposit it validates in `seen`, slowly enough for cycles to overlap
+fn counting_queue(pool: &sqlx::PgPool, seen: Arc<Mutex<HashMap<i32, usize>>>) -> L1Queue {
+    L1Queue::new(pool.clone(), queue_config()).on_commitment_found(Arc::new(
+        move |deposit: &Deposit| -> BoxFuture<'static, Result<(), ValidationError>> {
+            *seen.lock().unwrap().entry(deposit.id).or_default() += 1;
+            Box::pin(async {
+                tokio::time::sleep(Duration::from_millis(20)).await;
+                Ok(())
+            })
+        },
+    ))
+}
+
+async fn pending_count(pool: &sqlx::PgPool, ids: &[i32]) -> i64 {
+    sqlx::query_scalar!(
+        r#"SELECT COUNT(*) AS "count!" FROM deposits WHERE id = ANY($1) AND status = 'pending'"#,
+        ids
+    )
+    .fetch_one(pool)
+    .await
+    .unwrap()
+}
+
+#[tokio::test]
+async fn test_concurrent_queues_process_each_deposit_once() {
+    let app = create_test_app().await;
+    let mut ids = Vec::new();
+    for _ in 0..DEPOSITS {
+        ids.push(insert_pending_deposit(&app.db).await);
+    }
+
+    let seen = Arc::new(Mutex::new(HashMap::new()));
+    let first = counting_queue(&app.db, seen.clone());
+    let second = counting_queue(&app.db, seen.clone());
+    let config = queue_config();
+
+    // Older pending deposits left by other tests are drained along the way
+    for _ in 0..100 {
+        if pending_count(&app.db, &ids).await == 0 {
+            break;
+        }
+        let (a, b) = tokio::join!(
+            first.process_deposits(&config),
+            second.process_deposits(&config)
+        );
+        a.unwrap();
+        b.unwrap();
+    }
+
+    assert_eq!(pending_count(&app.db, &ids).await, 0);
+    {
+        let seen = seen.lock().unwrap();
+        for id in &ids {
+            assert_eq!(seen.get(id), Some(&1), "deposit {}", id);
+        }
+    }
+    let processed = sqlx::query_scalar!(
+        r#"SELECT COUNT(*) AS "count!" FROM deposits WHERE id = ANY($1) AND status = 'processed'"#,
+        &ids
+    )
+    .fetch_one(&app.db)
+    .await
+    .unwrap();
+    assert_eq!(processed, DEPOSITS as i64);
+}
+
+#[tokio::test]
+async fn test_each_deposit_outcome_is_committed_on_its_own() {
+    let app = create_test_app().await;
+    let first = insert_pending_deposit(&app.db).await;
+    let second = insert_pending_deposit(&app.db).await;
+
+    // Status of `first` as another connection sees it while `second` is being validated
+    let seen_status = Arc::new(Mutex::new(None));
+    let pool = app.db.clone();
+    let observed = seen_status.clone();
+    let queue = L1Queue::new(app.db.clone(), queue_config()).on_commitment_found(Arc::new(
+        move |deposit: &Deposit| -> BoxFuture<'static, Result<(), ValidationError>> {
+            let is_second = deposit.id == second;
+            let pool = pool.clone();
+            let observed = observed.clone();
+            Box::pin(async move {
+                if is_second {
+                    let status =
+                        sqlx::query_scalar!("SELECT status FROM deposits WHERE id = $1", first)
+                            .fetch_one(&pool)
+                            .await
+                            .unwrap();
+                    *observed.lock().unwrap() = Some(status);
+                }
+                Ok(())
+            })
+        },
+    ));
+
+    let ids = [first, second];
+    for _ in 0..100 {
+        if pending_count(&app.db, &ids).await == 0 {
+            break;
+        }
+        queue.process_deposits(&queue_config()).await.unwrap();
+    }
+
+    assert_eq!(
+        seen_status.lock().unwrap().as_deref(),
+        Some("processed"),
+        "the first deposit should be committed before the second is validated"
+    );
+}
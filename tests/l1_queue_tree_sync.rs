@@ -0,0 +1,44 @@
+#[path = "utils.rs"]
+mod utils;
+
+use sqlx::PgPool;
+use utils::create_test_config;
+use zeroxbridge_sequencer::config::QueueConfig;
+use zeroxbridge_sequencer::merkle::DepositTree;
+use zeroxbridge_sequencer::queue::l1_queue::L1Queue;
+
+/// Pending deposit whose `DepositEvent` reported `l1_element_count`
+async fn insert_pending_deposit(pool: &PgPool, l1_element_count: i64) {
+    sqlx::query(
+        r#"
+        INSERT INTO deposits (stark_pub_key, amount, commitment_hash, status, l1_element_count)
+        VALUES ('0x123', 1000, $1, 'pending', $2)
+        "#,
+    )
+    .bind(format!("0x{}", uuid::Uuid::new_v4().simple()))
+    .bind(l1_element_count)
+    .execute(pool)
+    .await
+    .unwrap();
+}
+
+#[sqlx::test]
+async fn test_tree_is_checked_against_element_count_after_append(pool: PgPool) {
+    let config = QueueConfig {
+        initial_retry_delay_sec: 0,
+        retry_delay_seconds: 0,
+        ..create_test_config().queue
+    };
+    let deposit_tree = DepositTree::new();
+    let queue = L1Queue::new(pool.clone(), config.clone()).with_deposit_tree(deposit_tree.clone());
+
+    // One leaf is a one-element MMR, two short of what the contract reported
+    insert_pending_deposit(&pool, 3).await;
+    queue.process_deposits(&config).await.unwrap();
+    assert_eq!(deposit_tree.tree_sync_lag(), 2);
+
+    // The second leaf adds their parent, bringing the tree to the contract's three elements
+    insert_pending_deposit(&pool, 3).await;
+    queue.process_deposits(&config).await.unwrap();
+    assert_eq!(deposit_tree.tree_sync_lag(), 0);
+}
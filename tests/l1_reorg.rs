@@ -0,0 +1,97 @@
+#[path = "utils.rs"]
+mod utils;
+
+use utils::create_test_app;
+use zeroxbridge_sequencer::db::database::{
+    get_last_processed_block_with_hash, set_last_processed_block, upsert_deposit, BlockTrackerKey,
+};
+use zeroxbridge_sequencer::events::l1_event_watcher::check_for_reorg;
+
+const STORED_HASH: &str = "0x1111111111111111111111111111111111111111111111111111111111111111";
+const REORGED_HASH: &str = "0x2222222222222222222222222222222222222222222222222222222222222222";
+
+/// Records a deposit with `status` emitted in `block_number` under a random commitment hash
+async fn insert_deposit(pool: &sqlx::PgPool, block_number: i64, status: &str) -> String {
+    let commitment_hash = hex::encode(rand::random::<[u8; 32]>());
+    upsert_deposit(
+        pool,
+        "0xbb",
+        100,
+        &commitment_hash,
+        status,
+        &rand::random::<u64>().to_string(),
+        Some(block_number),
+        None,
+    )
+    .await
+    .expect("Failed to insert deposit");
+    commitment_hash
+}
+
+async fn deposit_status(pool: &sqlx::PgPool, commitment_hash: &str) -> String {
+    sqlx::query_scalar("SELECT status FROM deposits WHERE commitment_hash = $1")
+        .bind(commitment_hash)
+        .fetch_one(pool)
+        .await
+        .expect("Deposit was not stored")
+}
+
+type ChainHash = Result<Option<String>, Box<dyn std::error::Error>>;
+
+/// Block hash lookup that reports `hash` as the chain's current hash for any block
+fn chain_hash(hash: &'static str) -> impl FnOnce(u64) -> std::future::Ready<ChainHash> {
+    move |_| std::future::ready(Ok(Some(hash.to_string())))
+}
+
+// Both scenarios share the L1 deposit tracker row, so they run in one test
+#[tokio::test]
+async fn test_reorg_rolls_back_tracker_and_invalidates_deposits() {
+    let app = create_test_app().await;
+    let base = 1_000_000 + rand::random::<u32>() as u64;
+    let last_block = base + 20;
+
+    set_last_processed_block(
+        &app.db,
+        BlockTrackerKey::L1DepositEvents,
+        last_block,
+        Some(STORED_HASH),
+    )
+    .await
+    .unwrap();
+    let kept = insert_deposit(&app.db, (base + 5) as i64, "PENDING_TREE_INCLUSION").await;
+    let reorged = insert_deposit(&app.db, (base + 15) as i64, "PENDING_TREE_INCLUSION").await;
+    // Replaying a deposit that was already acted on could mint twice
+    let processed = insert_deposit(&app.db, (base + 15) as i64, "processed").await;
+
+    // Matching hash: nothing changes
+    let resume = check_for_reorg(&app.db, 10, chain_hash(STORED_HASH))
+        .await
+        .unwrap();
+    assert_eq!(resume, Some(last_block));
+    assert_eq!(
+        deposit_status(&app.db, &reorged).await,
+        "PENDING_TREE_INCLUSION"
+    );
+
+    // Different hash: roll back 10 blocks and invalidate deposits past the new last block
+    let resume = check_for_reorg(&app.db, 10, chain_hash(REORGED_HASH))
+        .await
+        .unwrap();
+    assert_eq!(resume, Some(base + 10));
+
+    let tracker = get_last_processed_block_with_hash(&app.db, BlockTrackerKey::L1DepositEvents)
+        .await
+        .unwrap();
+    assert_eq!(tracker, Some((base + 10, None)));
+    assert_eq!(deposit_status(&app.db, &reorged).await, "INVALIDATED");
+    assert_eq!(deposit_status(&app.db, &processed).await, "processed");
+    assert_eq!(
+        deposit_status(&app.db, &kept).await,
+        "PENDING_TREE_INCLUSION"
+    );
+
+    // Without a stored hash the rewound tracker is trusted without a lookup
+    let failing_lookup = |_: u64| std::future::ready::<ChainHash>(Err("unexpected lookup".into()));
+    let resume = check_for_reorg(&app.db, 10, failing_lookup).await.unwrap();
+    assert_eq!(resume, Some(base + 10));
+}
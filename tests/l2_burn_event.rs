@@ -0,0 +1,79 @@
+use zeroxbridge_sequencer::events::CommitmentLog;
+use zeroxbridge_sequencer::queue::l2_queue::{
+    parse_u128_from_hex, ConversionError, L2Transaction, PriorityLevel,
+};
+
+fn burn_log(amount_low: &str, amount_high: &str) -> CommitmentLog {
+    CommitmentLog {
+        commitment_hash: "0xabc".to_string(),
+        block_number: 42,
+        transaction_hash: "0xdef".to_string(),
+        user: "0x123".to_string(),
+        amount_low: amount_low.to_string(),
+        amount_high: amount_high.to_string(),
+    }
+}
+
+#[test]
+fn test_parse_u128_from_hex_rebuilds_amount() {
+    assert_eq!(parse_u128_from_hex("0x0", "0x0"), Ok(0));
+    assert_eq!(parse_u128_from_hex("0x3e8", "0x0"), Ok(1_000));
+    assert_eq!(parse_u128_from_hex("3e8", "0"), Ok(1_000));
+    assert_eq!(
+        parse_u128_from_hex("0x7fffffffffffffff", "0x0"),
+        Ok(i64::MAX)
+    );
+}
+
+#[test]
+fn test_parse_u128_from_hex_rejects_overflow() {
+    // Low half above i64::MAX
+    assert!(matches!(
+        parse_u128_from_hex("0x8000000000000000", "0x0"),
+        Err(ConversionError::Overflow { .. })
+    ));
+    // Low half at the u128 maximum
+    assert!(matches!(
+        parse_u128_from_hex("0xffffffffffffffffffffffffffffffff", "0x0"),
+        Err(ConversionError::Overflow { .. })
+    ));
+    // Any high bit puts the amount at or above 2^128
+    assert!(matches!(
+        parse_u128_from_hex("0x1", "0x1"),
+        Err(ConversionError::Overflow { .. })
+    ));
+}
+
+#[test]
+fn test_parse_u128_from_hex_rejects_invalid_hex() {
+    assert_eq!(
+        parse_u128_from_hex("0xzz", "0x0"),
+        Err(ConversionError::InvalidHex("0xzz".to_string()))
+    );
+    // More than 128 bits in one half
+    assert!(matches!(
+        parse_u128_from_hex("0x1ffffffffffffffffffffffffffffffff", "0x0"),
+        Err(ConversionError::InvalidHex(_))
+    ));
+}
+
+#[test]
+fn test_from_burn_event_maps_log_fields() {
+    let tx = L2Transaction::from_burn_event(&burn_log("0x2710", "0x0"), "0xtoken").unwrap();
+
+    assert_eq!(tx.stark_pub_key, "0x123");
+    assert_eq!(tx.amount, 10_000);
+    assert_eq!(tx.token_address, "0xtoken");
+    assert_eq!(tx.status, "pending");
+    assert_eq!(tx.proof_data, None);
+    assert_eq!(tx.retry_count, 0);
+    assert_eq!(tx.priority, PriorityLevel::Normal.as_i16());
+    assert_eq!(tx.commitment_hash.as_deref(), Some("0xabc"));
+}
+
+#[test]
+fn test_from_burn_event_rejects_overflowing_amount() {
+    let result = L2Transaction::from_burn_event(&burn_log("0x0", "0x1"), "0xtoken");
+
+    assert!(matches!(result, Err(ConversionError::Overflow { .. })));
+}
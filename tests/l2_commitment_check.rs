@@ -0,0 +1,203 @@
+#[path = "utils.rs"]
+mod utils;
+
+use mockito::{mock, Matcher, Mock};
+use sqlx::PgPool;
+use starknet::core::types::Felt;
+use starknet::providers::jsonrpc::{HttpTransport, JsonRpcClient};
+use std::sync::Arc;
+use url::Url;
+use utils::create_test_app;
+use zeroxbridge_sequencer::events::CommitmentLog;
+use zeroxbridge_sequencer::queue::l2_queue::{L2Queue, L2QueueError, L2Transaction, QueueConfig};
+use zeroxbridge_sequencer::utils::normalize_commitment_hash;
+
+/// Bridge contract the queue checks against, so mocks don't answer other tests' requests
+const BRIDGE_ADDRESS: &str = "0xc0117";
+
+fn queue_config() -> QueueConfig {
+    QueueConfig {
+        min_interval_sec: 1,
+        max_interval_sec: 60,
+        backoff_factor: 2.0,
+        initial_retry_delay_sec: 0,
+        max_retries: 3,
+        batch_size: 1000,
+        high_priority_threshold_usd: None,
+        max_concurrent_validations: 4,
+    }
+}
+
+async fn test_queue() -> (L2Queue, PgPool) {
+    let app = create_test_app().await;
+    let provider = JsonRpcClient::new(HttpTransport::new(
+        Url::parse(&mockito::server_url()).unwrap(),
+    ));
+    let queue = L2Queue::new(app.db.clone(), queue_config())
+        .with_starknet_provider(Arc::new(provider), Felt::from_hex(BRIDGE_ADDRESS).unwrap());
+    (queue, app.db.clone())
+}
+
+/// Withdrawal committed to by `commitment`, with a proof row in `proof_status` holding
+/// `proof_data`
+async fn insert_withdrawal_proof(
+    pool: &PgPool,
+    commitment: &str,
+    proof_status: &str,
+    proof_data: &[u8],
+) {
+    let withdrawal_id: i32 = sqlx::query_scalar(
+        r#"
+        INSERT INTO withdrawals (stark_pub_key, amount, l1_token, commitment_hash, status)
+        VALUES ('0x123', 1000, '0xtoken', $1, 'pending')
+        RETURNING id
+        "#,
+    )
+    .bind(normalize_commitment_hash(commitment).unwrap())
+    .fetch_one(pool)
+    .await
+    .unwrap();
+    sqlx::query(
+        r#"
+        INSERT INTO withdrawal_proofs (withdrawal_id, proof_data, status)
+        VALUES ($1, $2, $3)
+        "#,
+    )
+    .bind(withdrawal_id)
+    .bind(proof_data)
+    .bind(proof_status)
+    .execute(pool)
+    .await
+    .unwrap();
+}
+
+fn burn_transaction(commitment_hash: &str) -> L2Transaction {
+    let log = CommitmentLog {
+        commitment_hash: commitment_hash.to_string(),
+        block_number: 42,
+        transaction_hash: "0xdef".to_string(),
+        user: "0x123".to_string(),
+        amount_low: "0x3e8".to_string(),
+        amount_high: "0x0".to_string(),
+    };
+    L2Transaction::from_burn_event(&log, "0xtoken").unwrap()
+}
+
+/// `is_commitment_recorded` answers `recorded` for `commitment`
+fn is_commitment_recorded_mock(commitment: &str, recorded: bool) -> Mock {
+    mock("POST", "/")
+        .match_body(Matcher::Regex(format!(
+            r#"starknet_call.*"{}".*"{}""#,
+            BRIDGE_ADDRESS, commitment
+        )))
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(format!(
+            r#"{{"jsonrpc":"2.0","id":1,"result":["{}"]}}"#,
+            if recorded { "0x1" } else { "0x0" }
+        ))
+        .create()
+}
+
+/// `get_root` is the only view the queue calls without calldata
+fn get_root_mock(root: &str) -> Mock {
+    mock("POST", "/")
+        .match_body(Matcher::Regex(format!(
+            r#"starknet_call.*"{}".*"calldata":\[\]"#,
+            BRIDGE_ADDRESS
+        )))
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(format!(
+            r#"{{"jsonrpc":"2.0","id":1,"result":["{}"]}}"#,
+            root
+        ))
+        .create()
+}
+
+#[tokio::test]
+async fn test_recorded_commitment_returns_proof_data() {
+    let (queue, pool) = test_queue().await;
+    // Unique per run, since withdrawals persist across runs of this test
+    let commitment = format!("0xc{}", &uuid::Uuid::new_v4().simple().to_string()[..12]);
+    let mut proof_words = [0u8; 64];
+    proof_words[31] = 0x0a;
+    proof_words[63] = 0x0b;
+    insert_withdrawal_proof(&pool, &commitment, "ready", &proof_words).await;
+    let recorded = is_commitment_recorded_mock(&commitment, true).expect(1);
+    let root = get_root_mock("0x7007").expect(1);
+
+    let proof_data = queue
+        .check_l2_commitment(&burn_transaction(&commitment))
+        .await
+        .unwrap()
+        .expect("commitment should be recorded");
+
+    recorded.assert();
+    root.assert();
+    let proof: serde_json::Value = serde_json::from_str(&proof_data).unwrap();
+    assert_eq!(proof["commitment_hash"], commitment);
+    assert_eq!(proof["merkle_root"], "0x7007");
+    assert_eq!(
+        proof["proof"],
+        serde_json::json!([format!("0x{:0>64}", "a"), format!("0x{:0>64}", "b")])
+    );
+}
+
+#[tokio::test]
+async fn test_recorded_commitment_waits_for_its_proof() {
+    let (queue, pool) = test_queue().await;
+    // Unique per run, since withdrawals persist across runs of this test
+    let commitment = format!("0xc{}", &uuid::Uuid::new_v4().simple().to_string()[..12]);
+    insert_withdrawal_proof(&pool, &commitment, "submitted", &[]).await;
+    let recorded = is_commitment_recorded_mock(&commitment, true).expect(1);
+
+    let proof_data = queue
+        .check_l2_commitment(&burn_transaction(&commitment))
+        .await
+        .unwrap();
+
+    recorded.assert();
+    assert_eq!(proof_data, None);
+}
+
+#[tokio::test]
+async fn test_unrecorded_commitment_is_pending() {
+    let (queue, _) = test_queue().await;
+    let commitment = "0xc0a2";
+    let recorded = is_commitment_recorded_mock(commitment, false).expect(1);
+
+    let proof_data = queue
+        .check_l2_commitment(&burn_transaction(commitment))
+        .await
+        .unwrap();
+
+    recorded.assert();
+    assert_eq!(proof_data, None);
+}
+
+#[tokio::test]
+async fn test_transaction_without_commitment_is_not_checked() {
+    let (queue, _) = test_queue().await;
+
+    // No mock answers for this transaction, so any view call would surface as an error
+    let mut tx = burn_transaction("0xc0a3");
+    tx.commitment_hash = None;
+    let proof_data = queue.check_l2_commitment(&tx).await.unwrap();
+
+    assert_eq!(proof_data, None);
+}
+
+#[tokio::test]
+async fn test_invalid_commitment_hash_is_rejected() {
+    let (queue, _) = test_queue().await;
+
+    let result = queue
+        .check_l2_commitment(&burn_transaction("not-a-felt"))
+        .await;
+
+    assert!(matches!(
+        result,
+        Err(L2QueueError::InvalidCommitmentHash(hash)) if hash == "not-a-felt"
+    ));
+}
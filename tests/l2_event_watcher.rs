@@ -1,10 +1,12 @@
 use anyhow::Result;
 use mockall::predicate::*;
 use mockall::*;
-use starknet::core::types::{EmittedEvent, EventFilter, EventsPage, Felt};
+use starknet::core::types::{BlockId, EmittedEvent, EventFilter, EventsPage, Felt};
+use std::sync::{Arc, Mutex};
 
-use zeroxbridge_sequencer::events::fetch_l2_events;
+use zeroxbridge_sequencer::db::database::BlockTrackerKey;
 use zeroxbridge_sequencer::events::l2_event_watcher::TestProvider;
+use zeroxbridge_sequencer::events::{CommitmentLog, EventBus, EventMetrics, L2EventWatcher};
 
 #[path = "utils.rs"]
 mod utils;
@@ -40,11 +42,91 @@ impl TestProvider for MockStarknetProvider {
     }
 }
 
+/// Serves `pages` one per `get_events` call, handing out `page-<n>` as the continuation token
+/// for every page but the last
+pub struct PaginatedMockProvider {
+    block_number: u64,
+    pages: Vec<Vec<EmittedEvent>>,
+    /// Continuation token of each `get_events` call, in order
+    requested_tokens: Mutex<Vec<Option<String>>>,
+}
+
+impl PaginatedMockProvider {
+    pub fn new(block_number: u64, pages: Vec<Vec<EmittedEvent>>) -> Self {
+        Self {
+            block_number,
+            pages,
+            requested_tokens: Mutex::new(Vec::new()),
+        }
+    }
+
+    pub fn requested_tokens(&self) -> Vec<Option<String>> {
+        self.requested_tokens.lock().unwrap().clone()
+    }
+}
+
+impl TestProvider for PaginatedMockProvider {
+    fn block_number(&self) -> Result<u64, Box<dyn std::error::Error + Send + Sync>> {
+        Ok(self.block_number)
+    }
+
+    fn get_events(
+        &self,
+        _filter: EventFilter,
+        continuation_token: Option<String>,
+        _chunk_size: u64,
+    ) -> Result<EventsPage, Box<dyn std::error::Error + Send + Sync>> {
+        self.requested_tokens
+            .lock()
+            .unwrap()
+            .push(continuation_token.clone());
+
+        let index = match continuation_token {
+            None => 0,
+            Some(token) => token
+                .strip_prefix("page-")
+                .and_then(|n| n.parse::<usize>().ok())
+                .ok_or_else(|| format!("Unknown continuation token {token}"))?,
+        };
+        let events = self
+            .pages
+            .get(index)
+            .cloned()
+            .ok_or_else(|| format!("No page {index}"))?;
+        let continuation_token =
+            (index + 1 < self.pages.len()).then(|| format!("page-{}", index + 1));
+
+        Ok(EventsPage {
+            events,
+            continuation_token,
+        })
+    }
+}
+
 // Test module to group all L2 event watcher tests
 #[cfg(test)]
 mod tests {
     use super::*;
+    use axum::{
+        body::Body,
+        http::{Request, StatusCode},
+    };
+    use sqlx::PgPool;
+    use tower::ServiceExt;
+    use tree_builder::l2_tree::L2MerkleTreeBuilder;
     use utils::create_test_app;
+    use zeroxbridge_sequencer::api::routes::{create_router, AppState};
+    use zeroxbridge_sequencer::events::WithdrawalCommitmentLog;
+    use zeroxbridge_sequencer::merkle::WithdrawalTree;
+    use zeroxbridge_sequencer::utils::normalize_commitment_hash;
+
+    /// Watcher starting from block 90 unless a block tracker is further along
+    fn create_watcher(
+        app: &AppState,
+        provider: Arc<dyn TestProvider + Send + Sync>,
+    ) -> L2EventWatcher {
+        L2EventWatcher::new(app.config.clone(), app.db.clone(), provider).with_start_block(90)
+    }
 
     // Helper function to create a test event
     fn create_test_burn_event(
@@ -73,6 +155,32 @@ mod tests {
         }
     }
 
+    fn create_test_withdrawal_event(
+        block_number: u64,
+        tx_hash: &str,
+        index: &str,
+        commitment: &str,
+        root: &str,
+        elements_count: &str,
+    ) -> EmittedEvent {
+        EmittedEvent {
+            from_address: Felt::from_hex("0x456").unwrap(),
+            keys: vec![Felt::from_hex(
+                "0x01e3ad31c1ae0cf5ec9a8eaf3c540d6cf961c8f4e3bfe1d55a5b92a09e1c9c1e",
+            )
+            .unwrap()],
+            data: vec![
+                Felt::from_hex(index).unwrap(),
+                Felt::from_hex(commitment).unwrap(),
+                Felt::from_hex(root).unwrap(),
+                Felt::from_hex(elements_count).unwrap(),
+            ],
+            block_number: Some(block_number),
+            block_hash: None,
+            transaction_hash: Felt::from_hex(tx_hash).unwrap(),
+        }
+    }
+
     #[tokio::test]
     async fn test_commitment_hash_decoding() -> Result<()> {
         let app = create_test_app().await;
@@ -95,7 +203,7 @@ mod tests {
             })
         });
 
-        let result = fetch_l2_events(&app.config, &app.db, 90, &mock_provider).await?;
+        let result = create_watcher(&app, Arc::new(mock_provider)).poll().await?;
 
         assert_eq!(result.burn_events.len(), 2);
         assert_eq!(result.burn_events[0].commitment_hash, "0xabc");
@@ -108,9 +216,9 @@ mod tests {
         Ok(())
     }
 
-    #[tokio::test]
-    async fn test_block_index_tracking() -> Result<()> {
-        let app = create_test_app().await;
+    // A database of its own, so no other test's poll moves the block tracker it checks
+    #[sqlx::test]
+    async fn test_block_index_tracking(pool: PgPool) -> Result<()> {
         let mut mock_provider = MockStarknetProvider::new();
 
         mock_provider.expect_block_number().returning(|| Ok(100));
@@ -135,14 +243,21 @@ mod tests {
             });
 
         // First call: should process blocks 90-95
-        let result = fetch_l2_events(&app.config, &app.db, 90, &mock_provider).await?;
+        let result = L2EventWatcher::new(
+            utils::create_test_config(),
+            pool.clone(),
+            Arc::new(mock_provider),
+        )
+        .with_start_block(90)
+        .poll()
+        .await?;
         assert_eq!(result.burn_events.len(), 1);
 
         // Verify block tracker was updated
         let last_block = sqlx::query!(
             "SELECT last_block FROM block_trackers WHERE key = 'l2_events_last_block'"
         )
-        .fetch_one(&app.db)
+        .fetch_one(&pool)
         .await?;
 
         assert_eq!(result.burn_events[0].block_number, 95);
@@ -174,7 +289,10 @@ mod tests {
             })
         });
 
-        let result = fetch_l2_events(&app.config, &app.db, 92, &mock_provider).await?;
+        let result = create_watcher(&app, Arc::new(mock_provider))
+            .with_start_block(92)
+            .poll()
+            .await?;
 
         assert_eq!(result.burn_events.len(), 1);
         assert_eq!(result.burn_events[0].commitment_hash, "0xabc");
@@ -185,4 +303,490 @@ mod tests {
 
         Ok(())
     }
+
+    #[tokio::test]
+    async fn test_burn_events_published_to_subscribers() -> Result<()> {
+        let app = create_test_app().await;
+        let mut mock_provider = MockStarknetProvider::new();
+
+        mock_provider.expect_block_number().returning(|| Ok(100));
+
+        let test_events = vec![
+            create_test_burn_event(97, "0x789", "0x1234567890abcdef", "0x1000", "0x0", "0xabc"),
+            create_test_burn_event(98, "0xabc", "0xfedcba0987654321", "0x2000", "0x0", "0xdef"),
+        ];
+
+        mock_provider.expect_get_events().returning(move |_, _, _| {
+            Ok(EventsPage {
+                events: test_events.clone(),
+                continuation_token: None,
+            })
+        });
+
+        let bus: EventBus<CommitmentLog> = EventBus::default();
+        let mut queue_subscriber = bus.subscribe();
+        let mut audit_subscriber = bus.subscribe();
+
+        let watcher = create_watcher(&app, Arc::new(mock_provider)).with_event_bus(bus.clone());
+        let result = watcher.poll().await?;
+        assert_eq!(result.burn_events.len(), 2);
+
+        for subscriber in [&mut queue_subscriber, &mut audit_subscriber] {
+            let first = subscriber.try_recv()?;
+            let second = subscriber.try_recv()?;
+            assert_eq!(first.commitment_hash, "0xabc");
+            assert_eq!(second.commitment_hash, "0xdef");
+            assert!(subscriber.try_recv().is_err());
+        }
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_withdrawal_events_persisted_and_queryable() -> Result<()> {
+        let app = create_test_app().await;
+        let mut mock_provider = MockStarknetProvider::new();
+
+        mock_provider.expect_block_number().returning(|| Ok(100));
+
+        let tx_hash = format!("0x{}", uuid::Uuid::new_v4().simple());
+        let test_events = vec![create_test_withdrawal_event(
+            99, &tx_hash, "0x7", "0xc0ffee", "0xbeef", "0x8",
+        )];
+
+        mock_provider.expect_get_events().returning(move |_, _, _| {
+            Ok(EventsPage {
+                events: test_events.clone(),
+                continuation_token: None,
+            })
+        });
+
+        // Fetching twice must not duplicate the stored log
+        let watcher = create_watcher(&app, Arc::new(mock_provider));
+        watcher.poll().await?;
+        let result = watcher.poll().await?;
+        assert_eq!(result.withdrawal_events.len(), 1);
+
+        let router = create_router(app.as_ref().clone());
+        let request = Request::builder()
+            .method("GET")
+            .uri("/withdrawal-commitments?from_block=99&to_block=99")
+            .body(Body::empty())
+            .unwrap();
+        let response = router.oneshot(request).await?;
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await?;
+        let logs: Vec<WithdrawalCommitmentLog> = serde_json::from_slice(&body)?;
+        let stored: Vec<_> = logs
+            .iter()
+            .filter(|log| log.transaction_hash == Felt::from_hex(&tx_hash).unwrap().to_hex_string())
+            .collect();
+        assert_eq!(stored.len(), 1);
+        // Stored normalized, and returned with its 0x prefix
+        assert_eq!(stored[0].commitment_hash, format!("0x{:0>64}", "c0ffee"));
+        assert_eq!(stored[0].root_hash, "0xbeef");
+        assert_eq!(stored[0].block_number, 99);
+
+        sqlx::query!(
+            "DELETE FROM withdrawal_commitment_logs WHERE transaction_hash = $1",
+            stored[0].transaction_hash
+        )
+        .execute(&app.db)
+        .await?;
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_poll_metrics_count_duplicates_and_malformed_events() -> Result<()> {
+        let app = create_test_app().await;
+        let mut mock_provider = MockStarknetProvider::new();
+
+        mock_provider.expect_block_number().returning(|| Ok(100));
+
+        let commitment = format!("0x{}", uuid::Uuid::new_v4().simple());
+        let mut malformed = create_test_burn_event(98, "0x99", "0x1", "0x1", "0x0", "0x1");
+        malformed.data.truncate(2);
+        let test_events = vec![
+            create_test_burn_event(97, "0x789", "0xabcdef", "0x1000", "0x0", &commitment),
+            malformed,
+        ];
+
+        mock_provider.expect_get_events().returning(move |_, _, _| {
+            Ok(EventsPage {
+                events: test_events.clone(),
+                continuation_token: None,
+            })
+        });
+
+        let watcher = create_watcher(&app, Arc::new(mock_provider));
+        let first = watcher.poll().await?;
+        assert_eq!(
+            first.metrics,
+            EventMetrics {
+                fetched: 2,
+                stored: 1,
+                skipped_duplicate: 0,
+                failed_decode: 1,
+            }
+        );
+
+        // The node serves the same burn again, which must not count as stored twice
+        let second = watcher.poll().await?;
+        assert_eq!(second.metrics.stored, 0);
+        assert_eq!(second.metrics.skipped_duplicate, 1);
+        assert_eq!(second.metrics.failed_decode, 1);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_withdrawal_commitments_rejects_inverted_range() -> Result<()> {
+        let app = create_test_app().await;
+        let router = create_router(app.as_ref().clone());
+
+        let request = Request::builder()
+            .method("GET")
+            .uri("/withdrawal-commitments?from_block=10&to_block=5")
+            .body(Body::empty())
+            .unwrap();
+        let response = router.oneshot(request).await?;
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+        Ok(())
+    }
+
+    fn burn_key() -> Felt {
+        Felt::from_hex("0x0099de3f38fed0a76764f614c6bc2b958814813685abc1af6deedab612df44f3")
+            .unwrap()
+    }
+
+    fn withdrawal_key() -> Felt {
+        Felt::from_hex("0x01e3ad31c1ae0cf5ec9a8eaf3c540d6cf961c8f4e3bfe1d55a5b92a09e1c9c1e")
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_single_filter_matches_either_event_key() -> Result<()> {
+        let app = create_test_app().await;
+        let mut mock_provider = MockStarknetProvider::new();
+
+        mock_provider.expect_block_number().returning(|| Ok(100));
+        mock_provider
+            .expect_get_events()
+            .withf(|filter, _, _| filter.keys == Some(vec![vec![burn_key(), withdrawal_key()]]))
+            .times(1)
+            .returning(|_, _, _| {
+                Ok(EventsPage {
+                    events: vec![],
+                    continuation_token: None,
+                })
+            });
+
+        create_watcher(&app, Arc::new(mock_provider)).poll().await?;
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_only_burn_events() -> Result<()> {
+        let app = create_test_app().await;
+        let mut mock_provider = MockStarknetProvider::new();
+
+        mock_provider.expect_block_number().returning(|| Ok(100));
+        let test_events = vec![create_test_burn_event(
+            97, "0x321", "0x1234", "0x10", "0x0", "0xb0b",
+        )];
+        mock_provider.expect_get_events().returning(move |_, _, _| {
+            Ok(EventsPage {
+                events: test_events.clone(),
+                continuation_token: None,
+            })
+        });
+
+        let result = create_watcher(&app, Arc::new(mock_provider)).poll().await?;
+
+        assert_eq!(result.burn_events.len(), 1);
+        assert_eq!(result.burn_events[0].commitment_hash, "0xb0b");
+        assert!(result.withdrawal_events.is_empty());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_only_withdrawal_events() -> Result<()> {
+        let app = create_test_app().await;
+        let mut mock_provider = MockStarknetProvider::new();
+
+        mock_provider.expect_block_number().returning(|| Ok(100));
+        let tx_hash = format!("0x{}", uuid::Uuid::new_v4().simple());
+        let mut event =
+            create_test_withdrawal_event(98, &tx_hash, "0x9", "0xfade", "0xcafe", "0xa");
+        // A later key equal to the burn selector must not make this a burn event
+        event.keys.push(burn_key());
+        let test_events = vec![event];
+        mock_provider.expect_get_events().returning(move |_, _, _| {
+            Ok(EventsPage {
+                events: test_events.clone(),
+                continuation_token: None,
+            })
+        });
+
+        let result = create_watcher(&app, Arc::new(mock_provider)).poll().await?;
+
+        assert!(result.burn_events.is_empty());
+        assert_eq!(result.withdrawal_events.len(), 1);
+        assert_eq!(result.withdrawal_events[0].commitment_hash, "0xfade");
+
+        sqlx::query!(
+            "DELETE FROM withdrawal_commitment_logs WHERE transaction_hash = $1",
+            result.withdrawal_events[0].transaction_hash
+        )
+        .execute(&app.db)
+        .await?;
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_withdrawal_events_feed_the_withdrawal_tree() -> Result<()> {
+        let app = create_test_app().await;
+        let mut mock_provider = MockStarknetProvider::new();
+
+        mock_provider.expect_block_number().returning(|| Ok(100));
+        let tx_hashes: Vec<String> = (0..3)
+            .map(|_| format!("0x{}", uuid::Uuid::new_v4().simple()))
+            .collect();
+        // The last event repeats index 0, as after a refetch, and must not be appended again
+        let test_events = vec![
+            create_test_withdrawal_event(98, &tx_hashes[0], "0x0", "0xfade", "0xcafe", "0x1"),
+            create_test_withdrawal_event(98, &tx_hashes[1], "0x1", "0xbead", "0xcafe", "0x2"),
+            create_test_withdrawal_event(99, &tx_hashes[2], "0x0", "0xfade", "0xcafe", "0x2"),
+        ];
+        mock_provider.expect_get_events().returning(move |_, _, _| {
+            Ok(EventsPage {
+                events: test_events.clone(),
+                continuation_token: None,
+            })
+        });
+        let withdrawal_tree = WithdrawalTree::new();
+
+        create_watcher(&app, Arc::new(mock_provider))
+            .with_withdrawal_tree(withdrawal_tree.clone())
+            .poll()
+            .await?;
+
+        let mut expected = L2MerkleTreeBuilder::new();
+        expected
+            .build_merkle(vec![
+                Felt::from_hex("0xfade")?.to_bytes_be(),
+                Felt::from_hex("0xbead")?.to_bytes_be(),
+            ])
+            .await?;
+        assert_eq!(
+            withdrawal_tree.get_root().await?,
+            expected.get_root().await?
+        );
+
+        sqlx::query!(
+            "DELETE FROM withdrawal_commitment_logs WHERE transaction_hash = ANY($1)",
+            &tx_hashes
+                .iter()
+                .map(|hash| Felt::from_hex(hash).map(|felt| felt.to_hex_string()))
+                .collect::<Result<Vec<_>, _>>()?
+        )
+        .execute(&app.db)
+        .await?;
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_from_block_clamped_to_deploy_block() -> Result<()> {
+        let app = create_test_app().await;
+        let deploy_block = 1_000_000;
+        let mut config = app.config.clone();
+        config.contracts.l2_contract_deploy_block = deploy_block;
+
+        let tracker_keys = [
+            BlockTrackerKey::L2BurnEvents,
+            BlockTrackerKey::L2WithdrawalEvents,
+        ];
+        let mut previous = Vec::new();
+        for key in tracker_keys {
+            let last_block = sqlx::query_scalar!(
+                "SELECT last_block FROM block_trackers WHERE key = $1",
+                key.as_str()
+            )
+            .fetch_optional(&app.db)
+            .await?;
+            previous.push((key, last_block));
+        }
+
+        let mut mock_provider = MockStarknetProvider::new();
+        mock_provider
+            .expect_block_number()
+            .returning(move || Ok(deploy_block + 10));
+        mock_provider
+            .expect_get_events()
+            .withf(move |filter, _, _| filter.from_block == Some(BlockId::Number(deploy_block)))
+            .times(1)
+            .returning(|_, _, _| {
+                Ok(EventsPage {
+                    events: vec![],
+                    continuation_token: None,
+                })
+            });
+
+        L2EventWatcher::new(config, app.db.clone(), Arc::new(mock_provider))
+            .with_start_block(90)
+            .poll()
+            .await?;
+
+        // Put the trackers back so the other tests keep reading from their own block range
+        for (key, last_block) in previous {
+            match last_block {
+                Some(last_block) => {
+                    sqlx::query!(
+                        "UPDATE block_trackers SET last_block = $1 WHERE key = $2",
+                        last_block,
+                        key.as_str()
+                    )
+                    .execute(&app.db)
+                    .await?;
+                }
+                None => {
+                    sqlx::query!("DELETE FROM block_trackers WHERE key = $1", key.as_str())
+                        .execute(&app.db)
+                        .await?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn random_felt_hex() -> String {
+        format!("0x{}", &uuid::Uuid::new_v4().simple().to_string()[..24])
+    }
+
+    async fn delete_burn_events(pool: &sqlx::PgPool, commitment_hashes: &[String]) -> Result<()> {
+        for commitment_hash in commitment_hashes {
+            sqlx::query!(
+                "DELETE FROM l2_burn_events WHERE commitment_hash = $1",
+                normalize_commitment_hash(commitment_hash).unwrap()
+            )
+            .execute(pool)
+            .await?;
+        }
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_follows_pagination_across_pages() -> Result<()> {
+        let app = create_test_app().await;
+        let commitments: Vec<String> = (0..5).map(|_| random_felt_hex()).collect();
+        let burn = |block, commitment: &String| {
+            create_test_burn_event(block, "0x123", "0x1234", "0x10", "0x0", commitment)
+        };
+        let pages = vec![
+            vec![burn(91, &commitments[0]), burn(92, &commitments[1])],
+            vec![burn(93, &commitments[2])],
+            vec![burn(94, &commitments[3]), burn(95, &commitments[4])],
+        ];
+        let provider = Arc::new(PaginatedMockProvider::new(100, pages));
+
+        let result = create_watcher(&app, provider.clone()).poll().await?;
+
+        let fetched: Vec<String> = result
+            .burn_events
+            .iter()
+            .map(|event| event.commitment_hash.clone())
+            .collect();
+        let expected: Vec<String> = commitments
+            .iter()
+            .map(|commitment| Felt::from_hex(commitment).unwrap().to_hex_string())
+            .collect();
+        assert_eq!(fetched, expected);
+        assert_eq!(
+            provider.requested_tokens(),
+            vec![None, Some("page-1".to_string()), Some("page-2".to_string())]
+        );
+
+        delete_burn_events(&app.db, &fetched).await
+    }
+
+    #[tokio::test]
+    async fn test_stops_when_last_page_has_no_continuation_token() -> Result<()> {
+        let app = create_test_app().await;
+        let commitment = random_felt_hex();
+        let pages = vec![vec![create_test_burn_event(
+            96,
+            "0x456",
+            "0x1234",
+            "0x10",
+            "0x0",
+            &commitment,
+        )]];
+        let provider = Arc::new(PaginatedMockProvider::new(100, pages));
+
+        let result = create_watcher(&app, provider.clone()).poll().await?;
+
+        assert_eq!(result.burn_events.len(), 1);
+        assert_eq!(provider.requested_tokens(), vec![None]);
+
+        let fetched = vec![result.burn_events[0].commitment_hash.clone()];
+        delete_burn_events(&app.db, &fetched).await
+    }
+
+    // A database of its own, so no other test's poll moves the block trackers it resumes from
+    #[sqlx::test]
+    async fn test_next_poll_resumes_after_last_processed_block(pool: PgPool) -> Result<()> {
+        let commitment = random_felt_hex();
+        let test_events = vec![create_test_burn_event(
+            95,
+            "0x789",
+            "0x1234",
+            "0x10",
+            "0x0",
+            &commitment,
+        )];
+
+        let mut mock_provider = MockStarknetProvider::new();
+        let mut sequence = Sequence::new();
+        mock_provider.expect_block_number().returning(|| Ok(100));
+        mock_provider
+            .expect_get_events()
+            .times(1)
+            .in_sequence(&mut sequence)
+            .returning(move |_, _, _| {
+                Ok(EventsPage {
+                    events: test_events.clone(),
+                    continuation_token: None,
+                })
+            });
+        mock_provider
+            .expect_get_events()
+            .withf(|filter, _, _| filter.from_block == Some(BlockId::Number(96)))
+            .times(1)
+            .in_sequence(&mut sequence)
+            .returning(|_, _, _| {
+                Ok(EventsPage {
+                    events: vec![],
+                    continuation_token: None,
+                })
+            });
+
+        let watcher =
+            L2EventWatcher::new(utils::create_test_config(), pool, Arc::new(mock_provider))
+                .with_start_block(90);
+        assert_eq!(watcher.last_processed_block(), None);
+
+        watcher.poll().await?;
+        assert_eq!(watcher.last_processed_block(), Some(95));
+        watcher.poll().await?;
+
+        Ok(())
+    }
 }
@@ -0,0 +1,63 @@
+use sqlx::PgPool;
+use std::time::Duration;
+use tokio::time::Instant;
+use zeroxbridge_sequencer::queue::l2_queue::{L2Queue, QueueConfig};
+
+fn backoff_config() -> QueueConfig {
+    QueueConfig {
+        min_interval_sec: 1,
+        max_interval_sec: 16,
+        backoff_factor: 2.0,
+        initial_retry_delay_sec: 0,
+        max_retries: 1,
+        batch_size: 10,
+        high_priority_threshold_usd: None,
+        max_concurrent_validations: 4,
+    }
+}
+
+#[test]
+fn test_interval_doubles_on_empty_cycles_up_to_max() {
+    let config = backoff_config();
+    let mut interval = Duration::from_secs(config.min_interval_sec);
+
+    let mut waits = Vec::new();
+    for _ in 0..6 {
+        interval = config.next_poll_interval(interval, 0);
+        waits.push(interval.as_secs());
+    }
+
+    assert_eq!(waits, vec![2, 4, 8, 16, 16, 16]);
+}
+
+#[test]
+fn test_processed_batch_resets_interval() {
+    let config = backoff_config();
+
+    assert_eq!(
+        config.next_poll_interval(Duration::from_secs(16), 3),
+        Duration::from_secs(1)
+    );
+    assert_eq!(
+        config.next_poll_interval(Duration::from_secs(1), 0),
+        Duration::from_secs(2)
+    );
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_wait_follows_backed_off_interval() {
+    // Waiting never touches the database
+    let db_pool = PgPool::connect_lazy("postgres://localhost/unused").unwrap();
+    let queue = L2Queue::new(db_pool, backoff_config());
+    let config = backoff_config();
+
+    let mut interval = Duration::from_secs(config.min_interval_sec);
+    for expected in [2, 4, 8] {
+        interval = config.next_poll_interval(interval, 0);
+
+        let started = Instant::now();
+        queue.wait_for_next_cycle(interval).await;
+
+        assert_eq!(started.elapsed(), Duration::from_secs(expected));
+    }
+}
@@ -0,0 +1,91 @@
+#[path = "utils.rs"]
+mod utils;
+
+use futures_util::future::BoxFuture;
+use std::collections::HashSet;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::Barrier;
+use utils::create_test_app;
+use zeroxbridge_sequencer::queue::l2_queue::{L2Queue, L2QueueError, L2Transaction, QueueConfig};
+
+const TOKEN: &str = "0xconcurrent";
+const MAX_CONCURRENT_VALIDATIONS: usize = 4;
+
+fn queue_config() -> QueueConfig {
+    QueueConfig {
+        min_interval_sec: 1,
+        max_interval_sec: 60,
+        backoff_factor: 2.0,
+        initial_retry_delay_sec: 0,
+        max_retries: 10,
+        batch_size: 1000,
+        high_priority_threshold_usd: None,
+        max_concurrent_validations: MAX_CONCURRENT_VALIDATIONS,
+    }
+}
+
+async fn status_of(pool: &sqlx::PgPool, id: i64) -> String {
+    sqlx::query_scalar!("SELECT status FROM l2_transactions WHERE id = $1", id)
+        .fetch_one(pool)
+        .await
+        .unwrap()
+}
+
+#[tokio::test]
+async fn test_batch_is_validated_concurrently_and_applied_in_order() {
+    let app = create_test_app().await;
+    let queue = L2Queue::new(app.db.clone(), queue_config());
+    let commitment = format!("0x{}", uuid::Uuid::new_v4().simple());
+    let mut ids = Vec::new();
+    for _ in 0..MAX_CONCURRENT_VALIDATIONS {
+        ids.push(
+            queue
+                .enqueue_transaction("0x123", 1000, TOKEN, Some(&commitment))
+                .await
+                .unwrap(),
+        );
+    }
+
+    // Each of our validations waits for all the others, so the cycle can only finish when they
+    // all run at once. Transactions left pending by other tests are answered straight away.
+    let barrier = Arc::new(Barrier::new(MAX_CONCURRENT_VALIDATIONS));
+    let ours: Arc<HashSet<i64>> = Arc::new(ids.iter().copied().collect());
+    let proof = serde_json::json!({
+        "commitment_hash": commitment,
+        "merkle_root": "0xc0c0",
+        "proof": [],
+    })
+    .to_string();
+    let queue = queue.with_commitment_check(Arc::new(
+        move |tx: &L2Transaction| -> BoxFuture<'static, Result<Option<String>, L2QueueError>> {
+            let barrier = Arc::clone(&barrier);
+            let is_ours = ours.contains(&tx.id);
+            let proof = proof.clone();
+            Box::pin(async move {
+                if !is_ours {
+                    return Ok(None);
+                }
+                barrier.wait().await;
+                Ok(Some(proof))
+            })
+        },
+    ));
+
+    tokio::time::timeout(Duration::from_secs(10), queue.process_transactions())
+        .await
+        .expect("The batch should be validated concurrently")
+        .unwrap();
+
+    // All of them carry the same commitment and finished together, yet the first in batch order
+    // is the one handed to the relayer
+    assert_eq!(status_of(&app.db, ids[0]).await, "ready_for_relay");
+    for id in &ids[1..] {
+        assert_eq!(status_of(&app.db, *id).await, "duplicate");
+    }
+
+    sqlx::query!("DELETE FROM l2_transactions WHERE id = ANY($1)", &ids)
+        .execute(&app.db)
+        .await
+        .unwrap();
+}
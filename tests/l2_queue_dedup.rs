@@ -0,0 +1,170 @@
+#[path = "utils.rs"]
+mod utils;
+
+use utils::create_test_app;
+use zeroxbridge_sequencer::queue::l2_queue::{L2Queue, L2QueueError, QueueConfig};
+
+const TOKEN: &str = "0xdedup";
+
+fn queue_config() -> QueueConfig {
+    QueueConfig {
+        min_interval_sec: 1,
+        max_interval_sec: 60,
+        backoff_factor: 2.0,
+        initial_retry_delay_sec: 0,
+        max_retries: 3,
+        batch_size: 1000,
+        high_priority_threshold_usd: None,
+        max_concurrent_validations: 4,
+    }
+}
+
+/// Commitment unique to this test run, so other runs' rows never count as duplicates
+fn commitment() -> String {
+    format!("0x{}", uuid::Uuid::new_v4().simple())
+}
+
+fn proof_data(commitment: &str, merkle_root: &str) -> String {
+    serde_json::json!({
+        "commitment_hash": commitment,
+        "merkle_root": merkle_root,
+        "proof": [],
+    })
+    .to_string()
+}
+
+async fn status_of(pool: &sqlx::PgPool, id: i64) -> String {
+    sqlx::query_scalar!("SELECT status FROM l2_transactions WHERE id = $1", id)
+        .fetch_one(pool)
+        .await
+        .unwrap()
+}
+
+async fn cleanup(pool: &sqlx::PgPool, ids: &[i64]) {
+    sqlx::query!("DELETE FROM l2_transactions WHERE id = ANY($1)", ids)
+        .execute(pool)
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn test_second_transaction_with_same_commitment_is_duplicate() {
+    let app = create_test_app().await;
+    let queue = L2Queue::new(app.db.clone(), queue_config());
+    let commitment = commitment();
+
+    let first = queue
+        .enqueue_transaction("0x123", 1000, TOKEN, Some(&commitment))
+        .await
+        .unwrap();
+    // Spelled differently, the hash still names the same commitment
+    let respelled = commitment.trim_start_matches("0x").to_uppercase();
+    let second = queue
+        .enqueue_transaction("0x123", 1000, TOKEN, Some(&respelled))
+        .await
+        .unwrap();
+
+    // The root moved between the two checks, which must not hide the duplicate
+    queue
+        .mark_transaction_ready_for_relay(first, &proof_data(&commitment, "0x7007"))
+        .await
+        .unwrap();
+    queue
+        .mark_transaction_ready_for_relay(second, &proof_data(&commitment, "0x7008"))
+        .await
+        .unwrap();
+
+    assert_eq!(status_of(&app.db, first).await, "ready_for_relay");
+    assert_eq!(status_of(&app.db, second).await, "duplicate");
+
+    cleanup(&app.db, &[first, second]).await;
+}
+
+#[tokio::test]
+async fn test_distinct_commitments_are_both_ready() {
+    let app = create_test_app().await;
+    let queue = L2Queue::new(app.db.clone(), queue_config());
+    let (first_commitment, second_commitment) = (commitment(), commitment());
+
+    let first = queue
+        .enqueue_transaction("0x123", 1000, TOKEN, Some(&first_commitment))
+        .await
+        .unwrap();
+    let second = queue
+        .enqueue_transaction("0x123", 1000, TOKEN, Some(&second_commitment))
+        .await
+        .unwrap();
+
+    queue
+        .mark_transaction_ready_for_relay(first, &proof_data(&first_commitment, "0x7007"))
+        .await
+        .unwrap();
+    queue
+        .mark_transaction_ready_for_relay(second, &proof_data(&second_commitment, "0x7007"))
+        .await
+        .unwrap();
+
+    assert_eq!(status_of(&app.db, first).await, "ready_for_relay");
+    assert_eq!(status_of(&app.db, second).await, "ready_for_relay");
+
+    cleanup(&app.db, &[first, second]).await;
+}
+
+#[tokio::test]
+async fn test_commitment_of_failed_transaction_is_not_a_duplicate() {
+    let app = create_test_app().await;
+    let queue = L2Queue::new(app.db.clone(), queue_config());
+    let commitment = commitment();
+    let proof = proof_data(&commitment, "0x7007");
+
+    let failed = queue
+        .enqueue_transaction("0x123", 1000, TOKEN, Some(&commitment))
+        .await
+        .unwrap();
+    let retry = queue
+        .enqueue_transaction("0x123", 1000, TOKEN, Some(&commitment))
+        .await
+        .unwrap();
+
+    queue
+        .mark_transaction_ready_for_relay(failed, &proof)
+        .await
+        .unwrap();
+    sqlx::query!(
+        "UPDATE l2_transactions SET status = 'failed' WHERE id = $1",
+        failed
+    )
+    .execute(&app.db)
+    .await
+    .unwrap();
+    queue
+        .mark_transaction_ready_for_relay(retry, &proof)
+        .await
+        .unwrap();
+
+    assert_eq!(status_of(&app.db, retry).await, "ready_for_relay");
+
+    cleanup(&app.db, &[failed, retry]).await;
+}
+
+#[tokio::test]
+async fn test_marking_non_pending_transaction_fails() {
+    let app = create_test_app().await;
+    let queue = L2Queue::new(app.db.clone(), queue_config());
+    let commitment = commitment();
+    let proof = proof_data(&commitment, "0x7007");
+    let id = queue
+        .enqueue_transaction("0x123", 1000, TOKEN, Some(&commitment))
+        .await
+        .unwrap();
+    queue
+        .mark_transaction_ready_for_relay(id, &proof)
+        .await
+        .unwrap();
+
+    let result = queue.mark_transaction_ready_for_relay(id, &proof).await;
+
+    assert!(matches!(result, Err(L2QueueError::TransactionNotFound(tx)) if tx == id));
+
+    cleanup(&app.db, &[id]).await;
+}
@@ -0,0 +1,86 @@
+#[path = "utils.rs"]
+mod utils;
+
+use utils::create_test_app;
+use zeroxbridge_sequencer::queue::l2_queue::{L2Queue, PriorityLevel, QueueConfig};
+
+fn queue_config(high_priority_threshold_usd: Option<u64>) -> QueueConfig {
+    QueueConfig {
+        min_interval_sec: 1,
+        max_interval_sec: 60,
+        backoff_factor: 2.0,
+        initial_retry_delay_sec: 0,
+        max_retries: 1,
+        batch_size: 1000,
+        high_priority_threshold_usd,
+        max_concurrent_validations: 4,
+    }
+}
+
+#[test]
+fn test_priority_level_for_amount() {
+    assert_eq!(
+        PriorityLevel::for_amount(10_000, Some(10_000)),
+        PriorityLevel::High
+    );
+    assert_eq!(
+        PriorityLevel::for_amount(9_999, Some(10_000)),
+        PriorityLevel::Normal
+    );
+    assert_eq!(
+        PriorityLevel::for_amount(1_000_000, None),
+        PriorityLevel::Normal
+    );
+    assert_eq!(
+        PriorityLevel::for_amount(-1, Some(0)),
+        PriorityLevel::Normal
+    );
+
+    assert!(PriorityLevel::High.as_i16() > PriorityLevel::Normal.as_i16());
+    assert!(PriorityLevel::Normal.as_i16() > PriorityLevel::Low.as_i16());
+}
+
+#[tokio::test]
+async fn test_high_priority_transaction_is_dequeued_before_older_ones() {
+    let app = create_test_app().await;
+    let queue = L2Queue::new(app.db.clone(), queue_config(Some(10_000)));
+
+    let token = "0xpriority";
+    sqlx::query!(
+        "DELETE FROM l2_transactions WHERE token_address = $1",
+        token
+    )
+    .execute(&app.db)
+    .await
+    .unwrap();
+
+    let normal_id = queue
+        .enqueue_transaction("0xnormal", 500, token, None)
+        .await
+        .unwrap();
+    let high_id = queue
+        .enqueue_transaction("0xwhale", 50_000, token, None)
+        .await
+        .unwrap();
+
+    let pending: Vec<_> = queue
+        .get_pending_transactions_for_proof(1000)
+        .await
+        .unwrap()
+        .into_iter()
+        .filter(|tx| tx.token_address == token)
+        .collect();
+
+    let ids: Vec<i64> = pending.iter().map(|tx| tx.id).collect();
+    assert_eq!(ids, vec![high_id, normal_id]);
+    assert_eq!(pending[0].priority, PriorityLevel::High.as_i16());
+    assert_eq!(pending[1].priority, PriorityLevel::Normal.as_i16());
+
+    sqlx::query!(
+        "DELETE FROM l2_transactions WHERE token_address = $1",
+        token
+    )
+    .execute(&app.db)
+    .await
+    .unwrap();
+}
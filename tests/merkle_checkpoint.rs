@@ -0,0 +1,96 @@
+#[path = "utils.rs"]
+mod utils;
+
+use axum::{
+    body::{to_bytes, Body},
+    http::{Request, StatusCode},
+};
+use serde_json::Value;
+use tower::ServiceExt;
+use tree_builder::l2_tree::L2MerkleTreeBuilder;
+use utils::create_test_app;
+use zeroxbridge_sequencer::api::routes::create_router;
+
+fn restore_request(token: Option<&str>, checkpoint: String) -> Request<Body> {
+    let mut builder = Request::builder()
+        .method("POST")
+        .uri("/merkle/checkpoint")
+        .header("content-type", "text/plain");
+    if let Some(token) = token {
+        builder = builder.header("x-admin-token", token);
+    }
+    builder.body(Body::from(checkpoint)).unwrap()
+}
+
+#[tokio::test]
+async fn test_uploaded_checkpoint_is_restored_and_downloadable() {
+    let app = create_test_app().await;
+    let leaves: Vec<[u8; 32]> = (1u8..=5).map(|i| [i; 32]).collect();
+    let mut original = L2MerkleTreeBuilder::new();
+    original.build_merkle(leaves.clone()).await.unwrap();
+    let checkpoint = original.serialize_to_hex().await.unwrap();
+
+    let response = create_router(app.as_ref().clone())
+        .oneshot(restore_request(
+            Some("test-admin-token"),
+            checkpoint.clone(),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    let restored: Value = serde_json::from_slice(&body).unwrap();
+    let original_root = format!("0x{}", hex::encode(original.get_root().await.unwrap()));
+    assert_eq!(restored["root"], original_root);
+
+    let request = Request::builder()
+        .method("GET")
+        .uri("/merkle/checkpoint")
+        .body(Body::empty())
+        .unwrap();
+    let response = create_router(app.as_ref().clone())
+        .oneshot(request)
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    assert_eq!(String::from_utf8(body.to_vec()).unwrap(), checkpoint);
+
+    let downloaded = L2MerkleTreeBuilder::deserialize_from_hex(&checkpoint)
+        .await
+        .unwrap();
+    for leaf in leaves {
+        let proof = downloaded.get_proof(leaf).await.unwrap().unwrap();
+        assert!(original.verify_proof(proof, leaf).await.unwrap());
+    }
+}
+
+#[tokio::test]
+async fn test_restore_requires_admin_token() {
+    let app = create_test_app().await;
+    let checkpoint = L2MerkleTreeBuilder::new().serialize_to_hex().await.unwrap();
+
+    let response = create_router(app.as_ref().clone())
+        .oneshot(restore_request(None, checkpoint))
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+}
+
+#[tokio::test]
+async fn test_invalid_checkpoint_leaves_tree_untouched() {
+    let app = create_test_app().await;
+    let root_before = app.withdrawal_tree.get_root().await.unwrap();
+
+    let response = create_router(app.as_ref().clone())
+        .oneshot(restore_request(
+            Some("test-admin-token"),
+            hex::encode(r#"{"1":"0x01","3":"0x02"}"#),
+        ))
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+    assert_eq!(app.withdrawal_tree.get_root().await.unwrap(), root_before);
+}
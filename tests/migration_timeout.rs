@@ -0,0 +1,39 @@
+#[path = "utils.rs"]
+mod utils;
+
+use sqlx::migrate::Migrate;
+use utils::create_test_app;
+use zeroxbridge_sequencer::db::migrations::{MigrationError, SqlxMigrationRunner};
+
+#[tokio::test]
+async fn test_migrations_complete_within_timeout() {
+    let app = create_test_app().await;
+
+    let result = SqlxMigrationRunner::new(app.db.clone(), 60)
+        .run_with_timeout()
+        .await;
+
+    assert!(result.is_ok(), "{:?}", result);
+}
+
+#[tokio::test]
+async fn test_migrations_time_out_while_lock_is_held() {
+    let app = create_test_app().await;
+
+    // Stand in for another instance that is in the middle of migrating
+    let mut holder = app.db.acquire().await.unwrap();
+    holder.lock().await.unwrap();
+
+    let result = SqlxMigrationRunner::new(app.db.clone(), 1)
+        .run_with_timeout()
+        .await;
+
+    holder.unlock().await.unwrap();
+
+    let err = result.unwrap_err();
+    assert!(matches!(err, MigrationError::TimedOut(1)), "{:?}", err);
+    assert_eq!(
+        err.to_string(),
+        "Database migrations timed out after 1s — is another instance holding the migration lock?"
+    );
+}
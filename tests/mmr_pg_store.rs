@@ -0,0 +1,65 @@
+#[path = "utils.rs"]
+mod utils;
+
+use tree_builder::l1_tree::L1MerkleTreeBuilder;
+use utils::create_test_app;
+
+fn leaves(range: std::ops::Range<u8>) -> Vec<[u8; 32]> {
+    range.map(|i| [i; 32]).collect()
+}
+
+// One test, since every builder on a prefix shares its table
+#[tokio::test]
+async fn test_pg_backed_tree_survives_restart() {
+    let app = create_test_app().await;
+    for table in ["l1_mmr_nodes", "l2_mmr_nodes"] {
+        sqlx::query(&format!("DELETE FROM {}", table))
+            .execute(&app.db)
+            .await
+            .unwrap();
+    }
+
+    let mut builder = L1MerkleTreeBuilder::new_with_pg_store(app.db.clone(), "l1")
+        .await
+        .unwrap();
+    builder.build_merkle(leaves(1..6)).await.unwrap();
+    let root = builder.get_root().await.unwrap();
+    drop(builder);
+
+    // "Restart" with a fresh builder over the same table
+    let mut restarted = L1MerkleTreeBuilder::new_with_pg_store(app.db.clone(), "l1")
+        .await
+        .unwrap();
+    assert_eq!(restarted.get_root().await.unwrap(), root);
+    for leaf in leaves(1..6) {
+        assert!(restarted.find_leaf_by_commitment_hash(leaf).is_some());
+        let proof = restarted
+            .get_proof(leaf)
+            .await
+            .unwrap()
+            .expect("leaf from before the restart should be found");
+        assert!(restarted.verify_proof(proof, leaf).await.unwrap());
+    }
+    assert!(restarted.get_proof([99u8; 32]).await.unwrap().is_none());
+
+    // Appending after the restart continues the same tree
+    restarted.build_merkle(leaves(6..9)).await.unwrap();
+    let mut in_memory = L1MerkleTreeBuilder::new();
+    in_memory.build_merkle(leaves(1..9)).await.unwrap();
+    assert_eq!(
+        restarted.get_root().await.unwrap(),
+        in_memory.get_root().await.unwrap()
+    );
+    let proof = restarted.get_proof([7u8; 32]).await.unwrap().unwrap();
+    assert!(restarted.verify_proof(proof, [7u8; 32]).await.unwrap());
+
+    // The L2 prefix is a separate tree in its own table
+    let l2 = L1MerkleTreeBuilder::new_with_pg_store(app.db.clone(), "l2")
+        .await
+        .unwrap();
+    assert_eq!(
+        l2.get_root().await.unwrap(),
+        L1MerkleTreeBuilder::new().get_root().await.unwrap()
+    );
+    assert!(l2.get_proof([1u8; 32]).await.unwrap().is_none());
+}
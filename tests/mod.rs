@@ -1,14 +1,65 @@
+pub mod api_version;
+pub mod block_tracker;
+pub mod block_trackers_api;
+pub mod body_limit;
+pub mod calldata_format;
+pub mod commitment_registry;
 pub mod compute_hash;
 pub mod compute_hash_api;
+pub mod config_dump;
+pub mod config_reload;
+pub mod config_validator;
+pub mod content_negotiation;
+pub mod cors;
+pub mod database_connection;
+pub mod dead_letter_api;
 pub mod deposit_api;
+pub mod deposit_hash_upsert;
+pub mod deposit_proof_api;
+pub mod deposit_proof_job_link;
+pub mod ethereum_relayer_dry_run;
+pub mod fact_registry;
+pub mod finalization_watcher;
+pub mod health_api;
 pub mod herodotus_api;
 pub mod integration_proof_submission;
+pub mod l1_deposit_id;
+pub mod l1_event_stream;
+pub mod l1_event_watcher;
 pub mod l1_events_logs;
+pub mod l1_reorg;
+pub mod l2_burn_event;
+pub mod l2_commitment_check;
 pub mod l2_event_watcher;
+pub mod l2_queue_backoff;
+pub mod l2_queue_dedup;
+pub mod l2_queue_priority;
+pub mod merkle_checkpoint;
+pub mod merkle_root_watcher;
+pub mod migration_timeout;
 pub mod poseidon_test;
+pub mod proof_fee_estimation;
+pub mod proof_generation_worker;
+pub mod proof_job_calldata;
+pub mod proof_job_handoff;
+pub mod proof_job_metadata;
+pub mod proof_jobs_api;
 pub mod proof_submission_integration_test;
 pub mod proof_submission_test;
+pub mod provider_pool;
+pub mod queue_indexes;
+pub mod redis_block_tracker;
+pub mod request_id;
+pub mod round_robin_provider;
 pub mod scarb_build;
+pub mod sequencer_snapshot;
+pub mod service_registry;
 pub mod starknet_relayer_test;
+pub mod user_mappings;
 pub mod utils;
 pub mod withdrawal_api;
+pub mod withdrawal_completions;
+pub mod withdrawal_matcher;
+pub mod withdrawal_proof_status;
+pub mod withdrawal_relay_locking;
+pub mod withdrawal_timeline;
@@ -0,0 +1,133 @@
+#[path = "utils.rs"]
+mod utils;
+
+use axum::{
+    body::{to_bytes, Body},
+    http::{Request, StatusCode},
+};
+use chrono::{DateTime, Duration, Utc};
+use tower::ServiceExt;
+use utils::create_test_app;
+use zeroxbridge_sequencer::api::handlers::{is_tvl_snapshot_stale, OracleTvlResponse};
+use zeroxbridge_sequencer::api::routes::{create_router, AppState};
+use zeroxbridge_sequencer::db::database::{
+    insert_tvl_snapshot, l1_vault_tvl_chain, TvlSnapshot, L1_TOTAL_TVL_CHAIN, L2_TVL_CHAIN,
+};
+
+/// Polling interval of the test config's oracle
+const POLLING_INTERVAL_SECONDS: u64 = 60;
+
+fn l1_snapshot(captured_at: DateTime<Utc>) -> TvlSnapshot {
+    TvlSnapshot {
+        chain: L1_TOTAL_TVL_CHAIN.to_string(),
+        tvl_wei: "1000".to_string(),
+        block_number: 1,
+        captured_at,
+    }
+}
+
+/// Seeds a snapshot of `chain` captured `age_seconds` ago
+async fn seed_snapshot(
+    pool: &sqlx::PgPool,
+    chain: &str,
+    tvl_wei: &str,
+    block_number: i64,
+    age_seconds: f64,
+) {
+    sqlx::query!(
+        r#"
+        INSERT INTO tvl_snapshots (chain, tvl_wei, block_number, captured_at)
+        VALUES ($1, $2::TEXT::NUMERIC, $3, NOW() - make_interval(secs => $4))
+        "#,
+        chain,
+        tvl_wei,
+        block_number,
+        age_seconds
+    )
+    .execute(pool)
+    .await
+    .unwrap();
+}
+
+async fn get_oracle_tvl(app: &AppState) -> (StatusCode, Option<OracleTvlResponse>) {
+    let request = Request::builder()
+        .uri("/oracle/tvl")
+        .body(Body::empty())
+        .unwrap();
+
+    let response = create_router(app.clone()).oneshot(request).await.unwrap();
+    let status = response.status();
+    let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    (status, serde_json::from_slice(&body).ok())
+}
+
+#[test]
+fn test_snapshot_is_stale_after_two_polling_intervals() {
+    let now = Utc::now();
+    let stale = |age: i64| {
+        let snapshot = l1_snapshot(now - Duration::seconds(age));
+        is_tvl_snapshot_stale(&snapshot, POLLING_INTERVAL_SECONDS, now)
+    };
+
+    assert!(!stale(0));
+    assert!(!stale(119));
+    assert!(!stale(120));
+    assert!(stale(121));
+}
+
+// One test owns the table, the latest snapshot of each chain is global
+#[tokio::test]
+async fn test_oracle_tvl_reports_latest_snapshots_and_staleness() {
+    let app = create_test_app().await;
+    sqlx::query!("DELETE FROM tvl_snapshots")
+        .execute(&app.db)
+        .await
+        .unwrap();
+
+    let (status, _) = get_oracle_tvl(&app).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+
+    // Equal TVLs, but the L2 one was read three intervals ago. Single vaults are left out.
+    let vault = l1_vault_tvl_chain("0x00000000000000000000000000000000000000aa");
+    seed_snapshot(&app.db, &vault, "1000000000000000000000", 100, 0.0).await;
+    seed_snapshot(
+        &app.db,
+        L1_TOTAL_TVL_CHAIN,
+        "5000000000000000000000",
+        100,
+        0.0,
+    )
+    .await;
+    seed_snapshot(&app.db, L2_TVL_CHAIN, "5000000000000000000000", 900, 180.0).await;
+
+    let (status, body) = get_oracle_tvl(&app).await;
+    assert_eq!(status, StatusCode::OK);
+    let body = body.unwrap();
+    assert_eq!(body.l1.tvl_wei, "5000000000000000000000");
+    assert_eq!(body.l1.block, 100);
+    assert_eq!(body.l2.block, 900);
+    assert_eq!(body.diff_bps, 0);
+    assert!(!body.is_in_sync);
+
+    // A fresh L2 read brings them back in sync
+    insert_tvl_snapshot(&app.db, L2_TVL_CHAIN, "4990000000000000000000", 901)
+        .await
+        .unwrap();
+
+    let (_, body) = get_oracle_tvl(&app).await;
+    let body = body.unwrap();
+    assert_eq!(body.l2.tvl_wei, "4990000000000000000000");
+    assert_eq!(body.l2.block, 901);
+    assert_eq!(body.diff_bps, 20);
+    assert!(body.is_in_sync);
+
+    // Fresh but 2% apart is outside the 1% tolerance
+    insert_tvl_snapshot(&app.db, L2_TVL_CHAIN, "4900000000000000000000", 902)
+        .await
+        .unwrap();
+
+    let (_, body) = get_oracle_tvl(&app).await;
+    let body = body.unwrap();
+    assert_eq!(body.diff_bps, 200);
+    assert!(!body.is_in_sync);
+}
@@ -0,0 +1,428 @@
+#[path = "utils.rs"]
+mod utils;
+
+use axum::{
+    body::{to_bytes, Body},
+    http::{Request, StatusCode},
+};
+use mockito::{mock, Matcher, Mock};
+use sqlx::PgPool;
+use std::sync::Arc;
+use tempfile::tempdir;
+use tower::ServiceExt;
+use utils::{create_test_app, create_test_config};
+use zeroxbridge_sequencer::api::handlers::ProofJobFeeEstimatesResponse;
+use zeroxbridge_sequencer::api::routes::{create_router, AppState};
+use zeroxbridge_sequencer::relayer::proof_submission::{
+    check_step_fee, compute_calldata_hash, ProofSubmissionConfig, ProofSubmissionError,
+    ProofSubmissionRelayer,
+};
+
+/// Account the relayer estimates and sends from, so mocks don't answer other tests' requests
+const ACCOUNT_ADDRESS: &str = "0xfee5";
+
+fn nonce_mock() -> Mock {
+    mock("POST", "/")
+        .match_body(Matcher::Regex(format!(
+            r#"starknet_getNonce.*"{}""#,
+            ACCOUNT_ADDRESS
+        )))
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"jsonrpc":"2.0","id":1,"result":"0x0"}"#)
+        .create()
+}
+
+/// `starknet_estimateFee` answers `overall_fee` for calls carrying `job_id_hex`
+fn estimate_fee_mock(job_id_hex: &str, overall_fee: &str) -> Mock {
+    mock("POST", "/")
+        .match_body(Matcher::AllOf(vec![
+            Matcher::Regex("starknet_estimateFee".to_string()),
+            Matcher::Regex(format!(r#""{}""#, job_id_hex)),
+        ]))
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(format!(
+            r#"{{"jsonrpc":"2.0","id":1,"result":[{{"gas_consumed":"0x1","gas_price":"0x1","data_gas_consumed":"0x0","data_gas_price":"0x1","overall_fee":"{}","unit":"FRI"}}]}}"#,
+            overall_fee
+        ))
+        .create()
+}
+
+fn send_mock() -> Mock {
+    mock("POST", "/")
+        .match_body(Matcher::Regex(format!(
+            r#"starknet_addInvokeTransaction.*"{}""#,
+            ACCOUNT_ADDRESS
+        )))
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"jsonrpc":"2.0","id":1,"result":{"transaction_hash":"0x1"}}"#)
+        .expect(0)
+        .create()
+}
+
+async fn test_relayer(
+    pool: PgPool,
+    calldata_base_dir: &std::path::Path,
+    max_fee_per_step: Option<u128>,
+    max_total_fee: Option<u128>,
+) -> ProofSubmissionRelayer {
+    std::env::set_var("STARKNET_RPC_URL", mockito::server_url());
+    std::env::set_var("ETHEREUM_RPC_URL", mockito::server_url());
+
+    let mut config = ProofSubmissionConfig::from(create_test_config());
+    config.rpc_url = mockito::server_url();
+    config.ethereum_rpc_url = mockito::server_url();
+    config.starknet.account_address = ACCOUNT_ADDRESS.to_string();
+    config.calldata_base_dir = calldata_base_dir.to_path_buf();
+    config.max_retries = 1;
+    config.max_fee_per_step = max_fee_per_step;
+    config.max_total_fee = max_total_fee;
+    ProofSubmissionRelayer::new(pool, config)
+        .await
+        .expect("Failed to create relayer")
+}
+
+fn write_calldata(dir: &std::path::Path) {
+    std::fs::write(dir.join("initial"), "0x123 0x456").unwrap();
+    std::fs::write(dir.join("step1"), "0xabc").unwrap();
+    std::fs::write(dir.join("step2"), "0xdef").unwrap();
+    std::fs::write(dir.join("final"), "0x999").unwrap();
+}
+
+async fn delete_job(pool: &PgPool, job_id: i64) {
+    sqlx::query!("DELETE FROM proof_jobs WHERE job_id = $1", job_id)
+        .execute(pool)
+        .await
+        .unwrap();
+}
+
+#[test]
+fn test_check_step_fee() {
+    assert!(check_step_fee(1_000, None).is_ok());
+    assert!(check_step_fee(1_000, Some(1_000)).is_ok());
+    assert!(matches!(
+        check_step_fee(1_001, Some(1_000)),
+        Err(ProofSubmissionError::FeeTooHigh {
+            estimated: 1_001,
+            max: 1_000
+        })
+    ));
+}
+
+#[tokio::test]
+async fn test_submission_aborts_when_fee_exceeds_max() {
+    let app = create_test_app().await;
+    let job_id: i64 = 9_600_001;
+    delete_job(&app.db, job_id).await;
+
+    let base_dir = tempdir().unwrap();
+    write_calldata(base_dir.path());
+    let _nonce = nonce_mock();
+    let estimate = estimate_fee_mock("0x927c01", "0x3e8").expect(1);
+    let send = send_mock();
+
+    let relayer = test_relayer(app.db.clone(), base_dir.path(), Some(999), None).await;
+    let result = relayer
+        .submit_proof_from_calldata(
+            base_dir.path().to_path_buf(),
+            job_id as u64,
+            "recursive_with_poseidon".to_string(),
+            "keccak_160_lsb".to_string(),
+            "stone6".to_string(),
+            "true".to_string(),
+            false,
+            Vec::new(),
+            None,
+        )
+        .await;
+
+    estimate.assert();
+    send.assert();
+    match result {
+        Err(ProofSubmissionError::FeeTooHigh { estimated, max }) => {
+            assert_eq!(estimated, 1_000);
+            assert_eq!(max, 999);
+        }
+        other => panic!("expected FeeTooHigh, got {:?}", other),
+    }
+
+    // Nothing was sent, so the job is still waiting for its initial proof
+    let stage = sqlx::query_scalar!(
+        "SELECT current_stage FROM proof_jobs WHERE job_id = $1",
+        job_id
+    )
+    .fetch_one(&app.db)
+    .await
+    .unwrap();
+    assert_eq!(stage.as_deref(), Some("processing"));
+
+    delete_job(&app.db, job_id).await;
+}
+
+async fn post_estimate_fees(state: AppState, job_id: i64) -> (StatusCode, Vec<u8>) {
+    let request = Request::builder()
+        .method("POST")
+        .uri(format!("/proof-jobs/{}/estimate-fees", job_id))
+        .body(Body::empty())
+        .unwrap();
+    let response = create_router(state).oneshot(request).await.unwrap();
+    let status = response.status();
+    let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    (status, body.to_vec())
+}
+
+#[tokio::test]
+async fn test_estimate_fees_endpoint_covers_remaining_steps() {
+    let app = create_test_app().await;
+    let job_id: i64 = 9_600_002;
+    delete_job(&app.db, job_id).await;
+
+    let base_dir = tempdir().unwrap();
+    write_calldata(base_dir.path());
+    sqlx::query!(
+        r#"
+        INSERT INTO proof_jobs (job_id, calldata_dir, layout, hasher, stone_version, memory_verification, status, current_stage)
+        VALUES ($1, $2, 'recursive_with_poseidon', 'keccak_160_lsb', 'stone6', 'true', 'processing', 'initial_submitted')
+        "#,
+        job_id,
+        base_dir.path().display().to_string()
+    )
+    .execute(&app.db)
+    .await
+    .unwrap();
+
+    let _nonce = nonce_mock();
+    let estimate = estimate_fee_mock("0x927c02", "0x3e8").expect(3);
+    let send = send_mock();
+
+    let relayer = test_relayer(app.db.clone(), base_dir.path(), Some(1_500), None).await;
+    let state = app.as_ref().clone().with_proof_relayer(Arc::new(relayer));
+    let (status, body) = post_estimate_fees(state, job_id).await;
+
+    assert_eq!(status, StatusCode::OK);
+    estimate.assert();
+    send.assert();
+    let response: ProofJobFeeEstimatesResponse = serde_json::from_slice(&body).unwrap();
+    let stages: Vec<&str> = response.steps.iter().map(|s| s.stage.as_str()).collect();
+    assert_eq!(stages, ["step1", "step2", "final"]);
+    assert!(response
+        .steps
+        .iter()
+        .all(|step| step.overall_fee == 1_000 && !step.exceeds_max));
+    assert_eq!(response.total_fee, 3_000);
+    assert_eq!(response.max_fee_per_step, Some(1_500));
+
+    delete_job(&app.db, job_id).await;
+}
+
+#[tokio::test]
+async fn test_estimate_fees_endpoint_requires_relayer() {
+    let app = create_test_app().await;
+
+    let (status, _) = post_estimate_fees(app.as_ref().clone(), 9_600_003).await;
+
+    assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+}
+
+async fn job_status(pool: &PgPool, job_id: i64) -> (String, Option<String>) {
+    let row = sqlx::query!(
+        "SELECT status, current_stage FROM proof_jobs WHERE job_id = $1",
+        job_id
+    )
+    .fetch_one(pool)
+    .await
+    .unwrap();
+    (row.status, row.current_stage)
+}
+
+#[tokio::test]
+async fn test_submission_suspends_when_budget_is_exceeded() {
+    let app = create_test_app().await;
+    let job_id: i64 = 9_600_004;
+    delete_job(&app.db, job_id).await;
+
+    let base_dir = tempdir().unwrap();
+    write_calldata(base_dir.path());
+    let _nonce = nonce_mock();
+    let estimate = estimate_fee_mock("0x927c04", "0x3e8").expect(1);
+    let send = send_mock();
+
+    let relayer = test_relayer(app.db.clone(), base_dir.path(), None, Some(500)).await;
+    let result = relayer
+        .submit_proof_from_calldata(
+            base_dir.path().to_path_buf(),
+            job_id as u64,
+            "recursive_with_poseidon".to_string(),
+            "keccak_160_lsb".to_string(),
+            "stone6".to_string(),
+            "true".to_string(),
+            false,
+            Vec::new(),
+            None,
+        )
+        .await;
+
+    estimate.assert();
+    send.assert();
+    assert!(
+        matches!(
+            result,
+            Err(ProofSubmissionError::BudgetExceeded {
+                spent: 0,
+                limit: 500
+            })
+        ),
+        "{:?}",
+        result
+    );
+    assert_eq!(
+        job_status(&app.db, job_id).await,
+        ("suspended".to_string(), Some("processing".to_string()))
+    );
+
+    delete_job(&app.db, job_id).await;
+}
+
+async fn patch_resume(state: AppState, job_id: i64, token: Option<&str>, body: &str) -> StatusCode {
+    let mut request = Request::builder()
+        .method("PATCH")
+        .uri(format!("/proof-jobs/{}/resume-with-budget", job_id))
+        .header("content-type", "application/json");
+    if let Some(token) = token {
+        request = request.header("x-admin-token", token);
+    }
+    let response = create_router(state)
+        .oneshot(request.body(Body::from(body.to_string())).unwrap())
+        .await
+        .unwrap();
+    response.status()
+}
+
+async fn insert_job(pool: &PgPool, job_id: i64, calldata_dir: &std::path::Path, status: &str) {
+    sqlx::query!(
+        r#"
+        INSERT INTO proof_jobs (job_id, calldata_dir, layout, hasher, stone_version, memory_verification, status, current_stage, calldata_hash)
+        VALUES ($1, $2, 'recursive_with_poseidon', 'keccak_160_lsb', 'stone6', 'true', $3, 'initial_submitted', $4)
+        "#,
+        job_id,
+        calldata_dir.display().to_string(),
+        status,
+        compute_calldata_hash(calldata_dir).unwrap()
+    )
+    .execute(pool)
+    .await
+    .unwrap();
+}
+
+#[tokio::test]
+async fn test_resume_with_budget_endpoint() {
+    let app = create_test_app().await;
+    let job_id: i64 = 9_600_005;
+    let running_job_id: i64 = 9_600_006;
+    delete_job(&app.db, job_id).await;
+    delete_job(&app.db, running_job_id).await;
+
+    let base_dir = tempdir().unwrap();
+    write_calldata(base_dir.path());
+    insert_job(&app.db, job_id, base_dir.path(), "suspended").await;
+    insert_job(&app.db, running_job_id, base_dir.path(), "processing").await;
+
+    let _nonce = nonce_mock();
+    let estimate = estimate_fee_mock("0x927c05", "0x3e8").expect(1);
+    let send = send_mock();
+    let relayer = Arc::new(test_relayer(app.db.clone(), base_dir.path(), None, None).await);
+    let state = app.as_ref().clone().with_proof_relayer(relayer.clone());
+    let body = r#"{"max_total_fee": 999}"#;
+
+    assert_eq!(
+        patch_resume(state.clone(), job_id, None, body).await,
+        StatusCode::UNAUTHORIZED
+    );
+    assert_eq!(
+        patch_resume(
+            state.clone(),
+            running_job_id,
+            Some("test-admin-token"),
+            body
+        )
+        .await,
+        StatusCode::CONFLICT
+    );
+    assert_eq!(
+        patch_resume(state.clone(), 9_600_007, Some("test-admin-token"), body).await,
+        StatusCode::NOT_FOUND
+    );
+
+    // Resuming only queues the job, with its new budget
+    assert_eq!(
+        patch_resume(state.clone(), job_id, Some("test-admin-token"), body).await,
+        StatusCode::OK
+    );
+    assert_eq!(job_status(&app.db, job_id).await.0, "queued");
+    let budget: Option<String> =
+        sqlx::query_scalar("SELECT max_total_fee::TEXT FROM proof_jobs WHERE job_id = $1")
+            .bind(job_id)
+            .fetch_one(&app.db)
+            .await
+            .unwrap();
+    assert_eq!(budget.as_deref(), Some("999"));
+
+    // Only a suspended job can be queued, so a second resume is refused
+    assert_eq!(
+        patch_resume(state, job_id, Some("test-admin-token"), body).await,
+        StatusCode::CONFLICT
+    );
+
+    // The first remaining step alone is over the new budget, so the job is suspended again
+    let failures = relayer.process_all_queued_jobs().await.unwrap();
+    assert!(
+        failures.iter().any(|(failed, e)| *failed == job_id as u64
+            && matches!(e, ProofSubmissionError::BudgetExceeded { limit: 999, .. })),
+        "{:?}",
+        failures
+    );
+    estimate.assert();
+    send.assert();
+    assert_eq!(
+        job_status(&app.db, job_id).await,
+        (
+            "suspended".to_string(),
+            Some("initial_submitted".to_string())
+        )
+    );
+    assert_eq!(job_status(&app.db, running_job_id).await.0, "processing");
+
+    delete_job(&app.db, job_id).await;
+    delete_job(&app.db, running_job_id).await;
+}
+
+#[tokio::test]
+async fn test_resume_with_budget_refuses_modified_calldata() {
+    let app = create_test_app().await;
+    let job_id: i64 = 9_600_008;
+    delete_job(&app.db, job_id).await;
+
+    let base_dir = tempdir().unwrap();
+    write_calldata(base_dir.path());
+    insert_job(&app.db, job_id, base_dir.path(), "suspended").await;
+    std::fs::write(base_dir.path().join("step1"), "0xbad").unwrap();
+
+    let relayer = Arc::new(test_relayer(app.db.clone(), base_dir.path(), None, None).await);
+    let state = app.as_ref().clone().with_proof_relayer(relayer);
+
+    assert_eq!(
+        patch_resume(
+            state,
+            job_id,
+            Some("test-admin-token"),
+            r#"{"max_total_fee": 999}"#
+        )
+        .await,
+        StatusCode::CONFLICT
+    );
+    assert_eq!(job_status(&app.db, job_id).await.0, "suspended");
+
+    delete_job(&app.db, job_id).await;
+}
@@ -0,0 +1,152 @@
+#[path = "utils.rs"]
+mod utils;
+
+use mockito::{mock, Matcher};
+use tempfile::tempdir;
+use utils::{create_test_app, create_test_config};
+use zeroxbridge_sequencer::workers::proof_generation::{
+    ProofGenerationWorker, PROOF_STATUS_READY, STATUS_PENDING_PROOF, STATUS_PROOF_SUBMITTED,
+    STATUS_READY_FOR_RELAY,
+};
+
+#[tokio::test]
+async fn test_proof_generation_worker_lifecycle() {
+    let app = create_test_app().await;
+    std::env::set_var("HERODOTUS_API_KEY", "test_api");
+
+    let commitment_hash = format!("0xproofgen{}", uuid::Uuid::new_v4().simple());
+    let withdrawal_id: i32 = sqlx::query_scalar!(
+        r#"
+        INSERT INTO withdrawals (stark_pub_key, amount, l1_token, commitment_hash, status)
+        VALUES ('0xabc123', 1000, '0xtoken123', $1, $2)
+        RETURNING id
+        "#,
+        commitment_hash,
+        STATUS_PENDING_PROOF
+    )
+    .fetch_one(&app.db)
+    .await
+    .unwrap();
+
+    let work_dir = tempdir().unwrap();
+    let program_path = work_dir.path().join("cairo1.sierra.json");
+    std::fs::write(&program_path, r#"{"dummy":"data"}"#).unwrap();
+    let input_dir = work_dir.path().join(withdrawal_id.to_string());
+    std::fs::create_dir_all(&input_dir).unwrap();
+    std::fs::write(input_dir.join("input.cairo1.txt"), "[1 2 3]").unwrap();
+
+    let mut config = create_test_config().herodotus;
+    config.atlantic_endpoint = mockito::server_url();
+    config.program_path = program_path.to_string_lossy().into_owned();
+    config.inputs_dir = work_dir.path().to_string_lossy().into_owned();
+
+    let atlantic_job_id = format!("01JQWORKER{}", withdrawal_id);
+    let submit_mock = mock("POST", "/atlantic-query")
+        .match_query(Matcher::UrlEncoded("apiKey".into(), "test_api".into()))
+        .with_status(200)
+        .with_body(format!(r#"{{"atlanticQueryId":"{}"}}"#, atlantic_job_id))
+        .create();
+
+    let worker = ProofGenerationWorker::new(app.db.clone(), config);
+    assert!(worker.submit_pending_proofs().await.unwrap() >= 1);
+    submit_mock.assert();
+
+    let row = sqlx::query!(
+        "SELECT status, atlantic_job_id FROM withdrawals WHERE id = $1",
+        withdrawal_id
+    )
+    .fetch_one(&app.db)
+    .await
+    .unwrap();
+    assert_eq!(row.status, STATUS_PROOF_SUBMITTED);
+    assert_eq!(
+        row.atlantic_job_id.as_deref(),
+        Some(atlantic_job_id.as_str())
+    );
+
+    let status_mock = mock(
+        "GET",
+        format!("/atlantic-query/{}/status", atlantic_job_id).as_str(),
+    )
+    .match_query(Matcher::UrlEncoded("apiKey".into(), "test_api".into()))
+    .with_status(200)
+    .with_body(format!(
+        r#"{{"atlanticQuery":{{"id":"{}","status":"DONE"}}}}"#,
+        atlantic_job_id
+    ))
+    .expect(2)
+    .create();
+
+    // Until Atlantic hands over the proof, the withdrawal stays submitted
+    let missing_result_mock = mock(
+        "GET",
+        format!("/atlantic-query/{}/result", atlantic_job_id).as_str(),
+    )
+    .match_query(Matcher::Any)
+    .with_status(404)
+    .create();
+
+    worker.poll_submitted_proofs().await.unwrap();
+    missing_result_mock.assert();
+    drop(missing_result_mock);
+
+    let status: String = sqlx::query_scalar!(
+        "SELECT status FROM withdrawals WHERE id = $1",
+        withdrawal_id
+    )
+    .fetch_one(&app.db)
+    .await
+    .unwrap();
+    assert_eq!(status, STATUS_PROOF_SUBMITTED);
+
+    let result_mock = mock(
+        "GET",
+        format!("/atlantic-query/{}/result", atlantic_job_id).as_str(),
+    )
+    .match_query(Matcher::UrlEncoded("apiKey".into(), "test_api".into()))
+    .with_status(200)
+    .with_body(r#"{"proofParams":["0x1","0x2"],"proof":["0xff"]}"#)
+    .create();
+
+    assert!(worker.poll_submitted_proofs().await.unwrap() >= 1);
+    status_mock.assert();
+    result_mock.assert();
+
+    let status: String = sqlx::query_scalar!(
+        "SELECT status FROM withdrawals WHERE id = $1",
+        withdrawal_id
+    )
+    .fetch_one(&app.db)
+    .await
+    .unwrap();
+    assert_eq!(status, STATUS_READY_FOR_RELAY);
+
+    let proof = sqlx::query!(
+        "SELECT status, atlantic_job_id, proof_params, proof_data FROM withdrawal_proofs WHERE withdrawal_id = $1",
+        withdrawal_id
+    )
+    .fetch_one(&app.db)
+    .await
+    .unwrap();
+    assert_eq!(proof.status, PROOF_STATUS_READY);
+    assert_eq!(proof.atlantic_job_id, Some(atlantic_job_id));
+    let word = |value: u8| {
+        let mut word = [0u8; 32];
+        word[31] = value;
+        word
+    };
+    assert_eq!(proof.proof_params, Some([word(1), word(2)].concat()));
+    assert_eq!(proof.proof_data, Some(word(0xff).to_vec()));
+
+    sqlx::query!(
+        "DELETE FROM withdrawal_proofs WHERE withdrawal_id = $1",
+        withdrawal_id
+    )
+    .execute(&app.db)
+    .await
+    .unwrap();
+    sqlx::query!("DELETE FROM withdrawals WHERE id = $1", withdrawal_id)
+        .execute(&app.db)
+        .await
+        .unwrap();
+}
@@ -0,0 +1,173 @@
+#[path = "utils.rs"]
+mod utils;
+
+use axum::{
+    body::{to_bytes, Body},
+    http::{header::CONTENT_TYPE, Request, StatusCode},
+};
+use tempfile::{tempdir, TempDir};
+use tower::ServiceExt;
+use utils::create_test_app;
+use zeroxbridge_sequencer::api::routes::create_router;
+use zeroxbridge_sequencer::relayer::proof_submission::is_calldata_stage;
+
+const INITIAL_CALLDATA: &str = "0x123 0x456\n0x789\n";
+
+/// Proof job whose calldata directory holds `initial`, `step1` and `final` files
+async fn insert_job_with_calldata(pool: &sqlx::PgPool, job_id: i64) -> TempDir {
+    let calldata_dir = tempdir().unwrap();
+    std::fs::write(calldata_dir.path().join("initial"), INITIAL_CALLDATA).unwrap();
+    std::fs::write(calldata_dir.path().join("step1"), "0xabc").unwrap();
+    std::fs::write(calldata_dir.path().join("final"), "0x999").unwrap();
+
+    delete_job(pool, job_id).await;
+    sqlx::query!(
+        r#"
+        INSERT INTO proof_jobs (job_id, calldata_dir, layout, hasher, stone_version, memory_verification, status)
+        VALUES ($1, $2, 'recursive_with_poseidon', 'keccak_160_lsb', 'stone6', 'true', 'failed')
+        "#,
+        job_id,
+        calldata_dir.path().display().to_string()
+    )
+    .execute(pool)
+    .await
+    .unwrap();
+
+    calldata_dir
+}
+
+async fn delete_job(pool: &sqlx::PgPool, job_id: i64) {
+    sqlx::query!("DELETE FROM proof_jobs WHERE job_id = $1", job_id)
+        .execute(pool)
+        .await
+        .unwrap();
+}
+
+fn calldata_request(job_id: i64, stage: &str, token: Option<&str>) -> Request<Body> {
+    let mut builder = Request::builder()
+        .method("GET")
+        .uri(format!("/proof-jobs/{}/calldata/{}", job_id, stage));
+    if let Some(token) = token {
+        builder = builder.header("x-admin-token", token);
+    }
+    builder.body(Body::empty()).unwrap()
+}
+
+#[test]
+fn test_is_calldata_stage() {
+    for stage in ["initial", "step1", "step12", "final"] {
+        assert!(is_calldata_stage(stage), "{} should be accepted", stage);
+    }
+    for stage in [
+        "step", "stepx", "step1a", "../final", "initial/", "Final", "",
+    ] {
+        assert!(!is_calldata_stage(stage), "{} should be rejected", stage);
+    }
+}
+
+#[tokio::test]
+async fn test_download_calldata_file() {
+    let app = create_test_app().await;
+    let job_id: i64 = 9_700_001;
+    let _calldata_dir = insert_job_with_calldata(&app.db, job_id).await;
+
+    let response = create_router(app.as_ref().clone())
+        .oneshot(calldata_request(
+            job_id,
+            "initial",
+            Some("test-admin-token"),
+        ))
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), StatusCode::OK);
+    assert!(response.headers()[CONTENT_TYPE]
+        .to_str()
+        .unwrap()
+        .starts_with("text/plain"));
+    let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    assert_eq!(body, INITIAL_CALLDATA.as_bytes());
+
+    let response = create_router(app.as_ref().clone())
+        .oneshot(calldata_request(job_id, "step1", Some("test-admin-token")))
+        .await
+        .unwrap();
+    let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    assert_eq!(body, "0xabc".as_bytes());
+
+    delete_job(&app.db, job_id).await;
+}
+
+#[tokio::test]
+async fn test_missing_job_or_stage_returns_404() {
+    let app = create_test_app().await;
+    let job_id: i64 = 9_700_002;
+    let _calldata_dir = insert_job_with_calldata(&app.db, job_id).await;
+
+    for (job_id, stage) in [(job_id, "step2"), (9_700_099, "initial")] {
+        let response = create_router(app.as_ref().clone())
+            .oneshot(calldata_request(job_id, stage, Some("test-admin-token")))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
+
+    delete_job(&app.db, job_id).await;
+}
+
+#[tokio::test]
+async fn test_invalid_stage_is_rejected() {
+    let app = create_test_app().await;
+
+    for stage in ["proof", "step", "..%2F..%2Fetc%2Fpasswd"] {
+        let response = create_router(app.as_ref().clone())
+            .oneshot(calldata_request(9_700_003, stage, Some("test-admin-token")))
+            .await
+            .unwrap();
+        assert_eq!(
+            response.status(),
+            StatusCode::BAD_REQUEST,
+            "stage {}",
+            stage
+        );
+    }
+}
+
+#[cfg(unix)]
+#[tokio::test]
+async fn test_symlink_out_of_calldata_dir_is_not_served() {
+    let app = create_test_app().await;
+    let job_id: i64 = 9_700_004;
+    let calldata_dir = insert_job_with_calldata(&app.db, job_id).await;
+
+    let outside = tempdir().unwrap();
+    std::fs::write(outside.path().join("secret"), "do not serve").unwrap();
+    std::os::unix::fs::symlink(
+        outside.path().join("secret"),
+        calldata_dir.path().join("step2"),
+    )
+    .unwrap();
+
+    let response = create_router(app.as_ref().clone())
+        .oneshot(calldata_request(job_id, "step2", Some("test-admin-token")))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+    delete_job(&app.db, job_id).await;
+}
+
+#[tokio::test]
+async fn test_download_requires_admin_token() {
+    let app = create_test_app().await;
+    let job_id: i64 = 9_700_005;
+    let _calldata_dir = insert_job_with_calldata(&app.db, job_id).await;
+
+    let response = create_router(app.as_ref().clone())
+        .oneshot(calldata_request(job_id, "initial", None))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+    delete_job(&app.db, job_id).await;
+}
@@ -0,0 +1,181 @@
+#[path = "utils.rs"]
+mod utils;
+
+use axum::{
+    body::{to_bytes, Body},
+    http::{Request, StatusCode},
+};
+use chrono::{TimeZone, Utc};
+use serde_json::{json, Value};
+use tower::ServiceExt;
+use utils::create_test_app;
+use zeroxbridge_sequencer::api::routes::{create_router, AppState};
+use zeroxbridge_sequencer::db::database::{fetch_proof_job_by_job_id, import_proof_job};
+use zeroxbridge_sequencer::relayer::proof_submission::{ProofJob, ProofSubmissionError};
+
+fn proof_job(job_id: i64) -> ProofJob {
+    ProofJob {
+        id: 0,
+        job_id,
+        calldata_dir: "/tmp/calldata".to_string(),
+        layout: "recursive_with_poseidon".to_string(),
+        hasher: "keccak_160_lsb".to_string(),
+        stone_version: "stone6".to_string(),
+        memory_verification: "true".to_string(),
+        // Failed jobs are never picked up by other tests' submission runs
+        status: "failed".to_string(),
+        current_stage: Some("step2_submitted".to_string()),
+        retry_count: 1,
+        error_message: Some("sequencer shut down".to_string()),
+        tx_hashes: json!({ "initial": "0x1", "step1": "0x2", "step2": "0x3" }),
+        stage_started_at: Some(Utc.with_ymd_and_hms(2025, 8, 20, 12, 0, 0).unwrap()),
+        fact_hash: None,
+        calldata_hash: Some("ab".repeat(32)),
+        metadata: json!({ "deposit_ids": [1, 2], "notes": { "operator": "handed over" } }),
+    }
+}
+
+async fn clear_proof_job(pool: &sqlx::PgPool, job_id: i64) {
+    sqlx::query!("DELETE FROM proof_jobs WHERE job_id = $1", job_id)
+        .execute(pool)
+        .await
+        .unwrap();
+}
+
+async fn send(app: &AppState, request: Request<Body>) -> (StatusCode, Vec<u8>) {
+    let response = create_router(app.clone()).oneshot(request).await.unwrap();
+    let status = response.status();
+    let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    (status, body.to_vec())
+}
+
+fn export_request(job_id: i64, token: Option<&str>) -> Request<Body> {
+    let mut builder = Request::builder()
+        .method("GET")
+        .uri(format!("/proof-jobs/{}/handoff", job_id));
+    if let Some(token) = token {
+        builder = builder.header("x-admin-token", token);
+    }
+    builder.body(Body::empty()).unwrap()
+}
+
+fn import_request(handoff: &Value, token: Option<&str>) -> Request<Body> {
+    let mut builder = Request::builder()
+        .method("POST")
+        .uri("/proof-jobs/handoff")
+        .header("content-type", "application/json");
+    if let Some(token) = token {
+        builder = builder.header("x-admin-token", token);
+    }
+    builder.body(Body::from(handoff.to_string())).unwrap()
+}
+
+#[test]
+fn test_handoff_json_round_trips_every_field() {
+    let job = proof_job(1);
+
+    let restored = ProofJob::from_handoff_json(&job.to_handoff_json()).unwrap();
+
+    assert_eq!(
+        serde_json::to_value(&restored).unwrap(),
+        serde_json::to_value(&job).unwrap()
+    );
+}
+
+#[test]
+fn test_handoff_rejects_unknown_stage_and_status() {
+    for (field, value) in [
+        ("current_stage", "step_submitted"),
+        ("current_stage", "uploading"),
+        ("status", "paused"),
+    ] {
+        let mut handoff = proof_job(1).to_handoff_json();
+        handoff[field] = json!(value);
+
+        let result = ProofJob::from_handoff_json(&handoff);
+
+        assert!(
+            matches!(result, Err(ProofSubmissionError::InvalidHandoff(_))),
+            "{} = {:?} was accepted",
+            field,
+            value
+        );
+    }
+}
+
+#[test]
+fn test_handoff_requires_every_field() {
+    let handoff = proof_job(1).to_handoff_json();
+
+    for field in handoff.as_object().unwrap().keys() {
+        let mut partial = handoff.clone();
+        partial.as_object_mut().unwrap().remove(field);
+
+        assert!(
+            ProofJob::from_handoff_json(&partial).is_err(),
+            "handoff without {} was accepted",
+            field
+        );
+    }
+
+    let mut extra = handoff;
+    extra["priority"] = json!(1);
+    assert!(ProofJob::from_handoff_json(&extra).is_err());
+}
+
+#[tokio::test]
+async fn test_handoff_endpoints_move_job_between_instances() {
+    let app = create_test_app().await;
+    let (source_id, target_id) = (9_300_001, 9_300_002);
+    clear_proof_job(&app.db, source_id).await;
+    clear_proof_job(&app.db, target_id).await;
+    import_proof_job(&app.db, &proof_job(source_id))
+        .await
+        .unwrap();
+
+    let (status, _) = send(&app, export_request(source_id, None)).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+
+    let (status, body) = send(&app, export_request(source_id, Some("test-admin-token"))).await;
+    assert_eq!(status, StatusCode::OK);
+    let mut handoff: Value = serde_json::from_slice(&body).unwrap();
+
+    // Stands in for the other instance's database by importing under a fresh job id
+    handoff["job_id"] = json!(target_id);
+    let (status, _) = send(&app, import_request(&handoff, Some("test-admin-token"))).await;
+    assert_eq!(status, StatusCode::CREATED);
+
+    let imported = fetch_proof_job_by_job_id(&app.db, target_id)
+        .await
+        .unwrap()
+        .expect("Proof job was not imported");
+    assert_eq!(imported.current_stage.as_deref(), Some("step2_submitted"));
+    assert_eq!(imported.retry_count, 1);
+    assert_eq!(imported.tx_hashes, proof_job(target_id).tx_hashes);
+    assert_eq!(imported.calldata_hash, proof_job(target_id).calldata_hash);
+
+    let (status, _) = send(&app, import_request(&handoff, Some("test-admin-token"))).await;
+    assert_eq!(status, StatusCode::CONFLICT);
+
+    clear_proof_job(&app.db, source_id).await;
+    clear_proof_job(&app.db, target_id).await;
+}
+
+#[tokio::test]
+async fn test_handoff_import_validates_before_storing() {
+    let app = create_test_app().await;
+    let job_id = 9_300_003;
+    clear_proof_job(&app.db, job_id).await;
+    let mut handoff = proof_job(job_id).to_handoff_json();
+
+    let (status, _) = send(&app, import_request(&handoff, None)).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+
+    handoff["current_stage"] = json!("bogus");
+    let (status, body) = send(&app, import_request(&handoff, Some("test-admin-token"))).await;
+    assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+    assert!(String::from_utf8_lossy(&body).contains("unknown stage 'bogus'"));
+
+    let stored = fetch_proof_job_by_job_id(&app.db, job_id).await.unwrap();
+    assert!(stored.is_none());
+}
@@ -0,0 +1,150 @@
+#[path = "utils.rs"]
+mod utils;
+
+use axum::{
+    body::{to_bytes, Body},
+    http::{Request, StatusCode},
+};
+use serde_json::{json, Value};
+use tower::ServiceExt;
+use utils::create_test_app;
+use zeroxbridge_sequencer::api::routes::{create_router, AppState};
+use zeroxbridge_sequencer::db::database::{fetch_proof_job_by_job_id, import_proof_job};
+use zeroxbridge_sequencer::relayer::proof_submission::ProofJob;
+
+const ADMIN_TOKEN: &str = "test-admin-token";
+
+async fn insert_proof_job(pool: &sqlx::PgPool, job_id: i64) {
+    clear_proof_job(pool, job_id).await;
+    let job = ProofJob {
+        id: 0,
+        job_id,
+        calldata_dir: "/tmp/calldata".to_string(),
+        layout: "recursive_with_poseidon".to_string(),
+        hasher: "keccak_160_lsb".to_string(),
+        stone_version: "stone6".to_string(),
+        memory_verification: "true".to_string(),
+        // Failed jobs are never picked up by other tests' submission runs
+        status: "failed".to_string(),
+        current_stage: None,
+        retry_count: 0,
+        error_message: None,
+        tx_hashes: json!({}),
+        stage_started_at: None,
+        fact_hash: None,
+        calldata_hash: None,
+        metadata: json!({}),
+    };
+    import_proof_job(pool, &job).await.unwrap();
+}
+
+async fn clear_proof_job(pool: &sqlx::PgPool, job_id: i64) {
+    sqlx::query!("DELETE FROM proof_jobs WHERE job_id = $1", job_id)
+        .execute(pool)
+        .await
+        .unwrap();
+}
+
+async fn patch_metadata(
+    app: &AppState,
+    job_id: i64,
+    patch: Value,
+    token: Option<&str>,
+) -> (StatusCode, Option<Value>) {
+    let mut builder = Request::builder()
+        .method("PATCH")
+        .uri(format!("/proof-jobs/{}/metadata", job_id))
+        .header("content-type", "application/json");
+    if let Some(token) = token {
+        builder = builder.header("x-admin-token", token);
+    }
+    let request = builder.body(Body::from(patch.to_string())).unwrap();
+
+    let response = create_router(app.clone()).oneshot(request).await.unwrap();
+    let status = response.status();
+    let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    (status, serde_json::from_slice(&body).ok())
+}
+
+#[tokio::test]
+async fn test_patches_merge_into_metadata() {
+    let app = create_test_app().await;
+    let job_id = 9_900_001;
+    insert_proof_job(&app.db, job_id).await;
+
+    let (status, _) = patch_metadata(&app, job_id, json!({ "a": 1 }), Some(ADMIN_TOKEN)).await;
+    assert_eq!(status, StatusCode::OK);
+    let (status, metadata) =
+        patch_metadata(&app, job_id, json!({ "b": 2 }), Some(ADMIN_TOKEN)).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(metadata, Some(json!({ "a": 1, "b": 2 })));
+
+    let (_, metadata) = patch_metadata(&app, job_id, json!({ "a": 3 }), Some(ADMIN_TOKEN)).await;
+    assert_eq!(metadata, Some(json!({ "a": 3, "b": 2 })));
+
+    let stored = fetch_proof_job_by_job_id(&app.db, job_id)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(stored.metadata, json!({ "a": 3, "b": 2 }));
+
+    clear_proof_job(&app.db, job_id).await;
+}
+
+#[tokio::test]
+async fn test_nested_objects_are_merged_recursively() {
+    let app = create_test_app().await;
+    let job_id = 9_900_002;
+    insert_proof_job(&app.db, job_id).await;
+
+    patch_metadata(
+        &app,
+        job_id,
+        json!({ "program": { "version": "1.0", "hash": "0x1" } }),
+        Some(ADMIN_TOKEN),
+    )
+    .await;
+    let (_, metadata) = patch_metadata(
+        &app,
+        job_id,
+        json!({ "program": { "version": "1.1" } }),
+        Some(ADMIN_TOKEN),
+    )
+    .await;
+
+    assert_eq!(
+        metadata,
+        Some(json!({ "program": { "version": "1.1", "hash": "0x1" } }))
+    );
+
+    clear_proof_job(&app.db, job_id).await;
+}
+
+#[tokio::test]
+async fn test_non_object_patch_is_rejected() {
+    let app = create_test_app().await;
+    let job_id = 9_900_003;
+    insert_proof_job(&app.db, job_id).await;
+
+    let (status, _) = patch_metadata(&app, job_id, json!([1, 2]), Some(ADMIN_TOKEN)).await;
+
+    assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+    let stored = fetch_proof_job_by_job_id(&app.db, job_id)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(stored.metadata, json!({}));
+
+    clear_proof_job(&app.db, job_id).await;
+}
+
+#[tokio::test]
+async fn test_patch_requires_admin_token_and_existing_job() {
+    let app = create_test_app().await;
+
+    let (status, _) = patch_metadata(&app, 9_900_004, json!({ "a": 1 }), None).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+
+    let (status, _) = patch_metadata(&app, 9_900_004, json!({ "a": 1 }), Some(ADMIN_TOKEN)).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
@@ -0,0 +1,163 @@
+#[path = "utils.rs"]
+mod utils;
+
+use axum::{
+    body::{to_bytes, Body},
+    http::{Request, StatusCode},
+};
+use serde_json::json;
+use tower::ServiceExt;
+use utils::create_test_app;
+use zeroxbridge_sequencer::api::routes::{create_router, AppState};
+use zeroxbridge_sequencer::db::database::{
+    fetch_proof_job_by_job_id, import_proof_job, insert_proof_job_tx_hash, ProofJobTxHash,
+};
+use zeroxbridge_sequencer::relayer::proof_submission::{
+    parse_tx_hashes, ProofJob, ProofSubmissionError,
+};
+
+async fn insert_proof_job(pool: &sqlx::PgPool, job_id: i64, tx_hashes: serde_json::Value) -> i64 {
+    sqlx::query!("DELETE FROM proof_jobs WHERE job_id = $1", job_id)
+        .execute(pool)
+        .await
+        .unwrap();
+    let job = ProofJob {
+        id: 0,
+        job_id,
+        calldata_dir: "/tmp/calldata".to_string(),
+        layout: "recursive_with_poseidon".to_string(),
+        hasher: "keccak_160_lsb".to_string(),
+        stone_version: "stone6".to_string(),
+        memory_verification: "true".to_string(),
+        // Failed jobs are never picked up by other tests' submission runs
+        status: "failed".to_string(),
+        current_stage: Some("step1_submitted".to_string()),
+        retry_count: 0,
+        error_message: None,
+        tx_hashes,
+        stage_started_at: None,
+        fact_hash: None,
+        calldata_hash: None,
+        metadata: json!({}),
+    };
+    import_proof_job(pool, &job).await.unwrap().id
+}
+
+async fn get_transactions(app: &AppState, job_id: i64) -> (StatusCode, Vec<ProofJobTxHash>) {
+    let request = Request::builder()
+        .uri(format!("/proof-jobs/{}/transactions", job_id))
+        .body(Body::empty())
+        .unwrap();
+
+    let response = create_router(app.clone()).oneshot(request).await.unwrap();
+    let status = response.status();
+    let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    (status, serde_json::from_slice(&body).unwrap_or_default())
+}
+
+#[tokio::test]
+async fn test_transactions_of_every_stage_are_listed() {
+    let app = create_test_app().await;
+    let job_id = 9_910_001;
+    let id = insert_proof_job(&app.db, job_id, json!({ "initial": "0x1" })).await;
+
+    insert_proof_job_tx_hash(&app.db, id, "step1", "0x2")
+        .await
+        .unwrap();
+    insert_proof_job_tx_hash(&app.db, id, "final", "0x3")
+        .await
+        .unwrap();
+
+    let (status, transactions) = get_transactions(&app, job_id).await;
+
+    assert_eq!(status, StatusCode::OK);
+    let stages: Vec<(&str, &str)> = transactions
+        .iter()
+        .map(|t| (t.stage.as_str(), t.tx_hash.as_str()))
+        .collect();
+    assert_eq!(
+        stages,
+        vec![("initial", "0x1"), ("step1", "0x2"), ("final", "0x3")]
+    );
+    assert!(transactions.iter().all(|t| t.proof_job_id == id));
+}
+
+#[tokio::test]
+async fn test_proof_job_reads_tx_hashes_from_table() {
+    let app = create_test_app().await;
+    let job_id = 9_910_002;
+    let id = insert_proof_job(&app.db, job_id, json!({})).await;
+
+    let job = fetch_proof_job_by_job_id(&app.db, job_id)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(job.tx_hashes, json!({}));
+
+    insert_proof_job_tx_hash(&app.db, id, "initial", "0xa")
+        .await
+        .unwrap();
+    insert_proof_job_tx_hash(&app.db, id, "step1", "0xb")
+        .await
+        .unwrap();
+
+    let job = fetch_proof_job_by_job_id(&app.db, job_id)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(job.tx_hashes, json!({ "initial": "0xa", "step1": "0xb" }));
+}
+
+#[tokio::test]
+async fn test_transactions_of_unknown_job_is_not_found() {
+    let app = create_test_app().await;
+
+    let (status, _) = get_transactions(&app, 9_910_999).await;
+
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn test_null_tx_hashes_recover_to_an_empty_map() {
+    let app = create_test_app().await;
+    let job_id = 9_910_003;
+    let id = insert_proof_job(&app.db, job_id, serde_json::Value::Null).await;
+
+    let job = fetch_proof_job_by_job_id(&app.db, job_id)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(job.tx_hashes, json!({}));
+
+    insert_proof_job_tx_hash(&app.db, id, "initial", "0xc")
+        .await
+        .unwrap();
+    let job = fetch_proof_job_by_job_id(&app.db, job_id)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(job.tx_hashes, json!({ "initial": "0xc" }));
+}
+
+#[test]
+fn test_non_object_tx_hashes_are_reported_as_corrupted() {
+    for tx_hashes in [
+        serde_json::Value::Null,
+        json!(["0x1"]),
+        json!({ "initial": 1 }),
+    ] {
+        let result = parse_tx_hashes(7, &tx_hashes);
+
+        assert!(
+            matches!(
+                result,
+                Err(ProofSubmissionError::TxHashesCorrupted { job_id: 7, .. })
+            ),
+            "{}",
+            tx_hashes
+        );
+    }
+
+    let hashes = parse_tx_hashes(7, &json!({ "initial": "0x1" })).unwrap();
+    assert_eq!(hashes["initial"], "0x1");
+}
@@ -0,0 +1,343 @@
+#[path = "utils.rs"]
+mod utils;
+
+use axum::{
+    body::Body,
+    http::{Request, StatusCode},
+};
+use tower::ServiceExt;
+use utils::create_test_app;
+use zeroxbridge_sequencer::api::routes::{create_router, AppState};
+
+async fn insert_proof_job(pool: &sqlx::PgPool, job_id: i64, status: &str) {
+    sqlx::query!("DELETE FROM proof_jobs WHERE job_id = $1", job_id)
+        .execute(pool)
+        .await
+        .unwrap();
+    sqlx::query!(
+        r#"
+        INSERT INTO proof_jobs (job_id, calldata_dir, layout, hasher, stone_version, memory_verification, status)
+        VALUES ($1, '/tmp/calldata', 'recursive_with_poseidon', 'keccak_160_lsb', 'stone6', 'true', $2)
+        "#,
+        job_id,
+        status
+    )
+    .execute(pool)
+    .await
+    .unwrap();
+}
+
+#[tokio::test]
+async fn test_list_proof_jobs_filtered_by_status() {
+    let app = create_test_app().await;
+    let router = create_router(app.as_ref().clone());
+
+    insert_proof_job(&app.db, 9_100_001, "completed").await;
+    insert_proof_job(&app.db, 9_100_002, "failed").await;
+    insert_proof_job(&app.db, 9_100_003, "completed").await;
+
+    let request = Request::builder()
+        .method("GET")
+        .uri("/proof-jobs?status=completed&limit=500")
+        .body(Body::empty())
+        .unwrap();
+
+    let response = router.oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let parsed: Vec<serde_json::Value> = serde_json::from_slice(&body).unwrap();
+
+    assert!(parsed.iter().all(|job| job["status"] == "completed"));
+    let job_ids: Vec<i64> = parsed
+        .iter()
+        .map(|job| job["job_id"].as_i64().unwrap())
+        .collect();
+    assert!(job_ids.contains(&9_100_001));
+    assert!(job_ids.contains(&9_100_003));
+    assert!(!job_ids.contains(&9_100_002));
+
+    sqlx::query!("DELETE FROM proof_jobs WHERE job_id BETWEEN 9100001 AND 9100003")
+        .execute(&app.db)
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn test_list_proof_jobs_rejects_invalid_status() {
+    let app = create_test_app().await;
+    let router = create_router(app.as_ref().clone());
+
+    let request = Request::builder()
+        .method("GET")
+        .uri("/proof-jobs?status=unknown")
+        .body(Body::empty())
+        .unwrap();
+
+    let response = router.oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+}
+
+async fn count_job_rows(pool: &sqlx::PgPool, job_id: i64) -> (i64, i64) {
+    let live = sqlx::query_scalar!(
+        r#"SELECT COUNT(*) AS "count!" FROM proof_jobs WHERE job_id = $1"#,
+        job_id
+    )
+    .fetch_one(pool)
+    .await
+    .unwrap();
+    let archived = sqlx::query_scalar!(
+        r#"SELECT COUNT(*) AS "count!" FROM proof_jobs_archive WHERE job_id = $1"#,
+        job_id
+    )
+    .fetch_one(pool)
+    .await
+    .unwrap();
+    (live, archived)
+}
+
+fn cleanup_request(token: Option<&str>) -> Request<Body> {
+    let mut builder = Request::builder()
+        .method("POST")
+        .uri("/admin/cleanup-proof-jobs?retention_days=30");
+    if let Some(token) = token {
+        builder = builder.header("x-admin-token", token);
+    }
+    builder.body(Body::empty()).unwrap()
+}
+
+#[tokio::test]
+async fn test_cleanup_proof_jobs_moves_old_rows_to_archive() {
+    let app = create_test_app().await;
+
+    sqlx::query!("DELETE FROM proof_jobs_archive WHERE job_id BETWEEN 9500001 AND 9500002")
+        .execute(&app.db)
+        .await
+        .unwrap();
+    insert_proof_job(&app.db, 9_500_001, "completed").await;
+    insert_proof_job(&app.db, 9_500_002, "completed").await;
+    sqlx::query!(
+        r#"
+        UPDATE proof_jobs
+        SET updated_at = NOW() - INTERVAL '40 days', stage_started_at = NOW() - INTERVAL '41 days',
+            fact_hash = '0xfac7', calldata_hash = 'c0de'
+        WHERE job_id = 9500001
+        "#
+    )
+    .execute(&app.db)
+    .await
+    .unwrap();
+
+    // Running twice must not duplicate archived rows
+    for _ in 0..2 {
+        let router = create_router(app.as_ref().clone());
+        let response = router
+            .oneshot(cleanup_request(Some("test-admin-token")))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    assert_eq!(count_job_rows(&app.db, 9_500_001).await, (0, 1));
+    assert_eq!(count_job_rows(&app.db, 9_500_002).await, (1, 0));
+    let archived = sqlx::query!(
+        "SELECT stage_started_at, fact_hash, calldata_hash FROM proof_jobs_archive WHERE job_id = 9500001"
+    )
+    .fetch_one(&app.db)
+    .await
+    .unwrap();
+    assert!(archived.stage_started_at.is_some());
+    assert_eq!(archived.fact_hash.as_deref(), Some("0xfac7"));
+    assert_eq!(archived.calldata_hash.as_deref(), Some("c0de"));
+
+    sqlx::query!("DELETE FROM proof_jobs WHERE job_id BETWEEN 9500001 AND 9500002")
+        .execute(&app.db)
+        .await
+        .unwrap();
+    sqlx::query!("DELETE FROM proof_jobs_archive WHERE job_id BETWEEN 9500001 AND 9500002")
+        .execute(&app.db)
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn test_cleanup_proof_jobs_requires_admin_token() {
+    let app = create_test_app().await;
+
+    let router = create_router(app.as_ref().clone());
+    let response = router.oneshot(cleanup_request(None)).await.unwrap();
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+    let router = create_router(app.as_ref().clone());
+    let response = router
+        .oneshot(cleanup_request(Some("wrong-token")))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+}
+
+#[tokio::test]
+async fn test_get_proof_job_estimates_remaining_time_from_history() {
+    let app = create_test_app().await;
+    let job_id: i64 = 9_800_001;
+    let calldata_dir = tempfile::tempdir().unwrap();
+    for file in ["initial", "step1", "step2", "final"] {
+        std::fs::write(calldata_dir.path().join(file), "0x1").unwrap();
+    }
+
+    // History dated in the future so it makes up the whole averaging window: 10s per call
+    sqlx::query!("DELETE FROM proof_job_stats WHERE job_id = $1", job_id)
+        .execute(&app.db)
+        .await
+        .unwrap();
+    sqlx::query!(
+        r#"
+        INSERT INTO proof_job_stats (job_id, num_steps, total_duration_seconds, average_step_duration_seconds, completed_at)
+        SELECT $1, 4, 40.0, 10.0, NOW() + INTERVAL '1 day'
+        FROM generate_series(1, 50)
+        "#,
+        job_id
+    )
+    .execute(&app.db)
+    .await
+    .unwrap();
+
+    // Initial proof done, two steps and the final call left
+    insert_proof_job(&app.db, job_id, "processing").await;
+    sqlx::query!(
+        r#"
+        UPDATE proof_jobs
+        SET calldata_dir = $2, current_stage = 'initial_submitted', stage_started_at = NOW()
+        WHERE job_id = $1
+        "#,
+        job_id,
+        calldata_dir.path().display().to_string()
+    )
+    .execute(&app.db)
+    .await
+    .unwrap();
+
+    let router = create_router(app.as_ref().clone());
+    let request = Request::builder()
+        .method("GET")
+        .uri(format!("/proof-jobs/{}", job_id))
+        .body(Body::empty())
+        .unwrap();
+    let response = router.oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let parsed: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(parsed["job_id"], job_id);
+    assert_eq!(parsed["current_stage"], "initial_submitted");
+    let estimate = parsed["estimated_remaining_seconds"].as_u64().unwrap();
+    assert!((25..=30).contains(&estimate), "estimate was {}", estimate);
+
+    sqlx::query!("DELETE FROM proof_jobs WHERE job_id = $1", job_id)
+        .execute(&app.db)
+        .await
+        .unwrap();
+    sqlx::query!("DELETE FROM proof_job_stats WHERE job_id = $1", job_id)
+        .execute(&app.db)
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn test_get_unknown_proof_job_returns_404() {
+    let app = create_test_app().await;
+    let router = create_router(app.as_ref().clone());
+
+    let request = Request::builder()
+        .method("GET")
+        .uri("/proof-jobs/9899999")
+        .body(Body::empty())
+        .unwrap();
+    let response = router.oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+}
+
+async fn get_json(uri: &str, app: &AppState) -> (StatusCode, serde_json::Value) {
+    let request = Request::builder()
+        .method("GET")
+        .uri(uri)
+        .body(Body::empty())
+        .unwrap();
+    let response = create_router(app.clone()).oneshot(request).await.unwrap();
+    let status = response.status();
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    (status, serde_json::from_slice(&body).unwrap_or_default())
+}
+
+#[tokio::test]
+async fn test_pipeline_stage_is_reported_before_and_after_the_job_exists() {
+    let app = create_test_app().await;
+    let job_id: i64 = 9_800_002;
+    sqlx::query!("DELETE FROM proof_jobs WHERE job_id = $1", job_id)
+        .execute(&app.db)
+        .await
+        .unwrap();
+    sqlx::query!(
+        r#"
+        INSERT INTO proof_pipeline_progress (job_id, stage, stage_started_at)
+        VALUES ($1, 'proof_generation', NOW())
+        ON CONFLICT (job_id) DO UPDATE SET stage = EXCLUDED.stage
+        "#,
+        job_id
+    )
+    .execute(&app.db)
+    .await
+    .unwrap();
+
+    // The pipeline is still running, so only its progress is known
+    let (status, _) = get_json(&format!("/proof-jobs/{}", job_id), &app).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    let (status, progress) = get_json(&format!("/proof-jobs/{}/pipeline", job_id), &app).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(progress["stage"], "proof_generation");
+
+    insert_proof_job(&app.db, job_id, "processing").await;
+    let (status, job) = get_json(&format!("/proof-jobs/{}", job_id), &app).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(job["pipeline"]["stage"], "proof_generation");
+
+    sqlx::query!("DELETE FROM proof_jobs WHERE job_id = $1", job_id)
+        .execute(&app.db)
+        .await
+        .unwrap();
+    sqlx::query!(
+        "DELETE FROM proof_pipeline_progress WHERE job_id = $1",
+        job_id
+    )
+    .execute(&app.db)
+    .await
+    .unwrap();
+
+    let (status, _) = get_json(&format!("/proof-jobs/{}/pipeline", job_id), &app).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn test_list_proof_jobs_filtered_by_suspended_status() {
+    let app = create_test_app().await;
+    insert_proof_job(&app.db, 9_100_004, "suspended").await;
+    insert_proof_job(&app.db, 9_100_005, "processing").await;
+
+    let (status, jobs) = get_json("/proof-jobs?status=suspended&limit=500", &app).await;
+    assert_eq!(status, StatusCode::OK);
+    let jobs = jobs.as_array().unwrap();
+    assert!(jobs.iter().all(|job| job["status"] == "suspended"));
+    assert!(jobs.iter().any(|job| job["job_id"] == 9_100_004));
+    assert!(!jobs.iter().any(|job| job["job_id"] == 9_100_005));
+
+    sqlx::query!("DELETE FROM proof_jobs WHERE job_id BETWEEN 9100004 AND 9100005")
+        .execute(&app.db)
+        .await
+        .unwrap();
+}
@@ -1,16 +1,31 @@
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+use starknet::core::types::Felt;
 use std::path::PathBuf;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
 use tempfile::tempdir;
+use tokio::sync::Mutex;
 use zeroxbridge_sequencer::config::AppConfig;
+use zeroxbridge_sequencer::db::database::get_db_pool;
+use zeroxbridge_sequencer::relayer::client::ProofSubmissionClient;
 use zeroxbridge_sequencer::relayer::proof_submission::{
-    ProofSubmissionConfig, ProofSubmissionError,
+    compute_calldata_hash, count_proof_steps, validate_calldata_path, validate_hex_felt,
+    validate_transition, with_timeout, NonceCache, ProofJob, ProofJobStage, ProofSubmissionConfig,
+    ProofSubmissionError, ProofSubmissionRelayer, ResumePoint,
 };
 
+/// Tests that drain the whole queue would otherwise pick up each other's queued jobs
+static QUEUE_LOCK: Mutex<()> = Mutex::const_new(());
+
 /// Mock configuration for testing
 fn create_test_config() -> AppConfig {
     use zeroxbridge_sequencer::config::*;
 
-    // Set environment variable for testing
+    // Set environment variables for testing
     std::env::set_var("STARKNET_RPC_URL", "http://localhost:5050");
+    std::env::set_var("ETHEREUM_RPC_URL", "http://localhost:8545");
 
     AppConfig {
         contract: ContractConfig {
@@ -19,17 +34,30 @@ fn create_test_config() -> AppConfig {
         contracts: Contracts {
             l1_contract_address: "0x0000000000000000000000000000000000000000".to_string(),
             l2_contract_address: "0x0000000000000000000000000000000000000000".to_string(),
+            l2_contract_deploy_block: 0,
+            l2_burn_event_key: DEFAULT_L2_BURN_EVENT_KEY.to_string(),
+            l2_withdrawal_event_key: DEFAULT_L2_WITHDRAWAL_EVENT_KEY.to_string(),
+            l1_tvl_contract_addresses: vec![],
         },
         server: ServerConfig {
             host: "127.0.0.1".to_string(),
             server_url: "http://127.0.0.1:4000".to_string(),
+            instance_id: None,
+            admin_token: None,
+            max_body_bytes: 1024 * 1024,
+            cors_allowed_origins: vec![],
         },
         database: DatabaseConfig {
             max_connections: 10,
+            migration_timeout_seconds: 60,
+            redis_url: None,
         },
         ethereum: EthereumConfig {
             chain_id: 1,
             confirmations: 3,
+            challenge_window_blocks: 64,
+            reorg_depth: 10,
+            polling_interval_seconds: 10,
         },
         starknet: StarknetConfig {
             chain_id: "0x534e5f4d41494e".to_string(),
@@ -38,15 +66,27 @@ fn create_test_config() -> AppConfig {
             account_address: "0x0000000000000000000000000000000000000000000000000000000000000000"
                 .to_string(),
             private_key: "0x0000000000000000000000000000000000000000000000000000000000000000"
-                .to_string(),
+                .into(),
             max_retries: Some(3),
             retry_delay_ms: Some(1000),
             transaction_timeout_ms: Some(30000),
+            receipt_poll_timeout_ms: Some(10000),
+            fallback_rpc_urls: vec![],
+            fact_registry_address: None,
+            max_fee_per_step: None,
+            max_total_fee: None,
+            polling_interval_seconds: 10,
         },
         relayer: RelayerConfig {
             max_retries: 5,
             retry_delay_seconds: 10,
             gas_limit: 500000,
+            gas_estimation_multiplier: 1.2,
+            max_gas_price_gwei: None,
+            allowed_l1_tokens: vec![],
+            dry_run: false,
+            amount_tolerance_bps: 100,
+            run_in_sequencer: false,
         },
         queue: QueueConfig {
             process_interval_sec: 5,
@@ -55,6 +95,9 @@ fn create_test_config() -> AppConfig {
             initial_retry_delay_sec: 10,
             retry_delay_seconds: 15,
             merkle_update_confirmations: 5,
+            batch_size: 10,
+            commitment_cache_ttl_seconds: 300,
+            max_concurrent_validations: 1,
         },
         merkle: MerkleConfig {
             tree_depth: 32,
@@ -65,12 +108,26 @@ fn create_test_config() -> AppConfig {
             file: "test.log".to_string(),
         },
         oracle: OracleConfig {
-            tolerance_percent: Some(0.01),
+            tolerance_bps: Some(100),
             polling_interval_seconds: 60,
+            enabled: false,
         },
         herodotus: HerodotusConfig {
             herodotus_endpoint: "https://test.example.com".to_string(),
+            atlantic_endpoint: "https://staging.atlantic.api.herodotus.cloud".to_string(),
+            poll_interval_seconds: 30,
+            program_path: "tmp/target/dev/cairo1.sierra.json".to_string(),
+            inputs_dir: "tmp/inputs".to_string(),
+            atlantic_max_retries: 3,
+            atlantic_retry_delay_ms: 10,
         },
+        proof: ProofConfig {
+            stale_job_age_minutes: Some(30),
+            calldata_base_dir: None,
+            max_concurrent_jobs: Some(4),
+            retention_days: None,
+        },
+        webhook: None,
     }
 }
 
@@ -83,10 +140,64 @@ async fn test_proof_submission_config_conversion() {
     assert_eq!(proof_config.max_retries, 3);
     assert_eq!(proof_config.retry_delay_ms, 1000);
     assert_eq!(proof_config.transaction_timeout_ms, 30000);
+    assert_eq!(proof_config.receipt_poll_timeout_ms, 10000);
     assert!(proof_config.contract_address.starts_with("0x"));
-    assert!(proof_config.account_address.starts_with("0x"));
-    assert!(proof_config.private_key.starts_with("0x"));
+    assert!(proof_config.starknet.account_address.starts_with("0x"));
+    assert!(proof_config.starknet.private_key.expose().starts_with("0x"));
     assert_eq!(proof_config.rpc_url, "http://localhost:5050");
+    assert_eq!(proof_config.ethereum_rpc_url, "http://localhost:8545");
+    assert_eq!(proof_config.challenge_window_blocks, 64);
+}
+
+#[test]
+fn test_validate_hex_felt() {
+    assert!(validate_hex_felt("0x1", "contract_address").is_ok());
+    assert!(validate_hex_felt(&format!("0x{}", "f".repeat(64)), "contract_address").is_ok());
+
+    for (input, reason) in [
+        ("1234abcd", "must start with 0x"),
+        ("0x", "no hex digits"),
+        ("0x12g4", "non-hex character at position 4"),
+        ("0X1234", "must start with 0x"),
+    ] {
+        match validate_hex_felt(input, "account_address") {
+            Err(ProofSubmissionError::InvalidField { field, reason: got }) => {
+                assert_eq!(field, "account_address");
+                assert!(got.contains(reason), "{:?}: {}", input, got);
+            }
+            other => panic!("expected InvalidField for {:?}, got {:?}", input, other),
+        }
+    }
+
+    let oversized = format!("0x{}", "1".repeat(65));
+    assert!(matches!(
+        validate_hex_felt(&oversized, "account_address"),
+        Err(ProofSubmissionError::InvalidField { reason, .. }) if reason.contains("67 characters")
+    ));
+}
+
+#[tokio::test]
+async fn test_relayer_rejects_malformed_config_fields() {
+    let pool = sqlx::PgPool::connect_lazy("postgres://localhost/unused").unwrap();
+    let secret_key = "0xnot-a-secret-key";
+
+    for field in ["contract_address", "account_address", "private_key"] {
+        let mut config = ProofSubmissionConfig::from(create_test_config());
+        match field {
+            "contract_address" => config.contract_address = "1234".to_string(),
+            "account_address" => config.starknet.account_address = format!("0x{}", "a".repeat(65)),
+            _ => config.starknet.private_key = secret_key.into(),
+        }
+
+        match ProofSubmissionRelayer::new(pool.clone(), config).await {
+            Err(e @ ProofSubmissionError::InvalidField { .. }) => {
+                assert!(e.to_string().contains(field), "{}", e);
+                assert!(!e.to_string().contains(secret_key), "{}", e);
+            }
+            Err(e) => panic!("expected InvalidField for {}, got {:?}", field, e),
+            Ok(_) => panic!("relayer accepted a malformed {}", field),
+        }
+    }
 }
 
 #[tokio::test]
@@ -146,7 +257,7 @@ fn test_hex_conversion_edge_cases() {
 
 #[test]
 fn test_stage_progression() {
-    let stages = vec![
+    let stages = [
         "processing",
         "initial_submitted",
         "step1_submitted",
@@ -156,7 +267,7 @@ fn test_stage_progression() {
     ];
 
     // Test stage parsing for step numbers
-    for (_i, stage) in stages.iter().enumerate() {
+    for stage in stages.iter() {
         if stage.starts_with("step") && stage.ends_with("_submitted") {
             let step_num: Option<u32> = stage
                 .strip_prefix("step")
@@ -206,3 +317,917 @@ fn test_retry_backoff_calculation() {
         );
     }
 }
+
+#[sqlx::test]
+async fn test_recover_stale_jobs(pool: PgPool) {
+    let app_config = create_test_config();
+    let insert_processing_job = |job_id: i64, updated_at: DateTime<Utc>| {
+        sqlx::query!(
+            r#"
+            INSERT INTO proof_jobs (job_id, calldata_dir, layout, hasher, stone_version, memory_verification, status, current_stage, updated_at)
+            VALUES ($1, '/tmp/calldata', 'recursive_with_poseidon', 'keccak_160_lsb', 'stone6', 'true', 'processing', 'step2_submitted', $2)
+            "#,
+            job_id,
+            updated_at
+        )
+        .execute(&pool)
+    };
+    let stale_job_id: i64 = 9_000_001;
+    let active_job_id: i64 = 9_000_002;
+    insert_processing_job(stale_job_id, Utc::now() - chrono::Duration::hours(2))
+        .await
+        .unwrap();
+    insert_processing_job(active_job_id, Utc::now())
+        .await
+        .unwrap();
+
+    let relayer =
+        ProofSubmissionRelayer::new(pool.clone(), ProofSubmissionConfig::from(app_config))
+            .await
+            .expect("Failed to create relayer");
+
+    let recovered = relayer.recover_stale_jobs(30).await.unwrap();
+    assert_eq!(recovered, 1);
+
+    let job = sqlx::query!(
+        "SELECT status, current_stage FROM proof_jobs WHERE job_id = $1",
+        stale_job_id
+    )
+    .fetch_one(&pool)
+    .await
+    .unwrap();
+    assert_eq!(job.status, "queued");
+    assert!(job.current_stage.is_none());
+
+    // A job still being worked on is left alone
+    let job = sqlx::query!(
+        "SELECT status, current_stage FROM proof_jobs WHERE job_id = $1",
+        active_job_id
+    )
+    .fetch_one(&pool)
+    .await
+    .unwrap();
+    assert_eq!(job.status, "processing");
+    assert_eq!(job.current_stage.as_deref(), Some("step2_submitted"));
+}
+
+#[test]
+fn test_validate_calldata_path() {
+    let base_dir = tempdir().unwrap();
+    let base = base_dir.path();
+    std::fs::create_dir(base.join("job_42")).unwrap();
+
+    // Relative paths are resolved against the base directory
+    let resolved = validate_calldata_path(base, &PathBuf::from("job_42")).unwrap();
+    assert_eq!(resolved, base.canonicalize().unwrap().join("job_42"));
+
+    // Absolute paths inside the base directory are accepted
+    let absolute = base.join("job_42");
+    assert!(validate_calldata_path(base, &absolute).is_ok());
+
+    // Traversal outside of the base directory is rejected
+    let result = validate_calldata_path(base, &PathBuf::from("../../etc"));
+    assert!(matches!(
+        result,
+        Err(ProofSubmissionError::CalldataDirNotFound(_))
+    ));
+
+    // Absolute paths outside of the base directory are rejected
+    let other_dir = tempdir().unwrap();
+    assert!(validate_calldata_path(base, other_dir.path()).is_err());
+
+    // Missing directories are rejected
+    assert!(validate_calldata_path(base, &PathBuf::from("missing")).is_err());
+}
+
+#[tokio::test]
+async fn test_process_all_queued_jobs_collects_failures() {
+    let _queue = QUEUE_LOCK.lock().await;
+    dotenv::dotenv().ok();
+    let app_config = create_test_config();
+    let pool = get_db_pool(app_config.database.get_db_url().expose())
+        .await
+        .expect("Failed to connect to test database");
+
+    let job_ids: [i64; 3] = [9_200_001, 9_200_002, 9_200_003];
+    for job_id in job_ids {
+        sqlx::query!("DELETE FROM proof_jobs WHERE job_id = $1", job_id)
+            .execute(&pool)
+            .await
+            .unwrap();
+        // Calldata directories that do not exist make every submission fail fast
+        sqlx::query!(
+            r#"
+            INSERT INTO proof_jobs (job_id, calldata_dir, layout, hasher, stone_version, memory_verification, status)
+            VALUES ($1, $2, 'recursive_with_poseidon', 'keccak_160_lsb', 'stone6', 'true', 'queued')
+            "#,
+            job_id,
+            format!("missing_calldata_{}", job_id)
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+    }
+
+    let mut proof_config = ProofSubmissionConfig::from(app_config);
+    proof_config.max_concurrent_jobs = 2;
+    let relayer = Arc::new(
+        ProofSubmissionRelayer::new(pool.clone(), proof_config)
+            .await
+            .expect("Failed to create relayer"),
+    );
+
+    let failures = relayer.process_all_queued_jobs().await.unwrap();
+
+    for job_id in job_ids {
+        let failure = failures
+            .iter()
+            .find(|(failed_id, _)| *failed_id == job_id as u64)
+            .expect("every failing job should be reported");
+        assert!(matches!(
+            failure.1,
+            ProofSubmissionError::CalldataDirNotFound(_)
+        ));
+
+        sqlx::query!("DELETE FROM proof_jobs WHERE job_id = $1", job_id)
+            .execute(&pool)
+            .await
+            .unwrap();
+    }
+}
+
+fn write_dry_run_calldata(dir: &std::path::Path) {
+    std::fs::write(dir.join("initial"), "0x123 0x456").unwrap();
+    std::fs::write(dir.join("step1"), "0xabc").unwrap();
+    std::fs::write(dir.join("final"), "0x999").unwrap();
+}
+
+#[tokio::test]
+async fn test_dry_run_does_not_touch_database() {
+    dotenv::dotenv().ok();
+    let app_config = create_test_config();
+    let pool = get_db_pool(app_config.database.get_db_url().expose())
+        .await
+        .expect("Failed to connect to test database");
+
+    let job_id: i64 = 9_300_001;
+    sqlx::query!("DELETE FROM proof_jobs WHERE job_id = $1", job_id)
+        .execute(&pool)
+        .await
+        .unwrap();
+
+    let base_dir = tempdir().unwrap();
+    write_dry_run_calldata(base_dir.path());
+
+    // Every call for this job carries its job_id (0x8de821) as the first calldata element
+    let rpc_mock = mockito::mock("POST", "/")
+        .match_body(mockito::Matcher::Regex(
+            r#"starknet_call.*"0x8de821""#.to_string(),
+        ))
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"jsonrpc":"2.0","id":1,"result":[]}"#)
+        .expect(3)
+        .create();
+
+    let mut proof_config = ProofSubmissionConfig::from(app_config);
+    proof_config.rpc_url = mockito::server_url();
+    proof_config.calldata_base_dir = base_dir.path().to_path_buf();
+    proof_config.dry_run = true;
+    let relayer = ProofSubmissionRelayer::new(pool.clone(), proof_config)
+        .await
+        .expect("Failed to create relayer");
+
+    relayer
+        .submit_proof_from_calldata(
+            base_dir.path().to_path_buf(),
+            job_id as u64,
+            "recursive_with_poseidon".to_string(),
+            "keccak_160_lsb".to_string(),
+            "stone6".to_string(),
+            "true".to_string(),
+            false,
+            Vec::new(),
+            None,
+        )
+        .await
+        .expect("Dry run should succeed");
+    rpc_mock.assert();
+
+    let rows = sqlx::query!("SELECT id FROM proof_jobs WHERE job_id = $1", job_id)
+        .fetch_all(&pool)
+        .await
+        .unwrap();
+    assert!(rows.is_empty());
+}
+
+#[tokio::test]
+async fn test_dry_run_reports_revert() {
+    dotenv::dotenv().ok();
+    let app_config = create_test_config();
+    let pool = get_db_pool(app_config.database.get_db_url().expose())
+        .await
+        .expect("Failed to connect to test database");
+
+    let job_id: u64 = 9_300_002;
+    let base_dir = tempdir().unwrap();
+    write_dry_run_calldata(base_dir.path());
+
+    let _rpc_mock = mockito::mock("POST", "/")
+        .match_body(mockito::Matcher::Regex(
+            r#"starknet_call.*"0x8de822""#.to_string(),
+        ))
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"jsonrpc":"2.0","id":1,"error":{"code":20,"message":"Contract not found"}}"#)
+        .create();
+
+    let mut proof_config = ProofSubmissionConfig::from(app_config);
+    proof_config.rpc_url = mockito::server_url();
+    proof_config.calldata_base_dir = base_dir.path().to_path_buf();
+    proof_config.dry_run = true;
+    let relayer = ProofSubmissionRelayer::new(pool, proof_config)
+        .await
+        .expect("Failed to create relayer");
+
+    let result = relayer
+        .submit_proof_from_calldata(
+            base_dir.path().to_path_buf(),
+            job_id,
+            "recursive_with_poseidon".to_string(),
+            "keccak_160_lsb".to_string(),
+            "stone6".to_string(),
+            "true".to_string(),
+            false,
+            Vec::new(),
+            None,
+        )
+        .await;
+
+    assert!(matches!(
+        result,
+        Err(ProofSubmissionError::DryRunReverted(_))
+    ));
+}
+
+/// In-memory log sink so tests can inspect formatted output
+#[derive(Clone, Default)]
+struct CapturedLogs(Arc<std::sync::Mutex<Vec<u8>>>);
+
+impl std::io::Write for CapturedLogs {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.lock().unwrap().extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+impl<'a> tracing_subscriber::fmt::MakeWriter<'a> for CapturedLogs {
+    type Writer = Self;
+
+    fn make_writer(&'a self) -> Self::Writer {
+        self.clone()
+    }
+}
+
+#[tokio::test]
+async fn test_proof_submission_logs_carry_job_id() {
+    dotenv::dotenv().ok();
+    let app_config = create_test_config();
+    let pool = get_db_pool(app_config.database.get_db_url().expose())
+        .await
+        .expect("Failed to connect to test database");
+
+    let job_id: u64 = 9_300_003;
+    let base_dir = tempdir().unwrap();
+    write_dry_run_calldata(base_dir.path());
+
+    let _rpc_mock = mockito::mock("POST", "/")
+        .match_body(mockito::Matcher::Regex(
+            r#"starknet_call.*"0x8de823""#.to_string(),
+        ))
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"jsonrpc":"2.0","id":1,"result":[]}"#)
+        .create();
+
+    let mut proof_config = ProofSubmissionConfig::from(app_config);
+    proof_config.rpc_url = mockito::server_url();
+    proof_config.calldata_base_dir = base_dir.path().to_path_buf();
+    proof_config.dry_run = true;
+    let relayer = ProofSubmissionRelayer::new(pool, proof_config)
+        .await
+        .expect("Failed to create relayer");
+
+    let logs = CapturedLogs::default();
+    let subscriber = tracing_subscriber::fmt()
+        .json()
+        .with_current_span(true)
+        .with_span_list(true)
+        .with_env_filter("zeroxbridge_sequencer=debug")
+        .with_writer(logs.clone())
+        .finish();
+    let guard = tracing::subscriber::set_default(subscriber);
+
+    relayer
+        .submit_proof_from_calldata(
+            base_dir.path().to_path_buf(),
+            job_id,
+            "recursive_with_poseidon".to_string(),
+            "keccak_160_lsb".to_string(),
+            "stone6".to_string(),
+            "true".to_string(),
+            false,
+            Vec::new(),
+            None,
+        )
+        .await
+        .expect("Dry run should succeed");
+    drop(guard);
+
+    let output = String::from_utf8(logs.0.lock().unwrap().clone()).unwrap();
+    let lines: Vec<serde_json::Value> = output
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+
+    assert!(!lines.is_empty());
+    for line in &lines {
+        assert_eq!(line["spans"][0]["name"], "proof_submission");
+        assert_eq!(line["spans"][0]["job_id"], "9300003");
+    }
+}
+
+#[test]
+fn test_resume_point_from_stage() {
+    assert_eq!(ResumePoint::from_stage(None), ResumePoint::Initial);
+    assert_eq!(
+        ResumePoint::from_stage(Some("processing")),
+        ResumePoint::Initial
+    );
+    assert_eq!(
+        ResumePoint::from_stage(Some("initial_submitted")),
+        ResumePoint::Step(1)
+    );
+    assert_eq!(
+        ResumePoint::from_stage(Some("step3_submitted")),
+        ResumePoint::Step(4)
+    );
+    assert_eq!(
+        ResumePoint::from_stage(Some("final_submitted")),
+        ResumePoint::MarkCompleted
+    );
+    assert_eq!(
+        ResumePoint::from_stage(Some("completed")),
+        ResumePoint::Completed
+    );
+    assert_eq!(
+        ResumePoint::from_stage(Some("failed")),
+        ResumePoint::RetryFailed
+    );
+    assert_eq!(
+        ResumePoint::from_stage(Some("something_else")),
+        ResumePoint::Initial
+    );
+}
+
+#[tokio::test]
+async fn test_existing_job_requires_resume() {
+    dotenv::dotenv().ok();
+    let app_config = create_test_config();
+    let pool = get_db_pool(app_config.database.get_db_url().expose())
+        .await
+        .expect("Failed to connect to test database");
+
+    let job_id: i64 = 9_400_001;
+    let base_dir = tempdir().unwrap();
+    write_dry_run_calldata(base_dir.path());
+
+    sqlx::query!("DELETE FROM proof_jobs WHERE job_id = $1", job_id)
+        .execute(&pool)
+        .await
+        .unwrap();
+    sqlx::query!(
+        r#"
+        INSERT INTO proof_jobs (job_id, calldata_dir, layout, hasher, stone_version, memory_verification, status, current_stage)
+        VALUES ($1, $2, 'recursive_with_poseidon', 'keccak_160_lsb', 'stone6', 'true', 'completed', 'completed')
+        "#,
+        job_id,
+        base_dir.path().display().to_string()
+    )
+    .execute(&pool)
+    .await
+    .unwrap();
+
+    let mut proof_config = ProofSubmissionConfig::from(app_config);
+    proof_config.calldata_base_dir = base_dir.path().to_path_buf();
+    let relayer = ProofSubmissionRelayer::new(pool.clone(), proof_config)
+        .await
+        .expect("Failed to create relayer");
+
+    let without_resume = relayer
+        .submit_proof_from_calldata(
+            base_dir.path().to_path_buf(),
+            job_id as u64,
+            "recursive_with_poseidon".to_string(),
+            "keccak_160_lsb".to_string(),
+            "stone6".to_string(),
+            "true".to_string(),
+            false,
+            Vec::new(),
+            None,
+        )
+        .await;
+    assert!(matches!(
+        without_resume,
+        Err(ProofSubmissionError::ProofJobAlreadyExists {
+            job_id: 9_400_001,
+            ..
+        })
+    ));
+
+    // A completed job has nothing left to submit, so resuming is a no-op
+    relayer
+        .submit_proof_from_calldata(
+            base_dir.path().to_path_buf(),
+            job_id as u64,
+            "recursive_with_poseidon".to_string(),
+            "keccak_160_lsb".to_string(),
+            "stone6".to_string(),
+            "true".to_string(),
+            true,
+            Vec::new(),
+            None,
+        )
+        .await
+        .expect("Resuming a completed job should succeed");
+
+    sqlx::query!("DELETE FROM proof_jobs WHERE job_id = $1", job_id)
+        .execute(&pool)
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn test_watch_marks_failing_jobs_failed_and_stops_on_shutdown() {
+    let _queue = QUEUE_LOCK.lock().await;
+    dotenv::dotenv().ok();
+    let app_config = create_test_config();
+    let pool = get_db_pool(app_config.database.get_db_url().expose())
+        .await
+        .expect("Failed to connect to test database");
+
+    let job_id: i64 = 9_700_001;
+    sqlx::query!("DELETE FROM proof_jobs WHERE job_id = $1", job_id)
+        .execute(&pool)
+        .await
+        .unwrap();
+    sqlx::query!(
+        r#"
+        INSERT INTO proof_jobs (job_id, calldata_dir, layout, hasher, stone_version, memory_verification, status)
+        VALUES ($1, 'missing_calldata_watch', 'recursive_with_poseidon', 'keccak_160_lsb', 'stone6', 'true', 'queued')
+        "#,
+        job_id
+    )
+    .execute(&pool)
+    .await
+    .unwrap();
+
+    let client =
+        ProofSubmissionClient::from_config(pool.clone(), ProofSubmissionConfig::from(app_config))
+            .await
+            .expect("Failed to create client");
+
+    // The watcher must return once the shutdown future resolves
+    tokio::time::timeout(
+        Duration::from_secs(5),
+        client.watch_queued_jobs(
+            Duration::from_millis(50),
+            tokio::time::sleep(Duration::from_millis(200)),
+        ),
+    )
+    .await
+    .expect("watcher did not stop on shutdown");
+
+    let job = sqlx::query!(
+        "SELECT status, error_message FROM proof_jobs WHERE job_id = $1",
+        job_id
+    )
+    .fetch_one(&pool)
+    .await
+    .unwrap();
+    assert_eq!(job.status, "failed");
+    assert!(job.error_message.is_some());
+
+    sqlx::query!("DELETE FROM proof_jobs WHERE job_id = $1", job_id)
+        .execute(&pool)
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn test_nonce_cache_increments_across_sequential_submissions() {
+    let cache = NonceCache::new(Duration::from_secs(5));
+    let fetches = AtomicUsize::new(0);
+
+    // initial, two steps and final, as in a full proof submission
+    let mut nonces = Vec::new();
+    for _ in 0..4 {
+        let nonce = cache
+            .reserve(|| async {
+                fetches.fetch_add(1, Ordering::SeqCst);
+                Ok::<_, ProofSubmissionError>(Felt::from(7u64))
+            })
+            .await
+            .unwrap()
+            .nonce();
+        nonces.push(nonce);
+    }
+
+    assert_eq!(
+        nonces,
+        vec![
+            Felt::from(7u64),
+            Felt::from(8u64),
+            Felt::from(9u64),
+            Felt::from(10u64)
+        ]
+    );
+    assert_eq!(fetches.load(Ordering::SeqCst), 1);
+}
+
+#[tokio::test]
+async fn test_nonce_cache_refetches_when_stale_or_invalidated() {
+    let cache = NonceCache::new(Duration::from_millis(20));
+    let fetch = |value: u64| async move { Ok::<_, ProofSubmissionError>(Felt::from(value)) };
+
+    let reserve = |value: u64| {
+        let cache = cache.clone();
+        async move { cache.reserve(|| fetch(value)).await.unwrap().nonce() }
+    };
+
+    assert_eq!(reserve(3).await, Felt::from(3u64));
+    tokio::time::sleep(Duration::from_millis(40)).await;
+    // An expired entry is replaced by whatever the node reports
+    assert_eq!(reserve(10).await, Felt::from(10u64));
+    assert_eq!(reserve(99).await, Felt::from(11u64));
+
+    cache.invalidate().await;
+    assert_eq!(reserve(12).await, Felt::from(12u64));
+
+    // A failed send gives its nonce up, so the next reservation asks the node again
+    cache.reserve(|| fetch(99)).await.unwrap().invalidate();
+    assert_eq!(reserve(13).await, Felt::from(13u64));
+}
+
+#[tokio::test]
+async fn test_nonce_reservations_are_held_one_at_a_time() {
+    let cache = NonceCache::new(Duration::from_secs(5));
+    let fetch = || async { Ok::<_, ProofSubmissionError>(Felt::from(1u64)) };
+
+    let first = cache.reserve(fetch).await.unwrap();
+    // The next send cannot take a nonce while the first is still being sent
+    let waiting = tokio::time::timeout(Duration::from_millis(50), cache.reserve(fetch)).await;
+    assert!(waiting.is_err());
+
+    drop(first);
+    let second = cache.reserve(fetch).await.unwrap();
+    assert_eq!(second.nonce(), Felt::from(2u64));
+}
+
+#[tokio::test]
+async fn test_with_timeout_gives_up_on_hung_call() {
+    tokio::time::pause();
+    let call = tokio::spawn(with_timeout(
+        Duration::from_millis(30_000),
+        std::future::pending::<()>(),
+    ));
+    tokio::task::yield_now().await;
+
+    tokio::time::advance(Duration::from_millis(29_999)).await;
+    assert!(!call.is_finished());
+
+    tokio::time::advance(Duration::from_millis(2)).await;
+    assert!(matches!(
+        call.await.unwrap(),
+        Err(ProofSubmissionError::TransactionTimeout)
+    ));
+}
+
+#[tokio::test]
+async fn test_with_timeout_returns_output_of_timely_call() {
+    tokio::time::pause();
+    let call = tokio::spawn(with_timeout(Duration::from_millis(30_000), async {
+        tokio::time::sleep(Duration::from_millis(10_000)).await;
+        Ok::<_, ProofSubmissionError>(Felt::from(5u64))
+    }));
+    tokio::task::yield_now().await;
+
+    tokio::time::advance(Duration::from_millis(10_000)).await;
+    assert_eq!(call.await.unwrap().unwrap().unwrap(), Felt::from(5u64));
+}
+
+#[test]
+fn test_proof_job_stage_parsing() {
+    assert_eq!(
+        ProofJobStage::parse("processing"),
+        Some(ProofJobStage::Processing)
+    );
+    assert_eq!(
+        ProofJobStage::parse("step12_submitted"),
+        Some(ProofJobStage::StepSubmitted)
+    );
+    assert_eq!(
+        ProofJobStage::parse("completed"),
+        Some(ProofJobStage::Completed)
+    );
+    assert_eq!(ProofJobStage::parse("stepx_submitted"), None);
+    assert_eq!(ProofJobStage::parse("unknown"), None);
+}
+
+#[test]
+fn test_allowed_stage_transitions() {
+    let happy_path = [
+        (None, "initial_submitted"),
+        (Some("processing"), "initial_submitted"),
+        (Some("initial_submitted"), "step1_submitted"),
+        (Some("step1_submitted"), "step2_submitted"),
+        (Some("step2_submitted"), "final_submitted"),
+        (Some("initial_submitted"), "final_submitted"),
+        (Some("final_submitted"), "completed"),
+        (Some("step1_submitted"), "failed"),
+        (Some("failed"), "processing"),
+    ];
+
+    for (from, to) in happy_path {
+        assert!(
+            validate_transition(from, to).is_ok(),
+            "{:?} -> {} should be allowed",
+            from,
+            to
+        );
+    }
+}
+
+#[test]
+fn test_disallowed_stage_transitions_are_rejected() {
+    let err = validate_transition(Some("completed"), "processing").unwrap_err();
+    assert!(matches!(
+        err,
+        ProofSubmissionError::InvalidStageTransition { ref from, ref to }
+            if from == "completed" && to == "processing"
+    ));
+
+    for (from, to) in [
+        (Some("completed"), "failed"),
+        (Some("processing"), "completed"),
+        (Some("step1_submitted"), "initial_submitted"),
+        (Some("final_submitted"), "step3_submitted"),
+        (Some("processing"), "processing"),
+        (Some("garbage"), "initial_submitted"),
+        (Some("processing"), "garbage"),
+    ] {
+        assert!(
+            matches!(
+                validate_transition(from, to),
+                Err(ProofSubmissionError::InvalidStageTransition { .. })
+            ),
+            "{:?} -> {} should be rejected",
+            from,
+            to
+        );
+    }
+}
+
+fn running_job(
+    status: &str,
+    stage: Option<&str>,
+    stage_age_seconds: i64,
+) -> (ProofJob, DateTime<Utc>) {
+    let now = Utc::now();
+    let job = ProofJob {
+        id: 1,
+        job_id: 1,
+        calldata_dir: String::new(),
+        layout: String::new(),
+        hasher: String::new(),
+        stone_version: String::new(),
+        memory_verification: String::new(),
+        status: status.to_string(),
+        current_stage: stage.map(str::to_string),
+        retry_count: 0,
+        error_message: None,
+        tx_hashes: serde_json::json!({}),
+        stage_started_at: Some(now - chrono::Duration::seconds(stage_age_seconds)),
+        fact_hash: None,
+        calldata_hash: None,
+        metadata: serde_json::json!({}),
+    };
+    (job, now)
+}
+
+#[test]
+fn test_estimate_completion_time() {
+    // 5 calls: initial, three steps, final; 20s each
+    let estimate = |status, stage, age| {
+        let (job, now) = running_job(status, stage, age);
+        job.estimate_completion_time(5, 20.0, now)
+    };
+
+    assert_eq!(estimate("queued", None, 0), Some(100));
+    assert_eq!(estimate("processing", Some("processing"), 5), Some(95));
+    assert_eq!(estimate("processing", Some("step2_submitted"), 0), Some(40));
+    // A call running longer than average never counts for more than one step
+    assert_eq!(
+        estimate("processing", Some("step2_submitted"), 600),
+        Some(20)
+    );
+    assert_eq!(estimate("processing", Some("final_submitted"), 0), Some(0));
+    assert_eq!(estimate("completed", Some("completed"), 0), Some(0));
+    assert_eq!(estimate("failed", Some("step1_submitted"), 0), None);
+}
+
+#[test]
+fn test_count_proof_steps() {
+    let dir = tempdir().unwrap();
+    for file in ["initial", "step1", "step2", "step4", "final"] {
+        std::fs::write(dir.path().join(file), "0x1").unwrap();
+    }
+
+    // step4 is never reached because submission stops at the first missing step file
+    assert_eq!(count_proof_steps(dir.path()), 4);
+}
+
+#[test]
+fn test_calldata_hash_covers_every_file_in_order() {
+    use sha2::{Digest, Sha256};
+
+    let dir = tempdir().unwrap();
+    write_dry_run_calldata(dir.path());
+    std::fs::write(dir.path().join("step2"), "0xdef").unwrap();
+    // Not consecutive with step2, so never submitted and not hashed
+    std::fs::write(dir.path().join("step4"), "0x444").unwrap();
+
+    let hash = compute_calldata_hash(dir.path()).unwrap();
+    assert_eq!(
+        hash,
+        hex::encode(Sha256::digest("0x123 0x4560xabc0xdef0x999"))
+    );
+
+    std::fs::write(dir.path().join("step2"), "0xdee").unwrap();
+    assert_ne!(compute_calldata_hash(dir.path()).unwrap(), hash);
+
+    std::fs::remove_file(dir.path().join("final")).unwrap();
+    assert!(matches!(
+        compute_calldata_hash(dir.path()),
+        Err(ProofSubmissionError::CalldataFileMissing(file)) if file == "final"
+    ));
+}
+
+#[tokio::test]
+async fn test_resume_pins_calldata_of_jobs_without_a_hash() {
+    dotenv::dotenv().ok();
+    let app_config = create_test_config();
+    let pool = get_db_pool(app_config.database.get_db_url().expose())
+        .await
+        .expect("Failed to connect to test database");
+
+    let job_id: i64 = 9_400_003;
+    let base_dir = tempdir().unwrap();
+    write_dry_run_calldata(base_dir.path());
+
+    sqlx::query!("DELETE FROM proof_jobs WHERE job_id = $1", job_id)
+        .execute(&pool)
+        .await
+        .unwrap();
+    sqlx::query!(
+        r#"
+        INSERT INTO proof_jobs (job_id, calldata_dir, layout, hasher, stone_version, memory_verification, status, current_stage)
+        VALUES ($1, $2, 'recursive_with_poseidon', 'keccak_160_lsb', 'stone6', 'true', 'completed', 'completed')
+        "#,
+        job_id,
+        base_dir.path().display().to_string()
+    )
+    .execute(&pool)
+    .await
+    .unwrap();
+
+    let mut proof_config = ProofSubmissionConfig::from(app_config);
+    proof_config.calldata_base_dir = base_dir.path().to_path_buf();
+    let relayer = ProofSubmissionRelayer::new(pool.clone(), proof_config)
+        .await
+        .expect("Failed to create relayer");
+    let resume = || {
+        relayer.submit_proof_from_calldata(
+            base_dir.path().to_path_buf(),
+            job_id as u64,
+            "recursive_with_poseidon".to_string(),
+            "keccak_160_lsb".to_string(),
+            "stone6".to_string(),
+            "true".to_string(),
+            true,
+            Vec::new(),
+            None,
+        )
+    };
+
+    resume().await.expect("A job without a hash should resume");
+    let stored_hash = sqlx::query_scalar!(
+        "SELECT calldata_hash FROM proof_jobs WHERE job_id = $1",
+        job_id
+    )
+    .fetch_one(&pool)
+    .await
+    .unwrap();
+    assert_eq!(
+        stored_hash,
+        Some(compute_calldata_hash(base_dir.path()).unwrap())
+    );
+
+    std::fs::write(base_dir.path().join("step1"), "0xabd").unwrap();
+    assert!(matches!(
+        resume().await,
+        Err(ProofSubmissionError::CalldataModified { .. })
+    ));
+
+    sqlx::query!("DELETE FROM proof_jobs WHERE job_id = $1", job_id)
+        .execute(&pool)
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn test_resume_rejects_modified_calldata() {
+    dotenv::dotenv().ok();
+    let app_config = create_test_config();
+    let pool = get_db_pool(app_config.database.get_db_url().expose())
+        .await
+        .expect("Failed to connect to test database");
+
+    let job_id: i64 = 9_400_002;
+    let base_dir = tempdir().unwrap();
+    write_dry_run_calldata(base_dir.path());
+    let stored_hash = compute_calldata_hash(base_dir.path()).unwrap();
+
+    sqlx::query!("DELETE FROM proof_jobs WHERE job_id = $1", job_id)
+        .execute(&pool)
+        .await
+        .unwrap();
+    sqlx::query!(
+        r#"
+        INSERT INTO proof_jobs (job_id, calldata_dir, layout, hasher, stone_version, memory_verification, status, current_stage, calldata_hash)
+        VALUES ($1, $2, 'recursive_with_poseidon', 'keccak_160_lsb', 'stone6', 'true', 'completed', 'completed', $3)
+        "#,
+        job_id,
+        base_dir.path().display().to_string(),
+        stored_hash
+    )
+    .execute(&pool)
+    .await
+    .unwrap();
+
+    let mut proof_config = ProofSubmissionConfig::from(app_config);
+    proof_config.calldata_base_dir = base_dir.path().to_path_buf();
+    let relayer = ProofSubmissionRelayer::new(pool.clone(), proof_config)
+        .await
+        .expect("Failed to create relayer");
+    let resume = || {
+        relayer.submit_proof_from_calldata(
+            base_dir.path().to_path_buf(),
+            job_id as u64,
+            "recursive_with_poseidon".to_string(),
+            "keccak_160_lsb".to_string(),
+            "stone6".to_string(),
+            "true".to_string(),
+            true,
+            Vec::new(),
+            None,
+        )
+    };
+
+    resume().await.expect("Unchanged calldata should resume");
+
+    std::fs::write(base_dir.path().join("step1"), "0xabd").unwrap();
+    let result = resume().await;
+    match result {
+        Err(ProofSubmissionError::CalldataModified {
+            stored_hash: stored,
+            current_hash,
+        }) => {
+            assert_eq!(stored, stored_hash);
+            assert_eq!(
+                current_hash,
+                compute_calldata_hash(base_dir.path()).unwrap()
+            );
+        }
+        other => panic!("Expected CalldataModified, got {:?}", other),
+    }
+
+    sqlx::query!("DELETE FROM proof_jobs WHERE job_id = $1", job_id)
+        .execute(&pool)
+        .await
+        .unwrap();
+}
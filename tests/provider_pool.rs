@@ -0,0 +1,47 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use zeroxbridge_sequencer::relayer::provider_pool::ProviderPool;
+
+#[tokio::test]
+async fn test_provider_pool_bounds_concurrent_requests() {
+    let max_connections = 3;
+    let pool = Arc::new(ProviderPool::new("http://localhost:5050", max_connections).unwrap());
+    let in_flight = Arc::new(AtomicUsize::new(0));
+    let peak = Arc::new(AtomicUsize::new(0));
+
+    let handles: Vec<_> = (0..max_connections * 4)
+        .map(|_| {
+            let pool = pool.clone();
+            let in_flight = in_flight.clone();
+            let peak = peak.clone();
+            tokio::spawn(async move {
+                let _provider = pool.acquire().await;
+                let current = in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+                peak.fetch_max(current, Ordering::SeqCst);
+                tokio::time::sleep(Duration::from_millis(20)).await;
+                in_flight.fetch_sub(1, Ordering::SeqCst);
+            })
+        })
+        .collect();
+
+    for handle in handles {
+        handle.await.unwrap();
+    }
+
+    assert!(peak.load(Ordering::SeqCst) <= max_connections);
+    assert_eq!(peak.load(Ordering::SeqCst), max_connections);
+    assert_eq!(pool.available_connections(), max_connections);
+}
+
+#[tokio::test]
+async fn test_provider_pool_shares_one_client() {
+    let pool = ProviderPool::new("http://localhost:5050", 2).unwrap();
+
+    assert!(Arc::ptr_eq(&pool.provider(), &pool.provider()));
+}
+
+#[test]
+fn test_provider_pool_rejects_invalid_url() {
+    assert!(ProviderPool::new("not a url", 2).is_err());
+}
@@ -0,0 +1,91 @@
+#[path = "utils.rs"]
+mod utils;
+
+use sqlx::PgConnection;
+use utils::{create_test_app, TestTransaction};
+
+/// Query plan for `query`, with sequential scans disabled so the planner picks an index whenever
+/// one can serve the query, regardless of how few rows the test database holds.
+async fn query_plan(conn: &mut PgConnection, query: &str) -> String {
+    sqlx::query("SET LOCAL enable_seqscan = off")
+        .execute(&mut *conn)
+        .await
+        .unwrap();
+
+    // EXPLAIN output has no fixed shape, so it can't go through the checked query macros
+    let lines: Vec<String> = sqlx::query_scalar(&format!("EXPLAIN {}", query))
+        .fetch_all(&mut *conn)
+        .await
+        .unwrap();
+    lines.join("\n")
+}
+
+#[tokio::test]
+async fn test_pending_deposit_lookup_uses_status_retry_index() {
+    let app = create_test_app().await;
+    let mut tx = TestTransaction::begin(&app.db).await;
+
+    let plan = query_plan(
+        &mut tx,
+        "SELECT * FROM deposits WHERE status = 'pending' AND retry_count < 5 ORDER BY created_at ASC LIMIT 10",
+    )
+    .await;
+
+    assert!(plan.contains("deposits_status_retry"), "{}", plan);
+}
+
+#[tokio::test]
+async fn test_pending_withdrawal_lookup_uses_status_retry_index() {
+    let app = create_test_app().await;
+    let mut tx = TestTransaction::begin(&app.db).await;
+
+    let plan = query_plan(
+        &mut tx,
+        "SELECT * FROM withdrawals WHERE status = 'pending' AND retry_count < 5 ORDER BY created_at ASC LIMIT 10",
+    )
+    .await;
+
+    assert!(plan.contains("withdrawals_status_retry"), "{}", plan);
+}
+
+#[tokio::test]
+async fn test_pending_l2_transaction_lookup_uses_status_priority_created_index() {
+    let app = create_test_app().await;
+    let mut tx = TestTransaction::begin(&app.db).await;
+
+    let plan = query_plan(
+        &mut tx,
+        "SELECT * FROM l2_transactions WHERE status = 'pending' ORDER BY priority DESC, created_at ASC LIMIT 10",
+    )
+    .await;
+
+    assert!(
+        plan.contains("l2_transactions_status_priority_created"),
+        "{}",
+        plan
+    );
+}
+
+#[tokio::test]
+async fn test_stale_proof_job_recovery_uses_status_updated_index() {
+    let app = create_test_app().await;
+    let mut tx = TestTransaction::begin(&app.db).await;
+
+    let plan = query_plan(
+        &mut tx,
+        "SELECT id FROM proof_jobs WHERE status = 'processing' AND updated_at < NOW() - 30::BIGINT * INTERVAL '1 minute'",
+    )
+    .await;
+
+    assert!(plan.contains("proof_jobs_status_updated"), "{}", plan);
+}
+
+#[tokio::test]
+async fn test_proof_job_status_filter_uses_status_updated_index() {
+    let app = create_test_app().await;
+    let mut tx = TestTransaction::begin(&app.db).await;
+
+    let plan = query_plan(&mut tx, "SELECT id FROM proof_jobs WHERE status = 'queued'").await;
+
+    assert!(plan.contains("proof_jobs_status_updated"), "{}", plan);
+}
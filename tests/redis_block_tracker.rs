@@ -0,0 +1,87 @@
+use testcontainers::runners::AsyncRunner;
+use testcontainers::ContainerAsync;
+use testcontainers_modules::redis::{Redis, REDIS_PORT};
+use zeroxbridge_sequencer::db::database::{BlockTracker, BlockTrackerKey};
+use zeroxbridge_sequencer::db::redis_block_tracker::RedisBlockTracker;
+
+async fn start_redis() -> (ContainerAsync<Redis>, String) {
+    let container = Redis::default()
+        .start()
+        .await
+        .expect("Failed to start Redis container");
+    let host = container.get_host().await.unwrap();
+    let port = container.get_host_port_ipv4(REDIS_PORT).await.unwrap();
+    (container, format!("redis://{}:{}", host, port))
+}
+
+#[tokio::test]
+async fn test_unset_tracker_reads_none() {
+    let (_container, redis_url) = start_redis().await;
+    let tracker = RedisBlockTracker::new(&redis_url).unwrap();
+
+    assert_eq!(
+        tracker.get(BlockTrackerKey::L2BurnEvents).await.unwrap(),
+        None
+    );
+}
+
+#[tokio::test]
+async fn test_tracker_never_moves_back() {
+    let (_container, redis_url) = start_redis().await;
+    let tracker = BlockTracker::Redis(RedisBlockTracker::new(&redis_url).unwrap());
+    let key = BlockTrackerKey::L2WithdrawalEvents;
+
+    tracker.set(key, 120).await.unwrap();
+    tracker.set(key, 100).await.unwrap();
+
+    assert_eq!(tracker.get(key).await.unwrap(), Some(120));
+}
+
+#[tokio::test]
+async fn test_rewind_moves_tracker_back() {
+    let (_container, redis_url) = start_redis().await;
+    let tracker = BlockTracker::Redis(RedisBlockTracker::new(&redis_url).unwrap());
+    let key = BlockTrackerKey::L2WithdrawalEvents;
+
+    tracker.set(key, 120).await.unwrap();
+    tracker.rewind(key, 100).await.unwrap();
+
+    assert_eq!(tracker.get(key).await.unwrap(), Some(100));
+}
+
+#[tokio::test]
+async fn test_concurrent_instances_advance_monotonically() {
+    let (_container, redis_url) = start_redis().await;
+    let key = BlockTrackerKey::L2BurnEvents;
+
+    // Two sequencer instances sharing the server, one recording even blocks and one odd ones
+    let instances = (0..2u64).map(|offset| {
+        let tracker = RedisBlockTracker::new(&redis_url).unwrap();
+        tokio::spawn(async move {
+            let mut last_seen = 0;
+            for block in (offset..=200).step_by(2) {
+                tracker.set(key, block).await.unwrap();
+                let current = tracker.get(key).await.unwrap().unwrap();
+                assert!(
+                    current >= block,
+                    "tracker at {} after setting {}",
+                    current,
+                    block
+                );
+                assert!(
+                    current >= last_seen,
+                    "tracker moved back from {} to {}",
+                    last_seen,
+                    current
+                );
+                last_seen = current;
+            }
+        })
+    });
+    for instance in instances.collect::<Vec<_>>() {
+        instance.await.unwrap();
+    }
+
+    let tracker = RedisBlockTracker::new(&redis_url).unwrap();
+    assert_eq!(tracker.get(key).await.unwrap(), Some(200));
+}
@@ -0,0 +1,114 @@
+#[path = "utils.rs"]
+mod utils;
+
+use axum::{
+    body::{to_bytes, Body},
+    http::Request,
+    routing::get,
+    Extension, Router,
+};
+use std::fmt;
+use std::sync::{Arc, Mutex};
+use tower::ServiceExt;
+use tracing::field::{Field, Visit};
+use tracing::span::{Attributes, Id};
+use tracing::Subscriber;
+use tracing_subscriber::layer::{Context, SubscriberExt};
+use utils::create_test_app;
+use uuid::Uuid;
+use zeroxbridge_sequencer::api::request_id::{RequestId, RequestIdLayer, X_REQUEST_ID};
+use zeroxbridge_sequencer::api::routes::create_router;
+
+/// Records the `request_id` field of every span opened while it is the default subscriber
+#[derive(Clone, Default)]
+struct SpanRequestIds(Arc<Mutex<Vec<String>>>);
+
+struct RequestIdVisitor(Option<String>);
+
+impl Visit for RequestIdVisitor {
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        if field.name() == "request_id" {
+            self.0 = Some(format!("{:?}", value));
+        }
+    }
+}
+
+impl<S: Subscriber> tracing_subscriber::Layer<S> for SpanRequestIds {
+    fn on_new_span(&self, attrs: &Attributes<'_>, _: &Id, _: Context<'_, S>) {
+        let mut visitor = RequestIdVisitor(None);
+        attrs.record(&mut visitor);
+        if let Some(request_id) = visitor.0 {
+            self.0.lock().unwrap().push(request_id);
+        }
+    }
+}
+
+/// Router answering with the `RequestId` its handler was given
+fn echo_router() -> Router {
+    Router::new()
+        .route(
+            "/echo",
+            get(|Extension(request_id): Extension<RequestId>| async move { request_id.0 }),
+        )
+        .layer(RequestIdLayer)
+}
+
+#[tokio::test]
+async fn test_every_response_carries_a_generated_request_id() {
+    let app = create_test_app().await;
+
+    let request = Request::builder()
+        .uri("/health")
+        .body(Body::empty())
+        .unwrap();
+    let response = create_router(app.as_ref().clone())
+        .oneshot(request)
+        .await
+        .unwrap();
+
+    let request_id = response.headers().get(X_REQUEST_ID).unwrap();
+    assert!(Uuid::parse_str(request_id.to_str().unwrap()).is_ok());
+}
+
+#[tokio::test]
+async fn test_handler_and_header_share_the_request_id() {
+    let response = echo_router()
+        .oneshot(Request::builder().uri("/echo").body(Body::empty()).unwrap())
+        .await
+        .unwrap();
+
+    let header = response.headers().get(X_REQUEST_ID).unwrap().clone();
+    let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    assert_eq!(body, header.as_bytes());
+}
+
+#[tokio::test]
+async fn test_incoming_request_id_is_reused() {
+    let request = Request::builder()
+        .uri("/echo")
+        .header(X_REQUEST_ID, "upstream-trace-42")
+        .body(Body::empty())
+        .unwrap();
+
+    let response = echo_router().oneshot(request).await.unwrap();
+
+    assert_eq!(response.headers()[X_REQUEST_ID], "upstream-trace-42");
+    let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    assert_eq!(body, "upstream-trace-42");
+}
+
+#[tokio::test]
+async fn test_request_id_is_recorded_on_the_span() {
+    let spans = SpanRequestIds::default();
+    let _guard =
+        tracing::subscriber::set_default(tracing_subscriber::registry().with(spans.clone()));
+
+    let request = Request::builder()
+        .uri("/echo")
+        .header(X_REQUEST_ID, "span-check-7")
+        .body(Body::empty())
+        .unwrap();
+    echo_router().oneshot(request).await.unwrap();
+
+    assert_eq!(*spans.0.lock().unwrap(), vec!["span-check-7".to_string()]);
+}
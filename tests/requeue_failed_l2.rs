@@ -0,0 +1,115 @@
+#[path = "utils.rs"]
+mod utils;
+
+use axum::{
+    body::{to_bytes, Body},
+    http::{Request, StatusCode},
+};
+use serde_json::{json, Value};
+use tower::ServiceExt;
+use utils::create_test_app;
+use zeroxbridge_sequencer::api::routes::{create_router, AppState};
+
+const ADMIN_TOKEN: &str = "test-admin-token";
+
+/// Inserts a failed L2 transaction last updated `hours_ago` hours ago
+async fn insert_failed_transaction(pool: &sqlx::PgPool, id: i64, hours_ago: i32) {
+    sqlx::query!("DELETE FROM l2_transactions WHERE id = $1", id)
+        .execute(pool)
+        .await
+        .unwrap();
+    sqlx::query!(
+        r#"
+        INSERT INTO l2_transactions (id, stark_pub_key, amount, token_address, status, retry_count, error, updated_at)
+        VALUES ($1, '0xrequeue', 500, '0xtoken123', 'failed', 5, 'Max retries exceeded',
+            NOW() - make_interval(hours => $2))
+        "#,
+        id,
+        hours_ago
+    )
+    .execute(pool)
+    .await
+    .unwrap();
+}
+
+async fn fetch_state(pool: &sqlx::PgPool, id: i64) -> (String, i32, Option<String>) {
+    let row = sqlx::query!(
+        "SELECT status, retry_count, error FROM l2_transactions WHERE id = $1",
+        id
+    )
+    .fetch_one(pool)
+    .await
+    .unwrap();
+    (row.status, row.retry_count, row.error)
+}
+
+async fn requeue(app: &AppState, body: Value, token: Option<&str>) -> (StatusCode, Option<Value>) {
+    let mut builder = Request::builder()
+        .method("POST")
+        .uri("/admin/relayer/requeue-failed")
+        .header("content-type", "application/json");
+    if let Some(token) = token {
+        builder = builder.header("x-admin-token", token);
+    }
+    let request = builder.body(Body::from(body.to_string())).unwrap();
+    let response = create_router(app.clone()).oneshot(request).await.unwrap();
+    let status = response.status();
+    let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    (status, serde_json::from_slice(&body).ok())
+}
+
+#[tokio::test]
+async fn test_requeue_failed_by_ids_resets_status() {
+    let app = create_test_app().await;
+    let ids = [9_700_001, 9_700_002, 9_700_003];
+    for id in ids {
+        insert_failed_transaction(&app.db, id, 1).await;
+    }
+
+    let (status, body) = requeue(&app, json!({ "ids": &ids[..2] }), Some(ADMIN_TOKEN)).await;
+
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body.unwrap()["requeued"], 2);
+    for id in &ids[..2] {
+        assert_eq!(
+            fetch_state(&app.db, *id).await,
+            ("pending".to_string(), 0, None)
+        );
+    }
+    assert_eq!(fetch_state(&app.db, ids[2]).await.0, "failed");
+
+    // Already requeued transactions are no longer failed
+    let (_, body) = requeue(&app, json!({ "ids": ids }), Some(ADMIN_TOKEN)).await;
+    assert_eq!(body.unwrap()["requeued"], 1);
+}
+
+#[tokio::test]
+async fn test_requeue_failed_by_age_skips_older_failures() {
+    let app = create_test_app().await;
+    let (recent, old) = (9_700_011, 9_700_012);
+    insert_failed_transaction(&app.db, recent, 2).await;
+    insert_failed_transaction(&app.db, old, 48).await;
+
+    let (status, body) = requeue(
+        &app,
+        json!({ "ids": [recent, old], "max_age_hours": 24 }),
+        Some(ADMIN_TOKEN),
+    )
+    .await;
+
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body.unwrap()["requeued"], 1);
+    assert_eq!(fetch_state(&app.db, recent).await.0, "pending");
+    assert_eq!(fetch_state(&app.db, old).await.0, "failed");
+}
+
+#[tokio::test]
+async fn test_requeue_failed_requires_admin_token_and_a_filter() {
+    let app = create_test_app().await;
+
+    let (status, _) = requeue(&app, json!({ "ids": [9_700_021] }), None).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+
+    let (status, _) = requeue(&app, json!({}), Some(ADMIN_TOKEN)).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+}
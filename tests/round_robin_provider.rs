@@ -0,0 +1,83 @@
+use mockito::{mock, Matcher};
+use starknet::core::types::{Felt, StarknetError};
+use starknet::providers::{Provider, ProviderError};
+use zeroxbridge_sequencer::relayer::round_robin_provider::{is_transient, RoundRobinProvider};
+
+/// Nothing listens on the discard port, so connections to it are refused
+const UNREACHABLE_URL: &str = "http://127.0.0.1:9";
+
+#[tokio::test]
+async fn test_fails_over_to_fallback_when_primary_is_unreachable() {
+    let _mock = mock("POST", "/")
+        .match_body(Matcher::Regex("starknet_blockNumber".to_string()))
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"jsonrpc":"2.0","id":1,"result":650020}"#)
+        .create();
+    let providers = RoundRobinProvider::new(UNREACHABLE_URL, &[mockito::server_url()]).unwrap();
+
+    let block = providers
+        .call(|provider| async move { provider.block_number().await })
+        .await
+        .unwrap();
+
+    assert_eq!(block, 650_020);
+    assert_eq!(providers.active_index(), 1);
+    assert_eq!(
+        providers.active_url().as_str().trim_end_matches('/'),
+        mockito::server_url()
+    );
+
+    // The fallback stays active for later calls
+    providers
+        .call(|provider| async move { provider.block_number().await })
+        .await
+        .unwrap();
+    assert_eq!(providers.active_index(), 1);
+}
+
+#[tokio::test]
+async fn test_node_errors_do_not_fail_over() {
+    let _mock = mock("POST", "/")
+        .match_body(Matcher::Regex("starknet_getTransactionReceipt".to_string()))
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(
+            r#"{"jsonrpc":"2.0","id":1,"error":{"code":29,"message":"Transaction hash not found"}}"#,
+        )
+        .create();
+    let providers =
+        RoundRobinProvider::new(&mockito::server_url(), &[UNREACHABLE_URL.to_string()]).unwrap();
+
+    let err = providers
+        .call(|provider| async move { provider.get_transaction_receipt(Felt::ONE).await })
+        .await
+        .unwrap_err();
+
+    assert!(matches!(
+        err,
+        ProviderError::StarknetError(StarknetError::TransactionHashNotFound)
+    ));
+    assert!(!is_transient(&err));
+    assert_eq!(providers.active_index(), 0);
+}
+
+#[tokio::test]
+async fn test_gives_up_after_every_node_fails() {
+    let providers =
+        RoundRobinProvider::new(UNREACHABLE_URL, &["http://127.0.0.1:19".to_string()]).unwrap();
+
+    let err = providers
+        .call(|provider| async move { provider.block_number().await })
+        .await
+        .unwrap_err();
+
+    assert!(is_transient(&err));
+    // Each node was tried once, leaving the primary active again
+    assert_eq!(providers.active_index(), 0);
+}
+
+#[test]
+fn test_rejects_invalid_fallback_url() {
+    assert!(RoundRobinProvider::new(UNREACHABLE_URL, &["not a url".to_string()]).is_err());
+}
@@ -3,7 +3,9 @@ mod tests {
     use std::fs;
     use std::path::Path;
     use tempfile::tempdir;
-    use zeroxbridge_sequencer::proof_client::proof_generator::run_scarb_build;
+    use zeroxbridge_sequencer::proof_client::proof_generator::{
+        locate_build_output, run_scarb_build, ScarbBuildError,
+    };
 
     #[test]
     fn test_run_scarb_build_pass() {
@@ -48,13 +50,90 @@ fn main() {
     #[test]
     fn test_run_scarb_build_fail() {
         let result = run_scarb_build("non_existent_path");
-        assert!(result.is_err());
         assert!(
+            matches!(
+                result,
+                Err(ScarbBuildError::ProjectNotFound { ref path }) if path == "non_existent_path"
+            ),
+            "Expected missing project error, got {:?}",
             result
-                .unwrap_err()
-                .to_lowercase()
-                .contains("no scarb project"),
-            "Expected missing project error"
         );
     }
+
+    #[test]
+    fn test_run_scarb_build_without_manifest_fails_build() {
+        // Scarb refuses to build a directory with no Scarb.toml; without Scarb installed at all
+        // the build fails before it starts
+        let tmp_dir = tempdir().expect("Failed to create temporary directory");
+
+        let result = run_scarb_build(tmp_dir.path().to_str().unwrap());
+        assert!(
+            matches!(result, Err(ScarbBuildError::BuildFailed { .. })),
+            "Expected build failure, got {:?}",
+            result
+        );
+    }
+
+    #[test]
+    fn test_locate_build_output_without_manifest() {
+        let tmp_dir = tempdir().expect("Failed to create temporary directory");
+
+        let result = locate_build_output(tmp_dir.path());
+        assert!(
+            matches!(result, Err(ScarbBuildError::ManifestReadError(_))),
+            "Expected manifest read error, got {:?}",
+            result
+        );
+    }
+
+    #[test]
+    fn test_locate_build_output_with_invalid_manifest() {
+        let tmp_dir = tempdir().expect("Failed to create temporary directory");
+        fs::write(
+            tmp_dir.path().join("Scarb.toml"),
+            "[package]\nversion = \"0.1.0\"\n",
+        )
+        .unwrap();
+
+        let result = locate_build_output(tmp_dir.path());
+        assert!(
+            matches!(result, Err(ScarbBuildError::ManifestParseError(_))),
+            "Expected manifest parse error, got {:?}",
+            result
+        );
+    }
+
+    #[test]
+    fn test_locate_build_output_missing_sierra_file() {
+        let tmp_dir = tempdir().expect("Failed to create temporary directory");
+        fs::write(
+            tmp_dir.path().join("Scarb.toml"),
+            "[package]\nname = \"test_project\"\nversion = \"0.1.0\"\n",
+        )
+        .unwrap();
+
+        let result = locate_build_output(tmp_dir.path());
+        match result {
+            Err(ScarbBuildError::OutputMissing { expected }) => assert_eq!(
+                expected,
+                tmp_dir.path().join("target/dev/test_project.sierra.json")
+            ),
+            other => panic!("Expected missing output error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_locate_build_output_finds_sierra_file() {
+        let tmp_dir = tempdir().expect("Failed to create temporary directory");
+        fs::write(
+            tmp_dir.path().join("Scarb.toml"),
+            "[package]\nname = \"test_project\"\nversion = \"0.1.0\"\n",
+        )
+        .unwrap();
+        let sierra = tmp_dir.path().join("target/dev/test_project.sierra.json");
+        fs::create_dir_all(sierra.parent().unwrap()).unwrap();
+        fs::write(&sierra, "{}").unwrap();
+
+        assert_eq!(locate_build_output(tmp_dir.path()).unwrap(), sierra);
+    }
 }
@@ -0,0 +1,84 @@
+#[path = "utils.rs"]
+mod utils;
+
+use axum::{
+    body::{to_bytes, Body},
+    http::{Request, StatusCode},
+};
+use serde_json::Value;
+use tower::ServiceExt;
+use utils::create_test_app;
+use zeroxbridge_sequencer::api::routes::create_router;
+
+fn snapshot_request(token: Option<&str>) -> Request<Body> {
+    let mut builder = Request::builder().method("GET").uri("/admin/snapshot");
+    if let Some(token) = token {
+        builder = builder.header("x-admin-token", token);
+    }
+    builder.body(Body::empty()).unwrap()
+}
+
+#[tokio::test]
+async fn test_snapshot_contains_every_section() {
+    let app = create_test_app().await;
+
+    let router = create_router(app.as_ref().clone());
+    let response = router
+        .oneshot(snapshot_request(Some("test-admin-token")))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    let snapshot: Value = serde_json::from_slice(&body).unwrap();
+
+    for key in [
+        "timestamp",
+        "pending_deposits_count",
+        "pending_withdrawals_count",
+        "l2_transactions_by_status",
+        "block_trackers",
+        "active_proof_jobs",
+        "config_summary",
+    ] {
+        assert!(snapshot.get(key).is_some(), "snapshot is missing {}", key);
+    }
+    assert!(snapshot["pending_deposits_count"].is_i64());
+    assert!(snapshot["l2_transactions_by_status"].is_object());
+    assert!(snapshot["block_trackers"].is_array());
+    assert!(snapshot["active_proof_jobs"].is_array());
+}
+
+#[tokio::test]
+async fn test_snapshot_redacts_secrets() {
+    let app = create_test_app().await;
+
+    let router = create_router(app.as_ref().clone());
+    let response = router
+        .oneshot(snapshot_request(Some("test-admin-token")))
+        .await
+        .unwrap();
+    let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    let snapshot: Value = serde_json::from_slice(&body).unwrap();
+
+    let config = &snapshot["config_summary"];
+    assert_eq!(config["server"]["admin_token"], "***");
+    assert_eq!(config["starknet"]["private_key"], "***");
+    assert!(!String::from_utf8_lossy(&body).contains("test-admin-token"));
+}
+
+#[tokio::test]
+async fn test_snapshot_requires_admin_token() {
+    let app = create_test_app().await;
+
+    let router = create_router(app.as_ref().clone());
+    let response = router.oneshot(snapshot_request(None)).await.unwrap();
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+    let router = create_router(app.as_ref().clone());
+    let response = router
+        .oneshot(snapshot_request(Some("wrong-token")))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+}
@@ -0,0 +1,65 @@
+#[path = "utils.rs"]
+mod utils;
+
+use std::time::Duration;
+use utils::create_test_app;
+use zeroxbridge_sequencer::workers::registry::ServiceRegistry;
+
+#[tokio::test]
+async fn test_spawned_services_stop_on_shutdown() {
+    let registry = ServiceRegistry::new();
+    registry.spawn("forever", std::future::pending());
+    registry.spawn("ticker", async {
+        loop {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+    });
+    assert_eq!(registry.len(), 2);
+
+    let timed_out = registry.shutdown(Duration::from_secs(5)).await;
+
+    assert!(timed_out.is_empty(), "{:?}", timed_out);
+    assert!(registry.is_empty());
+    assert!(registry.shutdown_signal().is_triggered());
+}
+
+#[tokio::test]
+async fn test_registered_task_watching_the_signal_shuts_down_cleanly() {
+    let registry = ServiceRegistry::new();
+    let signal = registry.shutdown_signal();
+    let handle = tokio::spawn(async move { signal.wait().await });
+    registry.register("watcher", handle);
+
+    let timed_out = registry.shutdown(Duration::from_secs(5)).await;
+
+    assert!(timed_out.is_empty(), "{:?}", timed_out);
+}
+
+#[tokio::test]
+async fn test_tasks_ignoring_the_signal_time_out_and_are_aborted() {
+    let registry = ServiceRegistry::new();
+    let stubborn = tokio::spawn(tokio::time::sleep(Duration::from_secs(3600)));
+    let abort = stubborn.abort_handle();
+    registry.register("stubborn", stubborn);
+    registry.spawn("forever", std::future::pending());
+
+    let timed_out = registry.shutdown(Duration::from_millis(100)).await;
+
+    assert_eq!(timed_out, vec!["stubborn".to_string()]);
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    assert!(abort.is_finished());
+}
+
+#[tokio::test]
+async fn test_shutdown_services_reports_final_stats() {
+    let app = create_test_app().await;
+    app.services.spawn("forever", std::future::pending());
+
+    let report = app.shutdown_services(Duration::from_secs(5)).await;
+
+    assert!(report.timed_out.is_empty());
+    let stats = report.stats.expect("Final stats were not read");
+    assert!(stats.queue_depth >= 0);
+    assert!(stats.processed_transactions >= 0);
+    assert!(app.services.is_empty());
+}
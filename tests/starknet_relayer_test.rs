@@ -2,12 +2,27 @@
 mod tests {
     use mockall::mock;
     use mockall::predicate::*;
-    use sqlx::{Pool, Postgres};
+    use sqlx::{Pool, Postgres, Transaction};
+    use starknet::core::types::ExecutionResult;
+    use starknet::core::types::Felt;
+    use starknet::core::types::StarknetError;
+    use starknet::core::utils::get_contract_address;
+    use starknet::providers::ProviderError;
+    use starknet::signers::SigningKey;
+    use std::ops::Deref;
     use std::sync::atomic::{AtomicUsize, Ordering};
     use std::sync::Arc;
+    use zeroxbridge_sequencer::config::StarknetConfig;
     use zeroxbridge_sequencer::queue::l2_queue::L2Transaction;
+    use zeroxbridge_sequencer::relayer::provider_pool::ProviderPool;
     use zeroxbridge_sequencer::relayer::starknet_relayer::StarknetRelayer;
-    use zeroxbridge_sequencer::relayer::starknet_relayer::StarknetRelayerConfig;
+    use zeroxbridge_sequencer::relayer::starknet_relayer::{is_retriable, StarknetRelayerError};
+    use zeroxbridge_sequencer::relayer::starknet_relayer::{
+        AccountType, ARGENT_X_ACCOUNT_CLASS_HASH,
+    };
+    use zeroxbridge_sequencer::relayer::starknet_relayer::{
+        BuilderError, StarknetRelayerConfig, StarknetRelayerConfigBuilder,
+    };
 
     // Mock the Starknet provider
     mock! {
@@ -17,16 +32,50 @@ mod tests {
         }
     }
 
-    // Helper function to create a test database pool
-    async fn create_test_db_pool() -> Pool<Postgres> {
-        let database_url = std::env::var("DATABASE_URL")
-            .expect("DATABASE_URL environment variable must be set for tests");
+    /// Migrated test database, with a transaction that is rolled back when the guard is
+    /// dropped. Derefs to the pool for `sqlx::query!` calls and for the relayer.
+    ///
+    /// Only work done inside the transaction is rolled back. The relayer writes through its
+    /// own pool connections, so rows it must see are still cleaned up by the test.
+    struct TestDbGuard {
+        pool: Pool<Postgres>,
+        _tx: Option<Transaction<'static, Postgres>>,
+    }
 
-        sqlx::postgres::PgPoolOptions::new()
-            .max_connections(5)
-            .connect(&database_url)
-            .await
-            .expect("Failed to connect to database")
+    impl TestDbGuard {
+        async fn begin() -> Self {
+            let database_url = std::env::var("DATABASE_URL")
+                .expect("DATABASE_URL environment variable must be set for tests");
+
+            let pool = sqlx::postgres::PgPoolOptions::new()
+                .max_connections(5)
+                .connect(&database_url)
+                .await
+                .expect("Failed to connect to database");
+
+            // Always bring the schema up to date so tests don't depend on out-of-band setup
+            sqlx::migrate!("./migrations")
+                .run(&pool)
+                .await
+                .expect("Migrations failed");
+
+            let tx = pool
+                .begin()
+                .await
+                .expect("Failed to begin test transaction");
+            Self {
+                pool,
+                _tx: Some(tx),
+            }
+        }
+    }
+
+    impl Deref for TestDbGuard {
+        type Target = Pool<Postgres>;
+
+        fn deref(&self) -> &Self::Target {
+            &self.pool
+        }
     }
 
     // Helper function to create sample L2Transaction
@@ -40,6 +89,9 @@ mod tests {
             created_at: chrono::Utc::now(),
             updated_at: chrono::Utc::now(),
             retry_count: 0,
+            priority: 1,
+            commitment_hash: None,
+            proof_data_hash: None,
             tx_hash: None,
             error: None,
             proof_data: Some(
@@ -52,27 +104,151 @@ mod tests {
         }
     }
 
+    fn create_provider_pool() -> Arc<ProviderPool> {
+        Arc::new(
+            ProviderPool::new("http://localhost:8545", 4).expect("Failed to create provider pool"),
+        )
+    }
+
+    /// Sample config with every required field set, to override before `build()`
+    fn sample_config_builder() -> StarknetRelayerConfigBuilder {
+        StarknetRelayerConfig::builder()
+            .bridge_contract_address("0x1234567890abcdef")
+            .rpc_url("http://localhost:8545")
+            .private_key("0xabcdef1234567890abcdef1234567890abcdef1234567890abcdef1234567890")
+            .account_address("0xabcdef1234567890abcdef1234567890abcdef1234567890abcdef1234567890")
+            .retry_delay_ms(1000)
+            .transaction_timeout_ms(30000)
+    }
+
     fn create_sample_config() -> StarknetRelayerConfig {
-        StarknetRelayerConfig {
-            bridge_contract_address: "0x1234567890abcdef".to_string(),
-            rpc_url: "http://localhost:8545".to_string(),
-            private_key: "0xabcdef1234567890abcdef1234567890abcdef1234567890abcdef1234567890"
-                .to_string(),
-            max_retries: 3,
-            retry_delay_ms: 1000,
-            transaction_timeout_ms: 30000,
-            account_address: "0xabcdef1234567890abcdef1234567890abcdef1234567890abcdef1234567890"
-                .to_string(),
+        sample_config_builder()
+            .build()
+            .expect("Sample config sets every required field")
+    }
+
+    #[test]
+    fn test_builder_defaults_optional_fields() {
+        let config = StarknetRelayerConfig::builder()
+            .bridge_contract_address("0x1")
+            .rpc_url("http://localhost:8545")
+            .account_address("0x2")
+            .private_key("0x3")
+            .build()
+            .unwrap();
+
+        assert_eq!(config.max_retries, 3);
+        assert_eq!(config.retry_delay_ms, 5000);
+        assert_eq!(config.transaction_timeout_ms, 60000);
+        assert_eq!(config.max_batch_size, 1);
+        assert!(config.fallback_rpc_urls.is_empty());
+        assert_eq!(config.account_type, AccountType::OpenZeppelin);
+    }
+
+    fn sample_starknet_config() -> StarknetConfig {
+        StarknetConfig {
+            chain_id: "0x534e5f5345504f4c4941".to_string(),
+            contract_address: "0x1".to_string(),
+            account_address: "0x2".to_string(),
+            private_key: "0x3".into(),
+            max_retries: None,
+            retry_delay_ms: None,
+            transaction_timeout_ms: None,
+            receipt_poll_timeout_ms: None,
+            fallback_rpc_urls: vec!["http://fallback:5050".to_string()],
+            fact_registry_address: None,
+            max_fee_per_step: None,
+            max_total_fee: None,
+            polling_interval_seconds: 10,
+        }
+    }
+
+    #[test]
+    fn test_config_from_starknet_config() {
+        std::env::set_var("STARKNET_RPC_URL", "http://localhost:5050");
+        let config = StarknetRelayerConfig::from(StarknetConfig {
+            max_retries: Some(7),
+            retry_delay_ms: Some(250),
+            transaction_timeout_ms: Some(9000),
+            ..sample_starknet_config()
+        });
+
+        assert_eq!(config.bridge_contract_address, "0x1");
+        assert_eq!(config.account_address, "0x2");
+        assert_eq!(config.private_key, "0x3");
+        assert_eq!(config.rpc_url, "http://localhost:5050");
+        assert_eq!(config.fallback_rpc_urls, vec!["http://fallback:5050"]);
+        assert_eq!(config.max_retries, 7);
+        assert_eq!(config.retry_delay_ms, 250);
+        assert_eq!(config.transaction_timeout_ms, 9000);
+        assert_eq!(config.max_batch_size, 1);
+    }
+
+    #[test]
+    fn test_config_from_starknet_config_shares_proof_submission_defaults() {
+        std::env::set_var("STARKNET_RPC_URL", "http://localhost:5050");
+        let config = StarknetRelayerConfig::from(sample_starknet_config());
+
+        assert_eq!(config.max_retries, StarknetConfig::DEFAULT_MAX_RETRIES);
+        assert_eq!(
+            config.retry_delay_ms,
+            StarknetConfig::DEFAULT_RETRY_DELAY_MS
+        );
+        assert_eq!(
+            config.transaction_timeout_ms,
+            StarknetConfig::DEFAULT_TRANSACTION_TIMEOUT_MS
+        );
+    }
+
+    #[test]
+    fn test_builder_requires_contract_node_account_and_key() {
+        let builder = StarknetRelayerConfig::builder;
+        let cases = [
+            (
+                builder()
+                    .rpc_url("http://localhost:8545")
+                    .account_address("0x2")
+                    .private_key("0x3"),
+                "bridge_contract_address",
+            ),
+            (
+                builder()
+                    .bridge_contract_address("0x1")
+                    .account_address("0x2")
+                    .private_key("0x3"),
+                "rpc_url",
+            ),
+            (
+                builder()
+                    .bridge_contract_address("0x1")
+                    .rpc_url("http://localhost:8545")
+                    .private_key("0x3"),
+                "account_address",
+            ),
+            (
+                builder()
+                    .bridge_contract_address("0x1")
+                    .rpc_url("http://localhost:8545")
+                    .account_address("0x2"),
+                "private_key",
+            ),
+        ];
+
+        for (builder, field) in cases {
+            assert_eq!(
+                builder.build().unwrap_err(),
+                BuilderError::MissingField(field)
+            );
         }
     }
 
     #[tokio::test]
     async fn test_fetch_ready_transactions() {
         let config = create_sample_config();
-        let pool = create_test_db_pool().await;
+        let db = TestDbGuard::begin().await;
         // Create relayer config
 
-        let mock_provider = MockStarknetProvider::new();
+        let _mock_provider = MockStarknetProvider::new();
         // configure mock_provider expectations...
 
         // Insert a test transaction
@@ -99,11 +275,11 @@ mod tests {
             test_tx.error,
             test_tx.proof_data
         )
-        .execute(&pool)
+        .execute(&*db)
         .await
         .expect("Failed to insert test transaction");
 
-        let relayer = StarknetRelayer::new(pool.clone(), config)
+        let relayer = StarknetRelayer::new(db.clone(), config, create_provider_pool())
             .await
             .expect("Failed to create relayer");
 
@@ -118,7 +294,7 @@ mod tests {
         );
 
         sqlx::query!("DELETE FROM l2_transactions WHERE id = $1", test_tx.id)
-            .execute(&pool)
+            .execute(&*db)
             .await
             .unwrap();
     }
@@ -126,9 +302,9 @@ mod tests {
     #[tokio::test]
     async fn test_process_transaction_success() {
         let config = create_sample_config();
-        let pool = create_test_db_pool().await;
+        let db = TestDbGuard::begin().await;
 
-        let relayer = StarknetRelayer::new(pool.clone(), config)
+        let relayer = StarknetRelayer::new(db.clone(), config, create_provider_pool())
             .await
             .expect("Failed to create relayer");
         // Create a mock provider
@@ -157,7 +333,7 @@ mod tests {
             "SELECT * FROM l2_transactions WHERE id = $1",
             test_tx.id
         )
-        .fetch_one(&pool)
+        .fetch_one(&*db)
         .await
         .expect("Failed to fetch updated transaction");
 
@@ -168,13 +344,13 @@ mod tests {
     #[tokio::test]
     async fn test_process_transaction_with_retries() {
         let config = create_sample_config();
-        let pool = create_test_db_pool().await;
+        let db = TestDbGuard::begin().await;
         // Create relayer config
 
         let mut mock_provider = MockStarknetProvider::new();
         // configure mock_provider expectations...
 
-        let mock_relayer = StarknetRelayer::new(pool.clone(), config)
+        let mock_relayer = StarknetRelayer::new(db.clone(), config, create_provider_pool())
             .await
             .expect("Failed to create relayer");
 
@@ -211,7 +387,7 @@ mod tests {
             "SELECT * FROM l2_transactions WHERE id = $1",
             test_tx.id
         )
-        .fetch_one(&pool)
+        .fetch_one(&*db)
         .await
         .expect("Failed to fetch updated transaction");
 
@@ -221,10 +397,10 @@ mod tests {
     #[tokio::test]
     async fn test_process_transaction_failure() {
         let config = create_sample_config();
-        let pool = create_test_db_pool().await;
+        let db = TestDbGuard::begin().await;
 
         // Create relayer config
-        let mock_relayer = StarknetRelayer::new(pool.clone(), config)
+        let mock_relayer = StarknetRelayer::new(db.clone(), config, create_provider_pool())
             .await
             .expect("Failed to create relayer");
 
@@ -249,11 +425,387 @@ mod tests {
             "SELECT * FROM l2_transactions WHERE id = $1",
             test_tx.id
         )
-        .fetch_one(&pool)
+        .fetch_one(&*db)
         .await
         .expect("Failed to fetch updated transaction");
 
         assert_eq!(updated_tx.status, "failed");
         assert!(updated_tx.error.is_some());
     }
+
+    #[test]
+    fn test_effective_account_address_derives_from_private_key() {
+        let config = sample_config_builder()
+            .private_key("0x71d7bb07b9a64f6f78ac4c816aff4da9")
+            .account_address("")
+            .build()
+            .unwrap();
+
+        let address = config
+            .effective_account_address()
+            .expect("Failed to derive account address");
+
+        assert_eq!(
+            address,
+            Felt::from_hex("0x22e855eadadbb672fb52137c02675caff6845e26544de9d2cc22b93fa7969ee")
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn test_effective_account_address_derives_argent_x_account() {
+        let config = sample_config_builder()
+            .private_key("0x71d7bb07b9a64f6f78ac4c816aff4da9")
+            .account_address("")
+            .account_type(AccountType::ArgentX)
+            .build()
+            .unwrap();
+
+        let address = config
+            .effective_account_address()
+            .expect("Failed to derive account address");
+
+        let public_key = SigningKey::from_secret_scalar(
+            Felt::from_hex("0x71d7bb07b9a64f6f78ac4c816aff4da9").unwrap(),
+        )
+        .verifying_key()
+        .scalar();
+        assert_eq!(
+            address,
+            get_contract_address(
+                public_key,
+                Felt::from_hex(ARGENT_X_ACCOUNT_CLASS_HASH).unwrap(),
+                &[public_key, Felt::ZERO],
+                Felt::ZERO,
+            )
+        );
+        assert_ne!(
+            address,
+            Felt::from_hex("0x22e855eadadbb672fb52137c02675caff6845e26544de9d2cc22b93fa7969ee")
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn test_account_type_from_str() {
+        assert_eq!(
+            "openzeppelin".parse::<AccountType>().unwrap(),
+            AccountType::OpenZeppelin
+        );
+        assert_eq!(
+            "ArgentX".parse::<AccountType>().unwrap(),
+            AccountType::ArgentX
+        );
+        assert!("braavos".parse::<AccountType>().is_err());
+    }
+
+    #[test]
+    fn test_effective_account_address_prefers_explicit_address() {
+        let config = create_sample_config();
+
+        let address = config
+            .effective_account_address()
+            .expect("Failed to parse account address");
+
+        assert_eq!(address, Felt::from_hex(&config.account_address).unwrap());
+    }
+
+    #[test]
+    fn test_is_retriable_classifies_errors() {
+        let node_error = |e| StarknetRelayerError::Provider(ProviderError::StarknetError(e));
+
+        assert!(!is_retriable(&node_error(StarknetError::ContractNotFound)));
+        assert!(!is_retriable(&node_error(StarknetError::NonAccount)));
+        assert!(!is_retriable(&node_error(
+            StarknetError::InsufficientAccountBalance
+        )));
+        assert!(!is_retriable(&node_error(
+            StarknetError::ValidationFailure("invalid signature".to_string())
+        )));
+        assert!(!is_retriable(&StarknetRelayerError::ProofDataMissing));
+        assert!(!is_retriable(&StarknetRelayerError::InvalidContractAddress));
+
+        assert!(is_retriable(&StarknetRelayerError::Provider(
+            ProviderError::RateLimited
+        )));
+        assert!(is_retriable(&node_error(
+            StarknetError::TransactionHashNotFound
+        )));
+        assert!(is_retriable(&StarknetRelayerError::Timeout));
+        assert!(is_retriable(&StarknetRelayerError::TransactionFailed(
+            "connection reset".to_string()
+        )));
+    }
+
+    #[tokio::test]
+    async fn test_permanent_error_is_not_retried() {
+        let db = TestDbGuard::begin().await;
+        let config = sample_config_builder()
+            .rpc_url(mockito::server_url())
+            .account_address("0x5e1a7e")
+            .build()
+            .unwrap();
+
+        // The nonce lookup is the first request of every attempt
+        let rpc_mock = mockito::mock("POST", "/")
+            .match_body(mockito::Matcher::Regex(
+                r#"starknet_getNonce.*"0x5e1a7e""#.to_string(),
+            ))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                r#"{"jsonrpc":"2.0","id":1,"error":{"code":20,"message":"Contract not found"}}"#,
+            )
+            .expect(1)
+            .create();
+
+        let provider_pool = Arc::new(
+            ProviderPool::new(&config.rpc_url, 4).expect("Failed to create provider pool"),
+        );
+        let relayer = StarknetRelayer::new(db.clone(), config, provider_pool)
+            .await
+            .expect("Failed to create relayer");
+
+        let mut tx = create_sample_l2_transaction();
+        tx.id = 9_137_001;
+        tx.proof_data = Some(r#"{"proof": ["0x1", "0x2"], "merkle_root": "0xabc"}"#.to_string());
+
+        let result = relayer.process_transaction(&mut tx).await;
+
+        assert!(
+            matches!(
+                result,
+                Err(StarknetRelayerError::Provider(
+                    ProviderError::StarknetError(StarknetError::ContractNotFound)
+                ))
+            ),
+            "Expected the node's error, got {:?}",
+            result
+        );
+        rpc_mock.assert();
+    }
+
+    #[tokio::test]
+    async fn test_batch_relays_transactions_in_one_multicall() {
+        let db = TestDbGuard::begin().await;
+        let config = sample_config_builder()
+            .rpc_url(mockito::server_url())
+            .account_address("0xba7c4")
+            .max_batch_size(2)
+            .build()
+            .unwrap();
+
+        let nonce_mock = mockito::mock("POST", "/")
+            .match_body(mockito::Matcher::Regex(
+                r#"starknet_getNonce.*"0xba7c4""#.to_string(),
+            ))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"jsonrpc":"2.0","id":1,"result":"0x3"}"#)
+            .create();
+        // Both withdrawals arrive in the calldata of a single invoke with two calls
+        let estimate_mock = mockito::mock("POST", "/")
+            .match_body(mockito::Matcher::AllOf(vec![
+                mockito::Matcher::Regex("starknet_estimateFee".to_string()),
+                mockito::Matcher::Regex(r#""sender_address":"0xba7c4""#.to_string()),
+                mockito::Matcher::Regex(
+                    r#""calldata":\["0x2",.*"0x8b6bcd",.*"0x8b6bce""#.to_string(),
+                ),
+            ]))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                r#"{"jsonrpc":"2.0","id":1,"error":{"code":20,"message":"Contract not found"}}"#,
+            )
+            .expect(1)
+            .create();
+
+        let provider_pool = Arc::new(
+            ProviderPool::new(&config.rpc_url, 4).expect("Failed to create provider pool"),
+        );
+        let relayer = StarknetRelayer::new(db.clone(), config, provider_pool)
+            .await
+            .expect("Failed to create relayer");
+
+        let batch: Vec<L2Transaction> = [9_137_101, 9_137_102]
+            .into_iter()
+            .map(|id| {
+                let mut tx = create_sample_l2_transaction();
+                tx.id = id;
+                tx.proof_data =
+                    Some(r#"{"proof": ["0x1", "0x2"], "merkle_root": "0xabc"}"#.to_string());
+                tx
+            })
+            .collect();
+
+        let result = relayer.batch_process_transactions(&batch).await;
+
+        // Rejected before it was sent, so the batch may be retried transaction by transaction
+        assert!(
+            matches!(
+                result,
+                Err(StarknetRelayerError::Provider(
+                    ProviderError::StarknetError(StarknetError::ContractNotFound)
+                ))
+            ),
+            "Expected the node's error, got {:?}",
+            result
+        );
+        nonce_mock.assert();
+        estimate_mock.assert();
+    }
+
+    #[tokio::test]
+    async fn test_batch_rejects_transaction_without_proof_data() {
+        let db = TestDbGuard::begin().await;
+        let config = sample_config_builder().max_batch_size(2).build().unwrap();
+        let relayer = StarknetRelayer::new(db.clone(), config, create_provider_pool())
+            .await
+            .expect("Failed to create relayer");
+
+        let mut missing_proof = create_sample_l2_transaction();
+        missing_proof.id = 9_137_103;
+        missing_proof.proof_data = None;
+
+        let result = relayer
+            .batch_process_transactions(&[create_sample_l2_transaction(), missing_proof])
+            .await;
+
+        assert!(matches!(
+            result,
+            Err(StarknetRelayerError::ProofDataMissing)
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_simulate_transaction_parses_simulation() {
+        let db = TestDbGuard::begin().await;
+        let config = sample_config_builder()
+            .rpc_url(mockito::server_url())
+            .account_address("0x51a1a7e")
+            .build()
+            .unwrap();
+
+        let nonce_mock = mockito::mock("POST", "/")
+            .match_body(mockito::Matcher::Regex(
+                r#"starknet_getNonce.*"0x51a1a7e""#.to_string(),
+            ))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"jsonrpc":"2.0","id":1,"result":"0x3"}"#)
+            .create();
+        let simulate_mock = mockito::mock("POST", "/")
+            .match_body(mockito::Matcher::Regex(
+                r#"starknet_simulateTransactions.*"sender_address":"0x51a1a7e".*"simulation_flags":\["SKIP_VALIDATE"\]"#
+                    .to_string(),
+            ))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(SIMULATION_RESPONSE)
+            .expect(1)
+            .create();
+
+        let provider_pool = Arc::new(
+            ProviderPool::new(&config.rpc_url, 4).expect("Failed to create provider pool"),
+        );
+        let relayer = StarknetRelayer::new(db.clone(), config, provider_pool)
+            .await
+            .expect("Failed to create relayer");
+
+        let calls = relayer
+            .build_relay_calls(42, r#"{"proof": ["0x1", "0x2"], "merkle_root": "0xabc"}"#)
+            .expect("Failed to build calls");
+        assert_eq!(
+            calls[0].calldata,
+            vec![
+                Felt::from(42u64),
+                Felt::from(2u64),
+                Felt::ONE,
+                Felt::TWO,
+                Felt::from_hex("0xabc").unwrap()
+            ]
+        );
+
+        let result = relayer
+            .simulate_transaction(calls)
+            .await
+            .expect("Simulation failed");
+
+        assert_eq!(result.fee_estimate, 0x2710);
+        assert_eq!(result.execution_result, ExecutionResult::Succeeded);
+        // Events of nested calls are flattened in emission order
+        let emitters: Vec<Felt> = result.events.iter().map(|e| e.from_address).collect();
+        assert_eq!(
+            emitters,
+            vec![
+                Felt::from_hex("0xb41d6e").unwrap(),
+                Felt::from_hex("0x70ca1").unwrap()
+            ]
+        );
+        assert_eq!(result.events[0].keys, vec![Felt::from_hex("0x1e").unwrap()]);
+        nonce_mock.assert();
+        simulate_mock.assert();
+    }
+
+    const SIMULATION_RESPONSE: &str = r#"{
+        "jsonrpc": "2.0",
+        "id": 1,
+        "result": [{
+            "transaction_trace": {
+                "type": "INVOKE",
+                "execute_invocation": {
+                    "contract_address": "0x51a1a7e",
+                    "entry_point_selector": "0x15d40a3d6ca2ac30f4031e42be28da9b056fef9bb7357ac5e85627ee876e5ad",
+                    "calldata": [],
+                    "caller_address": "0x0",
+                    "class_hash": "0x1",
+                    "entry_point_type": "EXTERNAL",
+                    "call_type": "CALL",
+                    "result": [],
+                    "calls": [{
+                        "contract_address": "0xb41d6e",
+                        "entry_point_selector": "0x2",
+                        "calldata": [],
+                        "caller_address": "0x51a1a7e",
+                        "class_hash": "0x3",
+                        "entry_point_type": "EXTERNAL",
+                        "call_type": "CALL",
+                        "result": [],
+                        "calls": [{
+                            "contract_address": "0x70ca1",
+                            "entry_point_selector": "0x4",
+                            "calldata": [],
+                            "caller_address": "0xb41d6e",
+                            "class_hash": "0x5",
+                            "entry_point_type": "EXTERNAL",
+                            "call_type": "CALL",
+                            "result": [],
+                            "calls": [],
+                            "events": [{"order": 1, "keys": ["0x2f"], "data": []}],
+                            "messages": [],
+                            "execution_resources": {"steps": 10}
+                        }],
+                        "events": [{"order": 0, "keys": ["0x1e"], "data": ["0x7"]}],
+                        "messages": [],
+                        "execution_resources": {"steps": 20}
+                    }],
+                    "events": [],
+                    "messages": [],
+                    "execution_resources": {"steps": 30}
+                },
+                "execution_resources": {
+                    "steps": 60,
+                    "data_availability": {"l1_gas": 0, "l1_data_gas": 128}
+                }
+            },
+            "fee_estimation": {
+                "gas_consumed": "0x0",
+                "gas_price": "0x1",
+                "data_gas_consumed": "0x80",
+                "data_gas_price": "0x1",
+                "overall_fee": "0x2710",
+                "unit": "FRI"
+            }
+        }]
+    }"#;
 }
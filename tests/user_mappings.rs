@@ -0,0 +1,273 @@
+#[path = "utils.rs"]
+mod utils;
+
+use alloy_primitives::Address;
+use axum::{
+    body::{to_bytes, Body},
+    http::{Request, StatusCode},
+};
+use serde_json::json;
+use sqlx::PgPool;
+use starknet::core::types::Felt;
+use starknet::signers::SigningKey;
+use tower::ServiceExt;
+use utils::create_test_app;
+use zeroxbridge_sequencer::api::routes::{create_router, AppState};
+use zeroxbridge_sequencer::db::database::UserMapping;
+use zeroxbridge_sequencer::utils::{ethereum_address_message, normalize_felt_hex};
+
+/// Known key pair whose public key is mapped in these tests
+fn signing_key(secret: &str) -> SigningKey {
+    SigningKey::from_secret_scalar(Felt::from_hex(secret).unwrap())
+}
+
+fn starknet_address(key: &SigningKey) -> String {
+    normalize_felt_hex(&key.verifying_key().scalar().to_hex_string()).unwrap()
+}
+
+/// Starknet chain id and L2 bridge address `app` expects mapping signatures to be bound to
+fn signing_domain(app: &AppState) -> (Felt, Felt) {
+    (
+        Felt::from_hex(&app.config.starknet.chain_id).unwrap(),
+        Felt::from_hex(&app.config.contracts.l2_contract_address).unwrap(),
+    )
+}
+
+/// Request body mapping `key` to `ethereum_address`, signed over `signed_address` for `app`
+fn mapping_body(
+    app: &AppState,
+    key: &SigningKey,
+    ethereum_address: &str,
+    signed_address: &str,
+) -> String {
+    let (chain_id, bridge_address) = signing_domain(app);
+    let message = ethereum_address_message(
+        &chain_id,
+        &bridge_address,
+        &signed_address.parse::<Address>().unwrap(),
+    );
+    signed_body(key, ethereum_address, &message)
+}
+
+/// Request body mapping `key` to `ethereum_address` with its signature over `message`
+fn signed_body(key: &SigningKey, ethereum_address: &str, message: &Felt) -> String {
+    let signature = key.sign(message).unwrap();
+    json!({
+        "starknet_address": starknet_address(key),
+        "ethereum_address": ethereum_address,
+        "signature": [signature.r.to_hex_string(), signature.s.to_hex_string()],
+    })
+    .to_string()
+}
+
+async fn post_mapping(state: AppState, body: String) -> (StatusCode, Vec<u8>) {
+    let request = Request::builder()
+        .method("POST")
+        .uri("/user-mappings")
+        .header("content-type", "application/json")
+        .body(Body::from(body))
+        .unwrap();
+    let response = create_router(state).oneshot(request).await.unwrap();
+    let status = response.status();
+    let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    (status, body.to_vec())
+}
+
+async fn delete_mappings(pool: &PgPool, starknet_address: &str, ethereum_address: &str) {
+    sqlx::query!(
+        "DELETE FROM user_mappings WHERE starknet_address = $1 OR ethereum_address = $2",
+        starknet_address,
+        ethereum_address
+    )
+    .execute(pool)
+    .await
+    .unwrap();
+}
+
+#[tokio::test]
+async fn test_signed_mapping_is_stored_and_returned() {
+    let app = create_test_app().await;
+    let key = signing_key("0x5eed01");
+    let ethereum_address = "0x00000000000000000000000000000000000a11Ce";
+    let stored_address = ethereum_address.to_lowercase();
+    delete_mappings(&app.db, &starknet_address(&key), &stored_address).await;
+
+    let (status, body) = post_mapping(
+        app.as_ref().clone(),
+        mapping_body(&app, &key, ethereum_address, ethereum_address),
+    )
+    .await;
+
+    assert_eq!(status, StatusCode::CREATED);
+    let created: UserMapping = serde_json::from_slice(&body).unwrap();
+    assert_eq!(created.ethereum_address, stored_address);
+    assert!(created.verified_at.is_some());
+
+    let request = Request::builder()
+        .method("GET")
+        .uri(format!(
+            "/user-mappings/{}",
+            key.verifying_key().scalar().to_hex_string()
+        ))
+        .body(Body::empty())
+        .unwrap();
+    let response = create_router(app.as_ref().clone())
+        .oneshot(request)
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    let mapping: UserMapping = serde_json::from_slice(&body).unwrap();
+    assert_eq!(mapping.starknet_address, starknet_address(&key));
+    assert_eq!(mapping.ethereum_address, stored_address);
+
+    delete_mappings(&app.db, &starknet_address(&key), &stored_address).await;
+}
+
+#[tokio::test]
+async fn test_signature_over_another_address_is_rejected() {
+    let app = create_test_app().await;
+    let key = signing_key("0x5eed02");
+    let ethereum_address = "0x0000000000000000000000000000000000000b0b";
+    delete_mappings(&app.db, &starknet_address(&key), ethereum_address).await;
+
+    let (status, _) = post_mapping(
+        app.as_ref().clone(),
+        mapping_body(
+            &app,
+            &key,
+            ethereum_address,
+            "0x0000000000000000000000000000000000000bad",
+        ),
+    )
+    .await;
+
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+    let stored = sqlx::query_scalar!(
+        "SELECT COUNT(*) FROM user_mappings WHERE starknet_address = $1",
+        starknet_address(&key)
+    )
+    .fetch_one(&app.db)
+    .await
+    .unwrap();
+    assert_eq!(stored, Some(0));
+}
+
+#[tokio::test]
+async fn test_signature_from_another_domain_is_rejected() {
+    let app = create_test_app().await;
+    let key = signing_key("0x5eed06");
+    let ethereum_address = "0x0000000000000000000000000000000000000e01";
+    let address = ethereum_address.parse::<Address>().unwrap();
+    let (chain_id, bridge_address) = signing_domain(&app);
+    delete_mappings(&app.db, &starknet_address(&key), ethereum_address).await;
+
+    for message in [
+        // Same address signed for another chain
+        ethereum_address_message(
+            &Felt::from_hex("0x534e5f5345504f4c4941").unwrap(),
+            &bridge_address,
+            &address,
+        ),
+        // Same address signed for another bridge deployment
+        ethereum_address_message(&chain_id, &Felt::from_hex("0xb1d6e").unwrap(), &address),
+        // The bare address, with no domain at all
+        Felt::from_bytes_be_slice(address.as_slice()),
+    ] {
+        let (status, _) = post_mapping(
+            app.as_ref().clone(),
+            signed_body(&key, ethereum_address, &message),
+        )
+        .await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+    }
+}
+
+#[tokio::test]
+async fn test_already_mapped_addresses_conflict() {
+    let app = create_test_app().await;
+    let key = signing_key("0x5eed03");
+    let other_key = signing_key("0x5eed04");
+    let ethereum_address = "0x0000000000000000000000000000000000000c01";
+    let other_address = "0x0000000000000000000000000000000000000c02";
+    delete_mappings(&app.db, &starknet_address(&key), ethereum_address).await;
+    delete_mappings(&app.db, &starknet_address(&other_key), other_address).await;
+
+    let (status, _) = post_mapping(
+        app.as_ref().clone(),
+        mapping_body(&app, &key, ethereum_address, ethereum_address),
+    )
+    .await;
+    assert_eq!(status, StatusCode::CREATED);
+
+    // Same Stark key, new Ethereum address
+    let (status, _) = post_mapping(
+        app.as_ref().clone(),
+        mapping_body(&app, &key, other_address, other_address),
+    )
+    .await;
+    assert_eq!(status, StatusCode::CONFLICT);
+
+    // New Stark key, Ethereum address already taken
+    let (status, _) = post_mapping(
+        app.as_ref().clone(),
+        mapping_body(&app, &other_key, ethereum_address, ethereum_address),
+    )
+    .await;
+    assert_eq!(status, StatusCode::CONFLICT);
+
+    delete_mappings(&app.db, &starknet_address(&key), ethereum_address).await;
+    delete_mappings(&app.db, &starknet_address(&other_key), other_address).await;
+}
+
+#[tokio::test]
+async fn test_malformed_mapping_requests_are_rejected() {
+    let app = create_test_app().await;
+    let key = signing_key("0x5eed05");
+
+    for body in [
+        json!({
+            "starknet_address": "not-a-felt",
+            "ethereum_address": "0x0000000000000000000000000000000000000d01",
+            "signature": ["0x1", "0x2"],
+        }),
+        json!({
+            "starknet_address": starknet_address(&key),
+            "ethereum_address": "0x1234",
+            "signature": ["0x1", "0x2"],
+        }),
+        json!({
+            "starknet_address": starknet_address(&key),
+            "ethereum_address": "0x0000000000000000000000000000000000000d01",
+            "signature": ["0x1"],
+        }),
+    ] {
+        let (status, _) = post_mapping(app.as_ref().clone(), body.to_string()).await;
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY, "{}", body);
+    }
+}
+
+#[tokio::test]
+async fn test_unmapped_starknet_address_returns_404() {
+    let app = create_test_app().await;
+    let starknet_address = "0x5eed0000000000000000000000000000000000000000000000000000000000";
+    sqlx::query!(
+        "DELETE FROM user_mappings WHERE starknet_address = $1",
+        normalize_felt_hex(starknet_address).unwrap()
+    )
+    .execute(&app.db)
+    .await
+    .unwrap();
+
+    let request = Request::builder()
+        .method("GET")
+        .uri(format!("/user-mappings/{}", starknet_address))
+        .body(Body::empty())
+        .unwrap();
+    let response = create_router(app.as_ref().clone())
+        .oneshot(request)
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+}
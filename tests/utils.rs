@@ -1,13 +1,18 @@
 use dotenv::dotenv;
 use sqlx::postgres::PgPoolOptions;
+use sqlx::{PgConnection, PgPool, Postgres, Transaction};
+use std::ops::{Deref, DerefMut};
 use std::sync::Arc;
 use zeroxbridge_sequencer::api::routes::AppState;
 use zeroxbridge_sequencer::config::{
     AppConfig, ContractConfig, Contracts, DatabaseConfig, EthereumConfig, HerodotusConfig,
-    LoggingConfig, MerkleConfig, OracleConfig, QueueConfig, RelayerConfig, ServerConfig,
-    StarknetConfig,
+    LoggingConfig, MerkleConfig, OracleConfig, ProofConfig, QueueConfig, RelayerConfig,
+    ServerConfig, StarknetConfig, DEFAULT_L2_BURN_EVENT_KEY, DEFAULT_L2_WITHDRAWAL_EVENT_KEY,
 };
+use zeroxbridge_sequencer::db::database::{upsert_token_metadata, TokenMetadata};
+use zeroxbridge_sequencer::utils::canonical_felt_hex;
 
+#[allow(dead_code)]
 pub async fn create_test_app() -> Arc<AppState> {
     dotenv().ok();
     let configuration = create_test_config();
@@ -15,16 +20,97 @@ pub async fn create_test_app() -> Arc<AppState> {
 
     let pool = PgPoolOptions::new()
         .max_connections(5)
-        .connect(&database_url)
+        .connect(database_url.expose())
         .await
         .expect("Failed to connect to test database");
 
-    let state = Arc::new(AppState {
-        db: pool.clone(),
-        config: configuration.clone(),
-    });
+    // Always bring the schema up to date so tests don't depend on out-of-band setup
+    sqlx::migrate!("./migrations")
+        .run(&pool)
+        .await
+        .expect("Migrations failed");
+
+    // Withdrawals are only accepted for tokens with metadata
+    for token in test_token_metadata() {
+        upsert_token_metadata(&pool, &token)
+            .await
+            .expect("Failed to seed token metadata");
+    }
 
-    state
+    Arc::new(AppState::new(pool.clone(), configuration.clone()))
+}
+
+/// Metadata of the tokens whitelisted in [`create_test_config`]. The test tokens have 2 decimals
+/// and cost a dollar, so their amount in base units equals its value in cents.
+/// A stark key no other test uses, so its withdrawal nonces start from 0
+#[allow(dead_code)]
+pub fn unique_stark_pub_key() -> String {
+    let key = format!("0x{}", uuid::Uuid::new_v4().simple());
+    canonical_felt_hex(starknet::core::types::Felt::from_hex(&key).unwrap())
+}
+
+#[allow(dead_code)]
+pub fn test_token_metadata() -> Vec<TokenMetadata> {
+    let test_token = |address: &str| TokenMetadata {
+        address: address.to_string(),
+        symbol: "TKN".to_string(),
+        decimals: 2,
+        usd_price_cents: 100,
+    };
+    vec![
+        test_token("0xtoken123"),
+        test_token("0xtoken789"),
+        TokenMetadata {
+            address: "0xa0b86991c6218b36c1d19d4a2e9eb0ce3606eb48".to_string(),
+            symbol: "USDC".to_string(),
+            decimals: 6,
+            usd_price_cents: 100,
+        },
+    ]
+}
+
+/// Test-scoped database transaction that rolls back when dropped,
+/// so individual tests don't pollute each other.
+///
+/// Derefs to the transaction's connection, so `&mut tx` can be passed
+/// anywhere a `&mut PgConnection` is expected.
+// Every test binary compiles this module, but only some of them use the guard
+#[allow(dead_code)]
+pub struct TestTransaction {
+    tx: Transaction<'static, Postgres>,
+}
+
+#[allow(dead_code)]
+impl TestTransaction {
+    pub async fn begin(pool: &PgPool) -> Self {
+        let tx = pool
+            .begin()
+            .await
+            .expect("Failed to begin test transaction");
+        Self { tx }
+    }
+
+    /// Explicitly roll back instead of waiting for the guard to be dropped
+    pub async fn rollback(self) {
+        self.tx
+            .rollback()
+            .await
+            .expect("Failed to roll back test transaction");
+    }
+}
+
+impl Deref for TestTransaction {
+    type Target = PgConnection;
+
+    fn deref(&self) -> &Self::Target {
+        &self.tx
+    }
+}
+
+impl DerefMut for TestTransaction {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.tx
+    }
 }
 
 // Helper function to create test config
@@ -36,15 +122,30 @@ pub fn create_test_config() -> AppConfig {
         contracts: Contracts {
             l1_contract_address: "0x123".to_string(),
             l2_contract_address: "0x456".to_string(),
+            l2_contract_deploy_block: 0,
+            l2_burn_event_key: DEFAULT_L2_BURN_EVENT_KEY.to_string(),
+            l2_withdrawal_event_key: DEFAULT_L2_WITHDRAWAL_EVENT_KEY.to_string(),
+            l1_tvl_contract_addresses: vec![],
         },
         server: ServerConfig {
             host: "127.0.0.1".to_string(),
             server_url: "http://localhost:8080".to_string(),
+            instance_id: Some("test-sequencer".to_string()),
+            admin_token: Some("test-admin-token".into()),
+            max_body_bytes: 1024 * 1024,
+            cors_allowed_origins: vec![],
+        },
+        database: DatabaseConfig {
+            max_connections: 5,
+            migration_timeout_seconds: 60,
+            redis_url: None,
         },
-        database: DatabaseConfig { max_connections: 5 },
         ethereum: EthereumConfig {
             chain_id: 11155111, // Sepolia testnet
             confirmations: 1,
+            challenge_window_blocks: 0,
+            reorg_depth: 10,
+            polling_interval_seconds: 10,
         },
         starknet: StarknetConfig {
             chain_id: "0x534e5f4d41494e".to_string(),
@@ -53,15 +154,31 @@ pub fn create_test_config() -> AppConfig {
             account_address: "0x0000000000000000000000000000000000000000000000000000000000000000"
                 .to_string(),
             private_key: "0x0000000000000000000000000000000000000000000000000000000000000000"
-                .to_string(),
+                .into(),
             max_retries: Some(5),
             retry_delay_ms: Some(5000),
             transaction_timeout_ms: Some(300000),
+            receipt_poll_timeout_ms: Some(30000),
+            fallback_rpc_urls: vec![],
+            fact_registry_address: None,
+            max_fee_per_step: None,
+            max_total_fee: None,
+            polling_interval_seconds: 10,
         },
         relayer: RelayerConfig {
             max_retries: 3,
             retry_delay_seconds: 60,
             gas_limit: 300000,
+            gas_estimation_multiplier: 1.2,
+            max_gas_price_gwei: None,
+            allowed_l1_tokens: vec![
+                "0xtoken123".to_string(),
+                "0xtoken789".to_string(),
+                "0xA0b86991c6218b36c1d19D4a2e9Eb0cE3606eB48".to_string(),
+            ],
+            dry_run: false,
+            amount_tolerance_bps: 100,
+            run_in_sequencer: false,
         },
         queue: QueueConfig {
             process_interval_sec: 60,
@@ -70,6 +187,9 @@ pub fn create_test_config() -> AppConfig {
             initial_retry_delay_sec: 60,
             retry_delay_seconds: 60,
             merkle_update_confirmations: 1,
+            batch_size: 10,
+            commitment_cache_ttl_seconds: 300,
+            max_concurrent_validations: 1,
         },
         merkle: MerkleConfig {
             tree_depth: 32,
@@ -80,11 +200,25 @@ pub fn create_test_config() -> AppConfig {
             file: "logs/zeroxbridge.log".to_string(),
         },
         oracle: OracleConfig {
-            tolerance_percent: Some(0.01), // 1% tolerance
+            tolerance_bps: Some(100), // 1% tolerance
             polling_interval_seconds: 60,
+            enabled: false,
         },
         herodotus: HerodotusConfig {
             herodotus_endpoint: "https://herodotus.example.com/api".to_string(),
+            atlantic_endpoint: "https://staging.atlantic.api.herodotus.cloud".to_string(),
+            poll_interval_seconds: 30,
+            program_path: "tmp/target/dev/cairo1.sierra.json".to_string(),
+            inputs_dir: "tmp/inputs".to_string(),
+            atlantic_max_retries: 3,
+            atlantic_retry_delay_ms: 10,
+        },
+        proof: ProofConfig {
+            stale_job_age_minutes: Some(30),
+            calldata_base_dir: None,
+            max_concurrent_jobs: Some(4),
+            retention_days: None,
         },
+        webhook: None,
     }
 }
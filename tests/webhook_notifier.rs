@@ -0,0 +1,156 @@
+use chrono::{TimeZone, Utc};
+use hmac::{Hmac, Mac};
+use mockito::{mock, Matcher, Mock};
+use reqwest::StatusCode;
+use sha2::Sha256;
+use std::sync::Arc;
+use std::time::Duration;
+use zeroxbridge_sequencer::config::{WebhookConfig, WebhookEventType};
+use zeroxbridge_sequencer::events::EventBus;
+use zeroxbridge_sequencer::http::webhook::{WebhookError, WebhookEvent, WebhookNotifier};
+use zeroxbridge_sequencer::relayer::ethereum_relayer::CompletedWithdrawalEvent;
+
+const SECRET: &str = "webhook-test-secret";
+
+fn notifier(path: &str, events: Vec<WebhookEventType>) -> WebhookNotifier {
+    WebhookNotifier::new(WebhookConfig {
+        url: format!("{}{}", mockito::server_url(), path),
+        secret: SECRET.into(),
+        events,
+    })
+}
+
+fn completion(withdrawal_id: i32) -> CompletedWithdrawalEvent {
+    CompletedWithdrawalEvent {
+        withdrawal_id,
+        tx_hash: "0xdeadbeef".to_string(),
+        amount: 1000,
+        stark_pub_key: "0x123".to_string(),
+        timestamp: Utc.with_ymd_and_hms(2025, 9, 1, 12, 0, 0).unwrap(),
+    }
+}
+
+/// Expected `X-Signature-256` of `event`, computed independently of the notifier
+fn signature_of(event: &WebhookEvent) -> String {
+    let body = serde_json::to_vec(event).unwrap();
+    let mut mac = Hmac::<Sha256>::new_from_slice(SECRET.as_bytes()).unwrap();
+    mac.update(&body);
+    format!("sha256={}", hex::encode(mac.finalize().into_bytes()))
+}
+
+fn endpoint_mock(path: &str, event: &WebhookEvent, status: usize) -> Mock {
+    mock("POST", path)
+        .match_header("content-type", "application/json")
+        .match_header("x-signature-256", signature_of(event).as_str())
+        .match_body(Matcher::Json(serde_json::to_value(event).unwrap()))
+        .with_status(status)
+}
+
+#[tokio::test]
+async fn test_send_posts_signed_event() {
+    let event = WebhookEvent::ProofJobFailed {
+        job_id: 42,
+        error: "Fact not registered".to_string(),
+    };
+    let endpoint = endpoint_mock("/hooks/signed", &event, 200)
+        .expect(1)
+        .create();
+
+    notifier("/hooks/signed", vec![WebhookEventType::ProofJobFailed])
+        .send(event)
+        .await
+        .expect("Signed event should be accepted");
+
+    endpoint.assert();
+}
+
+#[test]
+fn test_event_body_is_tagged_with_its_type() {
+    let event = WebhookEvent::ProofJobCompleted {
+        job_id: 7,
+        fact_hash: Some("0xabc".to_string()),
+    };
+
+    assert_eq!(event.event_type(), WebhookEventType::ProofJobCompleted);
+    assert_eq!(
+        serde_json::to_value(&event).unwrap(),
+        serde_json::json!({
+            "type": "proof_job_completed",
+            "data": { "job_id": 7, "fact_hash": "0xabc" }
+        })
+    );
+}
+
+#[tokio::test]
+async fn test_server_error_is_retried_once() {
+    let event = WebhookEvent::WithdrawalCompleted(completion(1));
+    let endpoint = endpoint_mock("/hooks/retry", &event, 503)
+        .expect(2)
+        .create();
+
+    let result = notifier("/hooks/retry", vec![WebhookEventType::WithdrawalCompleted])
+        .send(event)
+        .await;
+
+    endpoint.assert();
+    match result {
+        Err(WebhookError::HttpError(status, _)) => {
+            assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE)
+        }
+        other => panic!("expected HttpError, got {:?}", other),
+    }
+}
+
+#[tokio::test]
+async fn test_client_error_is_not_retried() {
+    let event = WebhookEvent::WithdrawalCompleted(completion(2));
+    let endpoint = endpoint_mock("/hooks/rejected", &event, 400)
+        .expect(1)
+        .create();
+
+    let result = notifier(
+        "/hooks/rejected",
+        vec![WebhookEventType::WithdrawalCompleted],
+    )
+    .send(event)
+    .await;
+
+    endpoint.assert();
+    assert!(
+        matches!(result, Err(WebhookError::HttpError(status, _)) if status == StatusCode::BAD_REQUEST)
+    );
+}
+
+#[tokio::test]
+async fn test_unsubscribed_events_are_not_sent() {
+    let endpoint = mock("POST", "/hooks/filtered").expect(0).create();
+
+    notifier("/hooks/filtered", vec![WebhookEventType::ProofJobFailed])
+        .send(WebhookEvent::WithdrawalCompleted(completion(3)))
+        .await
+        .unwrap();
+
+    endpoint.assert();
+}
+
+#[tokio::test]
+async fn test_completions_published_on_bus_are_sent() {
+    let event = WebhookEvent::WithdrawalCompleted(completion(4));
+    let endpoint = endpoint_mock("/hooks/bus", &event, 200).expect(1).create();
+
+    let bus = EventBus::default();
+    let task = Arc::new(notifier(
+        "/hooks/bus",
+        vec![WebhookEventType::WithdrawalCompleted],
+    ))
+    .subscribe_completions(&bus);
+    bus.publish(completion(4));
+    // Dropping the last publisher closes the subscription once the completion is sent
+    drop(bus);
+
+    tokio::time::timeout(Duration::from_secs(10), task)
+        .await
+        .expect("Subscription should end when the bus closes")
+        .unwrap();
+    endpoint.assert();
+}
@@ -1,21 +1,24 @@
 #[path = "utils.rs"]
 mod utils;
 
-use std::usize;
-
 use axum::{
     body::Body,
     http::{Request, StatusCode},
 };
 use serde_json::json;
+use starknet::core::types::Felt;
 use tower::ServiceExt;
-use utils::create_test_app;
-use zeroxbridge_sequencer::api::routes::create_router;
+use utils::{create_test_app, unique_stark_pub_key};
+use zeroxbridge_sequencer::api::handlers::check_withdrawal_usd_value;
+use zeroxbridge_sequencer::api::routes::{create_router, AppState};
+use zeroxbridge_sequencer::db::database::{
+    fetch_withdrawals_by_status, upsert_token_metadata, TokenMetadata,
+};
 
 #[tokio::test]
 async fn test_post_valid_withdrawal() {
     let app = create_test_app().await;
-    let router = create_router(app.db.clone());
+    let router = create_router(app.as_ref().clone());
 
     let request = Request::builder()
         .method("POST")
@@ -23,10 +26,12 @@ async fn test_post_valid_withdrawal() {
         .header("content-type", "application/json")
         .body(Body::from(
             json!({
-                "stark_pub_key": "0xabc123",
+                "stark_pub_key": unique_stark_pub_key(),
                 "amount": 5000,
-                "commitment_hash": "0xcommitment123",
-                "l1_token": "0xtoken123"  // ADDED: New required field
+                "commitment_hash": "0xc0ffee123",
+                "nonce": 0,
+                "l1_token": "0xtoken123",  // ADDED: New required field
+                "token_amount": "5000"
             })
             .to_string(),
         ))
@@ -46,7 +51,7 @@ async fn test_post_valid_withdrawal() {
 #[tokio::test]
 async fn test_post_invalid_withdrawal() {
     let app = create_test_app().await;
-    let router = create_router(app.db.clone());
+    let router = create_router(app.as_ref().clone());
 
     let request = Request::builder()
         .method("POST")
@@ -56,7 +61,8 @@ async fn test_post_invalid_withdrawal() {
             json!({
                 "stark_pub_key": "",
                 "amount": -10,
-                "commitment_hash": "0xtest123",
+                "commitment_hash": "0x7e57123",
+                "nonce": 0,
                 "l1_token": "0xtoken123"  // ADDED: New required field
             })
             .to_string(),
@@ -83,7 +89,7 @@ async fn test_post_invalid_withdrawal() {
 #[tokio::test]
 async fn test_get_pending_withdrawals() {
     let app = create_test_app().await;
-    let router = create_router(app.db.clone());
+    let router = create_router(app.as_ref().clone());
 
     // ADDED: First create a test withdrawal (following deposit_api.rs pattern)
     let post_request = Request::builder()
@@ -92,10 +98,12 @@ async fn test_get_pending_withdrawals() {
         .header("content-type", "application/json")
         .body(Body::from(
             json!({
-                "stark_pub_key": "0xtest123",
+                "stark_pub_key": unique_stark_pub_key(),
                 "amount": 500,
-                "commitment_hash": "0xcommitment456",
-                "l1_token": "0xtoken789"  // ADDED: New required field
+                "commitment_hash": "0xc0ffee456",
+                "nonce": 0,
+                "l1_token": "0xtoken789",  // ADDED: New required field
+                "token_amount": "500"
             })
             .to_string(),
         ))
@@ -122,3 +130,481 @@ async fn test_get_pending_withdrawals() {
     assert!(!parsed.is_empty());
     assert_eq!(parsed[0]["status"], "pending");
 }
+
+#[tokio::test]
+async fn test_post_withdrawal_rejects_unlisted_token() {
+    let app = create_test_app().await;
+    let router = create_router(app.as_ref().clone());
+
+    let request = Request::builder()
+        .method("POST")
+        .uri("/withdrawals")
+        .header("content-type", "application/json")
+        .body(Body::from(
+            json!({
+                "stark_pub_key": unique_stark_pub_key(),
+                "amount": 5000,
+                "commitment_hash": "0xc0ffee456",
+                "nonce": 0,
+                "l1_token": "0xnotwhitelisted"
+            })
+            .to_string(),
+        ))
+        .unwrap();
+
+    let response = router.oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    assert_eq!(&body[..], b"l1_token is not a whitelisted ERC-20");
+}
+
+#[tokio::test]
+async fn test_post_withdrawal_accepts_whitelisted_token_case_insensitive() {
+    let app = create_test_app().await;
+    let router = create_router(app.as_ref().clone());
+
+    let request = Request::builder()
+        .method("POST")
+        .uri("/withdrawals")
+        .header("content-type", "application/json")
+        .body(Body::from(
+            json!({
+                "stark_pub_key": unique_stark_pub_key(),
+                "amount": 5000,
+                "commitment_hash": "0xc0ffee789",
+                "nonce": 0,
+                "l1_token": "0xa0b86991c6218b36c1d19d4a2e9eb0ce3606eb48",
+                "token_amount": "50000000"
+            })
+            .to_string(),
+        ))
+        .unwrap();
+
+    let response = router.oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+}
+
+#[tokio::test]
+async fn test_get_allowed_tokens() {
+    let app = create_test_app().await;
+    let router = create_router(app.as_ref().clone());
+
+    let request = Request::builder()
+        .method("GET")
+        .uri("/allowed-tokens")
+        .body(Body::empty())
+        .unwrap();
+
+    let response = router.oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let tokens: Vec<String> = serde_json::from_slice(&body).unwrap();
+    assert_eq!(tokens, utils::create_test_config().relayer.allowed_l1_tokens);
+}
+
+#[tokio::test]
+async fn test_withdrawal_stark_pub_key_validation() {
+    let app = create_test_app().await;
+
+    // Fresh keys in hex and decimal form, so each starts at nonce 0
+    let decimal_key = Felt::from_hex(&unique_stark_pub_key()).unwrap().to_string();
+    let cases = [
+        (unique_stark_pub_key(), StatusCode::OK),
+        (decimal_key, StatusCode::OK),
+        (String::new(), StatusCode::BAD_REQUEST),
+        ("garbage".to_string(), StatusCode::UNPROCESSABLE_ENTITY),
+    ];
+
+    for (stark_pub_key, expected) in cases {
+        let router = create_router(app.as_ref().clone());
+        let request = Request::builder()
+            .method("POST")
+            .uri("/withdrawals")
+            .header("content-type", "application/json")
+            .body(Body::from(
+                json!({
+                    "stark_pub_key": stark_pub_key,
+                    "amount": 5000,
+                    "commitment_hash": "0xc0ffee123",
+                    "nonce": 0,
+                    "l1_token": "0xtoken123",
+                    "token_amount": "5000"
+                })
+                .to_string(),
+            ))
+            .unwrap();
+
+        let response = router.oneshot(request).await.unwrap();
+        let status = response.status();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let body_str = String::from_utf8_lossy(&body);
+
+        assert_eq!(
+            status, expected,
+            "stark_pub_key {:?}: {}",
+            stark_pub_key, body_str
+        );
+        if expected == StatusCode::UNPROCESSABLE_ENTITY {
+            assert_eq!(
+                body_str,
+                "stark_pub_key must be a valid felt252 hex or decimal value"
+            );
+        }
+    }
+}
+
+async fn post_withdrawal_with_l2_tx_hash(
+    app: &AppState,
+    l2_tx_hash: &str,
+) -> (StatusCode, Vec<u8>) {
+    let request = Request::builder()
+        .method("POST")
+        .uri("/withdrawals")
+        .header("content-type", "application/json")
+        .body(Body::from(
+            json!({
+                "stark_pub_key": unique_stark_pub_key(),
+                "amount": 5000,
+                "commitment_hash": "0xc0ffee123",
+                "nonce": 0,
+                "l1_token": "0xtoken123",
+                "token_amount": "5000",
+                "l2_tx_hash": l2_tx_hash
+            })
+            .to_string(),
+        ))
+        .unwrap();
+
+    let response = create_router(app.clone()).oneshot(request).await.unwrap();
+    let status = response.status();
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    (status, body.to_vec())
+}
+
+#[tokio::test]
+async fn test_withdrawal_l2_tx_hash_round_trip() {
+    let app = create_test_app().await;
+
+    let (status, body) = post_withdrawal_with_l2_tx_hash(&app, "0x5A1B").await;
+    assert_eq!(status, StatusCode::OK);
+    let parsed: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    let withdrawal_id = parsed["withdrawal_id"].as_i64().unwrap() as i32;
+
+    let withdrawals = fetch_withdrawals_by_status(&app.db, "pending", 1_000)
+        .await
+        .unwrap();
+    let stored = withdrawals
+        .iter()
+        .find(|withdrawal| withdrawal.id == withdrawal_id)
+        .expect("Withdrawal was not stored");
+    assert_eq!(
+        stored.l2_tx_hash.as_deref(),
+        Some("0x0000000000000000000000000000000000000000000000000000000000005a1b")
+    );
+
+    let serialized = serde_json::to_value(stored).unwrap();
+    assert_eq!(serialized["l2_tx_hash"], json!(stored.l2_tx_hash));
+    // The commitment hash is stored without its prefix but keeps it in responses
+    assert_eq!(stored.commitment_hash, format!("{:0>64}", "c0ffee123"));
+    assert_eq!(
+        serialized["commitment_hash"],
+        json!(format!("0x{:0>64}", "c0ffee123"))
+    );
+}
+
+#[tokio::test]
+async fn test_withdrawal_rejects_invalid_l2_tx_hash() {
+    let app = create_test_app().await;
+
+    for l2_tx_hash in ["1234", "0xnothex", ""] {
+        let (status, body) = post_withdrawal_with_l2_tx_hash(&app, l2_tx_hash).await;
+        assert_eq!(
+            status,
+            StatusCode::UNPROCESSABLE_ENTITY,
+            "l2_tx_hash {:?}",
+            l2_tx_hash
+        );
+        assert_eq!(
+            String::from_utf8_lossy(&body),
+            "l2_tx_hash must be a 0x-prefixed felt252 hex value"
+        );
+    }
+}
+
+async fn post_withdrawal_with_precision(
+    app: &AppState,
+    amount_precision: Option<u8>,
+) -> (StatusCode, Vec<u8>) {
+    let mut payload = json!({
+        "stark_pub_key": unique_stark_pub_key(),
+        "amount": 5000,
+        "commitment_hash": "0xc0ffee123",
+        "nonce": 0,
+        "l1_token": "0xtoken123",
+        "token_amount": "5000"
+    });
+    if let Some(precision) = amount_precision {
+        payload["amount_precision"] = json!(precision);
+    }
+    let request = Request::builder()
+        .method("POST")
+        .uri("/withdrawals")
+        .header("content-type", "application/json")
+        .body(Body::from(payload.to_string()))
+        .unwrap();
+
+    let response = create_router(app.clone()).oneshot(request).await.unwrap();
+    let status = response.status();
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    (status, body.to_vec())
+}
+
+#[tokio::test]
+async fn test_withdrawal_amount_precision_is_stored() {
+    let app = create_test_app().await;
+
+    for requested in [Some(2), None] {
+        let (status, body) = post_withdrawal_with_precision(&app, requested).await;
+        assert_eq!(status, StatusCode::OK);
+        let parsed: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        let withdrawal_id = parsed["withdrawal_id"].as_i64().unwrap() as i32;
+
+        let stored: i16 =
+            sqlx::query_scalar("SELECT amount_precision FROM withdrawals WHERE id = $1")
+                .bind(withdrawal_id)
+                .fetch_one(&app.db)
+                .await
+                .unwrap();
+        assert_eq!(stored, 2, "requested {:?}", requested);
+    }
+}
+
+#[tokio::test]
+async fn test_withdrawal_rejects_amount_precision_other_than_token_decimals() {
+    let app = create_test_app().await;
+
+    let (status, body) = post_withdrawal_with_precision(&app, Some(6)).await;
+
+    assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+    assert_eq!(
+        String::from_utf8_lossy(&body),
+        "amount_precision 6 does not match the 2 decimals of TKN"
+    );
+}
+
+#[tokio::test]
+async fn test_withdrawal_rejects_excessive_amount_precision() {
+    let app = create_test_app().await;
+
+    let (status, body) = post_withdrawal_with_precision(&app, Some(19)).await;
+
+    assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+    assert_eq!(
+        String::from_utf8_lossy(&body),
+        "amount_precision must be at most 18, got 19"
+    );
+}
+
+#[tokio::test]
+async fn test_withdrawal_amount_bounds() {
+    let app = create_test_app().await;
+    let cases = [
+        (0i64, "amount must be at least 1 cent"),
+        (
+            1_000_000_000_001,
+            "amount exceeds maximum of 1,000,000,000,000 USD cents",
+        ),
+    ];
+
+    for (amount, expected) in cases {
+        let request = Request::builder()
+            .method("POST")
+            .uri("/withdrawals")
+            .header("content-type", "application/json")
+            .body(Body::from(
+                json!({
+                    "stark_pub_key": unique_stark_pub_key(),
+                    "amount": amount,
+                    "commitment_hash": "0xc0ffee123",
+                    "nonce": 0,
+                    "l1_token": "0xtoken123",
+                    "token_amount": "5000"
+                })
+                .to_string(),
+            ))
+            .unwrap();
+
+        let response = create_router(app.as_ref().clone())
+            .oneshot(request)
+            .await
+            .unwrap();
+        let status = response.status();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+
+        assert_eq!(status, StatusCode::BAD_REQUEST, "amount {}", amount);
+        assert_eq!(String::from_utf8_lossy(&body), expected);
+    }
+}
+
+/// Whitelisted in the test config
+const USDC: &str = "0xA0b86991c6218b36c1d19D4a2e9Eb0cE3606eB48";
+
+fn usdc_metadata() -> TokenMetadata {
+    TokenMetadata {
+        address: USDC.to_lowercase(),
+        symbol: "USDC".to_string(),
+        decimals: 6,
+        usd_price_cents: 100,
+    }
+}
+
+#[test]
+fn test_withdrawal_usd_value_is_checked_against_token_price() {
+    let usdc = usdc_metadata();
+
+    // 1.5 USDC is 150 cents, 1% either way is accepted
+    assert!(check_withdrawal_usd_value(&usdc, Some("1500000"), 150, 100).is_ok());
+    assert!(check_withdrawal_usd_value(&usdc, Some("1500000"), 151, 100).is_ok());
+    assert_eq!(
+        check_withdrawal_usd_value(&usdc, Some("1500000"), 160, 100),
+        Err(
+            "amount of 160 USD cents does not match the 150 cents 1500000 USDC base units are worth"
+                .to_string()
+        )
+    );
+    assert_eq!(
+        check_withdrawal_usd_value(&usdc, None, 150, 100),
+        Err("token_amount is required for USDC withdrawals".to_string())
+    );
+    assert!(check_withdrawal_usd_value(&usdc, Some("1.5"), 150, 100).is_err());
+
+    let weth = TokenMetadata {
+        symbol: "WETH".to_string(),
+        decimals: 18,
+        usd_price_cents: 200_000,
+        ..usdc
+    };
+    // 0.25 WETH at $2,000.00
+    assert!(check_withdrawal_usd_value(&weth, Some("250000000000000000"), 50_000, 0).is_ok());
+}
+
+async fn post_usdc_withdrawal(
+    app: &AppState,
+    amount: i64,
+    token_amount: Option<&str>,
+) -> (StatusCode, String) {
+    let mut payload = json!({
+        "stark_pub_key": unique_stark_pub_key(),
+        "amount": amount,
+        "commitment_hash": format!("0x{}", uuid::Uuid::new_v4().simple()),
+        "nonce": 0,
+        "l1_token": USDC
+    });
+    if let Some(token_amount) = token_amount {
+        payload["token_amount"] = json!(token_amount);
+    }
+    let request = Request::builder()
+        .method("POST")
+        .uri("/withdrawals")
+        .header("content-type", "application/json")
+        .body(Body::from(payload.to_string()))
+        .unwrap();
+
+    let response = create_router(app.clone()).oneshot(request).await.unwrap();
+    let status = response.status();
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    (status, String::from_utf8_lossy(&body).to_string())
+}
+
+#[tokio::test]
+async fn test_withdrawal_of_listed_token_must_match_its_usd_value() {
+    let app = create_test_app().await;
+    upsert_token_metadata(&app.db, &usdc_metadata())
+        .await
+        .unwrap();
+
+    let (status, _) = post_usdc_withdrawal(&app, 150, Some("1500000")).await;
+    assert_eq!(status, StatusCode::OK);
+
+    let (status, body) = post_usdc_withdrawal(&app, 15_000, Some("1500000")).await;
+    assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+    assert_eq!(
+        body,
+        "amount of 15000 USD cents does not match the 150 cents 1500000 USDC base units are worth"
+    );
+
+    let (status, _) = post_usdc_withdrawal(&app, 150, None).await;
+    assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+}
+
+#[tokio::test]
+async fn test_withdrawal_stores_normalized_token_amount() {
+    let app = create_test_app().await;
+
+    let (status, body) = post_usdc_withdrawal(&app, 150, Some(" 0001500000 ")).await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+    let parsed: serde_json::Value = serde_json::from_str(&body).unwrap();
+    let withdrawal_id = parsed["withdrawal_id"].as_i64().unwrap() as i32;
+
+    let (token_amount, amount_precision): (Option<String>, i16) =
+        sqlx::query_as("SELECT token_amount, amount_precision FROM withdrawals WHERE id = $1")
+            .bind(withdrawal_id)
+            .fetch_one(&app.db)
+            .await
+            .unwrap();
+    assert_eq!(token_amount.as_deref(), Some("1500000"));
+    // The precision comes from the token's metadata when the request leaves it out
+    assert_eq!(amount_precision, 6);
+}
+
+#[tokio::test]
+async fn test_withdrawal_rejects_token_without_metadata() {
+    let app = create_test_app().await;
+    let mut config = utils::create_test_config();
+    config
+        .relayer
+        .allowed_l1_tokens
+        .push("0xtokenwithoutmetadata".to_string());
+    let app = AppState::new(app.db.clone(), config);
+
+    let request = Request::builder()
+        .method("POST")
+        .uri("/withdrawals")
+        .header("content-type", "application/json")
+        .body(Body::from(
+            json!({
+                "stark_pub_key": unique_stark_pub_key(),
+                "amount": 5000,
+                "commitment_hash": "0xc0ffee123",
+                "nonce": 0,
+                "l1_token": "0xtokenwithoutmetadata",
+                "token_amount": "5000"
+            })
+            .to_string(),
+        ))
+        .unwrap();
+
+    let response = create_router(app).oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    assert_eq!(&body[..], b"l1_token has no token metadata");
+}
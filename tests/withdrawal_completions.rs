@@ -0,0 +1,117 @@
+#[path = "utils.rs"]
+mod utils;
+
+use mockito::{mock, Matcher, Mock};
+use std::time::Duration;
+use url::Url;
+use utils::create_test_app;
+use zeroxbridge_sequencer::relayer::ethereum_relayer::EthereumRelayer;
+
+const TX_HASH: &str = "0x00000000000000000000000000000000000000000000000000000000000000ab";
+
+fn rpc_mock(method: &str, result: &str) -> Mock {
+    mock("POST", "/")
+        .match_body(Matcher::Regex(method.to_string()))
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(format!(r#"{{"jsonrpc":"2.0","id":0,"result":{}}}"#, result))
+        .create()
+}
+
+async fn insert_ready_withdrawal(pool: &sqlx::PgPool) -> i32 {
+    let id = sqlx::query_scalar!(
+        r#"
+        INSERT INTO withdrawals (stark_pub_key, amount, l1_token, commitment_hash, status)
+        VALUES ('0x123', 1000, '0xtoken', $1, 'ready_for_relay')
+        RETURNING id
+        "#,
+        format!("0x{}", uuid::Uuid::new_v4().simple())
+    )
+    .fetch_one(pool)
+    .await
+    .unwrap();
+
+    sqlx::query!(
+        "INSERT INTO withdrawal_proofs (withdrawal_id, proof_params, proof_data) VALUES ($1, '', '')",
+        id
+    )
+    .execute(pool)
+    .await
+    .unwrap();
+
+    id
+}
+
+#[tokio::test]
+async fn test_relayed_withdrawal_publishes_completion_event() {
+    let app = create_test_app().await;
+    let mut completions = app.subscribe_completions();
+
+    let withdrawal_id = insert_ready_withdrawal(&app.db).await;
+    // Leave withdrawals created by other tests alone
+    let others: Vec<i32> = sqlx::query_scalar!(
+        "SELECT id FROM withdrawals WHERE status = 'ready_for_relay' AND id <> $1",
+        withdrawal_id
+    )
+    .fetch_all(&app.db)
+    .await
+    .unwrap();
+
+    let _mocks = [
+        rpc_mock(
+            "eth_accounts",
+            r#"["0x00000000000000000000000000000000000000cc"]"#,
+        ),
+        rpc_mock("eth_gasPrice", r#""0x3b9aca00""#),
+        rpc_mock("eth_getTransactionCount", r#""0x1""#),
+        rpc_mock("eth_estimateGas", r#""0x5208""#),
+        rpc_mock("eth_sendTransaction", &format!(r#""{}""#, TX_HASH)),
+        rpc_mock(
+            "eth_getTransactionReceipt",
+            &format!(r#"{{"status":"0x1","transactionHash":"{}"}}"#, TX_HASH),
+        ),
+    ];
+
+    let relayer = EthereumRelayer::new(
+        app.db.clone(),
+        Url::parse(&mockito::server_url()).unwrap(),
+        "0x0000000000000000000000000000000000000001",
+        app.config.relayer.clone(),
+    )
+    .await
+    .unwrap()
+    .with_completion_bus(app.withdrawal_completions.clone());
+
+    let relayed = relayer.relay_next_withdrawal(&others).await.unwrap();
+    assert_eq!(relayed, Some(withdrawal_id));
+
+    let event = tokio::time::timeout(Duration::from_secs(5), completions.recv())
+        .await
+        .expect("No completion event was published")
+        .unwrap();
+    assert_eq!(event.withdrawal_id, withdrawal_id);
+    assert_eq!(event.tx_hash, TX_HASH);
+    assert_eq!(event.amount, 1000);
+    assert_eq!(event.stark_pub_key, "0x123");
+
+    let status: String = sqlx::query_scalar!(
+        "SELECT status FROM withdrawals WHERE id = $1",
+        withdrawal_id
+    )
+    .fetch_one(&app.db)
+    .await
+    .unwrap();
+    assert_eq!(status, "relayed");
+
+    sqlx::query!(
+        "DELETE FROM withdrawal_proofs WHERE withdrawal_id = $1",
+        withdrawal_id
+    )
+    .execute(&app.db)
+    .await
+    .unwrap();
+    sqlx::query!("DELETE FROM withdrawals WHERE id = $1", withdrawal_id)
+        .execute(&app.db)
+        .await
+        .unwrap();
+}
@@ -0,0 +1,95 @@
+#[path = "utils.rs"]
+mod utils;
+
+use axum::{
+    body::{to_bytes, Body},
+    http::{Request, StatusCode},
+};
+use serde_json::Value;
+use tower::ServiceExt;
+use utils::create_test_app;
+use zeroxbridge_sequencer::api::routes::{create_router, AppState};
+use zeroxbridge_sequencer::db::database::{update_withdrawal_gas_stats, GasMetrics};
+
+async fn insert_withdrawal(pool: &sqlx::PgPool, status: &str) -> i32 {
+    sqlx::query_scalar!(
+        r#"
+        INSERT INTO withdrawals (stark_pub_key, amount, l1_token, commitment_hash, status)
+        VALUES ('0x123', 1000, '0xtoken', $1, $2)
+        RETURNING id
+        "#,
+        format!("0x{}", uuid::Uuid::new_v4().simple()),
+        status
+    )
+    .fetch_one(pool)
+    .await
+    .unwrap()
+}
+
+async fn get_json(app: &AppState, uri: &str) -> (StatusCode, Value) {
+    let request = Request::builder()
+        .method("GET")
+        .uri(uri)
+        .body(Body::empty())
+        .unwrap();
+    let response = create_router(app.clone()).oneshot(request).await.unwrap();
+    let status = response.status();
+    let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    (status, serde_json::from_slice(&body).unwrap_or(Value::Null))
+}
+
+#[tokio::test]
+async fn test_withdrawal_reports_total_gas_spent() {
+    let app = create_test_app().await;
+    let id = insert_withdrawal(&app.db, "relayed").await;
+
+    let (status, body) = get_json(&app, &format!("/withdrawals/{}", id)).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["id"], id);
+    assert_eq!(body["total_gas_spent_gwei"], Value::Null);
+
+    let mut conn = app.db.acquire().await.unwrap();
+    update_withdrawal_gas_stats(&mut conn, id, Some(21_000), Some(20))
+        .await
+        .unwrap();
+
+    let (status, body) = get_json(&app, &format!("/withdrawals/{}", id)).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["gas_used"], 21_000);
+    assert_eq!(body["gas_price_gwei"], 20);
+    assert_eq!(body["total_gas_spent_gwei"], 420_000);
+}
+
+#[tokio::test]
+async fn test_unknown_withdrawal_is_not_found() {
+    let app = create_test_app().await;
+
+    let (status, _) = get_json(&app, &format!("/withdrawals/{}", i32::MAX)).await;
+
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn test_gas_metrics_only_count_relayed_withdrawals() {
+    let app = create_test_app().await;
+    let (_, before) = get_json(&app, "/metrics/gas").await;
+    let before: GasMetrics = serde_json::from_value(before).unwrap();
+
+    let relayed = insert_withdrawal(&app.db, "relayed").await;
+    let pending = insert_withdrawal(&app.db, "pending").await;
+    let mut conn = app.db.acquire().await.unwrap();
+    for id in [relayed, pending] {
+        update_withdrawal_gas_stats(&mut conn, id, Some(50_000), Some(3))
+            .await
+            .unwrap();
+    }
+
+    let (status, after) = get_json(&app, "/metrics/gas").await;
+    assert_eq!(status, StatusCode::OK);
+    let after: GasMetrics = serde_json::from_value(after).unwrap();
+    assert!(after.average_gas_used.is_some());
+    assert_eq!(
+        after.total_eth_spent_wei - before.total_eth_spent_wei,
+        50_000 * 3 * 1_000_000_000
+    );
+}
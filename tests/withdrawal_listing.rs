@@ -0,0 +1,328 @@
+#[path = "utils.rs"]
+mod utils;
+
+use axum::{
+    body::{to_bytes, Body},
+    http::{Request, StatusCode},
+};
+use starknet::core::types::Felt;
+use tower::ServiceExt;
+use utils::create_test_app;
+use zeroxbridge_sequencer::api::routes::{create_router, AppState};
+use zeroxbridge_sequencer::db::database::{
+    fetch_withdrawals_by_stark_pub_key, insert_withdrawal, list_withdrawals, PaginatedResult,
+    PaginationParams, SortField, Withdrawal, WithdrawalFilter, WithdrawalStatus,
+};
+use zeroxbridge_sequencer::utils::canonical_felt_hex;
+
+/// A stark key no other test uses, so each test only sees its own withdrawals
+fn unique_stark_pub_key() -> String {
+    let key = Felt::from_hex(&format!("0x{}", uuid::Uuid::new_v4().simple())).unwrap();
+    canonical_felt_hex(key)
+}
+
+async fn seed_withdrawal(
+    pool: &sqlx::PgPool,
+    stark_pub_key: &str,
+    amount: i64,
+    status: &str,
+) -> i32 {
+    let commitment_hash = format!("0x{}", uuid::Uuid::new_v4().simple());
+    let id = insert_withdrawal(
+        pool,
+        stark_pub_key,
+        amount,
+        "0xtoken123",
+        &commitment_hash,
+        None,
+        2,
+    )
+    .await
+    .unwrap();
+    sqlx::query!(
+        "UPDATE withdrawals SET status = $2 WHERE id = $1",
+        id,
+        status
+    )
+    .execute(pool)
+    .await
+    .unwrap();
+    id
+}
+
+fn filter_for(stark_pub_key: &str) -> WithdrawalFilter {
+    WithdrawalFilter {
+        status: None,
+        stark_pub_key: Some(stark_pub_key.to_string()),
+        min_amount: None,
+        max_amount: None,
+        sort_by: SortField::CreatedAt,
+        page: PaginationParams {
+            page: 1,
+            per_page: 50,
+        },
+    }
+}
+
+fn ids(result: &PaginatedResult<Withdrawal>) -> Vec<i32> {
+    result.items.iter().map(|w| w.id).collect()
+}
+
+async fn get_withdrawals(app: &AppState, query: &str) -> (StatusCode, Vec<u8>) {
+    let request = Request::builder()
+        .uri(format!("/v2/withdrawals?{}", query))
+        .body(Body::empty())
+        .unwrap();
+
+    let response = create_router(app.clone()).oneshot(request).await.unwrap();
+    let status = response.status();
+    let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    (status, body.to_vec())
+}
+
+#[tokio::test]
+async fn test_list_withdrawals_filters_by_status_and_amount() {
+    let app = create_test_app().await;
+    let key = unique_stark_pub_key();
+    let pending = seed_withdrawal(&app.db, &key, 100, "pending").await;
+    let relayed_small = seed_withdrawal(&app.db, &key, 200, "relayed").await;
+    let relayed_large = seed_withdrawal(&app.db, &key, 300, "relayed").await;
+
+    let all = list_withdrawals(&app.db, filter_for(&key)).await.unwrap();
+    assert_eq!(ids(&all), vec![pending, relayed_small, relayed_large]);
+
+    let relayed = list_withdrawals(
+        &app.db,
+        WithdrawalFilter {
+            status: Some(WithdrawalStatus::Relayed),
+            ..filter_for(&key)
+        },
+    )
+    .await
+    .unwrap();
+    assert_eq!(ids(&relayed), vec![relayed_small, relayed_large]);
+    assert_eq!(relayed.total, 2);
+
+    // Amount bounds are inclusive
+    let bounded = list_withdrawals(
+        &app.db,
+        WithdrawalFilter {
+            min_amount: Some(100),
+            max_amount: Some(200),
+            ..filter_for(&key)
+        },
+    )
+    .await
+    .unwrap();
+    assert_eq!(ids(&bounded), vec![pending, relayed_small]);
+
+    let none = list_withdrawals(
+        &app.db,
+        WithdrawalFilter {
+            status: Some(WithdrawalStatus::Failed),
+            ..filter_for(&key)
+        },
+    )
+    .await
+    .unwrap();
+    assert!(none.items.is_empty());
+    assert_eq!(none.total, 0);
+}
+
+#[tokio::test]
+async fn test_list_withdrawals_sorts_by_amount_or_creation() {
+    let app = create_test_app().await;
+    let key = unique_stark_pub_key();
+    let large = seed_withdrawal(&app.db, &key, 900, "pending").await;
+    let small = seed_withdrawal(&app.db, &key, 100, "pending").await;
+    let medium = seed_withdrawal(&app.db, &key, 500, "pending").await;
+
+    let by_creation = list_withdrawals(&app.db, filter_for(&key)).await.unwrap();
+    assert_eq!(ids(&by_creation), vec![large, small, medium]);
+
+    let by_amount = list_withdrawals(
+        &app.db,
+        WithdrawalFilter {
+            sort_by: SortField::Amount,
+            ..filter_for(&key)
+        },
+    )
+    .await
+    .unwrap();
+    assert_eq!(ids(&by_amount), vec![small, medium, large]);
+}
+
+#[tokio::test]
+async fn test_list_withdrawals_paginates() {
+    let app = create_test_app().await;
+    let key = unique_stark_pub_key();
+    let mut seeded = Vec::new();
+    for amount in 1..=5 {
+        seeded.push(seed_withdrawal(&app.db, &key, amount, "pending").await);
+    }
+
+    let mut listed = Vec::new();
+    for page in 1..=3 {
+        let result = list_withdrawals(
+            &app.db,
+            WithdrawalFilter {
+                page: PaginationParams { page, per_page: 2 },
+                ..filter_for(&key)
+            },
+        )
+        .await
+        .unwrap();
+        assert_eq!(result.total, 5);
+        assert_eq!(result.page, page);
+        assert_eq!(result.per_page, 2);
+        assert_eq!(result.items.len(), if page == 3 { 1 } else { 2 });
+        listed.extend(ids(&result));
+    }
+    assert_eq!(listed, seeded);
+
+    let past_end = list_withdrawals(
+        &app.db,
+        WithdrawalFilter {
+            page: PaginationParams {
+                page: 4,
+                per_page: 2,
+            },
+            ..filter_for(&key)
+        },
+    )
+    .await
+    .unwrap();
+    assert!(past_end.items.is_empty());
+    assert_eq!(past_end.total, 5);
+}
+
+#[tokio::test]
+async fn test_get_withdrawals_applies_query_parameters() {
+    let app = create_test_app().await;
+    let key = unique_stark_pub_key();
+    seed_withdrawal(&app.db, &key, 300, "relayed").await;
+    let small = seed_withdrawal(&app.db, &key, 100, "relayed").await;
+    seed_withdrawal(&app.db, &key, 200, "pending").await;
+
+    // The key is matched in canonical form, whichever form the query uses
+    let decimal_key = Felt::from_hex(&key).unwrap().to_string();
+    let (status, body) = get_withdrawals(
+        &app,
+        &format!(
+            "stark_pub_key={}&status=relayed&sort_by=amount&page=1&per_page=1",
+            decimal_key
+        ),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    let result: PaginatedResult<Withdrawal> = serde_json::from_slice(&body).unwrap();
+    assert_eq!(ids(&result), vec![small]);
+    assert_eq!(result.total, 2);
+}
+
+#[tokio::test]
+async fn test_get_withdrawals_rejects_invalid_parameters() {
+    let app = create_test_app().await;
+
+    for query in [
+        "status=teleported",
+        "sort_by=fee",
+        "page=0",
+        "per_page=0",
+        "per_page=501",
+        "min_amount=10&max_amount=5",
+    ] {
+        let (status, _) = get_withdrawals(&app, query).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST, "{}", query);
+    }
+
+    let (status, _) = get_withdrawals(&app, "stark_pub_key=not-a-key").await;
+    assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+}
+
+async fn get_user_withdrawals(app: &AppState, path: &str) -> (StatusCode, Vec<u8>) {
+    let request = Request::builder()
+        .uri(format!("/users/{}", path))
+        .body(Body::empty())
+        .unwrap();
+
+    let response = create_router(app.clone()).oneshot(request).await.unwrap();
+    let status = response.status();
+    let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    (status, body.to_vec())
+}
+
+#[tokio::test]
+async fn test_withdrawal_history_pages_through_one_users_withdrawals() {
+    let app = create_test_app().await;
+    let key = unique_stark_pub_key();
+    let other_key = unique_stark_pub_key();
+    let mut seeded = Vec::new();
+    for amount in 1..=5 {
+        seeded.push(seed_withdrawal(&app.db, &key, amount, "pending").await);
+        seed_withdrawal(&app.db, &other_key, amount, "pending").await;
+    }
+    // Newest first
+    seeded.reverse();
+
+    let mut listed = Vec::new();
+    let mut after = None;
+    for page in 1..=3 {
+        let result = fetch_withdrawals_by_stark_pub_key(&app.db, &key, 2, after)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(result.total, 5);
+        assert_eq!(result.page, page);
+        assert_eq!(result.per_page, 2);
+        assert!(result.items.iter().all(|w| w.stark_pub_key == key));
+        listed.extend(ids(&result));
+        after = result.next_after;
+        assert_eq!(after.is_none(), page == 3);
+    }
+    assert_eq!(listed, seeded);
+}
+
+#[tokio::test]
+async fn test_get_user_withdrawals_follows_after_cursor() {
+    let app = create_test_app().await;
+    let key = unique_stark_pub_key();
+    let first = seed_withdrawal(&app.db, &key, 100, "pending").await;
+    let second = seed_withdrawal(&app.db, &key, 200, "relayed").await;
+    let other = seed_withdrawal(&app.db, &unique_stark_pub_key(), 300, "pending").await;
+
+    // The path key is matched in canonical form, whichever form the request uses
+    let decimal_key = Felt::from_hex(&key).unwrap().to_string();
+    let (status, body) =
+        get_user_withdrawals(&app, &format!("{}/withdrawals?limit=1", decimal_key)).await;
+    assert_eq!(status, StatusCode::OK);
+    let result: PaginatedResult<Withdrawal> = serde_json::from_slice(&body).unwrap();
+    assert_eq!(ids(&result), vec![second]);
+    assert_eq!(result.total, 2);
+    assert_eq!(result.next_after, Some(second));
+
+    let (status, body) = get_user_withdrawals(
+        &app,
+        &format!("{}/withdrawals?limit=1&after={}", key, second),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    let result: PaginatedResult<Withdrawal> = serde_json::from_slice(&body).unwrap();
+    assert_eq!(ids(&result), vec![first]);
+    assert_eq!(result.next_after, None);
+
+    // A cursor that isn't one of the user's withdrawals is an error, not an empty page
+    for after in [i32::MAX, other] {
+        let (status, _) = get_user_withdrawals(
+            &app,
+            &format!("{}/withdrawals?limit=1&after={}", key, after),
+        )
+        .await;
+        assert_eq!(status, StatusCode::BAD_REQUEST, "after {}", after);
+    }
+
+    let (status, _) = get_user_withdrawals(&app, &format!("{}/withdrawals?limit=0", key)).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    let (status, _) = get_user_withdrawals(&app, "not-a-key/withdrawals").await;
+    assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+}
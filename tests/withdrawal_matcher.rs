@@ -0,0 +1,215 @@
+#[path = "utils.rs"]
+mod utils;
+
+use utils::create_test_app;
+use zeroxbridge_sequencer::db::database::{
+    fetch_withdrawal_events, insert_l2_burn_event, insert_withdrawal,
+};
+use zeroxbridge_sequencer::events::CommitmentLog;
+use zeroxbridge_sequencer::queue::withdrawal_matcher::WithdrawalMatcher;
+use zeroxbridge_sequencer::utils::normalize_commitment_hash;
+
+fn random_felt() -> String {
+    format!("0x{:x}", rand::random::<u64>())
+}
+
+fn burn_log(user: &str, commitment_hash: &str) -> CommitmentLog {
+    CommitmentLog {
+        commitment_hash: commitment_hash.to_string(),
+        block_number: 42,
+        transaction_hash: random_felt(),
+        user: user.to_string(),
+        amount_low: "0x3e8".to_string(),
+        amount_high: "0x0".to_string(),
+    }
+}
+
+async fn burn_id(pool: &sqlx::PgPool, commitment_hash: &str) -> i32 {
+    sqlx::query_scalar!(
+        "SELECT id FROM l2_burn_events WHERE commitment_hash = $1",
+        normalize_commitment_hash(commitment_hash).unwrap()
+    )
+    .fetch_one(pool)
+    .await
+    .unwrap()
+}
+
+async fn withdrawal_link(pool: &sqlx::PgPool, id: i32) -> (String, Option<i32>) {
+    let row = sqlx::query!("SELECT status, l2_tx_id FROM withdrawals WHERE id = $1", id)
+        .fetch_one(pool)
+        .await
+        .unwrap();
+    (row.status, row.l2_tx_id)
+}
+
+async fn cleanup(pool: &sqlx::PgPool, withdrawal_ids: &[i32], commitment_hashes: &[String]) {
+    sqlx::query!("DELETE FROM withdrawals WHERE id = ANY($1)", withdrawal_ids)
+        .execute(pool)
+        .await
+        .unwrap();
+    let commitment_hashes: Vec<String> = commitment_hashes
+        .iter()
+        .map(|hash| normalize_commitment_hash(hash).unwrap())
+        .collect();
+    sqlx::query!(
+        "DELETE FROM l2_burn_events WHERE commitment_hash = ANY($1)",
+        &commitment_hashes
+    )
+    .execute(pool)
+    .await
+    .unwrap();
+}
+
+#[tokio::test]
+async fn test_matching_burn_links_pending_withdrawal() {
+    let app = create_test_app().await;
+    let user = random_felt();
+    let commitment_hash = random_felt();
+    let withdrawal_id = insert_withdrawal(
+        &app.db,
+        &user,
+        1_000,
+        "0xtoken123",
+        &commitment_hash,
+        None,
+        2,
+    )
+    .await
+    .unwrap();
+    insert_l2_burn_event(&app.db, &burn_log(&user, &commitment_hash), 1_000)
+        .await
+        .unwrap();
+
+    let matched = WithdrawalMatcher::new(app.db.clone())
+        .match_pending()
+        .await
+        .unwrap();
+
+    assert!(matched >= 1);
+    let burn_id = burn_id(&app.db, &commitment_hash).await;
+    assert_eq!(
+        withdrawal_link(&app.db, withdrawal_id).await,
+        ("matched".to_string(), Some(burn_id))
+    );
+    let timeline = fetch_withdrawal_events(&app.db, withdrawal_id)
+        .await
+        .unwrap()
+        .unwrap();
+    let recorded: Vec<(&str, &str, &str)> = timeline
+        .iter()
+        .map(|e| {
+            (
+                e.old_status.as_str(),
+                e.new_status.as_str(),
+                e.actor.as_str(),
+            )
+        })
+        .collect();
+    assert_eq!(recorded, vec![("pending", "matched", "withdrawal_matcher")]);
+
+    cleanup(&app.db, &[withdrawal_id], &[commitment_hash]).await;
+}
+
+#[tokio::test]
+async fn test_mismatched_fields_leave_withdrawal_pending() {
+    let app = create_test_app().await;
+    let user = random_felt();
+    let other_amount_hash = random_felt();
+    let other_user_hash = random_felt();
+    let other_amount = insert_withdrawal(
+        &app.db,
+        &user,
+        999,
+        "0xtoken123",
+        &other_amount_hash,
+        None,
+        2,
+    )
+    .await
+    .unwrap();
+    let other_user = insert_withdrawal(
+        &app.db,
+        &user,
+        1_000,
+        "0xtoken123",
+        &other_user_hash,
+        None,
+        2,
+    )
+    .await
+    .unwrap();
+    insert_l2_burn_event(&app.db, &burn_log(&user, &other_amount_hash), 1_000)
+        .await
+        .unwrap();
+    insert_l2_burn_event(&app.db, &burn_log(&random_felt(), &other_user_hash), 1_000)
+        .await
+        .unwrap();
+
+    WithdrawalMatcher::new(app.db.clone())
+        .match_pending()
+        .await
+        .unwrap();
+
+    for id in [other_amount, other_user] {
+        assert_eq!(
+            withdrawal_link(&app.db, id).await,
+            ("pending".to_string(), None)
+        );
+    }
+
+    cleanup(
+        &app.db,
+        &[other_amount, other_user],
+        &[other_amount_hash, other_user_hash],
+    )
+    .await;
+}
+
+#[tokio::test]
+async fn test_burn_is_linked_to_one_withdrawal_only() {
+    let app = create_test_app().await;
+    let user = random_felt();
+    let commitment_hash = random_felt();
+    let first = insert_withdrawal(
+        &app.db,
+        &user,
+        1_000,
+        "0xtoken123",
+        &commitment_hash,
+        None,
+        2,
+    )
+    .await
+    .unwrap();
+    let second = insert_withdrawal(
+        &app.db,
+        &user,
+        1_000,
+        "0xtoken123",
+        &commitment_hash,
+        None,
+        2,
+    )
+    .await
+    .unwrap();
+    let burn = burn_log(&user, &commitment_hash);
+    insert_l2_burn_event(&app.db, &burn, 1_000).await.unwrap();
+    // A replayed event is not stored twice
+    insert_l2_burn_event(&app.db, &burn, 1_000).await.unwrap();
+
+    let matcher = WithdrawalMatcher::new(app.db.clone());
+    matcher.match_pending().await.unwrap();
+    matcher.match_pending().await.unwrap();
+
+    let burn_id = burn_id(&app.db, &commitment_hash).await;
+    assert_eq!(
+        withdrawal_link(&app.db, first).await,
+        ("matched".to_string(), Some(burn_id))
+    );
+    assert_eq!(
+        withdrawal_link(&app.db, second).await,
+        ("pending".to_string(), None)
+    );
+
+    cleanup(&app.db, &[first, second], &[commitment_hash]).await;
+}
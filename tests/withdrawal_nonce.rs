@@ -0,0 +1,189 @@
+#[path = "utils.rs"]
+mod utils;
+
+use axum::{
+    body::{to_bytes, Body},
+    http::{Request, StatusCode},
+};
+use serde_json::{json, Value};
+use starknet::core::types::Felt;
+use tower::ServiceExt;
+use utils::{create_test_app, unique_stark_pub_key};
+use zeroxbridge_sequencer::api::routes::{create_router, AppState};
+use zeroxbridge_sequencer::db::database::{
+    fetch_withdrawal_by_id, insert_withdrawal_with_next_nonce, next_nonce_for_user,
+};
+
+fn unique_commitment_hash() -> String {
+    format!("0x{}", uuid::Uuid::new_v4().simple())
+}
+
+async fn send(app: &AppState, request: Request<Body>) -> (StatusCode, Value) {
+    let response = create_router(app.clone()).oneshot(request).await.unwrap();
+    let status = response.status();
+    let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    // Errors come back as plain text
+    let body = serde_json::from_slice(&body)
+        .unwrap_or_else(|_| Value::String(String::from_utf8_lossy(&body).into_owned()));
+    (status, body)
+}
+
+async fn post_withdrawal_with_nonce(
+    app: &AppState,
+    stark_pub_key: &str,
+    nonce: Option<u64>,
+) -> (StatusCode, Value) {
+    let mut payload = json!({
+        "stark_pub_key": stark_pub_key,
+        "amount": 5000,
+        "commitment_hash": unique_commitment_hash(),
+        "l1_token": "0xtoken123",
+        "token_amount": "5000"
+    });
+    if let Some(nonce) = nonce {
+        payload["nonce"] = json!(nonce);
+    }
+    let request = Request::builder()
+        .method("POST")
+        .uri("/withdrawals")
+        .header("content-type", "application/json")
+        .body(Body::from(payload.to_string()))
+        .unwrap();
+
+    send(app, request).await
+}
+
+async fn get_next_nonce(app: &AppState, stark_pub_key: &str) -> (StatusCode, Value) {
+    let request = Request::builder()
+        .uri(format!("/users/{}/next-nonce", stark_pub_key))
+        .body(Body::empty())
+        .unwrap();
+
+    send(app, request).await
+}
+
+#[tokio::test]
+async fn test_nonces_are_sequential_per_user() {
+    let app = create_test_app().await;
+    let key = unique_stark_pub_key();
+    let other_key = unique_stark_pub_key();
+
+    let mut nonces = Vec::new();
+    for next in 0..3 {
+        let (_, nonce) = insert_withdrawal_with_next_nonce(
+            &app.db,
+            &key,
+            1000,
+            "0xtoken123",
+            &unique_commitment_hash(),
+            None,
+            2,
+            "1000",
+            next,
+        )
+        .await
+        .unwrap();
+        nonces.push(nonce);
+    }
+    assert_eq!(nonces, vec![0, 1, 2]);
+
+    // Another user's sequence is independent of the first one's
+    let (_, nonce) = insert_withdrawal_with_next_nonce(
+        &app.db,
+        &other_key,
+        1000,
+        "0xtoken123",
+        &unique_commitment_hash(),
+        None,
+        2,
+        "1000",
+        0,
+    )
+    .await
+    .unwrap();
+    assert_eq!(nonce, 0);
+
+    let mut conn = app.db.acquire().await.unwrap();
+    assert_eq!(next_nonce_for_user(&mut conn, &key).await.unwrap(), 3);
+    assert_eq!(next_nonce_for_user(&mut conn, &other_key).await.unwrap(), 1);
+}
+
+#[tokio::test]
+async fn test_post_withdrawal_stores_the_next_nonce() {
+    let app = create_test_app().await;
+    let key = unique_stark_pub_key();
+
+    let (status, first) = post_withdrawal_with_nonce(&app, &key, Some(0)).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(first["nonce"], 0);
+
+    // The same user in decimal form continues the same sequence
+    let decimal_key = Felt::from_hex(&key).unwrap().to_string();
+    let (status, second) = post_withdrawal_with_nonce(&app, &decimal_key, Some(1)).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(second["nonce"], 1);
+
+    let (status, other) = post_withdrawal_with_nonce(&app, &unique_stark_pub_key(), Some(0)).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(other["nonce"], 0);
+
+    let id = second["withdrawal_id"].as_i64().unwrap() as i32;
+    let stored = fetch_withdrawal_by_id(&app.db, id).await.unwrap().unwrap();
+    assert_eq!(stored.nonce, Some(1));
+}
+
+#[tokio::test]
+async fn test_next_nonce_endpoint_hands_out_the_nonce_to_hash() {
+    let app = create_test_app().await;
+    let key = unique_stark_pub_key();
+
+    let (status, body) = get_next_nonce(&app, &key).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["nonce"], 0);
+
+    // Asking again does not use the nonce up
+    let (_, body) = get_next_nonce(&app, &key).await;
+    assert_eq!(body["nonce"], 0);
+
+    let (status, created) = post_withdrawal_with_nonce(&app, &key, Some(0)).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(created["nonce"], 0);
+
+    let (_, body) = get_next_nonce(&app, &key).await;
+    assert_eq!(body["nonce"], 1);
+
+    let (status, _) = get_next_nonce(&app, "not-a-key").await;
+    assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+}
+
+#[tokio::test]
+async fn test_post_withdrawal_rejects_a_stale_nonce() {
+    let app = create_test_app().await;
+    let key = unique_stark_pub_key();
+
+    // Two clients hash the same nonce; only the first withdrawal gets it
+    let (status, _) = post_withdrawal_with_nonce(&app, &key, Some(0)).await;
+    assert_eq!(status, StatusCode::OK);
+
+    let (status, body) = post_withdrawal_with_nonce(&app, &key, Some(0)).await;
+    assert_eq!(status, StatusCode::CONFLICT);
+    assert_eq!(
+        body,
+        "nonce 0 was already used, the next nonce of this user is 1"
+    );
+
+    // Nonces ahead of the sequence are refused as well
+    let (status, body) = post_withdrawal_with_nonce(&app, &key, Some(5)).await;
+    assert_eq!(status, StatusCode::CONFLICT);
+    assert_eq!(
+        body,
+        "nonce 5 is ahead of the sequence, the next nonce of this user is 1"
+    );
+
+    // Without its nonce the withdrawal's commitment can't be tied to the stored one
+    let (status, _) = post_withdrawal_with_nonce(&app, &key, None).await;
+    assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+
+    let mut conn = app.db.acquire().await.unwrap();
+    assert_eq!(next_nonce_for_user(&mut conn, &key).await.unwrap(), 1);
+}
@@ -0,0 +1,142 @@
+#[path = "utils.rs"]
+mod utils;
+
+use axum::{
+    body::{to_bytes, Body},
+    http::{Request, StatusCode},
+};
+use chrono::{Duration, TimeZone, Utc};
+use tower::ServiceExt;
+use utils::create_test_app;
+use zeroxbridge_sequencer::api::handlers::{WithdrawalProofStatus, WithdrawalProofStatusResponse};
+use zeroxbridge_sequencer::api::routes::{create_router, AppState};
+use zeroxbridge_sequencer::db::database::record_system_stat_sample;
+use zeroxbridge_sequencer::workers::proof_generation::ATLANTIC_JOB_DURATION_STAT;
+
+async fn insert_withdrawal(pool: &sqlx::PgPool, status: &str) -> i32 {
+    sqlx::query_scalar!(
+        r#"
+        INSERT INTO withdrawals (stark_pub_key, amount, l1_token, commitment_hash, status)
+        VALUES ('0x123', 1000, '0xtoken', $1, $2)
+        RETURNING id
+        "#,
+        format!("0x{}", uuid::Uuid::new_v4().simple()),
+        status
+    )
+    .fetch_one(pool)
+    .await
+    .unwrap()
+}
+
+async fn insert_proof(pool: &sqlx::PgPool, withdrawal_id: i32, status: &str) -> String {
+    let atlantic_job_id = format!("01JPROOFSTATUS{}", withdrawal_id);
+    sqlx::query!(
+        r#"
+        INSERT INTO withdrawal_proofs (withdrawal_id, atlantic_job_id, status, submitted_at)
+        VALUES ($1, $2, $3, NOW())
+        "#,
+        withdrawal_id,
+        atlantic_job_id,
+        status
+    )
+    .execute(pool)
+    .await
+    .unwrap();
+    atlantic_job_id
+}
+
+async fn get_proof_status(
+    app: &AppState,
+    id: i32,
+) -> (StatusCode, Option<WithdrawalProofStatusResponse>) {
+    let request = Request::builder()
+        .method("GET")
+        .uri(format!("/withdrawals/{}/proof-status", id))
+        .body(Body::empty())
+        .unwrap();
+    let response = create_router(app.clone()).oneshot(request).await.unwrap();
+    let status = response.status();
+    let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    (status, serde_json::from_slice(&body).ok())
+}
+
+#[tokio::test]
+async fn test_proof_status_for_each_stage() {
+    let app = create_test_app().await;
+    // Guarantees an average exists, so pending and submitted proofs get an estimate
+    let mut conn = app.db.acquire().await.unwrap();
+    record_system_stat_sample(&mut conn, ATLANTIC_JOB_DURATION_STAT, 600.0)
+        .await
+        .unwrap();
+
+    let pending = insert_withdrawal(&app.db, "pending").await;
+    let (status, body) = get_proof_status(&app, pending).await;
+    assert_eq!(status, StatusCode::OK);
+    let body = body.unwrap();
+    assert_eq!(body.withdrawal_id, pending);
+    assert_eq!(body.status, WithdrawalProofStatus::PendingProof);
+    assert_eq!(body.atlantic_job_id, None);
+    assert!(body.estimated_completion_minutes.is_some());
+
+    let submitted = insert_withdrawal(&app.db, "proof_submitted").await;
+    let job_id = insert_proof(&app.db, submitted, "proof_submitted").await;
+    let body = get_proof_status(&app, submitted).await.1.unwrap();
+    assert_eq!(body.status, WithdrawalProofStatus::ProofSubmitted);
+    assert_eq!(body.atlantic_job_id, Some(job_id));
+    assert!(body.estimated_completion_minutes.is_some());
+
+    let verified = insert_withdrawal(&app.db, "ready_for_relay").await;
+    let job_id = insert_proof(&app.db, verified, "ready").await;
+    let body = get_proof_status(&app, verified).await.1.unwrap();
+    assert_eq!(body.status, WithdrawalProofStatus::ProofVerified);
+    assert_eq!(body.atlantic_job_id, Some(job_id));
+    assert_eq!(body.estimated_completion_minutes, Some(0));
+
+    let failed = insert_withdrawal(&app.db, "proof_failed").await;
+    insert_proof(&app.db, failed, "proof_failed").await;
+    let body = get_proof_status(&app, failed).await.1.unwrap();
+    assert_eq!(body.status, WithdrawalProofStatus::ProofFailed);
+    assert_eq!(body.estimated_completion_minutes, None);
+}
+
+#[tokio::test]
+async fn test_proof_status_unknown_withdrawal() {
+    let app = create_test_app().await;
+
+    let (status, _) = get_proof_status(&app, -1).await;
+
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
+
+#[test]
+fn test_estimate_subtracts_time_since_submission() {
+    let now = Utc.with_ymd_and_hms(2025, 8, 21, 12, 0, 0).unwrap();
+    let submitted_at = Some(now - Duration::minutes(4));
+
+    let estimate = |status: WithdrawalProofStatus, average: Option<f64>| {
+        status.estimate_completion_minutes(submitted_at, average, now)
+    };
+
+    assert_eq!(
+        estimate(WithdrawalProofStatus::PendingProof, Some(601.0)),
+        Some(11)
+    );
+    assert_eq!(
+        estimate(WithdrawalProofStatus::ProofSubmitted, Some(600.0)),
+        Some(6)
+    );
+    // Running past the average never yields a negative estimate
+    assert_eq!(
+        estimate(WithdrawalProofStatus::ProofSubmitted, Some(60.0)),
+        Some(0)
+    );
+    assert_eq!(estimate(WithdrawalProofStatus::ProofSubmitted, None), None);
+    assert_eq!(
+        estimate(WithdrawalProofStatus::ProofVerified, None),
+        Some(0)
+    );
+    assert_eq!(
+        estimate(WithdrawalProofStatus::ProofFailed, Some(600.0)),
+        None
+    );
+}
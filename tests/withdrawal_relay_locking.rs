@@ -0,0 +1,102 @@
+#[path = "utils.rs"]
+mod utils;
+
+use utils::{create_test_app, TestTransaction};
+use zeroxbridge_sequencer::relayer::ethereum_relayer::fetch_ready_for_relay_withdrawals;
+
+const MAX_RETRIES: i32 = 3;
+
+async fn insert_ready_withdrawal(conn: &mut sqlx::PgConnection) -> i32 {
+    let id = sqlx::query_scalar!(
+        r#"
+        INSERT INTO withdrawals (stark_pub_key, amount, l1_token, commitment_hash, status)
+        VALUES ('0x123', 1000, '0xtoken', $1, 'ready_for_relay')
+        RETURNING id
+        "#,
+        format!("0x{}", uuid::Uuid::new_v4().simple())
+    )
+    .fetch_one(&mut *conn)
+    .await
+    .unwrap();
+
+    sqlx::query!(
+        "INSERT INTO withdrawal_proofs (withdrawal_id, proof_params, proof_data) VALUES ($1, '', '')",
+        id
+    )
+    .execute(conn)
+    .await
+    .unwrap();
+
+    id
+}
+
+async fn delete_withdrawals(pool: &sqlx::PgPool, ids: &[i32]) {
+    sqlx::query!(
+        "DELETE FROM withdrawal_proofs WHERE withdrawal_id = ANY($1)",
+        ids
+    )
+    .execute(pool)
+    .await
+    .unwrap();
+    sqlx::query!("DELETE FROM withdrawals WHERE id = ANY($1)", ids)
+        .execute(pool)
+        .await
+        .unwrap();
+}
+
+/// Ids of every withdrawal `conn` can claim, locking them until its transaction ends
+async fn claim_all(conn: &mut sqlx::PgConnection) -> Vec<i32> {
+    fetch_ready_for_relay_withdrawals(conn, MAX_RETRIES, &[], 1_000)
+        .await
+        .unwrap()
+        .iter()
+        .map(|withdrawal| withdrawal.withdrawal_id)
+        .collect()
+}
+
+#[tokio::test]
+async fn test_concurrent_fetches_skip_locked_withdrawals() {
+    let app = create_test_app().await;
+    // Committed up front, since the claims below run in transactions of their own
+    let mut conn = app.db.acquire().await.unwrap();
+    let ours = [
+        insert_ready_withdrawal(&mut conn).await,
+        insert_ready_withdrawal(&mut conn).await,
+    ];
+
+    let mut first = app.db.begin().await.unwrap();
+    let mut second = app.db.begin().await.unwrap();
+
+    let first_ids = claim_all(&mut first).await;
+    let second_ids = claim_all(&mut second).await;
+
+    assert!(ours.iter().all(|id| first_ids.contains(id)));
+    assert!(second_ids.iter().all(|id| !first_ids.contains(id)));
+
+    // Once the first relayer's transaction ends, the rows can be claimed again
+    first.rollback().await.unwrap();
+    second.rollback().await.unwrap();
+    let mut third = app.db.begin().await.unwrap();
+    let third_ids = claim_all(&mut third).await;
+    assert!(ours.iter().all(|id| third_ids.contains(id)));
+    third.rollback().await.unwrap();
+
+    delete_withdrawals(&app.db, &ours).await;
+}
+
+#[tokio::test]
+async fn test_fetch_leaves_out_skipped_ids() {
+    let app = create_test_app().await;
+    let mut tx = TestTransaction::begin(&app.db).await;
+    let skipped = insert_ready_withdrawal(&mut tx).await;
+    let kept = insert_ready_withdrawal(&mut tx).await;
+
+    let withdrawals = fetch_ready_for_relay_withdrawals(&mut tx, MAX_RETRIES, &[skipped], 1_000)
+        .await
+        .unwrap();
+
+    let ids: Vec<i32> = withdrawals.iter().map(|w| w.withdrawal_id).collect();
+    assert!(ids.contains(&kept));
+    assert!(!ids.contains(&skipped));
+    assert!(withdrawals.iter().all(|w| w.retry_count < MAX_RETRIES));
+}
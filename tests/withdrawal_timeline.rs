@@ -0,0 +1,111 @@
+#[path = "utils.rs"]
+mod utils;
+
+use axum::{
+    body::{to_bytes, Body},
+    http::{Request, StatusCode},
+};
+use tower::ServiceExt;
+use utils::create_test_app;
+use zeroxbridge_sequencer::api::routes::{create_router, AppState};
+use zeroxbridge_sequencer::db::database::{update_withdrawal_status, WithdrawalEvent};
+
+async fn insert_withdrawal(pool: &sqlx::PgPool) -> i32 {
+    sqlx::query_scalar!(
+        r#"
+        INSERT INTO withdrawals (stark_pub_key, amount, l1_token, commitment_hash, status)
+        VALUES ('0x123', 1000, '0xtoken', $1, 'pending')
+        RETURNING id
+        "#,
+        format!("0x{}", uuid::Uuid::new_v4().simple())
+    )
+    .fetch_one(pool)
+    .await
+    .unwrap()
+}
+
+async fn get_timeline(app: &AppState, id: i32) -> (StatusCode, Option<Vec<WithdrawalEvent>>) {
+    let request = Request::builder()
+        .method("GET")
+        .uri(format!("/withdrawals/{}/timeline", id))
+        .body(Body::empty())
+        .unwrap();
+    let response = create_router(app.clone()).oneshot(request).await.unwrap();
+    let status = response.status();
+    let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    (status, serde_json::from_slice(&body).ok())
+}
+
+#[tokio::test]
+async fn test_timeline_lists_every_transition_in_order() {
+    let app = create_test_app().await;
+    let id = insert_withdrawal(&app.db).await;
+
+    let transitions = [
+        ("pending_proof", "l2_queue"),
+        ("proof_submitted", "proof_generation_worker"),
+        ("ready_for_relay", "proof_generation_worker"),
+        ("relayed", "ethereum_relayer"),
+    ];
+    let mut conn = app.db.acquire().await.unwrap();
+    for (status, actor) in transitions {
+        update_withdrawal_status(&mut conn, id, status, actor)
+            .await
+            .unwrap();
+    }
+
+    let (status, events) = get_timeline(&app, id).await;
+    assert_eq!(status, StatusCode::OK);
+    let events = events.expect("Timeline was not a list of transitions");
+
+    let recorded: Vec<(&str, &str, &str)> = events
+        .iter()
+        .map(|e| {
+            (
+                e.old_status.as_str(),
+                e.new_status.as_str(),
+                e.actor.as_str(),
+            )
+        })
+        .collect();
+    assert_eq!(
+        recorded,
+        vec![
+            ("pending", "pending_proof", "l2_queue"),
+            (
+                "pending_proof",
+                "proof_submitted",
+                "proof_generation_worker"
+            ),
+            (
+                "proof_submitted",
+                "ready_for_relay",
+                "proof_generation_worker"
+            ),
+            ("ready_for_relay", "relayed", "ethereum_relayer"),
+        ]
+    );
+    assert!(events
+        .windows(2)
+        .all(|pair| pair[0].occurred_at <= pair[1].occurred_at));
+}
+
+#[tokio::test]
+async fn test_timeline_of_untouched_withdrawal_is_empty() {
+    let app = create_test_app().await;
+    let id = insert_withdrawal(&app.db).await;
+
+    let (status, events) = get_timeline(&app, id).await;
+
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(events.map(|events| events.len()), Some(0));
+}
+
+#[tokio::test]
+async fn test_timeline_of_unknown_withdrawal_is_not_found() {
+    let app = create_test_app().await;
+
+    let (status, _) = get_timeline(&app, i32::MAX).await;
+
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}